@@ -0,0 +1,41 @@
+//! Config for the authentication and authorization of API requests.
+use std::path::PathBuf;
+
+/// Config for the authentication and authorization of API requests.
+///
+/// When neither a token file nor catalog tokens are configured, requests are
+/// not authenticated.
+#[derive(Debug, Clone, Default, clap::Parser)]
+pub struct AuthzConfig {
+    /// Path to a JSON file listing the API tokens accepted by this server and
+    /// the namespaces each token may read from and write to.
+    ///
+    /// The file has the form
+    /// `{"tokens": [{"token": "...", "read": ["ns", "*"], "write": ["ns"]}]}`,
+    /// where `*` grants access to all namespaces.
+    #[clap(
+        long = "--authz-token-file",
+        env = "INFLUXDB_IOX_AUTHZ_TOKEN_FILE",
+        action
+    )]
+    pub token_file: Option<PathBuf>,
+
+    /// Authorize API tokens against the namespace tokens stored in the
+    /// catalog.
+    ///
+    /// When a token file is also configured, a token accepted by either is
+    /// authorized.
+    #[clap(
+        long = "--authz-catalog-tokens",
+        env = "INFLUXDB_IOX_AUTHZ_CATALOG_TOKENS",
+        action
+    )]
+    pub catalog_tokens: bool,
+}
+
+impl AuthzConfig {
+    /// Returns true if requests must be authorized.
+    pub fn is_enabled(&self) -> bool {
+        self.token_file.is_some() || self.catalog_tokens
+    }
+}
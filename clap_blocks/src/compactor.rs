@@ -166,6 +166,20 @@ macro_rules! gen_compactor_config {
                 action
             )]
             pub max_num_compacting_files: usize,
+
+            /// Max number of parquet files rewritten per compaction cycle to materialize the
+            /// deletes of the tombstones covering them
+            ///
+            /// Tombstones are removed once every parquet file they cover has been rewritten and
+            /// the garbage collector deleted the replaced files. Set to 0 to leave tombstones to
+            /// be applied at query time.
+            #[clap(
+                long = "--compaction-max-files-to-materialize-deletes",
+                env = "INFLUXDB_IOX_COMPACTION_MAX_FILES_TO_MATERIALIZE_DELETES",
+                default_value = "10",
+                action
+            )]
+            pub max_num_files_to_materialize_deletes: usize,
         }
     };
 }
@@ -193,6 +207,7 @@ impl CompactorOnceConfig {
             min_num_rows_allocated_per_record_batch_to_datafusion_plan: self
                 .min_num_rows_allocated_per_record_batch_to_datafusion_plan,
             max_num_compacting_files: self.max_num_compacting_files,
+            max_num_files_to_materialize_deletes: self.max_num_files_to_materialize_deletes,
         }
     }
 }
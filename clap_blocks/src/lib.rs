@@ -10,6 +10,7 @@
     clippy::use_self,
     clippy::clone_on_ref_ptr
 )]
+pub mod authz;
 pub mod catalog_dsn;
 pub mod compactor;
 pub mod ingester;
@@ -17,4 +18,5 @@ pub mod object_store;
 pub mod querier;
 pub mod run_config;
 pub mod socket_addr;
+pub mod tls;
 pub mod write_buffer;
@@ -2,7 +2,7 @@
 use data_types::{IngesterMapping, ShardIndex};
 use serde::Deserialize;
 use snafu::{ResultExt, Snafu};
-use std::{collections::HashMap, fs, io, path::PathBuf, sync::Arc};
+use std::{collections::HashMap, fs, io, path::PathBuf, sync::Arc, time::Duration};
 
 #[derive(Debug, Snafu)]
 #[allow(missing_docs)]
@@ -228,6 +228,75 @@ pub struct QuerierConfig {
         action
     )]
     pub max_table_query_bytes: usize,
+
+    /// Namespace to write the history of completed queries to, into the `queries` table.
+    ///
+    /// If not specified, the query history is only kept in memory.
+    #[clap(
+        long = "--query-history-namespace",
+        env = "INFLUXDB_IOX_QUERY_HISTORY_NAMESPACE",
+        requires = "query_history_router_address",
+        action
+    )]
+    pub query_history_namespace: Option<String>,
+
+    /// gRPC address of the router the query history is written through.
+    #[clap(
+        long = "--query-history-router-address",
+        env = "INFLUXDB_IOX_QUERY_HISTORY_ROUTER_ADDRESS",
+        action
+    )]
+    pub query_history_router_address: Option<String>,
+
+    /// API token sent to the router the query history is written through, if it requires
+    /// authorization.
+    #[clap(
+        long = "--query-history-router-token",
+        env = "INFLUXDB_IOX_QUERY_HISTORY_ROUTER_TOKEN",
+        hide_env_values = true,
+        action
+    )]
+    pub query_history_router_token: Option<String>,
+
+    /// Identifier of this querier, written as the `querier` tag of its query history entries so
+    /// that the entries of different queriers never overwrite each other.
+    ///
+    /// If not specified, a random identifier is generated on startup.
+    #[clap(
+        long = "--query-history-querier-id",
+        env = "INFLUXDB_IOX_QUERY_HISTORY_QUERIER_ID",
+        action
+    )]
+    pub query_history_querier_id: Option<String>,
+
+    /// Maximum number of query history entries written at once.
+    #[clap(
+        long = "--query-history-batch-size",
+        env = "INFLUXDB_IOX_QUERY_HISTORY_BATCH_SIZE",
+        default_value = "1000",
+        action
+    )]
+    pub query_history_batch_size: usize,
+
+    /// Maximum time a query history entry is buffered before it is written.
+    #[clap(
+        long = "--query-history-flush-interval",
+        env = "INFLUXDB_IOX_QUERY_HISTORY_FLUSH_INTERVAL",
+        default_value = "10s",
+        value_parser = humantime::parse_duration,
+    )]
+    pub query_history_flush_interval: Duration,
+
+    /// Number of query history entries that are buffered before new entries are dropped.
+    ///
+    /// Queries are never blocked by a slow or unavailable router.
+    #[clap(
+        long = "--query-history-queue-size",
+        env = "INFLUXDB_IOX_QUERY_HISTORY_QUEUE_SIZE",
+        default_value = "10000",
+        action
+    )]
+    pub query_history_queue_size: usize,
 }
 
 impl QuerierConfig {
@@ -282,6 +351,18 @@ impl QuerierConfig {
     pub fn max_table_query_bytes(&self) -> usize {
         self.max_table_query_bytes
     }
+
+    /// Namespace and router address the query history is written to, if
+    /// it is enabled.
+    pub fn query_history_target(&self) -> Option<(&str, &str)> {
+        match (
+            &self.query_history_namespace,
+            &self.query_history_router_address,
+        ) {
+            (Some(namespace), Some(addr)) => Some((namespace, addr)),
+            _ => None,
+        }
+    }
 }
 
 fn deserialize_shard_ingester_map(
@@ -408,6 +489,41 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_query_history() {
+        let actual = QuerierConfig::try_parse_from(["my_binary"]).unwrap();
+        assert_eq!(actual.query_history_target(), None);
+
+        let actual = QuerierConfig::try_parse_from([
+            "my_binary",
+            "--query-history-namespace",
+            "history",
+            "--query-history-router-address",
+            "http://router:8081",
+            "--query-history-flush-interval",
+            "1m",
+            "--query-history-router-token",
+            "secret",
+            "--query-history-querier-id",
+            "querier-1",
+        ])
+        .unwrap();
+        assert_eq!(
+            actual.query_history_target(),
+            Some(("history", "http://router:8081"))
+        );
+        assert_eq!(actual.query_history_flush_interval, Duration::from_secs(60));
+        assert_eq!(actual.query_history_router_token.as_deref(), Some("secret"));
+        assert_eq!(
+            actual.query_history_querier_id.as_deref(),
+            Some("querier-1")
+        );
+
+        // The router address is required to write the history.
+        QuerierConfig::try_parse_from(["my_binary", "--query-history-namespace", "history"])
+            .unwrap_err();
+    }
+
     #[test]
     fn test_num_threads() {
         let actual =
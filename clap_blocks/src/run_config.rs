@@ -2,7 +2,7 @@
 use trace_exporters::TracingConfig;
 use trogging::cli::LoggingConfig;
 
-use crate::{object_store::ObjectStoreConfig, socket_addr::SocketAddr};
+use crate::{object_store::ObjectStoreConfig, socket_addr::SocketAddr, tls::TlsConfig};
 
 /// The default bind address for the HTTP API.
 pub const DEFAULT_API_BIND_ADDR: &str = "127.0.0.1:8080";
@@ -48,6 +48,10 @@ pub struct RunConfig {
     )]
     pub max_http_request_size: usize,
 
+    /// TLS options
+    #[clap(flatten)]
+    pub(crate) tls_config: TlsConfig,
+
     /// object store config
     #[clap(flatten)]
     pub(crate) object_store_config: ObjectStoreConfig,
@@ -64,6 +68,11 @@ impl RunConfig {
         &self.object_store_config
     }
 
+    /// Get a reference to the run config's TLS config.
+    pub fn tls_config(&self) -> &TlsConfig {
+        &self.tls_config
+    }
+
     /// Get a mutable reference to the run config's tracing config.
     pub fn tracing_config_mut(&mut self) -> &mut TracingConfig {
         &mut self.tracing_config
@@ -86,6 +95,12 @@ impl RunConfig {
         self
     }
 
+    /// set the TLS config
+    pub fn with_tls_config(mut self, tls_config: TlsConfig) -> Self {
+        self.tls_config = tls_config;
+        self
+    }
+
     /// Create a new instance for all-in-one mode, only allowing some arguments.
     pub fn new(
         logging_config: LoggingConfig,
@@ -101,6 +116,7 @@ impl RunConfig {
             http_bind_address,
             grpc_bind_address,
             max_http_request_size,
+            tls_config: Default::default(),
             object_store_config,
         }
     }
@@ -0,0 +1,54 @@
+//! Config for TLS on the gRPC and HTTP listeners and the internal clients.
+use std::path::PathBuf;
+
+/// Config for TLS on the gRPC and HTTP listeners and the internal clients.
+///
+/// When no server certificate is configured, listeners accept plaintext
+/// connections.
+#[derive(Debug, Clone, Default, clap::Parser)]
+pub struct TlsConfig {
+    /// Path to the PEM encoded certificate chain presented by the gRPC and
+    /// HTTP listeners.
+    ///
+    /// The certificate is also presented by internal clients (e.g. the
+    /// querier connecting to ingesters) to servers requiring client
+    /// certificates.
+    #[clap(
+        long = "--tls-cert",
+        env = "INFLUXDB_IOX_TLS_CERT",
+        requires = "tls_key",
+        action
+    )]
+    pub tls_cert: Option<PathBuf>,
+
+    /// Path to the PEM encoded private key of `--tls-cert`.
+    #[clap(
+        long = "--tls-key",
+        env = "INFLUXDB_IOX_TLS_KEY",
+        requires = "tls_cert",
+        action
+    )]
+    pub tls_key: Option<PathBuf>,
+
+    /// Path to a PEM encoded CA certificate. When set, the listeners require
+    /// clients to present a certificate signed by this CA (mutual TLS).
+    #[clap(
+        long = "--tls-client-ca",
+        env = "INFLUXDB_IOX_TLS_CLIENT_CA",
+        requires = "tls_cert",
+        action
+    )]
+    pub tls_client_ca: Option<PathBuf>,
+
+    /// Path to a PEM encoded CA certificate the internal clients verify the
+    /// certificates of `https://` servers against.
+    #[clap(long = "--tls-ca", env = "INFLUXDB_IOX_TLS_CA", action)]
+    pub tls_ca: Option<PathBuf>,
+}
+
+impl TlsConfig {
+    /// Returns true if the listeners serve TLS.
+    pub fn is_enabled(&self) -> bool {
+        self.tls_cert.is_some()
+    }
+}
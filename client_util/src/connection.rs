@@ -4,7 +4,7 @@ use http::{uri::InvalidUri, HeaderValue, Uri};
 use std::convert::TryInto;
 use std::time::Duration;
 use thiserror::Error;
-use tonic::transport::{Channel, Endpoint};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
 use tower::make::MakeConnection;
 
 /// The connection type used for clients
@@ -32,6 +32,10 @@ pub enum Error {
     /// Client received an unexpected error from the server
     #[error("Invalid URI: {}", .0)]
     InvalidUri(#[from] InvalidUri),
+
+    /// The API token cannot be sent in a header
+    #[error("Invalid API token: not a valid header value")]
+    InvalidToken,
 }
 
 // Custom impl to include underlying source (not included in tonic
@@ -68,12 +72,49 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 ///     .expect("connection must succeed");
 /// # }
 /// ```
+///
+/// TLS is used for `https://` URLs. The server certificate is verified
+/// against the CA configured with [`tls_ca_certificate`], and a client
+/// certificate is presented to servers that require one when configured with
+/// [`tls_identity`]:
+///
+/// ```no_run
+/// #[tokio::main]
+/// # async fn main() {
+/// use client_util::connection::Builder;
+///
+/// let connection = Builder::new()
+///     .tls_ca_certificate(std::fs::read("ca.pem").unwrap())
+///     .tls_identity(
+///         std::fs::read("client.pem").unwrap(),
+///         std::fs::read("client.key").unwrap(),
+///     )
+///     .build("https://iox.example.com:8082/")
+///     .await
+///     .expect("connection must succeed");
+/// # }
+/// ```
+///
+/// [`tls_ca_certificate`]: Self::tls_ca_certificate
+/// [`tls_identity`]: Self::tls_identity
 #[derive(Debug, Clone)]
 pub struct Builder {
     user_agent: String,
     headers: Vec<(HeaderName, HeaderValue)>,
     connect_timeout: Duration,
     timeout: Duration,
+    tls_config: Option<ClientTlsConfig>,
+    token: Option<ApiToken>,
+}
+
+/// An API token, not printed by `Debug`
+#[derive(Clone)]
+struct ApiToken(String);
+
+impl std::fmt::Debug for ApiToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ApiToken(***)")
+    }
 }
 
 impl std::default::Default for Builder {
@@ -83,6 +124,8 @@ impl std::default::Default for Builder {
             connect_timeout: DEFAULT_CONNECT_TIMEOUT,
             timeout: DEFAULT_TIMEOUT,
             headers: Default::default(),
+            tls_config: None,
+            token: None,
         }
     }
 }
@@ -98,9 +141,10 @@ impl Builder {
     where
         D: TryInto<Uri, Error = InvalidUri> + Send,
     {
+        let headers = self.headers()?;
         let endpoint = self.create_endpoint(dst)?;
         let channel = endpoint.connect().await?;
-        Ok(self.compose_middleware(channel))
+        Ok(Self::compose_middleware(headers, channel))
     }
 
     /// Construct the [`Connection`] instance using the specified base URL and custom connector.
@@ -112,26 +156,41 @@ impl Builder {
         C::Future: Send + 'static,
         Box<dyn std::error::Error + Send + Sync>: From<C::Error> + Send + 'static,
     {
+        let headers = self.headers()?;
         let endpoint = self.create_endpoint(dst)?;
         let channel = endpoint.connect_with_connector(connector).await?;
-        Ok(self.compose_middleware(channel))
+        Ok(Self::compose_middleware(headers, channel))
     }
 
     fn create_endpoint<D>(&self, dst: D) -> Result<Endpoint>
     where
         D: TryInto<Uri, Error = InvalidUri> + Send,
     {
-        let endpoint = Endpoint::from(dst.try_into()?)
+        let mut endpoint = Endpoint::from(dst.try_into()?)
             .user_agent(&self.user_agent)?
             .connect_timeout(self.connect_timeout)
             .timeout(self.timeout);
+        if let Some(tls_config) = &self.tls_config {
+            endpoint = endpoint.tls_config(tls_config.clone())?;
+        }
         Ok(endpoint)
     }
 
-    fn compose_middleware(self, channel: Channel) -> Connection {
+    fn headers(&self) -> Result<Vec<(HeaderName, HeaderValue)>> {
+        let mut headers = self.headers.clone();
+        if let Some(ApiToken(token)) = &self.token {
+            let mut value = HeaderValue::try_from(format!("Token {}", token))
+                .map_err(|_| Error::InvalidToken)?;
+            value.set_sensitive(true);
+            headers.push((http::header::AUTHORIZATION, value));
+        }
+        Ok(headers)
+    }
+
+    fn compose_middleware(headers: Vec<(HeaderName, HeaderValue)>, channel: Channel) -> Connection {
         // Compose channel with new tower middleware stack
         tower::ServiceBuilder::new()
-            .layer(SetRequestHeadersLayer::new(self.headers))
+            .layer(SetRequestHeadersLayer::new(headers))
             .service(channel)
     }
 
@@ -150,6 +209,15 @@ impl Builder {
         Self { headers, ..self }
     }
 
+    /// Sends the given API token in the `authorization` header of all
+    /// requests, using the `Token <token>` scheme.
+    pub fn token(self, token: impl Into<String>) -> Self {
+        Self {
+            token: Some(ApiToken(token.into())),
+            ..self
+        }
+    }
+
     /// Sets the maximum duration of time the client will wait for the IOx
     /// server to accept the TCP connection before aborting the request.
     ///
@@ -175,6 +243,35 @@ impl Builder {
     pub fn timeout(self, timeout: Duration) -> Self {
         Self { timeout, ..self }
     }
+
+    /// Verifies the certificate of `https://` servers against the given PEM
+    /// encoded CA certificate.
+    pub fn tls_ca_certificate(self, pem: impl AsRef<[u8]>) -> Self {
+        let certificate = Certificate::from_pem(pem);
+        self.map_tls_config(|tls_config| tls_config.ca_certificate(certificate))
+    }
+
+    /// Presents the given PEM encoded certificate and private key to
+    /// `https://` servers that require client certificates (mutual TLS).
+    pub fn tls_identity(self, cert_pem: impl AsRef<[u8]>, key_pem: impl AsRef<[u8]>) -> Self {
+        let identity = Identity::from_pem(cert_pem, key_pem);
+        self.map_tls_config(|tls_config| tls_config.identity(identity))
+    }
+
+    /// Sets the name the server certificate is verified against, instead of
+    /// the host of the URL.
+    pub fn tls_domain_name(self, domain_name: impl Into<String>) -> Self {
+        let domain_name = domain_name.into();
+        self.map_tls_config(|tls_config| tls_config.domain_name(domain_name))
+    }
+
+    fn map_tls_config(self, f: impl FnOnce(ClientTlsConfig) -> ClientTlsConfig) -> Self {
+        let tls_config = self.tls_config.unwrap_or_else(ClientTlsConfig::new);
+        Self {
+            tls_config: Some(f(tls_config)),
+            ..self
+        }
+    }
 }
 
 #[cfg(test)]
@@ -187,4 +284,21 @@ mod tests {
         fn assert_clone<T: Clone>(_t: T) {}
         assert_clone(Builder::default())
     }
+
+    #[test]
+    fn test_token() {
+        let builder = Builder::default().token("secret");
+        assert!(!format!("{:?}", builder).contains("secret"));
+
+        let headers = builder.headers().unwrap();
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers[0].0, http::header::AUTHORIZATION);
+        assert_eq!(headers[0].1, "Token secret");
+        assert!(headers[0].1.is_sensitive());
+
+        assert!(matches!(
+            Builder::default().token("sec\nret").headers(),
+            Err(Error::InvalidToken)
+        ));
+    }
 }
@@ -697,6 +697,7 @@ mod tests {
             memory_budget_bytes: 100_000_000,
             min_num_rows_allocated_per_record_batch_to_datafusion_plan: 1,
             max_num_compacting_files: 20,
+            max_num_files_to_materialize_deletes: 0,
         }
     }
 
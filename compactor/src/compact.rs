@@ -13,12 +13,13 @@ use metric::{
     Attributes, DurationHistogram, DurationHistogramOptions, Metric, U64Gauge, U64Histogram,
     U64HistogramOptions, DURATION_MAX,
 };
-use observability_deps::tracing::debug;
+use observability_deps::tracing::{debug, warn};
+use parking_lot::Mutex;
 use parquet_file::storage::ParquetStorage;
 use schema::sort::SortKey;
 use snafu::{OptionExt, ResultExt, Snafu};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     sync::Arc,
     time::Duration,
 };
@@ -143,6 +144,10 @@ pub struct Compactor {
     ///  . Whether there is a big difference between each cycle or not
     ///  . How well this process  is parallelized
     pub(crate) compaction_cycle_duration: Metric<DurationHistogram>,
+
+    /// Partitions queued for a full compaction in the next compaction cycle, in the order they
+    /// were queued
+    queued_partitions: Mutex<VecDeque<PartitionId>>,
 }
 
 impl Compactor {
@@ -247,6 +252,7 @@ impl Compactor {
             candidate_selection_duration,
             partitions_extra_info_reading_duration,
             compaction_cycle_duration,
+            queued_partitions: Default::default(),
         }
     }
 
@@ -336,6 +342,82 @@ impl Compactor {
         Ok(candidates)
     }
 
+    /// Queue the given partition for a full compaction in the next compaction cycle. Returns
+    /// `false` if the partition was already queued.
+    pub fn queue_partition(&self, partition_id: PartitionId) -> bool {
+        let mut queued = self.queued_partitions.lock();
+        if queued.contains(&partition_id) {
+            return false;
+        }
+        queued.push_back(partition_id);
+        true
+    }
+
+    /// Partitions queued for a full compaction that have not been compacted yet
+    pub fn queued_partitions(&self) -> Vec<PartitionId> {
+        self.queued_partitions.lock().iter().copied().collect()
+    }
+
+    /// Remove and return all partitions queued for a full compaction
+    pub(crate) fn take_queued_partitions(&self) -> Vec<PartitionId> {
+        self.queued_partitions.lock().drain(..).collect()
+    }
+
+    /// Return the given queued partitions with the information needed to compact them.
+    /// Partitions that no longer exist are dropped with a warning.
+    pub(crate) async fn queued_partitions_to_compact(
+        &self,
+        partition_ids: &[PartitionId],
+    ) -> Result<Vec<Arc<PartitionCompactionCandidateWithInfo>>> {
+        let compaction_type = "queued";
+
+        let mut candidates = Vec::with_capacity(partition_ids.len());
+        {
+            let mut repos = self.catalog.repositories().await;
+            for &partition_id in partition_ids {
+                let partition = match repos
+                    .partitions()
+                    .get_by_id(partition_id)
+                    .await
+                    .context(QueryingPartitionSnafu)?
+                {
+                    Some(partition) => partition,
+                    None => {
+                        warn!(
+                            partition_id = partition_id.get(),
+                            compaction_type, "queued partition not found"
+                        );
+                        continue;
+                    }
+                };
+                let table = repos
+                    .tables()
+                    .get_by_id(partition.table_id)
+                    .await
+                    .context(QueryingTableSnafu)?
+                    .context(TableNotFoundSnafu {
+                        table_id: partition.table_id,
+                    })?;
+
+                candidates.push(PartitionParam {
+                    partition_id,
+                    shard_id: partition.shard_id,
+                    namespace_id: table.namespace_id,
+                    table_id: table.id,
+                });
+            }
+        }
+
+        debug!(
+            num_candidates=?candidates.len(),
+            compaction_type,
+            "start getting additional info for the partition candidates"
+        );
+        let table_columns = self.table_columns(&candidates).await?;
+        self.add_info_to_partitions(&candidates, &table_columns)
+            .await
+    }
+
     /// Get column types for tables of given partitions
     pub(crate) async fn table_columns(
         &self,
@@ -722,6 +804,7 @@ pub mod tests {
             memory_budget_bytes: 10 * 1024 * 1024,
             min_num_rows_allocated_per_record_batch_to_datafusion_plan: 100,
             max_num_compacting_files: 20,
+            max_num_files_to_materialize_deletes: 0,
         }
     }
 
@@ -999,4 +1082,54 @@ pub mod tests {
         assert_eq!(candidates[2].id(), another_partition.id);
         assert_eq!(candidates[2].shard_id(), another_shard.id);
     }
+
+    #[tokio::test]
+    async fn test_queued_partitions_to_compact() {
+        let catalog = TestCatalog::new();
+        let namespace = catalog
+            .create_namespace("namespace_queued_partitions_to_compact")
+            .await;
+        let shard = namespace.create_shard(1).await;
+        let table = namespace.create_table("test_table").await;
+        table.create_column("time", ColumnType::Time).await;
+        let partition1 = table.with_shard(&shard).create_partition("one").await;
+        let partition2 = table.with_shard(&shard).create_partition("two").await;
+
+        let compactor = Compactor::new(
+            vec![shard.shard.id],
+            Arc::clone(&catalog.catalog),
+            ParquetStorage::new(Arc::clone(&catalog.object_store)),
+            Arc::new(Executor::new(1)),
+            Arc::new(SystemProvider::new()),
+            BackoffConfig::default(),
+            make_compactor_config(),
+            Arc::new(metric::Registry::new()),
+        );
+
+        // Partitions are only queued once
+        assert!(compactor.queue_partition(partition2.partition.id));
+        assert!(compactor.queue_partition(partition1.partition.id));
+        assert!(!compactor.queue_partition(partition2.partition.id));
+        assert_eq!(
+            compactor.queued_partitions(),
+            vec![partition2.partition.id, partition1.partition.id]
+        );
+
+        // Taking the queue empties it
+        let queued = compactor.take_queued_partitions();
+        assert!(compactor.queued_partitions().is_empty());
+
+        // Unknown partitions are dropped
+        let mut queued_with_unknown = queued.clone();
+        queued_with_unknown.push(PartitionId::new(i64::MAX));
+        let candidates = compactor
+            .queued_partitions_to_compact(&queued_with_unknown)
+            .await
+            .unwrap();
+        let candidate_ids: Vec<_> = candidates.iter().map(|c| c.id()).collect();
+        assert_eq!(candidate_ids, queued);
+        assert_eq!(candidates[0].namespace_id(), namespace.namespace.id);
+        assert_eq!(candidates[0].table.name, "test_table");
+        assert_eq!(candidates[0].partition_key, "two".into());
+    }
 }
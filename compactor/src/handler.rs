@@ -1,7 +1,12 @@
 //! Compactor handler
 
-use crate::{cold, compact::Compactor, hot};
+use crate::{
+    cold,
+    compact::{self, Compactor, PartitionCompactionCandidateWithInfo},
+    hot, queued, tombstones, ParquetFilesForCompaction,
+};
 use async_trait::async_trait;
+use data_types::{PartitionId, PartitionKey, ShardId, SkippedCompaction};
 use futures::{
     future::{BoxFuture, Shared},
     FutureExt, TryFutureExt,
@@ -18,9 +23,63 @@ use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Error)]
 #[allow(missing_copy_implementations, missing_docs)]
-pub enum Error {}
+pub enum Error {
+    #[error("Catalog error: {0}")]
+    Catalog(#[from] iox_catalog::interface::Error),
+
+    #[error("Partition {0} not found")]
+    PartitionNotFound(PartitionId),
+
+    #[error("Partition {partition_id} belongs to shard {shard_id}, which this compactor does not handle")]
+    ShardNotHandled {
+        partition_id: PartitionId,
+        shard_id: ShardId,
+    },
+
+    #[error("Namespace {0} not found")]
+    NamespaceNotFound(String),
+
+    #[error("Table {table} not found in namespace {namespace}")]
+    TableNotFound { namespace: String, table: String },
+
+    #[error("Error finding compaction candidates: {0}")]
+    Candidates(#[from] compact::Error),
 
-/// The [`CompactorHandler`] does nothing at this point
+    #[error("Error reading the parquet files of partition {partition_id}: {source}")]
+    ParquetFiles {
+        partition_id: PartitionId,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+/// A specialized `Error` for compactor handler errors
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A partition the compactor would select for compaction in its next cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactionCandidate {
+    /// The partition
+    pub partition_id: PartitionId,
+    /// The shard of the partition
+    pub shard_id: ShardId,
+    /// Name of the namespace of the partition
+    pub namespace: String,
+    /// Name of the table of the partition
+    pub table: String,
+    /// Key of the partition
+    pub partition_key: PartitionKey,
+    /// Whether the partition was selected as "hot" or "cold"
+    pub compaction_type: &'static str,
+    /// Number of level 0 files in the partition
+    pub num_level_0_files: usize,
+    /// Number of level 1 files in the partition
+    pub num_level_1_files: usize,
+    /// Estimated memory needed to compact all level 0 and level 1 files of the partition
+    pub estimated_memory_bytes: u64,
+}
+
+/// The [`CompactorHandler`] runs the compaction cycles and exposes operational controls over
+/// them.
 #[async_trait]
 pub trait CompactorHandler: Send + Sync {
     /// Wait until the handler finished  to shutdown.
@@ -30,6 +89,29 @@ pub trait CompactorHandler: Send + Sync {
 
     /// Shut down background workers.
     fn shutdown(&self);
+
+    /// List the partitions whose compaction was skipped.
+    async fn skipped_compactions(&self) -> Result<Vec<SkippedCompaction>>;
+
+    /// Forget that compacting the partition was skipped so it becomes a compaction candidate
+    /// again. Returns the cleared record, if any.
+    async fn clear_skipped_compaction(
+        &self,
+        partition_id: PartitionId,
+    ) -> Result<Option<SkippedCompaction>>;
+
+    /// Queue the partition for a full compaction in the next compaction cycle.
+    async fn queue_partition(&self, partition_id: PartitionId) -> Result<()>;
+
+    /// Queue all partitions of the table that belong to shards handled by this compactor for a
+    /// full compaction in the next compaction cycle. Returns the queued partitions.
+    async fn queue_table(&self, namespace: &str, table: &str) -> Result<Vec<PartitionId>>;
+
+    /// The partitions the compactor would select in its next cycle, hot candidates first.
+    async fn compaction_candidates(&self) -> Result<Vec<CompactionCandidate>>;
+
+    /// The memory budget candidates are compacted within.
+    fn memory_budget_bytes(&self) -> u64;
 }
 
 /// A [`JoinHandle`] that can be cloned
@@ -40,11 +122,10 @@ fn shared_handle(handle: JoinHandle<()>) -> SharedJoinHandle {
     handle.map_err(Arc::new).boxed().shared()
 }
 
-/// Implementation of the `CompactorHandler` trait (that currently does nothing)
+/// Implementation of the `CompactorHandler` trait
 #[derive(Debug)]
 pub struct CompactorHandlerImpl {
     /// Data to compact
-    #[allow(dead_code)]
     compactor_data: Arc<Compactor>,
 
     /// A token that is used to trigger shutdown of the background worker
@@ -138,6 +219,12 @@ pub struct CompactorConfig {
     ///
     /// Due to limit in fan-in of datafusion plan, we need to limit the number of files to compact per partition.
     pub max_num_compacting_files: usize,
+
+    /// Max number of parquet files rewritten per compaction cycle to materialize the deletes of
+    /// the tombstones covering them. Tombstones are removed once every parquet file they cover
+    /// has been rewritten and the garbage collector deleted the replaced files. 0 disables
+    /// materializing deletes.
+    pub max_num_files_to_materialize_deletes: usize,
 }
 
 /// How long to pause before checking for more work again if there was
@@ -164,6 +251,9 @@ pub async fn run_compactor_once(compactor: Arc<Compactor>) {
         num_cold_cycles = 1,
         "start running compactor once that includes"
     );
+    debug!("start queued cycle");
+    let queued_partitions = queued::compact(Arc::clone(&compactor)).await;
+
     let mut compacted_partitions = 0;
     for i in 0..num_hot_cycles {
         debug!(?i, "start hot cycle");
@@ -177,7 +267,10 @@ pub async fn run_compactor_once(compactor: Arc<Compactor>) {
     debug!("start cold cycle");
     compacted_partitions += cold::compact(Arc::clone(&compactor), true).await;
 
-    if compacted_partitions == 0 {
+    debug!("start materializing deletes");
+    let materialized_files = tombstones::materialize(Arc::clone(&compactor)).await;
+
+    if queued_partitions + compacted_partitions + materialized_files == 0 {
         // sleep for a second to avoid a busy loop when the catalog is polled
         tokio::time::sleep(PAUSE_BETWEEN_NO_WORK).await;
     }
@@ -202,6 +295,152 @@ impl CompactorHandler for CompactorHandlerImpl {
         self.shutdown.cancel();
         self.exec.shutdown();
     }
+
+    async fn skipped_compactions(&self) -> Result<Vec<SkippedCompaction>> {
+        let mut repos = self.compactor_data.catalog.repositories().await;
+        Ok(repos.partitions().list_skipped_compactions().await?)
+    }
+
+    async fn clear_skipped_compaction(
+        &self,
+        partition_id: PartitionId,
+    ) -> Result<Option<SkippedCompaction>> {
+        let mut repos = self.compactor_data.catalog.repositories().await;
+        let cleared = repos
+            .partitions()
+            .delete_skipped_compactions(partition_id)
+            .await?;
+        if cleared.is_some() {
+            info!(
+                partition_id = partition_id.get(),
+                "cleared skipped compaction"
+            );
+        }
+        Ok(cleared)
+    }
+
+    async fn queue_partition(&self, partition_id: PartitionId) -> Result<()> {
+        let mut repos = self.compactor_data.catalog.repositories().await;
+        let partition = repos
+            .partitions()
+            .get_by_id(partition_id)
+            .await?
+            .ok_or(Error::PartitionNotFound(partition_id))?;
+
+        if !self.compactor_data.shards.contains(&partition.shard_id) {
+            return Err(Error::ShardNotHandled {
+                partition_id,
+                shard_id: partition.shard_id,
+            });
+        }
+
+        if self.compactor_data.queue_partition(partition_id) {
+            info!(
+                partition_id = partition_id.get(),
+                "queued partition for full compaction"
+            );
+        }
+        Ok(())
+    }
+
+    async fn queue_table(&self, namespace: &str, table: &str) -> Result<Vec<PartitionId>> {
+        let partitions = {
+            let mut repos = self.compactor_data.catalog.repositories().await;
+            let namespace_id = repos
+                .namespaces()
+                .get_by_name(namespace)
+                .await?
+                .ok_or_else(|| Error::NamespaceNotFound(namespace.to_string()))?
+                .id;
+            let table_id = repos
+                .tables()
+                .get_by_namespace_and_name(namespace_id, table)
+                .await?
+                .ok_or_else(|| Error::TableNotFound {
+                    namespace: namespace.to_string(),
+                    table: table.to_string(),
+                })?
+                .id;
+            repos.partitions().list_by_table_id(table_id).await?
+        };
+
+        let mut queued = Vec::with_capacity(partitions.len());
+        for partition in partitions {
+            if self.compactor_data.shards.contains(&partition.shard_id) {
+                self.compactor_data.queue_partition(partition.id);
+                queued.push(partition.id);
+            }
+        }
+        info!(
+            namespace,
+            table,
+            n_partitions = queued.len(),
+            "queued table for full compaction"
+        );
+        Ok(queued)
+    }
+
+    async fn compaction_candidates(&self) -> Result<Vec<CompactionCandidate>> {
+        let compactor = &self.compactor_data;
+
+        let hot = hot::hot_partitions_to_compact(Arc::clone(compactor)).await?;
+        let cold = compactor
+            .cold_partitions_to_compact(compactor.config.max_number_partitions_per_shard)
+            .await?;
+
+        let mut candidates = Vec::with_capacity(hot.len() + cold.len());
+        for (compaction_type, partitions) in [("hot", hot), ("cold", cold)] {
+            for partition in partitions {
+                candidates.push(describe_candidate(compactor, compaction_type, partition).await?);
+            }
+        }
+        Ok(candidates)
+    }
+
+    fn memory_budget_bytes(&self) -> u64 {
+        self.compactor_data.config.memory_budget_bytes
+    }
+}
+
+/// Describe a compaction candidate together with the estimated memory needed to compact it.
+async fn describe_candidate(
+    compactor: &Compactor,
+    compaction_type: &'static str,
+    partition: Arc<PartitionCompactionCandidateWithInfo>,
+) -> Result<CompactionCandidate> {
+    let partition_id = partition.id();
+    let ParquetFilesForCompaction {
+        level_0, level_1, ..
+    } = ParquetFilesForCompaction::for_partition(
+        Arc::clone(&compactor.catalog),
+        compactor
+            .config
+            .min_num_rows_allocated_per_record_batch_to_datafusion_plan,
+        Arc::clone(&partition),
+    )
+    .await
+    .map_err(|e| Error::ParquetFiles {
+        partition_id,
+        source: Box::new(e),
+    })?;
+
+    let estimated_memory_bytes = level_0
+        .iter()
+        .chain(&level_1)
+        .map(|f| f.estimated_arrow_bytes())
+        .sum();
+
+    Ok(CompactionCandidate {
+        partition_id,
+        shard_id: partition.shard_id(),
+        namespace: partition.namespace.name.clone(),
+        table: partition.table.name.clone(),
+        partition_key: partition.partition_key.clone(),
+        compaction_type,
+        num_level_0_files: level_0.len(),
+        num_level_1_files: level_1.len(),
+        estimated_memory_bytes,
+    })
 }
 
 impl Drop for CompactorHandlerImpl {
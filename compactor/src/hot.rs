@@ -495,6 +495,7 @@ mod tests {
             memory_budget_bytes: 10 * 1024 * 1024,
             min_num_rows_allocated_per_record_batch_to_datafusion_plan: 100,
             max_num_compacting_files: 20,
+            max_num_files_to_materialize_deletes: 0,
         };
         let compactor = Arc::new(Compactor::new(
             vec![shard1.shard.id, shard2.shard.id],
@@ -20,7 +20,9 @@ pub(crate) mod parquet_file_combining;
 pub(crate) mod parquet_file_filtering;
 pub(crate) mod parquet_file_lookup;
 pub mod query;
+pub(crate) mod queued;
 pub mod server;
+pub(crate) mod tombstones;
 pub mod utils;
 
 use crate::{
@@ -536,6 +538,7 @@ pub mod tests {
             memory_budget_bytes: 14 * 1025, // 14,350
             min_num_rows_allocated_per_record_batch_to_datafusion_plan: 2,
             max_num_compacting_files: 20,
+            max_num_files_to_materialize_deletes: 0,
         }
     }
 
@@ -898,6 +901,7 @@ pub mod tests {
             memory_budget_bytes: 100_000_000,
             min_num_rows_allocated_per_record_batch_to_datafusion_plan: 100,
             max_num_compacting_files: 20,
+            max_num_files_to_materialize_deletes: 0,
         };
 
         let metrics = Arc::new(metric::Registry::new());
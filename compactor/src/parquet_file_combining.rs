@@ -4,7 +4,7 @@ use crate::{
 };
 use data_types::{
     CompactionLevel, ParquetFile, ParquetFileId, ParquetFileParams, PartitionId, SequenceNumber,
-    TableSchema, TimestampMinMax,
+    TableSchema, TimestampMinMax, Tombstone, TombstoneId,
 };
 use datafusion::{error::DataFusionError, logical_plan::LogicalPlan};
 use futures::{stream::FuturesOrdered, StreamExt, TryStreamExt};
@@ -23,11 +23,12 @@ use parquet_file::{
     serialize::CodecError,
     storage::{ParquetStorage, UploadError},
 };
+use predicate::delete_predicate::tombstones_to_delete_predicates;
 use schema::{sort::SortKey, Schema};
 use snafu::{ensure, ResultExt, Snafu};
 use std::{
     cmp::{max, min},
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet, HashSet},
     future,
     sync::Arc,
 };
@@ -152,6 +153,7 @@ pub(crate) async fn compact_parquet_files(
                 &partition.table_schema,
                 partition.sort_key.clone(),
                 target_level,
+                &[],
             )
         })
         .collect();
@@ -259,6 +261,7 @@ pub(crate) async fn compact_parquet_files(
         partition_id,
         compacted_parquet_files,
         &original_parquet_file_ids,
+        &[],
     )
     .await
     .context(CatalogSnafu { partition_id })?;
@@ -322,6 +325,7 @@ pub(crate) async fn compact_final_no_splits(
                 &partition.table_schema,
                 partition.sort_key.clone(),
                 target_level,
+                &[],
             )
         })
         .collect();
@@ -385,6 +389,7 @@ pub(crate) async fn compact_final_no_splits(
         partition_id,
         compacted_parquet_files,
         &original_parquet_file_ids,
+        &[],
     )
     .await
     .context(CatalogSnafu { partition_id })?;
@@ -400,6 +405,126 @@ pub(crate) async fn compact_final_no_splits(
     Ok(())
 }
 
+/// Rewrite the given file with the rows deleted by the given tombstones removed, keeping its
+/// compaction level and max sequence number, and record the tombstones as processed for the
+/// rewritten file, together with the tombstones already processed for the given file.
+///
+/// Tombstones whose predicate references a column the file does not have cannot delete any of
+/// its rows, just like queriers skip such predicates when reading the file. If no tombstone
+/// applies, the file is not rewritten and the tombstones are recorded as processed for the file
+/// itself.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn materialize_deletes(
+    file: CompactorParquetFile,
+    tombstones: Vec<Tombstone>,
+    partition: Arc<PartitionCompactionCandidateWithInfo>,
+    // The global catalog for schema, parquet files and tombstones
+    catalog: Arc<dyn Catalog>,
+    // Object store for reading the input parquet file and writing the rewritten parquet file
+    store: ParquetStorage,
+    // Executor for running queries, compacting, and persisting
+    exec: Arc<Executor>,
+    time_provider: Arc<dyn TimeProvider>,
+) -> Result<(), Error> {
+    let partition_id = partition.id();
+    let parquet_file_id = file.id();
+    let target_level = file.compaction_level();
+    let tombstone_ids: Vec<_> = tombstones.iter().map(|t| t.id).collect();
+
+    let column_id_lookup = partition.table_schema.column_id_map();
+    let file_columns: HashSet<_> = file
+        .column_set()
+        .iter()
+        .flat_map(|id| column_id_lookup.get(id).copied())
+        .collect();
+    // A tombstone only deletes rows of the file if it can match rows without the columns that are
+    // missing from the file, which are NULL for every row.
+    let applicable: Vec<_> = tombstones
+        .into_iter()
+        .filter(|tombstone| {
+            tombstones_to_delete_predicates(std::slice::from_ref(tombstone))
+                .iter()
+                .all(|predicate| {
+                    predicate
+                        .restrict_to_columns(|col| file_columns.contains(col))
+                        .is_some()
+                })
+        })
+        .collect();
+
+    if applicable.is_empty() {
+        debug!(
+            ?partition_id,
+            ?parquet_file_id,
+            "no tombstone deletes rows of the parquet file, not rewriting it"
+        );
+        return record_processed_tombstones(catalog, parquet_file_id, &tombstone_ids)
+            .await
+            .context(CatalogSnafu { partition_id });
+    }
+
+    debug!(
+        ?partition_id,
+        ?parquet_file_id,
+        num_tombstones = applicable.len(),
+        "materialize deletes"
+    );
+
+    let query_chunk = to_queryable_parquet_chunk(
+        file,
+        store.clone(),
+        partition.table.name.clone(),
+        &partition.table_schema,
+        partition.sort_key.clone(),
+        target_level,
+        &applicable,
+    );
+    let max_sequence_number = query_chunk.max_sequence_number();
+
+    let query_chunks = vec![Arc::new(query_chunk) as Arc<dyn QueryChunk>];
+    let merged_schema = QueryableParquetChunk::merge_schemas(&query_chunks);
+
+    // All partitions in the catalog MUST contain a sort key.
+    let sort_key = partition
+        .sort_key
+        .as_ref()
+        .expect("no partition sort key in catalog")
+        .filter_to(&merged_schema.primary_key(), partition_id.get());
+
+    let ctx = exec.new_context(ExecutorType::Reorg);
+    let plan = ReorgPlanner::new(ctx.child_ctx("ReorgPlanner"))
+        .compact_plan(Arc::clone(&merged_schema), query_chunks, sort_key.clone())
+        .context(CompactLogicalPlanSnafu)?;
+
+    // No file is produced if every row of the file was deleted
+    let rewritten_parquet_files = compact_with_plan(
+        store,
+        exec,
+        time_provider,
+        plan,
+        sort_key,
+        Arc::clone(&partition),
+        partition_id,
+        max_sequence_number,
+        target_level,
+    )
+    .await?;
+
+    update_catalog(
+        catalog,
+        partition_id,
+        rewritten_parquet_files,
+        &[parquet_file_id],
+        &tombstone_ids,
+    )
+    .await
+    .context(CatalogSnafu { partition_id })?;
+
+    info!(?partition_id, ?parquet_file_id, "deletes materialized");
+
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn compact_with_plan(
     store: ParquetStorage,
@@ -528,6 +653,7 @@ fn to_queryable_parquet_chunk(
     table_schema: &TableSchema,
     partition_sort_key: Option<SortKey>,
     target_level: CompactionLevel,
+    tombstones: &[Tombstone],
 ) -> QueryableParquetChunk {
     let column_id_lookup = table_schema.column_id_map();
     let selection: Vec<_> = file
@@ -576,7 +702,7 @@ fn to_queryable_parquet_chunk(
         table_name,
         file.partition_id,
         Arc::new(parquet_chunk),
-        &[],
+        tombstones,
         file.max_sequence_number,
         file.min_time,
         file.max_time,
@@ -616,6 +742,16 @@ pub(crate) enum CatalogUpdateError {
     FlagForDelete {
         source: iox_catalog::interface::Error,
     },
+
+    #[snafu(display("Error while recording a processed tombstone {}", source))]
+    ProcessedTombstone {
+        source: iox_catalog::interface::Error,
+    },
+
+    #[snafu(display("Error while listing processed tombstones {}", source))]
+    ListProcessedTombstones {
+        source: iox_catalog::interface::Error,
+    },
 }
 
 async fn update_catalog(
@@ -623,12 +759,34 @@ async fn update_catalog(
     partition_id: PartitionId,
     compacted_parquet_files: Vec<ParquetFileParams>,
     original_parquet_file_ids: &[ParquetFileId],
+    // Tombstones that were applied to the compacted files
+    processed_tombstone_ids: &[TombstoneId],
 ) -> Result<(), CatalogUpdateError> {
     let mut txn = catalog
         .start_transaction()
         .await
         .context(TransactionSnafu)?;
 
+    // The tombstones processed for every original file do not delete any row of the compacted
+    // files either
+    let original_processed_tombstones = txn
+        .processed_tombstones()
+        .list_by_parquet_file_ids(original_parquet_file_ids)
+        .await
+        .context(ListProcessedTombstonesSnafu)?;
+    let mut tombstone_ids = original_parquet_file_ids
+        .iter()
+        .map(|&parquet_file_id| {
+            original_processed_tombstones
+                .iter()
+                .filter(|pt| pt.parquet_file_id == parquet_file_id)
+                .map(|pt| pt.tombstone_id)
+                .collect::<BTreeSet<_>>()
+        })
+        .reduce(|a, b| &a & &b)
+        .unwrap_or_default();
+    tombstone_ids.extend(processed_tombstone_ids.iter().copied());
+
     // Create the new parquet file in the catalog first
     for parquet_file in compacted_parquet_files {
         debug!(
@@ -637,10 +795,18 @@ async fn update_catalog(
             "updating catalog"
         );
 
-        txn.parquet_files()
+        let parquet_file = txn
+            .parquet_files()
             .create(parquet_file)
             .await
             .context(UpdateSnafu)?;
+
+        for &tombstone_id in &tombstone_ids {
+            txn.processed_tombstones()
+                .create(parquet_file.id, tombstone_id)
+                .await
+                .context(ProcessedTombstoneSnafu)?;
+        }
     }
 
     // Mark input files for deletion
@@ -654,6 +820,26 @@ async fn update_catalog(
     txn.commit().await.context(TransactionCommitSnafu)
 }
 
+async fn record_processed_tombstones(
+    catalog: Arc<dyn Catalog>,
+    parquet_file_id: ParquetFileId,
+    tombstone_ids: &[TombstoneId],
+) -> Result<(), CatalogUpdateError> {
+    let mut txn = catalog
+        .start_transaction()
+        .await
+        .context(TransactionSnafu)?;
+
+    for &tombstone_id in tombstone_ids {
+        txn.processed_tombstones()
+            .create(parquet_file_id, tombstone_id)
+            .await
+            .context(ProcessedTombstoneSnafu)?;
+    }
+
+    txn.commit().await.context(TransactionCommitSnafu)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parquet_file::CompactorParquetFile;
@@ -0,0 +1,81 @@
+//! Fully compact the partitions that were queued through the compactor admin API.
+
+use crate::{
+    compact::Compactor, compact_candidates_with_memory_budget, compact_in_parallel,
+    utils::get_candidates_with_retry,
+};
+use data_types::CompactionLevel;
+use metric::Attributes;
+use observability_deps::tracing::*;
+use std::sync::Arc;
+
+/// Full compaction of the queued partitions. Returns the number of compacted partitions.
+pub async fn compact(compactor: Arc<Compactor>) -> usize {
+    let compaction_type = "queued";
+
+    let partition_ids = compactor.take_queued_partitions();
+    if partition_ids.is_empty() {
+        debug!(compaction_type, "no queued partitions");
+        return 0;
+    }
+    let partition_ids = Arc::new(partition_ids);
+
+    let candidates = get_candidates_with_retry(
+        Arc::clone(&compactor),
+        compaction_type,
+        move |compactor_for_retry| {
+            let partition_ids = Arc::clone(&partition_ids);
+            async move {
+                compactor_for_retry
+                    .queued_partitions_to_compact(&partition_ids)
+                    .await
+            }
+        },
+    )
+    .await;
+
+    let n_candidates = candidates.len();
+    if n_candidates == 0 {
+        debug!(compaction_type, "no compaction candidates found");
+        return 0;
+    } else {
+        debug!(n_candidates, compaction_type, "found compaction candidates");
+    }
+
+    let start_time = compactor.time_provider.now();
+
+    // Compact any remaining level 0 files in parallel
+    compact_candidates_with_memory_budget(
+        Arc::clone(&compactor),
+        compaction_type,
+        CompactionLevel::Initial,
+        compact_in_parallel,
+        true, // split
+        candidates.clone().into(),
+    )
+    .await;
+
+    // Compact level 1 files in parallel ("full compaction")
+    compact_candidates_with_memory_budget(
+        Arc::clone(&compactor),
+        compaction_type,
+        CompactionLevel::FileNonOverlapped,
+        compact_in_parallel,
+        false, // don't split
+        candidates.into(),
+    )
+    .await;
+
+    // Done compacting all candidates in the cycle, record its time
+    if let Some(delta) = compactor
+        .time_provider
+        .now()
+        .checked_duration_since(start_time)
+    {
+        let attributes = Attributes::from(&[("partition_type", compaction_type)]);
+        let duration = compactor.compaction_cycle_duration.recorder(attributes);
+        duration.record(delta);
+    }
+
+    n_candidates
+}
@@ -2,15 +2,20 @@
 
 use std::sync::Arc;
 
+use self::grpc::GrpcDelegate;
 use crate::handler::CompactorHandler;
 use std::fmt::Debug;
 
+pub mod grpc;
+
 /// The [`CompactorServer`] manages the lifecycle and contains all state for a
 /// `compactor` server instance.
 #[derive(Debug, Default)]
 pub struct CompactorServer<C: CompactorHandler> {
     metrics: Arc<metric::Registry>,
 
+    grpc: GrpcDelegate<C>,
+
     handler: Arc<C>,
 }
 
@@ -18,7 +23,11 @@ impl<C: CompactorHandler> CompactorServer<C> {
     /// Initialise a new [`CompactorServer`] using the provided HTTP and gRPC
     /// handlers.
     pub fn new(metrics: Arc<metric::Registry>, handler: Arc<C>) -> Self {
-        Self { metrics, handler }
+        Self {
+            metrics,
+            grpc: GrpcDelegate::new(Arc::clone(&handler)),
+            handler,
+        }
     }
 
     /// Return the [`metric::Registry`] used by the router.
@@ -36,3 +45,10 @@ impl<C: CompactorHandler> CompactorServer<C> {
         self.handler.shutdown();
     }
 }
+
+impl<C: CompactorHandler + Debug> CompactorServer<C> {
+    /// Get a reference to the compactor gRPC delegate.
+    pub fn grpc(&self) -> &GrpcDelegate<C> {
+        &self.grpc
+    }
+}
@@ -0,0 +1,162 @@
+//! gRPC service implementations for `compactor`.
+
+use std::sync::Arc;
+
+use data_types::{PartitionId, SkippedCompaction};
+use generated_types::influxdata::iox::compactor::v1::{
+    self as proto,
+    compactor_admin_service_server::{CompactorAdminService, CompactorAdminServiceServer},
+};
+use tonic::{Request, Response};
+
+use crate::handler::{CompactionCandidate, CompactorHandler, Error};
+
+/// This type is responsible for managing all gRPC services exposed by
+/// `compactor`.
+#[derive(Debug, Default)]
+pub struct GrpcDelegate<C: CompactorHandler> {
+    handler: Arc<C>,
+}
+
+impl<C: CompactorHandler> GrpcDelegate<C> {
+    /// Initialise a new [`GrpcDelegate`] passing requests to the specified
+    /// `handler`.
+    pub fn new(handler: Arc<C>) -> Self {
+        Self { handler }
+    }
+}
+
+impl<C: CompactorHandler + 'static> GrpcDelegate<C> {
+    /// Acquire a compactor admin gRPC service implementation.
+    pub fn admin_service(&self) -> CompactorAdminServiceServer<impl CompactorAdminService> {
+        CompactorAdminServiceServer::new(AdminServiceImpl {
+            handler: Arc::clone(&self.handler) as _,
+        })
+    }
+}
+
+/// Implementation of the compactor admin API
+struct AdminServiceImpl {
+    handler: Arc<dyn CompactorHandler + 'static>,
+}
+
+#[tonic::async_trait]
+impl CompactorAdminService for AdminServiceImpl {
+    async fn list_skipped_compactions(
+        &self,
+        _request: Request<proto::ListSkippedCompactionsRequest>,
+    ) -> Result<Response<proto::ListSkippedCompactionsResponse>, tonic::Status> {
+        let skipped_compactions = self
+            .handler
+            .skipped_compactions()
+            .await
+            .map_err(to_status)?
+            .into_iter()
+            .map(to_skipped_compaction)
+            .collect();
+
+        Ok(Response::new(proto::ListSkippedCompactionsResponse {
+            skipped_compactions,
+        }))
+    }
+
+    async fn clear_skipped_compaction(
+        &self,
+        request: Request<proto::ClearSkippedCompactionRequest>,
+    ) -> Result<Response<proto::ClearSkippedCompactionResponse>, tonic::Status> {
+        let partition_id = PartitionId::new(request.into_inner().partition_id);
+        let skipped_compaction = self
+            .handler
+            .clear_skipped_compaction(partition_id)
+            .await
+            .map_err(to_status)?
+            .map(to_skipped_compaction);
+
+        Ok(Response::new(proto::ClearSkippedCompactionResponse {
+            skipped_compaction,
+        }))
+    }
+
+    async fn queue_partition(
+        &self,
+        request: Request<proto::QueuePartitionRequest>,
+    ) -> Result<Response<proto::QueuePartitionResponse>, tonic::Status> {
+        let partition_id = PartitionId::new(request.into_inner().partition_id);
+        self.handler
+            .queue_partition(partition_id)
+            .await
+            .map_err(to_status)?;
+
+        Ok(Response::new(proto::QueuePartitionResponse {}))
+    }
+
+    async fn queue_table(
+        &self,
+        request: Request<proto::QueueTableRequest>,
+    ) -> Result<Response<proto::QueueTableResponse>, tonic::Status> {
+        let proto::QueueTableRequest { namespace, table } = request.into_inner();
+        let partition_ids = self
+            .handler
+            .queue_table(&namespace, &table)
+            .await
+            .map_err(to_status)?
+            .into_iter()
+            .map(|id| id.get())
+            .collect();
+
+        Ok(Response::new(proto::QueueTableResponse { partition_ids }))
+    }
+
+    async fn list_compaction_candidates(
+        &self,
+        _request: Request<proto::ListCompactionCandidatesRequest>,
+    ) -> Result<Response<proto::ListCompactionCandidatesResponse>, tonic::Status> {
+        let candidates = self
+            .handler
+            .compaction_candidates()
+            .await
+            .map_err(to_status)?
+            .into_iter()
+            .map(to_compaction_candidate)
+            .collect();
+
+        Ok(Response::new(proto::ListCompactionCandidatesResponse {
+            candidates,
+            memory_budget_bytes: self.handler.memory_budget_bytes(),
+        }))
+    }
+}
+
+fn to_status(e: Error) -> tonic::Status {
+    match e {
+        Error::PartitionNotFound(_) | Error::NamespaceNotFound(_) | Error::TableNotFound { .. } => {
+            tonic::Status::not_found(e.to_string())
+        }
+        Error::ShardNotHandled { .. } => tonic::Status::failed_precondition(e.to_string()),
+        Error::Catalog(_) | Error::Candidates(_) | Error::ParquetFiles { .. } => {
+            tonic::Status::internal(e.to_string())
+        }
+    }
+}
+
+fn to_skipped_compaction(s: SkippedCompaction) -> proto::SkippedCompaction {
+    proto::SkippedCompaction {
+        partition_id: s.partition_id.get(),
+        reason: s.reason,
+        skipped_at: s.skipped_at.get(),
+    }
+}
+
+fn to_compaction_candidate(c: CompactionCandidate) -> proto::CompactionCandidate {
+    proto::CompactionCandidate {
+        partition_id: c.partition_id.get(),
+        shard_id: c.shard_id.get(),
+        namespace: c.namespace,
+        table: c.table,
+        partition_key: c.partition_key.to_string(),
+        compaction_type: c.compaction_type.to_string(),
+        num_level_0_files: c.num_level_0_files as u64,
+        num_level_1_files: c.num_level_1_files as u64,
+        estimated_memory_bytes: c.estimated_memory_bytes,
+    }
+}
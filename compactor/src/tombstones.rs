@@ -0,0 +1,691 @@
+//! Materialize deletes: rewrite the parquet files covered by tombstones with the deleted rows
+//! removed so queriers no longer have to apply the tombstones at query time, then remove the
+//! tombstones once every file they cover has been processed and the garbage collector deleted
+//! the files replaced by the rewrites.
+
+use crate::{compact::Compactor, parquet_file::CompactorParquetFile, parquet_file_combining};
+use data_types::{
+    ParquetFile, ParquetFileId, PartitionParam, SequenceNumber, ShardId, TableId, Tombstone,
+    TombstoneId,
+};
+use observability_deps::tracing::*;
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
+
+#[derive(Debug, Snafu)]
+#[allow(missing_copy_implementations, missing_docs)]
+pub(crate) enum Error {
+    #[snafu(display("Error listing the tombstones of shard {}: {}", shard_id, source))]
+    ListingTombstones {
+        source: iox_catalog::interface::Error,
+        shard_id: ShardId,
+    },
+
+    #[snafu(display("Error listing the parquet files of table {}: {}", table_id, source))]
+    ListingParquetFiles {
+        source: iox_catalog::interface::Error,
+        table_id: TableId,
+    },
+
+    #[snafu(display("Error querying processed tombstones {}", source))]
+    QueryingProcessedTombstones {
+        source: iox_catalog::interface::Error,
+    },
+
+    #[snafu(display("Error removing tombstones {}", source))]
+    RemovingTombstones {
+        source: iox_catalog::interface::Error,
+    },
+
+    #[snafu(display(
+        "Error getting the partition of parquet file {}: {}",
+        parquet_file_id,
+        source
+    ))]
+    PartitionInfo {
+        source: crate::compact::Error,
+        parquet_file_id: ParquetFileId,
+    },
+
+    #[snafu(display("Error rewriting parquet file {}: {}", parquet_file_id, source))]
+    Rewrite {
+        source: parquet_file_combining::Error,
+        parquet_file_id: ParquetFileId,
+    },
+}
+
+/// A specialized `Error` for materializing deletes
+pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Materialize the deletes of at most `max_num_files_to_materialize_deletes` parquet files and
+/// remove the tombstones that no longer cover any unprocessed or replaced file. Returns the number
+/// of processed parquet files.
+pub async fn materialize(compactor: Arc<Compactor>) -> usize {
+    let max_files = compactor.config.max_num_files_to_materialize_deletes;
+    if max_files == 0 {
+        return 0;
+    }
+
+    let mut processed_files = 0;
+    for &shard_id in &compactor.shards {
+        if processed_files >= max_files {
+            break;
+        }
+
+        let (covered_files, fully_processed) =
+            match covered_files(&compactor, shard_id, max_files - processed_files).await {
+                Ok(v) => v,
+                Err(e) => {
+                    warn!(
+                        ?e,
+                        ?shard_id,
+                        "could not find the files covered by tombstones"
+                    );
+                    continue;
+                }
+            };
+
+        if !fully_processed.is_empty() {
+            match remove_tombstones(&compactor, &fully_processed).await {
+                Ok(()) => info!(
+                    ?shard_id,
+                    num_tombstones = fully_processed.len(),
+                    "removed fully processed tombstones"
+                ),
+                Err(e) => warn!(?e, ?shard_id, "could not remove fully processed tombstones"),
+            }
+        }
+
+        for (file, tombstones) in covered_files {
+            let parquet_file_id = file.id;
+            match materialize_file(&compactor, file, tombstones).await {
+                Ok(()) => processed_files += 1,
+                Err(e) => warn!(?e, ?parquet_file_id, "materializing deletes failed"),
+            }
+        }
+    }
+
+    processed_files
+}
+
+/// Return at most `max_files` parquet files of the shard that are not flagged for deletion,
+/// together with the tombstones covering them that have not been processed for them yet, oldest
+/// file first. Also return the tombstones of the shard that can be removed because they do not
+/// cover any unprocessed file anymore.
+///
+/// A tombstone covers a file of its table if the file only contains data written before the
+/// delete and the time ranges of the file and the tombstone overlap.
+///
+/// A tombstone is kept as long as it covers a file that is flagged for deletion but was not
+/// deleted by the garbage collector yet: queriers may still be reading the replaced file from
+/// their caches and rely on the tombstone to hide the deleted rows.
+///
+/// Tables are visited in order of their ID and no more tables are listed once `max_files` covered
+/// files were found; the tombstones of the remaining tables are left for later cycles.
+pub(crate) async fn covered_files(
+    compactor: &Compactor,
+    shard_id: ShardId,
+    max_files: usize,
+) -> Result<(Vec<(ParquetFile, Vec<Tombstone>)>, Vec<TombstoneId>)> {
+    let mut repos = compactor.catalog.repositories().await;
+
+    let tombstones = repos
+        .tombstones()
+        .list_tombstones_by_shard_greater_than(shard_id, SequenceNumber::new(-1))
+        .await
+        .context(ListingTombstonesSnafu { shard_id })?;
+
+    let mut tombstones_by_table: BTreeMap<TableId, Vec<Tombstone>> = BTreeMap::new();
+    for tombstone in tombstones {
+        tombstones_by_table
+            .entry(tombstone.table_id)
+            .or_default()
+            .push(tombstone);
+    }
+
+    let mut covered: BTreeMap<ParquetFileId, (ParquetFile, Vec<Tombstone>)> = BTreeMap::new();
+    let mut fully_processed = vec![];
+    for (table_id, tombstones) in tombstones_by_table {
+        if covered.len() >= max_files {
+            break;
+        }
+
+        let files: Vec<_> = repos
+            .parquet_files()
+            .list_by_table_not_to_delete(table_id)
+            .await
+            .context(ListingParquetFilesSnafu { table_id })?
+            .into_iter()
+            .filter(|f| f.shard_id == shard_id)
+            .collect();
+        let replaced_files: Vec<_> = repos
+            .parquet_files()
+            .list_by_table_to_delete(table_id)
+            .await
+            .context(ListingParquetFilesSnafu { table_id })?
+            .into_iter()
+            .filter(|f| f.shard_id == shard_id)
+            .collect();
+
+        let tombstone_ids: Vec<_> = tombstones.iter().map(|t| t.id).collect();
+        let processed_tombstones: HashSet<_> = repos
+            .processed_tombstones()
+            .list_by_tombstone_ids(&tombstone_ids)
+            .await
+            .context(QueryingProcessedTombstonesSnafu)?
+            .into_iter()
+            .map(|pt| (pt.parquet_file_id, pt.tombstone_id))
+            .collect();
+
+        for tombstone in tombstones {
+            let mut processed = !replaced_files.iter().any(|f| covers(&tombstone, f));
+            for file in files.iter().filter(|f| covers(&tombstone, f)) {
+                if processed_tombstones.contains(&(file.id, tombstone.id)) {
+                    continue;
+                }
+
+                processed = false;
+                covered
+                    .entry(file.id)
+                    .or_insert_with(|| (file.clone(), vec![]))
+                    .1
+                    .push(tombstone.clone());
+            }
+
+            if processed {
+                fully_processed.push(tombstone.id);
+            }
+        }
+    }
+
+    Ok((
+        covered.into_values().take(max_files).collect(),
+        fully_processed,
+    ))
+}
+
+fn covers(tombstone: &Tombstone, file: &ParquetFile) -> bool {
+    file.max_sequence_number < tombstone.sequence_number
+        && file.min_time <= tombstone.max_time
+        && file.max_time >= tombstone.min_time
+}
+
+async fn remove_tombstones(compactor: &Compactor, tombstone_ids: &[TombstoneId]) -> Result<()> {
+    compactor
+        .catalog
+        .repositories()
+        .await
+        .tombstones()
+        .remove(tombstone_ids)
+        .await
+        .context(RemovingTombstonesSnafu)
+}
+
+async fn materialize_file(
+    compactor: &Compactor,
+    file: ParquetFile,
+    tombstones: Vec<Tombstone>,
+) -> Result<()> {
+    let parquet_file_id = file.id;
+    let candidates = [PartitionParam {
+        partition_id: file.partition_id,
+        shard_id: file.shard_id,
+        namespace_id: file.namespace_id,
+        table_id: file.table_id,
+    }];
+    let table_columns = compactor
+        .table_columns(&candidates)
+        .await
+        .context(PartitionInfoSnafu { parquet_file_id })?;
+    let partition = compactor
+        .add_info_to_partitions(&candidates, &table_columns)
+        .await
+        .context(PartitionInfoSnafu { parquet_file_id })?
+        .pop()
+        .expect("one partition requested");
+
+    parquet_file_combining::materialize_deletes(
+        // The estimated arrow size is only used for choosing files to compact
+        CompactorParquetFile::new(file, 0),
+        tombstones,
+        partition,
+        Arc::clone(&compactor.catalog),
+        compactor.store.clone(),
+        Arc::clone(&compactor.exec),
+        Arc::clone(&compactor.time_provider),
+    )
+    .await
+    .context(RewriteSnafu { parquet_file_id })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::CompactorConfig;
+    use arrow_util::assert_batches_sorted_eq;
+    use backoff::BackoffConfig;
+    use data_types::{ColumnType, CompactionLevel, Timestamp};
+    use iox_query::exec::Executor;
+    use iox_tests::util::{TestCatalog, TestParquetFileBuilder};
+    use iox_time::SystemProvider;
+    use parquet_file::storage::ParquetStorage;
+
+    fn make_compactor_config() -> CompactorConfig {
+        CompactorConfig {
+            max_desired_file_size_bytes: 10_000,
+            percentage_max_file_size: 30,
+            split_percentage: 80,
+            max_number_partitions_per_shard: 1,
+            min_number_recent_ingested_files_per_partition: 1,
+            hot_multiple: 4,
+            memory_budget_bytes: 100_000_000,
+            min_num_rows_allocated_per_record_batch_to_datafusion_plan: 1,
+            max_num_compacting_files: 20,
+            max_num_files_to_materialize_deletes: 10,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_materialize_deletes() {
+        test_helpers::maybe_start_logging();
+        let catalog = TestCatalog::new();
+
+        let lp1 = vec![
+            "table,tag1=WA field_int=1000i 10",
+            "table,tag1=VT field_int=10i 20",
+            "table,tag1=UT field_int=70i 30",
+        ]
+        .join("\n");
+        let lp2 = vec![
+            "table,tag1=WA field_int=1500i 8000",
+            "table,tag1=VT field_int=20i 9000",
+        ]
+        .join("\n");
+        let lp3 = vec!["table,tag1=VT field_int=30i 40"].join("\n");
+
+        let ns = catalog.create_namespace("ns").await;
+        let shard = ns.create_shard(1).await;
+        let table = ns.create_table("table").await;
+        table.create_column("field_int", ColumnType::I64).await;
+        table.create_column("tag1", ColumnType::Tag).await;
+        table.create_column("tag2", ColumnType::Tag).await;
+        table.create_column("time", ColumnType::Time).await;
+        let table_shard = table.with_shard(&shard);
+        let partition = table_shard.create_partition("part").await;
+
+        let compactor = Arc::new(Compactor::new(
+            vec![shard.shard.id],
+            Arc::clone(&catalog.catalog),
+            ParquetStorage::new(Arc::clone(&catalog.object_store)),
+            Arc::new(Executor::new(1)),
+            Arc::new(SystemProvider::new()),
+            BackoffConfig::default(),
+            make_compactor_config(),
+            Arc::new(metric::Registry::new()),
+        ));
+
+        // pf1 is covered by both tombstones
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol(&lp1)
+            .with_max_seq(3)
+            .with_min_time(10)
+            .with_max_time(30)
+            .with_compaction_level(CompactionLevel::FileNonOverlapped);
+        let pf1 = partition.create_parquet_file(builder).await;
+        // pf2 does not overlap the time range of the tombstones
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol(&lp2)
+            .with_max_seq(4)
+            .with_min_time(8000)
+            .with_max_time(9000);
+        let pf2 = partition.create_parquet_file(builder).await;
+        // pf3 was written after the deletes
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol(&lp3)
+            .with_max_seq(20)
+            .with_min_time(40)
+            .with_max_time(40);
+        let pf3 = partition.create_parquet_file(builder).await;
+
+        let ts1 = table_shard.create_tombstone(10, 1, 100, "tag1=VT").await;
+        // pf1 has no tag2 column, so this tombstone cannot delete any of its rows
+        let ts2 = table_shard.create_tombstone(11, 1, 100, "tag2=PA").await;
+
+        // ------------------------------------------------
+        // Materialize the deletes of pf1
+        let (covered, fully_processed) =
+            covered_files(&compactor, shard.shard.id, 10).await.unwrap();
+        assert_eq!(covered.len(), 1);
+        assert_eq!(covered[0].0.id, pf1.parquet_file.id);
+        assert_eq!(covered[0].1.len(), 2);
+        assert!(fully_processed.is_empty());
+
+        assert_eq!(materialize(Arc::clone(&compactor)).await, 1);
+
+        let files = catalog.list_by_table_not_to_delete(table.table.id).await;
+        assert_eq!(files.len(), 3);
+        let rewritten = files
+            .iter()
+            .find(|f| {
+                ![
+                    pf1.parquet_file.id,
+                    pf2.parquet_file.id,
+                    pf3.parquet_file.id,
+                ]
+                .contains(&f.id)
+            })
+            .unwrap()
+            .clone();
+        assert_eq!(
+            rewritten.compaction_level,
+            CompactionLevel::FileNonOverlapped
+        );
+        assert_eq!(rewritten.max_sequence_number, SequenceNumber::new(3));
+        assert_eq!(
+            catalog.count_processed_tombstones(ts1.tombstone.id).await,
+            1
+        );
+        assert_eq!(
+            catalog.count_processed_tombstones(ts2.tombstone.id).await,
+            1
+        );
+
+        let batches = table.read_parquet_file(rewritten).await;
+        assert_batches_sorted_eq!(
+            &[
+                "+-----------+------+--------------------------------+",
+                "| field_int | tag1 | time                           |",
+                "+-----------+------+--------------------------------+",
+                "| 1000      | WA   | 1970-01-01T00:00:00.000000010Z |",
+                "| 70        | UT   | 1970-01-01T00:00:00.000000030Z |",
+                "+-----------+------+--------------------------------+",
+            ],
+            &batches
+        );
+
+        // ------------------------------------------------
+        // Every covered file is processed, but the tombstones are kept until the replaced file
+        // gets deleted
+        assert_eq!(materialize(Arc::clone(&compactor)).await, 0);
+        assert_eq!(catalog.count_tombstones_for_table(table.table.id).await, 2);
+
+        catalog
+            .catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .delete_old(Timestamp::new(i64::MAX))
+            .await
+            .unwrap();
+        assert_eq!(materialize(Arc::clone(&compactor)).await, 0);
+        assert_eq!(catalog.count_tombstones_for_table(table.table.id).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_covered_files_max_files() {
+        test_helpers::maybe_start_logging();
+        let catalog = TestCatalog::new();
+
+        let ns = catalog.create_namespace("ns").await;
+        let shard = ns.create_shard(1).await;
+
+        let compactor = Compactor::new(
+            vec![shard.shard.id],
+            Arc::clone(&catalog.catalog),
+            ParquetStorage::new(Arc::clone(&catalog.object_store)),
+            Arc::new(Executor::new(1)),
+            Arc::new(SystemProvider::new()),
+            BackoffConfig::default(),
+            make_compactor_config(),
+            Arc::new(metric::Registry::new()),
+        );
+
+        // Each table has one file covered by one tombstone
+        let mut files = vec![];
+        let mut tombstones = vec![];
+        for name in ["table1", "table2"] {
+            let table = ns.create_table(name).await;
+            table.create_column("field_int", ColumnType::I64).await;
+            table.create_column("time", ColumnType::Time).await;
+            let table_shard = table.with_shard(&shard);
+            let partition = table_shard.create_partition("part").await;
+
+            let builder = TestParquetFileBuilder::default()
+                .with_line_protocol(&format!("{} field_int=1i 10", name))
+                .with_max_seq(3)
+                .with_min_time(10)
+                .with_max_time(10);
+            files.push(partition.create_parquet_file(builder).await);
+            tombstones.push(
+                table_shard
+                    .create_tombstone(10, 1, 100, "field_int=1")
+                    .await,
+            );
+        }
+
+        let (covered, fully_processed) =
+            covered_files(&compactor, shard.shard.id, 2).await.unwrap();
+        assert_eq!(covered.len(), 2);
+        assert!(fully_processed.is_empty());
+
+        // The second table is not listed once the first one used up the budget
+        let (covered, fully_processed) =
+            covered_files(&compactor, shard.shard.id, 1).await.unwrap();
+        assert_eq!(covered.len(), 1);
+        assert_eq!(covered[0].0.id, files[0].parquet_file.id);
+        assert_eq!(covered[0].1[0].id, tombstones[0].tombstone.id);
+        assert!(fully_processed.is_empty());
+
+        // Processed tombstones are excluded, and reported once they cover no unprocessed file
+        catalog
+            .catalog
+            .repositories()
+            .await
+            .processed_tombstones()
+            .create(files[0].parquet_file.id, tombstones[0].tombstone.id)
+            .await
+            .unwrap();
+        let (covered, fully_processed) =
+            covered_files(&compactor, shard.shard.id, 1).await.unwrap();
+        assert_eq!(covered.len(), 1);
+        assert_eq!(covered[0].0.id, files[1].parquet_file.id);
+        assert_eq!(fully_processed, vec![tombstones[0].tombstone.id]);
+    }
+
+    #[tokio::test]
+    async fn test_materialize_deletes_keeps_processed_tombstones() {
+        test_helpers::maybe_start_logging();
+        let catalog = TestCatalog::new();
+
+        let lp = vec![
+            "table,tag1=WA field_int=1000i 10",
+            "table,tag1=VT field_int=10i 20",
+            "table,tag1=UT field_int=70i 30",
+        ]
+        .join("\n");
+
+        let ns = catalog.create_namespace("ns").await;
+        let shard = ns.create_shard(1).await;
+        let table = ns.create_table("table").await;
+        table.create_column("field_int", ColumnType::I64).await;
+        table.create_column("tag1", ColumnType::Tag).await;
+        table.create_column("time", ColumnType::Time).await;
+        let table_shard = table.with_shard(&shard);
+        let partition = table_shard.create_partition("part").await;
+
+        let compactor = Arc::new(Compactor::new(
+            vec![shard.shard.id],
+            Arc::clone(&catalog.catalog),
+            ParquetStorage::new(Arc::clone(&catalog.object_store)),
+            Arc::new(Executor::new(1)),
+            Arc::new(SystemProvider::new()),
+            BackoffConfig::default(),
+            make_compactor_config(),
+            Arc::new(metric::Registry::new()),
+        ));
+
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol(&lp)
+            .with_max_seq(3)
+            .with_min_time(10)
+            .with_max_time(30)
+            .with_compaction_level(CompactionLevel::FileNonOverlapped);
+        partition.create_parquet_file(builder).await;
+
+        let ts1 = table_shard.create_tombstone(10, 1, 100, "tag1=VT").await;
+        assert_eq!(materialize(Arc::clone(&compactor)).await, 1);
+
+        // The second rewrite keeps ts1 processed instead of applying it again
+        let ts2 = table_shard.create_tombstone(11, 1, 100, "tag1=WA").await;
+        let (covered, _) = covered_files(&compactor, shard.shard.id, 10).await.unwrap();
+        assert_eq!(covered.len(), 1);
+        assert_eq!(covered[0].1.len(), 1);
+        assert_eq!(covered[0].1[0].id, ts2.tombstone.id);
+
+        assert_eq!(materialize(Arc::clone(&compactor)).await, 1);
+        assert_eq!(
+            catalog.count_processed_tombstones(ts1.tombstone.id).await,
+            2
+        );
+        assert_eq!(
+            catalog.count_processed_tombstones(ts2.tombstone.id).await,
+            1
+        );
+        let (covered, _) = covered_files(&compactor, shard.shard.id, 10).await.unwrap();
+        assert!(covered.is_empty());
+
+        let files = catalog.list_by_table_not_to_delete(table.table.id).await;
+        assert_eq!(files.len(), 1);
+        let batches = table.read_parquet_file(files[0].clone()).await;
+        assert_batches_sorted_eq!(
+            &[
+                "+-----------+------+--------------------------------+",
+                "| field_int | tag1 | time                           |",
+                "+-----------+------+--------------------------------+",
+                "| 70        | UT   | 1970-01-01T00:00:00.000000030Z |",
+                "+-----------+------+--------------------------------+",
+            ],
+            &batches
+        );
+    }
+
+    #[tokio::test]
+    async fn test_materialize_deletes_with_missing_column() {
+        test_helpers::maybe_start_logging();
+        let catalog = TestCatalog::new();
+
+        let ns = catalog.create_namespace("ns").await;
+        let shard = ns.create_shard(1).await;
+        let table = ns.create_table("table").await;
+        table.create_column("field_int", ColumnType::I64).await;
+        table.create_column("tag1", ColumnType::Tag).await;
+        table.create_column("tag2", ColumnType::Tag).await;
+        table.create_column("time", ColumnType::Time).await;
+        let table_shard = table.with_shard(&shard);
+        let partition = table_shard.create_partition("part").await;
+
+        let compactor = Arc::new(Compactor::new(
+            vec![shard.shard.id],
+            Arc::clone(&catalog.catalog),
+            ParquetStorage::new(Arc::clone(&catalog.object_store)),
+            Arc::new(Executor::new(1)),
+            Arc::new(SystemProvider::new()),
+            BackoffConfig::default(),
+            make_compactor_config(),
+            Arc::new(metric::Registry::new()),
+        ));
+
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol("table,tag1=WA field_int=1000i 10")
+            .with_max_seq(3)
+            .with_min_time(10)
+            .with_max_time(10)
+            .with_compaction_level(CompactionLevel::FileNonOverlapped);
+        let pf = partition.create_parquet_file(builder).await;
+
+        // The file has no tag2 column, queriers do not delete any of its rows with this tombstone
+        // either (see `querier::chunk::tests::test_delete_predicates_on_missing_columns`)
+        let ts = table_shard.create_tombstone(10, 1, 100, "tag2=PA").await;
+
+        assert_eq!(materialize(Arc::clone(&compactor)).await, 1);
+        assert_eq!(catalog.count_processed_tombstones(ts.tombstone.id).await, 1);
+
+        // The file is not rewritten
+        let files = catalog.list_by_table_not_to_delete(table.table.id).await;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].id, pf.parquet_file.id);
+
+        // Nothing was replaced, the tombstone is removed right away
+        assert_eq!(materialize(Arc::clone(&compactor)).await, 0);
+        assert_eq!(catalog.count_tombstones_for_table(table.table.id).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_materialize_deletes_or_with_missing_column() {
+        test_helpers::maybe_start_logging();
+        let catalog = TestCatalog::new();
+
+        let lp = vec![
+            "table,tag1=WA field_int=1000i 10",
+            "table,tag1=VT field_int=10i 20",
+            "table,tag1=UT field_int=70i 30",
+        ]
+        .join("\n");
+
+        let ns = catalog.create_namespace("ns").await;
+        let shard = ns.create_shard(1).await;
+        let table = ns.create_table("table").await;
+        table.create_column("field_int", ColumnType::I64).await;
+        table.create_column("tag1", ColumnType::Tag).await;
+        table.create_column("tag2", ColumnType::Tag).await;
+        table.create_column("time", ColumnType::Time).await;
+        let table_shard = table.with_shard(&shard);
+        let partition = table_shard.create_partition("part").await;
+
+        let compactor = Arc::new(Compactor::new(
+            vec![shard.shard.id],
+            Arc::clone(&catalog.catalog),
+            ParquetStorage::new(Arc::clone(&catalog.object_store)),
+            Arc::new(Executor::new(1)),
+            Arc::new(SystemProvider::new()),
+            BackoffConfig::default(),
+            make_compactor_config(),
+            Arc::new(metric::Registry::new()),
+        ));
+
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol(&lp)
+            .with_max_seq(3)
+            .with_min_time(10)
+            .with_max_time(30)
+            .with_compaction_level(CompactionLevel::FileNonOverlapped);
+        let pf = partition.create_parquet_file(builder).await;
+
+        // The file has no tag2 column, but the other branch of the OR still deletes rows
+        let ts = table_shard
+            .create_tombstone(10, 1, 100, "tag2=PA OR field_int > 500 OR tag1 =~ /^V/")
+            .await;
+
+        assert_eq!(materialize(Arc::clone(&compactor)).await, 1);
+        assert_eq!(catalog.count_processed_tombstones(ts.tombstone.id).await, 1);
+
+        let files = catalog.list_by_table_not_to_delete(table.table.id).await;
+        assert_eq!(files.len(), 1);
+        assert_ne!(files[0].id, pf.parquet_file.id);
+
+        let batches = table.read_parquet_file(files[0].clone()).await;
+        assert_batches_sorted_eq!(
+            &[
+                "+-----------+------+--------------------------------+",
+                "| field_int | tag1 | time                           |",
+                "+-----------+------+--------------------------------+",
+                "| 70        | UT   | 1970-01-01T00:00:00.000000030Z |",
+                "+-----------+------+--------------------------------+",
+            ],
+            &batches
+        );
+    }
+}
@@ -30,6 +30,7 @@ use std::{
     num::{FpCategory, NonZeroU64},
     ops::{Add, Deref, RangeInclusive, Sub},
     sync::Arc,
+    time::Duration,
 };
 use uuid::Uuid;
 
@@ -303,6 +304,27 @@ impl std::fmt::Display for TombstoneId {
     }
 }
 
+/// Unique ID for a `NamespaceToken`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct NamespaceTokenId(i64);
+
+#[allow(missing_docs)]
+impl NamespaceTokenId {
+    pub fn new(v: i64) -> Self {
+        Self(v)
+    }
+    pub fn get(&self) -> i64 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for NamespaceTokenId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// A sequence number from a `router::Shard` (kafka partition)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::Type)]
 #[sqlx(transparent)]
@@ -448,6 +470,205 @@ pub struct Namespace {
     pub max_tables: i32,
     /// The maximum number of columns per table in this namespace
     pub max_columns_per_table: i32,
+    /// The limits on the resources used by queries against this namespace
+    #[sqlx(flatten)]
+    pub query_limits: NamespaceQueryLimits,
+    /// The thresholds that trigger the persistence of the data ingesters
+    /// buffer for this namespace
+    #[sqlx(flatten)]
+    pub persistence_policy: NamespacePersistencePolicy,
+    /// The limits on the rate of the writes routed to this namespace
+    #[sqlx(flatten)]
+    pub write_limits: NamespaceWriteLimits,
+    /// When true, writes may only use the tables and columns declared ahead
+    /// of time, instead of implicitly creating them
+    pub strict_schema: bool,
+    /// How writes to a field column with a conflicting type are resolved
+    pub field_coercion_policy: FieldCoercionPolicy,
+}
+
+/// Limits on the resources used by the queries of a namespace, enforced by
+/// the querier on top of its own global limits.
+///
+/// A `None` (NULL) limit means that the querier limits apply.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct NamespaceQueryLimits {
+    /// The maximum number of queries of the namespace that may run
+    /// concurrently on a single querier
+    pub max_concurrent_queries: Option<i32>,
+    /// The maximum memory, in bytes, the operators of a single query may
+    /// reserve
+    pub max_query_memory_bytes: Option<i64>,
+    /// The maximum estimated size, in bytes, of the chunks a single query may
+    /// scan
+    pub max_query_scanned_bytes: Option<i64>,
+    /// The maximum wall-clock duration of a single query, in seconds
+    pub max_query_duration_seconds: Option<i64>,
+}
+
+impl NamespaceQueryLimits {
+    /// The maximum number of concurrent queries, if limited.
+    pub fn max_concurrent_queries(&self) -> Option<usize> {
+        self.max_concurrent_queries.map(|v| v.max(0) as usize)
+    }
+
+    /// The maximum memory of a single query in bytes, if limited.
+    pub fn max_query_memory_bytes(&self) -> Option<usize> {
+        self.max_query_memory_bytes.map(|v| v.max(0) as usize)
+    }
+
+    /// The maximum estimated bytes scanned by a single query, if limited.
+    pub fn max_query_scanned_bytes(&self) -> Option<u64> {
+        self.max_query_scanned_bytes.map(|v| v.max(0) as u64)
+    }
+
+    /// The maximum duration of a single query, if limited.
+    pub fn max_query_duration(&self) -> Option<Duration> {
+        self.max_query_duration_seconds
+            .map(|v| Duration::from_secs(v.max(0) as u64))
+    }
+}
+
+/// Thresholds that trigger the persistence of the data an ingester buffers
+/// for the partitions of a namespace, overriding the thresholds the ingester
+/// is configured with.
+///
+/// A `None` (NULL) threshold means that the ingester threshold applies. The
+/// memory limits of the ingester always apply.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct NamespacePersistencePolicy {
+    /// The age, in seconds, of the first write into a partition after which
+    /// the partition is persisted
+    pub partition_age_threshold_seconds: Option<i64>,
+    /// The time, in seconds, since the last write into a partition after
+    /// which the partition is persisted
+    pub partition_cold_threshold_seconds: Option<i64>,
+    /// The estimated size, in bytes, above which a partition is persisted
+    pub partition_size_threshold_bytes: Option<i64>,
+    /// The number of rows at which a partition is persisted
+    pub partition_row_max: Option<i64>,
+}
+
+impl NamespacePersistencePolicy {
+    /// The age threshold of a partition, if set.
+    pub fn partition_age_threshold(&self) -> Option<Duration> {
+        self.partition_age_threshold_seconds
+            .map(|v| Duration::from_secs(v.max(0) as u64))
+    }
+
+    /// The cold threshold of a partition, if set.
+    pub fn partition_cold_threshold(&self) -> Option<Duration> {
+        self.partition_cold_threshold_seconds
+            .map(|v| Duration::from_secs(v.max(0) as u64))
+    }
+
+    /// The size threshold of a partition in bytes, if set.
+    pub fn partition_size_threshold(&self) -> Option<usize> {
+        self.partition_size_threshold_bytes
+            .map(|v| v.max(0) as usize)
+    }
+
+    /// The maximum number of rows of a partition, if set.
+    pub fn partition_row_max(&self) -> Option<usize> {
+        self.partition_row_max.map(|v| v.max(0) as usize)
+    }
+}
+
+/// Limits on the rate at which a namespace may be written to, enforced by
+/// each router on the writes it receives.
+///
+/// A `None` (NULL) limit means that the writes are not throttled. Limits that
+/// are not positive are ignored.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct NamespaceWriteLimits {
+    /// The number of lines (rows) per second a router accepts for the
+    /// namespace
+    pub write_lines_per_second: Option<i64>,
+    /// The number of bytes per second a router accepts for the namespace, as
+    /// measured by the in-memory size of the decoded writes
+    pub write_bytes_per_second: Option<i64>,
+}
+
+impl NamespaceWriteLimits {
+    /// The number of lines per second, if limited.
+    pub fn lines_per_second(&self) -> Option<u64> {
+        self.write_lines_per_second
+            .filter(|v| *v > 0)
+            .map(|v| v as u64)
+    }
+
+    /// The number of bytes per second, if limited.
+    pub fn bytes_per_second(&self) -> Option<u64> {
+        self.write_bytes_per_second
+            .filter(|v| *v > 0)
+            .map(|v| v as u64)
+    }
+
+    /// Returns true if writes are throttled.
+    pub fn is_limited(&self) -> bool {
+        self.lines_per_second().is_some() || self.bytes_per_second().is_some()
+    }
+}
+
+/// How the router resolves a write to a field column whose type conflicts
+/// with the type the column was created with.
+///
+/// The policies are ordered, each one applying the coercions of the
+/// previous ones before its own.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash, sqlx::Type)]
+#[repr(i16)]
+pub enum FieldCoercionPolicy {
+    /// Reject the write.
+    Reject = 0,
+    /// Widen integer values to float, and unsigned values to integer, when
+    /// every value of the write converts losslessly.
+    Widen = 1,
+    /// Convert the values to strings when the column is a string column.
+    Stringify = 2,
+    /// Drop the conflicting field from the write, keeping the other fields
+    /// of its lines.
+    Drop = 3,
+}
+
+impl Default for FieldCoercionPolicy {
+    fn default() -> Self {
+        Self::Reject
+    }
+}
+
+impl FieldCoercionPolicy {
+    /// the short string description of the policy
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Reject => "reject",
+            Self::Widen => "widen",
+            Self::Stringify => "stringify",
+            Self::Drop => "drop",
+        }
+    }
+}
+
+impl std::fmt::Display for FieldCoercionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for FieldCoercionPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reject" => Ok(Self::Reject),
+            "widen" => Ok(Self::Widen),
+            "stringify" => Ok(Self::Stringify),
+            "drop" => Ok(Self::Drop),
+            _ => Err(format!(
+                "invalid field coercion policy {}, expected one of reject, widen, stringify or drop",
+                s
+            )),
+        }
+    }
 }
 
 /// Schema collection for a namespace. This is an in-memory object useful for a schema
@@ -462,6 +683,12 @@ pub struct NamespaceSchema {
     pub query_pool_id: QueryPoolId,
     /// the tables in the namespace by name
     pub tables: BTreeMap<String, TableSchema>,
+    /// the limits on the rate of the writes to the namespace
+    pub write_limits: NamespaceWriteLimits,
+    /// whether writes are restricted to the declared tables and columns
+    pub strict_schema: bool,
+    /// how writes to a field column with a conflicting type are resolved
+    pub field_coercion_policy: FieldCoercionPolicy,
 }
 
 impl NamespaceSchema {
@@ -472,6 +699,9 @@ impl NamespaceSchema {
             tables: BTreeMap::new(),
             topic_id,
             query_pool_id,
+            write_limits: Default::default(),
+            strict_schema: false,
+            field_coercion_policy: Default::default(),
         }
     }
 
@@ -1120,6 +1350,23 @@ pub struct ProcessedTombstone {
     pub parquet_file_id: ParquetFileId,
 }
 
+/// An API token granting read and/or write access to a namespace.
+///
+/// Only a hash of the token is stored in the catalog, never the token itself.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct NamespaceToken {
+    /// the id of the token grant
+    pub id: NamespaceTokenId,
+    /// the hex encoded SHA-256 hash of the token
+    pub token_hash: String,
+    /// the namespace the token grants access to
+    pub namespace_id: NamespaceId,
+    /// whether the token may be used to query the namespace
+    pub can_read: bool,
+    /// whether the token may be used to write to and delete from the namespace
+    pub can_write: bool,
+}
+
 /// ID of a chunk.
 ///
 /// This ID is unique within a single partition.
@@ -1282,6 +1529,25 @@ impl DeletePredicate {
         out
     }
 
+    /// Restrict this predicate to data that only contains the columns for
+    /// which `has_column` returns true.
+    ///
+    /// A condition on a column missing from the data never matches, so it is
+    /// dropped from `OR` groups. Returns `None` if the predicate cannot match
+    /// any row of such data.
+    pub fn restrict_to_columns(&self, has_column: impl Fn(&str) -> bool) -> Option<Self> {
+        let exprs = self
+            .exprs
+            .iter()
+            .map(|expr| expr.restrict_to_columns(&has_column))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            range: self.range,
+            exprs,
+        })
+    }
+
     /// Return the approximate memory size of the predicate, in bytes.
     ///
     /// This includes `Self`.
@@ -1292,57 +1558,180 @@ impl DeletePredicate {
 
 /// Single expression to be used as parts of a predicate.
 ///
-/// Only very simple expression of the type `<column> <op> <scalar>` are supported.
+/// Expressions are comparisons of a column with scalar values, optionally
+/// combined using `OR` and `AND`.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct DeleteExpr {
-    /// Column (w/o table name).
-    pub column: String,
+pub enum DeleteExpr {
+    /// `<column> <op> <scalar>`
+    Compare {
+        /// Column (w/o table name).
+        column: String,
+
+        /// Operator.
+        op: Op,
+
+        /// Scalar value. A string holding the pattern for the regex
+        /// operators.
+        scalar: Scalar,
+    },
 
-    /// Operator.
-    pub op: Op,
+    /// `<column> [NOT] IN (<scalar>, ...)`
+    InList {
+        /// Column (w/o table name).
+        column: String,
+
+        /// Scalar values to compare against.
+        list: Vec<Scalar>,
+
+        /// True for `NOT IN`.
+        negated: bool,
+    },
 
-    /// Scalar value.
-    pub scalar: Scalar,
+    /// Disjunction of expressions, aka they are 'OR'ed together.
+    Or(Vec<DeleteExpr>),
+
+    /// Conjunction of expressions, aka they are 'AND'ed together.
+    ///
+    /// Only required within an [`Or`](Self::Or), as [`DeletePredicate::exprs`]
+    /// are already a conjunction.
+    And(Vec<DeleteExpr>),
 }
 
 impl DeleteExpr {
-    /// Create a new [`DeleteExpr`]
+    /// Create a new [`DeleteExpr::Compare`]
     pub fn new(column: String, op: Op, scalar: Scalar) -> Self {
-        Self { column, op, scalar }
+        Self::Compare { column, op, scalar }
+    }
+
+    /// Create a new [`DeleteExpr::InList`]
+    pub fn in_list(column: String, list: Vec<Scalar>, negated: bool) -> Self {
+        Self::InList {
+            column,
+            list,
+            negated,
+        }
     }
 
-    /// Column (w/o table name).
-    pub fn column(&self) -> &str {
-        &self.column
+    /// Names of all columns (w/o table name) referenced by this expression,
+    /// in order of appearance. May contain duplicates.
+    pub fn columns(&self) -> Vec<&str> {
+        match self {
+            Self::Compare { column, .. } | Self::InList { column, .. } => vec![column.as_str()],
+            Self::Or(exprs) | Self::And(exprs) => {
+                exprs.iter().flat_map(|expr| expr.columns()).collect()
+            }
+        }
     }
 
-    /// Operator.
-    pub fn op(&self) -> Op {
-        self.op
+    /// Whether a row for which this expression evaluates to NULL is deleted.
+    ///
+    /// This is the case for plain `=` and `!=` comparisons, which existing
+    /// tombstones were written with and whose semantics must not change. All
+    /// other expressions (`OR`, `AND`, `IN`, regexes and ordering comparisons)
+    /// retain such rows.
+    pub fn deletes_null_rows(&self) -> bool {
+        matches!(
+            self,
+            Self::Compare {
+                op: Op::Eq | Op::Ne,
+                ..
+            }
+        )
     }
 
-    /// Scalar value.
-    pub fn scalar(&self) -> &Scalar {
-        &self.scalar
+    /// See [`DeletePredicate::restrict_to_columns`].
+    fn restrict_to_columns(&self, has_column: &impl Fn(&str) -> bool) -> Option<Self> {
+        match self {
+            Self::Compare { column, .. } | Self::InList { column, .. } => {
+                has_column(column).then(|| self.clone())
+            }
+            Self::Or(exprs) => {
+                let exprs: Vec<_> = exprs
+                    .iter()
+                    .filter_map(|expr| expr.restrict_to_columns(has_column))
+                    .collect();
+                // keep the group even if a single branch remains, see
+                // [`DeleteExpr::deletes_null_rows`]
+                (!exprs.is_empty()).then(|| Self::Or(exprs))
+            }
+            Self::And(exprs) => exprs
+                .iter()
+                .map(|expr| expr.restrict_to_columns(has_column))
+                .collect::<Option<Vec<_>>>()
+                .map(Self::And),
+        }
     }
 
     /// Return the approximate memory size of the expression, in bytes.
     ///
     /// This includes `Self`.
     pub fn size(&self) -> usize {
-        std::mem::size_of::<Self>() + self.column.capacity() + self.scalar.size()
+        std::mem::size_of::<Self>()
+            + match self {
+                Self::Compare { column, scalar, .. } => column.capacity() + scalar.size(),
+                Self::InList { column, list, .. } => {
+                    column.capacity() + list.iter().map(|scalar| scalar.size()).sum::<usize>()
+                }
+                Self::Or(exprs) | Self::And(exprs) => {
+                    exprs.iter().map(|expr| expr.size()).sum::<usize>()
+                }
+            }
     }
 }
 
 impl std::fmt::Display for DeleteExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            r#""{}"{}{}"#,
-            self.column().replace('\\', r#"\\"#).replace('"', r#"\""#),
-            self.op(),
-            self.scalar(),
-        )
+        fn fmt_column(f: &mut std::fmt::Formatter<'_>, column: &str) -> std::fmt::Result {
+            write!(
+                f,
+                r#""{}""#,
+                column.replace('\\', r#"\\"#).replace('"', r#"\""#)
+            )
+        }
+
+        fn fmt_list(
+            f: &mut std::fmt::Formatter<'_>,
+            exprs: &[DeleteExpr],
+            sep: &str,
+        ) -> std::fmt::Result {
+            write!(f, "(")?;
+            for (i, expr) in exprs.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " {} ", sep)?;
+                }
+                write!(f, "{}", expr)?;
+            }
+            write!(f, ")")
+        }
+
+        match self {
+            Self::Compare { column, op, scalar } => {
+                fmt_column(f, column)?;
+                match (op, scalar) {
+                    (Op::RegexMatch | Op::RegexNotMatch, Scalar::String(pattern)) => {
+                        write!(f, "{}/{}/", op, pattern.replace('/', r#"\/"#))
+                    }
+                    _ => write!(f, "{}{}", op, scalar),
+                }
+            }
+            Self::InList {
+                column,
+                list,
+                negated,
+            } => {
+                fmt_column(f, column)?;
+                write!(f, "{}(", if *negated { " NOT IN " } else { " IN " })?;
+                for (i, scalar) in list.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", scalar)?;
+                }
+                write!(f, ")")
+            }
+            Self::Or(exprs) => fmt_list(f, exprs, "OR"),
+            Self::And(exprs) => fmt_list(f, exprs, "AND"),
+        }
     }
 }
 
@@ -1354,6 +1743,24 @@ pub enum Op {
 
     /// Inequality (`!=`).
     Ne,
+
+    /// Greater than (`>`).
+    Gt,
+
+    /// Greater than or equal (`>=`).
+    Ge,
+
+    /// Less than (`<`).
+    Lt,
+
+    /// Less than or equal (`<=`).
+    Le,
+
+    /// Regular expression match (`=~`).
+    RegexMatch,
+
+    /// Negated regular expression match (`!~`).
+    RegexNotMatch,
 }
 
 impl std::fmt::Display for Op {
@@ -1361,6 +1768,12 @@ impl std::fmt::Display for Op {
         match self {
             Self::Eq => write!(f, "="),
             Self::Ne => write!(f, "!="),
+            Self::Gt => write!(f, ">"),
+            Self::Ge => write!(f, ">="),
+            Self::Lt => write!(f, "<"),
+            Self::Le => write!(f, "<="),
+            Self::RegexMatch => write!(f, "=~"),
+            Self::RegexNotMatch => write!(f, "!~"),
         }
     }
 }
@@ -2340,16 +2753,8 @@ mod tests {
         let pred = DeletePredicate {
             range: TimestampRange::new(1, 2),
             exprs: vec![
-                DeleteExpr {
-                    column: String::from("col1"),
-                    op: Op::Eq,
-                    scalar: Scalar::I64(1),
-                },
-                DeleteExpr {
-                    column: String::from("col2"),
-                    op: Op::Ne,
-                    scalar: Scalar::I64(2),
-                },
+                DeleteExpr::new(String::from("col1"), Op::Eq, Scalar::I64(1)),
+                DeleteExpr::new(String::from("col2"), Op::Ne, Scalar::I64(2)),
             ],
         };
         assert_eq!(&pred.expr_sql_string(), r#""col1"=1 AND "col2"!=2"#);
@@ -2360,21 +2765,9 @@ mod tests {
         let pred = DeletePredicate {
             range: TimestampRange::new(1, 2),
             exprs: vec![
-                DeleteExpr {
-                    column: String::from("col 1"),
-                    op: Op::Eq,
-                    scalar: Scalar::I64(1),
-                },
-                DeleteExpr {
-                    column: String::from(r#"col\2"#),
-                    op: Op::Eq,
-                    scalar: Scalar::I64(2),
-                },
-                DeleteExpr {
-                    column: String::from(r#"col"3"#),
-                    op: Op::Eq,
-                    scalar: Scalar::I64(3),
-                },
+                DeleteExpr::new(String::from("col 1"), Op::Eq, Scalar::I64(1)),
+                DeleteExpr::new(String::from(r#"col\2"#), Op::Eq, Scalar::I64(2)),
+                DeleteExpr::new(String::from(r#"col"3"#), Op::Eq, Scalar::I64(3)),
             ],
         };
         assert_eq!(
@@ -2388,16 +2781,8 @@ mod tests {
         let pred = DeletePredicate {
             range: TimestampRange::new(1, 2),
             exprs: vec![
-                DeleteExpr {
-                    column: String::from("col1"),
-                    op: Op::Eq,
-                    scalar: Scalar::Bool(false),
-                },
-                DeleteExpr {
-                    column: String::from("col2"),
-                    op: Op::Eq,
-                    scalar: Scalar::Bool(true),
-                },
+                DeleteExpr::new(String::from("col1"), Op::Eq, Scalar::Bool(false)),
+                DeleteExpr::new(String::from("col2"), Op::Eq, Scalar::Bool(true)),
             ],
         };
         assert_eq!(&pred.expr_sql_string(), r#""col1"=false AND "col2"=true"#);
@@ -2408,31 +2793,11 @@ mod tests {
         let pred = DeletePredicate {
             range: TimestampRange::new(1, 2),
             exprs: vec![
-                DeleteExpr {
-                    column: String::from("col1"),
-                    op: Op::Eq,
-                    scalar: Scalar::I64(0),
-                },
-                DeleteExpr {
-                    column: String::from("col2"),
-                    op: Op::Eq,
-                    scalar: Scalar::I64(-1),
-                },
-                DeleteExpr {
-                    column: String::from("col3"),
-                    op: Op::Eq,
-                    scalar: Scalar::I64(1),
-                },
-                DeleteExpr {
-                    column: String::from("col4"),
-                    op: Op::Eq,
-                    scalar: Scalar::I64(i64::MIN),
-                },
-                DeleteExpr {
-                    column: String::from("col5"),
-                    op: Op::Eq,
-                    scalar: Scalar::I64(i64::MAX),
-                },
+                DeleteExpr::new(String::from("col1"), Op::Eq, Scalar::I64(0)),
+                DeleteExpr::new(String::from("col2"), Op::Eq, Scalar::I64(-1)),
+                DeleteExpr::new(String::from("col3"), Op::Eq, Scalar::I64(1)),
+                DeleteExpr::new(String::from("col4"), Op::Eq, Scalar::I64(i64::MIN)),
+                DeleteExpr::new(String::from("col5"), Op::Eq, Scalar::I64(i64::MAX)),
             ],
         };
         assert_eq!(
@@ -2446,36 +2811,36 @@ mod tests {
         let pred = DeletePredicate {
             range: TimestampRange::new(1, 2),
             exprs: vec![
-                DeleteExpr {
-                    column: String::from("col1"),
-                    op: Op::Eq,
-                    scalar: Scalar::F64(OrderedFloat::from(0.0)),
-                },
-                DeleteExpr {
-                    column: String::from("col2"),
-                    op: Op::Eq,
-                    scalar: Scalar::F64(OrderedFloat::from(-0.0)),
-                },
-                DeleteExpr {
-                    column: String::from("col3"),
-                    op: Op::Eq,
-                    scalar: Scalar::F64(OrderedFloat::from(1.0)),
-                },
-                DeleteExpr {
-                    column: String::from("col4"),
-                    op: Op::Eq,
-                    scalar: Scalar::F64(OrderedFloat::from(f64::INFINITY)),
-                },
-                DeleteExpr {
-                    column: String::from("col5"),
-                    op: Op::Eq,
-                    scalar: Scalar::F64(OrderedFloat::from(f64::NEG_INFINITY)),
-                },
-                DeleteExpr {
-                    column: String::from("col6"),
-                    op: Op::Eq,
-                    scalar: Scalar::F64(OrderedFloat::from(f64::NAN)),
-                },
+                DeleteExpr::new(
+                    String::from("col1"),
+                    Op::Eq,
+                    Scalar::F64(OrderedFloat::from(0.0)),
+                ),
+                DeleteExpr::new(
+                    String::from("col2"),
+                    Op::Eq,
+                    Scalar::F64(OrderedFloat::from(-0.0)),
+                ),
+                DeleteExpr::new(
+                    String::from("col3"),
+                    Op::Eq,
+                    Scalar::F64(OrderedFloat::from(1.0)),
+                ),
+                DeleteExpr::new(
+                    String::from("col4"),
+                    Op::Eq,
+                    Scalar::F64(OrderedFloat::from(f64::INFINITY)),
+                ),
+                DeleteExpr::new(
+                    String::from("col5"),
+                    Op::Eq,
+                    Scalar::F64(OrderedFloat::from(f64::NEG_INFINITY)),
+                ),
+                DeleteExpr::new(
+                    String::from("col6"),
+                    Op::Eq,
+                    Scalar::F64(OrderedFloat::from(f64::NAN)),
+                ),
             ],
         };
         assert_eq!(
@@ -2489,26 +2854,26 @@ mod tests {
         let pred = DeletePredicate {
             range: TimestampRange::new(1, 2),
             exprs: vec![
-                DeleteExpr {
-                    column: String::from("col1"),
-                    op: Op::Eq,
-                    scalar: Scalar::String(String::from("")),
-                },
-                DeleteExpr {
-                    column: String::from("col2"),
-                    op: Op::Eq,
-                    scalar: Scalar::String(String::from("foo")),
-                },
-                DeleteExpr {
-                    column: String::from("col3"),
-                    op: Op::Eq,
-                    scalar: Scalar::String(String::from(r#"fo\o"#)),
-                },
-                DeleteExpr {
-                    column: String::from("col4"),
-                    op: Op::Eq,
-                    scalar: Scalar::String(String::from(r#"fo'o"#)),
-                },
+                DeleteExpr::new(
+                    String::from("col1"),
+                    Op::Eq,
+                    Scalar::String(String::from("")),
+                ),
+                DeleteExpr::new(
+                    String::from("col2"),
+                    Op::Eq,
+                    Scalar::String(String::from("foo")),
+                ),
+                DeleteExpr::new(
+                    String::from("col3"),
+                    Op::Eq,
+                    Scalar::String(String::from(r#"fo\o"#)),
+                ),
+                DeleteExpr::new(
+                    String::from("col4"),
+                    Op::Eq,
+                    Scalar::String(String::from(r#"fo'o"#)),
+                ),
             ],
         };
         assert_eq!(
@@ -2517,6 +2882,134 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_expr_to_sql_comparisons() {
+        let pred = DeletePredicate {
+            range: TimestampRange::new(1, 2),
+            exprs: vec![
+                DeleteExpr::new(String::from("col1"), Op::Gt, Scalar::I64(1)),
+                DeleteExpr::new(String::from("col2"), Op::Ge, Scalar::F64((2.5).into())),
+                DeleteExpr::new(String::from("col3"), Op::Lt, Scalar::I64(-3)),
+                DeleteExpr::new(String::from("col4"), Op::Le, Scalar::I64(4)),
+            ],
+        };
+        assert_eq!(
+            &pred.expr_sql_string(),
+            r#""col1">1 AND "col2">=2.5 AND "col3"<-3 AND "col4"<=4"#
+        );
+    }
+
+    #[test]
+    fn test_expr_to_sql_regex() {
+        let pred = DeletePredicate {
+            range: TimestampRange::new(1, 2),
+            exprs: vec![
+                DeleteExpr::new(
+                    String::from("col1"),
+                    Op::RegexMatch,
+                    Scalar::String(String::from(r#"^fo\d+$"#)),
+                ),
+                DeleteExpr::new(
+                    String::from("col2"),
+                    Op::RegexNotMatch,
+                    Scalar::String(String::from("a/b'c")),
+                ),
+            ],
+        };
+        assert_eq!(
+            &pred.expr_sql_string(),
+            r#""col1"=~/^fo\d+$/ AND "col2"!~/a\/b'c/"#
+        );
+    }
+
+    #[test]
+    fn test_expr_to_sql_in_list() {
+        let pred = DeletePredicate {
+            range: TimestampRange::new(1, 2),
+            exprs: vec![
+                DeleteExpr::in_list(
+                    String::from("col1"),
+                    vec![
+                        Scalar::String(String::from("a")),
+                        Scalar::String(String::from("b")),
+                    ],
+                    false,
+                ),
+                DeleteExpr::in_list(String::from("col2"), vec![Scalar::I64(1)], true),
+            ],
+        };
+        assert_eq!(
+            &pred.expr_sql_string(),
+            r#""col1" IN ('a','b') AND "col2" NOT IN (1)"#
+        );
+    }
+
+    #[test]
+    fn test_expr_to_sql_or() {
+        let pred = DeletePredicate {
+            range: TimestampRange::new(1, 2),
+            exprs: vec![
+                DeleteExpr::Or(vec![
+                    DeleteExpr::new(String::from("col1"), Op::Eq, Scalar::I64(1)),
+                    DeleteExpr::And(vec![
+                        DeleteExpr::new(String::from("col2"), Op::Eq, Scalar::I64(2)),
+                        DeleteExpr::new(String::from("col3"), Op::Ne, Scalar::I64(3)),
+                    ]),
+                ]),
+                DeleteExpr::new(String::from("col4"), Op::Eq, Scalar::I64(4)),
+            ],
+        };
+        assert_eq!(
+            &pred.expr_sql_string(),
+            r#"("col1"=1 OR ("col2"=2 AND "col3"!=3)) AND "col4"=4"#
+        );
+    }
+
+    #[test]
+    fn test_restrict_to_columns() {
+        let pred = DeletePredicate {
+            range: TimestampRange::new(1, 2),
+            exprs: vec![
+                DeleteExpr::Or(vec![
+                    DeleteExpr::new(String::from("a"), Op::Eq, Scalar::I64(1)),
+                    DeleteExpr::And(vec![
+                        DeleteExpr::new(String::from("b"), Op::Eq, Scalar::I64(2)),
+                        DeleteExpr::new(String::from("c"), Op::Eq, Scalar::I64(3)),
+                    ]),
+                ]),
+                DeleteExpr::in_list(String::from("d"), vec![Scalar::I64(4)], false),
+            ],
+        };
+
+        // all columns present
+        assert_eq!(pred.restrict_to_columns(|_| true), Some(pred.clone()));
+
+        // missing column within an OR group is dropped from the group
+        assert_eq!(
+            pred.restrict_to_columns(|col| col != "c"),
+            Some(DeletePredicate {
+                range: TimestampRange::new(1, 2),
+                exprs: vec![
+                    DeleteExpr::Or(vec![DeleteExpr::new(
+                        String::from("a"),
+                        Op::Eq,
+                        Scalar::I64(1)
+                    )]),
+                    DeleteExpr::in_list(String::from("d"), vec![Scalar::I64(4)], false),
+                ],
+            })
+        );
+
+        // no branch of the OR group can match
+        assert_eq!(
+            pred.restrict_to_columns(|col| col != "a" && col != "b"),
+            None
+        );
+
+        // missing column in the top-level conjunction
+        assert_eq!(pred.restrict_to_columns(|col| col != "d"), None);
+    }
+
     #[test]
     fn test_org_bucket_map_db_ok() {
         let got = org_and_bucket_to_database("org", "bucket").expect("failed on valid DB mapping");
@@ -3358,12 +3851,18 @@ mod tests {
             topic_id: TopicId::new(2),
             query_pool_id: QueryPoolId::new(3),
             tables: BTreeMap::from([]),
+            write_limits: Default::default(),
+            strict_schema: false,
+            field_coercion_policy: Default::default(),
         };
         let schema2 = NamespaceSchema {
             id: NamespaceId::new(1),
             topic_id: TopicId::new(2),
             query_pool_id: QueryPoolId::new(3),
             tables: BTreeMap::from([(String::from("foo"), TableSchema::new(TableId::new(1)))]),
+            write_limits: Default::default(),
+            strict_schema: false,
+            field_coercion_policy: Default::default(),
         };
         assert!(schema1.size() < schema2.size());
     }
@@ -17,7 +17,8 @@
 
 use crate::{
     objectstore::{checker as os_checker, deleter as os_deleter, lister as os_lister},
-    parquetfile::deleter as pf_deleter,
+    parquetfile::{checker as pf_checker, deleter as pf_deleter},
+    report::Reporter,
 };
 
 use clap::Parser;
@@ -26,14 +27,16 @@ use iox_catalog::interface::Catalog;
 use object_store::DynObjectStore;
 use observability_deps::tracing::*;
 use snafu::prelude::*;
-use std::{fmt::Debug, sync::Arc, time::Duration};
+use std::{fmt::Debug, path::PathBuf, sync::Arc, time::Duration};
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
 /// Logic for listing, checking and deleting files in object storage
 mod objectstore;
-/// Logic deleting parquet files from the catalog
+/// Logic deleting parquet files from the catalog and checking their objects exist
 mod parquetfile;
+/// Logic for writing the reconciliation report
+mod report;
 
 const BUFFER_SIZE: usize = 1000;
 
@@ -49,6 +52,8 @@ pub struct GarbageCollector {
     os_checker: tokio::task::JoinHandle<Result<(), os_checker::Error>>,
     os_deleter: tokio::task::JoinHandle<Result<(), os_deleter::Error>>,
     pf_deleter: tokio::task::JoinHandle<Result<(), pf_deleter::Error>>,
+    pf_checker: tokio::task::JoinHandle<Result<(), pf_checker::Error>>,
+    report_writer: Option<tokio::task::JoinHandle<Result<(), report::Error>>>,
 }
 
 impl Debug for GarbageCollector {
@@ -64,6 +69,7 @@ impl GarbageCollector {
             object_store,
             sub_config,
             catalog,
+            metric_registry,
         } = config;
 
         let dry_run = sub_config.dry_run;
@@ -72,12 +78,25 @@ impl GarbageCollector {
             parquetfile_cutoff_days = %format_duration(sub_config.parquetfile_cutoff).to_string(),
             objectstore_sleep_interval_minutes = %sub_config.objectstore_sleep_interval_minutes,
             parquetfile_sleep_interval_minutes = %sub_config.parquetfile_sleep_interval_minutes,
+            missing_objects_sleep_interval_minutes = %sub_config.missing_objects_sleep_interval_minutes,
+            report_path = ?sub_config.report_path,
             "GarbageCollector starting"
         );
 
         // Shutdown handler channel to notify children
         let shutdown = CancellationToken::new();
 
+        // If a reconciliation report was requested, the tasks below send what they did to the
+        // report writer, which appends it to the report. The writer stops once all of them have
+        // stopped.
+        let (report, report_writer) = match sub_config.report_path {
+            Some(path) => {
+                let (report, entries) = Reporter::new();
+                (report, Some(tokio::spawn(report::perform(path, entries))))
+            }
+            None => (Reporter::default(), None),
+        };
+
         // Initialise the object store garbage collector, which works as three communicating threads:
         // - lister lists objects in the object store and sends them on a channel. the lister will
         //   run until it has enumerated all matching files, then sleep for the configured
@@ -106,9 +125,10 @@ impl GarbageCollector {
             tx2,
         ));
         let os_deleter = tokio::spawn(os_deleter::perform(
-            object_store,
+            Arc::clone(&object_store),
             dry_run,
             sub_config.objectstore_concurrent_deletes,
+            report.clone(),
             rx2,
         ));
 
@@ -116,9 +136,21 @@ impl GarbageCollector {
         // on the catalog then sleeps.
         let pf_deleter = tokio::spawn(pf_deleter::perform(
             shutdown.clone(),
-            catalog,
+            Arc::clone(&catalog),
             sub_config.parquetfile_cutoff,
             sub_config.parquetfile_sleep_interval_minutes,
+            report.clone(),
+        ));
+
+        // Initialise the parquet file checker, which is just one thread that looks for catalog
+        // parquet files whose objects are missing from the object store then sleeps.
+        let pf_checker = tokio::spawn(pf_checker::perform(
+            shutdown.clone(),
+            catalog,
+            object_store,
+            sub_config.missing_objects_sleep_interval_minutes,
+            pf_checker::MissingObjectMetrics::new(&metric_registry),
+            report,
         ));
 
         Ok(Self {
@@ -127,6 +159,8 @@ impl GarbageCollector {
             os_checker,
             os_deleter,
             pf_deleter,
+            pf_checker,
+            report_writer,
         })
     }
 
@@ -145,12 +179,18 @@ impl GarbageCollector {
             os_checker,
             os_deleter,
             pf_deleter,
+            pf_checker,
+            report_writer,
             shutdown: _,
         } = self;
 
-        let (os_lister, os_checker, os_deleter, pf_deleter) =
-            futures::join!(os_lister, os_checker, os_deleter, pf_deleter);
+        let (os_lister, os_checker, os_deleter, pf_deleter, pf_checker) =
+            futures::join!(os_lister, os_checker, os_deleter, pf_deleter, pf_checker);
 
+        if let Some(report_writer) = report_writer {
+            report_writer.await.context(ReportWriterPanicSnafu)??;
+        }
+        pf_checker.context(ParquetFileCheckerPanicSnafu)??;
         pf_deleter.context(ParquetFileDeleterPanicSnafu)??;
         os_deleter.context(ObjectStoreDeleterPanicSnafu)??;
         os_checker.context(ObjectStoreCheckerPanicSnafu)??;
@@ -171,6 +211,9 @@ pub struct Config {
 
     /// The garbage collector specific configuration
     pub sub_config: SubConfig,
+
+    /// The registry the garbage collector's metrics are registered with
+    pub metric_registry: Arc<metric::Registry>,
 }
 
 impl Debug for Config {
@@ -189,6 +232,13 @@ pub struct SubConfig {
     #[clap(long, env = "INFLUXDB_IOX_GC_DRY_RUN")]
     dry_run: bool,
 
+    /// Append a reconciliation report to this file, as JSON lines. It records the objects and
+    /// catalog parquet files that were deleted (or, with --dry-run, the objects that would have
+    /// been deleted) and the catalog parquet files whose objects are missing, with the reason,
+    /// size and age of each.
+    #[clap(long, env = "INFLUXDB_IOX_GC_REPORT_PATH")]
+    report_path: Option<PathBuf>,
+
     /// Items in the object store that are older than this duration.
     /// Parsed with <https://docs.rs/humantime/latest/humantime/fn.parse_duration.html>
     ///
@@ -238,6 +288,16 @@ pub struct SubConfig {
         env = "INFLUXDB_IOX_GC_PARQUETFILE_SLEEP_INTERVAL_MINUTES"
     )]
     parquetfile_sleep_interval_minutes: u64,
+
+    /// Number of minutes to sleep between checks for catalog parquet files whose objects are
+    /// missing from the object store.
+    /// Defaults to 60 minutes.
+    #[clap(
+        long,
+        default_value_t = 60,
+        env = "INFLUXDB_IOX_GC_MISSING_OBJECTS_SLEEP_INTERVAL_MINUTES"
+    )]
+    missing_objects_sleep_interval_minutes: u64,
 }
 
 #[derive(Debug, Snafu)]
@@ -269,6 +329,18 @@ pub enum Error {
     ParquetFileDeleter { source: pf_deleter::Error },
     #[snafu(display("The parquet file deleter task panicked"))]
     ParquetFileDeleterPanic { source: tokio::task::JoinError },
+
+    #[snafu(display("The parquet file checker task failed"))]
+    #[snafu(context(false))]
+    ParquetFileChecker { source: pf_checker::Error },
+    #[snafu(display("The parquet file checker task panicked"))]
+    ParquetFileCheckerPanic { source: tokio::task::JoinError },
+
+    #[snafu(display("The report writer task failed"))]
+    #[snafu(context(false))]
+    ReportWriter { source: report::Error },
+    #[snafu(display("The report writer task panicked"))]
+    ReportWriterPanic { source: tokio::task::JoinError },
 }
 
 #[allow(missing_docs)]
@@ -337,6 +409,7 @@ mod tests {
             object_store,
             catalog,
             sub_config,
+            metric_registry: Default::default(),
         }
     }
 
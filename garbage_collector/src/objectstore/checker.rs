@@ -20,7 +20,7 @@ pub enum Error {
 
     #[snafu(display("The deleter task exited unexpectedly"))]
     DeleterExited {
-        source: tokio::sync::mpsc::error::SendError<ObjectMeta>,
+        source: tokio::sync::mpsc::error::SendError<(ObjectMeta, &'static str)>,
     },
 }
 
@@ -30,26 +30,30 @@ pub(crate) async fn perform(
     catalog: Arc<dyn Catalog>,
     cutoff: Duration,
     mut items: mpsc::Receiver<ObjectMeta>,
-    deleter: mpsc::Sender<ObjectMeta>,
+    deleter: mpsc::Sender<(ObjectMeta, &'static str)>,
 ) -> Result<()> {
     let mut repositories = catalog.repositories().await;
     let parquet_files = repositories.parquet_files();
 
     while let Some(item) = items.recv().await {
         let older_than = chrono::offset::Utc::now() - cutoff;
-        if should_delete(&item, older_than, parquet_files).await? {
-            deleter.send(item).await.context(DeleterExitedSnafu)?;
+        if let Some(reason) = deletion_reason(&item, older_than, parquet_files).await? {
+            deleter
+                .send((item, reason))
+                .await
+                .context(DeleterExitedSnafu)?;
         }
     }
 
     Ok(())
 }
 
-async fn should_delete(
+/// Return why the object should be deleted, or `None` if it should be kept.
+async fn deletion_reason(
     item: &ObjectMeta,
     cutoff: DateTime<Utc>,
     parquet_files: &mut dyn ParquetFileRepo,
-) -> Result<bool> {
+) -> Result<Option<&'static str>> {
     if cutoff < item.last_modified {
         info!(
             location = %item.location,
@@ -60,12 +64,12 @@ async fn should_delete(
             "Ignoring object",
         );
         // Not old enough; do not delete
-        return Ok(false);
+        return Ok(None);
     }
 
     let file_name = item.location.parts().last().context(FileNameMissingSnafu)?;
 
-    if let Some(uuid) = file_name.as_ref().strip_suffix(".parquet") {
+    let reason = if let Some(uuid) = file_name.as_ref().strip_suffix(".parquet") {
         if let Ok(object_store_id) = uuid.parse() {
             let parquet_file = parquet_files
                 .get_by_object_store_id(object_store_id)
@@ -80,35 +84,41 @@ async fn should_delete(
                     reason = "exists in catalog",
                     "Ignoring object",
                 );
-                return Ok(false);
-            } else {
-                info!(
-                    location = %item.location,
-                    deleting = true,
-                    reason = "not in catalog",
-                    "Scheduling file for deletion",
-                );
+                return Ok(None);
             }
+
+            let reason = "not in catalog";
+            info!(
+                location = %item.location,
+                deleting = true,
+                reason,
+                "Scheduling file for deletion",
+            );
+            reason
         } else {
+            let reason = "not a valid UUID";
             info!(
                 location = %item.location,
                 deleting = true,
                 uuid,
-                reason = "not a valid UUID",
+                reason,
                 "Scheduling file for deletion",
             );
+            reason
         }
     } else {
+        let reason = "not a .parquet file";
         info!(
             location = %item.location,
             deleting = true,
             file_name = %file_name.as_ref(),
-            reason = "not a .parquet file",
+            reason,
             "Scheduling file for deletion",
         );
-    }
+        reason
+    };
 
-    Ok(true)
+    Ok(Some(reason))
 }
 
 #[cfg(test)]
@@ -206,7 +216,10 @@ mod tests {
             size: 0,
         };
 
-        assert!(!should_delete(&item, cutoff, parquet_files).await.unwrap());
+        assert_eq!(
+            deletion_reason(&item, cutoff, parquet_files).await.unwrap(),
+            None
+        );
     }
 
     #[tokio::test]
@@ -234,7 +247,10 @@ mod tests {
             size: 0,
         };
 
-        assert!(!should_delete(&item, cutoff, parquet_files).await.unwrap());
+        assert_eq!(
+            deletion_reason(&item, cutoff, parquet_files).await.unwrap(),
+            None
+        );
     }
 
     #[tokio::test]
@@ -253,7 +269,10 @@ mod tests {
             size: 0,
         };
 
-        assert!(!should_delete(&item, cutoff, parquet_files).await.unwrap());
+        assert_eq!(
+            deletion_reason(&item, cutoff, parquet_files).await.unwrap(),
+            None
+        );
     }
 
     #[tokio::test]
@@ -280,7 +299,10 @@ mod tests {
             size: 0,
         };
 
-        assert!(!should_delete(&item, cutoff, parquet_files).await.unwrap());
+        assert_eq!(
+            deletion_reason(&item, cutoff, parquet_files).await.unwrap(),
+            None
+        );
     }
 
     #[tokio::test]
@@ -308,7 +330,10 @@ mod tests {
             size: 0,
         };
 
-        assert!(should_delete(&item, cutoff, parquet_files).await.unwrap());
+        assert_eq!(
+            deletion_reason(&item, cutoff, parquet_files).await.unwrap(),
+            Some("not in catalog")
+        );
     }
 
     #[tokio::test]
@@ -327,6 +352,9 @@ mod tests {
             size: 0,
         };
 
-        assert!(should_delete(&item, cutoff, parquet_files).await.unwrap());
+        assert_eq!(
+            deletion_reason(&item, cutoff, parquet_files).await.unwrap(),
+            Some("not a valid UUID")
+        );
     }
 }
@@ -1,3 +1,4 @@
+use crate::report::{Action, Entry, Reporter};
 use futures::{StreamExt, TryStreamExt};
 use object_store::{DynObjectStore, ObjectMeta};
 use observability_deps::tracing::info;
@@ -9,24 +10,41 @@ pub(crate) async fn perform(
     object_store: Arc<DynObjectStore>,
     dry_run: bool,
     concurrent_deletes: usize,
-    items: mpsc::Receiver<ObjectMeta>,
+    report: Reporter,
+    items: mpsc::Receiver<(ObjectMeta, &'static str)>,
 ) -> Result<()> {
     tokio_stream::wrappers::ReceiverStream::new(items)
-        .map(|item| {
+        .map(|(item, reason)| {
             let object_store = Arc::clone(&object_store);
-
+            let report = report.clone();
             async move {
                 let path = item.location;
-                if dry_run {
+                let action = if dry_run {
                     info!(?path, "Not deleting due to dry run");
-                    Ok(())
+                    Action::WouldDelete
                 } else {
                     info!("Deleting {path}");
                     object_store
                         .delete(&path)
                         .await
-                        .context(DeletingSnafu { path })
-                }
+                        .context(DeletingSnafu { path: path.clone() })?;
+                    Action::Deleted
+                };
+
+                let now = chrono::offset::Utc::now();
+                report
+                    .report(Entry {
+                        time: now.timestamp(),
+                        action,
+                        location: path.to_string(),
+                        parquet_file_id: None,
+                        reason,
+                        size_bytes: item.size as u64,
+                        age_seconds: (now - item.last_modified).num_seconds(),
+                    })
+                    .await;
+
+                Ok::<_, Error>(())
             }
         })
         .buffer_unordered(concurrent_deletes)
@@ -0,0 +1,251 @@
+use crate::report::{Action, Entry, Reporter};
+use data_types::{ParquetFile, Timestamp};
+use futures::prelude::*;
+use iox_catalog::interface::Catalog;
+use metric::U64Gauge;
+use object_store::DynObjectStore;
+use observability_deps::tracing::*;
+use parquet_file::ParquetFilePath;
+use snafu::prelude::*;
+use std::{sync::Arc, time::Duration};
+use tokio::{select, time::sleep};
+use tokio_util::sync::CancellationToken;
+
+/// Number of objects whose existence is checked concurrently.
+const CONCURRENT_CHECKS: usize = 10;
+
+/// Gauges describing the catalog parquet files whose objects are missing from object storage,
+/// as of the last check.
+#[derive(Debug)]
+pub(crate) struct MissingObjectMetrics {
+    files: U64Gauge,
+    bytes: U64Gauge,
+}
+
+impl MissingObjectMetrics {
+    pub(crate) fn new(registry: &metric::Registry) -> Self {
+        let files = registry
+            .register_metric::<U64Gauge>(
+                "gc_missing_objects",
+                "Number of catalog parquet files whose objects are missing from object storage",
+            )
+            .recorder(&[]);
+        let bytes = registry
+            .register_metric::<U64Gauge>(
+                "gc_missing_object_bytes",
+                "Total size of the catalog parquet files whose objects are missing from object storage",
+            )
+            .recorder(&[]);
+
+        Self { files, bytes }
+    }
+}
+
+pub(crate) async fn perform(
+    shutdown: CancellationToken,
+    catalog: Arc<dyn Catalog>,
+    object_store: Arc<DynObjectStore>,
+    sleep_interval_minutes: u64,
+    metrics: MissingObjectMetrics,
+    report: Reporter,
+) -> Result<()> {
+    loop {
+        let missing = missing_objects(&*catalog, &object_store).await?;
+        info!(missing_count = %missing.len(), "Checked catalog parquet files for missing objects");
+
+        let now = Timestamp::from(catalog.time_provider().now());
+        for file in &missing {
+            let location = ParquetFilePath::from(file).object_store_path();
+            warn!(
+                %location,
+                parquet_file_id = file.id.get(),
+                "Object of catalog parquet file is missing from object storage",
+            );
+            report
+                .report(Entry {
+                    time: now.get() / 1_000_000_000,
+                    action: Action::Missing,
+                    location: location.to_string(),
+                    parquet_file_id: Some(file.id.get()),
+                    reason: "object missing from object storage",
+                    size_bytes: file.file_size_bytes as u64,
+                    age_seconds: (now.get() - file.created_at.get()) / 1_000_000_000,
+                })
+                .await;
+        }
+        metrics.files.set(missing.len() as u64);
+        metrics
+            .bytes
+            .set(missing.iter().map(|f| f.file_size_bytes as u64).sum());
+
+        select! {
+            _ = shutdown.cancelled() => {
+                break
+            },
+            _ = sleep(Duration::from_secs(60 * sleep_interval_minutes)) => (),
+        }
+    }
+    Ok(())
+}
+
+/// Return the parquet files that are not flagged for deletion in the catalog but whose objects
+/// do not exist in object storage.
+async fn missing_objects(
+    catalog: &dyn Catalog,
+    object_store: &Arc<DynObjectStore>,
+) -> Result<Vec<ParquetFile>> {
+    let namespaces = catalog
+        .repositories()
+        .await
+        .namespaces()
+        .list()
+        .await
+        .context(ListingNamespacesSnafu)?;
+
+    let mut missing = vec![];
+    for namespace in namespaces {
+        let files = catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .list_by_namespace_not_to_delete(namespace.id)
+            .await
+            .context(ListingParquetFilesSnafu {
+                namespace: namespace.name,
+            })?;
+
+        let mut checks = stream::iter(files)
+            .map(|file| {
+                let object_store = Arc::clone(object_store);
+                async move {
+                    let location = ParquetFilePath::from(&file).object_store_path();
+                    match object_store.head(&location).await {
+                        Ok(_) => None,
+                        Err(object_store::Error::NotFound { .. }) => Some(file),
+                        Err(e) => {
+                            // This may just be a hiccup, check again in the next iteration
+                            warn!(%location, %e, "Could not check whether object exists");
+                            None
+                        }
+                    }
+                }
+            })
+            .buffer_unordered(CONCURRENT_CHECKS);
+
+        while let Some(file) = checks.next().await {
+            missing.extend(file);
+        }
+    }
+
+    Ok(missing)
+}
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("Failed to list the namespaces in the catalog"))]
+    ListingNamespaces {
+        source: iox_catalog::interface::Error,
+    },
+
+    #[snafu(display("Failed to list the parquet files of namespace {namespace} in the catalog"))]
+    ListingParquetFiles {
+        source: iox_catalog::interface::Error,
+        namespace: String,
+    },
+}
+
+pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use data_types::{
+        ColumnId, ColumnSet, CompactionLevel, ParquetFileParams, SequenceNumber, ShardIndex,
+    };
+    use iox_catalog::mem::MemCatalog;
+    use object_store::memory::InMemory;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn finds_parquet_files_with_missing_objects() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::new())));
+        let object_store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+
+        let mut repos = catalog.repositories().await;
+        let topic = repos.topics().create_or_get("foo").await.unwrap();
+        let pool = repos.query_pools().create_or_get("foo").await.unwrap();
+        let namespace = repos
+            .namespaces()
+            .create("namespace_missing_objects", "inf", topic.id, pool.id)
+            .await
+            .unwrap();
+        let table = repos
+            .tables()
+            .create_or_get("test_table", namespace.id)
+            .await
+            .unwrap();
+        let shard = repos
+            .shards()
+            .create_or_get(&topic, ShardIndex::new(1))
+            .await
+            .unwrap();
+        let partition = repos
+            .partitions()
+            .create_or_get("one".into(), shard.id, table.id)
+            .await
+            .unwrap();
+        let params = ParquetFileParams {
+            shard_id: shard.id,
+            namespace_id: namespace.id,
+            table_id: table.id,
+            partition_id: partition.id,
+            object_store_id: Uuid::new_v4(),
+            max_sequence_number: SequenceNumber::new(140),
+            min_time: Timestamp::new(1),
+            max_time: Timestamp::new(10),
+            file_size_bytes: 1337,
+            row_count: 0,
+            compaction_level: CompactionLevel::Initial,
+            created_at: Timestamp::new(1),
+            column_set: ColumnSet::new([ColumnId::new(1), ColumnId::new(2)]),
+        };
+        let present = repos.parquet_files().create(params.clone()).await.unwrap();
+        let missing = repos
+            .parquet_files()
+            .create(ParquetFileParams {
+                object_store_id: Uuid::new_v4(),
+                ..params.clone()
+            })
+            .await
+            .unwrap();
+        // Files flagged for deletion may legitimately have lost their objects
+        let flagged = repos
+            .parquet_files()
+            .create(ParquetFileParams {
+                object_store_id: Uuid::new_v4(),
+                ..params
+            })
+            .await
+            .unwrap();
+        repos
+            .parquet_files()
+            .flag_for_delete(flagged.id)
+            .await
+            .unwrap();
+        drop(repos);
+
+        object_store
+            .put(
+                &ParquetFilePath::from(&present).object_store_path(),
+                Bytes::from_static(b"parquet"),
+            )
+            .await
+            .unwrap();
+
+        let found = missing_objects(&*catalog, &object_store).await.unwrap();
+        assert_eq!(found, vec![missing]);
+    }
+}
@@ -1,6 +1,8 @@
+use crate::report::{Action, Entry, Reporter};
 use data_types::Timestamp;
 use iox_catalog::interface::Catalog;
 use observability_deps::tracing::*;
+use parquet_file::ParquetFilePath;
 use snafu::prelude::*;
 use std::{sync::Arc, time::Duration};
 use tokio::{select, time::sleep};
@@ -11,20 +13,48 @@ pub(crate) async fn perform(
     catalog: Arc<dyn Catalog>,
     cutoff: Duration,
     sleep_interval_minutes: u64,
+    report: Reporter,
 ) -> Result<()> {
     loop {
-        let older_than = Timestamp::from(catalog.time_provider().now() - cutoff);
+        let now = catalog.time_provider().now();
+        let older_than = Timestamp::from(now - cutoff);
         // do the delete, returning the deleted files
-        let deleted = catalog
-            .repositories()
-            .await
-            .parquet_files()
-            .delete_old_ids_only(older_than)
-            .await
-            .context(DeletingSnafu)?;
-        info!(delete_count = %deleted.len(), "iox_catalog::delete_old()");
+        let mut repositories = catalog.repositories().await;
+        // Only fetch the full rows if they are reported
+        let delete_count = if report.is_enabled() {
+            let deleted = repositories
+                .parquet_files()
+                .delete_old(older_than)
+                .await
+                .context(DeletingSnafu)?;
+            for file in &deleted {
+                let flagged_at = file.to_delete.unwrap_or(older_than);
+                report
+                    .report(Entry {
+                        time: now.timestamp(),
+                        action: Action::Deleted,
+                        location: ParquetFilePath::from(file).object_store_path().to_string(),
+                        parquet_file_id: Some(file.id.get()),
+                        reason: "flagged for deletion before the cutoff",
+                        size_bytes: file.file_size_bytes as u64,
+                        age_seconds: (Timestamp::from(now).get() - flagged_at.get())
+                            / 1_000_000_000,
+                    })
+                    .await;
+            }
+            deleted.len()
+        } else {
+            repositories
+                .parquet_files()
+                .delete_old_ids_only(older_than)
+                .await
+                .context(DeletingSnafu)?
+                .len()
+        };
+        drop(repositories);
+        info!(%delete_count, "iox_catalog::delete_old()");
 
-        if deleted.is_empty() {
+        if delete_count == 0 {
             select! {
                 _ = shutdown.cancelled() => {
                     break
@@ -1,2 +1,4 @@
+/// Logic for checking that the objects of catalog parquet files exist in object storage.
+pub(crate) mod checker;
 /// Logic for deleting parquet_file entries from the catalog.
 pub(crate) mod deleter;
@@ -0,0 +1,145 @@
+use observability_deps::tracing::*;
+use serde::Serialize;
+use snafu::prelude::*;
+use std::path::PathBuf;
+use tokio::{fs::OpenOptions, io::AsyncWriteExt, sync::mpsc};
+
+/// What the garbage collector did, or would have done, with an object or catalog row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Action {
+    /// The object or catalog row was deleted.
+    Deleted,
+    /// The object would have been deleted if not for `--dry-run`.
+    WouldDelete,
+    /// The catalog row references an object that does not exist in object storage.
+    Missing,
+}
+
+/// One line of the reconciliation report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Entry {
+    /// When the entry was recorded, in seconds since the epoch.
+    pub(crate) time: i64,
+    pub(crate) action: Action,
+    /// The object store location of the object.
+    pub(crate) location: String,
+    /// The catalog ID of the parquet file, if the entry is about a catalog row.
+    pub(crate) parquet_file_id: Option<i64>,
+    pub(crate) reason: &'static str,
+    pub(crate) size_bytes: u64,
+    /// Seconds since the object was last modified, since the parquet file was flagged for
+    /// deletion, or since a parquet file with a missing object was created.
+    pub(crate) age_seconds: i64,
+}
+
+/// Sends entries to the report writer, or drops them if no report was requested.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Reporter {
+    entries: Option<mpsc::Sender<Entry>>,
+}
+
+impl Reporter {
+    /// Create a reporter and the channel receiving its entries.
+    pub(crate) fn new() -> (Self, mpsc::Receiver<Entry>) {
+        let (tx, rx) = mpsc::channel(crate::BUFFER_SIZE);
+        (Self { entries: Some(tx) }, rx)
+    }
+
+    /// Whether entries are written to a report.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.entries.is_some()
+    }
+
+    pub(crate) async fn report(&self, entry: Entry) {
+        if let Some(entries) = &self.entries {
+            if entries.send(entry).await.is_err() {
+                warn!("The report writer exited; dropping report entry");
+            }
+        }
+    }
+}
+
+/// Append the received entries to the report at `path` as JSON lines.
+pub(crate) async fn perform(path: PathBuf, mut entries: mpsc::Receiver<Entry>) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await
+        .context(OpeningSnafu { path: path.clone() })?;
+
+    while let Some(entry) = entries.recv().await {
+        let mut line = serde_json::to_vec(&entry).context(SerializingSnafu)?;
+        line.push(b'\n');
+        file.write_all(&line)
+            .await
+            .context(WritingSnafu { path: path.clone() })?;
+        file.flush()
+            .await
+            .context(WritingSnafu { path: path.clone() })?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("The report {} could not be opened", path.display()))]
+    Opening {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+
+    #[snafu(display("A report entry could not be serialized"))]
+    Serializing { source: serde_json::Error },
+
+    #[snafu(display("The report {} could not be written", path.display()))]
+    Writing {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+}
+
+pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn appends_entries_as_json_lines() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("report.jsonl");
+        std::fs::write(&path, "{\"existing\":true}\n").unwrap();
+
+        let (reporter, entries) = Reporter::new();
+        let writer = tokio::spawn(perform(path.clone(), entries));
+
+        reporter
+            .report(Entry {
+                time: 1,
+                action: Action::WouldDelete,
+                location: "some/object".into(),
+                parquet_file_id: None,
+                reason: "not in catalog",
+                size_bytes: 42,
+                age_seconds: 3600,
+            })
+            .await;
+        drop(reporter);
+        writer.await.unwrap().unwrap();
+
+        let report = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = report.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                r#"{"existing":true}"#,
+                r#"{"time":1,"action":"would_delete","location":"some/object","parquet_file_id":null,"reason":"not in catalog","size_bytes":42,"age_seconds":3600}"#,
+            ]
+        );
+    }
+}
@@ -8,7 +8,9 @@
 //! [Ballista]: https://github.com/apache/arrow-datafusion/blob/22fcb3d7a68a56afbe12eab9e7d98f7b8de33703/ballista/rust/core/proto/ballista.proto
 //! [Protocol Buffers 3]: https://developers.google.com/protocol-buffers/docs/proto3
 
-use crate::google::{FieldViolation, FromOptionalField, FromRepeatedField, OptionalField};
+use crate::google::{
+    FieldViolation, FromField, FromOptionalField, FromRepeatedField, OptionalField,
+};
 use crate::influxdata::iox::predicate::v1 as proto;
 use crate::influxdata::iox::predicate::v1::scalar::Value;
 use crate::influxdata::iox::predicate::v1::{Expr, Predicate};
@@ -43,24 +45,67 @@ impl TryFrom<proto::Expr> for DeleteExpr {
     type Error = FieldViolation;
 
     fn try_from(value: Expr) -> Result<Self, Self::Error> {
-        Ok(Self {
-            column: value.column,
-            op: proto::Op::from_i32(value.op).required("op")?,
-            scalar: value.scalar.required("scalar")?,
-        })
+        let op = proto::Op::from_i32(value.op).unwrap_field("op")?;
+
+        match op {
+            proto::Op::In | proto::Op::NotIn => Ok(Self::InList {
+                column: value.column,
+                list: value.list.repeated("list")?,
+                negated: op == proto::Op::NotIn,
+            }),
+            proto::Op::Or => Ok(Self::Or(value.children.repeated("children")?)),
+            proto::Op::And => Ok(Self::And(value.children.repeated("children")?)),
+            _ => Ok(Self::Compare {
+                column: value.column,
+                op: op.field("op")?,
+                scalar: value.scalar.required("scalar")?,
+            }),
+        }
     }
 }
 
 impl From<DeleteExpr> for proto::Expr {
     fn from(expr: DeleteExpr) -> Self {
-        Self {
-            column: expr.column,
-            op: proto::Op::from(expr.op).into(),
-            scalar: Some(expr.scalar.into()),
+        match expr {
+            DeleteExpr::Compare { column, op, scalar } => Self {
+                column,
+                op: proto::Op::from(op).into(),
+                scalar: Some(scalar.into()),
+                list: vec![],
+                children: vec![],
+            },
+            DeleteExpr::InList {
+                column,
+                list,
+                negated,
+            } => Self {
+                column,
+                op: if negated {
+                    proto::Op::NotIn
+                } else {
+                    proto::Op::In
+                }
+                .into(),
+                scalar: None,
+                list: list.into_iter().map(Into::into).collect(),
+                children: vec![],
+            },
+            DeleteExpr::Or(exprs) => combination_to_proto(proto::Op::Or, exprs),
+            DeleteExpr::And(exprs) => combination_to_proto(proto::Op::And, exprs),
         }
     }
 }
 
+fn combination_to_proto(op: proto::Op, children: Vec<DeleteExpr>) -> proto::Expr {
+    proto::Expr {
+        column: String::new(),
+        op: op.into(),
+        scalar: None,
+        list: vec![],
+        children: children.into_iter().map(Into::into).collect(),
+    }
+}
+
 impl TryFrom<proto::Scalar> for Scalar {
     type Error = FieldViolation;
 
@@ -101,6 +146,18 @@ impl TryFrom<proto::Op> for Op {
             proto::Op::Unspecified => Err(FieldViolation::required("")),
             proto::Op::Eq => Ok(Self::Eq),
             proto::Op::Ne => Ok(Self::Ne),
+            proto::Op::Gt => Ok(Self::Gt),
+            proto::Op::Ge => Ok(Self::Ge),
+            proto::Op::Lt => Ok(Self::Lt),
+            proto::Op::Le => Ok(Self::Le),
+            proto::Op::RegexMatch => Ok(Self::RegexMatch),
+            proto::Op::RegexNotMatch => Ok(Self::RegexNotMatch),
+            proto::Op::In | proto::Op::NotIn | proto::Op::Or | proto::Op::And => {
+                Err(FieldViolation {
+                    field: "".to_string(),
+                    description: format!("{:?} is not a comparison operator", value),
+                })
+            }
         }
     }
 }
@@ -110,6 +167,12 @@ impl From<Op> for proto::Op {
         match value {
             Op::Eq => Self::Eq,
             Op::Ne => Self::Ne,
+            Op::Gt => Self::Gt,
+            Op::Ge => Self::Ge,
+            Op::Lt => Self::Lt,
+            Op::Le => Self::Le,
+            Op::RegexMatch => Self::RegexMatch,
+            Op::RegexNotMatch => Self::RegexNotMatch,
         }
     }
 }
@@ -126,26 +189,60 @@ mod tests {
             assert_eq!(expr, deserialized);
         };
 
-        round_trip(DeleteExpr {
+        round_trip(DeleteExpr::new(
+            "foo".to_string(),
+            Op::Eq,
+            Scalar::Bool(true),
+        ));
+
+        round_trip(DeleteExpr::new("bar".to_string(), Op::Ne, Scalar::I64(-1)));
+        round_trip(DeleteExpr::new(
+            "baz".to_string(),
+            Op::Eq,
+            Scalar::F64((-1.1).into()),
+        ));
+        round_trip(DeleteExpr::new(
+            "col".to_string(),
+            Op::Eq,
+            Scalar::String("foo".to_string()),
+        ));
+
+        round_trip(DeleteExpr::new("foo".to_string(), Op::Ge, Scalar::I64(3)));
+        round_trip(DeleteExpr::new(
+            "foo".to_string(),
+            Op::RegexMatch,
+            Scalar::String("^b.r$".to_string()),
+        ));
+        round_trip(DeleteExpr::in_list(
+            "foo".to_string(),
+            vec![Scalar::String("a".to_string()), Scalar::I64(1)],
+            true,
+        ));
+        round_trip(DeleteExpr::Or(vec![
+            DeleteExpr::new("foo".to_string(), Op::Lt, Scalar::F64((1.5).into())),
+            DeleteExpr::And(vec![
+                DeleteExpr::new("bar".to_string(), Op::Ne, Scalar::Bool(false)),
+                DeleteExpr::in_list("baz".to_string(), vec![Scalar::I64(2)], false),
+            ]),
+        ]));
+    }
+
+    #[test]
+    fn test_invalid_op() {
+        let expr = proto::Expr {
             column: "foo".to_string(),
-            op: Op::Eq,
-            scalar: Scalar::Bool(true),
-        });
-
-        round_trip(DeleteExpr {
-            column: "bar".to_string(),
-            op: Op::Ne,
-            scalar: Scalar::I64(-1),
-        });
-        round_trip(DeleteExpr {
-            column: "baz".to_string(),
-            op: Op::Eq,
-            scalar: Scalar::F64((-1.1).into()),
-        });
-        round_trip(DeleteExpr {
-            column: "col".to_string(),
-            op: Op::Eq,
-            scalar: Scalar::String("foo".to_string()),
-        });
+            op: proto::Op::Or.into(),
+            scalar: None,
+            list: vec![],
+            children: vec![proto::Expr {
+                column: "bar".to_string(),
+                op: proto::Op::Unspecified.into(),
+                scalar: Some(Scalar::I64(1).into()),
+                list: vec![],
+                children: vec![],
+            }],
+        };
+        let err = DeleteExpr::try_from(expr).unwrap_err();
+        assert_eq!(err.field, "children.0.op");
     }
 }
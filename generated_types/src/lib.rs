@@ -48,6 +48,16 @@ pub mod influxdata {
             }
         }
 
+        pub mod compactor {
+            pub mod v1 {
+                include!(concat!(env!("OUT_DIR"), "/influxdata.iox.compactor.v1.rs"));
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/influxdata.iox.compactor.v1.serde.rs"
+                ));
+            }
+        }
+
         pub mod delete {
             pub mod v1 {
                 include!(concat!(env!("OUT_DIR"), "/influxdata.iox.delete.v1.rs"));
@@ -245,6 +255,7 @@ pub mod google;
 pub mod delete_predicate;
 #[cfg(any(feature = "data_types_conversions", test))]
 pub mod ingester;
+pub mod querier;
 #[cfg(any(feature = "data_types_conversions", test))]
 pub mod write_info;
 
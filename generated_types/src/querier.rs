@@ -0,0 +1,34 @@
+use crate::influxdata::iox::querier::v1::{query_param::Value, QueryParam};
+
+impl QueryParam {
+    /// A SQL `NULL` parameter.
+    pub fn null() -> Self {
+        Self {
+            value: Some(Value::NullValue(Default::default())),
+        }
+    }
+}
+
+macro_rules! impl_from_for_query_param {
+    ($t:ty, $variant:ident) => {
+        impl From<$t> for QueryParam {
+            fn from(v: $t) -> Self {
+                Self {
+                    value: Some(Value::$variant(v)),
+                }
+            }
+        }
+    };
+}
+
+impl_from_for_query_param!(bool, BoolValue);
+impl_from_for_query_param!(i64, I64Value);
+impl_from_for_query_param!(u64, U64Value);
+impl_from_for_query_param!(f64, F64Value);
+impl_from_for_query_param!(String, StringValue);
+
+impl From<&str> for QueryParam {
+    fn from(v: &str) -> Self {
+        v.to_string().into()
+    }
+}
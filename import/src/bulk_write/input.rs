@@ -0,0 +1,607 @@
+//! Discovery of input files and conversion of their contents into lines of
+//! line protocol.
+
+use arrow::{
+    array::{
+        Array, ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray,
+        TimestampNanosecondArray, UInt64Array,
+    },
+    compute::cast,
+    datatypes::DataType,
+};
+use chrono::DateTime;
+use flate2::read::MultiGzDecoder;
+use influxdb_line_protocol::{
+    builder::{AfterField, AfterMeasurement},
+    LineProtocolBuilder,
+};
+use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+use schema::{InfluxColumnType, InfluxFieldType, Schema};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use thiserror::Error;
+
+/// Number of rows read from parquet files at once.
+const PARQUET_BATCH_SIZE: usize = 8 * 1024;
+
+#[derive(Debug, Error)]
+pub enum InputError {
+    #[error("Error reading {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Unsupported input file {0:?}, expected .lp, .csv or .parquet (optionally .gz)")]
+    UnsupportedFormat(PathBuf),
+
+    #[error("Error reading CSV: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("CSV is missing the #datatype annotation")]
+    MissingCsvAnnotation,
+
+    #[error("CSV has no measurement column")]
+    MissingCsvMeasurement,
+
+    #[error("Unsupported CSV data type: {0}")]
+    UnsupportedCsvType(String),
+
+    #[error("Error reading parquet: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    #[error("Error converting parquet data: {0}")]
+    Arrow(#[from] arrow::error::ArrowError),
+
+    #[error("Parquet file does not have an IOx schema: {0}")]
+    Schema(#[from] schema::Error),
+
+    #[error("Parquet file does not have a measurement name in its schema")]
+    MissingParquetMeasurement,
+}
+
+/// The format of an input file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Line protocol.
+    LineProtocol,
+
+    /// CSV with an InfluxDB `#datatype` annotation.
+    AnnotatedCsv,
+
+    /// Parquet written by IOx.
+    Parquet,
+}
+
+/// A file to import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputFile {
+    pub path: PathBuf,
+    pub format: InputFormat,
+
+    /// If the file is gzip compressed.
+    pub gzip: bool,
+}
+
+impl InputFile {
+    /// Detects the format of the file at `path` from its extension.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, InputError> {
+        let path = path.into();
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        let (name, gzip) = match name.strip_suffix(".gz") {
+            Some(name) => (name, true),
+            None => (name.as_str(), false),
+        };
+
+        let format = match Path::new(name).extension().and_then(|ext| ext.to_str()) {
+            Some("lp" | "txt") => InputFormat::LineProtocol,
+            Some("csv") => InputFormat::AnnotatedCsv,
+            Some("parquet") if !gzip => InputFormat::Parquet,
+            _ => return Err(InputError::UnsupportedFormat(path)),
+        };
+
+        Ok(Self { path, format, gzip })
+    }
+
+    /// Opens the file for reading lines of line protocol.
+    pub fn open(&self) -> Result<Box<dyn LineSource>, InputError> {
+        let file = File::open(&self.path).map_err(|source| InputError::Io {
+            path: self.path.clone(),
+            source,
+        })?;
+
+        if self.format == InputFormat::Parquet {
+            return Ok(Box::new(ParquetSource::new(file)?));
+        }
+
+        let reader: Box<dyn Read + Send> = if self.gzip {
+            Box::new(MultiGzDecoder::new(file))
+        } else {
+            Box::new(file)
+        };
+        let reader = BufReader::new(reader);
+
+        Ok(match self.format {
+            InputFormat::LineProtocol => Box::new(LineProtocolSource {
+                path: self.path.clone(),
+                reader,
+                line_number: 0,
+            }),
+            InputFormat::AnnotatedCsv => Box::new(CsvSource::new(reader)?),
+            InputFormat::Parquet => unreachable!("handled above"),
+        })
+    }
+}
+
+/// Finds all supported input files in `paths`, descending into directories.
+///
+/// Files given explicitly must have a supported format, files found in
+/// directories without one are skipped.
+pub fn find_input_files(paths: &[PathBuf]) -> Result<Vec<InputFile>, InputError> {
+    let mut files = vec![];
+    for path in paths {
+        if path.is_dir() {
+            find_in_dir(path, &mut files)?;
+        } else {
+            files.push(InputFile::new(path)?);
+        }
+    }
+    Ok(files)
+}
+
+fn find_in_dir(dir: &Path, files: &mut Vec<InputFile>) -> Result<(), InputError> {
+    let io_err = |source| InputError::Io {
+        path: dir.to_path_buf(),
+        source,
+    };
+
+    let mut entries = std::fs::read_dir(dir)
+        .map_err(io_err)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(io_err)?;
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            find_in_dir(&path, files)?;
+        } else if let Ok(file) = InputFile::new(path) {
+            files.push(file);
+        }
+    }
+    Ok(())
+}
+
+/// A line of line protocol read from an input file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Line {
+    /// A line that can be written.
+    Data {
+        /// 1-based line (or row) number in the input file.
+        number: usize,
+        lp: String,
+    },
+
+    /// A line that could not be converted to line protocol.
+    Invalid {
+        /// 1-based line (or row) number in the input file.
+        number: usize,
+        error: String,
+    },
+}
+
+/// Source of lines of line protocol.
+pub trait LineSource: Send {
+    /// Returns the next line, or `None` once the input is exhausted.
+    fn next_line(&mut self) -> Result<Option<Line>, InputError>;
+}
+
+struct LineProtocolSource {
+    path: PathBuf,
+    reader: BufReader<Box<dyn Read + Send>>,
+    line_number: usize,
+}
+
+impl LineSource for LineProtocolSource {
+    fn next_line(&mut self) -> Result<Option<Line>, InputError> {
+        loop {
+            let mut lp = String::new();
+            let read = self
+                .reader
+                .read_line(&mut lp)
+                .map_err(|source| InputError::Io {
+                    path: self.path.clone(),
+                    source,
+                })?;
+            if read == 0 {
+                return Ok(None);
+            }
+            self.line_number += 1;
+
+            let trimmed = lp.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            return Ok(Some(Line::Data {
+                number: self.line_number,
+                lp: trimmed.to_string(),
+            }));
+        }
+    }
+}
+
+/// Type of a column of an annotated CSV file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CsvColumnType {
+    Measurement,
+    Tag,
+    Field(InfluxFieldType),
+    /// Timestamp in RFC3339 format.
+    TimeRfc3339,
+    /// Timestamp in nanoseconds since the epoch.
+    TimeNumber,
+    Ignored,
+}
+
+impl CsvColumnType {
+    fn parse(s: &str) -> Result<Self, InputError> {
+        Ok(match s.trim() {
+            "measurement" => Self::Measurement,
+            "tag" => Self::Tag,
+            "double" => Self::Field(InfluxFieldType::Float),
+            "long" => Self::Field(InfluxFieldType::Integer),
+            "unsignedLong" => Self::Field(InfluxFieldType::UInteger),
+            "boolean" => Self::Field(InfluxFieldType::Boolean),
+            "string" => Self::Field(InfluxFieldType::String),
+            "dateTime" | "dateTime:RFC3339" | "dateTime:RFC3339Nano" => Self::TimeRfc3339,
+            "dateTime:number" => Self::TimeNumber,
+            "ignored" | "" => Self::Ignored,
+            other => return Err(InputError::UnsupportedCsvType(other.to_string())),
+        })
+    }
+}
+
+/// Reads CSV files in the [annotated CSV] format of InfluxDB 2.
+///
+/// The `#datatype` annotation determines how each column is mapped to line
+/// protocol, other annotations are ignored. The row following the
+/// annotations names the columns, for example:
+///
+/// ```text
+/// #datatype measurement,tag,double,dateTime:RFC3339
+/// m,host,usage,time
+/// cpu,host1,64.2,2020-01-01T00:00:00Z
+/// ```
+///
+/// [annotated CSV]: https://docs.influxdata.com/influxdb/v2.4/reference/syntax/annotated-csv/extended/
+struct CsvSource {
+    reader: csv::Reader<BufReader<Box<dyn Read + Send>>>,
+    columns: Vec<(String, CsvColumnType)>,
+    row_number: usize,
+}
+
+impl CsvSource {
+    fn new(reader: BufReader<Box<dyn Read + Send>>) -> Result<Self, InputError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(reader);
+
+        let mut types = None;
+        let mut row_number = 0;
+        let mut record = csv::StringRecord::new();
+        let names = loop {
+            if !reader.read_record(&mut record)? {
+                return Err(InputError::MissingCsvAnnotation);
+            }
+            row_number += 1;
+
+            // the first cell of an annotation row holds the annotation
+            // followed by the value for the first column
+            match record.get(0).and_then(|cell| cell.strip_prefix('#')) {
+                Some(cell) => {
+                    let (annotation, first) = cell.split_once(' ').unwrap_or((cell, ""));
+                    if annotation == "datatype" {
+                        types = Some(
+                            std::iter::once(first)
+                                .chain(record.iter().skip(1))
+                                .map(CsvColumnType::parse)
+                                .collect::<Result<Vec<_>, _>>()?,
+                        );
+                    }
+                }
+                None => break record.iter().map(String::from).collect::<Vec<_>>(),
+            }
+        };
+
+        let types = types.ok_or(InputError::MissingCsvAnnotation)?;
+        if !types.contains(&CsvColumnType::Measurement) {
+            return Err(InputError::MissingCsvMeasurement);
+        }
+
+        Ok(Self {
+            reader,
+            columns: names.into_iter().zip(types).collect(),
+            row_number,
+        })
+    }
+
+    fn convert(&self, record: &csv::StringRecord) -> Result<String, String> {
+        let values = || {
+            self.columns
+                .iter()
+                .zip(record.iter())
+                .filter(|(_, value)| !value.is_empty())
+        };
+
+        let measurement = values()
+            .find(|((_, t), _)| *t == CsvColumnType::Measurement)
+            .map(|(_, value)| value)
+            .ok_or_else(|| "missing measurement".to_string())?;
+
+        let mut builder = LineProtocolBuilder::new().measurement(measurement);
+        for ((name, _), value) in values().filter(|((_, t), _)| *t == CsvColumnType::Tag) {
+            builder = builder.tag(name, value);
+        }
+
+        let mut fields = values().filter_map(|((name, t), value)| match t {
+            CsvColumnType::Field(field_type) => Some((name, *field_type, value)),
+            _ => None,
+        });
+        let (name, field_type, value) = fields.next().ok_or_else(|| "no fields".to_string())?;
+        let mut builder = first_field(builder, name, parse_csv_field(field_type, value)?);
+        for (name, field_type, value) in fields {
+            builder = next_field(builder, name, parse_csv_field(field_type, value)?);
+        }
+
+        let time = values().find_map(|((_, t), value)| match t {
+            CsvColumnType::TimeRfc3339 => Some(
+                DateTime::parse_from_rfc3339(value)
+                    .map(|t| t.timestamp_nanos())
+                    .map_err(|e| format!("invalid timestamp '{}': {}", value, e)),
+            ),
+            CsvColumnType::TimeNumber => Some(
+                value
+                    .parse::<i64>()
+                    .map_err(|e| format!("invalid timestamp '{}': {}", value, e)),
+            ),
+            _ => None,
+        });
+
+        let lp = match time {
+            Some(time) => builder.timestamp(time?).close_line().build(),
+            None => builder.close_line().build(),
+        };
+        Ok(String::from_utf8(lp).expect("line protocol of strings is valid UTF-8"))
+    }
+}
+
+impl LineSource for CsvSource {
+    fn next_line(&mut self) -> Result<Option<Line>, InputError> {
+        let mut record = csv::StringRecord::new();
+        loop {
+            if !self.reader.read_record(&mut record)? {
+                return Ok(None);
+            }
+            self.row_number += 1;
+
+            // skip empty rows and annotations of further tables
+            if record.iter().all(str::is_empty)
+                || record.get(0).map_or(false, |s| s.starts_with('#'))
+            {
+                continue;
+            }
+
+            let number = self.row_number;
+            return Ok(Some(match self.convert(&record) {
+                Ok(lp) => Line::Data {
+                    number,
+                    lp: lp.trim_end().to_string(),
+                },
+                Err(error) => Line::Invalid { number, error },
+            }));
+        }
+    }
+}
+
+/// A typed field value.
+#[derive(Debug, Clone, PartialEq)]
+enum FieldValue<'a> {
+    F64(f64),
+    I64(i64),
+    U64(u64),
+    Bool(bool),
+    String(&'a str),
+}
+
+fn parse_csv_field(field_type: InfluxFieldType, value: &str) -> Result<FieldValue<'_>, String> {
+    let invalid =
+        |e: &dyn std::fmt::Display| format!("invalid {:?} '{}': {}", field_type, value, e);
+    Ok(match field_type {
+        InfluxFieldType::Float => FieldValue::F64(value.parse().map_err(|e| invalid(&e))?),
+        InfluxFieldType::Integer => FieldValue::I64(value.parse().map_err(|e| invalid(&e))?),
+        InfluxFieldType::UInteger => FieldValue::U64(value.parse().map_err(|e| invalid(&e))?),
+        InfluxFieldType::Boolean => FieldValue::Bool(value.parse().map_err(|e| invalid(&e))?),
+        InfluxFieldType::String => FieldValue::String(value),
+    })
+}
+
+fn first_field(
+    builder: LineProtocolBuilder<Vec<u8>, AfterMeasurement>,
+    name: &str,
+    value: FieldValue<'_>,
+) -> LineProtocolBuilder<Vec<u8>, AfterField> {
+    match value {
+        FieldValue::F64(v) => builder.field(name, v),
+        FieldValue::I64(v) => builder.field(name, v),
+        FieldValue::U64(v) => builder.field(name, v),
+        FieldValue::Bool(v) => builder.field(name, v),
+        FieldValue::String(v) => builder.field(name, v),
+    }
+}
+
+fn next_field(
+    builder: LineProtocolBuilder<Vec<u8>, AfterField>,
+    name: &str,
+    value: FieldValue<'_>,
+) -> LineProtocolBuilder<Vec<u8>, AfterField> {
+    match value {
+        FieldValue::F64(v) => builder.field(name, v),
+        FieldValue::I64(v) => builder.field(name, v),
+        FieldValue::U64(v) => builder.field(name, v),
+        FieldValue::Bool(v) => builder.field(name, v),
+        FieldValue::String(v) => builder.field(name, v),
+    }
+}
+
+/// A column of a parquet record batch, with tags cast to plain strings.
+struct ParquetColumn {
+    name: String,
+    column_type: InfluxColumnType,
+    array: ArrayRef,
+}
+
+/// Reads parquet files with an IOx schema, as written by IOx.
+struct ParquetSource {
+    reader: ParquetRecordBatchReader,
+    measurement: String,
+    columns: Vec<ParquetColumn>,
+    num_rows: usize,
+    offset: usize,
+    row_number: usize,
+}
+
+impl ParquetSource {
+    fn new(file: File) -> Result<Self, InputError> {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+        let schema = Schema::try_from(Arc::clone(builder.schema()))?;
+        let measurement = schema
+            .measurement()
+            .cloned()
+            .ok_or(InputError::MissingParquetMeasurement)?;
+        let reader = builder.with_batch_size(PARQUET_BATCH_SIZE).build()?;
+
+        Ok(Self {
+            reader,
+            measurement,
+            columns: vec![],
+            num_rows: 0,
+            offset: 0,
+            row_number: 0,
+        })
+    }
+
+    /// Loads the next record batch, returning `false` once the file is
+    /// exhausted.
+    fn next_batch(&mut self) -> Result<bool, InputError> {
+        let batch = match self.reader.next() {
+            Some(batch) => batch?,
+            None => return Ok(false),
+        };
+        let schema = Schema::try_from(batch.schema())?;
+
+        self.columns = schema
+            .iter()
+            .zip(batch.columns())
+            .filter_map(|((column_type, field), array)| {
+                column_type.map(|column_type| (column_type, field, array))
+            })
+            .map(|(column_type, field, array)| {
+                let array = match column_type {
+                    InfluxColumnType::Tag => cast(array, &DataType::Utf8)?,
+                    _ => Arc::clone(array),
+                };
+                Ok(ParquetColumn {
+                    name: field.name().clone(),
+                    column_type,
+                    array,
+                })
+            })
+            .collect::<Result<Vec<_>, InputError>>()?;
+        self.num_rows = batch.num_rows();
+        self.offset = 0;
+        Ok(true)
+    }
+
+    fn convert(&self, row: usize) -> Result<String, String> {
+        let valid = || self.columns.iter().filter(|c| c.array.is_valid(row));
+
+        let mut builder = LineProtocolBuilder::new().measurement(&self.measurement);
+        for column in valid().filter(|c| c.column_type == InfluxColumnType::Tag) {
+            let array = downcast::<StringArray>(&column.array);
+            builder = builder.tag(&column.name, array.value(row));
+        }
+
+        let mut fields = valid().filter_map(|c| match c.column_type {
+            InfluxColumnType::Field(field_type) => {
+                Some((c.name.as_str(), field_value(field_type, &c.array, row)))
+            }
+            _ => None,
+        });
+        let (name, value) = fields.next().ok_or_else(|| "no fields".to_string())?;
+        let mut builder = first_field(builder, name, value);
+        for (name, value) in fields {
+            builder = next_field(builder, name, value);
+        }
+
+        let time = valid()
+            .find(|c| c.column_type == InfluxColumnType::Timestamp)
+            .map(|c| downcast::<TimestampNanosecondArray>(&c.array).value(row));
+
+        let lp = match time {
+            Some(time) => builder.timestamp(time).close_line().build(),
+            None => builder.close_line().build(),
+        };
+        Ok(String::from_utf8(lp).expect("line protocol of strings is valid UTF-8"))
+    }
+}
+
+impl LineSource for ParquetSource {
+    fn next_line(&mut self) -> Result<Option<Line>, InputError> {
+        while self.offset == self.num_rows {
+            if !self.next_batch()? {
+                return Ok(None);
+            }
+        }
+
+        let row = self.offset;
+        self.offset += 1;
+        self.row_number += 1;
+
+        let number = self.row_number;
+        Ok(Some(match self.convert(row) {
+            Ok(lp) => Line::Data {
+                number,
+                lp: lp.trim_end().to_string(),
+            },
+            Err(error) => Line::Invalid { number, error },
+        }))
+    }
+}
+
+fn downcast<T: 'static>(array: &ArrayRef) -> &T {
+    array
+        .as_any()
+        .downcast_ref::<T>()
+        .expect("IOx schema matches arrow type")
+}
+
+fn field_value(field_type: InfluxFieldType, array: &ArrayRef, row: usize) -> FieldValue<'_> {
+    match field_type {
+        InfluxFieldType::Float => FieldValue::F64(downcast::<Float64Array>(array).value(row)),
+        InfluxFieldType::Integer => FieldValue::I64(downcast::<Int64Array>(array).value(row)),
+        InfluxFieldType::UInteger => FieldValue::U64(downcast::<UInt64Array>(array).value(row)),
+        InfluxFieldType::Boolean => FieldValue::Bool(downcast::<BooleanArray>(array).value(row)),
+        InfluxFieldType::String => FieldValue::String(downcast::<StringArray>(array).value(row)),
+    }
+}
@@ -0,0 +1,559 @@
+//! Bulk loading of line protocol, annotated CSV and parquet files via the
+//! write API.
+//!
+//! Input files are read on a blocking thread, converted to line protocol and
+//! accumulated with a [`LinesConverter`] into size-bounded batches. Batches
+//! are sent with retries, optionally rate limited, and in the order they were
+//! read: a batch is only sent once all but `concurrency - 1` of the batches
+//! before it completed.
+//!
+//! Lines that cannot be parsed are reported as rejected and do not abort the
+//! import; failing to write a batch does.
+
+pub mod input;
+
+use self::input::{InputError, InputFile, Line};
+use dml::{DmlMeta, DmlWrite};
+use futures::{StreamExt, TryStreamExt};
+use hashbrown::HashMap;
+use influxdb_iox_client::{
+    error::Error as ClientError,
+    write::{self, generated_types::WriteRequest},
+};
+use mutable_batch::MutableBatch;
+use mutable_batch_lp::LinesConverter;
+use observability_deps::tracing::{debug, warn};
+use std::{num::NonZeroU64, path::PathBuf, time::Duration};
+use thiserror::Error;
+use tokio::{sync::Mutex, time::Instant};
+
+#[derive(Debug, Error)]
+pub enum BulkWriteError {
+    #[error("Error reading input: {0}")]
+    Input(#[from] InputError),
+
+    #[error("Error writing batch of {path:?} after {attempts} attempt(s): {source}")]
+    Write {
+        path: PathBuf,
+        attempts: usize,
+        source: ClientError,
+    },
+
+    #[error("Input reader failed: {0}")]
+    Reader(#[from] tokio::task::JoinError),
+}
+
+/// Configuration of a bulk write.
+#[derive(Debug, Clone)]
+pub struct BulkWriteConfig {
+    /// Maximum size of the line protocol of a batch, in bytes.
+    pub max_batch_bytes: usize,
+
+    /// Number of batches written concurrently.
+    ///
+    /// Batches in flight at the same time may be applied in any order, so
+    /// inputs writing the same point more than once need a concurrency of 1
+    /// for the last value to win.
+    pub concurrency: usize,
+
+    /// Number of times a failed batch is retried.
+    pub max_retries: usize,
+
+    /// Delay before the first retry, doubled for every further one.
+    pub retry_backoff: Duration,
+
+    /// Maximum number of lines written per second, if any.
+    pub max_lines_per_second: Option<NonZeroU64>,
+
+    /// Multiplier converting line protocol timestamps to nanoseconds.
+    pub timestamp_base: i64,
+
+    /// Timestamp of lines without one.
+    pub default_time: i64,
+}
+
+impl Default for BulkWriteConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_bytes: 10 * 1024 * 1024,
+            concurrency: 4,
+            max_retries: 3,
+            retry_backoff: Duration::from_secs(1),
+            max_lines_per_second: None,
+            timestamp_base: 1,
+            default_time: 0,
+        }
+    }
+}
+
+/// Progress of a bulk write, reported via the callback passed to
+/// [`bulk_write`].
+#[derive(Debug)]
+pub enum Progress<'a> {
+    /// Started reading a file.
+    FileStarted { path: &'a PathBuf },
+
+    /// A line was rejected.
+    Rejected {
+        path: &'a PathBuf,
+        line: usize,
+        error: &'a str,
+    },
+
+    /// A batch was written.
+    BatchWritten {
+        path: &'a PathBuf,
+        lines: usize,
+        bytes: usize,
+    },
+
+    /// All lines of a file were written.
+    FileFinished(&'a FileSummary),
+}
+
+/// Summary of the import of one file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileSummary {
+    pub path: PathBuf,
+    pub lines_written: usize,
+    pub lines_rejected: usize,
+    pub bytes_written: usize,
+    pub batches: usize,
+}
+
+/// A size-bounded batch of lines, converted to [`MutableBatch`]es.
+#[derive(Debug)]
+struct Batch {
+    tables: HashMap<String, MutableBatch>,
+    lines: usize,
+    bytes: usize,
+}
+
+/// Message from the blocking reader of a file.
+#[derive(Debug)]
+enum ReaderMessage {
+    Batch(Batch),
+    Rejected { line: usize, error: String },
+}
+
+/// Outcome of handling a [`ReaderMessage`].
+#[derive(Debug)]
+enum Outcome {
+    Written { lines: usize, bytes: usize },
+    Rejected { line: usize, error: String },
+}
+
+/// Writes all `files` to the namespace `namespace_name`, in order, and
+/// returns a summary per file.
+pub async fn bulk_write<F>(
+    client: write::Client,
+    namespace_name: &str,
+    files: Vec<InputFile>,
+    config: &BulkWriteConfig,
+    mut progress: F,
+) -> Result<Vec<FileSummary>, BulkWriteError>
+where
+    F: FnMut(Progress<'_>) + Send,
+{
+    let rate_limiter = config.max_lines_per_second.map(RateLimiter::new);
+
+    let mut summaries = Vec::with_capacity(files.len());
+    for file in files {
+        progress(Progress::FileStarted { path: &file.path });
+
+        let mut summary = FileSummary {
+            path: file.path.clone(),
+            ..Default::default()
+        };
+
+        // the channel bounds the number of batches buffered ahead of the writers
+        let (tx, rx) = tokio::sync::mpsc::channel(config.concurrency.max(1));
+        let reader = {
+            let config = config.clone();
+            tokio::task::spawn_blocking(move || read_batches(&file, &config, tx))
+        };
+
+        let messages = futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|message| (message, rx))
+        });
+
+        let mut writes = messages
+            .map(|message| {
+                let client = client.clone();
+                let rate_limiter = rate_limiter.as_ref();
+                async move {
+                    match message {
+                        ReaderMessage::Batch(batch) => {
+                            if let Some(rate_limiter) = rate_limiter {
+                                rate_limiter.acquire(batch.lines as u64).await;
+                            }
+                            let (lines, bytes) = (batch.lines, batch.bytes);
+                            write_batch(client, namespace_name, batch, config).await?;
+                            Ok(Outcome::Written { lines, bytes })
+                        }
+                        ReaderMessage::Rejected { line, error } => {
+                            Ok(Outcome::Rejected { line, error })
+                        }
+                    }
+                }
+            })
+            .buffered(config.concurrency.max(1));
+
+        while let Some(outcome) =
+            writes
+                .try_next()
+                .await
+                .map_err(|(attempts, source)| BulkWriteError::Write {
+                    path: summary.path.clone(),
+                    attempts,
+                    source,
+                })?
+        {
+            match outcome {
+                Outcome::Written { lines, bytes } => {
+                    summary.lines_written += lines;
+                    summary.bytes_written += bytes;
+                    summary.batches += 1;
+                    progress(Progress::BatchWritten {
+                        path: &summary.path,
+                        lines,
+                        bytes,
+                    });
+                }
+                Outcome::Rejected { line, error } => {
+                    summary.lines_rejected += 1;
+                    progress(Progress::Rejected {
+                        path: &summary.path,
+                        line,
+                        error: &error,
+                    });
+                }
+            }
+        }
+        drop(writes);
+
+        reader.await??;
+
+        progress(Progress::FileFinished(&summary));
+        summaries.push(summary);
+    }
+
+    Ok(summaries)
+}
+
+/// Reads `file`, sending batches and rejected lines to `tx`.
+fn read_batches(
+    file: &InputFile,
+    config: &BulkWriteConfig,
+    tx: tokio::sync::mpsc::Sender<ReaderMessage>,
+) -> Result<(), InputError> {
+    let mut source = file.open()?;
+
+    let new_converter = || {
+        let mut converter = LinesConverter::new(config.default_time);
+        if file.format == input::InputFormat::LineProtocol {
+            converter.set_timestamp_base(config.timestamp_base);
+        }
+        converter
+    };
+
+    let mut converter = new_converter();
+    let mut lines = 0;
+    let mut bytes = 0;
+
+    while let Some(line) = source.next_line()? {
+        let message = match line {
+            Line::Data { number, lp } => match converter.write_lp(&lp) {
+                Ok(()) => {
+                    lines += 1;
+                    bytes += lp.len() + 1;
+                    None
+                }
+                Err(e) => Some(ReaderMessage::Rejected {
+                    line: number,
+                    error: e.to_string(),
+                }),
+            },
+            Line::Invalid { number, error } => Some(ReaderMessage::Rejected {
+                line: number,
+                error,
+            }),
+        };
+
+        if let Some(message) = message {
+            if tx.blocking_send(message).is_err() {
+                // the writer failed
+                return Ok(());
+            }
+        }
+
+        if bytes >= config.max_batch_bytes {
+            let batch = finish_batch(
+                std::mem::replace(&mut converter, new_converter()),
+                lines,
+                bytes,
+            );
+            lines = 0;
+            bytes = 0;
+            if tx.blocking_send(ReaderMessage::Batch(batch)).is_err() {
+                return Ok(());
+            }
+        }
+    }
+
+    if lines > 0 {
+        // failure to send means the writer failed, which is reported there
+        tx.blocking_send(ReaderMessage::Batch(finish_batch(converter, lines, bytes)))
+            .ok();
+    }
+
+    Ok(())
+}
+
+fn finish_batch(converter: LinesConverter, lines: usize, bytes: usize) -> Batch {
+    // the converter is not empty as at least one line was written
+    let (tables, _) = converter.finish().expect("batch is not empty");
+
+    // rejected lines may leave behind empty tables
+    let tables = tables
+        .into_iter()
+        .filter(|(_, batch)| batch.rows() > 0)
+        .collect();
+
+    Batch {
+        tables,
+        lines,
+        bytes,
+    }
+}
+
+/// Writes `batch`, retrying failures that may be transient.
+///
+/// Returns the number of attempts along with the last error on failure.
+async fn write_batch(
+    mut client: write::Client,
+    namespace_name: &str,
+    batch: Batch,
+    config: &BulkWriteConfig,
+) -> Result<(), (usize, ClientError)> {
+    let write = DmlWrite::new(
+        namespace_name,
+        batch.tables,
+        None,
+        DmlMeta::unsequenced(None),
+    );
+    let request = WriteRequest {
+        database_batch: Some(mutable_batch_pb::encode::encode_write(
+            namespace_name,
+            &write,
+        )),
+    };
+
+    let mut backoff = config.retry_backoff;
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match client.write_pb(request.clone()).await {
+            Ok(_) => {
+                debug!(lines = batch.lines, bytes = batch.bytes, "wrote batch");
+                return Ok(());
+            }
+            Err(e) if attempts <= config.max_retries && is_retryable(&e) => {
+                warn!(%e, attempts, ?backoff, "failed to write batch, retrying");
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            Err(e) => return Err((attempts, e)),
+        }
+    }
+}
+
+fn is_retryable(e: &ClientError) -> bool {
+    matches!(
+        e,
+        ClientError::Unavailable(_)
+            | ClientError::ResourceExhausted(_)
+            | ClientError::DeadlineExceeded(_)
+            | ClientError::Aborted(_)
+            | ClientError::Internal(_)
+            | ClientError::Unknown(_)
+    )
+}
+
+/// Limits the rate of written lines by spacing out writes.
+#[derive(Debug)]
+struct RateLimiter {
+    lines_per_second: NonZeroU64,
+
+    /// Earliest time the next write may start.
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(lines_per_second: NonZeroU64) -> Self {
+        Self {
+            lines_per_second,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Waits until `lines` may be written.
+    async fn acquire(&self, lines: u64) {
+        let start = {
+            let mut next = self.next.lock().await;
+            let start = (*next).max(Instant::now());
+            *next =
+                start + Duration::from_secs_f64(lines as f64 / self.lines_per_second.get() as f64);
+            start
+        };
+        tokio::time::sleep_until(start).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_types::Statistics;
+    use std::io::Write;
+
+    fn time_range(batch: &MutableBatch) -> (i64, i64) {
+        match batch.column(schema::TIME_COLUMN_NAME).unwrap().stats() {
+            Statistics::I64(stats) => (stats.min.unwrap(), stats.max.unwrap()),
+            other => panic!("unexpected time stats: {:?}", other),
+        }
+    }
+
+    fn read_all(file: &InputFile, config: &BulkWriteConfig) -> Vec<ReaderMessage> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1000);
+        read_batches(file, config, tx).unwrap();
+
+        let mut messages = vec![];
+        while let Ok(message) = rx.try_recv() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    #[test]
+    fn test_read_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.lp");
+        let mut f = std::fs::File::create(&path).unwrap();
+        writeln!(f, "# comment").unwrap();
+        writeln!(f, "cpu,host=a usage=1 1").unwrap();
+        writeln!(f, "cpu,host=a usage=").unwrap();
+        writeln!(f).unwrap();
+        writeln!(f, "mem,host=a free=2i 2").unwrap();
+        writeln!(f, "cpu,host=b usage=3 3").unwrap();
+        drop(f);
+
+        let config = BulkWriteConfig {
+            // two lines per batch
+            max_batch_bytes: 40,
+            timestamp_base: 1_000,
+            ..Default::default()
+        };
+        let messages = read_all(&InputFile::new(&path).unwrap(), &config);
+        assert_eq!(messages.len(), 3);
+
+        assert!(matches!(
+            messages[0],
+            ReaderMessage::Rejected { line: 3, .. }
+        ));
+        match &messages[1] {
+            ReaderMessage::Batch(batch) => {
+                assert_eq!(batch.lines, 2);
+                assert_eq!(batch.tables.len(), 2);
+                assert_eq!(batch.tables["cpu"].rows(), 1);
+                assert_eq!(batch.tables["mem"].rows(), 1);
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+        match &messages[2] {
+            ReaderMessage::Batch(batch) => {
+                assert_eq!(batch.lines, 1);
+                assert_eq!(batch.tables["cpu"].rows(), 1);
+                assert_eq!(time_range(&batch.tables["cpu"]), (3_000, 3_000));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_read_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        std::fs::write(
+            &path,
+            "#datatype measurement,tag,double,long,dateTime:RFC3339\n\
+             m,host,usage,count,time\n\
+             cpu,a,1.5,2,1970-01-01T00:00:00.000000010Z\n\
+             cpu,,2.5,x,1970-01-01T00:00:00.000000020Z\n\
+             cpu,\"b,c\",3.5,,1970-01-01T00:00:00.000000030Z\n",
+        )
+        .unwrap();
+
+        let messages = read_all(&InputFile::new(&path).unwrap(), &Default::default());
+        assert_eq!(messages.len(), 2);
+        match &messages[0] {
+            ReaderMessage::Rejected { line, error } => {
+                assert_eq!(*line, 4);
+                assert!(error.contains("invalid Integer 'x'"), "{}", error);
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+        match &messages[1] {
+            ReaderMessage::Batch(batch) => {
+                assert_eq!(batch.lines, 2);
+                let cpu = &batch.tables["cpu"];
+                assert_eq!(cpu.rows(), 2);
+                assert_eq!(time_range(cpu), (10, 30));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_find_input_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        for name in ["a.lp", "b.lp.gz", "sub/c.csv", "sub/d.parquet", "e.json"] {
+            std::fs::write(dir.path().join(name), "").unwrap();
+        }
+
+        let files = input::find_input_files(&[dir.path().to_path_buf()]).unwrap();
+        let names = files
+            .iter()
+            .map(|f| {
+                (
+                    f.path.strip_prefix(dir.path()).unwrap().to_str().unwrap(),
+                    f.format,
+                    f.gzip,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                ("a.lp", input::InputFormat::LineProtocol, false),
+                ("b.lp.gz", input::InputFormat::LineProtocol, true),
+                ("sub/c.csv", input::InputFormat::AnnotatedCsv, false),
+                ("sub/d.parquet", input::InputFormat::Parquet, false),
+            ]
+        );
+
+        let err = input::find_input_files(&[dir.path().join("e.json")]).unwrap_err();
+        assert!(matches!(err, InputError::UnsupportedFormat(_)));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter() {
+        let start = Instant::now();
+        let limiter = RateLimiter::new(NonZeroU64::new(1_000).unwrap());
+
+        limiter.acquire(100).await;
+        limiter.acquire(50).await;
+        limiter.acquire(1).await;
+        assert!(start.elapsed() >= Duration::from_millis(150));
+    }
+}
@@ -6,6 +6,8 @@ use serde::*;
 use std::collections::{HashMap, HashSet};
 
 pub mod aggregate_tsm_schema;
+pub mod bulk_write;
+pub mod tsm_data;
 
 /// This struct is used to build up schemas from TSM snapshots that we are going to use to bulk
 /// ingest. They will be merged, then validated to check for anomalies that will complicate bulk
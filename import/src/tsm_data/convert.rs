@@ -0,0 +1,574 @@
+//! Conversion of TSM files and WAL segments into [`MutableBatch`]es, one
+//! table per measurement.
+use influxdb_tsm::{
+    key::{parse_any_key, ParsedSeriesKey},
+    mapper::{ColumnData, TsmMeasurementMapper},
+    reader::{TsmBlockReader, TsmIndexReader, ValuePair},
+    wal::{WalEntry, WalReader},
+    TsmError,
+};
+use mutable_batch::{writer::Writer, MutableBatch};
+use observability_deps::tracing::warn;
+use schema::TIME_COLUMN_NAME;
+use std::{
+    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+    fs::File,
+    io::BufReader,
+    mem,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// Separates the series key from the field key in a composite key.
+const FIELD_KEY_SEPARATOR: &[u8] = b"#!~#";
+
+#[derive(Debug, Error)]
+pub enum ConvertError {
+    #[error("Error opening {}: {source}", path.display())]
+    Open {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Error reading {}: {source}", path.display())]
+    Read { path: PathBuf, source: TsmError },
+
+    #[error("Error converting measurement '{measurement}' of {}: {source}", path.display())]
+    Write {
+        path: PathBuf,
+        measurement: String,
+        source: mutable_batch::writer::Error,
+    },
+}
+
+/// Converts the series of the TSM file at `path` into one table per
+/// measurement.
+///
+/// Tables are handed to `emit` once they hold at least `max_rows` rows, and
+/// once the whole file has been converted. Conversion stops early if `emit`
+/// returns [`ControlFlow::Break`].
+pub fn convert_tsm_file<F>(
+    path: &Path,
+    max_rows: usize,
+    emit: F,
+) -> Result<ControlFlow<()>, ConvertError>
+where
+    F: FnMut(String, MutableBatch) -> ControlFlow<()>,
+{
+    let open = || {
+        File::open(path)
+            .map(BufReader::new)
+            .map_err(|source| ConvertError::Open {
+                path: path.to_path_buf(),
+                source,
+            })
+    };
+    let read_error = |source| ConvertError::Read {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    let len = std::fs::metadata(path)
+        .map_err(|source| ConvertError::Open {
+            path: path.to_path_buf(),
+            source,
+        })?
+        .len();
+    let index = TsmIndexReader::try_new(open()?, len as usize).map_err(read_error)?;
+    let mut block_reader = TsmBlockReader::new(open()?);
+    let mut tables = Tables::new(max_rows, emit);
+
+    for table in TsmMeasurementMapper::new(index.peekable(), 0) {
+        let mut table = table.map_err(read_error)?;
+        let measurement = table.name.clone();
+
+        // `process` only stops early on an error, so breaks and write
+        // errors are smuggled out of the closure.
+        let mut flow = ControlFlow::Continue(());
+        let mut write_error = None;
+        let processed = table.process(&mut block_reader, |section| {
+            match tables.write(
+                &measurement,
+                &section.tag_cols,
+                &section.ts,
+                &section.field_cols,
+            ) {
+                Ok(ControlFlow::Continue(())) => return Ok(()),
+                Ok(ControlFlow::Break(())) => flow = ControlFlow::Break(()),
+                Err(e) => write_error = Some(e),
+            }
+            Err(TsmError {
+                description: "conversion stopped".into(),
+            })
+        });
+
+        if let Some(source) = write_error {
+            return Err(ConvertError::Write {
+                path: path.to_path_buf(),
+                measurement,
+                source,
+            });
+        }
+        if flow.is_break() {
+            return Ok(flow);
+        }
+        processed.map_err(read_error)?;
+    }
+
+    Ok(tables.finish())
+}
+
+/// Replays the WAL segments of a single shard, in the order given, and
+/// converts the values that remain into one table per measurement.
+///
+/// Later writes of a value replace earlier ones, and deletes remove the
+/// values written before them. Tables are handed to `emit` as in
+/// [`convert_tsm_file`].
+pub fn convert_wal_segments<F>(
+    paths: &[PathBuf],
+    max_rows: usize,
+    emit: F,
+) -> Result<ControlFlow<()>, ConvertError>
+where
+    F: FnMut(String, MutableBatch) -> ControlFlow<()>,
+{
+    // The values of each composite key, by timestamp.
+    let mut series: BTreeMap<Vec<u8>, (ParsedSeriesKey, BTreeMap<i64, ValuePair>)> =
+        BTreeMap::new();
+
+    for path in paths {
+        let read_error = |source| ConvertError::Read {
+            path: path.clone(),
+            source,
+        };
+        let file = File::open(path).map_err(|source| ConvertError::Open {
+            path: path.clone(),
+            source,
+        })?;
+
+        for entry in WalReader::new(BufReader::new(file)) {
+            match entry.map_err(read_error)? {
+                WalEntry::Write { values } => {
+                    for (key, pairs) in values {
+                        let (_, values) = match series.entry(key) {
+                            Entry::Occupied(entry) => entry.into_mut(),
+                            Entry::Vacant(entry) => {
+                                let parsed = parse_any_key(entry.key()).map_err(|e| {
+                                    read_error(TsmError {
+                                        description: e.to_string(),
+                                    })
+                                })?;
+                                entry.insert((parsed, BTreeMap::new()))
+                            }
+                        };
+                        values.extend(pairs.into_iter().map(|pair| (pair.timestamp(), pair)));
+                    }
+                }
+                WalEntry::Delete { keys } => {
+                    for key in keys {
+                        for_each_matching(&mut series, &key, |values| values.clear());
+                    }
+                }
+                WalEntry::DeleteRange {
+                    keys,
+                    min_time,
+                    max_time,
+                } => {
+                    for key in keys {
+                        for_each_matching(&mut series, &key, |values| {
+                            values.retain(|ts, _| !(min_time..=max_time).contains(ts))
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    // Gather the fields of each series so that its rows can be written at
+    // once, as for the sections of a TSM file.
+    let mut tag_sets: BTreeMap<
+        (String, Vec<(String, String)>),
+        Vec<(String, BTreeMap<i64, ValuePair>)>,
+    > = BTreeMap::new();
+    for (_, (key, values)) in series {
+        if !values.is_empty() {
+            tag_sets
+                .entry((key.measurement, key.tagset))
+                .or_default()
+                .push((key.field_key, values));
+        }
+    }
+
+    let mut tables = Tables::new(max_rows, emit);
+    for ((measurement, tagset), fields) in tag_sets {
+        let ts: Vec<i64> = fields
+            .iter()
+            .flat_map(|(_, values)| values.keys().copied())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let field_cols: BTreeMap<_, _> = fields
+            .into_iter()
+            .map(|(field_key, values)| {
+                let column = to_column(&ts, &values);
+                (field_key, column)
+            })
+            .collect();
+
+        let flow = tables
+            .write(&measurement, &tagset, &ts, &field_cols)
+            .map_err(|source| ConvertError::Write {
+                path: paths.last().cloned().unwrap_or_default(),
+                measurement,
+                source,
+            })?;
+        if flow.is_break() {
+            return Ok(flow);
+        }
+    }
+
+    Ok(tables.finish())
+}
+
+// Calls `f` with the values of every composite key matching a deleted key,
+// which is either a composite key itself or the series key of all of its
+// fields.
+fn for_each_matching<F>(
+    series: &mut BTreeMap<Vec<u8>, (ParsedSeriesKey, BTreeMap<i64, ValuePair>)>,
+    deleted: &[u8],
+    mut f: F,
+) where
+    F: FnMut(&mut BTreeMap<i64, ValuePair>),
+{
+    for (key, (_, values)) in series.range_mut(deleted.to_vec()..) {
+        if !key.starts_with(deleted) {
+            break;
+        }
+        let rest = &key[deleted.len()..];
+        if rest.is_empty() || rest.starts_with(FIELD_KEY_SEPARATOR) {
+            f(values);
+        }
+    }
+}
+
+// Aligns the values of a field to the timestamps `ts`.
+//
+// The type of the column is that of the first value; values of any other
+// type are dropped, as the TSM mapper does for blocks.
+fn to_column(ts: &[i64], values: &BTreeMap<i64, ValuePair>) -> ColumnData {
+    macro_rules! align {
+        ($variant:ident, $pair:ident, $to_owned:expr) => {
+            ColumnData::$variant(
+                ts.iter()
+                    .map(|ts| match values.get(ts) {
+                        Some(ValuePair::$pair((_, v))) => Some($to_owned(v)),
+                        Some(other) => {
+                            warn!(?other, "dropped WAL value with conflicting type");
+                            None
+                        }
+                        None => None,
+                    })
+                    .collect(),
+            )
+        };
+    }
+
+    match values.values().next() {
+        Some(ValuePair::I64(_)) => align!(Integer, I64, |v: &i64| *v),
+        Some(ValuePair::U64(_)) => align!(Unsigned, U64, |v: &u64| *v),
+        Some(ValuePair::Bool(_)) => align!(Bool, Bool, |v: &bool| *v),
+        Some(ValuePair::Str(_)) => align!(Str, Str, |v: &Vec<u8>| v.clone()),
+        Some(ValuePair::F64(_)) | None => align!(Float, F64, |v: &f64| *v),
+    }
+}
+
+// Accumulates rows into a batch per measurement, handing batches off once
+// they are large enough.
+struct Tables<F> {
+    batches: BTreeMap<String, MutableBatch>,
+    max_rows: usize,
+    emit: F,
+}
+
+impl<F> Tables<F>
+where
+    F: FnMut(String, MutableBatch) -> ControlFlow<()>,
+{
+    fn new(max_rows: usize, emit: F) -> Self {
+        Self {
+            batches: BTreeMap::new(),
+            max_rows,
+            emit,
+        }
+    }
+
+    fn write(
+        &mut self,
+        measurement: &str,
+        tagset: &[(String, String)],
+        ts: &[i64],
+        field_cols: &BTreeMap<String, ColumnData>,
+    ) -> Result<ControlFlow<()>, mutable_batch::writer::Error> {
+        if ts.is_empty() {
+            return Ok(ControlFlow::Continue(()));
+        }
+
+        let batch = self.batches.entry(measurement.to_string()).or_default();
+        write_rows(batch, tagset, ts, field_cols)?;
+
+        if batch.rows() >= self.max_rows {
+            let batch = self.batches.remove(measurement).unwrap();
+            return Ok((self.emit)(measurement.to_string(), batch));
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+
+    fn finish(mut self) -> ControlFlow<()> {
+        for (measurement, batch) in mem::take(&mut self.batches) {
+            if (self.emit)(measurement, batch).is_break() {
+                return ControlFlow::Break(());
+            }
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+// Appends the rows of a single series to `batch`.
+fn write_rows(
+    batch: &mut MutableBatch,
+    tagset: &[(String, String)],
+    ts: &[i64],
+    field_cols: &BTreeMap<String, ColumnData>,
+) -> Result<(), mutable_batch::writer::Error> {
+    let rows = ts.len();
+    let mut writer = Writer::new(batch, rows);
+
+    for (key, value) in tagset {
+        writer.write_tag(key, None, std::iter::repeat(value.as_str()).take(rows))?;
+    }
+
+    for (name, column) in field_cols {
+        match column {
+            ColumnData::Float(values) => writer.write_f64(
+                name,
+                Some(&valid_mask(values)),
+                values.iter().flatten().copied(),
+            )?,
+            ColumnData::Integer(values) => writer.write_i64(
+                name,
+                Some(&valid_mask(values)),
+                values.iter().flatten().copied(),
+            )?,
+            ColumnData::Unsigned(values) => writer.write_u64(
+                name,
+                Some(&valid_mask(values)),
+                values.iter().flatten().copied(),
+            )?,
+            ColumnData::Bool(values) => writer.write_bool(
+                name,
+                Some(&valid_mask(values)),
+                values.iter().flatten().copied(),
+            )?,
+            ColumnData::Str(values) => {
+                let values: Vec<_> = values
+                    .iter()
+                    .map(|v| v.as_deref().map(String::from_utf8_lossy))
+                    .collect();
+                writer.write_string(
+                    name,
+                    Some(&valid_mask(&values)),
+                    values.iter().flatten().map(|v| v.as_ref()),
+                )?
+            }
+        }
+    }
+
+    writer.write_time(TIME_COLUMN_NAME, ts.iter().copied())?;
+    writer.commit();
+    Ok(())
+}
+
+// Builds the bitmask of the non-null values, as expected by `Writer`.
+fn valid_mask<T>(values: &[Option<T>]) -> Vec<u8> {
+    let mut mask = vec![0; (values.len() + 7) / 8];
+    for (idx, _) in values.iter().enumerate().filter(|(_, v)| v.is_some()) {
+        mask[idx / 8] |= 1 << (idx % 8);
+    }
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_util::assert_batches_eq;
+    use schema::selection::Selection;
+
+    fn collect(
+        convert: impl FnOnce(
+            &mut dyn FnMut(String, MutableBatch) -> ControlFlow<()>,
+        ) -> Result<ControlFlow<()>, ConvertError>,
+    ) -> Vec<(String, MutableBatch)> {
+        let mut tables = vec![];
+        let flow = convert(&mut |name, batch| {
+            tables.push((name, batch));
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        assert!(flow.is_continue());
+        tables
+    }
+
+    #[test]
+    fn tsm_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let mut decoder = flate2::read::GzDecoder::new(
+            File::open("../test_fixtures/000000000000005-000000002.tsm.gz").unwrap(),
+        );
+        std::io::copy(&mut decoder, &mut file).unwrap();
+
+        let tables = collect(|emit| convert_tsm_file(file.path(), usize::MAX, emit));
+
+        // each measurement is emitted once
+        assert_eq!(tables.len(), 121);
+
+        let (_, cpu) = tables.iter().find(|(name, _)| name == "cpu").unwrap();
+        let schema = cpu.schema(Selection::All).unwrap();
+        assert_eq!(
+            schema
+                .tags_iter()
+                .map(|f| f.name().as_str())
+                .collect::<Vec<_>>(),
+            vec!["cpu", "host"]
+        );
+        assert!(schema.find_index_of("usage_user").is_some());
+        assert!(schema.find_index_of(TIME_COLUMN_NAME).is_some());
+
+        let rows: usize = tables.iter().map(|(_, batch)| batch.rows()).sum();
+
+        // small batches are emitted as soon as they are large enough
+        let split = collect(|emit| convert_tsm_file(file.path(), 1, emit));
+        assert!(split.len() > tables.len());
+        assert_eq!(split.iter().map(|(_, b)| b.rows()).sum::<usize>(), rows);
+    }
+
+    fn encode_entry(entry_type: u8, data: &[u8]) -> Vec<u8> {
+        let compressed = snap::raw::Encoder::new().compress_vec(data).unwrap();
+        let mut buf = vec![entry_type];
+        buf.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&compressed);
+        buf
+    }
+
+    fn encode_floats(buf: &mut Vec<u8>, key: &[u8], values: &[(i64, f64)]) {
+        buf.push(1);
+        buf.extend_from_slice(&(key.len() as u16).to_be_bytes());
+        buf.extend_from_slice(key);
+        buf.extend_from_slice(&(values.len() as u32).to_be_bytes());
+        for (ts, value) in values {
+            buf.extend_from_slice(&ts.to_be_bytes());
+            buf.extend_from_slice(&value.to_bits().to_be_bytes());
+        }
+    }
+
+    #[test]
+    fn wal_segments() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut write = vec![];
+        encode_floats(&mut write, b"cpu,host=a#!~#usage", &[(1, 1.0), (2, 2.0)]);
+        encode_floats(&mut write, b"cpu,host=a#!~#idle", &[(2, 8.0), (3, 7.0)]);
+        encode_floats(&mut write, b"cpu,host=b#!~#usage", &[(1, 3.0)]);
+        encode_floats(&mut write, b"mem#!~#free", &[(1, 10.0), (5, 11.0)]);
+        let mut first = encode_entry(1, &write);
+
+        // host=b is deleted entirely, and the second value of mem is deleted
+        // by range
+        first.extend(encode_entry(2, b"cpu,host=b"));
+        let mut delete_range = vec![];
+        delete_range.extend_from_slice(&4_i64.to_be_bytes());
+        delete_range.extend_from_slice(&6_i64.to_be_bytes());
+        delete_range.extend_from_slice(&11_u32.to_be_bytes());
+        delete_range.extend_from_slice(b"mem#!~#free");
+        first.extend(encode_entry(3, &delete_range));
+
+        // a later segment overwrites a value
+        let mut write = vec![];
+        encode_floats(&mut write, b"cpu,host=a#!~#usage", &[(2, 4.0)]);
+        let second = encode_entry(1, &write);
+
+        let paths = vec![dir.path().join("_00001.wal"), dir.path().join("_00002.wal")];
+        std::fs::write(&paths[0], first).unwrap();
+        std::fs::write(&paths[1], second).unwrap();
+
+        let tables = collect(|emit| convert_wal_segments(&paths, usize::MAX, emit));
+        assert_eq!(tables.len(), 2);
+
+        let (name, cpu) = &tables[0];
+        assert_eq!(name, "cpu");
+        assert_batches_eq!(
+            &[
+                "+------+------+--------------------------------+-------+",
+                "| host | idle | time                           | usage |",
+                "+------+------+--------------------------------+-------+",
+                "| a    |      | 1970-01-01T00:00:00.000000001Z | 1     |",
+                "| a    | 8    | 1970-01-01T00:00:00.000000002Z | 4     |",
+                "| a    | 7    | 1970-01-01T00:00:00.000000003Z |       |",
+                "+------+------+--------------------------------+-------+",
+            ],
+            &[cpu.to_arrow(Selection::All).unwrap()]
+        );
+
+        let (name, mem) = &tables[1];
+        assert_eq!(name, "mem");
+        assert_batches_eq!(
+            &[
+                "+------+--------------------------------+",
+                "| free | time                           |",
+                "+------+--------------------------------+",
+                "| 10   | 1970-01-01T00:00:00.000000001Z |",
+                "+------+--------------------------------+",
+            ],
+            &[mem.to_arrow(Selection::All).unwrap()]
+        );
+    }
+
+    #[test]
+    fn emit_break_stops_conversion() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut write = vec![];
+        encode_floats(&mut write, b"cpu#!~#usage", &[(1, 1.0)]);
+        encode_floats(&mut write, b"mem#!~#free", &[(1, 1.0)]);
+        let path = dir.path().join("_00001.wal");
+        std::fs::write(&path, encode_entry(1, &write)).unwrap();
+
+        let mut emitted = 0;
+        let flow = convert_wal_segments(&[path], 1, |_, _| {
+            emitted += 1;
+            ControlFlow::Break(())
+        })
+        .unwrap();
+        assert!(flow.is_break());
+        assert_eq!(emitted, 1);
+    }
+
+    #[test]
+    fn test_valid_mask() {
+        assert_eq!(valid_mask::<u8>(&[]), Vec::<u8>::new());
+        assert_eq!(
+            valid_mask(&[
+                Some(1),
+                None,
+                Some(3),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(9)
+            ]),
+            vec![0b0000_0101, 0b0000_0001]
+        );
+    }
+}
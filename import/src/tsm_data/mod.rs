@@ -0,0 +1,304 @@
+//! Import of the data of an InfluxDB 1.x or 2.x data directory, bypassing the
+//! router and ingester.
+//!
+//! TSM files and WAL segments are converted into one table per measurement
+//! on a blocking thread. Each table is written to parquet files, one per
+//! partition, which are registered in the catalog directly.
+//!
+//! Every TSM file, and the WAL of every shard, is imported with its own
+//! sequence number, in the order of [`DataFiles`]. Newer files of a shard,
+//! and its WAL, therefore take precedence over older ones when the data is
+//! deduplicated.
+//!
+//! These sequence numbers can't be ordered against those of data written
+//! through the ingester, so the import refuses to write to partitions that
+//! already exist, or to time ranges of a table with deletes. Data written to
+//! the imported tables and time ranges while the import runs has no defined
+//! order relative to the imported data.
+
+pub mod convert;
+pub mod persist;
+
+use self::{
+    convert::{convert_tsm_file, convert_wal_segments, ConvertError},
+    persist::{PersistError, Persister, ShardLookup},
+};
+use data_types::{ParquetFile, SequenceNumber};
+use std::{
+    collections::BTreeMap,
+    fs,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Error)]
+pub enum TsmDataError {
+    #[error("Error reading directory {}: {source}", path.display())]
+    ReadDir {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error(
+        "{} holds deletes that have not been compacted yet, which can't be imported; \
+        wait for the shard to be fully compacted",
+        path.display()
+    )]
+    Tombstones { path: PathBuf },
+
+    #[error("Error converting data: {0}")]
+    Convert(#[from] ConvertError),
+
+    #[error("Error persisting data: {0}")]
+    Persist(#[from] PersistError),
+
+    #[error("Converter failed: {0}")]
+    Converter(#[from] tokio::task::JoinError),
+}
+
+/// The files of a data directory, in the order they are imported.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DataFiles {
+    /// TSM files, ordered by path, and so by generation within a shard.
+    pub tsm_files: Vec<PathBuf>,
+
+    /// The WAL segments of each shard, oldest first.
+    pub wal_segments: Vec<Vec<PathBuf>>,
+}
+
+impl DataFiles {
+    pub fn is_empty(&self) -> bool {
+        self.tsm_files.is_empty() && self.wal_segments.is_empty()
+    }
+}
+
+/// Finds the TSM files and WAL segments below `dir`.
+///
+/// Shards with tombstone files are rejected, as the deletes they record
+/// have not been applied to the TSM files yet.
+pub fn find_data_files(dir: &Path) -> Result<DataFiles, TsmDataError> {
+    let mut tsm_files = vec![];
+    let mut wal_segments: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new();
+    visit(dir, &mut |path| {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("tsm") => tsm_files.push(path),
+            Some("wal") => {
+                let shard = path.parent().map(Path::to_path_buf).unwrap_or_default();
+                wal_segments.entry(shard).or_default().push(path);
+            }
+            Some("tombstone") => return Err(TsmDataError::Tombstones { path }),
+            _ => {}
+        }
+        Ok(())
+    })?;
+
+    Ok(DataFiles {
+        tsm_files,
+        wal_segments: wal_segments.into_values().collect(),
+    })
+}
+
+// Calls `f` for every file below `dir`, in path order.
+fn visit<F>(dir: &Path, f: &mut F) -> Result<(), TsmDataError>
+where
+    F: FnMut(PathBuf) -> Result<(), TsmDataError>,
+{
+    let read_dir_error = |source| TsmDataError::ReadDir {
+        path: dir.to_path_buf(),
+        source,
+    };
+
+    let mut entries = fs::read_dir(dir)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(read_dir_error)?;
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            visit(&path, f)?;
+        } else {
+            f(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Configuration of a data import.
+#[derive(Debug, Clone, Copy)]
+pub struct ImportDataConfig {
+    /// Number of rows of a measurement after which they are written to
+    /// parquet files, bounding the memory used by the import.
+    pub max_rows_per_batch: usize,
+}
+
+impl Default for ImportDataConfig {
+    fn default() -> Self {
+        Self {
+            max_rows_per_batch: 1_000_000,
+        }
+    }
+}
+
+/// Progress of an import, reported via the callback passed to
+/// [`import_data`].
+#[derive(Debug)]
+pub enum Progress<'a> {
+    /// Started converting a TSM file, or the WAL segments of a shard.
+    SourceStarted { path: &'a Path },
+
+    /// A parquet file was written and added to the catalog.
+    FileWritten {
+        table_name: &'a str,
+        file: &'a ParquetFile,
+    },
+
+    /// All data of a TSM file, or the WAL of a shard, was imported.
+    SourceFinished { path: &'a Path, rows: usize },
+}
+
+/// Summary of an import.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub sources: usize,
+    pub rows: usize,
+    pub parquet_files: usize,
+}
+
+#[derive(Debug)]
+enum Source {
+    Tsm(PathBuf),
+    Wal(Vec<PathBuf>),
+}
+
+impl Source {
+    // The TSM file, or the shard directory of the WAL segments.
+    fn path(&self) -> PathBuf {
+        match self {
+            Self::Tsm(path) => path.clone(),
+            Self::Wal(segments) => segments
+                .first()
+                .and_then(|p| p.parent())
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Imports all `files`, in order, into the namespace of `persister`.
+pub async fn import_data<S, F>(
+    files: DataFiles,
+    persister: &mut Persister<S>,
+    config: &ImportDataConfig,
+    mut progress: F,
+) -> Result<ImportSummary, TsmDataError>
+where
+    S: ShardLookup,
+    F: FnMut(Progress<'_>),
+{
+    let sources = files
+        .tsm_files
+        .into_iter()
+        .map(Source::Tsm)
+        .chain(files.wal_segments.into_iter().map(Source::Wal));
+
+    let mut summary = ImportSummary::default();
+    for (idx, source) in sources.enumerate() {
+        let path = source.path();
+        let max_sequence_number = SequenceNumber::new(idx as i64 + 1);
+        progress(Progress::SourceStarted { path: &path });
+
+        // Bound the number of converted tables waiting to be written.
+        let (tx, mut rx) = mpsc::channel(1);
+        let max_rows = config.max_rows_per_batch;
+        let converter = tokio::task::spawn_blocking(move || {
+            // Stop converting once the receiver is gone, because writing a
+            // table failed.
+            let emit = |table_name, batch| match tx.blocking_send((table_name, batch)) {
+                Ok(()) => ControlFlow::Continue(()),
+                Err(_) => ControlFlow::Break(()),
+            };
+            // Stopping early is only reported to the caller as a persist
+            // error, so the returned `ControlFlow` is of no interest.
+            match source {
+                Source::Tsm(path) => convert_tsm_file(&path, max_rows, emit).map(|_| ()),
+                Source::Wal(segments) => {
+                    convert_wal_segments(&segments, max_rows, emit).map(|_| ())
+                }
+            }
+        });
+
+        let mut rows = 0;
+        while let Some((table_name, batch)) = rx.recv().await {
+            let files = persister
+                .persist(&table_name, &batch, max_sequence_number)
+                .await?;
+
+            rows += batch.rows();
+            summary.parquet_files += files.len();
+            for file in &files {
+                progress(Progress::FileWritten {
+                    table_name: &table_name,
+                    file,
+                });
+            }
+        }
+        converter.await??;
+
+        summary.sources += 1;
+        summary.rows += rows;
+        progress(Progress::SourceFinished { path: &path, rows });
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let touch = |path: &str| {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, b"").unwrap();
+            path
+        };
+
+        let tsm_b = touch("data/db/autogen/2/000000001-000000001.tsm");
+        let tsm_a2 = touch("data/db/autogen/1/000000002-000000001.tsm");
+        let tsm_a1 = touch("data/db/autogen/1/000000001-000000002.tsm");
+        touch("data/db/autogen/1/fields.idx");
+        touch("data/db/_series/00/0000");
+        let wal_b = touch("wal/db/autogen/2/_00001.wal");
+        let wal_a2 = touch("wal/db/autogen/1/_00002.wal");
+        let wal_a1 = touch("wal/db/autogen/1/_00001.wal");
+
+        let files = find_data_files(dir.path()).unwrap();
+        assert_eq!(
+            files,
+            DataFiles {
+                tsm_files: vec![tsm_a1, tsm_a2, tsm_b],
+                wal_segments: vec![vec![wal_a1, wal_a2], vec![wal_b]],
+            }
+        );
+
+        let tombstone = touch("data/db/autogen/2/000000001-000000001.tombstone");
+        let err = find_data_files(dir.path()).unwrap_err();
+        assert!(matches!(err, TsmDataError::Tombstones { path } if path == tombstone));
+    }
+
+    #[test]
+    fn find_files_missing_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = find_data_files(&dir.path().join("missing")).unwrap_err();
+        assert!(matches!(err, TsmDataError::ReadDir { .. }));
+    }
+}
@@ -0,0 +1,604 @@
+//! Writing converted tables to parquet files and registering them in the
+//! catalog, without going through the router and ingester.
+use crate::aggregate_tsm_schema::update_catalog::generated_types::{
+    shard_service_client::ShardServiceClient, MapToShardRequest,
+};
+use arrow::{
+    compute::{lexsort_to_indices, take, SortColumn},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use data_types::{
+    CompactionLevel, NamespaceSchema, ParquetFile, PartitionId, PartitionKey, PartitionTemplate,
+    SequenceNumber, ShardId, Statistics, TableSchema, TemplatePart, Timestamp,
+};
+use influxdb_iox_client::connection::Connection;
+use iox_catalog::{
+    interface::{get_schema_by_name, Catalog},
+    validate_or_insert_schema, TableScopedError,
+};
+use iox_time::TimeProvider;
+use mutable_batch::{MutableBatch, PartitionWrite, WritePayload};
+use object_store::DynObjectStore;
+use parquet_file::{
+    metadata::IoxMetadata,
+    storage::{ParquetStorage, UploadError},
+};
+use schema::{
+    selection::Selection,
+    sort::{adjust_sort_key_columns, compute_sort_key, SortKey},
+    TIME_COLUMN_NAME,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::DerefMut,
+    sync::Arc,
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum PersistError {
+    #[error("No namespace named {0} in Catalog")]
+    NamespaceNotFound(String),
+
+    #[error("Error returned from the Catalog: {0}")]
+    Catalog(#[from] iox_catalog::interface::Error),
+
+    #[error("Schema conflict: {0}")]
+    Schema(#[from] TableScopedError),
+
+    #[error("Error fetching shard ID from shard service: {0}")]
+    ShardService(#[from] tonic::Status),
+
+    #[error("Error converting data: {0}")]
+    Batch(#[from] mutable_batch::Error),
+
+    #[error("Error sorting data: {0}")]
+    Sort(#[from] ArrowError),
+
+    #[error("Error writing parquet file: {0}")]
+    Upload(#[from] UploadError),
+
+    #[error(
+        "Partition {partition_key} of table {table_name} already exists; \
+        data can only be imported into new partitions"
+    )]
+    PartitionExists {
+        table_name: String,
+        partition_key: PartitionKey,
+    },
+
+    #[error(
+        "Table {table_name} has deletes overlapping the data of partition {partition_key}, \
+        which would apply to the imported data"
+    )]
+    Tombstones {
+        table_name: String,
+        partition_key: PartitionKey,
+    },
+}
+
+/// Determines the shard the data of a table is written to.
+#[async_trait::async_trait]
+pub trait ShardLookup: Send {
+    async fn shard_for_table(
+        &mut self,
+        namespace_name: &str,
+        table_name: &str,
+    ) -> Result<ShardId, tonic::Status>;
+}
+
+/// Asks the router, so that imported data ends up in the same shard as
+/// data written to the table.
+#[async_trait::async_trait]
+impl ShardLookup for ShardServiceClient<Connection> {
+    async fn shard_for_table(
+        &mut self,
+        namespace_name: &str,
+        table_name: &str,
+    ) -> Result<ShardId, tonic::Status> {
+        let response = self
+            .map_to_shard(tonic::Request::new(MapToShardRequest {
+                table_name: table_name.to_string(),
+                namespace_name: namespace_name.to_string(),
+            }))
+            .await?;
+        Ok(ShardId::new(response.into_inner().shard_id))
+    }
+}
+
+/// Writes tables of a single namespace to parquet files in object storage
+/// and adds them to the catalog, as the ingester does when persisting.
+#[derive(Debug)]
+pub struct Persister<S> {
+    catalog: Arc<dyn Catalog>,
+    store: ParquetStorage,
+    time_provider: Arc<dyn TimeProvider>,
+    namespace_name: Arc<str>,
+    schema: NamespaceSchema,
+    shard_lookup: S,
+    shards: HashMap<String, ShardId>,
+    partition_template: PartitionTemplate,
+    /// Partitions created by this persister, which further files may be
+    /// added to.
+    partitions: HashSet<PartitionId>,
+}
+
+impl<S> Persister<S>
+where
+    S: ShardLookup,
+{
+    /// Create a [`Persister`] for the namespace `namespace_name`, which must
+    /// exist in the catalog.
+    pub async fn new(
+        catalog: Arc<dyn Catalog>,
+        object_store: Arc<DynObjectStore>,
+        time_provider: Arc<dyn TimeProvider>,
+        namespace_name: &str,
+        shard_lookup: S,
+    ) -> Result<Self, PersistError> {
+        let schema = {
+            let mut repos = catalog.repositories().await;
+            match get_schema_by_name(namespace_name, repos.deref_mut()).await {
+                Ok(schema) => schema,
+                Err(iox_catalog::interface::Error::NamespaceNotFoundByName { .. }) => {
+                    return Err(PersistError::NamespaceNotFound(namespace_name.to_string()))
+                }
+                Err(e) => return Err(e.into()),
+            }
+        };
+
+        Ok(Self {
+            catalog,
+            store: ParquetStorage::new(object_store),
+            time_provider,
+            namespace_name: namespace_name.into(),
+            schema,
+            shard_lookup,
+            shards: HashMap::new(),
+            // partition by day, as the router does
+            partition_template: PartitionTemplate {
+                parts: vec![TemplatePart::TimeFormat("%Y-%m-%d".to_owned())],
+            },
+            partitions: HashSet::new(),
+        })
+    }
+
+    /// Write the rows of `batch` to one parquet file per partition, adding
+    /// the table and any new columns to the namespace schema.
+    ///
+    /// `batch` must not contain more than one row per series and timestamp.
+    /// Files with a higher `max_sequence_number` take precedence over files
+    /// with a lower one when rows are deduplicated.
+    ///
+    /// The sequence numbers of imported files are not related to those of
+    /// data written through the ingester, so data is only written to
+    /// partitions that did not exist before this persister created them, and
+    /// never to time ranges of the table with deletes. Otherwise imported
+    /// and ingested rows would be deduplicated, and deletes applied, in an
+    /// arbitrary order.
+    pub async fn persist(
+        &mut self,
+        table_name: &str,
+        batch: &MutableBatch,
+        max_sequence_number: SequenceNumber,
+    ) -> Result<Vec<ParquetFile>, PersistError> {
+        {
+            let mut repos = self.catalog.repositories().await;
+            if let Some(schema) =
+                validate_or_insert_schema([(table_name, batch)], &self.schema, repos.deref_mut())
+                    .await?
+            {
+                self.schema = schema;
+            }
+        }
+        let table_schema = self
+            .schema
+            .tables
+            .get(table_name)
+            .cloned()
+            .expect("table was added to the schema");
+
+        let shard_id = match self.shards.get(table_name) {
+            Some(shard_id) => *shard_id,
+            None => {
+                let shard_id = self
+                    .shard_lookup
+                    .shard_for_table(&self.namespace_name, table_name)
+                    .await?;
+                self.shards.insert(table_name.to_string(), shard_id);
+                shard_id
+            }
+        };
+
+        let mut partitions: Vec<_> =
+            PartitionWrite::partition(table_name, batch, &self.partition_template)
+                .into_iter()
+                .collect();
+        partitions.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut files = Vec::with_capacity(partitions.len());
+        for (partition_key, write) in partitions {
+            let mut partition_batch = MutableBatch::new();
+            write.write_to_batch(&mut partition_batch)?;
+
+            let file = self
+                .persist_partition(
+                    table_name,
+                    &table_schema,
+                    shard_id,
+                    partition_key,
+                    &partition_batch,
+                    max_sequence_number,
+                )
+                .await?;
+            files.push(file);
+        }
+        Ok(files)
+    }
+
+    async fn persist_partition(
+        &mut self,
+        table_name: &str,
+        table_schema: &TableSchema,
+        shard_id: ShardId,
+        partition_key: PartitionKey,
+        batch: &MutableBatch,
+        max_sequence_number: SequenceNumber,
+    ) -> Result<ParquetFile, PersistError> {
+        let partition = {
+            let mut repos = self.catalog.repositories().await;
+
+            let exists = repos
+                .partitions()
+                .list_by_table_id(table_schema.id)
+                .await?
+                .iter()
+                .any(|p| {
+                    p.shard_id == shard_id
+                        && p.partition_key == partition_key
+                        && !self.partitions.contains(&p.id)
+                });
+            if exists {
+                return Err(PersistError::PartitionExists {
+                    table_name: table_name.to_string(),
+                    partition_key,
+                });
+            }
+
+            if let Statistics::I64(stats) = batch.column(TIME_COLUMN_NAME)?.stats() {
+                let (min, max) = stats.min.zip(stats.max).expect("time column has no nulls");
+                let tombstones = repos
+                    .tombstones()
+                    .list_tombstones_for_time_range(
+                        shard_id,
+                        table_schema.id,
+                        SequenceNumber::new(0),
+                        Timestamp::new(min),
+                        Timestamp::new(max),
+                    )
+                    .await?;
+                if !tombstones.is_empty() {
+                    return Err(PersistError::Tombstones {
+                        table_name: table_name.to_string(),
+                        partition_key,
+                    });
+                }
+            }
+
+            let partition = repos
+                .partitions()
+                .create_or_get(partition_key, shard_id, table_schema.id)
+                .await?;
+            self.partitions.insert(partition.id);
+            partition
+        };
+
+        let schema = batch.schema(Selection::All)?;
+        let record_batch = batch.to_arrow(Selection::All)?;
+
+        // Use the sort key of the partition, or compute one from the data for
+        // new partitions, exactly as the ingester does.
+        let (sort_key, sort_key_update) = match partition.sort_key() {
+            Some(sort_key) => adjust_sort_key_columns(&sort_key, &schema.primary_key()),
+            None => {
+                let sort_key = compute_sort_key(&schema, std::iter::once(&record_batch));
+                (sort_key.clone(), Some(sort_key))
+            }
+        };
+        let record_batch = sort_record_batch(record_batch, &sort_key)?;
+
+        let meta = IoxMetadata {
+            object_store_id: Uuid::new_v4(),
+            creation_timestamp: self.time_provider.now(),
+            namespace_id: self.schema.id,
+            namespace_name: Arc::clone(&self.namespace_name),
+            shard_id,
+            table_id: table_schema.id,
+            table_name: table_name.into(),
+            partition_id: partition.id,
+            partition_key: partition.partition_key.clone(),
+            max_sequence_number,
+            compaction_level: CompactionLevel::Initial,
+            sort_key: Some(sort_key),
+        };
+        let (md, file_size) = self
+            .store
+            .upload(
+                futures::stream::iter([Ok::<_, ArrowError>(record_batch)]),
+                &meta,
+            )
+            .await?;
+
+        let mut repos = self.catalog.repositories().await;
+
+        // The sort key must be updated before the file is added, so that the
+        // file is never observed with a sort key inconsistent with its
+        // partition's.
+        if let Some(sort_key) = sort_key_update {
+            let sort_key = sort_key.to_columns().collect::<Vec<_>>();
+            repos
+                .partitions()
+                .update_sort_key(partition.id, &sort_key)
+                .await?;
+        }
+
+        let params = meta.to_parquet_file(partition.id, file_size, &md, |name| {
+            table_schema.columns.get(name).expect("unknown column").id
+        });
+        Ok(repos.parquet_files().create(params).await?)
+    }
+}
+
+// Sorts the rows of `batch` by the columns of `sort_key`.
+fn sort_record_batch(batch: RecordBatch, sort_key: &SortKey) -> Result<RecordBatch, ArrowError> {
+    let schema = batch.schema();
+    let sort_columns: Vec<_> = sort_key
+        .iter()
+        .filter_map(|(name, options)| {
+            schema.column_with_name(name).map(|(idx, _)| SortColumn {
+                values: Arc::clone(batch.column(idx)),
+                options: Some(*options),
+            })
+        })
+        .collect();
+
+    let indices = lexsort_to_indices(&sort_columns, None)?;
+    let columns = batch
+        .columns()
+        .iter()
+        .map(|column| take(column.as_ref(), &indices, None))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    RecordBatch::try_new(schema, columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_types::ShardIndex;
+    use iox_catalog::mem::MemCatalog;
+    use iox_time::{MockProvider, Time};
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+    use object_store::memory::InMemory;
+
+    struct FixedShard(ShardId);
+
+    #[async_trait::async_trait]
+    impl ShardLookup for FixedShard {
+        async fn shard_for_table(&mut self, _: &str, _: &str) -> Result<ShardId, tonic::Status> {
+            Ok(self.0)
+        }
+    }
+
+    async fn catalog_with_shard() -> (Arc<dyn Catalog>, ShardId) {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+        let shard_id = {
+            let mut repos = catalog.repositories().await;
+            let topic = repos.topics().create_or_get("iox-shared").await.unwrap();
+            let query_pool = repos.query_pools().create_or_get("pool").await.unwrap();
+            repos
+                .namespaces()
+                .create("ns", "inf", topic.id, query_pool.id)
+                .await
+                .unwrap();
+            repos
+                .shards()
+                .create_or_get(&topic, ShardIndex::new(0))
+                .await
+                .unwrap()
+                .id
+        };
+        (catalog, shard_id)
+    }
+
+    async fn new_persister(catalog: &Arc<dyn Catalog>, shard_id: ShardId) -> Persister<FixedShard> {
+        Persister::new(
+            Arc::clone(catalog),
+            Arc::new(InMemory::new()),
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))),
+            "ns",
+            FixedShard(shard_id),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn persist_partitions() {
+        let (catalog, shard_id) = catalog_with_shard().await;
+        let mut persister = new_persister(&catalog, shard_id).await;
+
+        let (_, batch) = lp_to_mutable_batch(
+            "cpu,host=b usage=2 1\n\
+             cpu,host=a usage=1 2\n\
+             cpu,host=a usage=3 86400000000001",
+        );
+        let files = persister
+            .persist("cpu", &batch, SequenceNumber::new(1))
+            .await
+            .unwrap();
+
+        // one file per day
+        assert_eq!(files.len(), 2);
+        assert!(files.iter().all(|f| f.shard_id == shard_id
+            && f.max_sequence_number == SequenceNumber::new(1)
+            && f.compaction_level == CompactionLevel::Initial));
+        assert_eq!(files[0].row_count, 2);
+        assert_eq!(files[1].row_count, 1);
+
+        let mut repos = catalog.repositories().await;
+        let partition = repos
+            .partitions()
+            .get_by_id(files[0].partition_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(partition.partition_key.to_string(), "1970-01-01");
+        assert_eq!(partition.sort_key, vec!["host", "time"]);
+
+        // the table and its columns were added to the schema
+        let schema = get_schema_by_name("ns", repos.deref_mut()).await.unwrap();
+        let table = schema.tables.get("cpu").unwrap();
+        assert!(table.columns.contains_key("host"));
+        assert!(table.columns.contains_key("usage"));
+        assert_eq!(
+            repos
+                .parquet_files()
+                .list_by_table_not_to_delete(table.id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn existing_partitions() {
+        let (catalog, shard_id) = catalog_with_shard().await;
+        let mut persister = new_persister(&catalog, shard_id).await;
+
+        let (_, batch) = lp_to_mutable_batch("cpu,host=a usage=1 1");
+        persister
+            .persist("cpu", &batch, SequenceNumber::new(1))
+            .await
+            .unwrap();
+
+        // partitions created by the same import can be written to again
+        let (_, batch) = lp_to_mutable_batch("cpu,host=a usage=2 1");
+        persister
+            .persist("cpu", &batch, SequenceNumber::new(2))
+            .await
+            .unwrap();
+
+        // but not by another import
+        let mut persister = new_persister(&catalog, shard_id).await;
+        let err = persister
+            .persist("cpu", &batch, SequenceNumber::new(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            PersistError::PartitionExists { table_name, partition_key }
+                if table_name == "cpu" && partition_key.to_string() == "1970-01-01"
+        ));
+
+        // other partitions of the table can still be imported
+        let (_, batch) = lp_to_mutable_batch("cpu,host=a usage=1 86400000000001");
+        persister
+            .persist("cpu", &batch, SequenceNumber::new(1))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn overlapping_tombstones() {
+        let (catalog, shard_id) = catalog_with_shard().await;
+        let mut persister = new_persister(&catalog, shard_id).await;
+
+        let (_, batch) = lp_to_mutable_batch("cpu,host=a usage=1 86400000000001");
+        let files = persister
+            .persist("cpu", &batch, SequenceNumber::new(1))
+            .await
+            .unwrap();
+
+        catalog
+            .repositories()
+            .await
+            .tombstones()
+            .create_or_get(
+                files[0].table_id,
+                shard_id,
+                SequenceNumber::new(10),
+                Timestamp::new(0),
+                Timestamp::new(10),
+                "host=a",
+            )
+            .await
+            .unwrap();
+
+        let (_, batch) = lp_to_mutable_batch("cpu,host=a usage=1 5");
+        let err = persister
+            .persist("cpu", &batch, SequenceNumber::new(2))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            PersistError::Tombstones { table_name, partition_key }
+                if table_name == "cpu" && partition_key.to_string() == "1970-01-01"
+        ));
+
+        // nothing was written to the partition
+        let partitions = catalog
+            .repositories()
+            .await
+            .partitions()
+            .list_by_table_id(files[0].table_id)
+            .await
+            .unwrap();
+        assert_eq!(partitions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn unknown_namespace() {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+        let err = Persister::new(
+            catalog,
+            Arc::new(InMemory::new()),
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))),
+            "missing",
+            FixedShard(ShardId::new(1)),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, PersistError::NamespaceNotFound(name) if name == "missing"));
+    }
+
+    #[test]
+    fn sort_by_sort_key() {
+        let (_, batch) = lp_to_mutable_batch(
+            "cpu,host=b usage=2 1\n\
+             cpu,host=a usage=1 2\n\
+             cpu,host=a usage=3 1",
+        );
+        let batch = batch.to_arrow(Selection::All).unwrap();
+        let sort_key = SortKey::from_columns(["host", "time"]);
+
+        let sorted = sort_record_batch(batch, &sort_key).unwrap();
+        arrow_util::assert_batches_eq!(
+            &[
+                "+------+--------------------------------+-------+",
+                "| host | time                           | usage |",
+                "+------+--------------------------------+-------+",
+                "| a    | 1970-01-01T00:00:00.000000001Z | 3     |",
+                "| a    | 1970-01-01T00:00:00.000000002Z | 1     |",
+                "| b    | 1970-01-01T00:00:00.000000001Z | 2     |",
+                "+------+--------------------------------+-------+",
+            ],
+            &[sorted]
+        );
+    }
+}
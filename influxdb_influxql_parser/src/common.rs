@@ -84,7 +84,7 @@ pub fn measurement_name_expression(i: &str) -> ParseResult<&str, MeasurementName
 }
 
 /// Parse an unsigned integer.
-fn unsigned_number(i: &str) -> ParseResult<&str, u64> {
+pub fn unsigned_number(i: &str) -> ParseResult<&str, u64> {
     map_fail("unable to parse unsigned integer", digit1, &str::parse)(i)
 }
 
@@ -0,0 +1,105 @@
+//! Parse a [`KILL QUERY`][sql] statement.
+//!
+//! [sql]: https://docs.influxdata.com/influxdb/v1.8/troubleshooting/query_management/#kill-query
+
+use crate::common::unsigned_number;
+use crate::identifier::{identifier, Identifier};
+use crate::internal::{expect, ParseResult};
+use nom::bytes::complete::tag_no_case;
+use nom::character::complete::{multispace0, multispace1};
+use nom::combinator::{map, opt};
+use nom::sequence::{pair, preceded, tuple};
+use std::fmt::{Display, Formatter};
+
+/// Represents a `KILL QUERY` statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KillQueryStatement {
+    /// The ID of the query to kill.
+    pub id: u64,
+
+    /// The host running the query, if specified.
+    pub host: Option<Identifier>,
+}
+
+impl Display for KillQueryStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "KILL QUERY {}", self.id)?;
+        if let Some(host) = &self.host {
+            write!(f, " ON {}", host)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a `KILL QUERY` statement.
+pub fn kill_statement(i: &str) -> ParseResult<&str, KillQueryStatement> {
+    // kill ::= "KILL" "QUERY" query_id ( "ON" host )?
+    preceded(
+        pair(tag_no_case("KILL"), multispace1),
+        expect(
+            "invalid KILL statement, must be followed by QUERY",
+            kill_query,
+        ),
+    )(i)
+}
+
+fn kill_query(i: &str) -> ParseResult<&str, KillQueryStatement> {
+    preceded(
+        pair(tag_no_case("QUERY"), multispace1),
+        map(
+            tuple((
+                expect(
+                    "invalid KILL QUERY statement, expected unsigned integer",
+                    unsigned_number,
+                ),
+                opt(preceded(
+                    tuple((multispace0, tag_no_case("ON"), multispace1)),
+                    expect("invalid ON clause, expected identifier", identifier),
+                )),
+            )),
+            |(id, host)| KillQueryStatement { id, host },
+        ),
+    )(i)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::assert_expect_error;
+
+    #[test]
+    fn test_kill_statement() {
+        let (_, got) = kill_statement("KILL QUERY 36").unwrap();
+        assert_eq!(got, KillQueryStatement { id: 36, host: None });
+        // validate Display
+        assert_eq!(format!("{}", got), "KILL QUERY 36");
+
+        let (_, got) = kill_statement("kill query 36 ON \"localhost:8088\"").unwrap();
+        assert_eq!(
+            got,
+            KillQueryStatement {
+                id: 36,
+                host: Some("localhost:8088".into())
+            }
+        );
+        assert_eq!(format!("{}", got), "KILL QUERY 36 ON \"localhost:8088\"");
+
+        // Fallible cases
+        assert_expect_error!(
+            kill_statement("KILL foo"),
+            "invalid KILL statement, must be followed by QUERY"
+        );
+        assert_expect_error!(
+            kill_statement("KILL QUERY foo"),
+            "invalid KILL QUERY statement, expected unsigned integer"
+        );
+        assert_expect_error!(
+            kill_statement("KILL QUERY -1"),
+            "invalid KILL QUERY statement, expected unsigned integer"
+        );
+        assert_expect_error!(
+            kill_statement("KILL QUERY 1 ON 'host'"),
+            "invalid ON clause, expected identifier"
+        );
+    }
+}
@@ -31,6 +31,7 @@ mod expression;
 mod identifier;
 mod internal;
 mod keywords;
+mod kill;
 mod literal;
 mod parameter;
 mod show;
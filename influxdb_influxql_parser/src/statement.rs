@@ -1,6 +1,7 @@
 use crate::delete::{delete_statement, DeleteStatement};
 use crate::drop::{drop_statement, DropMeasurementStatement};
 use crate::internal::ParseResult;
+use crate::kill::{kill_statement, KillQueryStatement};
 use crate::show::{show_statement, ShowDatabasesStatement};
 use crate::show_field_keys::ShowFieldKeysStatement;
 use crate::show_measurements::ShowMeasurementsStatement;
@@ -18,6 +19,8 @@ pub enum Statement {
     Delete(Box<DeleteStatement>),
     /// Represents a `DROP MEASUREMENT` statement.
     DropMeasurement(Box<DropMeasurementStatement>),
+    /// Represents a `KILL QUERY` statement.
+    KillQuery(Box<KillQueryStatement>),
     /// Represents a `SHOW DATABASES` statement.
     ShowDatabases(Box<ShowDatabasesStatement>),
     /// Represents a `SHOW MEASUREMENTS` statement.
@@ -37,6 +40,7 @@ impl Display for Statement {
         match self {
             Self::Delete(s) => Display::fmt(s, f)?,
             Self::DropMeasurement(s) => Display::fmt(s, f)?,
+            Self::KillQuery(s) => Display::fmt(s, f)?,
             Self::ShowDatabases(s) => Display::fmt(s, f)?,
             Self::ShowMeasurements(s) => Display::fmt(s, f)?,
             Self::ShowRetentionPolicies(s) => Display::fmt(s, f)?,
@@ -54,6 +58,7 @@ pub fn statement(i: &str) -> ParseResult<&str, Statement> {
     alt((
         map(delete_statement, |s| Statement::Delete(Box::new(s))),
         map(drop_statement, |s| Statement::DropMeasurement(Box::new(s))),
+        map(kill_statement, |s| Statement::KillQuery(Box::new(s))),
         show_statement,
     ))(i)
 }
@@ -72,6 +77,9 @@ mod test {
         // drop_statement combinator
         statement("DROP MEASUREMENT foo").unwrap();
 
+        // kill_statement combinator
+        statement("KILL QUERY 1").unwrap();
+
         // show_statement combinator
         statement("SHOW TAG KEYS").unwrap();
     }
@@ -5,6 +5,8 @@ use std::sync::Arc;
 use clap_blocks::catalog_dsn::CatalogDsnConfig;
 use thiserror::Error;
 
+mod namespace;
+mod token;
 mod topic;
 
 #[allow(clippy::enum_variant_names)]
@@ -13,6 +15,12 @@ pub enum Error {
     #[error("Error in topic subcommand: {0}")]
     Topic(#[from] topic::Error),
 
+    #[error("Error in namespace subcommand: {0}")]
+    Namespace(#[from] namespace::Error),
+
+    #[error("Error in token subcommand: {0}")]
+    Token(#[from] token::Error),
+
     #[error("Catalog error: {0}")]
     Catalog(#[from] iox_catalog::interface::Error),
 
@@ -42,6 +50,12 @@ enum Command {
 
     /// Manage topic
     Topic(topic::Config),
+
+    /// Manage namespace
+    Namespace(namespace::Config),
+
+    /// Manage namespace API tokens
+    Token(token::Config),
 }
 
 pub async fn command(config: Config) -> Result<(), Error> {
@@ -55,6 +69,12 @@ pub async fn command(config: Config) -> Result<(), Error> {
         Command::Topic(config) => {
             topic::command(config).await?;
         }
+        Command::Namespace(config) => {
+            namespace::command(config).await?;
+        }
+        Command::Token(config) => {
+            token::command(config).await?;
+        }
     }
 
     Ok(())
@@ -0,0 +1,210 @@
+//! This module implements the `catalog namespace` CLI subcommand
+
+use std::sync::Arc;
+
+use data_types::{
+    FieldCoercionPolicy, NamespacePersistencePolicy, NamespaceQueryLimits, NamespaceWriteLimits,
+};
+use thiserror::Error;
+
+use clap_blocks::catalog_dsn::CatalogDsnConfig;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error updating catalog: {0}")]
+    UpdateCatalogError(#[from] iox_catalog::interface::Error),
+
+    #[error("Catalog DSN error: {0}")]
+    CatalogDsn(#[from] clap_blocks::catalog_dsn::Error),
+}
+
+/// Manage namespaces
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// Set the limits on the resources used by the queries of a namespace.
+///
+/// Limits that are not given are removed, so that only the limits of the
+/// querier apply.
+#[derive(Debug, clap::Parser)]
+struct QueryLimits {
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    /// The name of the namespace
+    #[clap(action)]
+    namespace: String,
+
+    /// Maximum number of queries of the namespace running concurrently on a
+    /// querier.
+    #[clap(long, action)]
+    max_concurrent_queries: Option<i32>,
+
+    /// Maximum memory, in bytes, the operators of a single query may reserve.
+    #[clap(long, action)]
+    max_query_memory_bytes: Option<i64>,
+
+    /// Maximum estimated size, in bytes, of the data a single query may scan.
+    #[clap(long, action)]
+    max_query_scanned_bytes: Option<i64>,
+
+    /// Maximum wall-clock duration of a single query, in seconds.
+    #[clap(long, action)]
+    max_query_duration_seconds: Option<i64>,
+}
+
+/// Set the thresholds that trigger the persistence of the data the
+/// ingesters buffer for a namespace.
+///
+/// Thresholds that are not given are removed, so that only the thresholds of
+/// the ingester apply. The memory limits of the ingester always apply.
+#[derive(Debug, clap::Parser)]
+struct PersistencePolicy {
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    /// The name of the namespace
+    #[clap(action)]
+    namespace: String,
+
+    /// Age, in seconds, of the first write into a partition after which the
+    /// partition is persisted.
+    #[clap(long, action)]
+    partition_age_threshold_seconds: Option<i64>,
+
+    /// Time, in seconds, since the last write into a partition after which
+    /// the partition is persisted.
+    #[clap(long, action)]
+    partition_cold_threshold_seconds: Option<i64>,
+
+    /// Estimated size, in bytes, above which a partition is persisted.
+    #[clap(long, action)]
+    partition_size_threshold_bytes: Option<i64>,
+
+    /// Number of rows at which a partition is persisted.
+    #[clap(long, action)]
+    partition_row_max: Option<i64>,
+}
+
+/// Set the limits on the rate of the writes each router accepts for a
+/// namespace.
+///
+/// Limits that are not given are removed, so that the writes are not
+/// throttled. Routers pick up changed limits periodically.
+#[derive(Debug, clap::Parser)]
+struct WriteLimits {
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    /// The name of the namespace
+    #[clap(action)]
+    namespace: String,
+
+    /// Number of lines per second a router accepts for the namespace.
+    #[clap(long, action)]
+    write_lines_per_second: Option<i64>,
+
+    /// Number of bytes per second, measured as the in-memory size of the
+    /// decoded writes, a router accepts for the namespace.
+    #[clap(long, action)]
+    write_bytes_per_second: Option<i64>,
+}
+
+/// Set how routers resolve writes to a field column whose type conflicts
+/// with the type of the column.
+///
+/// Routers pick up a changed policy on the next conflicting write.
+#[derive(Debug, clap::Parser)]
+struct FieldCoercion {
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    /// The name of the namespace
+    #[clap(action)]
+    namespace: String,
+
+    /// The policy: "reject" the write, losslessly "widen" integer values to
+    /// float and unsigned values to integer, additionally "stringify" values
+    /// written to string columns, or additionally "drop" any other
+    /// conflicting field from the write.
+    #[clap(action)]
+    policy: FieldCoercionPolicy,
+}
+
+/// All possible subcommands for namespace
+#[derive(Debug, clap::Parser)]
+enum Command {
+    QueryLimits(QueryLimits),
+    PersistencePolicy(PersistencePolicy),
+    WriteLimits(WriteLimits),
+    FieldCoercion(FieldCoercion),
+}
+
+pub async fn command(config: Config) -> Result<(), Error> {
+    match config.command {
+        Command::QueryLimits(update) => {
+            let metrics = Arc::new(metric::Registry::new());
+            let catalog = update.catalog_dsn.get_catalog("cli", metrics).await?;
+            let mut repos = catalog.repositories().await;
+            let limits = NamespaceQueryLimits {
+                max_concurrent_queries: update.max_concurrent_queries,
+                max_query_memory_bytes: update.max_query_memory_bytes,
+                max_query_scanned_bytes: update.max_query_scanned_bytes,
+                max_query_duration_seconds: update.max_query_duration_seconds,
+            };
+            let namespace = repos
+                .namespaces()
+                .update_query_limits(&update.namespace, limits)
+                .await?;
+            println!("{:?}", namespace.query_limits);
+            Ok(())
+        }
+        Command::PersistencePolicy(update) => {
+            let metrics = Arc::new(metric::Registry::new());
+            let catalog = update.catalog_dsn.get_catalog("cli", metrics).await?;
+            let mut repos = catalog.repositories().await;
+            let policy = NamespacePersistencePolicy {
+                partition_age_threshold_seconds: update.partition_age_threshold_seconds,
+                partition_cold_threshold_seconds: update.partition_cold_threshold_seconds,
+                partition_size_threshold_bytes: update.partition_size_threshold_bytes,
+                partition_row_max: update.partition_row_max,
+            };
+            let namespace = repos
+                .namespaces()
+                .update_persistence_policy(&update.namespace, policy)
+                .await?;
+            println!("{:?}", namespace.persistence_policy);
+            Ok(())
+        }
+        Command::WriteLimits(update) => {
+            let metrics = Arc::new(metric::Registry::new());
+            let catalog = update.catalog_dsn.get_catalog("cli", metrics).await?;
+            let mut repos = catalog.repositories().await;
+            let limits = NamespaceWriteLimits {
+                write_lines_per_second: update.write_lines_per_second,
+                write_bytes_per_second: update.write_bytes_per_second,
+            };
+            let namespace = repos
+                .namespaces()
+                .update_write_limits(&update.namespace, limits)
+                .await?;
+            println!("{:?}", namespace.write_limits);
+            Ok(())
+        }
+        Command::FieldCoercion(update) => {
+            let metrics = Arc::new(metric::Registry::new());
+            let catalog = update.catalog_dsn.get_catalog("cli", metrics).await?;
+            let mut repos = catalog.repositories().await;
+            let namespace = repos
+                .namespaces()
+                .update_field_coercion_policy(&update.namespace, update.policy)
+                .await?;
+            println!("{}", namespace.field_coercion_policy);
+            Ok(())
+        }
+    }
+}
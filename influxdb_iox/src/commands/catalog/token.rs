@@ -0,0 +1,170 @@
+//! This module implements the `catalog token` CLI subcommand
+
+use std::sync::Arc;
+
+use data_types::NamespaceTokenId;
+use ioxd_common::authz::hash_token;
+use thiserror::Error;
+
+use clap_blocks::catalog_dsn::CatalogDsnConfig;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error updating catalog: {0}")]
+    UpdateCatalogError(#[from] iox_catalog::interface::Error),
+
+    #[error("Catalog DSN error: {0}")]
+    CatalogDsn(#[from] clap_blocks::catalog_dsn::Error),
+
+    #[error("Namespace {0} not found")]
+    NamespaceNotFound(String),
+
+    #[error("Error reading token from standard input: {0}")]
+    ReadToken(#[from] std::io::Error),
+
+    #[error("No token given via {} or standard input", TOKEN_ENV)]
+    NoToken,
+}
+
+/// The environment variable the token to grant is read from.
+const TOKEN_ENV: &str = "INFLUXDB_IOX_GRANT_TOKEN";
+
+/// Manage the API tokens granting access to namespaces.
+///
+/// Servers only authorize requests against these tokens when started with
+/// `--authz-catalog-tokens`.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// Grant a token access to a namespace, replacing any previous grant of the
+/// token for that namespace.
+///
+/// The token is read from the `INFLUXDB_IOX_GRANT_TOKEN` environment variable
+/// or, if it is not set, from the first line of standard input, so that it
+/// does not show up in the process list or the shell history. Only its hash
+/// is stored in the catalog.
+#[derive(Debug, clap::Parser)]
+struct Grant {
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    /// The name of the namespace
+    #[clap(action)]
+    namespace: String,
+
+    /// Allow the token to query the namespace.
+    #[clap(long, action)]
+    read: bool,
+
+    /// Allow the token to write to and delete from the namespace.
+    #[clap(long, action)]
+    write: bool,
+}
+
+/// List the token grants of a namespace.
+#[derive(Debug, clap::Parser)]
+struct List {
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    /// The name of the namespace
+    #[clap(action)]
+    namespace: String,
+}
+
+/// Revoke a token grant.
+#[derive(Debug, clap::Parser)]
+struct Revoke {
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    /// The id of the grant, as printed by `grant` and `list`
+    #[clap(action)]
+    id: i64,
+}
+
+/// All possible subcommands for token
+#[derive(Debug, clap::Parser)]
+enum Command {
+    Grant(Grant),
+    List(List),
+    Revoke(Revoke),
+}
+
+pub async fn command(config: Config) -> Result<(), Error> {
+    match config.command {
+        Command::Grant(grant) => {
+            let secret = read_token()?;
+            let metrics = Arc::new(metric::Registry::new());
+            let catalog = grant.catalog_dsn.get_catalog("cli", metrics).await?;
+            let mut repos = catalog.repositories().await;
+            let namespace = repos
+                .namespaces()
+                .get_by_name(&grant.namespace)
+                .await?
+                .ok_or(Error::NamespaceNotFound(grant.namespace))?;
+            let token = repos
+                .namespace_tokens()
+                .create_or_update(
+                    &hash_token(secret.as_bytes()),
+                    namespace.id,
+                    grant.read,
+                    grant.write,
+                )
+                .await?;
+            println!("{:?}", token);
+            Ok(())
+        }
+        Command::List(list) => {
+            let metrics = Arc::new(metric::Registry::new());
+            let catalog = list.catalog_dsn.get_catalog("cli", metrics).await?;
+            let mut repos = catalog.repositories().await;
+            let namespace = repos
+                .namespaces()
+                .get_by_name(&list.namespace)
+                .await?
+                .ok_or(Error::NamespaceNotFound(list.namespace))?;
+            let tokens = repos
+                .namespace_tokens()
+                .list_by_namespace_id(namespace.id)
+                .await?;
+            for token in tokens {
+                println!("{:?}", token);
+            }
+            Ok(())
+        }
+        Command::Revoke(revoke) => {
+            let metrics = Arc::new(metric::Registry::new());
+            let catalog = revoke.catalog_dsn.get_catalog("cli", metrics).await?;
+            let mut repos = catalog.repositories().await;
+            repos
+                .namespace_tokens()
+                .delete(NamespaceTokenId::new(revoke.id))
+                .await?;
+            println!("OK");
+            Ok(())
+        }
+    }
+}
+
+/// Reads the token to grant from [`TOKEN_ENV`], or else from the first line
+/// of standard input.
+fn read_token() -> Result<String, Error> {
+    let token = match std::env::var(TOKEN_ENV) {
+        Ok(token) => token,
+        Err(_) => {
+            let mut line = String::new();
+            std::io::stdin().read_line(&mut line)?;
+            line.trim_end_matches(&['\r', '\n'][..]).to_string()
+        }
+    };
+
+    if token.is_empty() {
+        return Err(Error::NoToken);
+    }
+    Ok(token)
+}
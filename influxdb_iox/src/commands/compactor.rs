@@ -1,8 +1,12 @@
+//! This module implements the `compactor` CLI command
+
 use clap_blocks::{
     catalog_dsn::CatalogDsnConfig,
     compactor::CompactorOnceConfig,
     object_store::{make_object_store, ObjectStoreConfig},
 };
+use futures::Future;
+use influxdb_iox_client::{compactor::Client as CompactorClient, connection::Connection};
 use iox_query::exec::Executor;
 use iox_time::{SystemProvider, TimeProvider};
 use ioxd_compactor::build_compactor_from_config;
@@ -39,9 +43,46 @@ pub enum Command {
         )]
         query_exec_thread_count: usize,
     },
+
+    /// List the partitions a running compactor skipped compacting, and why
+    ListSkipped,
+
+    /// Clear the skipped compaction of a partition so a running compactor retries it
+    ClearSkipped {
+        /// The ID of the partition
+        #[clap(action)]
+        partition_id: i64,
+    },
+
+    /// Queue a partition for full compaction in the next cycle of a running compactor
+    QueuePartition {
+        /// The ID of the partition
+        #[clap(action)]
+        partition_id: i64,
+    },
+
+    /// Queue all partitions of a table for full compaction in the next cycle of a running
+    /// compactor
+    QueueTable {
+        /// The namespace of the table
+        #[clap(action)]
+        namespace: String,
+
+        /// The name of the table
+        #[clap(action)]
+        table: String,
+    },
+
+    /// List the partitions a running compactor would select in its next cycle, with their
+    /// estimated memory
+    Candidates,
 }
 
-pub async fn command(config: Config) -> Result<()> {
+pub async fn command<C, CFut>(connection: C, config: Config) -> Result<()>
+where
+    C: Send + FnOnce() -> CFut,
+    CFut: Send + Future<Output = Connection>,
+{
     match config.command {
         Command::RunOnce {
             object_store_config,
@@ -82,6 +123,35 @@ pub async fn command(config: Config) -> Result<()> {
 
             compactor::handler::run_compactor_once(compactor).await;
         }
+        Command::ListSkipped => {
+            let mut client = CompactorClient::new(connection().await);
+            let skipped_compactions = client.list_skipped_compactions().await?;
+            println!("{}", serde_json::to_string_pretty(&skipped_compactions)?);
+        }
+        Command::ClearSkipped { partition_id } => {
+            let mut client = CompactorClient::new(connection().await);
+            match client.clear_skipped_compaction(partition_id).await? {
+                Some(skipped_compaction) => {
+                    println!("{}", serde_json::to_string_pretty(&skipped_compaction)?)
+                }
+                None => println!("Compacting partition {} was not skipped", partition_id),
+            }
+        }
+        Command::QueuePartition { partition_id } => {
+            let mut client = CompactorClient::new(connection().await);
+            client.queue_partition(partition_id).await?;
+            println!("Queued partition {} for full compaction", partition_id);
+        }
+        Command::QueueTable { namespace, table } => {
+            let mut client = CompactorClient::new(connection().await);
+            let partition_ids = client.queue_table(namespace, table).await?;
+            println!("{}", serde_json::to_string_pretty(&partition_ids)?);
+        }
+        Command::Candidates => {
+            let mut client = CompactorClient::new(connection().await);
+            let candidates = client.list_compaction_candidates().await?;
+            println!("{}", serde_json::to_string_pretty(&candidates)?);
+        }
     }
 
     Ok(())
@@ -101,6 +171,16 @@ pub enum Error {
 
     #[snafu(context(false))]
     Compacting { source: ioxd_compactor::Error },
+
+    #[snafu(context(false))]
+    #[snafu(display("JSON Serialization error: {}", source))]
+    Serde { source: serde_json::Error },
+
+    #[snafu(context(false))]
+    #[snafu(display("Client error: {}", source))]
+    Client {
+        source: influxdb_iox_client::error::Error,
+    },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
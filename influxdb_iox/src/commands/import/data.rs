@@ -0,0 +1,127 @@
+use std::{path::PathBuf, sync::Arc};
+
+use clap_blocks::{
+    catalog_dsn::CatalogDsnConfig,
+    object_store::{make_object_store, ObjectStoreConfig},
+};
+use influxdb_iox_client::connection::Connection;
+use iox_time::{SystemProvider, TimeProvider};
+use object_store::DynObjectStore;
+use object_store_metrics::ObjectStoreMetrics;
+use thiserror::Error;
+
+use import::{
+    aggregate_tsm_schema::update_catalog::generated_types::shard_service_client::ShardServiceClient,
+    tsm_data::{
+        find_data_files, import_data,
+        persist::{PersistError, Persister},
+        ImportDataConfig, Progress, TsmDataError,
+    },
+};
+
+// Possible errors from the data command
+#[derive(Debug, Error)]
+pub enum DataCommandError {
+    #[error("Cannot parse object store config: {0}")]
+    ObjectStoreParsing(#[from] clap_blocks::object_store::ParseError),
+
+    #[error("Catalog DSN error: {0}")]
+    CatalogDsn(#[from] clap_blocks::catalog_dsn::Error),
+
+    #[error("Error preparing import: {0}")]
+    Persister(#[from] PersistError),
+
+    #[error("No TSM or WAL files found in {}", .0.display())]
+    NoDataFiles(PathBuf),
+
+    #[error("Error importing data: {0}")]
+    Import(#[from] TsmDataError),
+}
+
+/// Import the TSM and WAL files of an InfluxDB 1.x or 2.x data directory,
+/// writing parquet files and registering them in the catalog directly.
+///
+/// The schema of the namespace should be imported with `import schema`
+/// first, so that conflicting field types are resolved beforehand.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(flatten)]
+    object_store: ObjectStoreConfig,
+
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    /// The namespace to import the data into. It must already exist.
+    #[clap(long)]
+    namespace: String,
+
+    /// Number of rows of a measurement that are converted before they are
+    /// written to parquet files. Bounds the memory used by the import.
+    #[clap(long, default_value = "1000000")]
+    max_rows_per_batch: usize,
+
+    /// The directory holding the `data` and `wal` directories of the
+    /// database (1.x) or the engine (2.x) to import.
+    #[clap(action)]
+    data_dir: PathBuf,
+}
+
+/// Entry-point for the data command
+pub async fn command(connection: Connection, config: Config) -> Result<(), DataCommandError> {
+    let time_provider = Arc::new(SystemProvider::new()) as Arc<dyn TimeProvider>;
+    let metrics = Arc::new(metric::Registry::default());
+
+    let object_store = make_object_store(&config.object_store)?;
+    // Decorate the object store with a metric recorder.
+    let object_store: Arc<DynObjectStore> = Arc::new(ObjectStoreMetrics::new(
+        object_store,
+        Arc::clone(&time_provider),
+        &*metrics,
+    ));
+
+    let catalog = config
+        .catalog_dsn
+        .get_catalog("import", Arc::clone(&metrics))
+        .await?;
+
+    let files = find_data_files(&config.data_dir)?;
+    if files.is_empty() {
+        return Err(DataCommandError::NoDataFiles(config.data_dir));
+    }
+
+    let mut persister = Persister::new(
+        catalog,
+        object_store,
+        time_provider,
+        &config.namespace,
+        ShardServiceClient::new(connection),
+    )
+    .await?;
+
+    let import_config = ImportDataConfig {
+        max_rows_per_batch: config.max_rows_per_batch,
+    };
+    let summary = import_data(
+        files,
+        &mut persister,
+        &import_config,
+        |progress| match progress {
+            Progress::SourceStarted { path } => eprintln!("Importing {}", path.display()),
+            Progress::FileWritten { table_name, file } => eprintln!(
+                "  wrote {} rows of {} to {}",
+                file.row_count, table_name, file.object_store_id
+            ),
+            Progress::SourceFinished { path, rows } => {
+                eprintln!("Imported {} rows from {}", rows, path.display())
+            }
+        },
+    )
+    .await?;
+
+    println!(
+        "Imported {} rows from {} sources into {} parquet files",
+        summary.rows, summary.sources, summary.parquet_files
+    );
+
+    Ok(())
+}
@@ -1,12 +1,16 @@
 use influxdb_iox_client::connection::Connection;
 use thiserror::Error;
 
+mod data;
 mod schema;
 
 #[derive(Debug, Error)]
 pub enum ImportError {
     #[error("Error in schema command: {0}")]
     SchemaError(#[from] schema::SchemaCommandError),
+
+    #[error("Error in data command: {0}")]
+    DataError(#[from] data::DataCommandError),
 }
 
 #[derive(Debug, clap::Parser)]
@@ -20,6 +24,9 @@ pub enum Command {
     /// Operations related to schema analysis.
     #[clap(subcommand)]
     Schema(Box<schema::Config>),
+
+    /// Import the data of TSM and WAL files.
+    Data(Box<data::Config>),
 }
 
 /// Handle variants of the schema command.
@@ -28,5 +35,8 @@ pub async fn command(connection: Connection, config: Config) -> Result<(), Impor
         Command::Schema(schema_config) => schema::command(connection, *schema_config)
             .await
             .map_err(ImportError::SchemaError),
+        Command::Data(data_config) => data::command(connection, *data_config)
+            .await
+            .map_err(ImportError::DataError),
     }
 }
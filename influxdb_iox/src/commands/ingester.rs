@@ -0,0 +1,121 @@
+//! This module implements the `ingester` CLI command
+
+use influxdb_iox_client::{
+    connection::Connection,
+    ingester::{self, generated_types::PersistRequest},
+};
+use thiserror::Error;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("JSON Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("Client error: {0}")]
+    ClientError(#[from] influxdb_iox_client::error::Error),
+}
+
+/// Administer an ingester
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// Persist buffered partitions now. Without any filter, all buffered
+/// partitions are persisted.
+#[derive(Debug, clap::Parser)]
+struct Persist {
+    /// Only persist the partitions of this namespace
+    #[clap(long, action)]
+    namespace: Option<String>,
+
+    /// Only persist the partitions of this table (requires --namespace)
+    #[clap(long, requires = "namespace", action)]
+    table: Option<String>,
+
+    /// Only persist the partition with this ID
+    #[clap(long, action)]
+    partition_id: Option<i64>,
+}
+
+/// Stop consuming writes from a shard
+#[derive(Debug, clap::Parser)]
+struct PauseShard {
+    /// The index of the shard
+    #[clap(action)]
+    shard_index: i32,
+}
+
+/// Resume consuming writes from a paused shard
+#[derive(Debug, clap::Parser)]
+struct ResumeShard {
+    /// The index of the shard
+    #[clap(action)]
+    shard_index: i32,
+}
+
+/// All possible subcommands for ingester
+#[derive(Debug, clap::Parser)]
+enum Command {
+    Persist(Persist),
+    PauseShard(PauseShard),
+    ResumeShard(ResumeShard),
+    /// List the partitions with buffered data and the paused shards
+    Partitions,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+    let mut client = ingester::Client::new(connection);
+
+    match config.command {
+        Command::Persist(persist) => {
+            let partition_ids = client
+                .persist(PersistRequest {
+                    namespace: persist.namespace,
+                    table: persist.table,
+                    partition_id: persist.partition_id,
+                })
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&partition_ids)?);
+        }
+        Command::PauseShard(pause) => {
+            client.pause_shard(pause.shard_index).await?;
+            println!("Paused shard {}", pause.shard_index);
+        }
+        Command::ResumeShard(resume) => {
+            client.resume_shard(resume.shard_index).await?;
+            println!("Resumed shard {}", resume.shard_index);
+        }
+        Command::Partitions => {
+            let partitions = client.list_buffered_partitions().await?;
+            println!("{}", serde_json::to_string_pretty(&partitions)?);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn test_persist_table_requires_namespace() {
+        let config =
+            Config::try_parse_from(["ingester", "persist", "--namespace", "ns", "--table", "cpu"])
+                .unwrap();
+        assert!(matches!(
+            config.command,
+            Command::Persist(Persist {
+                namespace: Some(_),
+                table: Some(_),
+                partition_id: None,
+            })
+        ));
+
+        Config::try_parse_from(["ingester", "persist", "--table", "cpu"]).unwrap_err();
+    }
+}
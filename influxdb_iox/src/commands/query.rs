@@ -1,9 +1,9 @@
 use influxdb_iox_client::{
     connection::Connection,
-    flight::{self, generated_types::ReadInfo},
+    flight::{self, generated_types::QueryParam, Query},
     format::QueryOutputFormat,
 };
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -13,6 +13,9 @@ pub enum Error {
 
     #[error("Error querying: {0}")]
     Query(#[from] influxdb_iox_client::flight::Error),
+
+    #[error("Error killing query: {0}")]
+    Kill(#[from] influxdb_iox_client::error::Error),
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -25,41 +28,156 @@ pub struct Config {
     namespace: String,
 
     /// The query to run, in SQL format
-    #[clap(action)]
-    query: String,
+    #[clap(required_unless_present = "kill", action)]
+    query: Option<String>,
 
     /// Optional format ('pretty', 'json', or 'csv')
     #[clap(short, long, default_value = "pretty", action)]
     format: String,
+
+    /// Value of the next query placeholder (`$1`, `$2`, ...), as `TYPE:VALUE`
+    /// where `TYPE` is one of `bool`, `i64`, `u64`, `f64` or `string`, or
+    /// `null`. Values without a type are strings.
+    #[clap(long = "param", value_parser = parse_param, action = clap::ArgAction::Append)]
+    params: Vec<QueryParam>,
+
+    /// Abort the query on the server if it takes longer than this (e.g. "30s")
+    #[clap(long, value_parser = humantime::parse_duration)]
+    timeout: Option<Duration>,
+
+    /// Print statistics of the query after the results
+    #[clap(long, action)]
+    stats: bool,
+
+    /// Instead of running a query, cancel the running query of the namespace
+    /// with this ID, as reported by the `query_id` column of `system.queries`
+    #[clap(long, value_name = "QUERY_ID", conflicts_with = "query", action)]
+    kill: Option<u64>,
+}
+
+fn parse_param(s: &str) -> Result<QueryParam, String> {
+    if s == "null" {
+        return Ok(QueryParam::null());
+    }
+
+    match s.split_once(':') {
+        Some(("bool", value)) => parse_typed_param::<bool>(value),
+        Some(("i64", value)) => parse_typed_param::<i64>(value),
+        Some(("u64", value)) => parse_typed_param::<u64>(value),
+        Some(("f64", value)) => parse_typed_param::<f64>(value),
+        Some(("string", value)) => Ok(value.into()),
+        // no type prefix, e.g. a timestamp
+        _ => Ok(s.into()),
+    }
+}
+
+fn parse_typed_param<T>(value: &str) -> Result<QueryParam, String>
+where
+    T: FromStr + Into<QueryParam>,
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse::<T>()
+        .map(Into::into)
+        .map_err(|e| format!("invalid parameter '{}': {}", value, e))
 }
 
 pub async fn command(connection: Connection, config: Config) -> Result<()> {
-    let mut client = flight::Client::new(connection);
     let Config {
         namespace,
         format,
         query,
+        params,
+        timeout,
+        stats,
+        kill,
     } = config;
 
+    if let Some(query_id) = kill {
+        influxdb_iox_client::query::Client::new(connection)
+            .kill_query(namespace, query_id)
+            .await?;
+        println!("Killed query {}", query_id);
+        return Ok(());
+    }
+
+    // required by clap unless a query is killed
+    let query = query.expect("query is required");
+
+    let mut client = flight::Client::new(connection);
+
     let format = QueryOutputFormat::from_str(&format)?;
 
-    let mut query_results = client
-        .perform_query(ReadInfo {
-            namespace_name: namespace,
-            sql_query: query,
-        })
-        .await?;
+    let mut query = params
+        .into_iter()
+        .fold(Query::new(namespace, query), Query::with_param);
+    if let Some(timeout) = timeout {
+        query = query.with_timeout(timeout);
+    }
+
+    let mut query_results = client.query(query).await?;
 
-    // It might be nice to do some sort of streaming write
-    // rather than buffering the whole thing.
-    let mut batches = vec![];
+    // Print the batches as they arrive, rather than buffering the whole
+    // result.
+    let mut writer = format.writer(std::io::stdout());
     while let Some(data) = query_results.next().await? {
-        batches.push(data);
+        writer.write(&data)?;
     }
+    writer.finish()?;
+    println!();
 
-    let formatted_result = format.format(&batches)?;
-
-    println!("{}", formatted_result);
+    if stats {
+        if let Some(stats) = query_results.stats() {
+            println!(
+                "rows scanned: {}, output rows: {}, chunks scanned: {}, files pruned: {}, execution time: {:?}",
+                stats.rows_scanned,
+                stats.output_rows,
+                stats.chunks_scanned,
+                stats.files_pruned,
+                Duration::from_nanos(stats.execution_time_ns),
+            );
+        }
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_param() {
+        assert_eq!(parse_param("i64:-3").unwrap(), QueryParam::from(-3i64));
+        assert_eq!(parse_param("u64:3").unwrap(), QueryParam::from(3u64));
+        assert_eq!(parse_param("f64:1.5").unwrap(), QueryParam::from(1.5));
+        assert_eq!(parse_param("bool:true").unwrap(), QueryParam::from(true));
+        assert_eq!(parse_param("null").unwrap(), QueryParam::null());
+        assert_eq!(parse_param("string:a:b").unwrap(), QueryParam::from("a:b"));
+        assert_eq!(parse_param("foo").unwrap(), QueryParam::from("foo"));
+        assert_eq!(
+            parse_param("2022-01-01T00:00:00Z").unwrap(),
+            QueryParam::from("2022-01-01T00:00:00Z")
+        );
+        assert!(parse_param("i64:x").is_err());
+    }
+
+    #[test]
+    fn test_kill() {
+        use clap::Parser;
+
+        let config = Config::try_parse_from(["query", "ns", "--kill", "42"]).unwrap();
+        assert_eq!(config.namespace, "ns");
+        assert_eq!(config.kill, Some(42));
+        assert!(config.query.is_none());
+
+        // a namespace may be called "kill"
+        let config = Config::try_parse_from(["query", "kill", "SELECT 1"]).unwrap();
+        assert_eq!(config.namespace, "kill");
+        assert_eq!(config.query.as_deref(), Some("SELECT 1"));
+        assert!(config.kill.is_none());
+
+        assert!(Config::try_parse_from(["query", "ns"]).is_err());
+        assert!(Config::try_parse_from(["query", "ns", "SELECT 1", "--kill", "42"]).is_err());
+    }
+}
@@ -400,6 +400,7 @@ mod tests {
             id: 1,
             topic_id: 1,
             query_pool_id: 1,
+            strict_schema: false,
             tables: HashMap::from([(
                 "table1".to_string(),
                 TableSchema {
@@ -433,6 +434,7 @@ mod tests {
             id: 1,
             topic_id: 1,
             query_pool_id: 1,
+            strict_schema: false,
             tables: HashMap::from([(
                 "table1".to_string(),
                 TableSchema {
@@ -453,6 +455,7 @@ mod tests {
             id: 1,
             topic_id: 1,
             query_pool_id: 1,
+            strict_schema: false,
             tables: HashMap::from([
                 (
                     "newtable".to_string(),
@@ -2,6 +2,7 @@
 
 use super::main;
 use clap_blocks::{
+    authz::AuthzConfig,
     catalog_dsn::CatalogDsnConfig,
     compactor::CompactorConfig,
     ingester::IngesterConfig,
@@ -9,6 +10,7 @@ use clap_blocks::{
     querier::{IngesterAddresses, QuerierConfig},
     run_config::RunConfig,
     socket_addr::SocketAddr,
+    tls::TlsConfig,
     write_buffer::WriteBufferConfig,
 };
 use data_types::{IngesterMapping, ShardIndex};
@@ -24,7 +26,7 @@ use ioxd_querier::{create_querier_server_type, QuerierServerTypeArgs};
 use ioxd_router::create_router_server_type;
 use object_store::DynObjectStore;
 use observability_deps::tracing::*;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use thiserror::Error;
 use trace_exporters::TracingConfig;
 use trogging::cli::LoggingConfig;
@@ -172,6 +174,12 @@ pub struct Config {
     #[clap(flatten)]
     catalog_dsn: CatalogDsnConfig,
 
+    #[clap(flatten)]
+    authz_config: AuthzConfig,
+
+    #[clap(flatten)]
+    tls_config: TlsConfig,
+
     /// The ingester will continue to pull data and buffer it from the write buffer
     /// as long as it is below this size. If it hits this size it will pause
     /// ingest from the write buffer until persistence goes below this threshold.
@@ -332,6 +340,8 @@ impl Config {
             max_http_request_size,
             object_store_config,
             catalog_dsn,
+            authz_config,
+            tls_config,
             pause_ingest_size_bytes,
             persist_memory_threshold_bytes,
             persist_partition_size_threshold_bytes,
@@ -372,7 +382,8 @@ impl Config {
             router_grpc_bind_address,
             max_http_request_size,
             object_store_config,
-        );
+        )
+        .with_tls_config(tls_config);
 
         let querier_run_config = router_run_config
             .clone()
@@ -424,6 +435,7 @@ impl Config {
             memory_budget_bytes: 300_000,
             min_num_rows_allocated_per_record_batch_to_datafusion_plan: 100,
             max_num_compacting_files: 20,
+            max_num_files_to_materialize_deletes: 10,
         };
 
         let querier_config = QuerierConfig {
@@ -434,6 +446,13 @@ impl Config {
             ram_pool_data_bytes: querier_ram_pool_data_bytes,
             max_concurrent_queries: querier_max_concurrent_queries,
             max_table_query_bytes: querier_max_table_query_bytes,
+            query_history_namespace: None,
+            query_history_router_address: None,
+            query_history_router_token: None,
+            query_history_querier_id: None,
+            query_history_batch_size: 1000,
+            query_history_flush_interval: Duration::from_secs(10),
+            query_history_queue_size: 10_000,
         };
 
         SpecializedConfig {
@@ -444,6 +463,7 @@ impl Config {
             compactor_run_config,
 
             catalog_dsn,
+            authz_config,
             write_buffer_config,
             ingester_config,
             compactor_config,
@@ -461,6 +481,7 @@ struct SpecializedConfig {
     compactor_run_config: RunConfig,
 
     catalog_dsn: CatalogDsnConfig,
+    authz_config: AuthzConfig,
     write_buffer_config: WriteBufferConfig,
     ingester_config: IngesterConfig,
     compactor_config: CompactorConfig,
@@ -474,6 +495,7 @@ pub async fn command(config: Config) -> Result<()> {
         ingester_run_config,
         compactor_run_config,
         catalog_dsn,
+        authz_config,
         write_buffer_config,
         ingester_config,
         compactor_config,
@@ -523,6 +545,7 @@ pub async fn command(config: Config) -> Result<()> {
         &write_buffer_config,
         QUERY_POOL_NAME,
         1_000, // max 1,000 concurrent HTTP requests
+        &authz_config,
     )
     .await?;
 
@@ -553,9 +576,7 @@ pub async fn command(config: Config) -> Result<()> {
     let ingester_addresses = IngesterAddresses::ByShardIndex(
         [(
             ShardIndex::new(0),
-            IngesterMapping::Addr(Arc::from(
-                format!("http://{}", ingester_run_config.grpc_bind_address).as_str(),
-            )),
+            IngesterMapping::Addr(Arc::from(ingester_address(&ingester_run_config).as_str())),
         )]
         .into_iter()
         .collect(),
@@ -570,6 +591,7 @@ pub async fn command(config: Config) -> Result<()> {
         time_provider,
         ingester_addresses,
         querier_config,
+        authz_config,
     })
     .await?;
 
@@ -584,3 +606,16 @@ pub async fn command(config: Config) -> Result<()> {
 
     Ok(main::main(common_state, services, metrics).await?)
 }
+
+/// The address the querier connects to the ingester at.
+///
+/// With TLS, the ingester is connected to as `localhost`, as server
+/// certificates cannot be verified against IP addresses.
+fn ingester_address(ingester_run_config: &RunConfig) -> String {
+    let addr = ingester_run_config.grpc_bind_address;
+    if ingester_run_config.tls_config().is_enabled() {
+        format!("https://localhost:{}", addr.port())
+    } else {
+        format!("http://{}", addr)
+    }
+}
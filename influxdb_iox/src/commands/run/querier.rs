@@ -2,8 +2,8 @@
 
 use super::main;
 use clap_blocks::{
-    catalog_dsn::CatalogDsnConfig, object_store::make_object_store, querier::QuerierConfig,
-    run_config::RunConfig,
+    authz::AuthzConfig, catalog_dsn::CatalogDsnConfig, object_store::make_object_store,
+    querier::QuerierConfig, run_config::RunConfig,
 };
 use iox_query::exec::Executor;
 use iox_time::{SystemProvider, TimeProvider};
@@ -66,6 +66,9 @@ pub struct Config {
 
     #[clap(flatten)]
     pub(crate) querier_config: QuerierConfig,
+
+    #[clap(flatten)]
+    pub(crate) authz_config: AuthzConfig,
 }
 
 pub async fn command(config: Config) -> Result<(), Error> {
@@ -108,6 +111,7 @@ pub async fn command(config: Config) -> Result<(), Error> {
         time_provider,
         ingester_addresses,
         querier_config: config.querier_config,
+        authz_config: config.authz_config,
     })
     .await?;
 
@@ -3,7 +3,8 @@
 use super::main;
 use clap_blocks::object_store::make_object_store;
 use clap_blocks::{
-    catalog_dsn::CatalogDsnConfig, run_config::RunConfig, write_buffer::WriteBufferConfig,
+    authz::AuthzConfig, catalog_dsn::CatalogDsnConfig, run_config::RunConfig,
+    write_buffer::WriteBufferConfig,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use ioxd_common::{
@@ -62,6 +63,9 @@ pub struct Config {
     #[clap(flatten)]
     pub(crate) write_buffer_config: WriteBufferConfig,
 
+    #[clap(flatten)]
+    pub(crate) authz_config: AuthzConfig,
+
     /// Query pool name to dispatch writes to.
     #[clap(
         long = "--query-pool",
@@ -116,6 +120,7 @@ pub async fn command(config: Config) -> Result<()> {
         &config.write_buffer_config,
         &config.query_pool_name,
         config.http_request_limit,
+        &config.authz_config,
     )
     .await?;
 
@@ -9,7 +9,7 @@ use datafusion::{
     datasource::MemTable,
     prelude::{SessionConfig, SessionContext},
 };
-use influxdb_iox_client::{connection::Connection, flight::generated_types::ReadInfo};
+use influxdb_iox_client::{connection::Connection, flight::Query};
 use observability_deps::tracing::{debug, info};
 use snafu::{ResultExt, Snafu};
 use std::{collections::HashMap, sync::Arc, time::Instant};
@@ -120,10 +120,7 @@ async fn load_remote_system_tables(
                 tokio::task::spawn(async move {
                     let mut client = influxdb_iox_client::flight::Client::new(connection);
                     let mut query_results = client
-                        .perform_query(ReadInfo {
-                            namespace_name: db_name.clone(),
-                            sql_query: sql,
-                        })
+                        .query(Query::new(db_name.clone(), sql))
                         .await
                         .context(RunningRemoteQuerySnafu)?;
 
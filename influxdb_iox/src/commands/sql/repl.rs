@@ -10,9 +10,7 @@ use snafu::{ResultExt, Snafu};
 
 use super::repl_command::ReplCommand;
 
-use influxdb_iox_client::{
-    connection::Connection, flight::generated_types::ReadInfo, format::QueryOutputFormat,
-};
+use influxdb_iox_client::{connection::Connection, flight::Query, format::QueryOutputFormat};
 
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -387,7 +385,17 @@ impl Repl {
     }
 
     /// Prints to the specified output format
+    ///
+    /// The plans returned by `EXPLAIN` and `EXPLAIN ANALYZE` span many lines,
+    /// so they are printed as indented text rather than as a table.
     fn print_results(&self, batches: &[RecordBatch]) -> Result<()> {
+        if matches!(self.output_format, QueryOutputFormat::Pretty) {
+            if let Some(plans) = format_explain(batches) {
+                println!("{}", plans);
+                return Ok(());
+            }
+        }
+
         let formatted_results = self
             .output_format
             .format(batches)
@@ -422,6 +430,39 @@ fn history_file() -> PathBuf {
     buf
 }
 
+/// Formats the `plan_type`, `plan` rows returned by `EXPLAIN` queries as
+/// indented text, or returns `None` if `batches` are the result of any other
+/// query.
+fn format_explain(batches: &[RecordBatch]) -> Option<String> {
+    let mut out = String::new();
+
+    for batch in batches {
+        let schema = batch.schema();
+        let names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        if names != ["plan_type", "plan"] {
+            return None;
+        }
+
+        let plan_types = batch.column(0).as_any().downcast_ref::<StringArray>()?;
+        let plans = batch.column(1).as_any().downcast_ref::<StringArray>()?;
+        for row in 0..batch.num_rows() {
+            out.push_str(plan_types.value(row));
+            out.push_str(":\n");
+            for line in plans.value(row).lines() {
+                out.push_str("    ");
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+
+    if out.is_empty() {
+        None
+    } else {
+        Some(out)
+    }
+}
+
 /// Runs the specified `query` and returns the record batches of the result
 async fn scrape_query(
     client: &mut influxdb_iox_client::flight::Client,
@@ -429,10 +470,7 @@ async fn scrape_query(
     query: &str,
 ) -> Result<Vec<RecordBatch>> {
     let mut query_results = client
-        .perform_query(ReadInfo {
-            namespace_name: db_name.to_string(),
-            sql_query: query.to_string(),
-        })
+        .query(Query::new(db_name, query))
         .await
         .context(RunningRemoteQuerySnafu)?;
 
@@ -448,3 +486,46 @@ async fn scrape_query(
 
     Ok(batches)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_explain() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "plan_type",
+                Arc::new(StringArray::from(vec![
+                    "Plan with Metrics",
+                    "Query Statistics",
+                ])) as ArrayRef,
+            ),
+            (
+                "plan",
+                Arc::new(StringArray::from(vec![
+                    "ProjectionExec: expr=[a], metrics=[output_rows=1]\n  MemoryExec: metrics=[]",
+                    "rows_returned=1",
+                ])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            format_explain(&[batch]).unwrap(),
+            "Plan with Metrics:\n\
+             \x20   ProjectionExec: expr=[a], metrics=[output_rows=1]\n\
+             \x20     MemoryExec: metrics=[]\n\
+             Query Statistics:\n\
+             \x20   rows_returned=1\n"
+        );
+
+        let other = RecordBatch::try_from_iter(vec![(
+            "plan",
+            Arc::new(StringArray::from(vec!["a"])) as ArrayRef,
+        )])
+        .unwrap();
+        assert_eq!(format_explain(&[other]), None);
+        assert_eq!(format_explain(&[]), None);
+    }
+}
@@ -118,6 +118,9 @@ USE DATABASE foo;
 SHOW TABLES; ;; Show available tables
 SHOW COLUMNS FROM my_table; ;; Show columns in the table
 
+;; Run a query and show its plan with metrics, files pruned and cache requests:
+EXPLAIN ANALYZE SELECT * FROM my_table;
+
 ;; Show storage usage across partitions and tables
 SELECT
    partition_key, table_name, storage,
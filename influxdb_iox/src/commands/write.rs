@@ -1,19 +1,24 @@
+use import::bulk_write::{
+    bulk_write,
+    input::{find_input_files, InputError},
+    BulkWriteConfig, BulkWriteError, Progress,
+};
 use influxdb_iox_client::{connection::Connection, write};
 use iox_time::TimeProvider;
-use std::{fs::File, io::Read, path::PathBuf};
+use std::{num::NonZeroU64, path::PathBuf};
 use thiserror::Error;
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Error reading file {:?}: {}", file_name, source)]
-    ReadingFile {
-        file_name: PathBuf,
-        source: std::io::Error,
-    },
-
-    #[error("Client error: {0}")]
-    ClientError(#[from] influxdb_iox_client::error::Error),
+    #[error("Error finding input files: {0}")]
+    FindingFiles(#[from] InputError),
+
+    #[error("No input files found")]
+    NoInputFiles,
+
+    #[error("Error writing data: {0}")]
+    BulkWrite(#[from] BulkWriteError),
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -25,30 +30,93 @@ pub struct Config {
     #[clap(action)]
     name: String,
 
-    /// File with data to load. Currently supported formats are .lp
-    #[clap(action)]
-    file_name: PathBuf,
+    /// Files or directories with data to load. Supported formats are line
+    /// protocol (.lp), annotated CSV (.csv), both optionally gzipped (.gz),
+    /// and parquet files written by IOx (.parquet)
+    #[clap(action, required = true)]
+    file_names: Vec<PathBuf>,
+
+    /// Maximum size of a single write request, in bytes of line protocol
+    #[clap(long, default_value = "10485760", action)]
+    max_batch_bytes: usize,
+
+    /// Number of concurrent write requests. Requests in flight at the same
+    /// time may be applied in any order; use 1 if the input writes the same
+    /// point more than once
+    #[clap(long, default_value = "4", action)]
+    concurrency: usize,
+
+    /// Number of times a failed write request is retried
+    #[clap(long, default_value = "3", action)]
+    max_retries: usize,
+
+    /// Maximum number of lines written per second
+    #[clap(long, action)]
+    max_lines_per_second: Option<NonZeroU64>,
+
+    /// Precision of the timestamps in line protocol files: 'ns', 'us', 'ms'
+    /// or 's'
+    #[clap(long, default_value = "ns", value_parser = parse_precision)]
+    precision: i64,
+}
+
+fn parse_precision(s: &str) -> Result<i64, String> {
+    match s {
+        "ns" => Ok(1),
+        "us" => Ok(1_000),
+        "ms" => Ok(1_000_000),
+        "s" => Ok(1_000_000_000),
+        other => Err(format!("invalid precision '{}'", other)),
+    }
 }
 
 pub async fn command(connection: Connection, config: Config) -> Result<()> {
-    let mut client = write::Client::new(connection);
+    let client = write::Client::new(connection);
+
+    let files = find_input_files(&config.file_names)?;
+    if files.is_empty() {
+        return Err(Error::NoInputFiles);
+    }
 
-    let mut file = File::open(&config.file_name).map_err(|e| Error::ReadingFile {
-        file_name: config.file_name.clone(),
-        source: e,
-    })?;
+    let bulk_config = BulkWriteConfig {
+        max_batch_bytes: config.max_batch_bytes,
+        concurrency: config.concurrency,
+        max_retries: config.max_retries,
+        max_lines_per_second: config.max_lines_per_second,
+        timestamp_base: config.precision,
+        default_time: iox_time::SystemProvider::new().now().timestamp_nanos(),
+        ..Default::default()
+    };
 
-    let mut lp_data = String::new();
-    file.read_to_string(&mut lp_data)
-        .map_err(|e| Error::ReadingFile {
-            file_name: config.file_name.clone(),
-            source: e,
-        })?;
+    let summaries = bulk_write(
+        client,
+        &config.name,
+        files,
+        &bulk_config,
+        |progress| match progress {
+            Progress::FileStarted { path } => eprintln!("{}: writing", path.display()),
+            Progress::Rejected { path, line, error } => {
+                eprintln!("{}:{}: rejected: {}", path.display(), line, error)
+            }
+            Progress::BatchWritten { .. } => {}
+            Progress::FileFinished(summary) => eprintln!(
+                "{}: {} lines written in {} batches, {} lines rejected",
+                summary.path.display(),
+                summary.lines_written,
+                summary.batches,
+                summary.lines_rejected,
+            ),
+        },
+    )
+    .await?;
 
-    let default_time = iox_time::SystemProvider::new().now().timestamp_nanos();
-    let lines_written = client.write_lp(config.name, lp_data, default_time).await?;
+    let lines_written: usize = summaries.iter().map(|s| s.lines_written).sum();
+    let lines_rejected: usize = summaries.iter().map(|s| s.lines_rejected).sum();
 
     println!("{} Lines OK", lines_written);
+    if lines_rejected > 0 {
+        println!("{} Lines rejected", lines_rejected);
+    }
 
     Ok(())
 }
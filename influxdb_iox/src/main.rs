@@ -22,6 +22,7 @@ use std::time::Duration;
 use std::{
     collections::hash_map::DefaultHasher,
     hash::{Hash, Hasher},
+    path::{Path, PathBuf},
     str::FromStr,
 };
 use tokio::runtime::Runtime;
@@ -31,6 +32,7 @@ mod commands {
     pub mod compactor;
     pub mod debug;
     pub mod import;
+    pub mod ingester;
     pub mod query;
     pub mod query_ingester;
     pub mod remote;
@@ -129,6 +131,16 @@ struct Config {
     #[clap(long, global = true, action)]
     header: Vec<KeyValue<http::header::HeaderName, http::HeaderValue>>,
 
+    /// API token sent with CLI requests, for servers requiring authorization
+    #[clap(
+        long,
+        global = true,
+        env = "INFLUXDB_IOX_TOKEN",
+        hide_env_values = true,
+        action
+    )]
+    token: Option<String>,
+
     /// Configure the request timeout for CLI requests
     #[clap(
         long,
@@ -144,6 +156,20 @@ struct Config {
     #[clap(long, global = true, action)]
     gen_trace_id: bool,
 
+    /// Path to a PEM encoded CA certificate to verify the certificate of
+    /// `https://` hosts against
+    #[clap(long, global = true, action)]
+    client_tls_ca: Option<PathBuf>,
+
+    /// Path to a PEM encoded client certificate presented to hosts requiring
+    /// mutual TLS
+    #[clap(long, global = true, requires = "client_tls_key", action)]
+    client_tls_cert: Option<PathBuf>,
+
+    /// Path to the PEM encoded private key of `--client-tls-cert`
+    #[clap(long, global = true, requires = "client_tls_cert", action)]
+    client_tls_key: Option<PathBuf>,
+
     /// Set the maximum number of threads to use. Defaults to the number of
     /// cores on the system
     #[clap(long, action)]
@@ -190,6 +216,9 @@ enum Command {
     /// Query the ingester only
     QueryIngester(commands::query_ingester::Config),
 
+    /// Administer an ingester
+    Ingester(commands::ingester::Config),
+
     /// Commands related to the bulk ingest of data
     Import(commands::import::Config),
 }
@@ -206,8 +235,11 @@ fn main() -> Result<(), std::io::Error> {
     tokio_runtime.block_on(async move {
         let host = config.host;
         let headers = config.header;
+        let token = config.token;
         let log_verbose_count = config.all_in_one_config.logging_config.log_verbose_count;
         let rpc_timeout = config.rpc_timeout;
+        let client_tls_ca = config.client_tls_ca;
+        let client_tls_identity = config.client_tls_cert.zip(config.client_tls_key);
 
         let connection = || async move {
             let mut builder = headers.into_iter().fold(Builder::default(), |builder, kv| {
@@ -216,6 +248,17 @@ fn main() -> Result<(), std::io::Error> {
 
             builder = builder.timeout(rpc_timeout);
 
+            if let Some(token) = token {
+                builder = builder.token(token);
+            }
+
+            if let Some(ca) = client_tls_ca {
+                builder = builder.tls_ca_certificate(read_tls_file(&ca));
+            }
+            if let Some((cert, key)) = client_tls_identity {
+                builder = builder.tls_identity(read_tls_file(&cert), read_tls_file(&key));
+            }
+
             if config.gen_trace_id {
                 let key = http::header::HeaderName::from_str(
                     trace_exporters::DEFAULT_JAEGER_TRACE_CONTEXT_HEADER_NAME,
@@ -297,7 +340,7 @@ fn main() -> Result<(), std::io::Error> {
             }
             Some(Command::Compactor(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
-                if let Err(e) = commands::compactor::command(*config).await {
+                if let Err(e) = commands::compactor::command(connection, *config).await {
                     eprintln!("{}", e);
                     std::process::exit(ReturnCode::Failure as _)
                 }
@@ -333,6 +376,14 @@ fn main() -> Result<(), std::io::Error> {
                     std::process::exit(ReturnCode::Failure as _)
                 }
             }
+            Some(Command::Ingester(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                let connection = connection().await;
+                if let Err(e) = commands::ingester::command(connection, config).await {
+                    eprintln!("{}", e);
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
             Some(Command::Import(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
                 let connection = connection().await;
@@ -347,6 +398,18 @@ fn main() -> Result<(), std::io::Error> {
     Ok(())
 }
 
+/// Reads a TLS certificate or key file of the CLI connection, exiting on
+/// failure.
+fn read_tls_file(path: &Path) -> Vec<u8> {
+    match std::fs::read(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("Error reading {}: {}", path.display(), e);
+            std::process::exit(ReturnCode::Failure as _)
+        }
+    }
+}
+
 // Generates a compatible header values for a jaeger trace context header.
 fn gen_trace_id() -> String {
     let now = SystemProvider::new().now();
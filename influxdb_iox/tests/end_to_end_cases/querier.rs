@@ -220,6 +220,75 @@ async fn table_not_found_on_ingester() {
     .await
 }
 
+#[tokio::test]
+async fn query_params_and_stats() {
+    test_helpers::maybe_start_logging();
+    let database_url = maybe_skip_integration!();
+
+    let table_name = "the_table";
+
+    // Set up the cluster  ====================================
+    let mut cluster = MiniCluster::create_shared(database_url).await;
+
+    StepTest::new(
+        &mut cluster,
+        vec![
+            Step::WriteLineProtocol(format!(
+                "{},tag1=A,tag2=B val=42i 123456\n\
+                 {},tag1=A,tag2=C val=43i 123457",
+                table_name, table_name
+            )),
+            Step::WaitForPersisted,
+            Step::Custom(Box::new(move |state: &mut StepTestState| {
+                async move {
+                    let mut client = influxdb_iox_client::flight::Client::new(
+                        state.cluster().querier().querier_grpc_connection(),
+                    );
+
+                    let query = influxdb_iox_client::flight::Query::new(
+                        state.cluster().namespace(),
+                        format!("select * from {} where tag2 = $1 and val > $2", table_name),
+                    )
+                    .with_param("B")
+                    .with_param(0i64)
+                    .with_timeout(std::time::Duration::from_secs(60));
+
+                    let mut results = client.query(query).await.unwrap();
+                    assert!(results.stats().is_none());
+
+                    let batches = results.collect().await.unwrap();
+                    let expected = [
+                        "+------+------+--------------------------------+-----+",
+                        "| tag1 | tag2 | time                           | val |",
+                        "+------+------+--------------------------------+-----+",
+                        "| A    | B    | 1970-01-01T00:00:00.000123456Z | 42  |",
+                        "+------+------+--------------------------------+-----+",
+                    ];
+                    assert_batches_sorted_eq!(&expected, &batches);
+
+                    let stats = results.stats().expect("stats sent");
+                    assert_eq!(stats.output_rows, 1);
+                    assert!(stats.rows_scanned >= 1);
+                    assert!(stats.chunks_scanned >= 1);
+
+                    // the ID of the query in `system.queries` is sent
+                    assert!(results.query_id().is_some());
+
+                    // unknown queries cannot be cancelled
+                    let err = client
+                        .cancel_query(state.cluster().namespace(), u64::MAX)
+                        .await
+                        .unwrap_err();
+                    assert_contains!(err.to_string(), format!("Query {} not found", u64::MAX));
+                }
+                .boxed()
+            })),
+        ],
+    )
+    .run()
+    .await
+}
+
 #[tokio::test]
 async fn ingester_panic() {
     test_helpers::maybe_start_logging();
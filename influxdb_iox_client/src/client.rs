@@ -20,12 +20,21 @@ pub mod write;
 /// Client for query API (based on Arrow flight)
 pub mod flight;
 
+/// Client for administering running queries
+pub mod query;
+
 /// Client for testing purposes.
 pub mod test;
 
 /// Client for fetching write info
 pub mod write_info;
 
+/// Client for administering an ingester
+pub mod ingester;
+
+/// Client for administering a compactor
+pub mod compactor;
+
 /// Client for interacting with a remote catalog
 pub mod catalog;
 
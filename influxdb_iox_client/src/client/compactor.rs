@@ -0,0 +1,93 @@
+use self::generated_types::{compactor_admin_service_client::CompactorAdminServiceClient, *};
+use crate::connection::Connection;
+use crate::error::Error;
+
+/// Re-export generated_types
+pub mod generated_types {
+    pub use generated_types::influxdata::iox::compactor::v1::{
+        compactor_admin_service_client, compactor_admin_service_server,
+        ClearSkippedCompactionRequest, CompactionCandidate, ListCompactionCandidatesRequest,
+        ListCompactionCandidatesResponse, ListSkippedCompactionsRequest, QueuePartitionRequest,
+        QueueTableRequest, SkippedCompaction,
+    };
+}
+
+/// A basic client for administering the compaction cycles of a single
+/// compactor.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: CompactorAdminServiceClient<Connection>,
+}
+
+impl Client {
+    /// Creates a new client with the provided connection
+    pub fn new(channel: Connection) -> Self {
+        Self {
+            inner: CompactorAdminServiceClient::new(channel),
+        }
+    }
+
+    /// List the partitions whose compaction was skipped
+    pub async fn list_skipped_compactions(&mut self) -> Result<Vec<SkippedCompaction>, Error> {
+        let response = self
+            .inner
+            .list_skipped_compactions(ListSkippedCompactionsRequest {})
+            .await?;
+
+        Ok(response.into_inner().skipped_compactions)
+    }
+
+    /// Clear the skipped compaction of the given partition so it is retried,
+    /// returning the cleared record if there was one
+    pub async fn clear_skipped_compaction(
+        &mut self,
+        partition_id: i64,
+    ) -> Result<Option<SkippedCompaction>, Error> {
+        let response = self
+            .inner
+            .clear_skipped_compaction(ClearSkippedCompactionRequest { partition_id })
+            .await?;
+
+        Ok(response.into_inner().skipped_compaction)
+    }
+
+    /// Queue the given partition for full compaction
+    pub async fn queue_partition(&mut self, partition_id: i64) -> Result<(), Error> {
+        self.inner
+            .queue_partition(QueuePartitionRequest { partition_id })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Queue all partitions of the given table for full compaction, returning
+    /// the IDs of the queued partitions
+    pub async fn queue_table(
+        &mut self,
+        namespace: impl Into<String> + Send,
+        table: impl Into<String> + Send,
+    ) -> Result<Vec<i64>, Error> {
+        let response = self
+            .inner
+            .queue_table(QueueTableRequest {
+                namespace: namespace.into(),
+                table: table.into(),
+            })
+            .await?;
+
+        Ok(response.into_inner().partition_ids)
+    }
+
+    /// List the current compaction candidates and the memory budget of the
+    /// compactor
+    pub async fn list_compaction_candidates(
+        &mut self,
+    ) -> Result<ListCompactionCandidatesResponse, Error> {
+        let response = self
+            .inner
+            .list_compaction_candidates(ListCompactionCandidatesRequest {})
+            .await?;
+
+        Ok(response.into_inner())
+    }
+}
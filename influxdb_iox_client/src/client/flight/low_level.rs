@@ -34,8 +34,8 @@ use arrow::{
     record_batch::RecordBatch,
 };
 use arrow_flight::{
-    flight_service_client::FlightServiceClient, utils::flight_data_to_arrow_batch, FlightData,
-    HandshakeRequest, Ticket,
+    flight_service_client::FlightServiceClient, utils::flight_data_to_arrow_batch, Action,
+    FlightData, HandshakeRequest, Ticket,
 };
 
 use super::Error;
@@ -62,7 +62,7 @@ impl ClientMetadata for IngesterQueryRequest {
 /// The type parameter `T` -- which must implement [`ClientMetadata`] describes the request and response metadata that
 /// is send and received during the flight request. The request is encoded as protobuf and send as the Flight "ticket",
 /// the response is received via the so called "app metadata".
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Client<T>
 where
     T: ClientMetadata,
@@ -89,6 +89,26 @@ where
         PerformQuery::<T::Response>::new(self, request).await
     }
 
+    /// Perform the flight action `action_type` with the given `body` and
+    /// return the bodies of all results.
+    pub async fn do_action(
+        &mut self,
+        action_type: impl Into<String> + Send,
+        body: Vec<u8>,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let action = Action {
+            r#type: action_type.into(),
+            body,
+        };
+        let mut response = self.inner.do_action(action).await?.into_inner();
+
+        let mut results = vec![];
+        while let Some(result) = response.next().await {
+            results.push(result?.body);
+        }
+        Ok(results)
+    }
+
     /// Perform a handshake with the server, as defined by the Arrow Flight API.
     pub async fn handshake(&mut self) -> Result<(), Error> {
         let request = HandshakeRequest {
@@ -1,4 +1,8 @@
-use ::generated_types::influxdata::iox::querier::v1::{AppMetadata, ReadInfo};
+use ::generated_types::influxdata::iox::querier::v1::{
+    AppMetadata, CancelQueryRequest, QueryParam, QueryStats, ReadInfo,
+};
+use prost::Message;
+use std::time::Duration;
 use thiserror::Error;
 
 use arrow::{
@@ -73,8 +77,9 @@ pub enum Error {
 /// An IOx Arrow Flight gRPC API client.
 ///
 /// # Protocol
-/// This client is only suitable to yield a stream of record batches with the same schema. No metadata handling is
-/// supported. For a more advanced usage use the [low level interface](low_level).
+/// This client is only suitable to yield a stream of record batches with the same schema. The only metadata handled
+/// are the [`QueryStats`] sent by the server once the query completed. For a more advanced usage use the
+/// [low level interface](low_level).
 ///
 /// # Example
 ///
@@ -100,6 +105,7 @@ pub enum Error {
 ///     .perform_query(ReadInfo {
 ///         namespace_name: "my_database".to_string(),
 ///         sql_query: "select * from cpu_load".to_string(),
+///         ..Default::default()
 ///     })
 ///     .await
 ///     .expect("query request should work");
@@ -111,7 +117,41 @@ pub enum Error {
 /// }
 /// # }
 /// ```
-#[derive(Debug)]
+///
+/// Queries can also be built with parameters, a timeout, an ID that allows cancelling them and the
+/// write token of a write they must observe:
+///
+/// ```rust,no_run
+/// #[tokio::main]
+/// # async fn main() {
+/// use std::time::Duration;
+/// use influxdb_iox_client::{
+///     connection::Builder,
+///     flight::{Client, Query},
+/// };
+///
+/// let connection = Builder::default()
+///     .build("http://127.0.0.1:8082")
+///     .await
+///     .expect("client should be valid");
+///
+/// let mut client = Client::new(connection);
+///
+/// let query = Query::new("my_database", "select * from cpu_load where host = $1")
+///     .with_param("server01")
+///     .with_timeout(Duration::from_secs(30))
+///     .with_write_token("<token returned by the write>");
+///
+/// let mut query_results = client.query(query).await.expect("query request should work");
+///
+/// // from elsewhere, `client.cancel_query("my_database", query_id)` aborts the
+/// // query, where `query_id` is `query_results.query_id()`
+///
+/// let batches = query_results.collect().await.expect("valid batches");
+/// println!("{:?}", query_results.stats());
+/// # }
+/// ```
+#[derive(Debug, Clone)]
 pub struct Client {
     inner: LowLevelClient<ReadInfo>,
 }
@@ -130,12 +170,86 @@ impl Client {
         PerformQuery::new(self, request).await
     }
 
+    /// Run the given [`Query`], and return a [`PerformQuery`] instance that
+    /// streams Arrow `RecordBatch` results.
+    pub async fn query(&mut self, query: Query) -> Result<PerformQuery, Error> {
+        self.perform_query(query.into()).await
+    }
+
+    /// Ask the server to abort the running query `query_id` of the given
+    /// namespace, as reported by [`PerformQuery::query_id`] or the `query_id`
+    /// column of `system.queries`.
+    pub async fn cancel_query(
+        &mut self,
+        namespace_name: impl Into<String> + Send,
+        query_id: u64,
+    ) -> Result<(), Error> {
+        let request = CancelQueryRequest {
+            namespace_name: namespace_name.into(),
+            query_id,
+        };
+        self.inner
+            .do_action("CancelQuery", request.encode_to_vec())
+            .await?;
+        Ok(())
+    }
+
     /// Perform a handshake with the server, as defined by the Arrow Flight API.
     pub async fn handshake(&mut self) -> Result<(), Error> {
         self.inner.handshake().await
     }
 }
 
+/// A SQL query to be run via [`Client::query`].
+#[derive(Debug, Clone)]
+pub struct Query {
+    read_info: ReadInfo,
+}
+
+impl Query {
+    /// Create a query of `sql_query` against the given namespace.
+    ///
+    /// The query may contain positional placeholders (`$1`, `$2`, ...) that
+    /// are bound by the server to the parameters added via
+    /// [`with_param`](Self::with_param).
+    pub fn new(namespace_name: impl Into<String>, sql_query: impl Into<String>) -> Self {
+        Self {
+            read_info: ReadInfo {
+                namespace_name: namespace_name.into(),
+                sql_query: sql_query.into(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Add the value of the next placeholder.
+    pub fn with_param(mut self, param: impl Into<QueryParam>) -> Self {
+        self.read_info.params.push(param.into());
+        self
+    }
+
+    /// Have the server abort the query once it ran longer than `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.read_info.timeout_ms = timeout.as_millis().try_into().unwrap_or(u64::MAX);
+        self
+    }
+
+    /// Have the server plan the query only once the write that returned
+    /// `write_token` is readable, so the query observes its data.
+    ///
+    /// The server waits for at most the timeout of the query.
+    pub fn with_write_token(mut self, write_token: impl Into<String>) -> Self {
+        self.read_info.write_token = write_token.into();
+        self
+    }
+}
+
+impl From<Query> for ReadInfo {
+    fn from(query: Query) -> Self {
+        query.read_info
+    }
+}
+
 /// A struct that manages the stream of Arrow `RecordBatch` results from an
 /// Arrow Flight query. Created by calling the `perform_query` method on a
 /// Flight [`Client`].
@@ -143,6 +257,8 @@ impl Client {
 pub struct PerformQuery {
     inner: LowLevelPerformQuery<AppMetadata>,
     got_schema: bool,
+    query_id: Option<u64>,
+    stats: Option<QueryStats>,
 }
 
 impl PerformQuery {
@@ -152,6 +268,8 @@ impl PerformQuery {
         Ok(Self {
             inner,
             got_schema: false,
+            query_id: None,
+            stats: None,
         })
     }
 
@@ -161,14 +279,19 @@ impl PerformQuery {
         loop {
             match self.inner.next().await? {
                 None => return Ok(None),
-                Some((LowLevelMessage::Schema(_), _)) => {
+                Some((LowLevelMessage::Schema(_), app_metadata)) => {
                     if self.got_schema {
                         return Err(Error::UnexpectedSchemaChange);
                     }
                     self.got_schema = true;
+                    self.query_id = (app_metadata.query_id != 0).then_some(app_metadata.query_id);
                 }
                 Some((LowLevelMessage::RecordBatch(batch), _)) => return Ok(Some(batch)),
-                Some((LowLevelMessage::None, _)) => (),
+                Some((LowLevelMessage::None, app_metadata)) => {
+                    if app_metadata.stats.is_some() {
+                        self.stats = app_metadata.stats;
+                    }
+                }
             }
         }
     }
@@ -182,4 +305,22 @@ impl PerformQuery {
 
         Ok(batches)
     }
+
+    /// ID of the query on the server, used to cancel it via
+    /// [`Client::cancel_query`].
+    ///
+    /// Available once the schema was received, i.e. after the first call to
+    /// [`next`](Self::next). Returns `None` before that or if the query
+    /// cannot be cancelled.
+    pub fn query_id(&self) -> Option<u64> {
+        self.query_id
+    }
+
+    /// Statistics of the query, available once all results were consumed.
+    ///
+    /// Returns `None` if the query has not completed yet or the server did
+    /// not report any statistics.
+    pub fn stats(&self) -> Option<&QueryStats> {
+        self.stats.as_ref()
+    }
 }
@@ -0,0 +1,66 @@
+use self::generated_types::{ingester_admin_service_client::IngesterAdminServiceClient, *};
+use crate::connection::Connection;
+use crate::error::Error;
+
+/// Re-export generated_types
+pub mod generated_types {
+    pub use generated_types::influxdata::iox::ingester::v1::{
+        ingester_admin_service_client, ingester_admin_service_server, BufferedPartition,
+        ListBufferedPartitionsRequest, ListBufferedPartitionsResponse, PauseShardRequest,
+        PersistRequest, PersistResponse, ResumeShardRequest,
+    };
+}
+
+/// A basic client for administering the buffered data and the shard
+/// consumption of a single ingester.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: IngesterAdminServiceClient<Connection>,
+}
+
+impl Client {
+    /// Creates a new client with the provided connection
+    pub fn new(channel: Connection) -> Self {
+        Self {
+            inner: IngesterAdminServiceClient::new(channel),
+        }
+    }
+
+    /// Persist the buffered partitions selected by `request` now, returning
+    /// the IDs of the partitions that will be persisted
+    pub async fn persist(&mut self, request: PersistRequest) -> Result<Vec<i64>, Error> {
+        let response = self.inner.persist(request).await?;
+
+        Ok(response.into_inner().partition_ids)
+    }
+
+    /// Stop consuming writes from the shard with the given index
+    pub async fn pause_shard(&mut self, shard_index: i32) -> Result<(), Error> {
+        self.inner
+            .pause_shard(PauseShardRequest { shard_index })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Resume consuming writes from the shard with the given index
+    pub async fn resume_shard(&mut self, shard_index: i32) -> Result<(), Error> {
+        self.inner
+            .resume_shard(ResumeShardRequest { shard_index })
+            .await?;
+
+        Ok(())
+    }
+
+    /// List the partitions with buffered data and the paused shards
+    pub async fn list_buffered_partitions(
+        &mut self,
+    ) -> Result<ListBufferedPartitionsResponse, Error> {
+        let response = self
+            .inner
+            .list_buffered_partitions(ListBufferedPartitionsRequest {})
+            .await?;
+
+        Ok(response.into_inner())
+    }
+}
@@ -0,0 +1,40 @@
+use self::generated_types::{query_service_client::QueryServiceClient, *};
+use crate::connection::Connection;
+use crate::error::Error;
+
+/// Re-export generated_types
+pub mod generated_types {
+    pub use generated_types::influxdata::iox::querier::v1::*;
+}
+
+/// A basic client for administering the queries running on a querier.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: QueryServiceClient<Connection>,
+}
+
+impl Client {
+    /// Creates a new client with the provided connection
+    pub fn new(channel: Connection) -> Self {
+        Self {
+            inner: QueryServiceClient::new(channel),
+        }
+    }
+
+    /// Cancel the running query with the given ID of the given namespace, as
+    /// reported by `system.queries`
+    pub async fn kill_query(
+        &mut self,
+        namespace_name: impl Into<String> + Send,
+        query_id: u64,
+    ) -> Result<(), Error> {
+        self.inner
+            .kill_query(KillQueryRequest {
+                query_id,
+                namespace_name: namespace_name.into(),
+            })
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use self::generated_types::{schema_service_client::SchemaServiceClient, *};
 use ::generated_types::google::OptionalField;
+use std::collections::HashMap;
 
 use crate::connection::Connection;
 use crate::error::Error;
@@ -9,7 +10,7 @@ pub mod generated_types {
     pub use generated_types::influxdata::iox::schema::v1::*;
 }
 
-/// A basic client for fetching the Schema for a Namespace.
+/// A basic client for fetching and declaring the Schema for a Namespace.
 #[derive(Debug, Clone)]
 pub struct Client {
     inner: SchemaServiceClient<Connection>,
@@ -34,4 +35,39 @@ impl Client {
 
         Ok(response.into_inner().schema.unwrap_field("schema")?)
     }
+
+    /// Declare the given tables and columns in a namespace, returning the
+    /// resulting schema.
+    pub async fn upsert_schema(
+        &mut self,
+        namespace: &str,
+        tables: HashMap<String, DeclaredTable>,
+    ) -> Result<NamespaceSchema, Error> {
+        let response = self
+            .inner
+            .upsert_schema(UpsertSchemaRequest {
+                namespace: namespace.to_string(),
+                tables,
+            })
+            .await?;
+
+        Ok(response.into_inner().schema.unwrap_field("schema")?)
+    }
+
+    /// Enable or disable strict schema mode for a namespace.
+    pub async fn set_strict_schema(
+        &mut self,
+        namespace: &str,
+        strict_schema: bool,
+    ) -> Result<NamespaceSchema, Error> {
+        let response = self
+            .inner
+            .set_strict_schema(SetStrictSchemaRequest {
+                namespace: namespace.to_string(),
+                strict_schema,
+            })
+            .await?;
+
+        Ok(response.into_inner().schema.unwrap_field("schema")?)
+    }
 }
@@ -1,6 +1,6 @@
 //! Output formatting utilities for Arrow record batches
 
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, io::Write, str::FromStr};
 
 use thiserror::Error;
 
@@ -34,6 +34,10 @@ pub enum Error {
     /// Error converting JSON output to utf-8
     #[error("Error converting JSON output to UTF-8: {}", .0)]
     JsonUtf8(std::string::FromUtf8Error),
+
+    /// Error writing formatted output
+    #[error("Error writing output: {}", .0)]
+    Io(std::io::Error),
 }
 type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -123,6 +127,100 @@ impl QueryOutputFormat {
             Self::Json => batches_to_json(batches),
         }
     }
+
+    /// Return a [`BatchWriter`] writing [`RecordBatch`]es in this format to
+    /// `writer` as they arrive, rather than [formatting](Self::format) them
+    /// all at once.
+    pub fn writer<W: Write>(&self, writer: W) -> BatchWriter<W> {
+        let inner = match self {
+            Self::Pretty => Writer::Pretty(writer),
+            Self::Csv => Writer::Csv(writer),
+            Self::Json => Writer::Json(ArrayWriter::new(writer)),
+        };
+        BatchWriter {
+            inner,
+            written: false,
+        }
+    }
+}
+
+/// Writes [`RecordBatch`]es to a [`Write`] in a [`QueryOutputFormat`], one
+/// batch at a time.
+///
+/// CSV and JSON output is the same as that of [`QueryOutputFormat::format`].
+/// Pretty printed batches are written as separate tables, one per line, as the
+/// column widths of later batches are not known when a batch is written.
+pub struct BatchWriter<W: Write> {
+    inner: Writer<W>,
+    written: bool,
+}
+
+enum Writer<W: Write> {
+    Pretty(W),
+    Csv(W),
+    Json(ArrayWriter<W>),
+}
+
+impl<W: Write> std::fmt::Debug for BatchWriter<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let format = match self.inner {
+            Writer::Pretty(_) => QueryOutputFormat::Pretty,
+            Writer::Csv(_) => QueryOutputFormat::Csv,
+            Writer::Json(_) => QueryOutputFormat::Json,
+        };
+        f.debug_struct("BatchWriter")
+            .field("format", &format)
+            .field("written", &self.written)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W: Write> BatchWriter<W> {
+    /// Write `batch`.
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        match &mut self.inner {
+            Writer::Pretty(writer) => {
+                if self.written {
+                    writeln!(writer).map_err(Error::Io)?;
+                }
+                let table = batches_to_pretty(std::slice::from_ref(batch))?;
+                write!(writer, "{}", table).map_err(Error::Io)?;
+            }
+            Writer::Csv(writer) => {
+                // Only the first batch carries a header. The CSV writer
+                // buffers its output, so each batch is formatted on its own.
+                let mut bytes = vec![];
+                WriterBuilder::new()
+                    .has_headers(!self.written)
+                    .build(&mut bytes)
+                    .write(batch)
+                    .map_err(Error::CsvArrow)?;
+                writer.write_all(&bytes).map_err(Error::Io)?;
+            }
+            Writer::Json(writer) => writer
+                .write_batches(std::slice::from_ref(batch))
+                .map_err(Error::JsonArrow)?,
+        }
+        self.written = true;
+        Ok(())
+    }
+
+    /// Write the end of the output, and an empty result if no batch was
+    /// written. Like [`QueryOutputFormat::format`], this does not end pretty
+    /// printed and JSON output with a newline.
+    pub fn finish(mut self) -> Result<()> {
+        match &mut self.inner {
+            Writer::Pretty(writer) => {
+                if !self.written {
+                    let table = batches_to_pretty(&[])?;
+                    write!(writer, "{}", table).map_err(Error::Io)?;
+                }
+                writer.flush().map_err(Error::Io)
+            }
+            Writer::Csv(writer) => writer.flush().map_err(Error::Io),
+            Writer::Json(writer) => writer.finish().map_err(Error::JsonArrow),
+        }
+    }
 }
 
 fn batches_to_pretty(batches: &[RecordBatch]) -> Result<String> {
@@ -214,4 +312,49 @@ mod tests {
             QueryOutputFormat::Json
         );
     }
+
+    #[test]
+    fn test_writer() {
+        use arrow::array::{ArrayRef, Int64Array};
+        use std::sync::Arc;
+
+        let batch = |values: Vec<i64>| {
+            RecordBatch::try_from_iter([("a", Arc::new(Int64Array::from(values)) as ArrayRef)])
+                .unwrap()
+        };
+        let batches = [batch(vec![1, 2]), batch(vec![3])];
+
+        let write = |format: QueryOutputFormat, batches: &[RecordBatch]| {
+            let mut bytes = vec![];
+            let mut writer = format.writer(&mut bytes);
+            for batch in batches {
+                writer.write(batch).unwrap();
+            }
+            writer.finish().unwrap();
+            String::from_utf8(bytes).unwrap()
+        };
+
+        for format in [QueryOutputFormat::Csv, QueryOutputFormat::Json] {
+            assert_eq!(write(format, &batches), format.format(&batches).unwrap());
+        }
+
+        assert_eq!(
+            write(QueryOutputFormat::Pretty, &batches),
+            "+---+\n\
+             | a |\n\
+             +---+\n\
+             | 1 |\n\
+             | 2 |\n\
+             +---+\n\
+             +---+\n\
+             | a |\n\
+             +---+\n\
+             | 3 |\n\
+             +---+"
+        );
+        assert_eq!(
+            write(QueryOutputFormat::Pretty, &[]),
+            QueryOutputFormat::Pretty.format(&[]).unwrap()
+        );
+    }
 }
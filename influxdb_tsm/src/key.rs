@@ -10,6 +10,28 @@ pub struct ParsedTsmKey {
     pub field_key: String,
 }
 
+/// The measurement, tag set and field key of a series, independent of the
+/// format of the key it was parsed from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsedSeriesKey {
+    pub measurement: String,
+    pub tagset: Vec<(String, String)>,
+    pub field_key: String,
+}
+
+impl From<ParsedTsmKey> for ParsedSeriesKey {
+    fn from(key: ParsedTsmKey) -> Self {
+        Self {
+            measurement: key.measurement,
+            tagset: key.tagset,
+            field_key: key.field_key,
+        }
+    }
+}
+
+/// Separates the series key from the field key in a composite key.
+const FIELD_KEY_SEPARATOR: &str = "#!~#";
+
 /// Public error type that wraps the underlying data parsing error
 /// with the actual key value being parsed.
 #[derive(Debug, Snafu, PartialEq, Eq)]
@@ -67,6 +89,9 @@ pub enum DataError {
 
     #[snafu(display(r#"Error parsing tsm field key: {}"#, description))]
     ParsingTsmFieldKey { description: String },
+
+    #[snafu(display(r#"Error parsing series key: {}"#, description))]
+    ParsingSeriesKey { description: String },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -167,6 +192,112 @@ fn parse_tsm_key_internal(key: &[u8]) -> Result<ParsedTsmKey, DataError> {
     })
 }
 
+/// Parses a key in either of the formats found in TSM indexes and WAL
+/// segments: the org/bucket prefixed format understood by
+/// [`parse_tsm_key`], or the series key format of the InfluxDB 1.x storage
+/// engine understood by [`parse_series_key`].
+pub fn parse_any_key(key: &[u8]) -> Result<ParsedSeriesKey, Error> {
+    // The measurement of a prefixed key is stored in the special `\x00` tag,
+    // which can't appear in a series key.
+    if key.windows(3).any(|w| w == b",\x00=") {
+        parse_tsm_key(key).map(Into::into)
+    } else {
+        parse_series_key(key)
+    }
+}
+
+/// parses the measurement, field key and tag set from a composite series key
+/// as written by the InfluxDB 1.x storage engine.
+///
+/// The format looks like:
+///
+/// <measurement>,<tag_key>=<tag_value>,...#!~#<field_key>
+///
+/// where the measurement, tag keys and tag values are escaped as in line
+/// protocol.
+///
+/// For example:
+/// cpu,host=a,region=west#!~#usage_user
+///
+///    measurement = "cpu"
+///    tags = [("host", "a"), ("region", "west")]
+///    field = "usage_user"
+pub fn parse_series_key(key: &[u8]) -> Result<ParsedSeriesKey, Error> {
+    parse_series_key_internal(key).context(ParsingTsmKeySnafu {
+        key: String::from_utf8_lossy(key),
+    })
+}
+
+fn parse_series_key_internal(key: &[u8]) -> Result<ParsedSeriesKey, DataError> {
+    let key = std::str::from_utf8(key).map_err(|e| DataError::ParsingSeriesKey {
+        description: e.to_string(),
+    })?;
+    let (series_key, field_key) = key
+        .split_once(FIELD_KEY_SEPARATOR)
+        .context(NoFieldKeySnafu)?;
+
+    let mut parts = split_unescaped(series_key, ',').into_iter();
+    let measurement = unescape(parts.next().unwrap_or_default());
+    if measurement.is_empty() {
+        return NoMeasurementSnafu.fail();
+    }
+
+    let tagset = parts
+        .map(|tag| match split_unescaped(tag, '=').as_slice() {
+            [tag_key, tag_value] if !tag_key.is_empty() => {
+                Ok((unescape(tag_key), unescape(tag_value)))
+            }
+            _ => ParsingSeriesKeySnafu {
+                description: format!("invalid tag '{}'", tag),
+            }
+            .fail(),
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(ParsedSeriesKey {
+        measurement,
+        tagset,
+        field_key: field_key.to_string(),
+    })
+}
+
+// Splits `s` at every occurrence of `delimiter` that is not escaped with a
+// backslash.
+fn split_unescaped(s: &str, delimiter: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == delimiter {
+            parts.push(&s[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+// Removes the line protocol escaping of commas, equals signs and spaces.
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(&next @ (',' | '=' | ' ')) = chars.peek() {
+                unescaped.push(next);
+                chars.next();
+                continue;
+            }
+        }
+        unescaped.push(c);
+    }
+    unescaped
+}
+
 // Parses an influx id from the byte sequence. IDs are generally just 8 bytes, but we escape
 // certain characters ('\', ' ' and '='), so we unescape them as part of this process.
 // The iterator will consume all bytes that are part of the id.
@@ -827,6 +958,62 @@ mod tests {
         ];
         assert_eq!(parsed_key.tagset, exp_tagset);
         assert_eq!(parsed_key.field_key, String::from("sum"));
+
+        // the same key is understood when the format isn't known up front
+        let parsed_key = super::parse_any_key(&tsm_key).unwrap();
+        assert_eq!(parsed_key.tagset, exp_tagset);
+    }
+
+    #[test]
+    fn test_parse_series_key() {
+        let parsed_key = parse_series_key(b"cpu,host=a,region=west#!~#usage_user").unwrap();
+        assert_eq!(
+            parsed_key,
+            ParsedSeriesKey {
+                measurement: "cpu".to_string(),
+                tagset: vec![
+                    ("host".to_string(), "a".to_string()),
+                    ("region".to_string(), "west".to_string()),
+                ],
+                field_key: "usage_user".to_string(),
+            }
+        );
+
+        let parsed_key = parse_series_key(b"m#!~#f").unwrap();
+        assert_eq!(parsed_key.measurement, "m");
+        assert!(parsed_key.tagset.is_empty());
+        assert_eq!(parsed_key.field_key, "f");
+
+        let parsed_key =
+            parse_series_key(br"my\ cpu\,x,host\=name=a\,b\ c,path=C:\dir#!~#f").unwrap();
+        assert_eq!(parsed_key.measurement, "my cpu,x");
+        assert_eq!(
+            parsed_key.tagset,
+            vec![
+                ("host=name".to_string(), "a,b c".to_string()),
+                ("path".to_string(), r"C:\dir".to_string()),
+            ]
+        );
+        assert_eq!(parse_any_key(b"m#!~#f").unwrap().measurement, "m");
+    }
+
+    #[test]
+    fn test_parse_series_key_errors() {
+        for (key, expected_error) in [
+            (&b"cpu,host=a"[..], "No field key"),
+            (b",host=a#!~#f", "No measurement found"),
+            (b"cpu,host#!~#f", "invalid tag 'host'"),
+            (b"cpu,=a#!~#f", "invalid tag '=a'"),
+            (b"cpu,host=a=b#!~#f", "invalid tag 'host=a=b'"),
+        ] {
+            let err = parse_series_key(key).unwrap_err().to_string();
+            assert!(
+                err.contains(expected_error),
+                "Did not find expected error '{}' in actual error '{}'",
+                expected_error,
+                err
+            );
+        }
     }
 
     #[test]
@@ -11,13 +11,14 @@ pub mod encoders;
 pub mod key;
 pub mod mapper;
 pub mod reader;
+pub mod wal;
 
 use std::convert::TryFrom;
 use std::error;
 use std::fmt;
 use std::io;
 
-pub use key::ParsedTsmKey;
+pub use key::{ParsedSeriesKey, ParsedTsmKey};
 
 #[derive(Clone, Debug, Copy, PartialEq, Eq)]
 pub enum BlockType {
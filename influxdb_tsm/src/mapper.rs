@@ -1,5 +1,5 @@
 ///! Types for mapping and converting series data from TSM indexes produced by
-///! InfluxDB >= 2.x
+///! InfluxDB 1.x and 2.x
 use crate::reader::{BlockData, BlockDecoder, TsmIndexReader, ValuePair};
 use crate::{Block, BlockType, TsmError};
 
@@ -53,7 +53,7 @@ impl<R: Read + Seek> Iterator for TsmMeasurementMapper<R> {
         // `None` indicates the end of index iteration.
         let entry = try_or_some!(self.iter.next()?);
 
-        let parsed_key = try_or_some!(entry.parse_key());
+        let parsed_key = try_or_some!(entry.parse_series_key());
         let mut measurement: MeasurementTable =
             MeasurementTable::new(parsed_key.measurement, self.reader_idx);
         try_or_some!(measurement.add_series_data(
@@ -69,7 +69,7 @@ impl<R: Read + Seek> Iterator for TsmMeasurementMapper<R> {
         while let Some(res) = self.iter.peek() {
             match res {
                 Ok(entry) => {
-                    let parsed_key = try_or_some!(entry.parse_key());
+                    let parsed_key = try_or_some!(entry.parse_series_key());
                     if measurement.name != parsed_key.measurement {
                         // Next entry is for a different measurement.
                         return Some(Ok(measurement));
@@ -225,6 +225,14 @@ impl IndexEntry {
             description: e.to_string(),
         })
     }
+
+    /// Parse the key of this entry, which may either be in the format of
+    /// [`Self::parse_key`] or an InfluxDB 1.x series key.
+    pub fn parse_series_key(&self) -> Result<ParsedSeriesKey, TsmError> {
+        key::parse_any_key(&self.key).map_err(|e| TsmError {
+            description: e.to_string(),
+        })
+    }
 }
 
 /// A BlockDecoder is capable of decoding a block definition into block data
@@ -0,0 +1,373 @@
+//! Types for reading the write-ahead log (WAL) segments written by the
+//! InfluxDB 1.x and 2.x storage engines.
+//!
+//! A WAL segment is a sequence of entries, each of which is laid out as:
+//!
+//! ┌──────────┬──────────────┬────────────────────────────┐
+//! │   Type   │    Length    │ Snappy compressed payload  │
+//! │ 1 byte   │ 4 bytes (BE) │       `Length` bytes       │
+//! └──────────┴──────────────┴────────────────────────────┘
+//!
+//! Writes are keyed by the same composite series keys as the TSM index and
+//! contain the values of a single field for a single series.
+use super::*;
+use crate::reader::ValuePair;
+use std::io::{self, Read};
+
+const WRITE_ENTRY_TYPE: u8 = 0x01;
+const DELETE_ENTRY_TYPE: u8 = 0x02;
+const DELETE_RANGE_ENTRY_TYPE: u8 = 0x03;
+
+const FLOAT_VALUE_TYPE: u8 = 1;
+const INTEGER_VALUE_TYPE: u8 = 2;
+const BOOL_VALUE_TYPE: u8 = 3;
+const STRING_VALUE_TYPE: u8 = 4;
+const UNSIGNED_VALUE_TYPE: u8 = 5;
+
+/// A single decoded entry of a WAL segment.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WalEntry {
+    /// Values written for each key, in the order they were written.
+    Write {
+        values: Vec<(Vec<u8>, Vec<ValuePair>)>,
+    },
+
+    /// All values of the keys were deleted.
+    Delete { keys: Vec<Vec<u8>> },
+
+    /// The values of the keys within the inclusive time range were deleted.
+    DeleteRange {
+        keys: Vec<Vec<u8>>,
+        min_time: i64,
+        max_time: i64,
+    },
+}
+
+/// `WalReader` iterates over the entries of a single WAL segment.
+///
+/// An entry that was only partially written when the segment was closed
+/// (e.g. because the server crashed) ends the iteration, as it does when
+/// InfluxDB replays the segment.
+#[derive(Debug)]
+pub struct WalReader<R>
+where
+    R: Read,
+{
+    r: R,
+    decoder: snap::raw::Decoder,
+    done: bool,
+}
+
+impl<R> WalReader<R>
+where
+    R: Read,
+{
+    pub fn new(r: R) -> Self {
+        Self {
+            r,
+            decoder: snap::raw::Decoder::new(),
+            done: false,
+        }
+    }
+
+    // Reads the next entry, returning `None` at the end of the segment.
+    fn next_entry(&mut self) -> Result<Option<WalEntry>, TsmError> {
+        let mut header = [0u8; 5];
+        if !read_exact_or_eof(&mut self.r, &mut header)? {
+            return Ok(None);
+        }
+
+        let entry_type = header[0];
+        let len = u32::from_be_bytes(header[1..].try_into().unwrap()) as usize;
+        let mut compressed = vec![0; len];
+        if !read_exact_or_eof(&mut self.r, &mut compressed)? {
+            return Ok(None);
+        }
+
+        let data = self
+            .decoder
+            .decompress_vec(&compressed)
+            .map_err(|e| TsmError {
+                description: format!("unable to decompress WAL entry: {}", e),
+            })?;
+
+        let entry = match entry_type {
+            WRITE_ENTRY_TYPE => decode_write_entry(&data)?,
+            DELETE_ENTRY_TYPE => WalEntry::Delete {
+                keys: data
+                    .split(|b| *b == b'\n')
+                    .filter(|k| !k.is_empty())
+                    .map(<[u8]>::to_vec)
+                    .collect(),
+            },
+            DELETE_RANGE_ENTRY_TYPE => decode_delete_range_entry(&data)?,
+            _ => {
+                return Err(TsmError {
+                    description: format!("{:?} is invalid WAL entry type", entry_type),
+                })
+            }
+        };
+        Ok(Some(entry))
+    }
+}
+
+impl<R: Read> Iterator for WalReader<R> {
+    type Item = Result<WalEntry, TsmError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let entry = self.next_entry().transpose();
+        if !matches!(entry, Some(Ok(_))) {
+            self.done = true;
+        }
+        entry
+    }
+}
+
+// Fills `buf` from `r`, returning false if the reader ended before `buf`
+// was filled.
+fn read_exact_or_eof(r: &mut impl Read, buf: &mut [u8]) -> Result<bool, TsmError> {
+    match r.read_exact(buf) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// A cursor over the bytes of a decompressed entry.
+struct EntryData<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> EntryData<'a> {
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], TsmError> {
+        if self.data.len() < n {
+            return Err(TsmError {
+                description: "WAL entry is truncated".into(),
+            });
+        }
+        let (taken, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, TsmError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, TsmError> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, TsmError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, TsmError> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn i64(&mut self) -> Result<i64, TsmError> {
+        Ok(self.u64()? as i64)
+    }
+}
+
+fn decode_write_entry(data: &[u8]) -> Result<WalEntry, TsmError> {
+    let mut data = EntryData { data };
+    let mut values = vec![];
+
+    while !data.is_empty() {
+        let value_type = data.u8()?;
+        let key_len = data.u16()? as usize;
+        let key = data.take(key_len)?.to_vec();
+        let count = data.u32()? as usize;
+
+        let mut pairs = Vec::with_capacity(count.min(MAX_BLOCK_VALUES));
+        for _ in 0..count {
+            let ts = data.i64()?;
+            let pair = match value_type {
+                FLOAT_VALUE_TYPE => ValuePair::F64((ts, f64::from_bits(data.u64()?))),
+                INTEGER_VALUE_TYPE => ValuePair::I64((ts, data.i64()?)),
+                BOOL_VALUE_TYPE => ValuePair::Bool((ts, data.u8()? == 1)),
+                STRING_VALUE_TYPE => {
+                    let len = data.u32()? as usize;
+                    ValuePair::Str((ts, data.take(len)?.to_vec()))
+                }
+                UNSIGNED_VALUE_TYPE => ValuePair::U64((ts, data.u64()?)),
+                _ => {
+                    return Err(TsmError {
+                        description: format!("{:?} is invalid WAL value type", value_type),
+                    })
+                }
+            };
+            pairs.push(pair);
+        }
+        values.push((key, pairs));
+    }
+
+    Ok(WalEntry::Write { values })
+}
+
+fn decode_delete_range_entry(data: &[u8]) -> Result<WalEntry, TsmError> {
+    let mut data = EntryData { data };
+    let min_time = data.i64()?;
+    let max_time = data.i64()?;
+
+    let mut keys = vec![];
+    while !data.is_empty() {
+        let len = data.u32()? as usize;
+        keys.push(data.take(len)?.to_vec());
+    }
+
+    Ok(WalEntry::DeleteRange {
+        keys,
+        min_time,
+        max_time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn encode_entry(entry_type: u8, data: &[u8]) -> Vec<u8> {
+        let compressed = snap::raw::Encoder::new().compress_vec(data).unwrap();
+        let mut buf = vec![entry_type];
+        buf.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&compressed);
+        buf
+    }
+
+    fn encode_values(buf: &mut Vec<u8>, value_type: u8, key: &[u8], values: Vec<(i64, Vec<u8>)>) {
+        buf.push(value_type);
+        buf.extend_from_slice(&(key.len() as u16).to_be_bytes());
+        buf.extend_from_slice(key);
+        buf.extend_from_slice(&(values.len() as u32).to_be_bytes());
+        for (ts, value) in values {
+            buf.extend_from_slice(&ts.to_be_bytes());
+            buf.extend_from_slice(&value);
+        }
+    }
+
+    #[test]
+    fn read_wal_entries() {
+        let mut write = vec![];
+        encode_values(
+            &mut write,
+            FLOAT_VALUE_TYPE,
+            b"cpu,host=a#!~#usage",
+            vec![
+                (1, 1.5_f64.to_bits().to_be_bytes().to_vec()),
+                (2, 2.5_f64.to_bits().to_be_bytes().to_vec()),
+            ],
+        );
+        encode_values(
+            &mut write,
+            INTEGER_VALUE_TYPE,
+            b"cpu,host=a#!~#count",
+            vec![(1, (-3_i64).to_be_bytes().to_vec())],
+        );
+        encode_values(
+            &mut write,
+            BOOL_VALUE_TYPE,
+            b"cpu#!~#up",
+            vec![(3, vec![1])],
+        );
+        encode_values(
+            &mut write,
+            STRING_VALUE_TYPE,
+            b"cpu#!~#msg",
+            vec![(4, vec![0, 0, 0, 2, b'h', b'i'])],
+        );
+        encode_values(
+            &mut write,
+            UNSIGNED_VALUE_TYPE,
+            b"cpu#!~#bytes",
+            vec![(5, 42_u64.to_be_bytes().to_vec())],
+        );
+
+        let mut delete_range = vec![];
+        delete_range.extend_from_slice(&10_i64.to_be_bytes());
+        delete_range.extend_from_slice(&20_i64.to_be_bytes());
+        delete_range.extend_from_slice(&9_u32.to_be_bytes());
+        delete_range.extend_from_slice(b"cpu#!~#up");
+
+        let mut segment = encode_entry(WRITE_ENTRY_TYPE, &write);
+        segment.extend(encode_entry(DELETE_ENTRY_TYPE, b"cpu#!~#msg\ncpu#!~#bytes"));
+        segment.extend(encode_entry(DELETE_RANGE_ENTRY_TYPE, &delete_range));
+
+        let entries = WalReader::new(Cursor::new(segment))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            entries,
+            vec![
+                WalEntry::Write {
+                    values: vec![
+                        (
+                            b"cpu,host=a#!~#usage".to_vec(),
+                            vec![ValuePair::F64((1, 1.5)), ValuePair::F64((2, 2.5))]
+                        ),
+                        (
+                            b"cpu,host=a#!~#count".to_vec(),
+                            vec![ValuePair::I64((1, -3))]
+                        ),
+                        (b"cpu#!~#up".to_vec(), vec![ValuePair::Bool((3, true))]),
+                        (
+                            b"cpu#!~#msg".to_vec(),
+                            vec![ValuePair::Str((4, b"hi".to_vec()))]
+                        ),
+                        (b"cpu#!~#bytes".to_vec(), vec![ValuePair::U64((5, 42))]),
+                    ]
+                },
+                WalEntry::Delete {
+                    keys: vec![b"cpu#!~#msg".to_vec(), b"cpu#!~#bytes".to_vec()]
+                },
+                WalEntry::DeleteRange {
+                    keys: vec![b"cpu#!~#up".to_vec()],
+                    min_time: 10,
+                    max_time: 20,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn truncated_segment() {
+        let mut write = vec![];
+        encode_values(
+            &mut write,
+            BOOL_VALUE_TYPE,
+            b"cpu#!~#up",
+            vec![(3, vec![0])],
+        );
+
+        let mut segment = encode_entry(WRITE_ENTRY_TYPE, &write);
+        let second = encode_entry(WRITE_ENTRY_TYPE, &write);
+        segment.extend_from_slice(&second[..second.len() - 1]);
+
+        let entries = WalReader::new(Cursor::new(segment))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[test]
+    fn invalid_entry_type() {
+        let segment = encode_entry(0x7f, b"");
+
+        let mut reader = WalReader::new(Cursor::new(segment));
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.description, "127 is invalid WAL entry type");
+        assert!(reader.next().is_none());
+    }
+}
@@ -1,12 +1,18 @@
 //! Data for the lifecycle of the Ingester
 
-use std::{collections::BTreeMap, pin::Pin, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    pin::Pin,
+    sync::Arc,
+};
 
 use arrow::{error::ArrowError, record_batch::RecordBatch};
 use arrow_util::optimize::{optimize_record_batch, optimize_schema};
 use async_trait::async_trait;
 use backoff::{Backoff, BackoffConfig};
-use data_types::{PartitionId, SequenceNumber, ShardId, ShardIndex};
+use data_types::{
+    NamespaceId, NamespacePersistencePolicy, PartitionId, SequenceNumber, ShardId, ShardIndex,
+};
 use datafusion::physical_plan::SendableRecordBatchStream;
 use dml::DmlOperation;
 use futures::{Stream, StreamExt};
@@ -196,6 +202,37 @@ impl IngesterData {
         }
         progresses
     }
+
+    /// Return the shard, namespace and table of every buffered partition
+    pub(crate) async fn partition_locations(&self) -> BTreeMap<PartitionId, PartitionLocation> {
+        let mut locations = BTreeMap::new();
+        for shard_data in self.shards.values() {
+            for (namespace, namespace_data) in shard_data.namespaces() {
+                for table_data in namespace_data.tables() {
+                    let table_data = table_data.read().await;
+                    for partition_id in table_data.partition_ids() {
+                        locations.insert(
+                            partition_id,
+                            PartitionLocation {
+                                shard_index: shard_data.shard_index(),
+                                namespace: namespace.clone(),
+                                table: Arc::clone(table_data.table_name()),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+        locations
+    }
+}
+
+/// The shard, namespace and table a buffered partition belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PartitionLocation {
+    pub(crate) shard_index: ShardIndex,
+    pub(crate) namespace: String,
+    pub(crate) table: Arc<str>,
 }
 
 /// The Persister has a function to persist a given partition ID and to update the
@@ -214,6 +251,15 @@ pub trait Persister: Send + Sync + 'static {
         shard_id: ShardId,
         sequence_number: SequenceNumber,
     );
+
+    /// Returns the persistence policies of the given namespaces, which
+    /// override any threshold of the
+    /// [`LifecycleConfig`](crate::lifecycle::LifecycleConfig), or `None` if
+    /// they cannot be loaded. Namespaces that do not exist are omitted.
+    async fn persistence_policies(
+        &self,
+        namespace_ids: &[NamespaceId],
+    ) -> Option<HashMap<NamespaceId, NamespacePersistencePolicy>>;
 }
 
 #[async_trait]
@@ -456,6 +502,27 @@ impl Persister for IngesterData {
             .await
             .expect("retry forever")
     }
+
+    async fn persistence_policies(
+        &self,
+        namespace_ids: &[NamespaceId],
+    ) -> Option<HashMap<NamespaceId, NamespacePersistencePolicy>> {
+        let mut repos = self.catalog.repositories().await;
+        let mut policies = HashMap::with_capacity(namespace_ids.len());
+        for &namespace_id in namespace_ids {
+            match repos.namespaces().get_by_id(namespace_id).await {
+                Ok(Some(namespace)) => {
+                    policies.insert(namespace_id, namespace.persistence_policy);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    warn!(%e, %namespace_id, "failed to load namespace persistence policy");
+                    return None;
+                }
+            }
+        }
+        Some(policies)
+    }
 }
 
 /// Stream of snapshots.
@@ -1513,6 +1580,12 @@ mod tests {
                 .tombstone_max_sequence_number(),
             Some(SequenceNumber::new(2)),
         );
+
+        // The delete is readable once its tombstone is in the catalog.
+        let expected_progress = ShardProgress::new()
+            .with_buffered(SequenceNumber::new(1))
+            .with_persisted(SequenceNumber::new(2));
+        assert_progress(&data, shard_index, expected_progress).await;
     }
 
     /// Verifies that the progress in data is the same as expected_progress
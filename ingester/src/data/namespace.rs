@@ -144,6 +144,7 @@ impl NamespaceData {
                                 sequence_number,
                                 b,
                                 partition_key.clone(),
+                                self.namespace_id,
                                 catalog,
                                 lifecycle_handle,
                             )
@@ -229,6 +230,12 @@ impl NamespaceData {
         t.get(table_name).cloned()
     }
 
+    /// Gets the buffered data of all tables
+    pub(crate) fn tables(&self) -> Vec<Arc<tokio::sync::RwLock<TableData>>> {
+        let t = self.tables.read();
+        t.values().cloned().collect()
+    }
+
     /// Inserts the table or returns it if it happens to be inserted by some other thread
     async fn insert_table(
         &self,
@@ -81,6 +81,14 @@ impl ShardData {
         n.get(namespace).cloned()
     }
 
+    /// Gets the data of all namespaces, by name
+    pub(crate) fn namespaces(&self) -> Vec<(String, Arc<NamespaceData>)> {
+        let n = self.namespaces.read();
+        n.iter()
+            .map(|(name, data)| (name.clone(), Arc::clone(data)))
+            .collect()
+    }
+
     /// Retrieves the namespace from the catalog and initializes an empty buffer, or
     /// retrieves the buffer if some other caller gets it first
     async fn insert_namespace(
@@ -127,7 +135,7 @@ impl ShardData {
     }
 
     /// Return the [`ShardIndex`] this [`ShardData`] is buffering for.
-    pub(super) fn shard_index(&self) -> ShardIndex {
+    pub(crate) fn shard_index(&self) -> ShardIndex {
         self.shard_index
     }
 }
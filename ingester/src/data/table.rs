@@ -2,7 +2,10 @@
 
 use std::{collections::BTreeMap, sync::Arc};
 
-use data_types::{DeletePredicate, PartitionKey, SequenceNumber, ShardId, TableId, Timestamp};
+use data_types::{
+    DeletePredicate, NamespaceId, PartitionId, PartitionKey, SequenceNumber, ShardId, TableId,
+    Timestamp,
+};
 use iox_catalog::interface::Catalog;
 use iox_query::exec::Executor;
 use mutable_batch::MutableBatch;
@@ -66,6 +69,7 @@ impl TableData {
         sequence_number: SequenceNumber,
         batch: MutableBatch,
         partition_key: PartitionKey,
+        namespace_id: NamespaceId,
         catalog: &dyn Catalog,
         lifecycle_handle: &dyn LifecycleHandle,
     ) -> Result<bool, super::Error> {
@@ -88,6 +92,7 @@ impl TableData {
         let should_pause = lifecycle_handle.log_write(
             partition_data.id(),
             self.shard_id,
+            namespace_id,
             sequence_number,
             batch.size(),
             batch.rows(),
@@ -177,13 +182,30 @@ impl TableData {
         Ok(())
     }
 
+    /// Return the IDs of the buffered partitions
+    pub(crate) fn partition_ids(&self) -> impl Iterator<Item = PartitionId> + '_ {
+        self.partition_data.values().map(|p| p.id())
+    }
+
+    /// Return the name of this table
+    pub(crate) fn table_name(&self) -> &Arc<str> {
+        &self.table_name
+    }
+
     /// Return progress from this Table
+    ///
+    /// Deletes are reported as persisted once their tombstone is recorded in
+    /// the catalog.
     pub(crate) fn progress(&self) -> ShardProgress {
         let progress = ShardProgress::new();
         let progress = match self.parquet_max_sequence_number() {
             Some(n) => progress.with_persisted(n),
             None => progress,
         };
+        let progress = match self.tombstone_max_sequence_number {
+            Some(n) => progress.with_persisted(n),
+            None => progress,
+        };
 
         self.partition_data
             .values()
@@ -4,7 +4,7 @@ use std::{collections::BTreeMap, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use backoff::BackoffConfig;
-use data_types::{Shard, ShardIndex, TopicMetadata};
+use data_types::{PartitionId, Shard, ShardIndex, TopicMetadata};
 use futures::{
     future::{BoxFuture, Shared},
     stream::FuturesUnordered,
@@ -28,7 +28,10 @@ use write_summary::ShardProgress;
 
 use crate::{
     data::{shard::ShardData, IngesterData, IngesterQueryResponse},
-    lifecycle::{run_lifecycle_manager, LifecycleConfig, LifecycleManager},
+    lifecycle::{
+        run_lifecycle_manager, LifecycleConfig, LifecycleHandleImpl, LifecycleManager,
+        PartitionLifecycleStats,
+    },
     poison::PoisonCabinet,
     querier_handler::prepare_data_to_querier,
     stream_handler::{
@@ -64,6 +67,25 @@ pub trait IngestHandler: Send + Sync {
         shard_indexes: Vec<ShardIndex>,
     ) -> BTreeMap<ShardIndex, ShardProgress>;
 
+    /// List the partitions with buffered data.
+    async fn buffered_partitions(&self) -> Vec<BufferedPartition>;
+
+    /// Persist the buffered partitions matching `selection` without waiting
+    /// for the lifecycle thresholds to be reached, returning their IDs.
+    ///
+    /// Persistence happens asynchronously: the partitions are no longer listed
+    /// by [`buffered_partitions`](Self::buffered_partitions) once persisted.
+    async fn persist(&self, selection: PersistSelection) -> Vec<PartitionId>;
+
+    /// Pause (or resume) consuming writes from the shard with the given index.
+    ///
+    /// Returns false if this ingester does not consume the shard.
+    fn set_shard_paused(&self, shard_index: ShardIndex, paused: bool) -> bool;
+
+    /// Return the indexes of the shards paused by
+    /// [`set_shard_paused`](Self::set_shard_paused).
+    fn paused_shards(&self) -> Vec<ShardIndex>;
+
     /// Wait until the handler finished  to shutdown.
     ///
     /// Use [`shutdown`](Self::shutdown) to trigger a shutdown.
@@ -73,6 +95,52 @@ pub trait IngestHandler: Send + Sync {
     fn shutdown(&self);
 }
 
+/// A partition with buffered data, as listed by
+/// [`IngestHandler::buffered_partitions`].
+#[derive(Debug, Clone)]
+pub struct BufferedPartition {
+    /// The shard the partition is buffered for.
+    pub shard_index: ShardIndex,
+    /// The namespace of the partition.
+    pub namespace: String,
+    /// The table of the partition.
+    pub table: String,
+    /// Time since the first write buffered for the partition.
+    pub age: Duration,
+    /// The lifecycle stats of the partition.
+    pub stats: PartitionLifecycleStats,
+}
+
+/// Selects the buffered partitions to persist with [`IngestHandler::persist`].
+///
+/// Every criterion that is set must match, so the default selection selects
+/// all buffered partitions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PersistSelection {
+    /// Only select the partitions of this namespace.
+    pub namespace: Option<String>,
+    /// Only select the partitions of this table.
+    pub table: Option<String>,
+    /// Only select the partition with this ID.
+    pub partition_id: Option<PartitionId>,
+}
+
+impl PersistSelection {
+    /// Returns true if `partition` is selected.
+    pub fn matches(&self, partition: &BufferedPartition) -> bool {
+        self.namespace
+            .as_ref()
+            .map_or(true, |namespace| *namespace == partition.namespace)
+            && self
+                .table
+                .as_ref()
+                .map_or(true, |table| *table == partition.table)
+            && self
+                .partition_id
+                .map_or(true, |id| id == partition.stats.partition_id)
+    }
+}
+
 /// A [`JoinHandle`] that can be cloned
 type SharedJoinHandle = Shared<BoxFuture<'static, Result<(), Arc<JoinError>>>>;
 
@@ -98,6 +166,10 @@ pub struct IngestHandlerImpl<T = SystemProvider> {
     /// The cache and buffered data for the ingester
     data: Arc<IngesterData>,
 
+    /// Handle to the lifecycle manager, used to administer persistence and
+    /// the consumption of shards.
+    lifecycle_handle: LifecycleHandleImpl,
+
     time_provider: T,
 
     /// Query execution duration distribution for successes.
@@ -264,6 +336,7 @@ impl IngestHandlerImpl {
 
         Ok(Self {
             data,
+            lifecycle_handle,
             topic,
             join_handles,
             shutdown,
@@ -360,6 +433,61 @@ impl IngestHandler for IngestHandlerImpl {
     ) -> BTreeMap<ShardIndex, ShardProgress> {
         self.data.progresses(shard_indexes).await
     }
+
+    async fn buffered_partitions(&self) -> Vec<BufferedPartition> {
+        let locations = self.data.partition_locations().await;
+        let now = self.time_provider.now();
+
+        self.lifecycle_handle
+            .partition_stats()
+            .into_iter()
+            .filter_map(|stats| {
+                let location = locations.get(&stats.partition_id)?;
+                Some(BufferedPartition {
+                    shard_index: location.shard_index,
+                    namespace: location.namespace.clone(),
+                    table: location.table.to_string(),
+                    age: now
+                        .checked_duration_since(stats.first_write)
+                        .unwrap_or_default(),
+                    stats,
+                })
+            })
+            .collect()
+    }
+
+    async fn persist(&self, selection: PersistSelection) -> Vec<PartitionId> {
+        let partition_ids: Vec<_> = self
+            .buffered_partitions()
+            .await
+            .into_iter()
+            .filter(|partition| selection.matches(partition))
+            .map(|partition| partition.stats.partition_id)
+            .collect();
+
+        let partition_ids = self.lifecycle_handle.request_persist(partition_ids);
+        info!(?selection, ?partition_ids, "persistence requested");
+        partition_ids
+    }
+
+    fn set_shard_paused(&self, shard_index: ShardIndex, paused: bool) -> bool {
+        let consumed = self
+            .data
+            .shards()
+            .any(|(_, shard_data)| shard_data.shard_index() == shard_index);
+        if consumed {
+            info!(
+                shard_index = shard_index.get(),
+                paused, "shard pause requested"
+            );
+            self.lifecycle_handle.set_shard_paused(shard_index, paused);
+        }
+        consumed
+    }
+
+    fn paused_shards(&self) -> Vec<ShardIndex> {
+        self.lifecycle_handle.paused_shards()
+    }
 }
 
 impl<T> Drop for IngestHandlerImpl<T> {
@@ -900,6 +1028,100 @@ mod tests {
         assert!(matches!(res, crate::querier_handler::Error::RequestLimit));
     }
 
+    #[tokio::test]
+    async fn administer_buffered_partitions() {
+        let ingester = TestIngester::new().await;
+
+        let schema = NamespaceSchema::new(
+            ingester.namespace.id,
+            ingester.topic.id,
+            ingester.query_pool.id,
+        );
+        let mut txn = ingester.catalog.start_transaction().await.unwrap();
+        let write = DmlWrite::new(
+            "foo",
+            lines_to_batches("cpu bar=1 10\nmem foo=2 20", 0).unwrap(),
+            Some("1970-01-01".into()),
+            DmlMeta::sequenced(
+                Sequence::new(ShardIndex::new(0), SequenceNumber::new(3)),
+                Time::from_timestamp_millis(42),
+                None,
+                50,
+            ),
+        );
+        validate_or_insert_schema(write.tables(), &schema, txn.deref_mut())
+            .await
+            .unwrap();
+        txn.commit().await.unwrap();
+        ingester.write_buffer_state.push_write(write);
+
+        let partitions = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                let partitions = ingester.ingester.buffered_partitions().await;
+                if partitions.len() == 2 {
+                    break partitions;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("timeout");
+
+        let mut tables: Vec<_> = partitions.iter().map(|p| p.table.as_str()).collect();
+        tables.sort_unstable();
+        assert_eq!(tables, vec!["cpu", "mem"]);
+        for partition in &partitions {
+            assert_eq!(partition.shard_index, ShardIndex::new(0));
+            assert_eq!(partition.namespace, "foo");
+            assert_eq!(
+                partition.stats.first_sequence_number,
+                SequenceNumber::new(3)
+            );
+            assert_eq!(partition.stats.last_sequence_number, SequenceNumber::new(3));
+        }
+
+        // persisting an unknown table selects nothing
+        let selection = PersistSelection {
+            namespace: Some("foo".to_string()),
+            table: Some("disk".to_string()),
+            partition_id: None,
+        };
+        assert!(ingester.ingester.persist(selection).await.is_empty());
+
+        let selection = PersistSelection {
+            namespace: Some("foo".to_string()),
+            table: Some("cpu".to_string()),
+            partition_id: None,
+        };
+        let cpu = partitions.iter().find(|p| p.table == "cpu").unwrap();
+        assert_eq!(
+            ingester.ingester.persist(selection).await,
+            vec![cpu.stats.partition_id]
+        );
+
+        // the lifecycle manager persists the partition on its next run
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let partitions = ingester.ingester.buffered_partitions().await;
+                if partitions.iter().all(|p| p.table != "cpu") {
+                    assert_eq!(partitions.len(), 1);
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("timeout");
+
+        assert!(ingester.ingester.set_shard_paused(ShardIndex::new(0), true));
+        assert_eq!(ingester.ingester.paused_shards(), vec![ShardIndex::new(0)]);
+        assert!(!ingester.ingester.set_shard_paused(ShardIndex::new(5), true));
+        assert!(ingester
+            .ingester
+            .set_shard_paused(ShardIndex::new(0), false));
+        assert!(ingester.ingester.paused_shards().is_empty());
+    }
+
     struct TestIngester {
         catalog: Arc<dyn Catalog>,
         shard: Shard,
@@ -5,9 +5,15 @@
 //! some absolute number and individual Parquet files that get persisted below some number. It
 //! is expected that they may be above or below the absolute thresholds.
 
-use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+    time::Duration,
+};
 
-use data_types::{PartitionId, SequenceNumber, ShardId};
+use data_types::{
+    NamespaceId, NamespacePersistencePolicy, PartitionId, SequenceNumber, ShardId, ShardIndex,
+};
 use iox_time::{Time, TimeProvider};
 use metric::{Metric, U64Counter};
 use observability_deps::tracing::{error, info, warn};
@@ -30,6 +36,7 @@ pub trait LifecycleHandle: Send + Sync + 'static {
         &self,
         partition_id: PartitionId,
         shard_id: ShardId,
+        namespace_id: NamespaceId,
         sequence_number: SequenceNumber,
         bytes_written: usize,
         rows_written: usize,
@@ -60,6 +67,7 @@ impl LifecycleHandle for LifecycleHandleImpl {
         &self,
         partition_id: PartitionId,
         shard_id: ShardId,
+        namespace_id: NamespaceId,
         sequence_number: SequenceNumber,
         bytes_written: usize,
         rows_written: usize,
@@ -72,17 +80,21 @@ impl LifecycleHandle for LifecycleHandleImpl {
                 .entry(partition_id)
                 .or_insert_with(|| PartitionLifecycleStats {
                     shard_id,
+                    namespace_id,
                     partition_id,
                     first_write: now,
                     last_write: now,
                     bytes_written: 0,
                     rows_written: 0,
                     first_sequence_number: sequence_number,
+                    last_sequence_number: sequence_number,
+                    persist_requested: false,
                 });
 
         stats.bytes_written += bytes_written;
         stats.last_write = now;
         stats.rows_written += rows_written;
+        stats.last_sequence_number = stats.last_sequence_number.max(sequence_number);
 
         s.total_bytes += bytes_written;
 
@@ -96,6 +108,67 @@ impl LifecycleHandle for LifecycleHandleImpl {
     }
 }
 
+impl LifecycleHandleImpl {
+    /// Returns a point in time snapshot of the stats of every buffered
+    /// partition.
+    pub fn partition_stats(&self) -> Vec<PartitionLifecycleStats> {
+        let s = self.state.lock();
+        s.partition_stats.values().cloned().collect()
+    }
+
+    /// Requests the given partitions to be persisted by the next run of the
+    /// [`LifecycleManager`], regardless of their size and age.
+    ///
+    /// Returns the IDs of the partitions that are buffered and thus will be
+    /// persisted.
+    pub fn request_persist(
+        &self,
+        partition_ids: impl IntoIterator<Item = PartitionId>,
+    ) -> Vec<PartitionId> {
+        let mut s = self.state.lock();
+        partition_ids
+            .into_iter()
+            .filter(
+                |partition_id| match s.partition_stats.get_mut(partition_id) {
+                    Some(stats) => {
+                        stats.persist_requested = true;
+                        true
+                    }
+                    None => false,
+                },
+            )
+            .collect()
+    }
+
+    /// Pauses (or resumes) consuming writes from the shard with the given
+    /// index.
+    ///
+    /// Unlike the pauses the [`LifecycleManager`] applies to control memory
+    /// pressure, the shard stays paused until it is resumed explicitly.
+    pub fn set_shard_paused(&self, shard_index: ShardIndex, paused: bool) {
+        let mut s = self.state.lock();
+        if paused {
+            s.paused_shards.insert(shard_index);
+        } else {
+            s.paused_shards.remove(&shard_index);
+        }
+    }
+
+    /// Returns true if consuming writes from the shard with the given index
+    /// was paused by [`set_shard_paused`](Self::set_shard_paused).
+    pub fn is_shard_paused(&self, shard_index: ShardIndex) -> bool {
+        let s = self.state.lock();
+        s.paused_shards.contains(&shard_index)
+    }
+
+    /// Returns the indexes of the shards paused by
+    /// [`set_shard_paused`](Self::set_shard_paused).
+    pub fn paused_shards(&self) -> Vec<ShardIndex> {
+        let s = self.state.lock();
+        s.paused_shards.iter().copied().collect()
+    }
+}
+
 /// The lifecycle manager keeps track of the size and age of partitions across
 /// all shards. It triggers persistence based on keeping total memory usage
 /// around a set amount while ensuring that partitions don't get too old or
@@ -135,6 +208,14 @@ pub struct LifecycleManager {
     /// Counter tracking the number of times a partition has been evicted for
     /// containing too many rows.
     persist_rows_counter: U64Counter,
+    /// Counter for persistence requested through
+    /// [`LifecycleHandleImpl::request_persist()`].
+    persist_requested_counter: U64Counter,
+    /// The persistence policies of the namespaces with buffered data, which
+    /// override any of the partition thresholds of the [`LifecycleConfig`].
+    persistence_policies: HashMap<NamespaceId, NamespacePersistencePolicy>,
+    /// When the `persistence_policies` were last reloaded.
+    persistence_policies_loaded: Option<Time>,
 }
 
 /// The configuration options for the lifecycle on the ingester.
@@ -202,6 +283,7 @@ impl LifecycleConfig {
 struct LifecycleState {
     total_bytes: usize,
     partition_stats: BTreeMap<PartitionId, PartitionLifecycleStats>,
+    paused_shards: BTreeSet<ShardIndex>,
 }
 
 impl LifecycleState {
@@ -222,25 +304,33 @@ struct LifecycleStats {
 
 /// The stats for a partition
 #[derive(Debug, Clone, Copy)]
-struct PartitionLifecycleStats {
+pub struct PartitionLifecycleStats {
     /// The shard this partition is under
-    shard_id: ShardId,
+    pub shard_id: ShardId,
+    /// The namespace this partition belongs to
+    pub namespace_id: NamespaceId,
     /// The partition identifier
-    partition_id: PartitionId,
+    pub partition_id: PartitionId,
     /// Time that the partition received its first write. This is reset anytime
     /// the partition is persisted.
-    first_write: Time,
+    pub first_write: Time,
     /// Time that the partition received its last write. This is reset anytime
     /// the partition is persisted.
-    last_write: Time,
+    pub last_write: Time,
     /// The number of bytes in the partition as estimated by the mutable batch sizes.
-    bytes_written: usize,
+    pub bytes_written: usize,
     /// The number of rows in the partition as estimated by the mutable batch
     /// sizes + snapshots.
-    rows_written: usize,
+    pub rows_written: usize,
     /// The sequence number the partition received on its first write. This is reset anytime
     /// the partition is persisted.
-    first_sequence_number: SequenceNumber,
+    pub first_sequence_number: SequenceNumber,
+    /// The highest sequence number the partition received. This is reset anytime the
+    /// partition is persisted.
+    pub last_sequence_number: SequenceNumber,
+    /// Whether persistence of the partition was requested through
+    /// [`LifecycleHandleImpl::request_persist()`].
+    pub persist_requested: bool,
 }
 
 impl LifecycleManager {
@@ -261,6 +351,7 @@ impl LifecycleManager {
         let persist_age_counter = persist_counter.recorder(&[("trigger", "age")]);
         let persist_cold_counter = persist_counter.recorder(&[("trigger", "cold")]);
         let persist_rows_counter = persist_counter.recorder(&[("trigger", "rows")]);
+        let persist_requested_counter = persist_counter.recorder(&[("trigger", "requested")]);
 
         let job_registry = Arc::new(JobRegistry::new(
             metric_registry,
@@ -276,6 +367,9 @@ impl LifecycleManager {
             persist_age_counter,
             persist_cold_counter,
             persist_rows_counter,
+            persist_requested_counter,
+            persistence_policies: Default::default(),
+            persistence_policies_loaded: None,
         }
     }
 
@@ -298,6 +392,9 @@ impl LifecycleManager {
             partition_stats,
         } = self.stats();
 
+        self.maybe_load_persistence_policies(persister, &partition_stats)
+            .await;
+
         // get anything over the threshold size or age to persist
         let now = self.time_provider.now();
 
@@ -310,10 +407,30 @@ impl LifecycleManager {
             // consistent fields across all trigger types.
             //
 
+            // The persistence policy of the namespace overrides the
+            // partition thresholds of the config.
+            let policy = self
+                .persistence_policies
+                .get(&s.namespace_id)
+                .copied()
+                .unwrap_or_default();
+            let partition_age_threshold = policy
+                .partition_age_threshold()
+                .unwrap_or(self.config.partition_age_threshold);
+            let partition_cold_threshold = policy
+                .partition_cold_threshold()
+                .unwrap_or(self.config.partition_cold_threshold);
+            let partition_row_max = policy
+                .partition_row_max()
+                .unwrap_or(self.config.partition_row_max);
+            let partition_size_threshold = policy
+                .partition_size_threshold()
+                .unwrap_or(self.config.partition_size_threshold);
+
             // Check if this partition's first write occurred long enough ago
             // that the data is considered "old" and can be flushed.
             let aged_out = match now.checked_duration_since(s.first_write) {
-                Some(age) if age > self.config.partition_age_threshold => {
+                Some(age) if age > partition_age_threshold => {
                     info!(
                         shard_id=%s.shard_id,
                         partition_id=%s.partition_id,
@@ -343,7 +460,7 @@ impl LifecycleManager {
             // that the partition is considered "cold" and is unlikely to see
             // new writes imminently.
             let is_cold = match now.checked_duration_since(s.last_write) {
-                Some(age) if age > self.config.partition_cold_threshold => {
+                Some(age) if age > partition_cold_threshold => {
                     info!(
                         shard_id=%s.shard_id,
                         partition_id=%s.partition_id,
@@ -371,7 +488,7 @@ impl LifecycleManager {
 
             // If this partition contains more rows than it is permitted, flush
             // it.
-            let exceeded_max_rows = s.rows_written >= self.config.partition_row_max;
+            let exceeded_max_rows = s.rows_written >= partition_row_max;
             if exceeded_max_rows {
                 info!(
                     shard_id=%s.shard_id,
@@ -388,7 +505,7 @@ impl LifecycleManager {
 
             // If the partition's in-memory buffer is larger than the configured
             // maximum byte size, flush it.
-            let sized_out = s.bytes_written > self.config.partition_size_threshold;
+            let sized_out = s.bytes_written > partition_size_threshold;
             if sized_out {
                 info!(
                     shard_id=%s.shard_id,
@@ -403,7 +520,22 @@ impl LifecycleManager {
                 self.persist_size_counter.inc(1);
             }
 
-            aged_out || sized_out || is_cold || exceeded_max_rows
+            // Persist the partition if an operator asked for it.
+            if s.persist_requested {
+                info!(
+                    shard_id=%s.shard_id,
+                    partition_id=%s.partition_id,
+                    first_write=%s.first_write,
+                    last_write=%s.last_write,
+                    bytes_written=s.bytes_written,
+                    rows_written=s.rows_written,
+                    first_sequence_number=?s.first_sequence_number,
+                    "partition persistence requested, persisting"
+                );
+                self.persist_requested_counter.inc(1);
+            }
+
+            aged_out || sized_out || is_cold || exceeded_max_rows || s.persist_requested
         });
 
         // keep track of what we'll be evicting to see what else to drop
@@ -514,6 +646,55 @@ impl LifecycleManager {
         }
     }
 
+    /// Loads the persistence policies of the namespaces of the buffered
+    /// `partition_stats` from `persister`.
+    ///
+    /// All policies are reloaded every [`PERSISTENCE_POLICY_LOAD_INTERVAL`],
+    /// in between only those of namespaces that started buffering data are
+    /// loaded. The previous policies stay in effect if loading fails.
+    async fn maybe_load_persistence_policies<P: Persister>(
+        &mut self,
+        persister: &Arc<P>,
+        partition_stats: &[PartitionLifecycleStats],
+    ) {
+        let now = self.time_provider.now();
+        let due = match self.persistence_policies_loaded {
+            Some(loaded) => now
+                .checked_duration_since(loaded)
+                .map_or(true, |d| d >= PERSISTENCE_POLICY_LOAD_INTERVAL),
+            None => true,
+        };
+
+        let buffered: BTreeSet<_> = partition_stats.iter().map(|s| s.namespace_id).collect();
+        if due {
+            self.persistence_policies
+                .retain(|namespace_id, _| buffered.contains(namespace_id));
+            self.persistence_policies_loaded = Some(now);
+        }
+        let to_load: Vec<_> = buffered
+            .into_iter()
+            .filter(|namespace_id| due || !self.persistence_policies.contains_key(namespace_id))
+            .collect();
+        if to_load.is_empty() {
+            return;
+        }
+
+        match persister.persistence_policies(&to_load).await {
+            Some(mut policies) => {
+                for namespace_id in to_load {
+                    let policy = policies.remove(&namespace_id).unwrap_or_default();
+                    self.persistence_policies.insert(namespace_id, policy);
+                }
+            }
+            None => {
+                // Fall back to the config until the next reload.
+                for namespace_id in to_load {
+                    self.persistence_policies.entry(namespace_id).or_default();
+                }
+            }
+        }
+    }
+
     /// Returns a point in time snapshot of the lifecycle state.
     fn stats(&self) -> LifecycleStats {
         let s = self.state.lock();
@@ -534,6 +715,10 @@ impl LifecycleManager {
 
 const CHECK_INTERVAL: Duration = Duration::from_secs(1);
 
+/// How often the persistence policies of the namespaces are loaded, so that
+/// changes in the catalog are picked up by a running ingester.
+const PERSISTENCE_POLICY_LOAD_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Runs the lifecycle manager to trigger persistence every second.
 pub(crate) async fn run_lifecycle_manager<P: Persister>(
     mut manager: LifecycleManager,
@@ -581,6 +766,8 @@ mod tests {
     struct TestPersister {
         persist_called: Mutex<BTreeSet<PartitionId>>,
         update_min_calls: Mutex<Vec<(ShardId, SequenceNumber)>>,
+        persistence_policies: Mutex<HashMap<NamespaceId, NamespacePersistencePolicy>>,
+        policy_loads: Mutex<Vec<Vec<NamespaceId>>>,
     }
 
     #[async_trait]
@@ -598,6 +785,20 @@ mod tests {
             let mut u = self.update_min_calls.lock();
             u.push((shard_id, sequence_number));
         }
+
+        async fn persistence_policies(
+            &self,
+            namespace_ids: &[NamespaceId],
+        ) -> Option<HashMap<NamespaceId, NamespacePersistencePolicy>> {
+            self.policy_loads.lock().push(namespace_ids.to_vec());
+            let p = self.persistence_policies.lock();
+            Some(
+                namespace_ids
+                    .iter()
+                    .filter_map(|id| p.get(id).map(|policy| (*id, *policy)))
+                    .collect(),
+            )
+        }
     }
 
     impl TestPersister {
@@ -610,6 +811,20 @@ mod tests {
             let u = self.update_min_calls.lock();
             u.clone()
         }
+
+        fn set_persistence_policy(
+            &self,
+            namespace_id: NamespaceId,
+            policy: NamespacePersistencePolicy,
+        ) {
+            let mut p = self.persistence_policies.lock();
+            p.insert(namespace_id, policy);
+        }
+
+        fn policy_loads(&self) -> Vec<Vec<NamespaceId>> {
+            let l = self.policy_loads.lock();
+            l.clone()
+        }
     }
 
     #[derive(Debug, Clone)]
@@ -662,6 +877,13 @@ mod tests {
                 .update_min_unpersisted_sequence_number(shard_id, sequence_number)
                 .await
         }
+
+        async fn persistence_policies(
+            &self,
+            namespace_ids: &[NamespaceId],
+        ) -> Option<HashMap<NamespaceId, NamespacePersistencePolicy>> {
+            self.inner.persistence_policies(namespace_ids).await
+        }
     }
 
     impl PausablePersister {
@@ -714,14 +936,33 @@ mod tests {
         let h = m.handle();
 
         // log first two writes at different times
-        assert!(!h.log_write(PartitionId::new(1), shard_id, SequenceNumber::new(1), 1, 1));
+        assert!(!h.log_write(
+            PartitionId::new(1),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(1),
+            1,
+            1
+        ));
         time_provider.inc(Duration::from_nanos(10));
-        assert!(!h.log_write(PartitionId::new(1), shard_id, SequenceNumber::new(2), 1, 1));
+        assert!(!h.log_write(
+            PartitionId::new(1),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(2),
+            1,
+            1
+        ));
 
         // log another write for different partition using a different handle
-        assert!(!m
-            .handle()
-            .log_write(PartitionId::new(2), shard_id, SequenceNumber::new(3), 3, 3));
+        assert!(!m.handle().log_write(
+            PartitionId::new(2),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(3),
+            3,
+            3
+        ));
 
         let stats = m.stats();
         assert_eq!(stats.total_bytes, 5);
@@ -755,11 +996,25 @@ mod tests {
         let h = m.handle();
 
         // write more than the limit (10)
-        assert!(h.log_write(partition_id, shard_id, SequenceNumber::new(1), 15, 1));
+        assert!(h.log_write(
+            partition_id,
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(1),
+            15,
+            1
+        ));
         assert!(!h.can_resume_ingest());
 
         // all subsequent writes should also indicate a pause
-        assert!(h.log_write(partition_id, shard_id, SequenceNumber::new(2), 1, 1));
+        assert!(h.log_write(
+            partition_id,
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(2),
+            1,
+            1
+        ));
         assert!(!h.can_resume_ingest());
 
         // persist the partition
@@ -768,7 +1023,14 @@ mod tests {
 
         // ingest can resume
         assert!(h.can_resume_ingest());
-        assert!(!h.log_write(partition_id, shard_id, SequenceNumber::new(3), 3, 1));
+        assert!(!h.log_write(
+            partition_id,
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(3),
+            3,
+            1
+        ));
     }
 
     #[tokio::test]
@@ -794,7 +1056,14 @@ mod tests {
 
         // write more than the limit (10) and don't get stopped, because the
         // per-partition limit does not pause the server from ingesting.
-        assert!(!h.log_write(partition_id, shard_id, SequenceNumber::new(1), 1, 50));
+        assert!(!h.log_write(
+            partition_id,
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(1),
+            1,
+            50
+        ));
         assert!(h.can_resume_ingest());
 
         // Rows were counted
@@ -808,7 +1077,14 @@ mod tests {
         }
 
         // all subsequent writes should also be allowed
-        assert!(!h.log_write(partition_id, shard_id, SequenceNumber::new(2), 1, 1));
+        assert!(!h.log_write(
+            partition_id,
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(2),
+            1,
+            1
+        ));
         assert!(h.can_resume_ingest());
 
         // persist the partition
@@ -823,7 +1099,14 @@ mod tests {
 
         // ingest can continue
         assert!(h.can_resume_ingest());
-        assert!(!h.log_write(partition_id, shard_id, SequenceNumber::new(3), 1, 1));
+        assert!(!h.log_write(
+            partition_id,
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(3),
+            1,
+            1
+        ));
     }
 
     #[tokio::test]
@@ -842,7 +1125,14 @@ mod tests {
         let h = m.handle();
 
         // write more than the limit (20)
-        h.log_write(partition_id, shard_id, SequenceNumber::new(1), 25, 1);
+        h.log_write(
+            partition_id,
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(1),
+            25,
+            1,
+        );
 
         // can not resume ingest as we are overall the pause ingest limit
         assert!(!h.can_resume_ingest());
@@ -870,7 +1160,14 @@ mod tests {
 
         // ingest can resume
         assert!(h.can_resume_ingest());
-        assert!(!h.log_write(partition_id, shard_id, SequenceNumber::new(2), 3, 1));
+        assert!(!h.log_write(
+            partition_id,
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(2),
+            3,
+            1
+        ));
     }
 
     #[tokio::test]
@@ -893,7 +1190,14 @@ mod tests {
         let shard_id = ShardId::new(1);
         let h = m.handle();
 
-        h.log_write(partition_id, shard_id, SequenceNumber::new(1), 10, 1);
+        h.log_write(
+            partition_id,
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(1),
+            10,
+            1,
+        );
 
         m.maybe_persist(&persister).await;
         let stats = m.stats();
@@ -908,7 +1212,14 @@ mod tests {
 
         // write in data for a new partition so we can be sure it isn't persisted, but the older
         // one is
-        h.log_write(PartitionId::new(2), shard_id, SequenceNumber::new(2), 6, 1);
+        h.log_write(
+            PartitionId::new(2),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(2),
+            6,
+            1,
+        );
 
         m.maybe_persist(&persister).await;
 
@@ -928,6 +1239,223 @@ mod tests {
         assert_eq!(age_counter, 1);
     }
 
+    #[tokio::test]
+    async fn persists_on_request() {
+        let config = LifecycleConfig {
+            pause_ingest_size: 30,
+            persist_memory_threshold: 20,
+            partition_size_threshold: 10,
+            partition_age_threshold: Duration::from_secs(500),
+            partition_cold_threshold: Duration::from_secs(500),
+            partition_row_max: 100,
+        };
+        let TestLifecycleManger {
+            mut m,
+            metric_registry,
+            ..
+        } = TestLifecycleManger::new(config);
+        let persister = Arc::new(TestPersister::default());
+        let shard_id = ShardId::new(1);
+        let h = m.handle();
+
+        h.log_write(
+            PartitionId::new(1),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(1),
+            5,
+            1,
+        );
+        h.log_write(
+            PartitionId::new(2),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(2),
+            5,
+            1,
+        );
+        h.log_write(
+            PartitionId::new(1),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(3),
+            5,
+            1,
+        );
+
+        let stats = h.partition_stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].first_sequence_number, SequenceNumber::new(1));
+        assert_eq!(stats[0].last_sequence_number, SequenceNumber::new(3));
+        assert_eq!(stats[0].bytes_written, 10);
+        assert_eq!(stats[0].rows_written, 2);
+
+        // nothing is over a threshold
+        m.maybe_persist(&persister).await;
+        assert!(!persister.persist_called_for(PartitionId::new(1)));
+
+        // only buffered partitions can be requested
+        let requested = h.request_persist([PartitionId::new(1), PartitionId::new(3)]);
+        assert_eq!(requested, vec![PartitionId::new(1)]);
+
+        m.maybe_persist(&persister).await;
+        assert!(persister.persist_called_for(PartitionId::new(1)));
+        assert!(!persister.persist_called_for(PartitionId::new(2)));
+
+        let stats = m.stats();
+        assert_eq!(stats.total_bytes, 5);
+        assert_eq!(stats.partition_stats.len(), 1);
+        assert_eq!(stats.partition_stats[0].partition_id, PartitionId::new(2));
+
+        let requested_counter = get_counter(&metric_registry, "requested");
+        assert_eq!(requested_counter, 1);
+    }
+
+    #[tokio::test]
+    async fn persists_based_on_namespace_policy() {
+        let config = LifecycleConfig {
+            pause_ingest_size: 100,
+            persist_memory_threshold: 50,
+            partition_size_threshold: 20,
+            partition_age_threshold: Duration::from_secs(500),
+            partition_cold_threshold: Duration::from_secs(500),
+            partition_row_max: 100,
+        };
+        let TestLifecycleManger {
+            mut m,
+            time_provider,
+            metric_registry,
+        } = TestLifecycleManger::new(config);
+        let persister = Arc::new(TestPersister::default());
+        let shard_id = ShardId::new(1);
+        let h = m.handle();
+
+        // namespace 1 wants small and fresh files, namespace 3 large ones
+        persister.set_persistence_policy(
+            NamespaceId::new(1),
+            NamespacePersistencePolicy {
+                partition_age_threshold_seconds: Some(10),
+                partition_size_threshold_bytes: Some(5),
+                ..Default::default()
+            },
+        );
+        persister.set_persistence_policy(
+            NamespaceId::new(3),
+            NamespacePersistencePolicy {
+                partition_size_threshold_bytes: Some(1000),
+                ..Default::default()
+            },
+        );
+
+        h.log_write(
+            PartitionId::new(1),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(1),
+            10,
+            1,
+        );
+        h.log_write(
+            PartitionId::new(2),
+            shard_id,
+            NamespaceId::new(2),
+            SequenceNumber::new(2),
+            10,
+            1,
+        );
+
+        m.maybe_persist(&persister).await;
+        assert!(persister.persist_called_for(PartitionId::new(1)));
+        assert!(!persister.persist_called_for(PartitionId::new(2)));
+        assert_eq!(get_counter(&metric_registry, "size"), 1);
+
+        h.log_write(
+            PartitionId::new(3),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(3),
+            1,
+            1,
+        );
+        time_provider.inc(Duration::from_secs(11));
+
+        m.maybe_persist(&persister).await;
+        assert!(persister.persist_called_for(PartitionId::new(3)));
+        assert!(!persister.persist_called_for(PartitionId::new(2)));
+        assert_eq!(get_counter(&metric_registry, "age"), 1);
+
+        // changed policies are picked up once they are loaded again
+        persister.set_persistence_policy(
+            NamespaceId::new(2),
+            NamespacePersistencePolicy {
+                partition_size_threshold_bytes: Some(5),
+                ..Default::default()
+            },
+        );
+        m.maybe_persist(&persister).await;
+        assert!(!persister.persist_called_for(PartitionId::new(2)));
+
+        time_provider.inc(PERSISTENCE_POLICY_LOAD_INTERVAL);
+        m.maybe_persist(&persister).await;
+        assert!(persister.persist_called_for(PartitionId::new(2)));
+        assert_eq!(get_counter(&metric_registry, "size"), 2);
+
+        // memory pressure overrides the thresholds of a namespace
+        h.log_write(
+            PartitionId::new(4),
+            shard_id,
+            NamespaceId::new(3),
+            SequenceNumber::new(4),
+            60,
+            1,
+        );
+        m.maybe_persist(&persister).await;
+        assert!(persister.persist_called_for(PartitionId::new(4)));
+        assert_eq!(get_counter(&metric_registry, "size"), 2);
+        assert_eq!(get_counter(&metric_registry, "memory"), 1);
+        assert_eq!(m.stats().total_bytes, 0);
+
+        // only the policies of namespaces with buffered data were loaded, new
+        // namespaces as soon as they buffered data
+        assert_eq!(
+            persister.policy_loads(),
+            vec![
+                vec![NamespaceId::new(1), NamespaceId::new(2)],
+                vec![NamespaceId::new(2)],
+                vec![NamespaceId::new(3)],
+            ]
+        );
+    }
+
+    #[test]
+    fn pause_shard() {
+        let config = LifecycleConfig {
+            pause_ingest_size: 30,
+            persist_memory_threshold: 20,
+            partition_size_threshold: 10,
+            partition_age_threshold: Duration::from_secs(500),
+            partition_cold_threshold: Duration::from_secs(500),
+            partition_row_max: 100,
+        };
+        let TestLifecycleManger { m, .. } = TestLifecycleManger::new(config);
+        let h = m.handle();
+        let shard_index = ShardIndex::new(1);
+
+        assert!(!h.is_shard_paused(shard_index));
+
+        h.set_shard_paused(shard_index, true);
+        assert!(h.is_shard_paused(shard_index));
+        assert!(!h.is_shard_paused(ShardIndex::new(2)));
+        assert_eq!(h.paused_shards(), vec![shard_index]);
+
+        // the pause is independent of memory pressure
+        assert!(h.can_resume_ingest());
+
+        h.set_shard_paused(shard_index, false);
+        assert!(!h.is_shard_paused(shard_index));
+        assert!(h.paused_shards().is_empty());
+    }
+
     #[tokio::test]
     async fn persists_based_on_age_with_multiple_unpersisted() {
         let config = LifecycleConfig {
@@ -948,7 +1476,14 @@ mod tests {
         let shard_id = ShardId::new(1);
         let h = m.handle();
 
-        h.log_write(partition_id, shard_id, SequenceNumber::new(1), 10, 1);
+        h.log_write(
+            partition_id,
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(1),
+            10,
+            1,
+        );
 
         m.maybe_persist(&persister).await;
         let stats = m.stats();
@@ -963,8 +1498,22 @@ mod tests {
 
         // write in data for a new partition so we can be sure it isn't persisted, but the older
         // one is
-        h.log_write(PartitionId::new(2), shard_id, SequenceNumber::new(2), 6, 1);
-        h.log_write(PartitionId::new(3), shard_id, SequenceNumber::new(3), 7, 1);
+        h.log_write(
+            PartitionId::new(2),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(2),
+            6,
+            1,
+        );
+        h.log_write(
+            PartitionId::new(3),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(3),
+            7,
+            1,
+        );
 
         m.maybe_persist(&persister).await;
 
@@ -1005,7 +1554,14 @@ mod tests {
 
         let partition_id = PartitionId::new(1);
         let persister = Arc::new(TestPersister::default());
-        h.log_write(partition_id, shard_id, SequenceNumber::new(1), 4, 1);
+        h.log_write(
+            partition_id,
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(1),
+            4,
+            1,
+        );
 
         m.maybe_persist(&persister).await;
 
@@ -1015,8 +1571,22 @@ mod tests {
         assert!(!persister.persist_called_for(partition_id));
 
         // introduce a new partition under the limit to verify it doesn't get taken with the other
-        h.log_write(PartitionId::new(2), shard_id, SequenceNumber::new(2), 3, 1);
-        h.log_write(partition_id, shard_id, SequenceNumber::new(3), 5, 1);
+        h.log_write(
+            PartitionId::new(2),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(2),
+            3,
+            1,
+        );
+        h.log_write(
+            partition_id,
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(3),
+            5,
+            1,
+        );
 
         m.maybe_persist(&persister).await;
 
@@ -1055,8 +1625,22 @@ mod tests {
         let h = m.handle();
         let partition_id = PartitionId::new(1);
         let persister = Arc::new(TestPersister::default());
-        h.log_write(partition_id, shard_id, SequenceNumber::new(1), 8, 1);
-        h.log_write(PartitionId::new(2), shard_id, SequenceNumber::new(2), 13, 1);
+        h.log_write(
+            partition_id,
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(1),
+            8,
+            1,
+        );
+        h.log_write(
+            PartitionId::new(2),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(2),
+            13,
+            1,
+        );
 
         m.maybe_persist(&persister).await;
 
@@ -1072,8 +1656,22 @@ mod tests {
         );
 
         // add that partition back in over size
-        h.log_write(partition_id, shard_id, SequenceNumber::new(3), 20, 1);
-        h.log_write(PartitionId::new(2), shard_id, SequenceNumber::new(4), 21, 1);
+        h.log_write(
+            partition_id,
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(3),
+            20,
+            1,
+        );
+        h.log_write(
+            PartitionId::new(2),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(4),
+            21,
+            1,
+        );
 
         // both partitions should now need to be persisted to bring us below the mem threshold of
         // 20.
@@ -1118,11 +1716,32 @@ mod tests {
         } = TestLifecycleManger::new(config);
         let h = m.handle();
         let persister = Arc::new(TestPersister::default());
-        h.log_write(PartitionId::new(1), shard_id, SequenceNumber::new(1), 4, 1);
+        h.log_write(
+            PartitionId::new(1),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(1),
+            4,
+            1,
+        );
         time_provider.inc(Duration::from_nanos(1));
-        h.log_write(PartitionId::new(2), shard_id, SequenceNumber::new(2), 6, 1);
+        h.log_write(
+            PartitionId::new(2),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(2),
+            6,
+            1,
+        );
         time_provider.inc(Duration::from_nanos(1));
-        h.log_write(PartitionId::new(3), shard_id, SequenceNumber::new(3), 3, 1);
+        h.log_write(
+            PartitionId::new(3),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(3),
+            3,
+            1,
+        );
 
         m.maybe_persist(&persister).await;
 
@@ -1162,7 +1781,14 @@ mod tests {
         let persister = Arc::new(TestPersister::default());
         let shard_id = ShardId::new(1);
 
-        h.log_write(partition_id, shard_id, SequenceNumber::new(1), 10, 1);
+        h.log_write(
+            partition_id,
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(1),
+            10,
+            1,
+        );
 
         m.maybe_persist(&persister).await;
         let stats = m.stats();
@@ -1176,7 +1802,14 @@ mod tests {
         assert!(!persister.persist_called_for(partition_id));
 
         // write in data for a new partition so we can be sure it isn't persisted, but the older one is
-        h.log_write(PartitionId::new(2), shard_id, SequenceNumber::new(2), 6, 1);
+        h.log_write(
+            PartitionId::new(2),
+            shard_id,
+            NamespaceId::new(1),
+            SequenceNumber::new(2),
+            6,
+            1,
+        );
 
         m.maybe_persist(&persister).await;
 
@@ -323,25 +323,21 @@ mod tests {
         let expected = vec![
             Arc::new(DeletePredicate {
                 range: TimestampRange::new(100, 200),
-                exprs: vec![DeleteExpr {
-                    column: String::from("temp"),
-                    op: Op::Eq,
-                    scalar: Scalar::I64(10),
-                }],
+                exprs: vec![DeleteExpr::new(
+                    String::from("temp"),
+                    Op::Eq,
+                    Scalar::I64(10),
+                )],
             }),
             Arc::new(DeletePredicate {
                 range: TimestampRange::new(100, 350),
                 exprs: vec![
-                    DeleteExpr {
-                        column: String::from("temp"),
-                        op: Op::Ne,
-                        scalar: Scalar::I64(10),
-                    },
-                    DeleteExpr {
-                        column: String::from("city"),
-                        op: Op::Eq,
-                        scalar: Scalar::String(String::from(r#"Boston"#)),
-                    },
+                    DeleteExpr::new(String::from("temp"), Op::Ne, Scalar::I64(10)),
+                    DeleteExpr::new(
+                        String::from("city"),
+                        Op::Eq,
+                        Scalar::String(String::from(r#"Boston"#)),
+                    ),
                 ],
             }),
         ];
@@ -15,10 +15,12 @@ use arrow_flight::{
     Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
     HandshakeRequest, HandshakeResponse, IpcMessage, PutResult, SchemaAsIpc, SchemaResult, Ticket,
 };
+use data_types::{PartitionId, ShardIndex};
 use flatbuffers::FlatBufferBuilder;
 use futures::Stream;
 use generated_types::influxdata::iox::ingester::v1::{
     self as proto,
+    ingester_admin_service_server::{IngesterAdminService, IngesterAdminServiceServer},
     write_info_service_server::{WriteInfoService, WriteInfoServiceServer},
 };
 use observability_deps::tracing::{debug, info, warn};
@@ -31,7 +33,7 @@ use write_summary::WriteSummary;
 
 use crate::{
     data::{FlatIngesterQueryResponse, FlatIngesterQueryResponseStream},
-    handler::IngestHandler,
+    handler::{IngestHandler, PersistSelection},
 };
 
 /// This type is responsible for managing all gRPC services exposed by
@@ -70,6 +72,126 @@ impl<I: IngestHandler + Send + Sync + 'static> GrpcDelegate<I> {
             Arc::clone(&self.ingest_handler) as _
         ))
     }
+
+    /// Acquire an IngesterAdmin gRPC service implementation.
+    pub fn admin_service(&self) -> IngesterAdminServiceServer<impl IngesterAdminService> {
+        IngesterAdminServiceServer::new(
+            AdminServiceImpl::new(Arc::clone(&self.ingest_handler) as _),
+        )
+    }
+}
+
+/// Implementation of the ingester admin API
+struct AdminServiceImpl {
+    handler: Arc<dyn IngestHandler + Send + Sync + 'static>,
+}
+
+impl AdminServiceImpl {
+    pub fn new(handler: Arc<dyn IngestHandler + Send + Sync + 'static>) -> Self {
+        Self { handler }
+    }
+
+    fn set_shard_paused(&self, shard_index: i32, paused: bool) -> Result<(), tonic::Status> {
+        if self
+            .handler
+            .set_shard_paused(ShardIndex::new(shard_index), paused)
+        {
+            Ok(())
+        } else {
+            Err(tonic::Status::not_found(format!(
+                "shard index {} is not consumed by this ingester",
+                shard_index
+            )))
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl IngesterAdminService for AdminServiceImpl {
+    async fn persist(
+        &self,
+        request: Request<proto::PersistRequest>,
+    ) -> Result<Response<proto::PersistResponse>, tonic::Status> {
+        let proto::PersistRequest {
+            namespace,
+            table,
+            partition_id,
+        } = request.into_inner();
+
+        if table.is_some() && namespace.is_none() {
+            return Err(tonic::Status::invalid_argument(
+                "a table can only be persisted together with its namespace",
+            ));
+        }
+
+        let selection = PersistSelection {
+            namespace,
+            table,
+            partition_id: partition_id.map(PartitionId::new),
+        };
+        let partition_ids = self
+            .handler
+            .persist(selection)
+            .await
+            .into_iter()
+            .map(|id| id.get())
+            .collect();
+
+        Ok(Response::new(proto::PersistResponse { partition_ids }))
+    }
+
+    async fn pause_shard(
+        &self,
+        request: Request<proto::PauseShardRequest>,
+    ) -> Result<Response<proto::PauseShardResponse>, tonic::Status> {
+        self.set_shard_paused(request.into_inner().shard_index, true)?;
+        Ok(Response::new(proto::PauseShardResponse {}))
+    }
+
+    async fn resume_shard(
+        &self,
+        request: Request<proto::ResumeShardRequest>,
+    ) -> Result<Response<proto::ResumeShardResponse>, tonic::Status> {
+        self.set_shard_paused(request.into_inner().shard_index, false)?;
+        Ok(Response::new(proto::ResumeShardResponse {}))
+    }
+
+    async fn list_buffered_partitions(
+        &self,
+        _request: Request<proto::ListBufferedPartitionsRequest>,
+    ) -> Result<Response<proto::ListBufferedPartitionsResponse>, tonic::Status> {
+        let partitions = self
+            .handler
+            .buffered_partitions()
+            .await
+            .into_iter()
+            .map(|p| proto::BufferedPartition {
+                shard_index: p.shard_index.get(),
+                namespace: p.namespace,
+                table: p.table,
+                partition_id: p.stats.partition_id.get(),
+                bytes: p.stats.bytes_written as u64,
+                rows: p.stats.rows_written as u64,
+                age_ms: p.age.as_millis() as u64,
+                first_write_time_ns: p.stats.first_write.timestamp_nanos(),
+                last_write_time_ns: p.stats.last_write.timestamp_nanos(),
+                first_sequence_number: p.stats.first_sequence_number.get(),
+                last_sequence_number: p.stats.last_sequence_number.get(),
+            })
+            .collect();
+
+        let paused_shard_indexes = self
+            .handler
+            .paused_shards()
+            .into_iter()
+            .map(|shard_index| shard_index.get())
+            .collect();
+
+        Ok(Response::new(proto::ListBufferedPartitionsResponse {
+            partitions,
+            paused_shard_indexes,
+        }))
+    }
 }
 
 /// Implementation of write info
@@ -2,17 +2,31 @@
 
 use std::sync::Arc;
 
-use hyper::{Body, Request, Response, StatusCode};
+use data_types::{PartitionId, ShardIndex};
+use hyper::{header::CONTENT_TYPE, Body, Method, Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::handler::IngestHandler;
+use crate::handler::{BufferedPartition, IngestHandler, PersistSelection};
 
-/// Errors returned by the `router` HTTP request handler.
-#[derive(Debug, Error, Copy, Clone)]
+/// Errors returned by the `ingester` HTTP request handler.
+#[derive(Debug, Error)]
 pub enum Error {
     /// The requested path has no registered handler.
     #[error("not found")]
     NotFound,
+
+    /// The query string of the request cannot be parsed.
+    #[error("invalid query string: {0}")]
+    InvalidQuery(#[from] serde_urlencoded::de::Error),
+
+    /// A table was selected for persistence without its namespace.
+    #[error("a table can only be persisted together with its namespace")]
+    TableWithoutNamespace,
+
+    /// The shard to pause or resume is not consumed by this ingester.
+    #[error("shard index {0} is not consumed by this ingester")]
+    ShardNotConsumed(i32),
 }
 
 impl Error {
@@ -21,6 +35,71 @@ impl Error {
     pub fn as_status_code(&self) -> StatusCode {
         match self {
             Self::NotFound => StatusCode::NOT_FOUND,
+            Self::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+            Self::TableWithoutNamespace => StatusCode::BAD_REQUEST,
+            Self::ShardNotConsumed(_) => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+/// The partitions to persist, see [`PersistSelection`].
+#[derive(Debug, Deserialize)]
+struct PersistQuery {
+    namespace: Option<String>,
+    table: Option<String>,
+    partition_id: Option<i64>,
+}
+
+/// The shard to pause or resume.
+#[derive(Debug, Deserialize)]
+struct ShardQuery {
+    shard_index: i32,
+}
+
+/// Response of `POST /api/v1/ingester/persist`.
+#[derive(Debug, Serialize)]
+struct PersistResponse {
+    partition_ids: Vec<i64>,
+}
+
+/// Response of `GET /api/v1/ingester/partitions`.
+#[derive(Debug, Serialize)]
+struct BufferedPartitionsResponse {
+    partitions: Vec<BufferedPartitionResponse>,
+    paused_shard_indexes: Vec<i32>,
+}
+
+/// A partition with buffered data, with the fields of the gRPC
+/// `BufferedPartition` message.
+#[derive(Debug, Serialize)]
+struct BufferedPartitionResponse {
+    shard_index: i32,
+    namespace: String,
+    table: String,
+    partition_id: i64,
+    bytes: u64,
+    rows: u64,
+    age_ms: u64,
+    first_write_time_ns: i64,
+    last_write_time_ns: i64,
+    first_sequence_number: i64,
+    last_sequence_number: i64,
+}
+
+impl From<BufferedPartition> for BufferedPartitionResponse {
+    fn from(p: BufferedPartition) -> Self {
+        Self {
+            shard_index: p.shard_index.get(),
+            namespace: p.namespace,
+            table: p.table,
+            partition_id: p.stats.partition_id.get(),
+            bytes: p.stats.bytes_written as u64,
+            rows: p.stats.rows_written as u64,
+            age_ms: p.age.as_millis() as u64,
+            first_write_time_ns: p.stats.first_write.timestamp_nanos(),
+            last_write_time_ns: p.stats.last_write.timestamp_nanos(),
+            first_sequence_number: p.stats.first_sequence_number.get(),
+            last_sequence_number: p.stats.last_sequence_number.get(),
         }
     }
 }
@@ -31,9 +110,18 @@ impl Error {
 /// Requests to some paths may be handled externally by the caller - the IOx
 /// server runner framework takes care of implementing the heath endpoint,
 /// metrics, pprof, etc.
+///
+/// The admin endpoints mirror the `IngesterAdminService` gRPC service:
+///
+/// - `GET /api/v1/ingester/partitions` lists the buffered partitions.
+/// - `POST /api/v1/ingester/persist` persists the buffered partitions
+///   selected by the optional `namespace`, `table` and `partition_id` query
+///   parameters.
+/// - `POST /api/v1/ingester/shards/pause` and
+///   `POST /api/v1/ingester/shards/resume` pause and resume consuming the
+///   shard given by the `shard_index` query parameter.
 #[derive(Debug, Default)]
 pub struct HttpDelegate<I: IngestHandler> {
-    #[allow(dead_code)]
     ingest_handler: Arc<I>,
 }
 
@@ -46,7 +134,341 @@ impl<I: IngestHandler> HttpDelegate<I> {
 
     /// Routes `req` to the appropriate handler, if any, returning the handler
     /// response.
-    pub fn route(&self, _req: Request<Body>) -> Result<Response<Body>, Error> {
-        unimplemented!()
+    pub async fn route(&self, req: Request<Body>) -> Result<Response<Body>, Error> {
+        let query = req.uri().query().unwrap_or_default();
+        match (req.method(), req.uri().path()) {
+            (&Method::GET, "/api/v1/ingester/partitions") => self.partitions_handler().await,
+            (&Method::POST, "/api/v1/ingester/persist") => {
+                let selection = persist_selection(query)?;
+                self.persist_handler(selection).await
+            }
+            (&Method::POST, "/api/v1/ingester/shards/pause") => {
+                self.shard_paused_handler(query, true)
+            }
+            (&Method::POST, "/api/v1/ingester/shards/resume") => {
+                self.shard_paused_handler(query, false)
+            }
+            _ => Err(Error::NotFound),
+        }
+    }
+
+    async fn partitions_handler(&self) -> Result<Response<Body>, Error> {
+        let partitions = self
+            .ingest_handler
+            .buffered_partitions()
+            .await
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        let paused_shard_indexes = self
+            .ingest_handler
+            .paused_shards()
+            .into_iter()
+            .map(|shard_index| shard_index.get())
+            .collect();
+
+        Ok(json_response(&BufferedPartitionsResponse {
+            partitions,
+            paused_shard_indexes,
+        }))
+    }
+
+    async fn persist_handler(&self, selection: PersistSelection) -> Result<Response<Body>, Error> {
+        let partition_ids = self
+            .ingest_handler
+            .persist(selection)
+            .await
+            .into_iter()
+            .map(|partition_id| partition_id.get())
+            .collect();
+
+        Ok(json_response(&PersistResponse { partition_ids }))
+    }
+
+    fn shard_paused_handler(&self, query: &str, paused: bool) -> Result<Response<Body>, Error> {
+        let ShardQuery { shard_index } = serde_urlencoded::from_str(query)?;
+
+        if !self
+            .ingest_handler
+            .set_shard_paused(ShardIndex::new(shard_index), paused)
+        {
+            return Err(Error::ShardNotConsumed(shard_index));
+        }
+
+        Ok(Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .body(Body::empty())
+            .unwrap())
+    }
+}
+
+/// Parses the partitions to persist from the `query` string.
+fn persist_selection(query: &str) -> Result<PersistSelection, Error> {
+    let PersistQuery {
+        namespace,
+        table,
+        partition_id,
+    } = serde_urlencoded::from_str(query)?;
+    if table.is_some() && namespace.is_none() {
+        return Err(Error::TableWithoutNamespace);
+    }
+
+    Ok(PersistSelection {
+        namespace,
+        table,
+        partition_id: partition_id.map(PartitionId::new),
+    })
+}
+
+/// Returns a `200 OK` response with `body` encoded as JSON.
+fn json_response(body: &impl Serialize) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            serde_json::to_vec(body).expect("serialize admin response"),
+        ))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, time::Duration};
+
+    use async_trait::async_trait;
+    use data_types::{NamespaceId, SequenceNumber, ShardId};
+    use generated_types::ingester::IngesterQueryRequest;
+    use iox_time::Time;
+    use parking_lot::Mutex;
+    use write_summary::ShardProgress;
+
+    use super::*;
+    use crate::{data::IngesterQueryResponse, lifecycle::PartitionLifecycleStats};
+
+    /// Serves a single buffered partition of shard 1.
+    #[derive(Debug, Default)]
+    struct MockIngestHandler {
+        persisted: Mutex<Vec<PersistSelection>>,
+        paused: Mutex<Vec<ShardIndex>>,
+    }
+
+    #[async_trait]
+    impl IngestHandler for MockIngestHandler {
+        async fn query(
+            &self,
+            _request: IngesterQueryRequest,
+        ) -> Result<IngesterQueryResponse, crate::querier_handler::Error> {
+            unimplemented!()
+        }
+
+        async fn progresses(
+            &self,
+            _shard_indexes: Vec<ShardIndex>,
+        ) -> BTreeMap<ShardIndex, ShardProgress> {
+            unimplemented!()
+        }
+
+        async fn buffered_partitions(&self) -> Vec<BufferedPartition> {
+            vec![BufferedPartition {
+                shard_index: ShardIndex::new(1),
+                namespace: "ns".to_string(),
+                table: "cpu".to_string(),
+                age: Duration::from_secs(2),
+                stats: PartitionLifecycleStats {
+                    shard_id: ShardId::new(1),
+                    namespace_id: NamespaceId::new(1),
+                    partition_id: PartitionId::new(3),
+                    first_write: Time::from_timestamp_nanos(10),
+                    last_write: Time::from_timestamp_nanos(20),
+                    bytes_written: 100,
+                    rows_written: 2,
+                    first_sequence_number: SequenceNumber::new(4),
+                    last_sequence_number: SequenceNumber::new(5),
+                    persist_requested: false,
+                },
+            }]
+        }
+
+        async fn persist(&self, selection: PersistSelection) -> Vec<PartitionId> {
+            self.persisted.lock().push(selection);
+            vec![PartitionId::new(3)]
+        }
+
+        fn set_shard_paused(&self, shard_index: ShardIndex, paused: bool) -> bool {
+            if shard_index != ShardIndex::new(1) {
+                return false;
+            }
+            let mut p = self.paused.lock();
+            p.retain(|s| *s != shard_index);
+            if paused {
+                p.push(shard_index);
+            }
+            true
+        }
+
+        fn paused_shards(&self) -> Vec<ShardIndex> {
+            self.paused.lock().clone()
+        }
+
+        async fn join(&self) {}
+
+        fn shutdown(&self) {}
+    }
+
+    async fn request(
+        delegate: &HttpDelegate<MockIngestHandler>,
+        method: Method,
+        uri: &str,
+    ) -> Result<(StatusCode, serde_json::Value), Error> {
+        let req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        let response = delegate.route(req).await?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body = if body.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&body).unwrap()
+        };
+        Ok((status, body))
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes() {
+        let handler = Arc::new(MockIngestHandler::default());
+        let delegate = HttpDelegate::new(Arc::clone(&handler));
+
+        let (status, _) = request(
+            &delegate,
+            Method::POST,
+            "http://ingester/api/v1/ingester/shards/pause?shard_index=1",
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (status, body) = request(
+            &delegate,
+            Method::GET,
+            "http://ingester/api/v1/ingester/partitions",
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "partitions": [{
+                    "shard_index": 1,
+                    "namespace": "ns",
+                    "table": "cpu",
+                    "partition_id": 3,
+                    "bytes": 100,
+                    "rows": 2,
+                    "age_ms": 2000,
+                    "first_write_time_ns": 10,
+                    "last_write_time_ns": 20,
+                    "first_sequence_number": 4,
+                    "last_sequence_number": 5,
+                }],
+                "paused_shard_indexes": [1],
+            })
+        );
+
+        let (status, body) = request(
+            &delegate,
+            Method::POST,
+            "http://ingester/api/v1/ingester/persist?namespace=ns&table=cpu",
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, serde_json::json!({ "partition_ids": [3] }));
+
+        let (status, _) = request(
+            &delegate,
+            Method::POST,
+            "http://ingester/api/v1/ingester/persist",
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            *handler.persisted.lock(),
+            vec![
+                PersistSelection {
+                    namespace: Some("ns".to_string()),
+                    table: Some("cpu".to_string()),
+                    partition_id: None,
+                },
+                PersistSelection::default(),
+            ]
+        );
+
+        let (status, _) = request(
+            &delegate,
+            Method::POST,
+            "http://ingester/api/v1/ingester/shards/resume?shard_index=1",
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(handler.paused_shards().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_admin_route_errors() {
+        let delegate = HttpDelegate::new(Arc::new(MockIngestHandler::default()));
+
+        let err = request(
+            &delegate,
+            Method::POST,
+            "http://ingester/api/v1/ingester/persist?table=cpu",
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, Error::TableWithoutNamespace));
+        assert_eq!(err.as_status_code(), StatusCode::BAD_REQUEST);
+
+        let err = request(
+            &delegate,
+            Method::POST,
+            "http://ingester/api/v1/ingester/persist?partition_id=bananas",
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidQuery(_)));
+        assert_eq!(err.as_status_code(), StatusCode::BAD_REQUEST);
+
+        let err = request(
+            &delegate,
+            Method::POST,
+            "http://ingester/api/v1/ingester/shards/pause",
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidQuery(_)));
+
+        let err = request(
+            &delegate,
+            Method::POST,
+            "http://ingester/api/v1/ingester/shards/pause?shard_index=2",
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, Error::ShardNotConsumed(2)));
+        assert_eq!(err.as_status_code(), StatusCode::NOT_FOUND);
+
+        let err = request(
+            &delegate,
+            Method::GET,
+            "http://ingester/api/v1/ingester/persist",
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, Error::NotFound));
     }
 }
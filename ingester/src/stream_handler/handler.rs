@@ -220,6 +220,34 @@ where
         let mut sequence_number_before_reset: Option<SequenceNumber> = None;
 
         loop {
+            // Do not consume any further ops while an operator paused this
+            // shard, but still respect the graceful stop signal.
+            if self.lifecycle_handle.is_shard_paused(self.shard_index) {
+                info!(
+                    kafka_topic=%self.topic_name,
+                    shard_index=%self.shard_index,
+                    "ingest paused by request"
+                );
+                while self.lifecycle_handle.is_shard_paused(self.shard_index) {
+                    futures::select!(
+                        _ = tokio::time::sleep(INGEST_POLL_INTERVAL).fuse() => {},
+                        _ = shutdown_fut => {
+                            info!(
+                                kafka_topic=%self.topic_name,
+                                shard_index=%self.shard_index,
+                                "stream handler shutdown",
+                            );
+                            return;
+                        }
+                    );
+                }
+                info!(
+                    kafka_topic=%self.topic_name,
+                    shard_index=%self.shard_index,
+                    "ingest resumed by request"
+                );
+            }
+
             // Wait for a DML operation from the shard, or a graceful stop signal.
             let maybe_op = futures::select!(
                 next = stream.next().fuse() => next,
@@ -1014,6 +1042,52 @@ mod tests {
             .await;
     }
 
+    // A paused shard is not consumed until resumed, but still shuts down gracefully.
+    #[tokio::test]
+    async fn test_paused_shard() {
+        let metrics = Arc::new(metric::Registry::default());
+        let time_provider = Arc::new(SystemProvider::default());
+        let lifecycle = LifecycleManager::new(
+            LifecycleConfig::new(
+                100,
+                2,
+                3,
+                Duration::from_secs(4),
+                Duration::from_secs(5),
+                1000000,
+            ),
+            Arc::clone(&metrics),
+            time_provider,
+        );
+        let lifecycle_handle = lifecycle.handle();
+        lifecycle_handle.set_shard_paused(ShardIndex::new(42), true);
+
+        // An empty stream causes a panic once it is polled.
+        let write_buffer_stream_handler = EmptyWriteBufferStreamHandler {};
+        let sink = MockDmlSink::default();
+
+        let handler = SequencedStreamHandler::new(
+            write_buffer_stream_handler,
+            SequenceNumber::new(0),
+            sink,
+            lifecycle_handle,
+            "topic_name".to_string(),
+            ShardIndex::new(42),
+            &*metrics,
+            false,
+        );
+
+        let shutdown = CancellationToken::new();
+        let stop = async {
+            tokio::time::sleep(INGEST_POLL_INTERVAL * 3).await;
+            shutdown.cancel();
+        };
+
+        futures::future::join(handler.run(shutdown.clone()), stop)
+            .with_timeout_panic(Duration::from_secs(1))
+            .await;
+    }
+
     // An abnormal end to the steam causes a panic, rather than a silent stream reader exit.
     #[tokio::test]
     #[should_panic(expected = "high watermark BEFORE the sequence number")]
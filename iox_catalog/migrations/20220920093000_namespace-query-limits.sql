@@ -0,0 +1,13 @@
+-- Per-namespace limits on the resources used by queries.
+--
+-- NULL == no namespace limit, only the querier limits apply.
+ALTER TABLE
+    "namespace"
+ADD
+    COLUMN "max_concurrent_queries" INT NULL DEFAULT NULL,
+ADD
+    COLUMN "max_query_memory_bytes" BIGINT NULL DEFAULT NULL,
+ADD
+    COLUMN "max_query_scanned_bytes" BIGINT NULL DEFAULT NULL,
+ADD
+    COLUMN "max_query_duration_seconds" BIGINT NULL DEFAULT NULL;
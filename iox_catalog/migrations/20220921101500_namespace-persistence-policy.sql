@@ -0,0 +1,14 @@
+-- Per-namespace thresholds that trigger the persistence of the data buffered
+-- by the ingesters.
+--
+-- NULL == no namespace threshold, only the ingester thresholds apply.
+ALTER TABLE
+    "namespace"
+ADD
+    COLUMN "partition_age_threshold_seconds" BIGINT NULL DEFAULT NULL,
+ADD
+    COLUMN "partition_cold_threshold_seconds" BIGINT NULL DEFAULT NULL,
+ADD
+    COLUMN "partition_size_threshold_bytes" BIGINT NULL DEFAULT NULL,
+ADD
+    COLUMN "partition_row_max" BIGINT NULL DEFAULT NULL;
@@ -0,0 +1,12 @@
+-- Hashes of the API tokens granting access to a namespace.
+CREATE TABLE IF NOT EXISTS namespace_token (
+    id BIGSERIAL NOT NULL,
+    token_hash TEXT NOT NULL,
+    namespace_id INT NOT NULL REFERENCES namespace (id) ON DELETE CASCADE,
+    can_read BOOLEAN NOT NULL DEFAULT FALSE,
+    can_write BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (id),
+    CONSTRAINT namespace_token_unique UNIQUE (token_hash, namespace_id)
+);
+
+CREATE INDEX IF NOT EXISTS namespace_token_hash_idx ON namespace_token (token_hash);
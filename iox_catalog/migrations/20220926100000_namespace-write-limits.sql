@@ -0,0 +1,9 @@
+-- Per-namespace limits on the rate of the writes accepted by each router.
+--
+-- NULL == no limit, writes are not throttled.
+ALTER TABLE
+    "namespace"
+ADD
+    COLUMN "write_lines_per_second" BIGINT NULL DEFAULT NULL,
+ADD
+    COLUMN "write_bytes_per_second" BIGINT NULL DEFAULT NULL;
@@ -0,0 +1,6 @@
+-- Namespaces in strict schema mode only accept writes to the tables and
+-- columns declared ahead of time, instead of creating them implicitly.
+ALTER TABLE
+    "namespace"
+ADD
+    COLUMN "strict_schema" BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,6 @@
+-- How the router resolves writes to a field column whose type conflicts with
+-- the type of the column: 0 = reject, 1 = widen, 2 = stringify, 3 = drop.
+ALTER TABLE
+    "namespace"
+ADD
+    COLUMN "field_coercion_policy" SMALLINT NOT NULL DEFAULT 0;
@@ -2,11 +2,13 @@
 
 use async_trait::async_trait;
 use data_types::{
-    Column, ColumnSchema, ColumnType, ColumnTypeCount, CompactionLevel, Namespace, NamespaceId,
-    NamespaceSchema, ParquetFile, ParquetFileId, ParquetFileParams, Partition, PartitionId,
-    PartitionInfo, PartitionKey, PartitionParam, ProcessedTombstone, QueryPool, QueryPoolId,
-    SequenceNumber, Shard, ShardId, ShardIndex, SkippedCompaction, Table, TableId, TablePartition,
-    TableSchema, Timestamp, Tombstone, TombstoneId, TopicId, TopicMetadata,
+    Column, ColumnSchema, ColumnType, ColumnTypeCount, CompactionLevel, FieldCoercionPolicy,
+    Namespace, NamespaceId, NamespacePersistencePolicy, NamespaceQueryLimits, NamespaceSchema,
+    NamespaceToken, NamespaceTokenId, NamespaceWriteLimits, ParquetFile, ParquetFileId,
+    ParquetFileParams, Partition, PartitionId, PartitionInfo, PartitionKey, PartitionParam,
+    ProcessedTombstone, QueryPool, QueryPoolId, SequenceNumber, Shard, ShardId, ShardIndex,
+    SkippedCompaction, Table, TableId, TablePartition, TableSchema, Timestamp, Tombstone,
+    TombstoneId, TopicId, TopicMetadata,
 };
 use iox_time::TimeProvider;
 use snafu::{OptionExt, Snafu};
@@ -126,6 +128,14 @@ pub enum Error {
 
     #[snafu(display("could not list skipped compactions: {source}"))]
     CouldNotListSkippedCompactions { source: sqlx::Error },
+
+    #[snafu(display(
+        "could not delete skipped compactions for partition {partition_id}: {source}"
+    ))]
+    CouldNotDeleteSkippedCompactions {
+        source: sqlx::Error,
+        partition_id: PartitionId,
+    },
 }
 
 /// A specialized `Error` for Catalog errors
@@ -260,6 +270,9 @@ pub trait RepoCollection: Send + Sync + Debug {
 
     /// Repository for [processed tombstones](data_types::ProcessedTombstone).
     fn processed_tombstones(&mut self) -> &mut dyn ProcessedTombstoneRepo;
+
+    /// Repository for [namespace tokens](data_types::NamespaceToken).
+    fn namespace_tokens(&mut self) -> &mut dyn NamespaceTokenRepo;
 }
 
 /// Functions for working with topics in the catalog.
@@ -306,6 +319,40 @@ pub trait NamespaceRepo: Send + Sync {
 
     /// Update the limit on the number of columns that can exist per table in a given namespace.
     async fn update_column_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace>;
+
+    /// Update the limits on the resources used by queries against the given namespace.
+    async fn update_query_limits(
+        &mut self,
+        name: &str,
+        limits: NamespaceQueryLimits,
+    ) -> Result<Namespace>;
+
+    /// Update the thresholds that trigger the persistence of the data ingesters buffer for the
+    /// given namespace.
+    async fn update_persistence_policy(
+        &mut self,
+        name: &str,
+        policy: NamespacePersistencePolicy,
+    ) -> Result<Namespace>;
+
+    /// Update the limits on the rate of the writes routed to the given namespace.
+    async fn update_write_limits(
+        &mut self,
+        name: &str,
+        limits: NamespaceWriteLimits,
+    ) -> Result<Namespace>;
+
+    /// Enable or disable the strict schema mode of the given namespace, restricting writes to the
+    /// declared tables and columns.
+    async fn update_strict_schema(&mut self, name: &str, strict_schema: bool) -> Result<Namespace>;
+
+    /// Update the policy resolving writes to field columns with a conflicting type for the given
+    /// namespace.
+    async fn update_field_coercion_policy(
+        &mut self,
+        name: &str,
+        policy: FieldCoercionPolicy,
+    ) -> Result<Namespace>;
 }
 
 /// Functions for working with tables in the catalog
@@ -481,6 +528,13 @@ pub trait PartitionRepo: Send + Sync {
     /// List the records of compacting a partition being skipped. This is mostly useful for testing.
     async fn list_skipped_compactions(&mut self) -> Result<Vec<SkippedCompaction>>;
 
+    /// Delete the record of compacting a partition being skipped, making the partition eligible
+    /// for compaction again. Returns the deleted record, if there was one.
+    async fn delete_skipped_compactions(
+        &mut self,
+        partition_id: PartitionId,
+    ) -> Result<Option<SkippedCompaction>>;
+
     /// Update the per-partition persistence watermark.
     ///
     /// The given `sequence_number` is the inclusive maximum [`SequenceNumber`]
@@ -574,6 +628,10 @@ pub trait ParquetFileRepo: Send + Sync {
     /// [`to_delete`](ParquetFile::to_delete).
     async fn list_by_table_not_to_delete(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>>;
 
+    /// List all parquet files within a given table that are marked as
+    /// [`to_delete`](ParquetFile::to_delete) but were not deleted yet.
+    async fn list_by_table_to_delete(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>>;
+
     /// Delete all parquet files that were marked to be deleted earlier than the specified time.
     /// Returns the deleted records.
     async fn delete_old(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFile>>;
@@ -687,6 +745,18 @@ pub trait ProcessedTombstoneRepo: Send + Sync {
         tombstone_id: TombstoneId,
     ) -> Result<bool>;
 
+    /// List the processed tombstones of the given tombstones
+    async fn list_by_tombstone_ids(
+        &mut self,
+        tombstone_ids: &[TombstoneId],
+    ) -> Result<Vec<ProcessedTombstone>>;
+
+    /// List the processed tombstones of the given parquet files
+    async fn list_by_parquet_file_ids(
+        &mut self,
+        parquet_file_ids: &[ParquetFileId],
+    ) -> Result<Vec<ProcessedTombstone>>;
+
     /// Return count
     async fn count(&mut self) -> Result<i64>;
 
@@ -694,6 +764,32 @@ pub trait ProcessedTombstoneRepo: Send + Sync {
     async fn count_by_tombstone_id(&mut self, tombstone_id: TombstoneId) -> Result<i64>;
 }
 
+/// Functions for working with the API tokens granting access to namespaces
+#[async_trait]
+pub trait NamespaceTokenRepo: Send + Sync {
+    /// Grant the token with the given hash access to a namespace, replacing the permissions of
+    /// any existing grant of that token for the namespace.
+    async fn create_or_update(
+        &mut self,
+        token_hash: &str,
+        namespace_id: NamespaceId,
+        can_read: bool,
+        can_write: bool,
+    ) -> Result<NamespaceToken>;
+
+    /// List all the grants of the token with the given hash
+    async fn list_by_token_hash(&mut self, token_hash: &str) -> Result<Vec<NamespaceToken>>;
+
+    /// List all the token grants of a namespace
+    async fn list_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<NamespaceToken>>;
+
+    /// Delete a token grant. Deleting a grant that does not exist is not an error.
+    async fn delete(&mut self, id: NamespaceTokenId) -> Result<()>;
+}
+
 /// Gets the namespace schema including all tables and columns.
 pub async fn get_schema_by_id<R>(id: NamespaceId, repos: &mut R) -> Result<NamespaceSchema>
 where
@@ -730,8 +826,14 @@ where
     let columns = repos.columns().list_by_namespace_id(namespace.id).await?;
     let tables = repos.tables().list_by_namespace_id(namespace.id).await?;
 
+    let write_limits = namespace.write_limits;
+    let strict_schema = namespace.strict_schema;
+    let field_coercion_policy = namespace.field_coercion_policy;
     let mut namespace =
         NamespaceSchema::new(namespace.id, namespace.topic_id, namespace.query_pool_id);
+    namespace.write_limits = write_limits;
+    namespace.strict_schema = strict_schema;
+    namespace.field_coercion_policy = field_coercion_policy;
 
     let mut table_id_to_schema = BTreeMap::new();
     for t in tables {
@@ -864,6 +966,9 @@ pub async fn list_schemas(
         .filter_map(move |v| {
             let mut ns = NamespaceSchema::new(v.id, v.topic_id, v.query_pool_id);
             ns.tables = joined.remove(&v.id)?;
+            ns.write_limits = v.write_limits;
+            ns.strict_schema = v.strict_schema;
+            ns.field_coercion_policy = v.field_coercion_policy;
             Some((v, ns))
         });
 
@@ -902,6 +1007,7 @@ pub(crate) mod test_helpers {
         test_recent_highest_throughput_partitions(Arc::clone(&catalog)).await;
         test_update_to_compaction_level_1(Arc::clone(&catalog)).await;
         test_processed_tombstones(Arc::clone(&catalog)).await;
+        test_namespace_tokens(Arc::clone(&catalog)).await;
         test_list_by_partiton_not_to_delete(Arc::clone(&catalog)).await;
         test_txn_isolation(Arc::clone(&catalog)).await;
         test_txn_drop(Arc::clone(&catalog)).await;
@@ -1028,6 +1134,131 @@ pub(crate) mod test_helpers {
             .await
             .expect("namespace should be updateable");
         assert_eq!(NEW_COLUMN_LIMIT, modified.max_columns_per_table);
+
+        assert_eq!(modified.query_limits, NamespaceQueryLimits::default());
+        let new_query_limits = NamespaceQueryLimits {
+            max_concurrent_queries: Some(2),
+            max_query_memory_bytes: Some(1024),
+            max_query_scanned_bytes: None,
+            max_query_duration_seconds: Some(60),
+        };
+        let modified = repos
+            .namespaces()
+            .update_query_limits(namespace_name, new_query_limits)
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(new_query_limits, modified.query_limits);
+        assert_eq!(NEW_COLUMN_LIMIT, modified.max_columns_per_table);
+
+        let err = repos
+            .namespaces()
+            .update_query_limits("does_not_exist", new_query_limits)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NamespaceNotFoundByName { .. }));
+
+        assert_eq!(
+            modified.persistence_policy,
+            NamespacePersistencePolicy::default()
+        );
+        let new_persistence_policy = NamespacePersistencePolicy {
+            partition_age_threshold_seconds: Some(60),
+            partition_cold_threshold_seconds: None,
+            partition_size_threshold_bytes: Some(1024 * 1024),
+            partition_row_max: Some(1000),
+        };
+        let modified = repos
+            .namespaces()
+            .update_persistence_policy(namespace_name, new_persistence_policy)
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(new_persistence_policy, modified.persistence_policy);
+        assert_eq!(new_query_limits, modified.query_limits);
+
+        let listed = repos
+            .namespaces()
+            .get_by_name(namespace_name)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(new_persistence_policy, listed.persistence_policy);
+
+        let err = repos
+            .namespaces()
+            .update_persistence_policy("does_not_exist", new_persistence_policy)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NamespaceNotFoundByName { .. }));
+
+        assert_eq!(modified.write_limits, NamespaceWriteLimits::default());
+        let new_write_limits = NamespaceWriteLimits {
+            write_lines_per_second: Some(1000),
+            write_bytes_per_second: None,
+        };
+        let modified = repos
+            .namespaces()
+            .update_write_limits(namespace_name, new_write_limits)
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(new_write_limits, modified.write_limits);
+        assert_eq!(new_persistence_policy, modified.persistence_policy);
+
+        let schema = get_schema_by_name(namespace_name, repos.deref_mut())
+            .await
+            .unwrap();
+        assert_eq!(new_write_limits, schema.write_limits);
+
+        let err = repos
+            .namespaces()
+            .update_write_limits("does_not_exist", new_write_limits)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NamespaceNotFoundByName { .. }));
+
+        assert!(!modified.strict_schema);
+        let modified = repos
+            .namespaces()
+            .update_strict_schema(namespace_name, true)
+            .await
+            .expect("namespace should be updateable");
+        assert!(modified.strict_schema);
+        assert_eq!(new_write_limits, modified.write_limits);
+
+        let schema = get_schema_by_name(namespace_name, repos.deref_mut())
+            .await
+            .unwrap();
+        assert!(schema.strict_schema);
+
+        let err = repos
+            .namespaces()
+            .update_strict_schema("does_not_exist", true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NamespaceNotFoundByName { .. }));
+
+        assert_eq!(modified.field_coercion_policy, FieldCoercionPolicy::Reject);
+        let modified = repos
+            .namespaces()
+            .update_field_coercion_policy(namespace_name, FieldCoercionPolicy::Stringify)
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(
+            modified.field_coercion_policy,
+            FieldCoercionPolicy::Stringify
+        );
+        assert!(modified.strict_schema);
+
+        let schema = get_schema_by_name(namespace_name, repos.deref_mut())
+            .await
+            .unwrap();
+        assert_eq!(schema.field_coercion_policy, FieldCoercionPolicy::Stringify);
+
+        let err = repos
+            .namespaces()
+            .update_field_coercion_policy("does_not_exist", FieldCoercionPolicy::Drop)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NamespaceNotFoundByName { .. }));
     }
 
     async fn test_table(catalog: Arc<dyn Catalog>) {
@@ -1611,6 +1842,27 @@ pub(crate) mod test_helpers {
         assert_eq!(skipped_compactions[0].partition_id, other_partition.id);
         assert_eq!(skipped_compactions[0].reason, "I'm on fire");
 
+        // Deleting the skipped compaction returns it and lets the partition be compacted again
+        let deleted = repos
+            .partitions()
+            .delete_skipped_compactions(other_partition.id)
+            .await
+            .unwrap()
+            .expect("skipped compaction should have been deleted");
+        assert_eq!(deleted.partition_id, other_partition.id);
+        assert_eq!(deleted.reason, "I'm on fire");
+        let skipped_compactions = repos.partitions().list_skipped_compactions().await.unwrap();
+        assert!(
+            skipped_compactions.is_empty(),
+            "Expected no skipped compactions, got: {skipped_compactions:?}"
+        );
+        let deleted = repos
+            .partitions()
+            .delete_skipped_compactions(other_partition.id)
+            .await
+            .unwrap();
+        assert!(deleted.is_none());
+
         // Test setting and reading the per-partition persistence numbers
         let partition = repos
             .partitions()
@@ -2146,6 +2398,20 @@ pub(crate) mod test_helpers {
         let marked_deleted = files.first().unwrap();
         assert!(marked_deleted.to_delete.is_some());
 
+        // test list_by_table_to_delete
+        let files = repos
+            .parquet_files()
+            .list_by_table_to_delete(table.id)
+            .await
+            .unwrap();
+        assert_eq!(&files, &[marked_deleted.clone()]);
+        let files = repos
+            .parquet_files()
+            .list_by_table_to_delete(other_table.id)
+            .await
+            .unwrap();
+        assert!(files.is_empty());
+
         // File is not deleted if it was marked to be deleted after the specified time
         let before_deleted = Timestamp::new(
             (catalog.time_provider().now() - Duration::from_secs(100)).timestamp_nanos(),
@@ -3612,6 +3878,68 @@ pub(crate) mod test_helpers {
         );
     }
 
+    async fn test_namespace_tokens(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let topic = repos.topics().create_or_get("foo").await.unwrap();
+        let pool = repos.query_pools().create_or_get("foo").await.unwrap();
+        let namespace = repos
+            .namespaces()
+            .create("namespace_token_test", "inf", topic.id, pool.id)
+            .await
+            .unwrap();
+        let other_namespace = repos
+            .namespaces()
+            .create("namespace_token_test_other", "inf", topic.id, pool.id)
+            .await
+            .unwrap();
+
+        let tokens = repos.namespace_tokens();
+        assert!(tokens.list_by_token_hash("hash1").await.unwrap().is_empty());
+
+        let read = tokens
+            .create_or_update("hash1", namespace.id, true, false)
+            .await
+            .unwrap();
+        assert_eq!(read.token_hash, "hash1");
+        assert_eq!(read.namespace_id, namespace.id);
+        assert!(read.can_read);
+        assert!(!read.can_write);
+
+        // granting again updates the permissions of the existing grant
+        let read_write = tokens
+            .create_or_update("hash1", namespace.id, true, true)
+            .await
+            .unwrap();
+        assert_eq!(read_write.id, read.id);
+        assert!(read_write.can_read);
+        assert!(read_write.can_write);
+
+        let other = tokens
+            .create_or_update("hash1", other_namespace.id, false, true)
+            .await
+            .unwrap();
+        tokens
+            .create_or_update("hash2", namespace.id, true, false)
+            .await
+            .unwrap();
+
+        let mut listed = tokens.list_by_token_hash("hash1").await.unwrap();
+        listed.sort_by_key(|t| t.id);
+        assert_eq!(listed, vec![read_write.clone(), other.clone()]);
+
+        let listed = tokens
+            .list_by_namespace_id(other_namespace.id)
+            .await
+            .unwrap();
+        assert_eq!(listed, vec![other.clone()]);
+
+        tokens.delete(read_write.id).await.unwrap();
+        // deleting twice is fine
+        tokens.delete(read_write.id).await.unwrap();
+        let listed = tokens.list_by_token_hash("hash1").await.unwrap();
+        assert_eq!(listed, vec![other]);
+    }
+
     async fn test_processed_tombstones(catalog: Arc<dyn Catalog>) {
         let mut repos = catalog.repositories().await;
         let topic = repos.topics().create_or_get("foo").await.unwrap();
@@ -3716,19 +4044,19 @@ pub(crate) mod test_helpers {
 
         // processed tombstones
         // p1, t2
-        let _pt1 = repos
+        let pt1 = repos
             .processed_tombstones()
             .create(p1.id, t2.id)
             .await
             .unwrap();
         // p1, t3
-        let _pt2 = repos
+        let pt2 = repos
             .processed_tombstones()
             .create(p1.id, t3.id)
             .await
             .unwrap();
         // p2, t3
-        let _pt3 = repos
+        let pt3 = repos
             .processed_tombstones()
             .create(p2.id, t3.id)
             .await
@@ -3748,6 +4076,43 @@ pub(crate) mod test_helpers {
             .unwrap();
         assert!(exist);
 
+        // test list_by_tombstone_ids
+        let mut processed = repos
+            .processed_tombstones()
+            .list_by_tombstone_ids(&[t1.id, t3.id])
+            .await
+            .unwrap();
+        processed.sort_by_key(|pt| pt.parquet_file_id);
+        assert_eq!(processed, vec![pt2, pt3]);
+        let processed = repos
+            .processed_tombstones()
+            .list_by_tombstone_ids(&[t2.id])
+            .await
+            .unwrap();
+        assert_eq!(processed, vec![pt1]);
+        let processed = repos
+            .processed_tombstones()
+            .list_by_tombstone_ids(&[])
+            .await
+            .unwrap();
+        assert!(processed.is_empty());
+
+        // test list_by_parquet_file_ids
+        let mut processed = repos
+            .processed_tombstones()
+            .list_by_parquet_file_ids(&[p1.id])
+            .await
+            .unwrap();
+        processed.sort_by_key(|pt| pt.tombstone_id);
+        assert_eq!(processed, vec![pt1, pt2]);
+        let mut processed = repos
+            .processed_tombstones()
+            .list_by_parquet_file_ids(&[p1.id, p2.id])
+            .await
+            .unwrap();
+        processed.sort_by_key(|pt| (pt.parquet_file_id, pt.tombstone_id));
+        assert_eq!(processed, vec![pt1, pt2, pt3]);
+
         // test count
         let count = repos.processed_tombstones().count().await.unwrap();
         assert_eq!(count, 3);
@@ -4,19 +4,20 @@
 use crate::{
     interface::{
         sealed::TransactionFinalize, Catalog, ColumnRepo, ColumnTypeMismatchSnafu,
-        ColumnUpsertRequest, Error, NamespaceRepo, ParquetFileRepo, PartitionRepo,
-        ProcessedTombstoneRepo, QueryPoolRepo, RepoCollection, Result, ShardRepo, TablePersistInfo,
-        TableRepo, TombstoneRepo, TopicMetadataRepo, Transaction,
+        ColumnUpsertRequest, Error, NamespaceRepo, NamespaceTokenRepo, ParquetFileRepo,
+        PartitionRepo, ProcessedTombstoneRepo, QueryPoolRepo, RepoCollection, Result, ShardRepo,
+        TablePersistInfo, TableRepo, TombstoneRepo, TopicMetadataRepo, Transaction,
     },
     metrics::MetricDecorator,
 };
 use async_trait::async_trait;
 use data_types::{
-    Column, ColumnId, ColumnType, ColumnTypeCount, CompactionLevel, Namespace, NamespaceId,
-    ParquetFile, ParquetFileId, ParquetFileParams, Partition, PartitionId, PartitionInfo,
-    PartitionKey, PartitionParam, ProcessedTombstone, QueryPool, QueryPoolId, SequenceNumber,
-    Shard, ShardId, ShardIndex, SkippedCompaction, Table, TableId, TablePartition, Timestamp,
-    Tombstone, TombstoneId, TopicId, TopicMetadata,
+    Column, ColumnId, ColumnType, ColumnTypeCount, CompactionLevel, FieldCoercionPolicy, Namespace,
+    NamespaceId, NamespacePersistencePolicy, NamespaceQueryLimits, NamespaceToken,
+    NamespaceTokenId, NamespaceWriteLimits, ParquetFile, ParquetFileId, ParquetFileParams,
+    Partition, PartitionId, PartitionInfo, PartitionKey, PartitionParam, ProcessedTombstone,
+    QueryPool, QueryPoolId, SequenceNumber, Shard, ShardId, ShardIndex, SkippedCompaction, Table,
+    TableId, TablePartition, Timestamp, Tombstone, TombstoneId, TopicId, TopicMetadata,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use observability_deps::tracing::warn;
@@ -68,6 +69,7 @@ struct MemCollections {
     tombstones: Vec<Tombstone>,
     parquet_files: Vec<ParquetFile>,
     processed_tombstones: Vec<ProcessedTombstone>,
+    namespace_tokens: Vec<NamespaceToken>,
 }
 
 #[derive(Debug)]
@@ -228,6 +230,10 @@ impl RepoCollection for MemTxn {
     fn processed_tombstones(&mut self) -> &mut dyn ProcessedTombstoneRepo {
         self
     }
+
+    fn namespace_tokens(&mut self) -> &mut dyn NamespaceTokenRepo {
+        self
+    }
 }
 
 #[async_trait]
@@ -304,6 +310,11 @@ impl NamespaceRepo for MemTxn {
             retention_duration: Some(retention_duration.to_string()),
             max_tables: 10000,
             max_columns_per_table: 1000,
+            query_limits: Default::default(),
+            persistence_policy: Default::default(),
+            write_limits: Default::default(),
+            strict_schema: false,
+            field_coercion_policy: Default::default(),
         };
         stage.namespaces.push(namespace);
         Ok(stage.namespaces.last().unwrap().clone())
@@ -340,6 +351,87 @@ impl NamespaceRepo for MemTxn {
         }
     }
 
+    async fn update_query_limits(
+        &mut self,
+        name: &str,
+        limits: NamespaceQueryLimits,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.query_limits = limits;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn update_persistence_policy(
+        &mut self,
+        name: &str,
+        policy: NamespacePersistencePolicy,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.persistence_policy = policy;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn update_strict_schema(&mut self, name: &str, strict_schema: bool) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.strict_schema = strict_schema;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn update_field_coercion_policy(
+        &mut self,
+        name: &str,
+        policy: FieldCoercionPolicy,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.field_coercion_policy = policy;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn update_write_limits(
+        &mut self,
+        name: &str,
+        limits: NamespaceWriteLimits,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.write_limits = limits;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
     async fn update_column_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace> {
         let stage = self.stage();
         match stage.namespaces.iter_mut().find(|n| n.name == name) {
@@ -880,6 +972,18 @@ impl PartitionRepo for MemTxn {
         Ok(stage.skipped_compactions.clone())
     }
 
+    async fn delete_skipped_compactions(
+        &mut self,
+        partition_id: PartitionId,
+    ) -> Result<Option<SkippedCompaction>> {
+        let stage = self.stage();
+        Ok(stage
+            .skipped_compactions
+            .iter()
+            .position(|s| s.partition_id == partition_id)
+            .map(|idx| stage.skipped_compactions.remove(idx)))
+    }
+
     async fn update_persisted_sequence_number(
         &mut self,
         partition_id: PartitionId,
@@ -1135,6 +1239,18 @@ impl ParquetFileRepo for MemTxn {
         Ok(parquet_files)
     }
 
+    async fn list_by_table_to_delete(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>> {
+        let stage = self.stage();
+
+        let parquet_files: Vec<_> = stage
+            .parquet_files
+            .iter()
+            .filter(|f| table_id == f.table_id && f.to_delete.is_some())
+            .cloned()
+            .collect();
+        Ok(parquet_files)
+    }
+
     async fn delete_old(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFile>> {
         let stage = self.stage();
 
@@ -1501,6 +1617,34 @@ impl ProcessedTombstoneRepo for MemTxn {
             .any(|f| f.parquet_file_id == parquet_file_id && f.tombstone_id == tombstone_id))
     }
 
+    async fn list_by_tombstone_ids(
+        &mut self,
+        tombstone_ids: &[TombstoneId],
+    ) -> Result<Vec<ProcessedTombstone>> {
+        let stage = self.stage();
+
+        Ok(stage
+            .processed_tombstones
+            .iter()
+            .filter(|pt| tombstone_ids.contains(&pt.tombstone_id))
+            .copied()
+            .collect())
+    }
+
+    async fn list_by_parquet_file_ids(
+        &mut self,
+        parquet_file_ids: &[ParquetFileId],
+    ) -> Result<Vec<ProcessedTombstone>> {
+        let stage = self.stage();
+
+        Ok(stage
+            .processed_tombstones
+            .iter()
+            .filter(|pt| parquet_file_ids.contains(&pt.parquet_file_id))
+            .copied()
+            .collect())
+    }
+
     async fn count(&mut self) -> Result<i64> {
         let stage = self.stage();
 
@@ -1525,6 +1669,85 @@ impl ProcessedTombstoneRepo for MemTxn {
     }
 }
 
+#[async_trait]
+impl NamespaceTokenRepo for MemTxn {
+    async fn create_or_update(
+        &mut self,
+        token_hash: &str,
+        namespace_id: NamespaceId,
+        can_read: bool,
+        can_write: bool,
+    ) -> Result<NamespaceToken> {
+        let stage = self.stage();
+
+        if !stage.namespaces.iter().any(|n| n.id == namespace_id) {
+            return Err(Error::NamespaceNotFoundById { id: namespace_id });
+        }
+
+        if let Some(token) = stage
+            .namespace_tokens
+            .iter_mut()
+            .find(|t| t.token_hash == token_hash && t.namespace_id == namespace_id)
+        {
+            token.can_read = can_read;
+            token.can_write = can_write;
+            return Ok(token.clone());
+        }
+
+        // grants can be deleted, so the number of grants can't be used as the next id
+        let id = stage
+            .namespace_tokens
+            .iter()
+            .map(|t| t.id.get())
+            .max()
+            .unwrap_or_default()
+            + 1;
+        let token = NamespaceToken {
+            id: NamespaceTokenId::new(id),
+            token_hash: token_hash.to_string(),
+            namespace_id,
+            can_read,
+            can_write,
+        };
+        stage.namespace_tokens.push(token.clone());
+
+        Ok(token)
+    }
+
+    async fn list_by_token_hash(&mut self, token_hash: &str) -> Result<Vec<NamespaceToken>> {
+        let stage = self.stage();
+
+        Ok(stage
+            .namespace_tokens
+            .iter()
+            .filter(|t| t.token_hash == token_hash)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<NamespaceToken>> {
+        let stage = self.stage();
+
+        Ok(stage
+            .namespace_tokens
+            .iter()
+            .filter(|t| t.namespace_id == namespace_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete(&mut self, id: NamespaceTokenId) -> Result<()> {
+        let stage = self.stage();
+
+        stage.namespace_tokens.retain(|t| t.id != id);
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,17 +1,19 @@
 //! Metric instrumentation for catalog implementations.
 
 use crate::interface::{
-    sealed::TransactionFinalize, ColumnRepo, ColumnUpsertRequest, NamespaceRepo, ParquetFileRepo,
-    PartitionRepo, ProcessedTombstoneRepo, QueryPoolRepo, RepoCollection, Result, ShardRepo,
-    TablePersistInfo, TableRepo, TombstoneRepo, TopicMetadataRepo,
+    sealed::TransactionFinalize, ColumnRepo, ColumnUpsertRequest, NamespaceRepo,
+    NamespaceTokenRepo, ParquetFileRepo, PartitionRepo, ProcessedTombstoneRepo, QueryPoolRepo,
+    RepoCollection, Result, ShardRepo, TablePersistInfo, TableRepo, TombstoneRepo,
+    TopicMetadataRepo,
 };
 use async_trait::async_trait;
 use data_types::{
-    Column, ColumnType, ColumnTypeCount, CompactionLevel, Namespace, NamespaceId, ParquetFile,
-    ParquetFileId, ParquetFileParams, Partition, PartitionId, PartitionInfo, PartitionKey,
-    PartitionParam, ProcessedTombstone, QueryPool, QueryPoolId, SequenceNumber, Shard, ShardId,
-    ShardIndex, SkippedCompaction, Table, TableId, TablePartition, Timestamp, Tombstone,
-    TombstoneId, TopicId, TopicMetadata,
+    Column, ColumnType, ColumnTypeCount, CompactionLevel, FieldCoercionPolicy, Namespace,
+    NamespaceId, NamespacePersistencePolicy, NamespaceQueryLimits, NamespaceToken,
+    NamespaceTokenId, NamespaceWriteLimits, ParquetFile, ParquetFileId, ParquetFileParams,
+    Partition, PartitionId, PartitionInfo, PartitionKey, PartitionParam, ProcessedTombstone,
+    QueryPool, QueryPoolId, SequenceNumber, Shard, ShardId, ShardIndex, SkippedCompaction, Table,
+    TableId, TablePartition, Timestamp, Tombstone, TombstoneId, TopicId, TopicMetadata,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use metric::{DurationHistogram, Metric};
@@ -54,6 +56,7 @@ where
         + TombstoneRepo
         + ProcessedTombstoneRepo
         + ParquetFileRepo
+        + NamespaceTokenRepo
         + Debug,
     P: TimeProvider,
 {
@@ -96,6 +99,10 @@ where
     fn processed_tombstones(&mut self) -> &mut dyn ProcessedTombstoneRepo {
         self
     }
+
+    fn namespace_tokens(&mut self) -> &mut dyn NamespaceTokenRepo {
+        self
+    }
 }
 
 #[async_trait]
@@ -199,6 +206,11 @@ decorate!(
         "namespace_get_by_name" = get_by_name(&mut self, name: &str) -> Result<Option<Namespace>>;
         "namespace_update_table_limit" = update_table_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace>;
         "namespace_update_column_limit" = update_column_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace>;
+        "namespace_update_query_limits" = update_query_limits(&mut self, name: &str, limits: NamespaceQueryLimits) -> Result<Namespace>;
+        "namespace_update_persistence_policy" = update_persistence_policy(&mut self, name: &str, policy: NamespacePersistencePolicy) -> Result<Namespace>;
+        "namespace_update_write_limits" = update_write_limits(&mut self, name: &str, limits: NamespaceWriteLimits) -> Result<Namespace>;
+        "namespace_update_strict_schema" = update_strict_schema(&mut self, name: &str, strict_schema: bool) -> Result<Namespace>;
+        "namespace_update_field_coercion_policy" = update_field_coercion_policy(&mut self, name: &str, policy: FieldCoercionPolicy) -> Result<Namespace>;
     ]
 );
 
@@ -249,6 +261,7 @@ decorate!(
         "partition_update_sort_key" = update_sort_key(&mut self, partition_id: PartitionId, sort_key: &[&str]) -> Result<Partition>;
         "partition_record_skipped_compaction" = record_skipped_compaction(&mut self, partition_id: PartitionId, reason: &str) -> Result<()>;
         "partition_list_skipped_compactions" = list_skipped_compactions(&mut self) -> Result<Vec<SkippedCompaction>>;
+        "partition_delete_skipped_compactions" = delete_skipped_compactions(&mut self, partition_id: PartitionId) -> Result<Option<SkippedCompaction>>;
         "partition_update_persisted_sequence_number" = update_persisted_sequence_number(&mut self, partition_id: PartitionId, sequence_number: SequenceNumber) -> Result<()>;
     ]
 );
@@ -274,6 +287,7 @@ decorate!(
         "parquet_list_by_shard_greater_than" = list_by_shard_greater_than(&mut self, shard_id: ShardId, sequence_number: SequenceNumber) -> Result<Vec<ParquetFile>>;
         "parquet_list_by_namespace_not_to_delete" = list_by_namespace_not_to_delete(&mut self, namespace_id: NamespaceId) -> Result<Vec<ParquetFile>>;
         "parquet_list_by_table_not_to_delete" = list_by_table_not_to_delete(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>>;
+        "parquet_list_by_table_to_delete" = list_by_table_to_delete(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>>;
         "parquet_delete_old" = delete_old(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFile>>;
         "parquet_delete_old_ids_only" = delete_old_ids_only(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFileId>>;
         "parquet_list_by_partition_not_to_delete" = list_by_partition_not_to_delete(&mut self, partition_id: PartitionId) -> Result<Vec<ParquetFile>>;
@@ -295,7 +309,19 @@ decorate!(
     methods = [
         "processed_tombstone_create" = create(&mut self, parquet_file_id: ParquetFileId, tombstone_id: TombstoneId) -> Result<ProcessedTombstone>;
         "processed_tombstone_exist" = exist(&mut self, parquet_file_id: ParquetFileId, tombstone_id: TombstoneId) -> Result<bool>;
+        "processed_tombstone_list_by_tombstone_ids" = list_by_tombstone_ids(&mut self, tombstone_ids: &[TombstoneId]) -> Result<Vec<ProcessedTombstone>>;
+        "processed_tombstone_list_by_parquet_file_ids" = list_by_parquet_file_ids(&mut self, parquet_file_ids: &[ParquetFileId]) -> Result<Vec<ProcessedTombstone>>;
         "processed_tombstone_count" = count(&mut self) -> Result<i64>;
         "processed_tombstone_count_by_tombstone_id" = count_by_tombstone_id(&mut self, tombstone_id: TombstoneId) -> Result<i64>;
     ]
 );
+
+decorate!(
+    impl_trait = NamespaceTokenRepo,
+    methods = [
+        "namespace_token_create_or_update" = create_or_update(&mut self, token_hash: &str, namespace_id: NamespaceId, can_read: bool, can_write: bool) -> Result<NamespaceToken>;
+        "namespace_token_list_by_token_hash" = list_by_token_hash(&mut self, token_hash: &str) -> Result<Vec<NamespaceToken>>;
+        "namespace_token_list_by_namespace_id" = list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<NamespaceToken>>;
+        "namespace_token_delete" = delete(&mut self, id: NamespaceTokenId) -> Result<()>;
+    ]
+);
@@ -3,19 +3,20 @@
 use crate::{
     interface::{
         self, sealed::TransactionFinalize, Catalog, ColumnRepo, ColumnTypeMismatchSnafu,
-        ColumnUpsertRequest, Error, NamespaceRepo, ParquetFileRepo, PartitionRepo,
-        ProcessedTombstoneRepo, QueryPoolRepo, RepoCollection, Result, ShardRepo, TablePersistInfo,
-        TableRepo, TombstoneRepo, TopicMetadataRepo, Transaction,
+        ColumnUpsertRequest, Error, NamespaceRepo, NamespaceTokenRepo, ParquetFileRepo,
+        PartitionRepo, ProcessedTombstoneRepo, QueryPoolRepo, RepoCollection, Result, ShardRepo,
+        TablePersistInfo, TableRepo, TombstoneRepo, TopicMetadataRepo, Transaction,
     },
     metrics::MetricDecorator,
 };
 use async_trait::async_trait;
 use data_types::{
-    Column, ColumnType, ColumnTypeCount, CompactionLevel, Namespace, NamespaceId, ParquetFile,
-    ParquetFileId, ParquetFileParams, Partition, PartitionId, PartitionInfo, PartitionKey,
-    PartitionParam, ProcessedTombstone, QueryPool, QueryPoolId, SequenceNumber, Shard, ShardId,
-    ShardIndex, SkippedCompaction, Table, TableId, TablePartition, Timestamp, Tombstone,
-    TombstoneId, TopicId, TopicMetadata,
+    Column, ColumnType, ColumnTypeCount, CompactionLevel, FieldCoercionPolicy, Namespace,
+    NamespaceId, NamespacePersistencePolicy, NamespaceQueryLimits, NamespaceToken,
+    NamespaceTokenId, NamespaceWriteLimits, ParquetFile, ParquetFileId, ParquetFileParams,
+    Partition, PartitionId, PartitionInfo, PartitionKey, PartitionParam, ProcessedTombstone,
+    QueryPool, QueryPoolId, SequenceNumber, Shard, ShardId, ShardIndex, SkippedCompaction, Table,
+    TableId, TablePartition, Timestamp, Tombstone, TombstoneId, TopicId, TopicMetadata,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use observability_deps::tracing::{debug, info, warn};
@@ -519,6 +520,10 @@ impl RepoCollection for PostgresTxn {
     fn processed_tombstones(&mut self) -> &mut dyn ProcessedTombstoneRepo {
         self
     }
+
+    fn namespace_tokens(&mut self) -> &mut dyn NamespaceTokenRepo {
+        self
+    }
 }
 
 #[async_trait]
@@ -701,6 +706,151 @@ RETURNING *;
         Ok(namespace)
     }
 
+    async fn update_query_limits(
+        &mut self,
+        name: &str,
+        limits: NamespaceQueryLimits,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET max_concurrent_queries = $1, max_query_memory_bytes = $2,
+    max_query_scanned_bytes = $3, max_query_duration_seconds = $4
+WHERE name = $5
+RETURNING *;
+        "#,
+        )
+        .bind(&limits.max_concurrent_queries) // $1
+        .bind(&limits.max_query_memory_bytes) // $2
+        .bind(&limits.max_query_scanned_bytes) // $3
+        .bind(&limits.max_query_duration_seconds) // $4
+        .bind(&name) // $5
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_persistence_policy(
+        &mut self,
+        name: &str,
+        policy: NamespacePersistencePolicy,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET partition_age_threshold_seconds = $1, partition_cold_threshold_seconds = $2,
+    partition_size_threshold_bytes = $3, partition_row_max = $4
+WHERE name = $5
+RETURNING *;
+        "#,
+        )
+        .bind(&policy.partition_age_threshold_seconds) // $1
+        .bind(&policy.partition_cold_threshold_seconds) // $2
+        .bind(&policy.partition_size_threshold_bytes) // $3
+        .bind(&policy.partition_row_max) // $4
+        .bind(&name) // $5
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_strict_schema(&mut self, name: &str, strict_schema: bool) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET strict_schema = $1
+WHERE name = $2
+RETURNING *;
+        "#,
+        )
+        .bind(&strict_schema) // $1
+        .bind(&name) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_field_coercion_policy(
+        &mut self,
+        name: &str,
+        policy: FieldCoercionPolicy,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET field_coercion_policy = $1
+WHERE name = $2
+RETURNING *;
+        "#,
+        )
+        .bind(&policy) // $1
+        .bind(&name) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_write_limits(
+        &mut self,
+        name: &str,
+        limits: NamespaceWriteLimits,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET write_lines_per_second = $1, write_bytes_per_second = $2
+WHERE name = $3
+RETURNING *;
+        "#,
+        )
+        .bind(&limits.write_lines_per_second) // $1
+        .bind(&limits.write_bytes_per_second) // $2
+        .bind(&name) // $3
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
     async fn update_column_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace> {
         let rec = sqlx::query_as::<_, Namespace>(
             r#"
@@ -1345,6 +1495,23 @@ SELECT * FROM skipped_compactions
         .context(interface::CouldNotListSkippedCompactionsSnafu)
     }
 
+    async fn delete_skipped_compactions(
+        &mut self,
+        partition_id: PartitionId,
+    ) -> Result<Option<SkippedCompaction>> {
+        sqlx::query_as::<_, SkippedCompaction>(
+            r#"
+DELETE FROM skipped_compactions
+WHERE partition_id = $1
+RETURNING *
+        "#,
+        )
+        .bind(partition_id)
+        .fetch_optional(&mut self.inner)
+        .await
+        .context(interface::CouldNotDeleteSkippedCompactionsSnafu { partition_id })
+    }
+
     async fn update_persisted_sequence_number(
         &mut self,
         partition_id: PartitionId,
@@ -1707,6 +1874,24 @@ WHERE table_id = $1 AND to_delete IS NULL;
         .map_err(|e| Error::SqlxError { source: e })
     }
 
+    async fn list_by_table_to_delete(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>> {
+        // Deliberately doesn't use `SELECT *` to avoid the performance hit of fetching the large
+        // `parquet_metadata` column!!
+        sqlx::query_as::<_, ParquetFile>(
+            r#"
+SELECT id, shard_id, namespace_id, table_id, partition_id, object_store_id,
+       max_sequence_number, min_time, max_time, to_delete, file_size_bytes,
+       row_count, compaction_level, created_at, column_set
+FROM parquet_file
+WHERE table_id = $1 AND to_delete IS NOT NULL;
+             "#,
+        )
+        .bind(&table_id) // $1
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
     async fn delete_old(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFile>> {
         sqlx::query_as::<_, ParquetFile>(
             r#"
@@ -2096,6 +2281,44 @@ WHERE parquet_file_id = $1
         Ok(read_result.count > 0)
     }
 
+    async fn list_by_tombstone_ids(
+        &mut self,
+        tombstone_ids: &[TombstoneId],
+    ) -> Result<Vec<ProcessedTombstone>> {
+        let ids: Vec<_> = tombstone_ids.iter().map(|t| t.get()).collect();
+
+        sqlx::query_as::<_, ProcessedTombstone>(
+            r#"
+SELECT *
+FROM processed_tombstone
+WHERE tombstone_id = ANY($1);
+            "#,
+        )
+        .bind(&ids[..]) // $1
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn list_by_parquet_file_ids(
+        &mut self,
+        parquet_file_ids: &[ParquetFileId],
+    ) -> Result<Vec<ProcessedTombstone>> {
+        let ids: Vec<_> = parquet_file_ids.iter().map(|f| f.get()).collect();
+
+        sqlx::query_as::<_, ProcessedTombstone>(
+            r#"
+SELECT *
+FROM processed_tombstone
+WHERE parquet_file_id = ANY($1);
+            "#,
+        )
+        .bind(&ids[..]) // $1
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
     async fn count(&mut self) -> Result<i64> {
         let read_result =
             sqlx::query_as::<_, Count>(r#"SELECT count(1) as count FROM processed_tombstone;"#)
@@ -2119,6 +2342,73 @@ WHERE parquet_file_id = $1
     }
 }
 
+#[async_trait]
+impl NamespaceTokenRepo for PostgresTxn {
+    async fn create_or_update(
+        &mut self,
+        token_hash: &str,
+        namespace_id: NamespaceId,
+        can_read: bool,
+        can_write: bool,
+    ) -> Result<NamespaceToken> {
+        sqlx::query_as::<_, NamespaceToken>(
+            r#"
+INSERT INTO namespace_token ( token_hash, namespace_id, can_read, can_write )
+VALUES ( $1, $2, $3, $4 )
+ON CONFLICT ON CONSTRAINT namespace_token_unique
+DO UPDATE SET can_read = EXCLUDED.can_read, can_write = EXCLUDED.can_write
+RETURNING *;
+        "#,
+        )
+        .bind(token_hash) // $1
+        .bind(namespace_id) // $2
+        .bind(can_read) // $3
+        .bind(can_write) // $4
+        .fetch_one(&mut self.inner)
+        .await
+        .map_err(|e| {
+            if is_fk_violation(&e) {
+                Error::ForeignKeyViolation { source: e }
+            } else {
+                Error::SqlxError { source: e }
+            }
+        })
+    }
+
+    async fn list_by_token_hash(&mut self, token_hash: &str) -> Result<Vec<NamespaceToken>> {
+        sqlx::query_as::<_, NamespaceToken>(
+            r#"SELECT * FROM namespace_token WHERE token_hash = $1;"#,
+        )
+        .bind(token_hash) // $1
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn list_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<NamespaceToken>> {
+        sqlx::query_as::<_, NamespaceToken>(
+            r#"SELECT * FROM namespace_token WHERE namespace_id = $1;"#,
+        )
+        .bind(namespace_id) // $1
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn delete(&mut self, id: NamespaceTokenId) -> Result<()> {
+        sqlx::query(r#"DELETE FROM namespace_token WHERE id = $1;"#)
+            .bind(id) // $1
+            .execute(&mut self.inner)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(())
+    }
+}
+
 /// The error code returned by Postgres for a unique constraint violation.
 ///
 /// See <https://www.postgresql.org/docs/9.2/errcodes-appendix.html>
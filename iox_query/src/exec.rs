@@ -2,9 +2,11 @@
 //! plans. This is currently implemented using DataFusion, and this
 //! interface abstracts away many of the details
 pub(crate) mod context;
+pub mod explain_analyze;
 pub mod field;
 pub mod fieldlist;
 mod non_null_checker;
+mod query_stats;
 mod query_tracing;
 mod schema_pivot;
 pub mod seriesset;
@@ -27,6 +29,7 @@ use datafusion::{
 };
 
 pub use context::{IOxSessionConfig, IOxSessionContext, SessionContextIOxExt};
+pub use query_stats::{QueryStats, ScanStats};
 use schema_pivot::SchemaPivotNode;
 
 use self::{non_null_checker::NonNullCheckerNode, split::StreamSplitNode};
@@ -114,7 +117,7 @@ impl Executor {
         let inner = SessionContext::with_state(state.clone());
         let exec = self.executor(executor_type).clone();
         let recorder = SpanRecorder::new(state.span_ctx().child_span("Query Execution"));
-        IOxSessionContext::new(inner, Some(exec), recorder)
+        IOxSessionContext::new(inner, Some(exec), recorder, state.query_stats())
     }
 
     /// Create a new execution context, suitable for executing a new query or system task
@@ -13,7 +13,9 @@ use datafusion::{
     config::OPT_COALESCE_TARGET_BATCH_SIZE,
     execution::{
         context::{QueryPlanner, SessionState, TaskContext},
-        runtime_env::RuntimeEnv,
+        disk_manager::DiskManagerConfig,
+        memory_manager::MemoryManagerConfig,
+        runtime_env::{RuntimeConfig, RuntimeEnv},
     },
     logical_plan::{LogicalPlan, UserDefinedLogicalNode},
     physical_plan::{
@@ -23,17 +25,21 @@ use datafusion::{
         EmptyRecordBatchStream, ExecutionPlan, PhysicalPlanner, SendableRecordBatchStream,
     },
     prelude::*,
+    scalar::ScalarValue,
+    sql::planner::SqlToRel,
 };
 use futures::TryStreamExt;
 use observability_deps::tracing::debug;
 use trace::{
     ctx::SpanContext,
     span::{MetaValue, Span, SpanExt, SpanRecorder},
+    TraceCollector,
 };
 
 use crate::exec::{
     fieldlist::{FieldList, IntoFieldList},
     non_null_checker::NonNullCheckerExec,
+    query_stats::QueryStats,
     query_tracing::TracedStream,
     schema_pivot::{SchemaPivotExec, SchemaPivotNode},
     seriesset::{
@@ -44,6 +50,8 @@ use crate::exec::{
     stringset::{IntoStringSet, StringSetRef},
 };
 
+use crate::frontend::sql::bind_params;
+use crate::physical_optimizer::ChunkAggregatePushdown;
 use crate::plan::{
     fieldlist::FieldListPlan,
     seriesset::{SeriesSetPlan, SeriesSetPlans},
@@ -218,6 +226,25 @@ impl IOxSessionConfig {
         }
     }
 
+    /// Limit the memory the operators of this query may reserve to
+    /// `max_memory` bytes.
+    ///
+    /// The query gets its own memory manager instead of the one of the
+    /// shared runtime; operators that exceed the limit fail with
+    /// [`DataFusionError::ResourcesExhausted`](datafusion::error::DataFusionError::ResourcesExhausted).
+    pub fn with_memory_limit(self, max_memory: usize) -> Self {
+        let runtime_config = RuntimeConfig::new()
+            .with_memory_manager(
+                MemoryManagerConfig::try_new_limit(max_memory, 1.0).expect("valid memory fraction"),
+            )
+            .with_disk_manager(DiskManagerConfig::Existing(Arc::clone(
+                &self.runtime.disk_manager,
+            )));
+        let runtime = Arc::new(RuntimeEnv::new(runtime_config).expect("creating runtime"));
+
+        Self { runtime, ..self }
+    }
+
     /// Set the span context from which to create  distributed tracing spans for this query
     pub fn with_span_context(self, span_ctx: Option<SpanContext>) -> Self {
         Self { span_ctx, ..self }
@@ -226,7 +253,8 @@ impl IOxSessionConfig {
     /// Create an ExecutionContext suitable for executing DataFusion plans
     pub fn build(self) -> IOxSessionContext {
         let state = SessionState::with_config_rt(self.session_config, self.runtime)
-            .with_query_planner(Arc::new(IOxQueryPlanner {}));
+            .with_query_planner(Arc::new(IOxQueryPlanner {}))
+            .add_physical_optimizer_rule(Arc::new(ChunkAggregatePushdown::default()));
 
         let state = register_selector_aggregates(state);
 
@@ -238,7 +266,12 @@ impl IOxSessionConfig {
 
         let maybe_span = self.span_ctx.child_span("Query Execution");
 
-        IOxSessionContext::new(inner, Some(self.exec), SpanRecorder::new(maybe_span))
+        IOxSessionContext::new(
+            inner,
+            Some(self.exec),
+            SpanRecorder::new(maybe_span),
+            Default::default(),
+        )
     }
 }
 
@@ -267,6 +300,9 @@ pub struct IOxSessionContext {
 
     /// Span context from which to create spans for this query
     recorder: SpanRecorder,
+
+    /// Statistics of this query, shared with all child contexts
+    stats: Arc<QueryStats>,
 }
 
 impl fmt::Debug for IOxSessionContext {
@@ -287,6 +323,7 @@ impl IOxSessionContext {
             inner: SessionContext::default(),
             exec: None,
             recorder: SpanRecorder::default(),
+            stats: Default::default(),
         }
     }
 
@@ -295,20 +332,23 @@ impl IOxSessionContext {
         inner: SessionContext,
         exec: Option<DedicatedExecutor>,
         recorder: SpanRecorder,
+        stats: Arc<QueryStats>,
     ) -> Self {
-        // attach span to DataFusion session
+        // attach span and stats to DataFusion session
         {
             let mut state = inner.state.write();
             state.config = state
                 .config
                 .clone()
-                .with_extension(Arc::new(recorder.span().cloned()));
+                .with_extension(Arc::new(recorder.span().cloned()))
+                .with_extension(Arc::clone(&stats));
         }
 
         Self {
             inner,
             exec,
             recorder,
+            stats,
         }
     }
 
@@ -327,6 +367,29 @@ impl IOxSessionContext {
         ctx.create_physical_plan(&logical_plan).await
     }
 
+    /// Prepare a SQL statement for execution, binding its positional
+    /// placeholders `$1`, `$2`, ... to the literals of `params`.
+    ///
+    /// The values are bound when the statement is parsed rather than pasted
+    /// into `sql`, so they need no quoting or escaping.
+    pub async fn prepare_sql_with_params(
+        &self,
+        sql: &str,
+        params: &[ScalarValue],
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if params.is_empty() {
+            return self.prepare_sql(sql).await;
+        }
+
+        let ctx = self.child_ctx("prepare_sql_with_params");
+        debug!(text=%sql, n_params=params.len(), "planning SQL query with parameters");
+        let statement = bind_params(sql, params)?;
+        let state = ctx.inner.state.read().clone();
+        let logical_plan = SqlToRel::new(&state).statement_to_plan(statement)?;
+        debug!(plan=%logical_plan.display_graphviz(), "logical plan");
+        ctx.create_physical_plan(&logical_plan).await
+    }
+
     /// Prepare (optimize + plan) a pre-created [`LogicalPlan`] for execution
     pub async fn create_physical_plan(&self, plan: &LogicalPlan) -> Result<Arc<dyn ExecutionPlan>> {
         let mut ctx = self.child_ctx("create_physical_plan");
@@ -609,9 +672,44 @@ impl IOxSessionContext {
             self.inner.clone(),
             self.exec.clone(),
             self.recorder.child(name),
+            Arc::clone(&self.stats),
         )
     }
 
+    /// Returns a IOxSessionContext with a child span that is exported, along
+    /// with all of its descendants, to `collector`.
+    ///
+    /// The span is created even if this context is not traced, so
+    /// `collector` sees the spans of everything that runs within the
+    /// returned context. It is responsible for forwarding them to the
+    /// collector of this context, if any.
+    pub fn child_ctx_with_collector(
+        &self,
+        name: &'static str,
+        collector: Arc<dyn TraceCollector>,
+    ) -> Self {
+        let span = match self.recorder.span() {
+            Some(span) => {
+                let mut span = span.child(name);
+                span.ctx.collector = Some(collector);
+                span
+            }
+            None => Span::root(name, collector),
+        };
+
+        Self::new(
+            self.inner.clone(),
+            self.exec.clone(),
+            SpanRecorder::new(Some(span)),
+            Arc::clone(&self.stats),
+        )
+    }
+
+    /// Returns the statistics of this query
+    pub fn stats(&self) -> &Arc<QueryStats> {
+        &self.stats
+    }
+
     /// Record an event on the span recorder
     pub fn record_event(&mut self, name: &'static str) {
         self.recorder.event(name);
@@ -640,6 +738,9 @@ pub trait SessionContextIOxExt {
 
     /// Get span context
     fn span_ctx(&self) -> Option<SpanContext>;
+
+    /// Get the statistics of the query this context belongs to.
+    fn query_stats(&self) -> Arc<QueryStats>;
 }
 
 impl SessionContextIOxExt for SessionState {
@@ -654,4 +755,8 @@ impl SessionContextIOxExt for SessionState {
             .get_extension::<Option<Span>>()
             .and_then(|span| span.as_ref().as_ref().map(|span| span.ctx.clone()))
     }
+
+    fn query_stats(&self) -> Arc<QueryStats> {
+        self.config.get_extension::<QueryStats>().unwrap_or_default()
+    }
 }
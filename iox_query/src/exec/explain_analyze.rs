@@ -0,0 +1,346 @@
+//! This module contains the implementation of `EXPLAIN ANALYZE` for SQL
+//! queries.
+//!
+//! DataFusion's own `EXPLAIN ANALYZE` only reports the metrics of the
+//! operators of the plan. [`ExplainAnalyzeExec`] additionally reports the
+//! [`QueryStats`] gathered while planning the query (e.g. the files pruned)
+//! and the catalog cache requests made for it, which are otherwise only
+//! visible in traces.
+
+use std::{any::Any, collections::BTreeMap, fmt, sync::Arc};
+
+use arrow::{
+    array::{ArrayRef, StringArray},
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use datafusion::{
+    error::{DataFusionError, Result},
+    execution::context::TaskContext,
+    physical_plan::{
+        collect, expressions::PhysicalSortExpr, stream::RecordBatchStreamAdapter,
+        DisplayFormatType, DisplayableExecutionPlan, Distribution, ExecutionPlan, Partitioning,
+        SendableRecordBatchStream, Statistics,
+    },
+};
+use futures::stream;
+use parking_lot::Mutex;
+use trace::{span::Span, TraceCollector};
+
+use super::{QueryStats, ScanStats};
+
+/// Prefix of the names of the spans recorded for cache requests, followed
+/// by the name of the cache.
+const CACHE_GET_SPAN_PREFIX: &str = "cache GET ";
+
+/// Number of requests made to a single cache, by status.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct CacheRequests {
+    hits: u64,
+    misses: u64,
+    misses_already_loading: u64,
+}
+
+/// A [`TraceCollector`] that counts the cache requests of a query.
+///
+/// Cache requests are recorded as `cache GET <cache>` spans that carry the
+/// status of the request as an event. All spans are forwarded to the
+/// collector of the query, if any.
+#[derive(Debug)]
+pub struct CacheRequestCollector {
+    inner: Option<Arc<dyn TraceCollector>>,
+    requests: Mutex<BTreeMap<String, CacheRequests>>,
+}
+
+impl CacheRequestCollector {
+    /// Create a new collector that forwards spans to `inner`.
+    pub fn new(inner: Option<Arc<dyn TraceCollector>>) -> Self {
+        Self {
+            inner,
+            requests: Default::default(),
+        }
+    }
+
+    /// Formats the cache requests seen so far, one line per cache.
+    fn report(&self) -> String {
+        let requests = self.requests.lock();
+        if requests.is_empty() {
+            return "no cache requests".to_string();
+        }
+
+        requests
+            .iter()
+            .map(|(cache, r)| {
+                format!(
+                    "{}: hits={}, misses={}, misses_already_loading={}",
+                    cache, r.hits, r.misses, r.misses_already_loading
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl TraceCollector for CacheRequestCollector {
+    fn export(&self, span: Span) {
+        if let Some(cache) = span.name.strip_prefix(CACHE_GET_SPAN_PREFIX) {
+            let mut requests = self.requests.lock();
+            for event in &span.events {
+                let requests = requests.entry(cache.to_string()).or_default();
+                match event.msg.as_ref() {
+                    "hit" => requests.hits += 1,
+                    "miss" => requests.misses += 1,
+                    "miss_already_loading" => requests.misses_already_loading += 1,
+                    _ => {}
+                }
+            }
+        }
+
+        if let Some(inner) = &self.inner {
+            inner.export(span);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Runs its input to completion and produces a report of the execution,
+/// consisting of the plan annotated with the metrics of every operator,
+/// the [`QueryStats`] and the cache requests of the query.
+///
+/// The output has the same `plan_type`, `plan` schema as DataFusion's
+/// `EXPLAIN` output.
+#[derive(Debug)]
+pub struct ExplainAnalyzeExec {
+    input: Arc<dyn ExecutionPlan>,
+    stats: Arc<QueryStats>,
+    cache_requests: Arc<CacheRequestCollector>,
+    schema: SchemaRef,
+}
+
+impl ExplainAnalyzeExec {
+    /// Create a new node analyzing `input`, whose planning was recorded in
+    /// `stats` and `cache_requests`.
+    pub fn new(
+        input: Arc<dyn ExecutionPlan>,
+        stats: Arc<QueryStats>,
+        cache_requests: Arc<CacheRequestCollector>,
+    ) -> Self {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("plan_type", DataType::Utf8, false),
+            Field::new("plan", DataType::Utf8, false),
+        ]));
+
+        Self {
+            input,
+            stats,
+            cache_requests,
+            schema,
+        }
+    }
+}
+
+impl ExecutionPlan for ExplainAnalyzeExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn required_child_distribution(&self) -> Distribution {
+        Distribution::UnspecifiedDistribution
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![Arc::clone(&self.input)]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(Self::new(
+                Arc::clone(&children[0]),
+                Arc::clone(&self.stats),
+                Arc::clone(&self.cache_requests),
+            ))),
+            _ => Err(DataFusionError::Internal(
+                "ExplainAnalyzeExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "ExplainAnalyzeExec invalid partition {}",
+                partition
+            )));
+        }
+
+        let input = Arc::clone(&self.input);
+        let stats = Arc::clone(&self.stats);
+        let cache_requests = Arc::clone(&self.cache_requests);
+        let schema = self.schema();
+
+        let report = async move {
+            let batches = collect(Arc::clone(&input), context)
+                .await
+                .map_err(|e| ArrowError::ExternalError(Box::new(e)))?;
+            let rows_returned: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+
+            let plan_types = StringArray::from(vec![
+                "Plan with Metrics",
+                "Query Statistics",
+                "Cache Statistics",
+            ]);
+            let plans = StringArray::from(vec![
+                DisplayableExecutionPlan::with_metrics(input.as_ref())
+                    .indent()
+                    .to_string(),
+                query_stats_report(&stats, &ScanStats::from_plan(input.as_ref()), rows_returned),
+                cache_requests.report(),
+            ]);
+
+            RecordBatch::try_new(
+                schema,
+                vec![Arc::new(plan_types) as ArrayRef, Arc::new(plans)],
+            )
+        };
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream::once(report),
+        )))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => write!(f, "ExplainAnalyzeExec"),
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Formats the statistics of an executed query.
+fn query_stats_report(stats: &QueryStats, scan_stats: &ScanStats, rows_returned: usize) -> String {
+    format!(
+        "rows_returned={}, rows_scanned={}, files_pruned={}, files_scanned={}, \
+         bytes_scanned={}, partitions_scanned={}, memory_bytes={}",
+        rows_returned,
+        scan_stats.rows_scanned,
+        stats.files_pruned(),
+        stats.files_scanned(),
+        stats.bytes_scanned(),
+        stats.partitions_scanned(),
+        scan_stats.memory_bytes,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, Int64Array};
+    use data_types::PartitionId;
+    use datafusion::{physical_plan::memory::MemoryExec, prelude::SessionContext};
+    use trace::RingBufferTraceCollector;
+
+    #[test]
+    fn test_cache_request_collector() {
+        let inner = Arc::new(RingBufferTraceCollector::new(10));
+        let collector = Arc::new(CacheRequestCollector::new(Some(
+            Arc::clone(&inner) as Arc<dyn TraceCollector>
+        )));
+        let root = Span::root("query", Arc::clone(&collector) as Arc<dyn TraceCollector>);
+
+        for (name, status) in [
+            ("cache GET parquet_file", "miss"),
+            ("cache GET parquet_file", "hit"),
+            ("cache GET tombstone", "miss_already_loading"),
+            ("ingester partitions", "hit"),
+        ] {
+            let mut span = root.child(name);
+            span.ok(status);
+            span.export();
+        }
+
+        // cancelled requests have no status
+        root.child("cache GET namespace schema").export();
+
+        assert_eq!(
+            collector.report(),
+            "parquet_file: hits=1, misses=1, misses_already_loading=0\n\
+             tombstone: hits=0, misses=0, misses_already_loading=1"
+        );
+        assert_eq!(inner.spans().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_explain_analyze() {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![Arc::new(Int64Array::from(vec![1, 2, 3]))],
+        )
+        .unwrap();
+        let input = Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap());
+
+        let stats = Arc::new(QueryStats::default());
+        stats.add_files_pruned(2);
+        stats.add_scanned(1, 100, [PartitionId::new(1)]);
+
+        let exec = Arc::new(ExplainAnalyzeExec::new(
+            input,
+            stats,
+            Arc::new(CacheRequestCollector::new(None)),
+        ));
+        let task_ctx = Arc::new(TaskContext::from(&SessionContext::new()));
+        let batches = collect(exec, task_ctx).await.unwrap();
+        assert_eq!(batches.len(), 1);
+
+        let column = |i: usize| {
+            let array = batches[0]
+                .column(i)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            (0..array.len())
+                .map(|row| array.value(row).to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            column(0),
+            vec!["Plan with Metrics", "Query Statistics", "Cache Statistics"]
+        );
+
+        let plans = column(1);
+        assert!(plans[0].starts_with("MemoryExec"), "{}", plans[0]);
+        assert_eq!(
+            plans[1],
+            "rows_returned=3, rows_scanned=0, files_pruned=2, files_scanned=1, \
+             bytes_scanned=100, partitions_scanned=1, memory_bytes=0"
+        );
+        assert_eq!(plans[2], "no cache requests");
+    }
+}
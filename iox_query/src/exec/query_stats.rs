@@ -0,0 +1,152 @@
+//! Statistics collected while planning and executing a single query.
+
+use data_types::PartitionId;
+use datafusion::physical_plan::ExecutionPlan;
+use parking_lot::Mutex;
+use std::{
+    collections::HashSet,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Statistics about a single query, shared by an [`IOxSessionContext`] and
+/// all of its children.
+///
+/// [`IOxSessionContext`]: super::IOxSessionContext
+#[derive(Debug, Default)]
+pub struct QueryStats {
+    /// Number of parquet files / chunks pruned before execution.
+    files_pruned: AtomicU64,
+
+    /// Number of parquet files left to scan after pruning.
+    files_scanned: AtomicU64,
+
+    /// Estimated size of the chunks left to scan after pruning.
+    bytes_scanned: AtomicU64,
+
+    /// Partitions of the chunks left to scan after pruning.
+    partitions_scanned: Mutex<HashSet<PartitionId>>,
+
+    /// Number of rows returned to the client.
+    rows_returned: AtomicU64,
+
+    /// Memory used by the executed plan, see [`ScanStats::memory_bytes`].
+    memory_bytes: AtomicU64,
+}
+
+impl QueryStats {
+    /// Record that `n` files (or chunks) were pruned.
+    pub fn add_files_pruned(&self, n: u64) {
+        self.files_pruned.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Number of files (or chunks) pruned so far.
+    pub fn files_pruned(&self) -> u64 {
+        self.files_pruned.load(Ordering::Relaxed)
+    }
+
+    /// Record the chunks that are left to scan once pruning is done:
+    /// `files` parquet files of `bytes` estimated bytes in total, belonging
+    /// to `partitions`.
+    pub fn add_scanned(
+        &self,
+        files: u64,
+        bytes: u64,
+        partitions: impl IntoIterator<Item = PartitionId>,
+    ) {
+        self.files_scanned.fetch_add(files, Ordering::Relaxed);
+        self.bytes_scanned.fetch_add(bytes, Ordering::Relaxed);
+        self.partitions_scanned.lock().extend(partitions);
+    }
+
+    /// Number of parquet files scanned.
+    pub fn files_scanned(&self) -> u64 {
+        self.files_scanned.load(Ordering::Relaxed)
+    }
+
+    /// Estimated number of bytes scanned.
+    pub fn bytes_scanned(&self) -> u64 {
+        self.bytes_scanned.load(Ordering::Relaxed)
+    }
+
+    /// Number of distinct partitions scanned.
+    pub fn partitions_scanned(&self) -> u64 {
+        self.partitions_scanned.lock().len() as u64
+    }
+
+    /// Record the outcome of executing the query plan.
+    pub fn set_executed(&self, rows_returned: u64, scan_stats: &ScanStats) {
+        self.rows_returned.store(rows_returned, Ordering::Relaxed);
+        self.memory_bytes
+            .store(scan_stats.memory_bytes, Ordering::Relaxed);
+    }
+
+    /// Number of rows returned to the client, if recorded by the caller.
+    pub fn rows_returned(&self) -> u64 {
+        self.rows_returned.load(Ordering::Relaxed)
+    }
+
+    /// Memory used by the executed plan, if recorded by the caller.
+    pub fn memory_bytes(&self) -> u64 {
+        self.memory_bytes.load(Ordering::Relaxed)
+    }
+}
+
+/// Statistics about the data scanned by an executed plan.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ScanStats {
+    /// Number of rows produced by the leaf nodes of the plan.
+    pub rows_scanned: u64,
+
+    /// Number of partitions (one per chunk for IOx scans) of the leaf
+    /// nodes of the plan.
+    pub chunks_scanned: u64,
+
+    /// Sum of the memory the operators of the plan report as used. As not
+    /// all operators hold on to their memory at the same time, this is an
+    /// upper bound of the peak memory use of the query.
+    pub memory_bytes: u64,
+}
+
+impl ScanStats {
+    /// Computes the scan statistics from the metrics of the (executed)
+    /// `plan`.
+    pub fn from_plan(plan: &dyn ExecutionPlan) -> Self {
+        let memory_bytes = plan
+            .metrics()
+            .map(|metrics| {
+                metrics
+                    .iter()
+                    .filter(|m| m.value().name() == "mem_used")
+                    .map(|m| m.value().as_usize() as u64)
+                    .sum()
+            })
+            .unwrap_or_default();
+
+        let children = plan.children();
+        if children.is_empty() {
+            return Self {
+                rows_scanned: plan
+                    .metrics()
+                    .and_then(|metrics| metrics.output_rows())
+                    .unwrap_or_default() as u64,
+                chunks_scanned: plan.output_partitioning().partition_count() as u64,
+                memory_bytes,
+            };
+        }
+
+        children
+            .iter()
+            .map(|child| Self::from_plan(child.as_ref()))
+            .fold(
+                Self {
+                    memory_bytes,
+                    ..Default::default()
+                },
+                |acc, stats| Self {
+                    rows_scanned: acc.rows_scanned + stats.rows_scanned,
+                    chunks_scanned: acc.chunks_scanned + stats.chunks_scanned,
+                    memory_bytes: acc.memory_bytes + stats.memory_bytes,
+                },
+            )
+    }
+}
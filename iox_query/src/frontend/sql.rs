@@ -1,7 +1,18 @@
 use std::sync::Arc;
 
-use crate::exec::context::IOxSessionContext;
-use datafusion::{error::Result, physical_plan::ExecutionPlan};
+use crate::exec::{
+    context::IOxSessionContext,
+    explain_analyze::{CacheRequestCollector, ExplainAnalyzeExec},
+};
+use datafusion::scalar::ScalarValue;
+use datafusion::{
+    error::{DataFusionError, Result},
+    physical_plan::ExecutionPlan,
+};
+use influxdb_influxql_parser::{parse_statements, Statement};
+
+mod params;
+pub(crate) use params::bind_params;
 
 /// This struct can create plans for running SQL queries against databases
 #[derive(Debug, Default)]
@@ -14,11 +25,177 @@ impl SqlQueryPlanner {
 
     /// Plan a SQL query against the catalogs registered with `ctx`, and return a
     /// DataFusion physical execution plan that runs on the query executor.
+    ///
+    /// `EXPLAIN ANALYZE <query>` is planned as an [`ExplainAnalyzeExec`], which
+    /// runs `<query>` and reports its metrics and statistics.
     pub async fn query(
         &self,
         query: &str,
         ctx: &IOxSessionContext,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        ctx.prepare_sql(query).await
+        self.query_with_params(query, &[], ctx).await
+    }
+
+    /// Plan a SQL query like [`query`](Self::query), binding its positional
+    /// placeholders `$1`, `$2`, ... to the literals of `params`.
+    pub async fn query_with_params(
+        &self,
+        query: &str,
+        params: &[ScalarValue],
+        ctx: &IOxSessionContext,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match parse_explain_analyze(query) {
+            Some(query) => {
+                let cache_requests = Arc::new(CacheRequestCollector::new(
+                    ctx.span().and_then(|span| span.ctx.collector.clone()),
+                ));
+                let analyze_ctx = ctx
+                    .child_ctx_with_collector("explain analyze", Arc::clone(&cache_requests) as _);
+                let plan = analyze_ctx.prepare_sql_with_params(query, params).await?;
+
+                Ok(Arc::new(ExplainAnalyzeExec::new(
+                    plan,
+                    Arc::clone(ctx.stats()),
+                    cache_requests,
+                )))
+            }
+            None => ctx.prepare_sql_with_params(query, params).await,
+        }
+    }
+}
+
+/// Parses an `EXPLAIN ANALYZE <query>` statement and returns `<query>`.
+///
+/// DataFusion has its own `EXPLAIN ANALYZE`, which only reports the metrics
+/// of the operators of the plan, so the statement is handled before planning.
+/// Returns `None` if `query` is any other statement, including
+/// `EXPLAIN ANALYZE VERBOSE`.
+pub fn parse_explain_analyze(query: &str) -> Option<&str> {
+    let query = strip_keyword(query, "explain")?;
+    let query = strip_keyword(query, "analyze")?;
+
+    match strip_keyword(query, "verbose") {
+        Some(_) => None,
+        None => Some(query),
+    }
+}
+
+/// Strips the leading `keyword` (in any case) and the following whitespace
+/// from `s`, if present.
+fn strip_keyword<'a>(s: &'a str, keyword: &str) -> Option<&'a str> {
+    let s = s.trim_start();
+    let end = s.find(char::is_whitespace)?;
+
+    if s[..end].eq_ignore_ascii_case(keyword) {
+        Some(s[end..].trim_start())
+    } else {
+        None
+    }
+}
+
+/// Parses a `KILL QUERY <id>` statement, which cancels the running query
+/// `<id>` as reported by `system.queries`.
+///
+/// The statement is not understood by DataFusion, so it must be handled
+/// before planning. It is parsed as InfluxQL, which shares the syntax with
+/// SQL. Returns `None` if `query` is any other statement.
+pub fn parse_kill_query(query: &str) -> Result<Option<u64>> {
+    if strip_keyword(query, "kill").is_none() {
+        return Ok(None);
+    }
+
+    let statements = parse_statements(query)
+        .map_err(|e| DataFusionError::Plan(format!("Invalid KILL QUERY statement: {}", e)))?;
+
+    match statements.as_slice() {
+        [Statement::KillQuery(kill)] => match &kill.host {
+            None => Ok(Some(kill.id)),
+            Some(host) => Err(DataFusionError::Plan(format!(
+                "KILL QUERY ON {} is not supported",
+                host
+            ))),
+        },
+        _ => Err(DataFusionError::Plan(
+            "KILL QUERY expects a single query ID".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exec::{Executor, ExecutorType};
+
+    #[tokio::test]
+    async fn test_query_with_params() {
+        let exec = Executor::new(1);
+        let ctx = exec.new_context(ExecutorType::Query);
+        let params = [
+            ScalarValue::Utf8(Some(r#"it's a \ "$1""#.to_string())),
+            ScalarValue::Int64(Some(-41)),
+        ];
+
+        let plan = SqlQueryPlanner::new()
+            .query_with_params(
+                "SELECT $1 AS s, '$1' AS lit, 1-$2 AS n /* $2 */ -- $1",
+                &params,
+                &ctx,
+            )
+            .await
+            .unwrap();
+        let batches = ctx.collect(plan).await.unwrap();
+
+        let expected = vec![
+            "+----------------+-----+----+",
+            "| s              | lit | n  |",
+            "+----------------+-----+----+",
+            r#"| it's a \ "$1" | $1  | 42 |"#,
+            "+----------------+-----+----+",
+        ];
+        arrow_util::assert_batches_eq!(expected, &batches);
+
+        let err = SqlQueryPlanner::new()
+            .query_with_params("SELECT $1, $3", &params, &ctx)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Placeholder $3 has no parameter (2 given)"
+        );
+
+        exec.join().await;
+    }
+
+    #[test]
+    fn test_parse_kill_query() {
+        assert_eq!(parse_kill_query("KILL QUERY 42").unwrap(), Some(42));
+        assert_eq!(parse_kill_query("  kill  query 7 ;").unwrap(), Some(7));
+        assert_eq!(parse_kill_query("SELECT * FROM kill").unwrap(), None);
+        assert_eq!(parse_kill_query("KILL").unwrap(), None);
+
+        assert!(parse_kill_query("KILL QUERY").is_err());
+        assert!(parse_kill_query("KILL QUERY abc").is_err());
+        assert!(parse_kill_query("KILL QUERY 1 2").is_err());
+        assert!(parse_kill_query("KILL QUERY 1; KILL QUERY 2").is_err());
+        assert!(parse_kill_query("KILL QUERY 1 ON \"host\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_explain_analyze() {
+        assert_eq!(
+            parse_explain_analyze("EXPLAIN ANALYZE SELECT * FROM cpu"),
+            Some("SELECT * FROM cpu")
+        );
+        assert_eq!(
+            parse_explain_analyze(" explain\n analyze  select 1;"),
+            Some("select 1;")
+        );
+        assert_eq!(parse_explain_analyze("EXPLAIN SELECT * FROM cpu"), None);
+        assert_eq!(
+            parse_explain_analyze("EXPLAIN ANALYZE VERBOSE SELECT * FROM cpu"),
+            None
+        );
+        assert_eq!(parse_explain_analyze("SELECT * FROM analyze"), None);
+        assert_eq!(parse_explain_analyze("EXPLAIN ANALYZE"), None);
     }
 }
@@ -0,0 +1,157 @@
+//! Binding of positional query parameters (`$1`, `$2`, ...) to literals.
+
+use datafusion::{
+    error::{DataFusionError, Result},
+    scalar::ScalarValue,
+    sql::{
+        parser::Statement,
+        sqlparser::{
+            dialect::GenericDialect,
+            parser::{Parser, ParserError},
+            tokenizer::{Token, Tokenizer},
+        },
+    },
+};
+
+/// Parses the single SQL statement `sql`, binding its positional placeholders
+/// `$1`, `$2`, ... to the literal of the corresponding entry of `params`.
+///
+/// The placeholders are replaced in the token stream of the statement, so
+/// that `$1` within string literals, quoted identifiers and comments is left
+/// untouched and the values never need to be quoted. Parameters that are not
+/// referenced are ignored.
+pub(crate) fn bind_params(sql: &str, params: &[ScalarValue]) -> Result<Statement> {
+    let dialect = GenericDialect {};
+    let tokens = Tokenizer::new(&dialect, sql)
+        .tokenize()
+        .map_err(|e| ParserError::TokenizerError(e.to_string()))?;
+
+    let mut bound = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        match token {
+            Token::Placeholder(placeholder) => match placeholder.strip_prefix('$') {
+                Some(index) if !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()) => {
+                    // an overly long index cannot refer to a parameter either
+                    let index = index.parse::<usize>().unwrap_or(usize::MAX);
+                    let param = index
+                        .checked_sub(1)
+                        .and_then(|i| params.get(i))
+                        .ok_or_else(|| {
+                            DataFusionError::Plan(format!(
+                                "Placeholder ${} has no parameter ({} given)",
+                                index,
+                                params.len()
+                            ))
+                        })?;
+                    bound.extend(literal_tokens(index, param)?);
+                }
+                _ => bound.push(Token::Placeholder(placeholder)),
+            },
+            token => bound.push(token),
+        }
+    }
+
+    let mut statements = Parser::new(bound, &dialect).parse_statements()?;
+    if statements.len() != 1 {
+        return Err(DataFusionError::NotImplemented(
+            "The context currently only supports a single SQL statement".to_string(),
+        ));
+    }
+    Ok(Statement::Statement(Box::new(statements.remove(0))))
+}
+
+/// Returns the tokens of the SQL literal of `param`, which DataFusion plans
+/// as a literal of the same type and value.
+fn literal_tokens(index: usize, param: &ScalarValue) -> Result<Vec<Token>> {
+    let token = match param {
+        v if v.is_null() => Token::make_keyword("NULL"),
+        ScalarValue::Boolean(Some(true)) => Token::make_keyword("TRUE"),
+        ScalarValue::Boolean(Some(false)) => Token::make_keyword("FALSE"),
+        ScalarValue::Int64(Some(v)) => Token::Number(v.to_string(), false),
+        ScalarValue::UInt64(Some(v)) => Token::Number(v.to_string(), false),
+        ScalarValue::Float64(Some(v)) if !v.is_finite() => {
+            return Err(DataFusionError::Plan(format!(
+                "Parameter ${} is not a finite number: {}",
+                index, v
+            )))
+        }
+        // `Debug` always includes a fraction or exponent, so the number is
+        // planned as f64
+        ScalarValue::Float64(Some(v)) => Token::Number(format!("{:?}", v), false),
+        ScalarValue::Utf8(Some(v)) => Token::SingleQuotedString(v.clone()),
+        other => {
+            return Err(DataFusionError::Plan(format!(
+                "Parameter ${} has unsupported type {}",
+                index,
+                other.get_datatype()
+            )))
+        }
+    };
+
+    Ok(vec![token])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bind(sql: &str, params: &[ScalarValue]) -> String {
+        bind_params(sql, params).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_bind_params() {
+        let params = [
+            ScalarValue::Utf8(Some("it's".to_string())),
+            ScalarValue::Int64(Some(-3)),
+            ScalarValue::Float64(Some(1.0)),
+            ScalarValue::Boolean(Some(true)),
+            ScalarValue::Utf8(None),
+            ScalarValue::UInt64(Some(7)),
+        ];
+
+        assert_eq!(
+            bind(
+                "SELECT * FROM t WHERE a = $1 AND b-$2 > $3 AND c = $4 OR d IS $5 LIMIT $6",
+                &params
+            ),
+            "SELECT * FROM t WHERE a = 'it''s' AND b - -3 > 1.0 AND c = true OR d IS NULL LIMIT 7"
+        );
+    }
+
+    #[test]
+    fn test_bind_params_quoting() {
+        let params = [ScalarValue::Utf8(Some(r#"a'b\c"d$1--e"#.to_string()))];
+
+        // placeholders in strings, quoted identifiers and comments are kept
+        let sql = "SELECT '$1', 'a''$1', \"$1\", $1 -- $1\n FROM t /* $1 */ WHERE x = '\\$1'";
+        assert_eq!(
+            bind(sql, &params),
+            r#"SELECT '$1', 'a''$1', "$1", 'a''b\c"d$1--e' FROM t WHERE x = '\$1'"#
+        );
+
+        // a negative number cannot form a comment with a preceding minus sign
+        assert_eq!(
+            bind("SELECT 1-$1", &[ScalarValue::Int64(Some(-1))]),
+            "SELECT 1 - -1"
+        );
+    }
+
+    #[test]
+    fn test_bind_params_errors() {
+        let params = [ScalarValue::Float64(Some(f64::NAN))];
+        assert_eq!(
+            bind_params("SELECT $1", &params).unwrap_err().to_string(),
+            "Error during planning: Parameter $1 is not a finite number: NaN"
+        );
+        assert_eq!(
+            bind_params("SELECT $2", &params).unwrap_err().to_string(),
+            "Error during planning: Placeholder $2 has no parameter (1 given)"
+        );
+        assert_eq!(
+            bind_params("SELECT $0", &params).unwrap_err().to_string(),
+            "Error during planning: Placeholder $0 has no parameter (1 given)"
+        );
+        assert!(bind_params("SELECT $1; SELECT $1", &[ScalarValue::Int64(Some(1))]).is_err());
+    }
+}
@@ -8,11 +8,12 @@
     clippy::future_not_send
 )]
 
+use arrow::error::ArrowError;
 use async_trait::async_trait;
 use data_types::{
     ChunkId, ChunkOrder, DeletePredicate, InfluxDbType, PartitionId, TableSummary, TimestampMinMax,
 };
-use datafusion::physical_plan::SendableRecordBatchStream;
+use datafusion::{error::DataFusionError, physical_plan::SendableRecordBatchStream};
 use exec::{stringset::StringSet, IOxSessionContext};
 use hashbrown::HashMap;
 use observability_deps::tracing::{debug, trace};
@@ -22,10 +23,16 @@ use schema::{
     sort::{SortKey, SortKeyBuilder},
     Schema, TIME_COLUMN_NAME,
 };
-use std::{any::Any, collections::BTreeSet, fmt::Debug, iter::FromIterator, sync::Arc};
+use snafu::Snafu;
+use std::{
+    any::Any, collections::BTreeSet, convert::Infallible, fmt::Debug, future::Future,
+    iter::FromIterator, sync::Arc, time::Duration,
+};
+use tracker::{TaskRegistration, TrackedFutureExt};
 
 pub mod exec;
 pub mod frontend;
+pub mod physical_optimizer;
 pub mod plan;
 pub mod provider;
 pub mod pruning;
@@ -74,8 +81,10 @@ pub trait QueryChunkMeta {
         let mut col_names = BTreeSet::new();
         for pred in self.delete_predicates() {
             for expr in &pred.exprs {
-                if expr.column != schema::TIME_COLUMN_NAME {
-                    col_names.insert(expr.column.as_str());
+                for column in expr.columns() {
+                    if column != schema::TIME_COLUMN_NAME {
+                        col_names.insert(column);
+                    }
                 }
             }
         }
@@ -102,12 +111,33 @@ pub struct QueryCompletedToken {
     /// Function invoked when the token is dropped. It is passed the
     /// vaue of `self.success`
     f: Option<Box<dyn FnOnce(bool) + Send>>,
+
+    /// Registration used to cancel the query, if it can be cancelled
+    registration: Option<TaskRegistration>,
+
+    /// ID of the query as reported by `system.queries`, if it is logged
+    query_id: Option<u64>,
+
+    /// Deadline of the query and the timeout it was derived from, if any
+    deadline: Option<(tokio::time::Instant, Duration)>,
+
+    /// Admission of the query against the limits of its database, released
+    /// once the token is dropped
+    admission: QueryAdmission,
+
+    /// Invoked whenever the query is aborted because it exceeded a limit
+    limit_observer: Option<LimitObserver>,
 }
 
 impl Debug for QueryCompletedToken {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("QueryCompletedToken")
             .field("success", &self.success)
+            .field("cancellable", &self.registration.is_some())
+            .field("query_id", &self.query_id)
+            .field("deadline", &self.deadline)
+            .field("admission", &self.admission)
+            .field("limit_observer", &self.limit_observer.is_some())
             .finish()
     }
 }
@@ -117,13 +147,99 @@ impl QueryCompletedToken {
         Self {
             success: false,
             f: Some(Box::new(f)),
+            registration: None,
+            query_id: None,
+            deadline: None,
+            admission: QueryAdmission::default(),
+            limit_observer: None,
+        }
+    }
+
+    /// Allow the query to be cancelled through the task tracker of
+    /// `registration`.
+    pub fn with_registration(self, registration: TaskRegistration) -> Self {
+        Self {
+            registration: Some(registration),
+            ..self
+        }
+    }
+
+    /// Set the ID under which the query is listed in `system.queries`, which
+    /// is used to cancel it.
+    pub fn with_query_id(self, query_id: u64) -> Self {
+        Self {
+            query_id: Some(query_id),
+            ..self
+        }
+    }
+
+    /// Abort the query once it ran for longer than `timeout`, measured from
+    /// now.
+    ///
+    /// If the query already has a timeout, the one expiring first applies.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_timeout_since(std::time::Instant::now(), timeout)
+    }
+
+    /// Abort the query once it ran for longer than `timeout`, measured from
+    /// `start`, e.g. when the request of the query was received.
+    ///
+    /// If the query already has a timeout, the one expiring first applies.
+    pub fn with_timeout_since(self, start: std::time::Instant, timeout: Duration) -> Self {
+        let deadline = tokio::time::Instant::from_std(start) + timeout;
+        let deadline = match self.deadline {
+            Some(existing) if existing.0 <= deadline => existing,
+            _ => (deadline, timeout),
+        };
+        Self {
+            deadline: Some(deadline),
+            ..self
         }
     }
 
+    /// Hold on to the `admission` of the query until the query is completed.
+    pub fn with_admission(self, admission: QueryAdmission) -> Self {
+        Self { admission, ..self }
+    }
+
+    /// Call `f` whenever the query is aborted because it exceeded one of its
+    /// [limits](QueryLimit).
+    pub fn with_limit_observer(self, f: impl Fn(QueryLimit) + Send + Sync + 'static) -> Self {
+        Self {
+            limit_observer: Some(Arc::new(f)),
+            ..self
+        }
+    }
+
+    /// ID of the query as reported by `system.queries`, if any.
+    pub fn query_id(&self) -> Option<u64> {
+        self.query_id
+    }
+
     /// Record that this query completed successfully
     pub fn set_success(&mut self) {
         self.success = true;
     }
+
+    /// Record that this query failed with `e`, reporting it to the
+    /// [limit observer](Self::with_limit_observer) if it ran out of memory.
+    pub fn record_error(&self, e: &(dyn std::error::Error + 'static)) {
+        if let Some(observer) = &self.limit_observer {
+            if is_memory_exhausted(e) {
+                observer(QueryLimit::Memory);
+            }
+        }
+    }
+
+    /// Returns the handle used to abort the work of this query once it is
+    /// cancelled or timed out.
+    pub fn cancellation(&self) -> QueryCancellation {
+        QueryCancellation {
+            registration: self.registration.clone(),
+            deadline: self.deadline,
+            limit_observer: self.limit_observer.clone(),
+        }
+    }
 }
 
 impl Drop for QueryCompletedToken {
@@ -134,6 +250,209 @@ impl Drop for QueryCompletedToken {
     }
 }
 
+/// Keeps a query counted against the limits of its database, see
+/// [`QueryCompletedToken::with_admission`].
+///
+/// The default admission is not counted against any limits.
+#[derive(Default)]
+pub struct QueryAdmission {
+    guard: Option<Box<dyn Send + Sync>>,
+}
+
+impl QueryAdmission {
+    /// Create an admission that holds on to `guard` until it is dropped.
+    pub fn new(guard: impl Send + Sync + 'static) -> Self {
+        Self {
+            guard: Some(Box::new(guard)),
+        }
+    }
+}
+
+impl Debug for QueryAdmission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryAdmission")
+            .field("limited", &self.guard.is_some())
+            .finish()
+    }
+}
+
+/// A limit that aborts a query once it is exceeded, see
+/// [`QueryCompletedToken::with_limit_observer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryLimit {
+    /// The query ran for longer than its timeout.
+    Duration,
+
+    /// The query used more memory than its DataFusion runtime allows.
+    Memory,
+}
+
+type LimitObserver = Arc<dyn Fn(QueryLimit) + Send + Sync>;
+
+/// Aborts the work of a query once it is cancelled, see
+/// [`QueryCompletedToken::cancellation`].
+#[derive(Clone, Default)]
+pub struct QueryCancellation {
+    registration: Option<TaskRegistration>,
+    deadline: Option<(tokio::time::Instant, Duration)>,
+    limit_observer: Option<LimitObserver>,
+}
+
+impl Debug for QueryCancellation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryCancellation")
+            .field("registration", &self.registration)
+            .field("deadline", &self.deadline)
+            .field("limit_observer", &self.limit_observer.is_some())
+            .finish()
+    }
+}
+
+impl QueryCancellation {
+    /// Runs `fut` to completion, unless the query is cancelled or times out
+    /// first.
+    ///
+    /// `fut` is dropped once the query is aborted, which aborts any
+    /// DataFusion execution it drives.
+    pub async fn run<F>(self, fut: F) -> Result<F::Output, QueryAborted>
+    where
+        F: Future + Send,
+    {
+        let registration = self.registration;
+        let cancellable = async move {
+            match registration {
+                Some(registration) => async move { Ok::<_, Infallible>(fut.await) }
+                    .track(registration)
+                    .await
+                    .map(|res| match res {
+                        Ok(output) => output,
+                        Err(e) => match e {},
+                    })
+                    .map_err(|_| QueryAborted::Cancelled),
+                None => Ok(fut.await),
+            }
+        };
+
+        match self.deadline {
+            Some((deadline, timeout)) => match tokio::time::timeout_at(deadline, cancellable).await
+            {
+                Ok(res) => res,
+                Err(_) => {
+                    if let Some(observer) = &self.limit_observer {
+                        observer(QueryLimit::Duration);
+                    }
+                    Err(QueryAborted::Timeout { timeout })
+                }
+            },
+            None => cancellable.await,
+        }
+    }
+}
+
+/// Error returned by [`QueryCancellation::run`] if the query was aborted.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Snafu)]
+pub enum QueryAborted {
+    #[snafu(display("query was cancelled"))]
+    Cancelled,
+
+    #[snafu(display("query exceeded its timeout of {:?}", timeout))]
+    Timeout { timeout: Duration },
+}
+
+/// A query exceeds one of the resource quotas of its database.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Snafu)]
+pub enum QueryQuotaExceeded {
+    #[snafu(display(
+        "namespace '{}' already runs its maximum of {} concurrent queries",
+        namespace,
+        limit
+    ))]
+    ConcurrentQueries { namespace: String, limit: usize },
+
+    #[snafu(display(
+        "query would scan at least {} bytes, more than the maximum of {} bytes of namespace '{}'",
+        bytes,
+        limit,
+        namespace
+    ))]
+    ScannedBytes {
+        namespace: String,
+        bytes: u64,
+        limit: u64,
+    },
+}
+
+/// Error returned while waiting for a write to become readable before a
+/// query is planned.
+#[allow(missing_docs)]
+#[derive(Debug, Snafu)]
+pub enum WriteWaitError {
+    #[snafu(display("invalid write token: {}", reason))]
+    InvalidToken { reason: String },
+
+    #[snafu(display("write did not become readable within {:?}", timeout))]
+    Timeout { timeout: Duration },
+
+    #[snafu(display("cannot determine whether the write is readable: {}", source))]
+    Unavailable { source: QueryDatabaseError },
+}
+
+/// Error returned when cancelling a query.
+#[allow(missing_docs)]
+#[derive(Debug, Snafu)]
+pub enum KillQueryError {
+    #[snafu(display("Query {} not found", query_id))]
+    NotFound { query_id: u64 },
+
+    #[snafu(display("Query {} is not running", query_id))]
+    NotRunning { query_id: u64 },
+}
+
+/// Returns true if `e`, or one of the errors it was caused by, means that a
+/// query exceeded a resource quota: either a [`QueryQuotaExceeded`] or the
+/// memory limit of the DataFusion runtime.
+pub fn is_quota_exceeded(e: &(dyn std::error::Error + 'static)) -> bool {
+    error_chain(e).any(|e| e.is::<QueryQuotaExceeded>() || is_resources_exhausted(e))
+}
+
+/// Returns true if `e`, or one of the errors it was caused by, means that a
+/// query exceeded the memory limit of the DataFusion runtime.
+pub fn is_memory_exhausted(e: &(dyn std::error::Error + 'static)) -> bool {
+    error_chain(e).any(is_resources_exhausted)
+}
+
+fn is_resources_exhausted(e: &(dyn std::error::Error + 'static)) -> bool {
+    matches!(
+        e.downcast_ref::<DataFusionError>(),
+        Some(DataFusionError::ResourcesExhausted(_))
+    )
+}
+
+/// Iterates over `e` and the errors it was caused by.
+fn error_chain(
+    e: &(dyn std::error::Error + 'static),
+) -> impl Iterator<Item = &(dyn std::error::Error + 'static)> {
+    std::iter::successors(Some(e), |&e| {
+        // DataFusion and Arrow errors do not report wrapped errors as their
+        // source
+        let wrapped: Option<&(dyn std::error::Error + 'static)> =
+            if let Some(e) = e.downcast_ref::<DataFusionError>() {
+                match e {
+                    DataFusionError::External(inner) => Some(inner.as_ref()),
+                    DataFusionError::ArrowError(inner) => Some(inner),
+                    _ => None,
+                }
+            } else if let Some(ArrowError::ExternalError(inner)) = e.downcast_ref::<ArrowError>() {
+                Some(inner.as_ref())
+            } else {
+                None
+            };
+        wrapped.or_else(|| e.source())
+    })
+}
+
 /// Boxed description of a query that knows how to render to a string
 ///
 /// This avoids storing potentially large strings
@@ -176,6 +495,51 @@ pub trait QueryDatabase: QueryDatabaseMeta + Debug + Send + Sync {
 /// Error type for [`QueryChunk`] operations.
 pub type QueryChunkError = Box<dyn std::error::Error + Send + Sync + 'static>;
 
+/// Describes a set of grouped aggregates that a [`QueryChunk`] may be able to
+/// compute directly, without materialising the rows being aggregated.
+///
+/// See [`QueryChunk::read_aggregate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkAggregateRequest {
+    /// Tag columns to group by, in output order.
+    pub group_columns: Vec<String>,
+
+    /// Optionally group rows by the start of the time window their timestamp
+    /// falls within, equivalent to `date_bin(every, time, offset)`.
+    pub window: Option<ChunkAggregateWindow>,
+
+    /// Columns to aggregate, along with the aggregate to compute, in output
+    /// order. Only [`Aggregate::Count`], [`Aggregate::Sum`],
+    /// [`Aggregate::Min`], [`Aggregate::Max`], [`Aggregate::First`] and
+    /// [`Aggregate::Last`] are supported.
+    pub aggregates: Vec<(String, Aggregate)>,
+}
+
+impl ChunkAggregateRequest {
+    /// The number of result columns produced for `agg`.
+    ///
+    /// [`Aggregate::First`] and [`Aggregate::Last`] produce the selected
+    /// value followed by the timestamp it was selected at; all other
+    /// aggregates produce a single column.
+    pub fn aggregate_columns(agg: Aggregate) -> usize {
+        match agg {
+            Aggregate::First | Aggregate::Last => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// A fixed-width time window, in nanoseconds, used to group rows in a
+/// [`ChunkAggregateRequest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkAggregateWindow {
+    /// The width of each window.
+    pub every: i64,
+
+    /// The origin windows are aligned to, since the epoch.
+    pub offset: i64,
+}
+
 /// Collection of data that shares the same partition key
 pub trait QueryChunk: QueryChunkMeta + Debug + Send + Sync + 'static {
     /// returns the Id of this chunk. Ids are unique within a
@@ -248,6 +612,39 @@ pub trait QueryChunk: QueryChunkMeta + Debug + Send + Sync + 'static {
         selection: Selection<'_>,
     ) -> Result<SendableRecordBatchStream, QueryChunkError>;
 
+    /// Returns true if this chunk can compute grouped aggregates over the
+    /// rows matching `predicate` via [`Self::read_aggregate`].
+    ///
+    /// This is called during planning, and implementations must only return
+    /// true if `predicate` will be applied exactly, as aggregates cannot be
+    /// filtered after the fact.
+    fn supports_read_aggregate(&self, _predicate: &Predicate) -> bool {
+        false
+    }
+
+    /// Provides grouped aggregates, as described by `request`, over the rows
+    /// matching `predicate` as an asynchronous stream of `RecordBatch`es.
+    ///
+    /// Each output batch contains the group columns, followed by a `time`
+    /// column holding the window start if the request is windowed, followed
+    /// by the columns of each aggregate, as described by
+    /// [`ChunkAggregateRequest::aggregate_columns`]. Group and aggregate
+    /// columns in `request` must exist in this chunk.
+    ///
+    /// Only called if [`Self::supports_read_aggregate`] returns true.
+    fn read_aggregate(
+        &self,
+        _ctx: IOxSessionContext,
+        _predicate: &Predicate,
+        _request: &ChunkAggregateRequest,
+    ) -> Result<SendableRecordBatchStream, QueryChunkError> {
+        Err(format!(
+            "read_aggregate not supported by {} chunks",
+            self.chunk_type()
+        )
+        .into())
+    }
+
     /// Returns chunk type. Useful in tests and debug logs.
     fn chunk_type(&self) -> &str;
 
@@ -406,3 +803,83 @@ fn compute_sort_key(summaries: impl Iterator<Item = Arc<TableSummary>>) -> SortK
 //
 //#[cfg(test)]
 pub mod test;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_query_cancellation_timeout() {
+        let token = QueryCompletedToken::new(|_| {}).with_timeout(Duration::from_millis(1));
+        let err = token
+            .cancellation()
+            .run(futures::future::pending::<()>())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, QueryAborted::Timeout { .. }));
+
+        let token = QueryCompletedToken::new(|_| {}).with_timeout(Duration::from_secs(60));
+        assert_eq!(token.cancellation().run(async { 1 }).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_query_cancellation_earliest_timeout() {
+        let start = std::time::Instant::now();
+        for (first, second) in [(1, 60_000), (60_000, 1)] {
+            let token = QueryCompletedToken::new(|_| {})
+                .with_timeout_since(start, Duration::from_millis(first))
+                .with_timeout_since(start, Duration::from_millis(second));
+            let err = token
+                .cancellation()
+                .run(futures::future::pending::<()>())
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                QueryAborted::Timeout { timeout } if timeout == Duration::from_millis(1)
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_limit_observer() {
+        let exceeded = Arc::new(parking_lot::Mutex::new(vec![]));
+        let captured = Arc::clone(&exceeded);
+        let token = QueryCompletedToken::new(|_| {})
+            .with_timeout(Duration::from_millis(1))
+            .with_limit_observer(move |limit| captured.lock().push(limit));
+
+        token
+            .cancellation()
+            .run(futures::future::pending::<()>())
+            .await
+            .unwrap_err();
+        token.record_error(&DataFusionError::Plan("not a limit".to_string()));
+        token.record_error(&DataFusionError::External(Box::new(
+            DataFusionError::ResourcesExhausted("out of memory".to_string()),
+        )));
+
+        assert_eq!(
+            *exceeded.lock(),
+            vec![QueryLimit::Duration, QueryLimit::Memory]
+        );
+    }
+
+    #[test]
+    fn test_is_quota_exceeded() {
+        let err = QueryQuotaExceeded::ConcurrentQueries {
+            namespace: "ns".to_string(),
+            limit: 1,
+        };
+        assert!(is_quota_exceeded(&err));
+        assert!(!is_memory_exhausted(&err));
+        assert!(is_quota_exceeded(&DataFusionError::External(Box::new(err))));
+        assert!(!is_quota_exceeded(&QueryAborted::Cancelled));
+
+        let err = ArrowError::ExternalError(Box::new(DataFusionError::ResourcesExhausted(
+            "out of memory".to_string(),
+        )));
+        assert!(is_quota_exceeded(&err));
+        assert!(is_memory_exhausted(&err));
+    }
+}
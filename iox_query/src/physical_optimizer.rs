@@ -0,0 +1,505 @@
+//! IOx specific rules for optimizing DataFusion physical plans
+
+use std::sync::Arc;
+
+use arrow::datatypes::DataType;
+use datafusion::{
+    error::Result,
+    execution::context::SessionConfig,
+    logical_plan::{Column as LogicalColumn, Expr, Operator},
+    physical_expr::ScalarFunctionExpr,
+    physical_optimizer::optimizer::PhysicalOptimizerRule,
+    physical_plan::{
+        aggregates::{AggregateExec, AggregateMode},
+        coalesce_batches::CoalesceBatchesExec,
+        expressions::{BinaryExpr, CastExpr, Column, Count, Literal, Max, Min, Sum},
+        filter::FilterExec,
+        repartition::RepartitionExec,
+        udaf::AggregateFunctionExpr,
+        AggregateExpr, ExecutionPlan, Partitioning, PhysicalExpr,
+    },
+    scalar::ScalarValue,
+};
+use observability_deps::tracing::debug;
+use predicate::Predicate;
+use query_functions::group_by::Aggregate;
+use schema::{InfluxColumnType, Schema, TIME_COLUMN_NAME};
+
+use crate::{
+    provider::{ChunkAggregateExec, IOxReadFilterNode},
+    ChunkAggregateRequest, ChunkAggregateWindow,
+};
+
+/// Nanoseconds in a day, used to convert `date_bin` strides
+const NANOS_PER_DAY: i64 = 86_400_000_000_000;
+
+/// Nanoseconds in a millisecond, used to convert `date_bin` strides
+const NANOS_PER_MILLI: i64 = 1_000_000;
+
+/// Pushes partial aggregates down into the chunks being scanned, when every
+/// chunk is able to compute them directly (see
+/// [`QueryChunk::read_aggregate`](crate::QueryChunk::read_aggregate)).
+///
+/// Matches plans of the form:
+///
+/// ```text
+/// AggregateExec: mode=Partial, gby=[tag, date_bin(interval, time, origin)], aggr=[count(field), ...]
+///   (optional) FilterExec: <conjunction of column/literal comparisons>
+///     IOxReadFilterNode
+/// ```
+///
+/// and replaces the partial aggregate (and everything below it) with a
+/// `ChunkAggregateExec` producing the same output. Only `count`, `sum`,
+/// `min` and `max` aggregates, and the `first` and `last` selectors, grouped
+/// by tags and/or a single `date_bin` window are supported, otherwise the
+/// plan is left unchanged.
+#[derive(Debug, Default)]
+pub struct ChunkAggregatePushdown {}
+
+impl PhysicalOptimizerRule for ChunkAggregatePushdown {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &SessionConfig,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if let Some(aggregate) = plan.as_any().downcast_ref::<AggregateExec>() {
+            if let Some(pushed_down) = try_push_down(aggregate) {
+                debug!("pushed partial aggregate down into chunks");
+                return Ok(pushed_down);
+            }
+        }
+
+        let children = plan
+            .children()
+            .into_iter()
+            .map(|child| self.optimize(child, config))
+            .collect::<Result<Vec<_>>>()?;
+
+        if children.is_empty() {
+            return Ok(plan);
+        }
+        plan.with_new_children(children)
+    }
+
+    fn name(&self) -> &str {
+        "chunk_aggregate_pushdown"
+    }
+}
+
+/// The source of each output column of a [`ChunkAggregateRequest`] result,
+/// resolved to a column position once all group columns are known.
+enum Slot {
+    Group(usize),
+    Window,
+    /// The column at the given offset within the aggregate columns.
+    Aggregate(usize),
+}
+
+/// Returns a `ChunkAggregateExec` replacing `aggregate` if possible.
+fn try_push_down(aggregate: &AggregateExec) -> Option<Arc<dyn ExecutionPlan>> {
+    if !matches!(aggregate.mode(), AggregateMode::Partial) {
+        return None;
+    }
+
+    // Grouping sets produce multiple group keys per row.
+    if !aggregate.group_expr().null_expr().is_empty() {
+        return None;
+    }
+
+    let (scan, filter) = find_scan(aggregate.input())?;
+    let iox_schema = scan.iox_schema();
+
+    // The filter, if any, must be exactly evaluated by the chunks. Without a
+    // filter, any predicate on the scan may be inexact.
+    let predicate = match filter {
+        Some(filter) => filter_to_predicate(filter.predicate())?,
+        None if scan.predicate().is_empty() => Predicate::default(),
+        None => return None,
+    };
+
+    let chunks = scan.chunks();
+    if chunks.is_empty()
+        || !chunks.iter().all(|chunk| {
+            !chunk.has_delete_predicates()
+                && !chunk.may_contain_pk_duplicates()
+                && chunk.supports_read_aggregate(&predicate)
+        })
+    {
+        return None;
+    }
+
+    let mut request = ChunkAggregateRequest {
+        group_columns: vec![],
+        window: None,
+        aggregates: vec![],
+    };
+    let mut slots = vec![];
+
+    for (expr, _) in aggregate.group_expr().expr() {
+        if let Some(column) = expr.as_any().downcast_ref::<Column>() {
+            if column_type(iox_schema, column.name()) != Some(InfluxColumnType::Tag) {
+                return None;
+            }
+            request.group_columns.push(column.name().to_string());
+            slots.push(Slot::Group(request.group_columns.len() - 1));
+        } else if let Some(func) = expr.as_any().downcast_ref::<ScalarFunctionExpr>() {
+            if request.window.is_some() {
+                return None;
+            }
+            request.window = Some(date_bin_window(func)?);
+            slots.push(Slot::Window);
+        } else {
+            return None;
+        }
+    }
+
+    for aggr_expr in aggregate.aggr_expr() {
+        let agg = aggregate_type(aggr_expr.as_ref())?;
+
+        // The chunk must compute the entire partial state, with one column
+        // per state field.
+        let width = ChunkAggregateRequest::aggregate_columns(agg);
+        if aggr_expr.state_fields().ok()?.len() != width {
+            return None;
+        }
+
+        let column = aggregate_input_column(aggr_expr.as_ref(), agg, iox_schema)?;
+        let idx = match request
+            .aggregates
+            .iter()
+            .position(|(c, a)| c == &column && *a == agg)
+        {
+            Some(idx) => idx,
+            None => {
+                request.aggregates.push((column, agg));
+                request.aggregates.len() - 1
+            }
+        };
+
+        let offset = request.aggregates[..idx]
+            .iter()
+            .map(|(_, agg)| ChunkAggregateRequest::aggregate_columns(*agg))
+            .sum::<usize>();
+        slots.extend((offset..offset + width).map(Slot::Aggregate));
+    }
+
+    let schema = aggregate.schema();
+    if slots.len() != schema.fields().len() {
+        return None;
+    }
+
+    let window_columns = usize::from(request.window.is_some());
+    let output_indices = slots
+        .into_iter()
+        .map(|slot| match slot {
+            Slot::Group(idx) => idx,
+            Slot::Window => request.group_columns.len(),
+            Slot::Aggregate(idx) => request.group_columns.len() + window_columns + idx,
+        })
+        .collect();
+
+    Some(Arc::new(ChunkAggregateExec::new(
+        scan.ctx().child_ctx("ChunkAggregateExec"),
+        Arc::clone(scan.table_name()),
+        chunks.to_vec(),
+        predicate,
+        request,
+        output_indices,
+        schema,
+    )))
+}
+
+/// Finds the scan below `plan`, looking through nodes that do not change the
+/// set of rows being aggregated, along with at most one filter.
+fn find_scan(
+    mut plan: &Arc<dyn ExecutionPlan>,
+) -> Option<(&IOxReadFilterNode, Option<&FilterExec>)> {
+    let mut filter = None;
+    loop {
+        let any = plan.as_any();
+        if let Some(scan) = any.downcast_ref::<IOxReadFilterNode>() {
+            return Some((scan, filter));
+        } else if let Some(coalesce) = any.downcast_ref::<CoalesceBatchesExec>() {
+            plan = coalesce.input();
+        } else if let Some(repartition) = any.downcast_ref::<RepartitionExec>() {
+            if !matches!(repartition.partitioning(), Partitioning::RoundRobinBatch(_)) {
+                return None;
+            }
+            plan = repartition.input();
+        } else if let Some(filter_exec) = any.downcast_ref::<FilterExec>() {
+            if filter.is_some() {
+                return None;
+            }
+            filter = Some(filter_exec);
+            plan = filter_exec.input();
+        } else {
+            return None;
+        }
+    }
+}
+
+/// Converts a physical filter expression that is a conjunction of
+/// comparisons between columns and literals into a [`Predicate`].
+fn filter_to_predicate(expr: &Arc<dyn PhysicalExpr>) -> Option<Predicate> {
+    let mut exprs = vec![];
+    split_conjunction(expr, &mut exprs)?;
+
+    Some(
+        exprs
+            .into_iter()
+            .fold(Predicate::default(), |predicate, expr| {
+                predicate.with_expr(expr)
+            }),
+    )
+}
+
+fn split_conjunction(expr: &Arc<dyn PhysicalExpr>, dst: &mut Vec<Expr>) -> Option<()> {
+    let binary = expr.as_any().downcast_ref::<BinaryExpr>()?;
+    let op = binary.op();
+
+    if matches!(op, Operator::And) {
+        split_conjunction(binary.left(), dst)?;
+        return split_conjunction(binary.right(), dst);
+    }
+
+    let (column, op, value) = match (
+        binary.left().as_any().downcast_ref::<Column>(),
+        literal_value(binary.right()),
+    ) {
+        (Some(column), Some(value)) => (column, *op, value),
+        _ => {
+            // Try (literal, op, column), which has the converse operator.
+            let column = binary.right().as_any().downcast_ref::<Column>()?;
+            let value = literal_value(binary.left())?;
+            let op = match op {
+                Operator::Eq => Operator::Eq,
+                Operator::NotEq => Operator::NotEq,
+                Operator::Lt => Operator::Gt,
+                Operator::LtEq => Operator::GtEq,
+                Operator::Gt => Operator::Lt,
+                Operator::GtEq => Operator::LtEq,
+                _ => return None,
+            };
+            (column, op, value)
+        }
+    };
+
+    if !matches!(
+        op,
+        Operator::Eq
+            | Operator::NotEq
+            | Operator::Lt
+            | Operator::LtEq
+            | Operator::Gt
+            | Operator::GtEq
+    ) {
+        return None;
+    }
+
+    dst.push(Expr::BinaryExpr {
+        left: Box::new(Expr::Column(LogicalColumn::from_name(column.name()))),
+        op,
+        right: Box::new(Expr::Literal(value)),
+    });
+    Some(())
+}
+
+/// Returns the value of a literal, looking through casts of string literals
+/// to dictionaries as used when comparing tags.
+fn literal_value(expr: &Arc<dyn PhysicalExpr>) -> Option<ScalarValue> {
+    if let Some(literal) = expr.as_any().downcast_ref::<Literal>() {
+        return Some(literal.value().clone());
+    }
+
+    let cast = expr.as_any().downcast_ref::<CastExpr>()?;
+    match (
+        cast.expr().as_any().downcast_ref::<Literal>()?.value(),
+        cast.cast_type(),
+    ) {
+        (value @ ScalarValue::Utf8(Some(_)), DataType::Dictionary(_, value_type))
+            if value_type.as_ref() == &DataType::Utf8 =>
+        {
+            Some(value.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Extracts the window described by `date_bin(stride, time[, origin])`.
+fn date_bin_window(func: &ScalarFunctionExpr) -> Option<ChunkAggregateWindow> {
+    if func.name() != "date_bin" {
+        return None;
+    }
+
+    let args = func.args();
+    if args.len() != 2 && args.len() != 3 {
+        return None;
+    }
+
+    let every = match args[0].as_any().downcast_ref::<Literal>()?.value() {
+        ScalarValue::IntervalDayTime(Some(v)) => {
+            let days = (*v >> 32) as i32;
+            let millis = *v as i32;
+            (days as i64)
+                .checked_mul(NANOS_PER_DAY)?
+                .checked_add((millis as i64).checked_mul(NANOS_PER_MILLI)?)?
+        }
+        ScalarValue::IntervalMonthDayNano(Some(v)) => {
+            let months = (*v >> 96) as i32;
+            let days = (*v >> 64) as i32;
+            let nanos = *v as i64;
+            if months != 0 {
+                return None;
+            }
+            (days as i64)
+                .checked_mul(NANOS_PER_DAY)?
+                .checked_add(nanos)?
+        }
+        _ => return None,
+    };
+    if every <= 0 {
+        return None;
+    }
+
+    let column = args[1].as_any().downcast_ref::<Column>()?;
+    if column.name() != TIME_COLUMN_NAME {
+        return None;
+    }
+
+    let offset = match args.get(2) {
+        None => 0,
+        Some(origin) => match origin.as_any().downcast_ref::<Literal>()?.value() {
+            ScalarValue::TimestampNanosecond(Some(v), _) => *v,
+            _ => return None,
+        },
+    };
+
+    Some(ChunkAggregateWindow { every, offset })
+}
+
+/// Returns the aggregate computed by `aggr_expr`, if supported.
+///
+/// The `first` and `last` selectors share the same partial state (the
+/// selected value and its timestamp) regardless of the part of the selection
+/// they finally output.
+fn aggregate_type(aggr_expr: &dyn AggregateExpr) -> Option<Aggregate> {
+    let any = aggr_expr.as_any();
+    if any.downcast_ref::<Count>().is_some() {
+        Some(Aggregate::Count)
+    } else if any.downcast_ref::<Sum>().is_some() {
+        Some(Aggregate::Sum)
+    } else if any.downcast_ref::<Min>().is_some() {
+        Some(Aggregate::Min)
+    } else if any.downcast_ref::<Max>().is_some() {
+        Some(Aggregate::Max)
+    } else if let Some(udaf) = any.downcast_ref::<AggregateFunctionExpr>() {
+        match udaf.fun().name.as_str() {
+            "selector_first" | "selector_first_value" | "selector_first_time" => {
+                Some(Aggregate::First)
+            }
+            "selector_last" | "selector_last_value" | "selector_last_time" => Some(Aggregate::Last),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+/// Returns the name of the column aggregated by `aggr_expr`.
+///
+/// Sums, minimums and maximums are only supported on fields. Counts are
+/// supported on any column, and `count(*)` counts timestamps, which are
+/// never NULL. Selectors are only supported on fields, selecting by the
+/// `time` column.
+fn aggregate_input_column(
+    aggr_expr: &dyn AggregateExpr,
+    agg: Aggregate,
+    iox_schema: &Schema,
+) -> Option<String> {
+    let exprs = aggr_expr.expressions();
+    if matches!(agg, Aggregate::First | Aggregate::Last) {
+        let time = exprs.get(1)?.as_any().downcast_ref::<Column>()?;
+        if exprs.len() != 2 || time.name() != TIME_COLUMN_NAME {
+            return None;
+        }
+
+        let column = exprs[0].as_any().downcast_ref::<Column>()?;
+        return match column_type(iox_schema, column.name())? {
+            InfluxColumnType::Field(_) => Some(column.name().to_string()),
+            _ => None,
+        };
+    }
+
+    if exprs.len() != 1 {
+        return None;
+    }
+
+    if let Some(column) = exprs[0].as_any().downcast_ref::<Column>() {
+        return match (column_type(iox_schema, column.name())?, agg) {
+            (InfluxColumnType::Field(_), _) | (_, Aggregate::Count) => {
+                Some(column.name().to_string())
+            }
+            _ => None,
+        };
+    }
+
+    match (exprs[0].as_any().downcast_ref::<Literal>()?.value(), agg) {
+        (value, Aggregate::Count) if !value.is_null() => Some(TIME_COLUMN_NAME.to_string()),
+        _ => None,
+    }
+}
+
+/// Returns the IOx column type of `name` in `iox_schema`.
+fn column_type(iox_schema: &Schema, name: &str) -> Option<InfluxColumnType> {
+    iox_schema
+        .find_index_of(name)
+        .and_then(|idx| iox_schema.field(idx).0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::physical_plan::expressions::{col, lit};
+
+    #[test]
+    fn test_filter_to_predicate() {
+        let schema = arrow::datatypes::Schema::new(vec![
+            arrow::datatypes::Field::new(
+                "tag",
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                true,
+            ),
+            arrow::datatypes::Field::new("field", DataType::Float64, true),
+        ]);
+
+        let tag_eq: Arc<dyn PhysicalExpr> = Arc::new(BinaryExpr::new(
+            col("tag", &schema).unwrap(),
+            Operator::Eq,
+            Arc::new(CastExpr::new(
+                lit(ScalarValue::Utf8(Some("a".to_string()))),
+                schema.field(0).data_type().clone(),
+                datafusion::physical_plan::expressions::DEFAULT_DATAFUSION_CAST_OPTIONS,
+            )),
+        ));
+        let field_gt: Arc<dyn PhysicalExpr> = Arc::new(BinaryExpr::new(
+            lit(ScalarValue::Float64(Some(1.0))),
+            Operator::Lt,
+            col("field", &schema).unwrap(),
+        ));
+        let conjunction: Arc<dyn PhysicalExpr> =
+            Arc::new(BinaryExpr::new(tag_eq, Operator::And, field_gt));
+
+        let predicate = filter_to_predicate(&conjunction).unwrap();
+        assert_eq!(
+            predicate.to_string(),
+            "Predicate exprs: [#tag = Utf8(\"a\"), #field > Float64(1)]"
+        );
+
+        let disjunction: Arc<dyn PhysicalExpr> = Arc::new(BinaryExpr::new(
+            Arc::clone(&conjunction),
+            Operator::Or,
+            conjunction,
+        ));
+        assert!(filter_to_predicate(&disjunction).is_none());
+    }
+}
@@ -36,10 +36,12 @@ use crate::{
 use snafu::{ResultExt, Snafu};
 
 mod adapter;
+mod aggregate;
 mod deduplicate;
 pub mod overlap;
 mod physical;
 use self::overlap::group_potential_duplicates;
+pub(crate) use aggregate::ChunkAggregateExec;
 pub(crate) use deduplicate::DeduplicateExec;
 pub use deduplicate::RecordBatchDeduplicator;
 pub(crate) use physical::IOxReadFilterNode;
@@ -1023,15 +1025,14 @@ impl Deduplicater {
             }
         }
 
-        // Cols of delete predicates
+        // Cols of delete predicates. Columns missing from the chunk are NULL for all of its rows and are
+        // removed from the delete predicates below.
         if chunk.has_delete_predicates() {
             for col in chunk.delete_predicate_columns() {
-                let idx = chunk_schema
-                    .find_index_of(col)
-                    .expect("delete predicate missing column");
-
-                let (t, field) = chunk_schema.field(idx);
-                schema_merger.merge_field(field, t).unwrap();
+                if let Some(idx) = chunk_schema.find_index_of(col) {
+                    let (t, field) = chunk_schema.field(idx);
+                    schema_merger.merge_field(field, t).unwrap();
+                }
             }
         }
 
@@ -1050,7 +1051,10 @@ impl Deduplicater {
         let del_preds = chunk.delete_predicates();
         let del_preds: Vec<Arc<Predicate>> = del_preds
             .iter()
-            .map(|pred| Arc::new(pred.as_ref().clone().into()))
+            .filter_map(|pred| {
+                pred.restrict_to_columns(|col| chunk_schema.find_index_of(col).is_some())
+            })
+            .map(|pred| Arc::new(pred.into()))
             .collect();
 
         trace!(?del_preds, "Chunk delete predicates");
@@ -0,0 +1,376 @@
+//! Implementation of a DataFusion PhysicalPlan node that computes partial
+//! aggregates directly within chunks
+
+use crate::{exec::IOxSessionContext, ChunkAggregateRequest, QueryChunk};
+use arrow::{
+    array::new_null_array, compute::cast, datatypes::SchemaRef, error::Result as ArrowResult,
+    record_batch::RecordBatch,
+};
+use datafusion::{
+    error::DataFusionError,
+    execution::context::TaskContext,
+    physical_plan::{
+        expressions::PhysicalSortExpr,
+        metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet},
+        stream::RecordBatchStreamAdapter,
+        DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream, Statistics,
+    },
+};
+use futures::StreamExt;
+use observability_deps::tracing::trace;
+use predicate::Predicate;
+use query_functions::group_by::Aggregate;
+use schema::{Schema, TIME_COLUMN_NAME};
+use std::{fmt, sync::Arc};
+
+/// Computes grouped aggregates for each of `chunks` using
+/// [`QueryChunk::read_aggregate`], producing one output partition per chunk.
+///
+/// The output of this node matches that of the partial `AggregateExec` it
+/// replaces, such that the final aggregation is unchanged.
+#[derive(Debug)]
+pub(crate) struct ChunkAggregateExec {
+    table_name: Arc<str>,
+    chunks: Vec<Arc<dyn QueryChunk>>,
+    predicate: Predicate,
+    request: ChunkAggregateRequest,
+
+    /// For each output column, the position of the column within a
+    /// (unrestricted) chunk result supplying its values.
+    output_indices: Vec<usize>,
+
+    /// The output schema
+    schema: SchemaRef,
+
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+
+    // execution context used for tracing
+    ctx: IOxSessionContext,
+}
+
+impl ChunkAggregateExec {
+    /// Create an execution plan node that computes the aggregates described
+    /// by `request` over the rows in `chunks` that match `predicate`.
+    ///
+    /// `output_indices` maps each field in `schema` to the position of the
+    /// column supplying it in the results described by `request`.
+    pub fn new(
+        ctx: IOxSessionContext,
+        table_name: Arc<str>,
+        chunks: Vec<Arc<dyn QueryChunk>>,
+        predicate: Predicate,
+        request: ChunkAggregateRequest,
+        output_indices: Vec<usize>,
+        schema: SchemaRef,
+    ) -> Self {
+        assert_eq!(output_indices.len(), schema.fields().len());
+
+        Self {
+            table_name,
+            chunks,
+            predicate,
+            request,
+            output_indices,
+            schema,
+            metrics: ExecutionPlanMetricsSet::new(),
+            ctx,
+        }
+    }
+}
+
+impl ExecutionPlan for ChunkAggregateExec {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(self.chunks.len())
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        // no inputs
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> datafusion::error::Result<Arc<dyn ExecutionPlan>> {
+        assert!(children.is_empty(), "no children expected in iox plan");
+
+        Ok(Arc::new(Self {
+            ctx: self.ctx.child_ctx("with_new_children"),
+            table_name: Arc::clone(&self.table_name),
+            chunks: self.chunks.clone(),
+            predicate: self.predicate.clone(),
+            request: self.request.clone(),
+            output_indices: self.output_indices.clone(),
+            schema: Arc::clone(&self.schema),
+            metrics: ExecutionPlanMetricsSet::new(),
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> datafusion::error::Result<SendableRecordBatchStream> {
+        trace!(partition, "Start ChunkAggregateExec::execute");
+
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let timer = baseline_metrics.elapsed_compute().timer();
+
+        let chunk = Arc::clone(&self.chunks[partition]);
+
+        // This chunk may not have all the requested columns. The request is
+        // restricted to the columns that are available and the rest are
+        // padded with NULLs.
+        let (request, chunk_indices) = restrict_request(&self.request, &chunk.schema());
+
+        let stream = chunk
+            .read_aggregate(
+                self.ctx.child_ctx("chunk read_aggregate"),
+                &self.predicate,
+                &request,
+            )
+            .map_err(|e| {
+                DataFusionError::Execution(format!(
+                    "Error creating aggregate for table {} chunk {}: {}",
+                    self.table_name,
+                    chunk.id(),
+                    e
+                ))
+            })?;
+
+        timer.done();
+
+        let schema = Arc::clone(&self.schema);
+        let output_indices = self.output_indices.clone();
+        let output_rows = baseline_metrics.output_rows().clone();
+        let stream = stream.map(move |batch| {
+            let batch = project_batch(batch?, &schema, &output_indices, &chunk_indices)?;
+            output_rows.add(batch.num_rows());
+            Ok(batch)
+        });
+
+        trace!(partition, "End ChunkAggregateExec::execute");
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            Arc::clone(&self.schema),
+            stream,
+        )))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                let aggregates = self
+                    .request
+                    .aggregates
+                    .iter()
+                    .map(|(column, agg)| format!("{:?}({})", agg, column))
+                    .collect::<Vec<_>>();
+
+                write!(
+                    f,
+                    "ChunkAggregateExec: table_name={}, chunks={} group_columns=[{}]",
+                    self.table_name,
+                    self.chunks.len(),
+                    self.request.group_columns.join(", "),
+                )?;
+                if let Some(window) = &self.request.window {
+                    write!(
+                        f,
+                        " window=[every={}, offset={}]",
+                        window.every, window.offset
+                    )?;
+                }
+                write!(
+                    f,
+                    " aggregates=[{}] predicate={}",
+                    aggregates.join(", "),
+                    self.predicate
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Restricts `request` to the columns present in `chunk_schema`.
+///
+/// Returns the restricted request, along with the position of each column of
+/// the unrestricted result within the restricted result, if present.
+fn restrict_request(
+    request: &ChunkAggregateRequest,
+    chunk_schema: &Schema,
+) -> (ChunkAggregateRequest, Vec<Option<usize>>) {
+    let mut restricted = ChunkAggregateRequest {
+        group_columns: vec![],
+        window: request.window,
+        aggregates: vec![],
+    };
+    let mut group_indices = vec![];
+    let mut aggregate_indices = vec![];
+    let mut aggregate_column = 0;
+
+    for column in &request.group_columns {
+        group_indices.push(chunk_schema.find_index_of(column).map(|_| {
+            restricted.group_columns.push(column.clone());
+            restricted.group_columns.len() - 1
+        }));
+    }
+
+    for (column, agg) in &request.aggregates {
+        let width = ChunkAggregateRequest::aggregate_columns(*agg);
+        match chunk_schema.find_index_of(column) {
+            Some(_) => {
+                restricted.aggregates.push((column.clone(), *agg));
+                aggregate_indices.extend((0..width).map(|i| Some(aggregate_column + i)));
+                aggregate_column += width;
+            }
+            None => aggregate_indices.extend((0..width).map(|_| None)),
+        }
+    }
+
+    // Chunks need at least one aggregate to produce a row per group key, so
+    // count the (never NULL) timestamps if no requested aggregate is present.
+    if restricted.aggregates.is_empty() {
+        restricted
+            .aggregates
+            .push((TIME_COLUMN_NAME.to_string(), Aggregate::Count));
+    }
+
+    let window_columns = usize::from(restricted.window.is_some());
+    let aggregates_start = restricted.group_columns.len() + window_columns;
+
+    let mut indices = group_indices;
+    if restricted.window.is_some() {
+        indices.push(Some(restricted.group_columns.len()));
+    }
+    indices.extend(
+        aggregate_indices
+            .into_iter()
+            .map(|idx| idx.map(|idx| idx + aggregates_start)),
+    );
+
+    (restricted, indices)
+}
+
+/// Projects a batch produced by a chunk to `schema`, casting columns to the
+/// output types and adding NULL columns for any not present in the chunk.
+fn project_batch(
+    batch: RecordBatch,
+    schema: &SchemaRef,
+    output_indices: &[usize],
+    chunk_indices: &[Option<usize>],
+) -> ArrowResult<RecordBatch> {
+    let columns = schema
+        .fields()
+        .iter()
+        .zip(output_indices)
+        .map(|(field, idx)| match chunk_indices[*idx] {
+            Some(chunk_idx) => {
+                let column = batch.column(chunk_idx);
+                if column.data_type() == field.data_type() {
+                    Ok(Arc::clone(column))
+                } else {
+                    cast(column, field.data_type())
+                }
+            }
+            None => Ok(new_null_array(field.data_type(), batch.num_rows())),
+        })
+        .collect::<ArrowResult<Vec<_>>>()?;
+
+    RecordBatch::try_new(Arc::clone(schema), columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChunkAggregateWindow;
+    use schema::builder::SchemaBuilder;
+
+    #[test]
+    fn test_restrict_request() {
+        let schema = SchemaBuilder::new()
+            .tag("region")
+            .influx_field("temp", schema::InfluxFieldType::Float)
+            .timestamp()
+            .build()
+            .unwrap();
+
+        let request = ChunkAggregateRequest {
+            group_columns: vec!["host".to_string(), "region".to_string()],
+            window: Some(ChunkAggregateWindow {
+                every: 10,
+                offset: 0,
+            }),
+            aggregates: vec![
+                ("temp".to_string(), Aggregate::Sum),
+                ("load".to_string(), Aggregate::Max),
+                ("temp".to_string(), Aggregate::Last),
+                ("load".to_string(), Aggregate::First),
+                ("temp".to_string(), Aggregate::Count),
+            ],
+        };
+
+        let (restricted, indices) = restrict_request(&request, &schema);
+        assert_eq!(
+            restricted,
+            ChunkAggregateRequest {
+                group_columns: vec!["region".to_string()],
+                window: request.window,
+                aggregates: vec![
+                    ("temp".to_string(), Aggregate::Sum),
+                    ("temp".to_string(), Aggregate::Last),
+                    ("temp".to_string(), Aggregate::Count),
+                ],
+            }
+        );
+        assert_eq!(
+            indices,
+            vec![
+                None,
+                Some(0),
+                Some(1),
+                Some(2),
+                None,
+                Some(3),
+                Some(4),
+                None,
+                None,
+                Some(5)
+            ]
+        );
+
+        // no aggregate columns present
+        let request = ChunkAggregateRequest {
+            group_columns: vec!["region".to_string()],
+            window: None,
+            aggregates: vec![("load".to_string(), Aggregate::Max)],
+        };
+
+        let (restricted, indices) = restrict_request(&request, &schema);
+        assert_eq!(
+            restricted.aggregates,
+            vec![(TIME_COLUMN_NAME.to_string(), Aggregate::Count)]
+        );
+        assert_eq!(indices, vec![Some(0), None]);
+    }
+}
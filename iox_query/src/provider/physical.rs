@@ -54,6 +54,31 @@ impl IOxReadFilterNode {
             metrics: ExecutionPlanMetricsSet::new(),
         }
     }
+
+    /// The name of the table being scanned.
+    pub fn table_name(&self) -> &Arc<str> {
+        &self.table_name
+    }
+
+    /// The chunks being scanned, one per output partition.
+    pub fn chunks(&self) -> &[Arc<dyn QueryChunk>] {
+        &self.chunks
+    }
+
+    /// The predicate pushed down to each chunk.
+    pub fn predicate(&self) -> &Predicate {
+        &self.predicate
+    }
+
+    /// The IOx schema of the output of this node.
+    pub fn iox_schema(&self) -> &Arc<Schema> {
+        &self.iox_schema
+    }
+
+    /// The execution context used for tracing.
+    pub fn ctx(&self) -> &IOxSessionContext {
+        &self.ctx
+    }
 }
 
 impl ExecutionPlan for IOxReadFilterNode {
@@ -0,0 +1,208 @@
+//! An [`Authorizer`] backed by the namespace tokens stored in the catalog.
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use data_types::NamespaceToken;
+use iox_catalog::interface::Catalog;
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+use snafu::ResultExt;
+
+use super::{Authorizer, CatalogSnafu, Error, Permission, Token};
+
+/// How long the grants of a token are cached before they are read from the
+/// catalog again.
+///
+/// This bounds the time it takes for a revoked grant to be rejected.
+const CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// The hash of a token as stored in the catalog: the hex encoded SHA-256 of
+/// the token bytes.
+pub fn hash_token(token: &[u8]) -> String {
+    hex::encode(Sha256::digest(token))
+}
+
+/// The grants of a token, keyed by namespace name.
+#[derive(Debug)]
+struct CachedGrants {
+    fetched_at: Instant,
+    namespaces: HashMap<String, NamespaceToken>,
+}
+
+/// An [`Authorizer`] accepting the [`NamespaceToken`]s stored in the catalog.
+///
+/// The grants of a token are cached for a short while, so that the catalog is
+/// not queried for every request.
+#[derive(Debug)]
+pub struct CatalogAuthorizer {
+    catalog: Arc<dyn Catalog>,
+    cache: Mutex<HashMap<String, Arc<CachedGrants>>>,
+}
+
+impl CatalogAuthorizer {
+    /// Authorize tokens against the grants stored in `catalog`.
+    pub fn new(catalog: Arc<dyn Catalog>) -> Self {
+        Self {
+            catalog,
+            cache: Default::default(),
+        }
+    }
+
+    async fn grants(&self, token_hash: &str) -> Result<Arc<CachedGrants>, Error> {
+        if let Some(grants) = self.cache.lock().get(token_hash) {
+            if grants.fetched_at.elapsed() < CACHE_TTL {
+                return Ok(Arc::clone(grants));
+            }
+        }
+
+        let mut repos = self.catalog.repositories().await;
+        let tokens = repos
+            .namespace_tokens()
+            .list_by_token_hash(token_hash)
+            .await
+            .context(CatalogSnafu)?;
+
+        let mut namespaces = HashMap::with_capacity(tokens.len());
+        for token in tokens {
+            if let Some(namespace) = repos
+                .namespaces()
+                .get_by_id(token.namespace_id)
+                .await
+                .context(CatalogSnafu)?
+            {
+                namespaces.insert(namespace.name, token);
+            }
+        }
+
+        let grants = Arc::new(CachedGrants {
+            fetched_at: Instant::now(),
+            namespaces,
+        });
+
+        let mut cache = self.cache.lock();
+        // don't let tokens that are no longer used accumulate
+        cache.retain(|_, g| g.fetched_at.elapsed() < CACHE_TTL);
+        cache.insert(token_hash.to_string(), Arc::clone(&grants));
+
+        Ok(grants)
+    }
+}
+
+#[async_trait]
+impl Authorizer for CatalogAuthorizer {
+    async fn authorize(
+        &self,
+        token: Option<&Token>,
+        namespace: &str,
+        permission: Permission,
+    ) -> Result<(), Error> {
+        let token = token.ok_or(Error::NoToken)?;
+        let grants = self.grants(&hash_token(token.as_bytes())).await?;
+
+        if grants.namespaces.is_empty() {
+            return Err(Error::InvalidToken);
+        }
+
+        let allowed = grants
+            .namespaces
+            .get(namespace)
+            .map(|grant| match permission {
+                Permission::Read => grant.can_read,
+                Permission::Write => grant.can_write,
+            })
+            .unwrap_or_default();
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(Error::Forbidden {
+                namespace: namespace.to_string(),
+                permission,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iox_catalog::mem::MemCatalog;
+
+    #[tokio::test]
+    async fn test_authorize() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+
+        let mut repos = catalog.repositories().await;
+        let topic = repos.topics().create_or_get("topic").await.unwrap();
+        let pool = repos.query_pools().create_or_get("pool").await.unwrap();
+        let ns1 = repos
+            .namespaces()
+            .create("ns1", "inf", topic.id, pool.id)
+            .await
+            .unwrap();
+        let ns2 = repos
+            .namespaces()
+            .create("ns2", "inf", topic.id, pool.id)
+            .await
+            .unwrap();
+        repos
+            .namespace_tokens()
+            .create_or_update(&hash_token(b"writer"), ns1.id, true, true)
+            .await
+            .unwrap();
+        repos
+            .namespace_tokens()
+            .create_or_update(&hash_token(b"writer"), ns2.id, true, false)
+            .await
+            .unwrap();
+        drop(repos);
+
+        let authz = CatalogAuthorizer::new(Arc::clone(&catalog));
+        let writer = Token::new("writer");
+
+        authz
+            .authorize(Some(&writer), "ns1", Permission::Write)
+            .await
+            .unwrap();
+        authz
+            .authorize(Some(&writer), "ns2", Permission::Read)
+            .await
+            .unwrap();
+        assert!(matches!(
+            authz
+                .authorize(Some(&writer), "ns2", Permission::Write)
+                .await,
+            Err(Error::Forbidden { .. })
+        ));
+        assert!(matches!(
+            authz
+                .authorize(Some(&writer), "ns3", Permission::Read)
+                .await,
+            Err(Error::Forbidden { .. })
+        ));
+        assert!(matches!(
+            authz
+                .authorize(Some(&Token::new("bananas")), "ns1", Permission::Read)
+                .await,
+            Err(Error::InvalidToken)
+        ));
+        assert!(matches!(
+            authz.authorize(None, "ns1", Permission::Read).await,
+            Err(Error::NoToken)
+        ));
+    }
+
+    #[test]
+    fn test_hash_token() {
+        assert_eq!(
+            hash_token(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
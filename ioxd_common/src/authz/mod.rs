@@ -0,0 +1,360 @@
+//! Authentication and authorization of API requests.
+//!
+//! Clients authenticate with an API token passed in the `authorization`
+//! header (or gRPC metadata) using either the InfluxDB 2 `Token <token>`
+//! scheme or the `Bearer <token>` scheme. An [`Authorizer`] decides whether
+//! that token grants the requested [`Permission`] on a namespace.
+
+use std::{fmt::Debug, path::PathBuf, sync::Arc};
+
+use async_trait::async_trait;
+use clap_blocks::authz::AuthzConfig;
+use hyper::{http::HeaderValue, Request, StatusCode};
+use iox_catalog::interface::Catalog;
+use snafu::Snafu;
+
+mod catalog;
+mod token_file;
+
+pub use catalog::{hash_token, CatalogAuthorizer};
+pub use token_file::TokenFileAuthorizer;
+
+/// The header (and gRPC metadata key) carrying the API token.
+pub const AUTHORIZATION_HEADER: &str = "authorization";
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("no authorization token provided"))]
+    NoToken,
+
+    #[snafu(display(
+        "malformed authorization header, expected \"Token <token>\" or \"Bearer <token>\""
+    ))]
+    MalformedHeader,
+
+    #[snafu(display("invalid authorization token"))]
+    InvalidToken,
+
+    #[snafu(display(
+        "authorization token does not grant {} access to namespace {}",
+        permission,
+        namespace
+    ))]
+    Forbidden {
+        namespace: String,
+        permission: Permission,
+    },
+
+    #[snafu(display("cannot read token file {}: {}", path.display(), source))]
+    ReadTokenFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("cannot parse token file {}: {}", path.display(), source))]
+    ParseTokenFile {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+
+    #[snafu(display("catalog error while authorizing request: {}", source))]
+    Catalog {
+        source: iox_catalog::interface::Error,
+    },
+}
+
+impl Error {
+    /// The HTTP status code to return to a client whose request failed with
+    /// this error.
+    pub fn http_status_code(&self) -> StatusCode {
+        match self {
+            Self::NoToken | Self::MalformedHeader | Self::InvalidToken => StatusCode::UNAUTHORIZED,
+            Self::Forbidden { .. } => StatusCode::FORBIDDEN,
+            Self::ReadTokenFile { .. } | Self::ParseTokenFile { .. } | Self::Catalog { .. } => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl From<Error> for tonic::Status {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::NoToken | Error::MalformedHeader | Error::InvalidToken => {
+                Self::unauthenticated(e.to_string())
+            }
+            Error::Forbidden { .. } => Self::permission_denied(e.to_string()),
+            Error::ReadTokenFile { .. } | Error::ParseTokenFile { .. } | Error::Catalog { .. } => {
+                Self::internal(e.to_string())
+            }
+        }
+    }
+}
+
+/// The access to a namespace requested by an API call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// Query the namespace.
+    Read,
+    /// Write to, or delete from, the namespace.
+    Write,
+}
+
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Read => write!(f, "read"),
+            Self::Write => write!(f, "write"),
+        }
+    }
+}
+
+/// An API token presented by a client.
+///
+/// The [`Debug`] implementation never prints the token.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Token(Vec<u8>);
+
+impl Token {
+    /// Wrap the raw bytes of a token.
+    pub fn new(token: impl Into<Vec<u8>>) -> Self {
+        Self(token.into())
+    }
+
+    /// The raw bytes of the token.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Parse the value of an `authorization` header, accepting the
+    /// `Token <token>` and `Bearer <token>` schemes (case-insensitively).
+    pub fn from_header_value(value: &[u8]) -> Result<Self, Error> {
+        let value = std::str::from_utf8(value).map_err(|_| Error::MalformedHeader)?;
+        let (scheme, token) = value.trim().split_once(' ').ok_or(Error::MalformedHeader)?;
+
+        if !scheme.eq_ignore_ascii_case("token") && !scheme.eq_ignore_ascii_case("bearer") {
+            return Err(Error::MalformedHeader);
+        }
+
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(Error::MalformedHeader);
+        }
+
+        Ok(Self::new(token))
+    }
+}
+
+impl Debug for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Token(<redacted>)")
+    }
+}
+
+/// The `authorization` header of an HTTP request.
+///
+/// The header is removed from the request headers before the request is
+/// logged, and stored as a request extension in this wrapper, whose [`Debug`]
+/// implementation never prints the token.
+#[derive(Clone)]
+pub struct AuthorizationHeader(HeaderValue);
+
+impl AuthorizationHeader {
+    pub(crate) fn new(value: HeaderValue) -> Self {
+        Self(value)
+    }
+}
+
+impl Debug for AuthorizationHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AuthorizationHeader(<redacted>)")
+    }
+}
+
+/// Extract the API token of an HTTP request, if any.
+pub fn token_from_http_request<B>(req: &Request<B>) -> Result<Option<Token>, Error> {
+    let value = req
+        .extensions()
+        .get::<AuthorizationHeader>()
+        .map(|h| &h.0)
+        .or_else(|| req.headers().get(AUTHORIZATION_HEADER));
+
+    value
+        .map(|v| Token::from_header_value(v.as_bytes()))
+        .transpose()
+}
+
+/// Extract the API token of a gRPC request, if any.
+pub fn token_from_grpc_request<T>(req: &tonic::Request<T>) -> Result<Option<Token>, Error> {
+    req.metadata()
+        .get(AUTHORIZATION_HEADER)
+        .map(|v| Token::from_header_value(v.as_bytes()))
+        .transpose()
+}
+
+/// Decides whether API tokens grant access to namespaces.
+#[async_trait]
+pub trait Authorizer: Debug + Send + Sync + 'static {
+    /// Returns `Ok(())` if `token` grants `permission` on `namespace`.
+    ///
+    /// A request that carries no token is passed a `token` of `None`.
+    async fn authorize(
+        &self,
+        token: Option<&Token>,
+        namespace: &str,
+        permission: Permission,
+    ) -> Result<(), Error>;
+}
+
+/// An [`Authorizer`] accepting a token if any of its inner authorizers does.
+#[derive(Debug)]
+pub struct AnyAuthorizer {
+    inner: Vec<Arc<dyn Authorizer>>,
+}
+
+impl AnyAuthorizer {
+    /// Accept the tokens accepted by any of `inner`.
+    pub fn new(inner: Vec<Arc<dyn Authorizer>>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl Authorizer for AnyAuthorizer {
+    async fn authorize(
+        &self,
+        token: Option<&Token>,
+        namespace: &str,
+        permission: Permission,
+    ) -> Result<(), Error> {
+        let mut error = Error::InvalidToken;
+        for authz in &self.inner {
+            match authz.authorize(token, namespace, permission).await {
+                Ok(()) => return Ok(()),
+                // report the most specific error: a token being unknown to one
+                // of the authorizers says nothing about the others
+                Err(Error::InvalidToken) => {}
+                Err(e) => error = e,
+            }
+        }
+        Err(error)
+    }
+}
+
+/// Build the [`Authorizer`] described by `config`, or `None` if requests are
+/// not to be authorized.
+pub fn from_config(
+    config: &AuthzConfig,
+    catalog: Arc<dyn Catalog>,
+) -> Result<Option<Arc<dyn Authorizer>>, Error> {
+    let mut authorizers: Vec<Arc<dyn Authorizer>> = vec![];
+    if let Some(path) = &config.token_file {
+        authorizers.push(Arc::new(TokenFileAuthorizer::from_file(path)?));
+    }
+    if config.catalog_tokens {
+        authorizers.push(Arc::new(CatalogAuthorizer::new(catalog)));
+    }
+
+    Ok(match authorizers.len() {
+        0 => None,
+        1 => authorizers.pop(),
+        _ => Some(Arc::new(AnyAuthorizer::new(authorizers))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::Body;
+
+    #[test]
+    fn test_parse_header() {
+        for value in [
+            "Token abc",
+            "token abc",
+            "Bearer abc",
+            "BEARER abc",
+            " Token   abc ",
+        ] {
+            let token = Token::from_header_value(value.as_bytes()).unwrap();
+            assert_eq!(token.as_bytes(), b"abc", "{}", value);
+        }
+
+        for value in ["", "abc", "Token", "Token ", "Basic abc", "Tokenabc"] {
+            assert!(
+                matches!(
+                    Token::from_header_value(value.as_bytes()),
+                    Err(Error::MalformedHeader)
+                ),
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_token_debug_is_redacted() {
+        let token = Token::new("secret");
+        assert!(!format!("{:?}", token).contains("secret"));
+
+        let header = AuthorizationHeader::new(HeaderValue::from_static("Token secret"));
+        assert!(!format!("{:?}", header).contains("secret"));
+    }
+
+    #[test]
+    fn test_token_from_http_request() {
+        let req = Request::builder().body(Body::empty()).unwrap();
+        assert!(token_from_http_request(&req).unwrap().is_none());
+
+        let req = Request::builder()
+            .header(AUTHORIZATION_HEADER, "Token abc")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(
+            token_from_http_request(&req).unwrap(),
+            Some(Token::new("abc"))
+        );
+
+        let mut req = Request::builder().body(Body::empty()).unwrap();
+        req.extensions_mut()
+            .insert(AuthorizationHeader::new(HeaderValue::from_static(
+                "Bearer def",
+            )));
+        assert_eq!(
+            token_from_http_request(&req).unwrap(),
+            Some(Token::new("def"))
+        );
+    }
+
+    #[test]
+    fn test_token_from_grpc_request() {
+        let mut req = tonic::Request::new(());
+        assert!(token_from_grpc_request(&req).unwrap().is_none());
+
+        req.metadata_mut()
+            .insert(AUTHORIZATION_HEADER, "Token abc".parse().unwrap());
+        assert_eq!(
+            token_from_grpc_request(&req).unwrap(),
+            Some(Token::new("abc"))
+        );
+    }
+
+    #[test]
+    fn test_status_codes() {
+        assert_eq!(Error::NoToken.http_status_code(), StatusCode::UNAUTHORIZED);
+        let forbidden = Error::Forbidden {
+            namespace: "ns".to_string(),
+            permission: Permission::Write,
+        };
+        assert_eq!(forbidden.http_status_code(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            tonic::Status::from(forbidden).code(),
+            tonic::Code::PermissionDenied
+        );
+        assert_eq!(
+            tonic::Status::from(Error::InvalidToken).code(),
+            tonic::Code::Unauthenticated
+        );
+    }
+}
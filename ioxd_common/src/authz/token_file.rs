@@ -0,0 +1,211 @@
+//! An [`Authorizer`] backed by a static file of tokens.
+
+use std::{collections::HashMap, path::Path};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use snafu::ResultExt;
+
+use super::{
+    hash_token, Authorizer, Error, ParseTokenFileSnafu, Permission, ReadTokenFileSnafu, Token,
+};
+
+/// The namespace pattern granting access to all namespaces.
+const ANY_NAMESPACE: &str = "*";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TokenFile {
+    tokens: Vec<TokenEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TokenEntry {
+    token: String,
+    #[serde(default)]
+    read: Vec<String>,
+    #[serde(default)]
+    write: Vec<String>,
+}
+
+/// The namespaces a token grants access to.
+#[derive(Debug, Default)]
+struct Grants {
+    read: Vec<String>,
+    write: Vec<String>,
+}
+
+impl Grants {
+    fn allows(&self, namespace: &str, permission: Permission) -> bool {
+        let namespaces = match permission {
+            Permission::Read => &self.read,
+            Permission::Write => &self.write,
+        };
+        namespaces
+            .iter()
+            .any(|n| n == ANY_NAMESPACE || n == namespace)
+    }
+}
+
+/// An [`Authorizer`] accepting the tokens listed in a JSON file of the form
+///
+/// ```json
+/// {
+///     "tokens": [
+///         {"token": "admin-token", "read": ["*"], "write": ["*"]},
+///         {"token": "dashboard-token", "read": ["myorg_mybucket"]}
+///     ]
+/// }
+/// ```
+///
+/// A namespace of `*` grants access to all namespaces. The file is read once,
+/// when the authorizer is created.
+///
+/// Tokens are kept and looked up by their hash, so that the time a lookup
+/// takes does not reveal how much of a presented token matches a valid one.
+pub struct TokenFileAuthorizer {
+    tokens: HashMap<String, Grants>,
+}
+
+impl std::fmt::Debug for TokenFileAuthorizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenFileAuthorizer")
+            .field("tokens", &self.tokens.len())
+            .finish()
+    }
+}
+
+impl TokenFileAuthorizer {
+    /// Load the tokens listed in the file at `path`.
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let contents = std::fs::read(path).context(ReadTokenFileSnafu { path })?;
+        let file: TokenFile =
+            serde_json::from_slice(&contents).context(ParseTokenFileSnafu { path })?;
+
+        Ok(Self::from_token_file(file))
+    }
+
+    fn from_token_file(file: TokenFile) -> Self {
+        let mut tokens: HashMap<String, Grants> = HashMap::with_capacity(file.tokens.len());
+        for entry in file.tokens {
+            // the same token listed more than once is granted the union of
+            // the namespaces of its entries
+            let grants = tokens
+                .entry(hash_token(entry.token.as_bytes()))
+                .or_default();
+            grants.read.extend(entry.read);
+            grants.write.extend(entry.write);
+        }
+
+        Self { tokens }
+    }
+}
+
+#[async_trait]
+impl Authorizer for TokenFileAuthorizer {
+    async fn authorize(
+        &self,
+        token: Option<&Token>,
+        namespace: &str,
+        permission: Permission,
+    ) -> Result<(), Error> {
+        let token = token.ok_or(Error::NoToken)?;
+        let grants = self
+            .tokens
+            .get(&hash_token(token.as_bytes()))
+            .ok_or(Error::InvalidToken)?;
+
+        if grants.allows(namespace, permission) {
+            Ok(())
+        } else {
+            Err(Error::Forbidden {
+                namespace: namespace.to_string(),
+                permission,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn authorizer(json: &str) -> TokenFileAuthorizer {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(json.as_bytes()).unwrap();
+        TokenFileAuthorizer::from_file(file.path()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_authorize() {
+        let authz = authorizer(
+            r#"{"tokens": [
+                {"token": "admin", "read": ["*"], "write": ["*"]},
+                {"token": "reader", "read": ["ns1", "ns2"]},
+                {"token": "writer", "write": ["ns1"]},
+                {"token": "writer", "read": ["ns1"]}
+            ]}"#,
+        );
+
+        let admin = Token::new("admin");
+        let reader = Token::new("reader");
+        let writer = Token::new("writer");
+
+        authz
+            .authorize(Some(&admin), "ns3", Permission::Write)
+            .await
+            .unwrap();
+        authz
+            .authorize(Some(&reader), "ns2", Permission::Read)
+            .await
+            .unwrap();
+        authz
+            .authorize(Some(&writer), "ns1", Permission::Write)
+            .await
+            .unwrap();
+        authz
+            .authorize(Some(&writer), "ns1", Permission::Read)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            authz
+                .authorize(Some(&reader), "ns1", Permission::Write)
+                .await,
+            Err(Error::Forbidden { .. })
+        ));
+        assert!(matches!(
+            authz
+                .authorize(Some(&writer), "ns2", Permission::Write)
+                .await,
+            Err(Error::Forbidden { .. })
+        ));
+        assert!(matches!(
+            authz
+                .authorize(Some(&Token::new("bananas")), "ns1", Permission::Read)
+                .await,
+            Err(Error::InvalidToken)
+        ));
+        assert!(matches!(
+            authz.authorize(None, "ns1", Permission::Read).await,
+            Err(Error::NoToken)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(br#"{"tokens": [{"tokn": "a"}]}"#).unwrap();
+        assert!(matches!(
+            TokenFileAuthorizer::from_file(file.path()),
+            Err(Error::ParseTokenFile { .. })
+        ));
+
+        assert!(matches!(
+            TokenFileAuthorizer::from_file(Path::new("/does/not/exist")),
+            Err(Error::ReadTokenFile { .. })
+        ));
+    }
+}
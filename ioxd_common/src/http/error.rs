@@ -1,5 +1,6 @@
-use hyper::{Body, Response, StatusCode};
+use hyper::{header::RETRY_AFTER, Body, Response, StatusCode};
 use observability_deps::tracing::warn;
+use std::time::Duration;
 
 /// Constants used in API error codes.
 ///
@@ -91,6 +92,15 @@ impl From<StatusCode> for HttpApiErrorCode {
     }
 }
 
+/// Round `d` up to whole seconds, never returning less than 1 second.
+///
+/// This is the value of the `Retry-After` header / metadata of throttled
+/// requests.
+pub fn retry_after_secs(d: Duration) -> u64 {
+    let secs = d.as_secs() + u64::from(d.subsec_nanos() > 0);
+    secs.max(1)
+}
+
 /// Error that is compatible with the Influxdata Cloud 2 HTTP API.
 ///
 /// See <https://docs.influxdata.com/influxdb/v2.1/api/#operation/PostWrite>.
@@ -101,6 +111,10 @@ pub struct HttpApiError {
 
     /// Human-readable message.
     msg: String,
+
+    /// Time after which the client may retry the request, returned in the
+    /// `Retry-After` header.
+    retry_after: Option<Duration>,
 }
 
 impl HttpApiError {
@@ -109,9 +123,17 @@ impl HttpApiError {
         Self {
             code: code.into(),
             msg: msg.into(),
+            retry_after: None,
         }
     }
 
+    /// Advise the client to retry the request after `retry_after`, rounded up
+    /// to whole seconds.
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = Some(retry_after);
+        self
+    }
+
     /// Generate response body for this error.
     fn body(&self) -> Body {
         let json = serde_json::json!({
@@ -125,10 +147,11 @@ impl HttpApiError {
 
     /// Generate response for this error.
     pub fn response(&self) -> Response<Body> {
-        Response::builder()
-            .status(self.code.status_code())
-            .body(self.body())
-            .unwrap()
+        let mut builder = Response::builder().status(self.code.status_code());
+        if let Some(retry_after) = self.retry_after {
+            builder = builder.header(RETRY_AFTER, retry_after_secs(retry_after));
+        }
+        builder.body(self.body()).unwrap()
     }
 
     /// Check if the error is an internal server error.
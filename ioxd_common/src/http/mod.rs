@@ -2,19 +2,23 @@ use std::{convert::Infallible, num::NonZeroI32, sync::Arc};
 
 use hyper::{
     http::HeaderValue,
-    server::conn::{AddrIncoming, AddrStream},
+    server::{accept::Accept, conn::AddrIncoming},
     Body, Method, Request, Response,
 };
 use observability_deps::tracing::{debug, error};
 use serde::Deserialize;
 use snafu::Snafu;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::TlsAcceptor;
 use tokio_util::sync::CancellationToken;
 use tower::Layer;
 use trace_http::{ctx::TraceHeaderParser, tower::TraceLayer};
 
 use crate::{
+    authz::{AuthorizationHeader, AUTHORIZATION_HEADER},
     http::error::{HttpApiError, HttpApiErrorExt, HttpApiErrorSource},
     server_type::ServerType,
+    tls::tls_incoming,
 };
 
 #[cfg(feature = "heappy")]
@@ -90,17 +94,43 @@ impl HttpApiErrorSource for ApplicationError {
 
 pub async fn serve(
     addr: AddrIncoming,
+    tls_acceptor: Option<TlsAcceptor>,
     server_type: Arc<dyn ServerType>,
     shutdown: CancellationToken,
     trace_header_parser: TraceHeaderParser,
 ) -> Result<(), hyper::Error> {
+    match tls_acceptor {
+        Some(tls_acceptor) => {
+            serve_incoming(
+                tls_incoming(addr, tls_acceptor),
+                server_type,
+                shutdown,
+                trace_header_parser,
+            )
+            .await
+        }
+        None => serve_incoming(addr, server_type, shutdown, trace_header_parser).await,
+    }
+}
+
+async fn serve_incoming<I>(
+    incoming: I,
+    server_type: Arc<dyn ServerType>,
+    shutdown: CancellationToken,
+    trace_header_parser: TraceHeaderParser,
+) -> Result<(), hyper::Error>
+where
+    I: Accept,
+    I::Conn: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    I::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
     let metric_registry = server_type.metric_registry();
     let trace_collector = server_type.trace_collector();
 
     let trace_layer = TraceLayer::new(trace_header_parser, metric_registry, trace_collector, false);
 
-    hyper::Server::builder(addr)
-        .serve(hyper::service::make_service_fn(|_conn: &AddrStream| {
+    hyper::Server::builder(incoming)
+        .serve(hyper::service::make_service_fn(|_conn: &I::Conn| {
             let server_type = Arc::clone(&server_type);
             let service = hyper::service::service_fn(move |request: Request<_>| {
                 route_request(Arc::clone(&server_type), request)
@@ -117,8 +147,12 @@ async fn route_request(
     server_type: Arc<dyn ServerType>,
     mut req: Request<Body>,
 ) -> Result<Response<Body>, Infallible> {
-    // we don't need the authorization header and we don't want to accidentally log it.
-    req.headers_mut().remove("authorization");
+    // we don't want to accidentally log the authorization header, so move it to an extension
+    // that redacts it for the server types that authorize requests.
+    if let Some(authorization) = req.headers_mut().remove(AUTHORIZATION_HEADER) {
+        req.extensions_mut()
+            .insert(AuthorizationHeader::new(authorization));
+    }
     debug!(request = ?req,"Processing request");
 
     let method = req.method().clone();
@@ -1,7 +1,9 @@
+pub mod authz;
 pub mod http;
 pub mod rpc;
 pub mod server_type;
 mod service;
+pub mod tls;
 
 // These crates are used by the macros we export; provide a stable
 // path to use them from in downstream crates.
@@ -41,6 +43,9 @@ pub enum Error {
         source: std::io::Error,
     },
 
+    #[snafu(display("Invalid TLS config: {}", source))]
+    Tls { source: tls::Error },
+
     #[snafu(display("Error serving HTTP: {}", source))]
     ServingHttp { source: hyper::Error },
 
@@ -124,9 +129,20 @@ pub async fn serve(
                 .traces_jaeger_debug_name,
         );
 
+    let tls_config = common_state.run_config().tls_config();
+    let grpc_tls_config = tls::grpc_server_tls_config(tls_config).context(TlsSnafu)?;
+    let http_tls_acceptor = tls::http_tls_acceptor(tls_config).context(TlsSnafu)?;
+    if tls_config.is_enabled() {
+        info!(
+            client_auth = tls_config.tls_client_ca.is_some(),
+            "serving gRPC and HTTP over TLS"
+        );
+    }
+
     // Construct and start up gRPC server
     let grpc_server = rpc::serve(
         grpc_listener,
+        grpc_tls_config,
         Arc::clone(&server_type),
         trace_header_parser.clone(),
         frontend_shutdown.clone(),
@@ -141,6 +157,7 @@ pub async fn serve(
             info!(server_type=?captured_server_type, "HTTP server listening");
             http::serve(
                 http_listener,
+                http_tls_acceptor,
                 captured_server_type,
                 captured_shutdown,
                 trace_header_parser,
@@ -3,7 +3,11 @@ use std::sync::Arc;
 
 use tokio::net::TcpListener;
 use tokio_util::sync::CancellationToken;
-use tonic::{body::BoxBody, transport::NamedService, Code};
+use tonic::{
+    body::BoxBody,
+    transport::{NamedService, ServerTlsConfig},
+    Code,
+};
 use tonic_health::server::HealthReporter;
 use trace_http::ctx::TraceHeaderParser;
 
@@ -17,6 +21,8 @@ pub fn service_name<S: NamedService>(_: &S) -> &'static str {
 #[derive(Debug)]
 pub struct RpcBuilderInput {
     pub socket: TcpListener,
+    /// TLS config of the server, `None` to serve plaintext.
+    pub tls_config: Option<ServerTlsConfig>,
     pub trace_header_parser: TraceHeaderParser,
     pub shutdown: CancellationToken,
 }
@@ -80,6 +86,7 @@ macro_rules! setup_builder {
 
         let RpcBuilderInput {
             socket,
+            tls_config,
             trace_header_parser,
             shutdown,
         } = $input;
@@ -94,6 +101,10 @@ macro_rules! setup_builder {
             .expect("gRPC reflection data broken");
 
         let builder = $crate::reexport::tonic::transport::Server::builder();
+        let builder = match tls_config {
+            Some(tls_config) => builder.tls_config(tls_config)?,
+            None => builder,
+        };
         let builder = builder
             .layer($crate::reexport::trace_http::tower::TraceLayer::new(
                 trace_header_parser,
@@ -170,12 +181,14 @@ pub fn handle_panic(err: Box<dyn Any + Send + 'static>) -> http::Response<BoxBod
 /// shutdown.
 pub async fn serve(
     socket: TcpListener,
+    tls_config: Option<ServerTlsConfig>,
     server_type: Arc<dyn ServerType>,
     trace_header_parser: TraceHeaderParser,
     shutdown: CancellationToken,
 ) -> Result<(), RpcError> {
     let builder_input = RpcBuilderInput {
         socket,
+        tls_config,
         trace_header_parser,
         shutdown,
     };
@@ -0,0 +1,481 @@
+//! TLS for the gRPC and HTTP listeners and the internal gRPC clients.
+//!
+//! All are configured from the same [`TlsConfig`]: the listeners present the
+//! server certificate and, when a client CA is configured, require clients
+//! to present a certificate signed by it (mutual TLS). Internal clients
+//! present the same certificate and verify servers against the configured
+//! CA.
+
+use std::{
+    future::Future,
+    io::BufReader,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use clap_blocks::tls::TlsConfig;
+use client_util::connection;
+use futures::ready;
+use hyper::server::{
+    accept,
+    conn::{AddrIncoming, AddrStream},
+};
+use observability_deps::tracing::debug;
+use rustls::{server::AllowAnyAuthenticatedClient, RootCertStore};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    time::Timeout,
+};
+use tokio_rustls::{server::TlsStream, Accept, TlsAcceptor};
+use tonic::transport::{Certificate, Identity, ServerTlsConfig};
+
+/// Time after which an HTTP connection that has not completed its TLS
+/// handshake is dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("Cannot read TLS file {}: {}", path.display(), source))]
+    ReadFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("No PEM encoded certificate found in {}", path.display()))]
+    NoCertificate { path: PathBuf },
+
+    #[snafu(display("Invalid CA certificate in {}", path.display()))]
+    InvalidCaCertificate { path: PathBuf },
+
+    #[snafu(display("No PEM encoded private key found in {}", path.display()))]
+    NoPrivateKey { path: PathBuf },
+
+    #[snafu(display("Invalid TLS certificate or private key: {}", source))]
+    InvalidIdentity { source: rustls::Error },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Returns the TLS config of the gRPC listener, or `None` if it serves
+/// plaintext.
+pub fn grpc_server_tls_config(config: &TlsConfig) -> Result<Option<ServerTlsConfig>> {
+    let (cert, key) = match (&config.tls_cert, &config.tls_key) {
+        (Some(cert), Some(key)) => (cert, key),
+        _ => return Ok(None),
+    };
+
+    let mut tls_config =
+        ServerTlsConfig::new().identity(Identity::from_pem(read_file(cert)?, read_file(key)?));
+    if let Some(client_ca) = &config.tls_client_ca {
+        tls_config = tls_config.client_ca_root(Certificate::from_pem(read_file(client_ca)?));
+    }
+
+    Ok(Some(tls_config))
+}
+
+/// Returns the TLS acceptor of the HTTP listener, or `None` if it serves
+/// plaintext.
+pub fn http_tls_acceptor(config: &TlsConfig) -> Result<Option<TlsAcceptor>> {
+    let (cert, key) = match (&config.tls_cert, &config.tls_key) {
+        (Some(cert), Some(key)) => (cert, key),
+        _ => return Ok(None),
+    };
+
+    let builder = rustls::ServerConfig::builder().with_safe_defaults();
+    let builder = match &config.tls_client_ca {
+        Some(client_ca) => {
+            let mut roots = RootCertStore::empty();
+            let ders: Vec<_> = read_certificates(client_ca)?
+                .into_iter()
+                .map(|cert| cert.0)
+                .collect();
+            let (_, invalid) = roots.add_parsable_certificates(&ders);
+            ensure!(invalid == 0, InvalidCaCertificateSnafu { path: client_ca });
+            builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots))
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut server_config = builder
+        .with_single_cert(read_certificates(cert)?, read_private_key(key)?)
+        .context(InvalidIdentitySnafu)?;
+    server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(Some(TlsAcceptor::from(Arc::new(server_config))))
+}
+
+/// Returns a connection builder for the internal gRPC clients.
+///
+/// The builder verifies `https://` servers against the configured CA and
+/// presents the server certificate to servers requiring client certificates.
+pub fn client_connection_builder(config: &TlsConfig) -> Result<connection::Builder> {
+    let mut builder = connection::Builder::new();
+    if let Some(ca) = &config.tls_ca {
+        builder = builder.tls_ca_certificate(read_file(ca)?);
+    }
+    if let (Some(cert), Some(key)) = (&config.tls_cert, &config.tls_key) {
+        builder = builder.tls_identity(read_file(cert)?, read_file(key)?);
+    }
+
+    Ok(builder)
+}
+
+/// Wraps the connections accepted by `incoming` in TLS.
+///
+/// The handshake is performed lazily by the task serving the connection,
+/// so that slow clients cannot hold up accepting further connections, and
+/// is aborted after [`HANDSHAKE_TIMEOUT`].
+pub(crate) fn tls_incoming(
+    mut incoming: AddrIncoming,
+    acceptor: TlsAcceptor,
+) -> impl accept::Accept<Conn = LazyTlsStream, Error = std::io::Error> {
+    accept::poll_fn(move |cx| loop {
+        match ready!(Pin::new(&mut incoming).poll_accept(cx)) {
+            Some(Ok(conn)) => {
+                return Poll::Ready(Some(Ok::<_, std::io::Error>(LazyTlsStream::new(
+                    &acceptor, conn,
+                ))))
+            }
+            Some(Err(e)) => debug!(%e, "error accepting HTTP connection"),
+            None => return Poll::Ready(None),
+        }
+    })
+}
+
+/// A TLS connection whose handshake is performed on first use.
+pub(crate) enum LazyTlsStream {
+    Handshake(Pin<Box<Timeout<Accept<AddrStream>>>>),
+    Stream(Box<TlsStream<AddrStream>>),
+    Failed,
+}
+
+impl LazyTlsStream {
+    fn new(acceptor: &TlsAcceptor, conn: AddrStream) -> Self {
+        Self::Handshake(Box::pin(tokio::time::timeout(
+            HANDSHAKE_TIMEOUT,
+            acceptor.accept(conn),
+        )))
+    }
+
+    /// Drives the handshake, returning the TLS stream once it completed.
+    fn poll_stream(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<std::io::Result<&mut TlsStream<AddrStream>>> {
+        if let Self::Handshake(handshake) = self {
+            let result = ready!(handshake.as_mut().poll(cx));
+            *self = match result {
+                Ok(Ok(stream)) => Self::Stream(Box::new(stream)),
+                Ok(Err(e)) => {
+                    debug!(%e, "TLS handshake failed");
+                    Self::Failed
+                }
+                Err(_) => {
+                    debug!(timeout=?HANDSHAKE_TIMEOUT, "TLS handshake timed out");
+                    Self::Failed
+                }
+            };
+        }
+
+        match self {
+            Self::Stream(stream) => Poll::Ready(Ok(stream.as_mut())),
+            Self::Failed => Poll::Ready(Err(std::io::ErrorKind::NotConnected.into())),
+            Self::Handshake(_) => unreachable!("handshake completed above"),
+        }
+    }
+}
+
+impl AsyncRead for LazyTlsStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let stream = ready!(self.get_mut().poll_stream(cx))?;
+        Pin::new(stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for LazyTlsStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let stream = ready!(self.get_mut().poll_stream(cx))?;
+        Pin::new(stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let stream = ready!(self.get_mut().poll_stream(cx))?;
+        Pin::new(stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let stream = ready!(self.get_mut().poll_stream(cx))?;
+        Pin::new(stream).poll_shutdown(cx)
+    }
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).context(ReadFileSnafu { path })
+}
+
+fn read_certificates(path: &Path) -> Result<Vec<rustls::Certificate>> {
+    let pem = read_file(path)?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(pem.as_slice()))
+        .context(ReadFileSnafu { path })?;
+    ensure!(!certs.is_empty(), NoCertificateSnafu { path });
+
+    Ok(certs.into_iter().map(rustls::Certificate).collect())
+}
+
+fn read_private_key(path: &Path) -> Result<rustls::PrivateKey> {
+    let pem = read_file(path)?;
+    let items = rustls_pemfile::read_all(&mut BufReader::new(pem.as_slice()))
+        .context(ReadFileSnafu { path })?;
+
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(rustls::PrivateKey(key)),
+            _ => None,
+        })
+        .context(NoPrivateKeySnafu { path })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, net::SocketAddr};
+
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Body, Response,
+    };
+    use rcgen::{BasicConstraints, CertificateParams, IsCa};
+    use tempfile::TempDir;
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tokio_util::sync::CancellationToken;
+    use tonic_health::{
+        proto::{health_client::HealthClient, HealthCheckRequest},
+        ServingStatus,
+    };
+
+    use super::*;
+
+    /// A CA and the server and client certificates it signed, written to a
+    /// temporary directory.
+    struct TestCerts {
+        dir: TempDir,
+    }
+
+    impl TestCerts {
+        fn new() -> Self {
+            let dir = TempDir::new().unwrap();
+
+            let mut ca_params = CertificateParams::new(vec![]);
+            ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            let ca = rcgen::Certificate::from_params(ca_params).unwrap();
+            std::fs::write(dir.path().join("ca.pem"), ca.serialize_pem().unwrap()).unwrap();
+
+            for (name, san) in [("server", "localhost"), ("client", "client")] {
+                let cert =
+                    rcgen::Certificate::from_params(CertificateParams::new(vec![san.to_string()]))
+                        .unwrap();
+                std::fs::write(
+                    dir.path().join(format!("{name}.pem")),
+                    cert.serialize_pem_with_signer(&ca).unwrap(),
+                )
+                .unwrap();
+                std::fs::write(
+                    dir.path().join(format!("{name}.key")),
+                    cert.serialize_private_key_pem(),
+                )
+                .unwrap();
+            }
+
+            Self { dir }
+        }
+
+        fn path(&self, file: &str) -> PathBuf {
+            self.dir.path().join(file)
+        }
+
+        /// Config of a server requiring client certificates.
+        fn server_config(&self) -> TlsConfig {
+            TlsConfig {
+                tls_cert: Some(self.path("server.pem")),
+                tls_key: Some(self.path("server.key")),
+                tls_client_ca: Some(self.path("ca.pem")),
+                tls_ca: None,
+            }
+        }
+
+        /// Config of a client presenting the client certificate.
+        fn client_config(&self) -> TlsConfig {
+            TlsConfig {
+                tls_cert: Some(self.path("client.pem")),
+                tls_key: Some(self.path("client.key")),
+                tls_client_ca: None,
+                tls_ca: Some(self.path("ca.pem")),
+            }
+        }
+    }
+
+    async fn serve_grpc(tls_config: ServerTlsConfig) -> (SocketAddr, CancellationToken) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
+        health_reporter
+            .set_service_status("test", ServingStatus::Serving)
+            .await;
+
+        let shutdown = CancellationToken::new();
+        let server_shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            tonic::transport::Server::builder()
+                .tls_config(tls_config)
+                .unwrap()
+                .add_service(health_service)
+                .serve_with_incoming_shutdown(
+                    TcpListenerStream::new(listener),
+                    server_shutdown.cancelled(),
+                )
+                .await
+                .unwrap();
+        });
+
+        (addr, shutdown)
+    }
+
+    async fn grpc_health_check(builder: connection::Builder, addr: SocketAddr) -> bool {
+        let connection = match builder
+            .tls_domain_name("localhost")
+            .build(format!("https://{addr}"))
+            .await
+        {
+            Ok(connection) => connection,
+            Err(_) => return false,
+        };
+
+        HealthClient::new(connection)
+            .check(HealthCheckRequest {
+                service: "test".to_string(),
+            })
+            .await
+            .is_ok()
+    }
+
+    #[tokio::test]
+    async fn test_grpc_mutual_tls() {
+        let certs = TestCerts::new();
+        let tls_config = grpc_server_tls_config(&certs.server_config())
+            .unwrap()
+            .unwrap();
+        let (addr, shutdown) = serve_grpc(tls_config).await;
+
+        // client presenting a certificate signed by the client CA
+        let builder = client_connection_builder(&certs.client_config()).unwrap();
+        assert!(grpc_health_check(builder, addr).await);
+
+        // client without a certificate
+        let builder = client_connection_builder(&TlsConfig {
+            tls_ca: Some(certs.path("ca.pem")),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(!grpc_health_check(builder, addr).await);
+
+        // client not trusting the server certificate
+        let builder = client_connection_builder(&TlsConfig {
+            tls_ca: Some(certs.path("client.pem")),
+            ..certs.client_config()
+        })
+        .unwrap();
+        assert!(!grpc_health_check(builder, addr).await);
+
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn test_http_mutual_tls() {
+        let certs = TestCerts::new();
+        let acceptor = http_tls_acceptor(&certs.server_config()).unwrap().unwrap();
+
+        let incoming = AddrIncoming::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+        let addr = incoming.local_addr();
+        let server = hyper::Server::builder(tls_incoming(incoming, acceptor)).serve(
+            make_service_fn(|_conn| async {
+                Ok::<_, Infallible>(service_fn(|_req| async {
+                    Ok::<_, Infallible>(Response::new(Body::from("ok")))
+                }))
+            }),
+        );
+        tokio::spawn(server);
+
+        let ca =
+            reqwest::Certificate::from_pem(&std::fs::read(certs.path("ca.pem")).unwrap()).unwrap();
+        let url = format!("https://localhost:{}/", addr.port());
+
+        // client presenting a certificate signed by the client CA
+        let mut identity = std::fs::read(certs.path("client.pem")).unwrap();
+        identity.extend(std::fs::read(certs.path("client.key")).unwrap());
+        let client = reqwest::Client::builder()
+            .use_rustls_tls()
+            .add_root_certificate(ca.clone())
+            .identity(reqwest::Identity::from_pem(&identity).unwrap())
+            .resolve("localhost", addr)
+            // a new connection, and handshake, per request
+            .pool_max_idle_per_host(0)
+            .build()
+            .unwrap();
+        let body = client.get(&url).send().await.unwrap().text().await.unwrap();
+        assert_eq!(body, "ok");
+
+        // connections stalling in the handshake do not hold up other clients
+        let mut stalled = vec![];
+        for _ in 0..100 {
+            stalled.push(tokio::net::TcpStream::connect(addr).await.unwrap());
+        }
+        let body = client.get(&url).send().await.unwrap().text().await.unwrap();
+        assert_eq!(body, "ok");
+        drop(stalled);
+
+        // client without a certificate
+        let client = reqwest::Client::builder()
+            .use_rustls_tls()
+            .add_root_certificate(ca)
+            .resolve("localhost", addr)
+            .build()
+            .unwrap();
+        client.get(&url).send().await.unwrap_err();
+    }
+
+    #[test]
+    fn test_missing_private_key() {
+        let certs = TestCerts::new();
+        let config = TlsConfig {
+            tls_key: Some(certs.path("server.pem")),
+            ..certs.server_config()
+        };
+
+        let err = http_tls_acceptor(&config).unwrap_err();
+        assert!(matches!(err, Error::NoPrivateKey { .. }), "{}", err);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let config = TlsConfig::default();
+        assert!(grpc_server_tls_config(&config).unwrap().is_none());
+        assert!(http_tls_acceptor(&config).unwrap().is_none());
+    }
+}
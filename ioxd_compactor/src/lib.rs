@@ -84,9 +84,10 @@ impl<C: CompactorHandler + std::fmt::Debug + 'static> ServerType for CompactorSe
         Err(Box::new(IoxHttpError::NotFound))
     }
 
-    /// Provide a placeholder gRPC service.
+    /// Provide the compactor admin gRPC service.
     async fn server_grpc(self: Arc<Self>, builder_input: RpcBuilderInput) -> Result<(), RpcError> {
         let builder = setup_builder!(builder_input, self);
+        add_service!(builder, self.server.grpc().admin_service());
         serve_builder!(builder);
 
         Ok(())
@@ -205,6 +206,7 @@ pub async fn build_compactor_from_config(
         memory_budget_bytes,
         min_num_rows_allocated_per_record_batch_to_datafusion_plan,
         max_num_compacting_files,
+        max_num_files_to_materialize_deletes,
         ..
     } = compactor_config;
 
@@ -218,6 +220,7 @@ pub async fn build_compactor_from_config(
         memory_budget_bytes,
         min_num_rows_allocated_per_record_batch_to_datafusion_plan,
         max_num_compacting_files,
+        max_num_files_to_materialize_deletes,
     };
 
     Ok(compactor::compact::Compactor::new(
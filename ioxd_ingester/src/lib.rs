@@ -11,7 +11,7 @@ use iox_catalog::interface::Catalog;
 use iox_query::exec::Executor;
 use ioxd_common::{
     add_service,
-    http::error::{HttpApiError, HttpApiErrorCode, HttpApiErrorSource},
+    http::error::{HttpApiError, HttpApiErrorSource},
     rpc::RpcBuilderInput,
     serve_builder,
     server_type::{CommonServerState, RpcError, ServerType},
@@ -80,12 +80,17 @@ impl<I: IngestHandler + Sync + Send + Debug + 'static> ServerType for IngesterSe
         self.trace_collector.as_ref().map(Arc::clone)
     }
 
-    /// Just return "not found".
+    /// Dispatches `req` to the ingester [`HttpDelegate`] delegate.
     async fn route_http_request(
         &self,
-        _req: Request<Body>,
+        req: Request<Body>,
     ) -> Result<Response<Body>, Box<dyn HttpApiErrorSource>> {
-        Err(Box::new(IoxHttpError::NotFound))
+        self.server
+            .http()
+            .route(req)
+            .await
+            .map_err(IoxHttpErrorAdaptor)
+            .map_err(|e| Box::new(e) as _)
     }
 
     /// Provide a placeholder gRPC service.
@@ -93,6 +98,7 @@ impl<I: IngestHandler + Sync + Send + Debug + 'static> ServerType for IngesterSe
         let builder = setup_builder!(builder_input, self);
         add_service!(builder, self.server.grpc().flight_service());
         add_service!(builder, self.server.grpc().write_info_service());
+        add_service!(builder, self.server.grpc().admin_service());
         serve_builder!(builder);
 
         Ok(())
@@ -107,31 +113,23 @@ impl<I: IngestHandler + Sync + Send + Debug + 'static> ServerType for IngesterSe
     }
 }
 
-/// Simple error struct, we're not really providing an HTTP interface for the ingester.
+/// This adaptor converts the `ingester` http error type into a type that
+/// satisfies the requirements of ioxd's runner framework, keeping the
+/// two decoupled.
 #[derive(Debug)]
-pub enum IoxHttpError {
-    NotFound,
-}
-
-impl IoxHttpError {
-    fn status_code(&self) -> HttpApiErrorCode {
-        match self {
-            IoxHttpError::NotFound => HttpApiErrorCode::NotFound,
-        }
-    }
-}
+pub struct IoxHttpErrorAdaptor(ingester::server::http::Error);
 
-impl Display for IoxHttpError {
+impl Display for IoxHttpErrorAdaptor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        Display::fmt(&self.0, f)
     }
 }
 
-impl std::error::Error for IoxHttpError {}
+impl std::error::Error for IoxHttpErrorAdaptor {}
 
-impl HttpApiErrorSource for IoxHttpError {
+impl HttpApiErrorSource for IoxHttpErrorAdaptor {
     fn to_http_api_error(&self) -> HttpApiError {
-        HttpApiError::new(self.status_code(), self.to_string())
+        HttpApiError::new(self.0.as_status_code(), self.to_string())
     }
 }
 
@@ -1,23 +1,29 @@
 use async_trait::async_trait;
-use clap_blocks::querier::{IngesterAddresses, QuerierConfig};
+use clap_blocks::{
+    authz::AuthzConfig,
+    querier::{IngesterAddresses, QuerierConfig},
+};
 use hyper::{Body, Request, Response};
 use iox_catalog::interface::Catalog;
 use iox_query::exec::Executor;
 use iox_time::TimeProvider;
 use ioxd_common::{
     add_service,
+    authz::{self, Authorizer},
     http::error::{HttpApiError, HttpApiErrorCode, HttpApiErrorSource},
     rpc::RpcBuilderInput,
     serve_builder,
     server_type::{CommonServerState, RpcError, ServerType},
-    setup_builder,
+    setup_builder, tls,
 };
 use metric::Registry;
 use object_store::DynObjectStore;
+use observability_deps::tracing::info;
 use parquet_file::storage::ParquetStorage;
 use querier::{
-    create_ingester_connections_by_shard, QuerierCatalogCache, QuerierDatabase, QuerierHandler,
-    QuerierHandlerImpl, QuerierServer,
+    create_ingester_connections_by_shard,
+    query_history::{QueryHistory, QueryHistoryConfig, RouterWriter},
+    QuerierCatalogCache, QuerierDatabase, QuerierHandler, QuerierHandlerImpl, QuerierServer,
 };
 use std::{
     fmt::{Debug, Display},
@@ -26,6 +32,7 @@ use std::{
 use thiserror::Error;
 use tokio::runtime::Handle;
 use trace::TraceCollector;
+use uuid::Uuid;
 
 mod rpc;
 
@@ -33,6 +40,7 @@ pub struct QuerierServerType<C: QuerierHandler> {
     database: Arc<QuerierDatabase>,
     server: QuerierServer<C>,
     trace_collector: Option<Arc<dyn TraceCollector>>,
+    authz: Option<Arc<dyn Authorizer>>,
 }
 
 impl<C: QuerierHandler> std::fmt::Debug for QuerierServerType<C> {
@@ -51,8 +59,16 @@ impl<C: QuerierHandler> QuerierServerType<C> {
             server,
             database,
             trace_collector: common_state.trace_collector(),
+            authz: None,
         }
     }
+
+    /// Require queries to carry a token granting `authz` read access to the
+    /// namespace they query.
+    pub fn with_authorizer(mut self, authz: Arc<dyn Authorizer>) -> Self {
+        self.authz = Some(authz);
+        self
+    }
 }
 
 #[async_trait]
@@ -80,11 +96,15 @@ impl<C: QuerierHandler + std::fmt::Debug + 'static> ServerType for QuerierServer
         let builder = setup_builder!(builder_input, self);
         add_service!(
             builder,
-            rpc::query::make_flight_server(Arc::clone(&self.database))
+            rpc::query::make_flight_server(Arc::clone(&self.database), self.authz.clone())
         );
         add_service!(
             builder,
-            rpc::query::make_storage_server(Arc::clone(&self.database))
+            rpc::query::make_storage_server(Arc::clone(&self.database), self.authz.clone())
+        );
+        add_service!(
+            builder,
+            rpc::query::query_service(Arc::clone(&self.database), self.authz.clone())
         );
         add_service!(
             builder,
@@ -148,18 +168,29 @@ pub struct QuerierServerTypeArgs<'a> {
     pub time_provider: Arc<dyn TimeProvider>,
     pub ingester_addresses: IngesterAddresses,
     pub querier_config: QuerierConfig,
+    pub authz_config: AuthzConfig,
 }
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("querier error: {0}")]
     Querier(#[from] querier::QuerierDatabaseError),
+
+    #[error("failed to init authorizer: {0}")]
+    Authz(#[from] authz::Error),
+
+    #[error("invalid TLS config: {0}")]
+    Tls(#[from] tls::Error),
 }
 
 /// Instantiate a querier server
 pub async fn create_querier_server_type(
     args: QuerierServerTypeArgs<'_>,
 ) -> Result<Arc<dyn ServerType>, Error> {
+    let authz = authz::from_config(&args.authz_config, Arc::clone(&args.catalog))?;
+    let connection_builder =
+        tls::client_connection_builder(args.common_state.run_config().tls_config())?;
+
     let catalog_cache = Arc::new(QuerierCatalogCache::new(
         Arc::clone(&args.catalog),
         args.time_provider,
@@ -175,27 +206,56 @@ pub async fn create_querier_server_type(
         IngesterAddresses::ByShardIndex(map) => Some(create_ingester_connections_by_shard(
             map,
             Arc::clone(&catalog_cache),
+            connection_builder.clone(),
         )),
     };
 
-    let database = Arc::new(
-        QuerierDatabase::new(
-            catalog_cache,
-            Arc::clone(&args.metric_registry),
-            ParquetStorage::new(args.object_store),
-            args.exec,
-            ingester_connection,
-            args.querier_config.max_concurrent_queries(),
-            args.querier_config.max_table_query_bytes(),
-        )
-        .await?,
-    );
+    let database = QuerierDatabase::new(
+        catalog_cache,
+        Arc::clone(&args.metric_registry),
+        ParquetStorage::new(args.object_store),
+        args.exec,
+        ingester_connection,
+        args.querier_config.max_concurrent_queries(),
+        args.querier_config.max_table_query_bytes(),
+    )
+    .await?;
+    let database = match args.querier_config.query_history_target() {
+        Some((namespace, router_address)) => {
+            let mut router_connection_builder = connection_builder;
+            if let Some(token) = &args.querier_config.query_history_router_token {
+                router_connection_builder = router_connection_builder.token(token);
+            }
+            let querier_id = args
+                .querier_config
+                .query_history_querier_id
+                .clone()
+                .unwrap_or_else(|| Uuid::new_v4().to_string());
+            info!(%querier_id, "writing query history");
+
+            let history = QueryHistory::new(
+                QueryHistoryConfig {
+                    namespace: namespace.to_string(),
+                    querier_id,
+                    batch_size: args.querier_config.query_history_batch_size,
+                    flush_interval: args.querier_config.query_history_flush_interval,
+                    queue_size: args.querier_config.query_history_queue_size,
+                },
+                Arc::new(RouterWriter::new(router_address, router_connection_builder)),
+                &args.metric_registry,
+            );
+            database.with_query_history(Arc::new(history))
+        }
+        None => database,
+    };
+    let database = Arc::new(database);
     let querier_handler = Arc::new(QuerierHandlerImpl::new(args.catalog, Arc::clone(&database)));
 
     let querier = QuerierServer::new(args.metric_registry, querier_handler);
-    Ok(Arc::new(QuerierServerType::new(
-        querier,
-        database,
-        args.common_state,
-    )))
+    let mut server_type = QuerierServerType::new(querier, database, args.common_state);
+    if let Some(authz) = authz {
+        info!("authorizing query requests");
+        server_type = server_type.with_authorizer(authz);
+    }
+    Ok(Arc::new(server_type))
 }
@@ -3,13 +3,164 @@ use std::sync::Arc;
 use arrow_flight::flight_service_server::{
     FlightService as Flight, FlightServiceServer as FlightServer,
 };
-use generated_types::storage_server::{Storage, StorageServer};
+use generated_types::{
+    influxdata::iox::querier::v1::{
+        self as proto,
+        query_service_server::{QueryService, QueryServiceServer},
+    },
+    storage_server::{Storage, StorageServer},
+};
+use iox_query::KillQueryError;
+use ioxd_common::authz::{self, Authorizer, Permission};
 use querier::QuerierDatabase;
+use service_common::QueryDatabaseProvider;
+
+pub fn make_flight_server(
+    server: Arc<QuerierDatabase>,
+    authz: Option<Arc<dyn Authorizer>>,
+) -> FlightServer<impl Flight> {
+    service_grpc_flight::make_server(server, authz)
+}
+
+pub fn make_storage_server(
+    server: Arc<QuerierDatabase>,
+    authz: Option<Arc<dyn Authorizer>>,
+) -> StorageServer<impl Storage> {
+    service_grpc_influxrpc::make_server(server, authz)
+}
+
+/// Acquire a [`QueryService`] gRPC service implementation, requiring
+/// requests to carry a token granting `authz` read access to the namespace of
+/// the query if an authorizer is given.
+pub fn query_service(
+    server: Arc<QuerierDatabase>,
+    authz: Option<Arc<dyn Authorizer>>,
+) -> QueryServiceServer<impl QueryService> {
+    QueryServiceServer::new(QueryServiceImpl::new(server, authz))
+}
+
+#[derive(Debug)]
+struct QueryServiceImpl {
+    server: Arc<QuerierDatabase>,
+    authz: Option<Arc<dyn Authorizer>>,
+}
+
+impl QueryServiceImpl {
+    pub fn new(server: Arc<QuerierDatabase>, authz: Option<Arc<dyn Authorizer>>) -> Self {
+        Self { server, authz }
+    }
+}
 
-pub fn make_flight_server(server: Arc<QuerierDatabase>) -> FlightServer<impl Flight> {
-    service_grpc_flight::make_server(server)
+#[tonic::async_trait]
+impl QueryService for QueryServiceImpl {
+    async fn kill_query(
+        &self,
+        request: tonic::Request<proto::KillQueryRequest>,
+    ) -> Result<tonic::Response<proto::KillQueryResponse>, tonic::Status> {
+        let token = match self.authz {
+            Some(_) => authz::token_from_grpc_request(&request)?,
+            None => None,
+        };
+        let proto::KillQueryRequest {
+            query_id,
+            namespace_name,
+        } = request.into_inner();
+
+        if let Some(authz) = &self.authz {
+            // The token can only be checked against a namespace.
+            if namespace_name.is_empty() {
+                return Err(tonic::Status::invalid_argument(
+                    "namespace_name is required",
+                ));
+            }
+            authz
+                .authorize(token.as_ref(), &namespace_name, Permission::Read)
+                .await?;
+        }
+
+        let res = if namespace_name.is_empty() {
+            self.server.kill_any_query(query_id)
+        } else {
+            self.server
+                .kill_query(&namespace_name, query_id, None)
+                .await
+        };
+        res.map_err(|e| match e {
+            KillQueryError::NotFound { .. } => tonic::Status::not_found(e.to_string()),
+            KillQueryError::NotRunning { .. } => tonic::Status::failed_precondition(e.to_string()),
+        })?;
+
+        Ok(tonic::Response::new(proto::KillQueryResponse {}))
+    }
 }
 
-pub fn make_storage_server(server: Arc<QuerierDatabase>) -> StorageServer<impl Storage> {
-    service_grpc_influxrpc::make_server(server)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iox_tests::util::TestCatalog;
+    use ioxd_common::authz::AnyAuthorizer;
+    use parquet_file::storage::ParquetStorage;
+    use querier::{create_ingester_connection_for_testing, QuerierCatalogCache};
+    use tokio::runtime::Handle;
+
+    #[tokio::test]
+    async fn test_kill_unknown_query() {
+        let catalog = TestCatalog::new();
+
+        // QuerierDatabase::new returns an error if there are no shards in the catalog
+        catalog.create_shard(0).await;
+
+        let catalog_cache = Arc::new(QuerierCatalogCache::new_testing(
+            catalog.catalog(),
+            catalog.time_provider(),
+            catalog.metric_registry(),
+            catalog.object_store(),
+            &Handle::current(),
+        ));
+        let db = Arc::new(
+            QuerierDatabase::new(
+                catalog_cache,
+                catalog.metric_registry(),
+                ParquetStorage::new(catalog.object_store()),
+                catalog.exec(),
+                Some(create_ingester_connection_for_testing()),
+                QuerierDatabase::MAX_CONCURRENT_QUERIES_MAX,
+                usize::MAX,
+            )
+            .await
+            .unwrap(),
+        );
+
+        let service = QueryServiceImpl::new(Arc::clone(&db), None);
+
+        let status = service
+            .kill_query(tonic::Request::new(proto::KillQueryRequest {
+                query_id: 42,
+                namespace_name: String::new(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+
+        // with authorization, the namespace is required and the token checked
+        let service = QueryServiceImpl::new(db, Some(Arc::new(AnyAuthorizer::new(vec![]))));
+
+        let status = service
+            .kill_query(tonic::Request::new(proto::KillQueryRequest {
+                query_id: 42,
+                namespace_name: String::new(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let status = service
+            .kill_query(tonic::Request::new(proto::KillQueryRequest {
+                query_id: 42,
+                namespace_name: "ns".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+    }
 }
@@ -1,11 +1,12 @@
 use async_trait::async_trait;
-use clap_blocks::write_buffer::WriteBufferConfig;
+use clap_blocks::{authz::AuthzConfig, write_buffer::WriteBufferConfig};
 use data_types::{DatabaseName, PartitionTemplate, TemplatePart};
 use hashbrown::HashMap;
 use hyper::{Body, Request, Response};
 use iox_catalog::interface::Catalog;
+use iox_time::{SystemProvider, TimeProvider};
 use ioxd_common::{
-    add_service,
+    add_service, authz,
     http::error::{HttpApiError, HttpApiErrorSource},
     rpc::RpcBuilderInput,
     serve_builder,
@@ -19,7 +20,7 @@ use observability_deps::tracing::info;
 use router::{
     dml_handlers::{
         DmlHandler, DmlHandlerChainExt, FanOutAdaptor, InstrumentationDecorator,
-        NamespaceAutocreation, Partitioner, SchemaValidator, ShardedWriteBuffer,
+        NamespaceAutocreation, Partitioner, SchemaValidator, ShardedWriteBuffer, WriteRateLimiter,
         WriteSummaryAdapter,
     },
     namespace_cache::{
@@ -27,7 +28,7 @@ use router::{
     },
     server::{
         grpc::{sharder::ShardService, GrpcDelegate},
-        http::HttpDelegate,
+        http::{idempotency::IdempotencyCache, HttpDelegate},
         RouterServer,
     },
     shard::Shard,
@@ -37,6 +38,7 @@ use std::{
     collections::BTreeSet,
     fmt::{Debug, Display},
     sync::Arc,
+    time::Duration,
 };
 use thiserror::Error;
 use tokio_util::sync::CancellationToken;
@@ -62,10 +64,29 @@ pub enum Error {
 
     #[error("Failed to init shard grpc service: {0}")]
     ShardServiceInit(iox_catalog::interface::Error),
+
+    #[error("Failed to init authorizer: {0}")]
+    Authz(#[from] authz::Error),
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
+/// How long the schema of a namespace is cached before it is re-read from
+/// the catalog, picking up changes to settings such as its field coercion
+/// policy.
+const NAMESPACE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// How often the write rate limits of a namespace are re-read from the
+/// catalog.
+const WRITE_LIMITS_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long the outcome of a write carrying an idempotency key is kept to
+/// answer retries of the write.
+const IDEMPOTENCY_KEY_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// The maximum number of idempotency keys kept by each router.
+const IDEMPOTENCY_KEY_MAX_ENTRIES: usize = 100_000;
+
 pub struct RouterServerType<D, S> {
     server: RouterServer<D, S>,
     shutdown: CancellationToken,
@@ -91,7 +112,11 @@ impl<D, S> std::fmt::Debug for RouterServerType<D, S> {
 #[async_trait]
 impl<D, S> ServerType for RouterServerType<D, S>
 where
-    D: DmlHandler<WriteInput = HashMap<String, MutableBatch>, WriteOutput = WriteSummary> + 'static,
+    D: DmlHandler<
+            WriteInput = HashMap<String, MutableBatch>,
+            WriteOutput = WriteSummary,
+            DeleteOutput = WriteSummary,
+        > + 'static,
     S: Sharder<(), Item = Arc<Shard>> + Clone + 'static,
 {
     /// Return the [`metric::Registry`] used by the router.
@@ -159,7 +184,11 @@ impl std::error::Error for IoxHttpErrorAdaptor {}
 
 impl HttpApiErrorSource for IoxHttpErrorAdaptor {
     fn to_http_api_error(&self) -> HttpApiError {
-        HttpApiError::new(self.0.as_status_code(), self.to_string())
+        let err = HttpApiError::new(self.0.as_status_code(), self.to_string());
+        match self.0.retry_after() {
+            Some(retry_after) => err.with_retry_after(retry_after),
+            None => err,
+        }
     }
 }
 
@@ -172,7 +201,10 @@ pub async fn create_router_server_type(
     write_buffer_config: &WriteBufferConfig,
     query_pool_name: &str,
     request_limit: usize,
+    authz_config: &AuthzConfig,
 ) -> Result<Arc<dyn ServerType>> {
+    let authz = authz::from_config(authz_config, Arc::clone(&catalog))?;
+
     // Initialise the sharded write buffer and instrument it with DML handler
     // metrics.
     let (write_buffer, sharder) = init_write_buffer(
@@ -185,11 +217,18 @@ pub async fn create_router_server_type(
         InstrumentationDecorator::new("sharded_write_buffer", &*metrics, write_buffer);
 
     // Initialise an instrumented namespace cache to be shared with the schema
-    // validator, and namespace auto-creator that reports cache hit/miss/update
-    // metrics.
+    // validator, namespace auto-creator and HTTP delegate that reports cache
+    // hit/miss/update metrics. Cached schemas expire to pick up changed namespace settings.
+    let time_provider: Arc<dyn TimeProvider> = Arc::new(SystemProvider::new());
     let ns_cache = Arc::new(InstrumentedCache::new(
         Arc::new(ShardedCache::new(
-            std::iter::repeat_with(|| Arc::new(MemoryNamespaceCache::default())).take(10),
+            std::iter::repeat_with(|| {
+                Arc::new(MemoryNamespaceCache::with_ttl(
+                    NAMESPACE_CACHE_TTL,
+                    Arc::clone(&time_provider),
+                ))
+            })
+            .take(10),
         )),
         &*metrics,
     ));
@@ -204,6 +243,15 @@ pub async fn create_router_server_type(
     let schema_validator =
         InstrumentationDecorator::new("schema_validator", &*metrics, schema_validator);
 
+    // Initialise and instrument the per-namespace write rate limiter
+    let rate_limiter = WriteRateLimiter::new(
+        Arc::clone(&catalog),
+        Arc::clone(&ns_cache),
+        WRITE_LIMITS_REFRESH_INTERVAL,
+        &*metrics,
+    );
+    let rate_limiter = InstrumentationDecorator::new("rate_limiter", &*metrics, rate_limiter);
+
     // Add a write partitioner into the handler stack that splits by the date
     // portion of the write's timestamp.
     let partitioner = Partitioner::new(PartitionTemplate {
@@ -250,7 +298,7 @@ pub async fn create_router_server_type(
 
     let ns_creator = NamespaceAutocreation::new(
         Arc::clone(&catalog),
-        ns_cache,
+        Arc::clone(&ns_cache),
         topic_id,
         query_id,
         iox_catalog::INFINITE_RETENTION_POLICY.to_owned(),
@@ -261,10 +309,13 @@ pub async fn create_router_server_type(
     let parallel_write = WriteSummaryAdapter::new(FanOutAdaptor::new(write_buffer));
 
     // Build the chain of DML handlers that forms the request processing
-    // pipeline, starting with the namespace creator (for testing purposes) and
-    // write partitioner that yields a set of partitioned batches.
+    // pipeline, starting with the namespace creator (for testing purposes),
+    // the schema validator rejecting writes to unknown namespaces, the rate
+    // limiter throttling writes to the known ones, and write partitioner that
+    // yields a set of partitioned batches.
     let handler_stack = ns_creator
         .and_then(schema_validator)
+        .and_then(rate_limiter)
         .and_then(partitioner)
         // Once writes have been partitioned, they are processed in parallel.
         //
@@ -286,19 +337,31 @@ pub async fn create_router_server_type(
 
     // Initialise the API delegates, sharing the handler stack between them.
     let handler_stack = Arc::new(handler_stack);
-    let http = HttpDelegate::new(
+    let mut http = HttpDelegate::new(
         common_state.run_config().max_http_request_size,
         request_limit,
         Arc::clone(&handler_stack),
         &metrics,
-    );
-    let grpc = GrpcDelegate::new(
+    )
+    .with_idempotency_cache(IdempotencyCache::new(
+        IDEMPOTENCY_KEY_TTL,
+        IDEMPOTENCY_KEY_MAX_ENTRIES,
+        Arc::new(SystemProvider::new()),
+        Arc::clone(&metrics),
+    ))
+    .with_namespace_schemas(Arc::new(ns_cache));
+    let mut grpc = GrpcDelegate::new(
         handler_stack,
         schema_catalog,
         object_store,
         Arc::clone(&metrics),
         shard_service,
     );
+    if let Some(authz) = authz {
+        info!("authorizing write requests");
+        http = http.with_authorizer(Arc::clone(&authz));
+        grpc = grpc.with_authorizer(authz);
+    }
 
     let router_server = RouterServer::new(http, grpc, metrics, common_state.trace_collector());
     let server_type = Arc::new(RouterServerType::new(router_server, common_state));
@@ -0,0 +1,223 @@
+//! Resolution of the type conflicts between the field columns of a
+//! [`MutableBatch`] and the established types of these columns.
+
+use crate::{
+    column::{Column, ColumnData},
+    MutableBatch,
+};
+use arrow_util::{bitset::iter_set_positions, string::PackedStringArray};
+use data_types::{ColumnType, FieldCoercionPolicy, StatValues};
+use schema::{InfluxColumnType, InfluxFieldType};
+use snafu::{OptionExt, Snafu};
+
+#[allow(missing_docs)]
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Column not found: {}", column))]
+    ColumnNotFound { column: String },
+
+    #[snafu(display("Column {} of type {} is not a field column", column, column_type))]
+    NotAField {
+        column: String,
+        column_type: ColumnType,
+    },
+
+    #[snafu(display(
+        "Field {} of type {} cannot be coerced to {} by the {} policy",
+        column,
+        from,
+        to,
+        policy
+    ))]
+    NotCoercible {
+        column: String,
+        from: ColumnType,
+        to: ColumnType,
+        policy: FieldCoercionPolicy,
+    },
+}
+
+/// A specialized `Error` for coercion errors
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The coercion applied to a field column by
+/// [`MutableBatch::coerce_field`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FieldCoercion {
+    /// The values were losslessly converted to a wider type.
+    Widened,
+    /// The values were converted to strings.
+    Stringified,
+    /// The column was removed from the batch.
+    Dropped,
+}
+
+impl FieldCoercion {
+    /// the short string description of the coercion
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Widened => "widened",
+            Self::Stringified => "stringified",
+            Self::Dropped => "dropped",
+        }
+    }
+}
+
+impl MutableBatch {
+    /// Convert the field column `name` to the field type `to`, applying the
+    /// first of the coercions allowed by `policy` that succeeds:
+    ///
+    /// - [`FieldCoercionPolicy::Widen`]: integer values are converted to
+    ///   float, and unsigned values to integer, if every value of the column
+    ///   converts losslessly.
+    /// - [`FieldCoercionPolicy::Stringify`]: values of any type are
+    ///   converted to strings if `to` is a string.
+    /// - [`FieldCoercionPolicy::Drop`]: the column is removed, leaving the
+    ///   other columns of its rows untouched.
+    ///
+    /// Returns `None` if the column is already of type `to`.
+    pub fn coerce_field(
+        &mut self,
+        name: &str,
+        to: InfluxFieldType,
+        policy: FieldCoercionPolicy,
+    ) -> Result<Option<FieldCoercion>> {
+        let idx = *self
+            .column_names
+            .get(name)
+            .context(ColumnNotFoundSnafu { column: name })?;
+        let column = &self.columns[idx];
+
+        let from = match column.influx_type {
+            InfluxColumnType::Field(from) if from == to => return Ok(None),
+            InfluxColumnType::Field(from) => from,
+            influx_type => {
+                return NotAFieldSnafu {
+                    column: name,
+                    column_type: influx_type,
+                }
+                .fail()
+            }
+        };
+
+        if policy >= FieldCoercionPolicy::Widen {
+            if let Some(data) = widen(column, to) {
+                self.columns[idx].influx_type = InfluxColumnType::Field(to);
+                self.columns[idx].data = data;
+                return Ok(Some(FieldCoercion::Widened));
+            }
+        }
+
+        if policy >= FieldCoercionPolicy::Stringify && to == InfluxFieldType::String {
+            let data = stringify(column);
+            self.columns[idx].influx_type = InfluxColumnType::Field(to);
+            self.columns[idx].data = data;
+            return Ok(Some(FieldCoercion::Stringified));
+        }
+
+        if policy >= FieldCoercionPolicy::Drop {
+            self.drop_column(idx);
+            return Ok(Some(FieldCoercion::Dropped));
+        }
+
+        NotCoercibleSnafu {
+            column: name,
+            from: InfluxColumnType::Field(from),
+            to: InfluxColumnType::Field(to),
+            policy,
+        }
+        .fail()
+    }
+
+    /// Returns the number of rows without a value in any field column, such
+    /// as the rows whose only fields were dropped by
+    /// [`FieldCoercionPolicy::Drop`].
+    pub fn rows_without_fields(&self) -> usize {
+        let fields: Vec<_> = self
+            .columns
+            .iter()
+            .filter(|c| matches!(c.influx_type, InfluxColumnType::Field(_)))
+            .collect();
+
+        (0..self.row_count)
+            .filter(|&row| !fields.iter().any(|c| c.valid.get(row)))
+            .count()
+    }
+
+    /// Remove the column at `idx`.
+    fn drop_column(&mut self, idx: usize) {
+        self.columns.remove(idx);
+        self.column_names.retain(|_, v| *v != idx);
+        for v in self.column_names.values_mut() {
+            if *v > idx {
+                *v -= 1;
+            }
+        }
+    }
+}
+
+/// Returns the data of `column` converted to `to`, if this is a widening
+/// conversion that is lossless for every value of the column.
+fn widen(column: &Column, to: InfluxFieldType) -> Option<ColumnData> {
+    let valid = column.valid.bytes();
+    match (&column.data, to) {
+        (ColumnData::I64(data, _), InfluxFieldType::Float) => {
+            let mut stats = StatValues::new_empty();
+            let mut converted = vec![0_f64; data.len()];
+            for idx in iter_set_positions(valid).take_while(|idx| *idx < data.len()) {
+                let value = i64_to_f64(data[idx])?;
+                converted[idx] = value;
+                stats.update(&value);
+            }
+            stats.update_for_nulls(data.len() as u64 - stats.total_count);
+            Some(ColumnData::F64(converted, stats))
+        }
+        (ColumnData::U64(data, _), InfluxFieldType::Integer) => {
+            let mut stats = StatValues::new_empty();
+            let mut converted = vec![0_i64; data.len()];
+            for idx in iter_set_positions(valid).take_while(|idx| *idx < data.len()) {
+                let value = i64::try_from(data[idx]).ok()?;
+                converted[idx] = value;
+                stats.update(&value);
+            }
+            stats.update_for_nulls(data.len() as u64 - stats.total_count);
+            Some(ColumnData::I64(converted, stats))
+        }
+        _ => None,
+    }
+}
+
+/// Returns `v` as a float, if it is exactly representable as one.
+fn i64_to_f64(v: i64) -> Option<f64> {
+    let f = v as f64;
+    // 2^63 is the nearest float to i64::MAX, but exceeds it - the cast back
+    // saturates, hiding the rounding.
+    (f != 9_223_372_036_854_775_808.0 && f as i64 == v).then_some(f)
+}
+
+/// Returns the data of the field `column` converted to strings.
+fn stringify(column: &Column) -> ColumnData {
+    let len = column.len();
+    let valid = column.valid.bytes();
+    let to_string = |idx: usize| match &column.data {
+        ColumnData::F64(data, _) => data[idx].to_string(),
+        ColumnData::I64(data, _) => data[idx].to_string(),
+        ColumnData::U64(data, _) => data[idx].to_string(),
+        ColumnData::Bool(data, _) => data.get(idx).to_string(),
+        ColumnData::String(data, _) => data.get(idx).unwrap_or_default().to_string(),
+        ColumnData::Tag(..) => unreachable!("tag columns are not fields"),
+    };
+
+    let mut stats = StatValues::new_empty();
+    let mut converted = PackedStringArray::new();
+    for idx in iter_set_positions(valid).take_while(|idx| *idx < len) {
+        let value = to_string(idx);
+        converted.extend(idx - converted.len());
+        converted.append(&value);
+        stats.update(value.as_str());
+    }
+    converted.extend(len - converted.len());
+    stats.update_for_nulls(len as u64 - stats.total_count);
+
+    ColumnData::String(converted, stats)
+}
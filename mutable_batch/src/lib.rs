@@ -25,6 +25,7 @@ use schema::{builder::SchemaBuilder, Schema, TIME_COLUMN_NAME};
 use snafu::{OptionExt, ResultExt, Snafu};
 use std::ops::Range;
 
+pub mod coerce;
 pub mod column;
 pub mod payload;
 pub mod writer;
@@ -15,6 +15,7 @@ use hashbrown::{hash_map::Entry, HashMap, HashSet};
 use influxdb_line_protocol::{parse_lines, FieldValue, ParsedLine};
 use mutable_batch::writer::Writer;
 use mutable_batch::MutableBatch;
+use schema::{InfluxColumnType, InfluxFieldType};
 use snafu::{ResultExt, Snafu};
 
 /// Error type for line protocol conversion
@@ -33,8 +34,21 @@ pub enum Error {
     #[snafu(display("empty write payload"))]
     EmptyPayload,
 
-    #[snafu(display("timestamp overflows i64"))]
-    TimestampOverflow,
+    #[snafu(display("timestamp overflows i64 on line {}", line))]
+    TimestampOverflow { line: usize },
+}
+
+impl Error {
+    /// Returns the (1-based) line the error occurred on, if it is specific to
+    /// a line.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::LineProtocol { line, .. }
+            | Self::Write { line, .. }
+            | Self::TimestampOverflow { line } => Some(*line),
+            Self::EmptyPayload => None,
+        }
+    }
 }
 
 /// Result type for line protocol conversion
@@ -60,6 +74,9 @@ pub struct LinesConverter {
     stats: PayloadStatistics,
     /// The current batches
     batches: HashMap<String, MutableBatch>,
+    /// The column types lines must conform to, keyed by table name and then
+    /// column name
+    column_types: HashMap<String, HashMap<String, InfluxColumnType>>,
 }
 
 impl LinesConverter {
@@ -70,6 +87,7 @@ impl LinesConverter {
             timestamp_base: 1,
             stats: Default::default(),
             batches: Default::default(),
+            column_types: Default::default(),
         }
     }
 
@@ -78,6 +96,16 @@ impl LinesConverter {
         self.timestamp_base = timestamp_base
     }
 
+    /// Requires lines writing `column` of `table` to write it as
+    /// `column_type`, rejecting the other lines with
+    /// [`LineWriteError::SchemaConflict`].
+    pub fn set_column_type(&mut self, table: &str, column: &str, column_type: InfluxColumnType) {
+        self.column_types
+            .entry(table.to_string())
+            .or_default()
+            .insert(column.to_string(), column_type);
+    }
+
     /// Write some line protocol data.
     ///
     /// If a field / tag name appears more than once in a single line, the
@@ -99,37 +127,71 @@ impl LinesConverter {
     ///
     pub fn write_lp(&mut self, lines: &str) -> Result<()> {
         for (line_idx, maybe_line) in parse_lines(lines).enumerate() {
-            let mut line = maybe_line.context(LineProtocolSnafu { line: line_idx + 1 })?;
-
-            if let Some(t) = line.timestamp.as_mut() {
-                *t = t
-                    .checked_mul(self.timestamp_base)
-                    .ok_or(Error::TimestampOverflow)?;
-            }
+            self.write_parsed_line(line_idx + 1, maybe_line)?;
+        }
+        Ok(())
+    }
 
-            self.stats.num_lines += 1;
-            self.stats.num_fields += line.field_set.len();
+    /// Write some line protocol data, skipping the lines that cannot be
+    /// written.
+    ///
+    /// Returns the errors of the skipped lines in line order, following the
+    /// same semantics as [`LinesConverter::write_lp()`] for each line.
+    pub fn write_lp_partial(&mut self, lines: &str) -> Vec<Error> {
+        parse_lines(lines)
+            .enumerate()
+            .filter_map(|(line_idx, maybe_line)| {
+                self.write_parsed_line(line_idx + 1, maybe_line).err()
+            })
+            .collect()
+    }
 
-            let measurement = line.series.measurement.as_str();
+    /// Write a single line, `line` being its 1-based line number.
+    ///
+    /// The line is not written if an error is returned.
+    fn write_parsed_line(
+        &mut self,
+        line: usize,
+        maybe_line: Result<ParsedLine<'_>, influxdb_line_protocol::Error>,
+    ) -> Result<()> {
+        let mut parsed = maybe_line.context(LineProtocolSnafu { line })?;
+
+        if let Some(t) = parsed.timestamp.as_mut() {
+            *t = t
+                .checked_mul(self.timestamp_base)
+                .ok_or(Error::TimestampOverflow { line })?;
+        }
 
-            let (_, batch) = self
-                .batches
-                .raw_entry_mut()
-                .from_key(measurement)
-                .or_insert_with(|| (measurement.to_string(), MutableBatch::new()));
+        let measurement = parsed.series.measurement.as_str();
 
-            // TODO: Reuse writer
-            let mut writer = Writer::new(batch, 1);
-            write_line(&mut writer, &line, self.default_time)
-                .context(WriteSnafu { line: line_idx + 1 })?;
-            writer.commit();
+        if let Some(column_types) = self.column_types.get(measurement) {
+            check_column_types(&parsed, column_types).context(WriteSnafu { line })?;
         }
+
+        let (_, batch) = self
+            .batches
+            .raw_entry_mut()
+            .from_key(measurement)
+            .or_insert_with(|| (measurement.to_string(), MutableBatch::new()));
+
+        // TODO: Reuse writer
+        let mut writer = Writer::new(batch, 1);
+        write_line(&mut writer, &parsed, self.default_time).context(WriteSnafu { line })?;
+        writer.commit();
+
+        self.stats.num_lines += 1;
+        self.stats.num_fields += parsed.field_set.len();
+
         Ok(())
     }
 
     /// Consume this [`LinesConverter`] returning the [`MutableBatch`]
     /// and the [`PayloadStatistics`] for the written data
-    pub fn finish(self) -> Result<(HashMap<String, MutableBatch>, PayloadStatistics)> {
+    pub fn finish(mut self) -> Result<(HashMap<String, MutableBatch>, PayloadStatistics)> {
+        // Skipped lines may leave behind the empty batch of a table no line
+        // was written to.
+        self.batches.retain(|_, batch| batch.rows() > 0);
+
         match self.batches.is_empty() {
             false => Ok((self.batches, self.stats)),
             true => Err(Error::EmptyPayload),
@@ -187,6 +249,61 @@ pub enum LineWriteError {
         /// The duplicated field name.
         name: String,
     },
+
+    /// The line writes a column with a type other than the one set with
+    /// [`LinesConverter::set_column_type()`].
+    #[snafu(display(
+        "schema conflict: column '{}' is of type {} but the line writes {}",
+        name,
+        existing,
+        new
+    ))]
+    SchemaConflict {
+        /// The column name.
+        name: String,
+        /// The type the column must be written as.
+        existing: InfluxColumnType,
+        /// The type the line writes.
+        new: InfluxColumnType,
+    },
+}
+
+/// Checks the tags and fields of `line` are of the types in `column_types`.
+fn check_column_types(
+    line: &ParsedLine<'_>,
+    column_types: &HashMap<String, InfluxColumnType>,
+) -> Result<(), LineWriteError> {
+    let tags = line
+        .series
+        .tag_set
+        .iter()
+        .flatten()
+        .map(|(key, _)| (key, InfluxColumnType::Tag));
+    let fields = line.field_set.iter().map(|(key, value)| {
+        let field_type = match value {
+            FieldValue::I64(_) => InfluxFieldType::Integer,
+            FieldValue::U64(_) => InfluxFieldType::UInteger,
+            FieldValue::F64(_) => InfluxFieldType::Float,
+            FieldValue::String(_) => InfluxFieldType::String,
+            FieldValue::Boolean(_) => InfluxFieldType::Boolean,
+        };
+        (key, InfluxColumnType::Field(field_type))
+    });
+
+    for (name, new) in tags.chain(fields) {
+        match column_types.get(name.as_str()) {
+            Some(&existing) if existing != new => {
+                return Err(LineWriteError::SchemaConflict {
+                    name: name.to_string(),
+                    existing,
+                    new,
+                })
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
 }
 
 /// Writes the [`ParsedLine`] to the [`MutableBatch`], respecting the edge case
@@ -367,6 +484,104 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_write_lp_partial() {
+        let lp = r#"cpu,tag1=v1 val=2i 0
+        cpu,tag1=v2 val="bananas" 1
+        cpu val=
+        mem,tag1=v2 ival=3i 0
+        cpu,tag1=v1,tag1=v2 val=4i 2
+        cpu,tag1=v3 val=5i 9223372036854775807
+        "#;
+
+        let mut converter = LinesConverter::new(5);
+        converter.set_timestamp_base(10);
+        let errors = converter.write_lp_partial(lp);
+
+        let lines: Vec<_> = errors.iter().map(|e| e.line().unwrap()).collect();
+        assert_eq!(lines, [2, 3, 5, 6]);
+        assert_matches!(
+            &errors[0],
+            Error::Write {
+                source: LineWriteError::MutableBatch { .. },
+                ..
+            }
+        );
+        assert_matches!(&errors[1], Error::LineProtocol { .. });
+        assert_matches!(
+            &errors[2],
+            Error::Write {
+                source: LineWriteError::DuplicateTag { .. },
+                ..
+            }
+        );
+        assert_matches!(&errors[3], Error::TimestampOverflow { .. });
+
+        let (batches, stats) = converter.finish().unwrap();
+        assert_eq!(stats.num_lines, 2);
+        assert_eq!(stats.num_fields, 2);
+
+        assert_batches_eq!(
+            &[
+                "+------+----------------------+-----+",
+                "| tag1 | time                 | val |",
+                "+------+----------------------+-----+",
+                "| v1   | 1970-01-01T00:00:00Z | 2   |",
+                "+------+----------------------+-----+",
+            ],
+            &[batches["cpu"].to_arrow(Selection::All).unwrap()]
+        );
+        assert_eq!(batches["mem"].rows(), 1);
+    }
+
+    #[test]
+    fn test_set_column_type() {
+        let lp = r#"cpu,host=a val=2i 0
+        cpu,host=b val=2.0 1
+        cpu val=3i,host="c" 2
+        mem val=2.0 3
+        "#;
+
+        let mut converter = LinesConverter::new(5);
+        converter.set_column_type(
+            "cpu",
+            "val",
+            InfluxColumnType::Field(InfluxFieldType::Integer),
+        );
+        converter.set_column_type("cpu", "host", InfluxColumnType::Tag);
+        let errors = converter.write_lp_partial(lp);
+
+        assert_eq!(errors.len(), 2);
+        assert_matches!(
+            &errors[0],
+            Error::Write {
+                line: 2,
+                source: LineWriteError::SchemaConflict {
+                    name,
+                    existing: InfluxColumnType::Field(InfluxFieldType::Integer),
+                    new: InfluxColumnType::Field(InfluxFieldType::Float),
+                },
+            } if name == "val"
+        );
+        assert_matches!(
+            &errors[1],
+            Error::Write {
+                line: 3,
+                source: LineWriteError::SchemaConflict { name, .. },
+            } if name == "host"
+        );
+
+        let (batches, _) = converter.finish().unwrap();
+        assert_eq!(batches["cpu"].rows(), 1);
+        assert_eq!(batches["mem"].rows(), 1);
+
+        // Without partial writes, the first conflict fails the write.
+        let mut converter = LinesConverter::new(5);
+        converter.set_column_type("cpu", "host", InfluxColumnType::Tag);
+        let err = converter.write_lp(lp).unwrap_err();
+        assert_matches!(err, Error::Write { line: 3, .. });
+    }
+
     #[test]
     fn test_nulls_string_and_float() {
         let lp = r#"m f0="cat" 1639612800000000000
@@ -0,0 +1,317 @@
+use data_types::{ColumnType, FieldCoercionPolicy, StatValues, Statistics};
+use mutable_batch::{
+    coerce::{Error, FieldCoercion},
+    column::ColumnData,
+    MutableBatch,
+};
+use mutable_batch_lp::lines_to_batches;
+use schema::{InfluxColumnType, InfluxFieldType};
+
+/// Parse `lp` into the batch of its single table "m".
+fn parse(lp: &str) -> MutableBatch {
+    lines_to_batches(lp, 0).unwrap().remove("m").unwrap()
+}
+
+fn column_type(batch: &MutableBatch, name: &str) -> InfluxColumnType {
+    batch.column(name).unwrap().influx_type()
+}
+
+fn f64_values(batch: &MutableBatch, name: &str) -> Vec<Option<f64>> {
+    let column = batch.column(name).unwrap();
+    match column.data() {
+        ColumnData::F64(data, _) => data
+            .iter()
+            .enumerate()
+            .map(|(idx, v)| column.valid_mask().get(idx).then_some(*v))
+            .collect(),
+        data => panic!("expected f64 column, got {}", data),
+    }
+}
+
+fn i64_values(batch: &MutableBatch, name: &str) -> Vec<Option<i64>> {
+    let column = batch.column(name).unwrap();
+    match column.data() {
+        ColumnData::I64(data, _) => data
+            .iter()
+            .enumerate()
+            .map(|(idx, v)| column.valid_mask().get(idx).then_some(*v))
+            .collect(),
+        data => panic!("expected i64 column, got {}", data),
+    }
+}
+
+fn string_values(batch: &MutableBatch, name: &str) -> Vec<Option<String>> {
+    let column = batch.column(name).unwrap();
+    match column.data() {
+        ColumnData::String(data, _) => data
+            .iter()
+            .enumerate()
+            .map(|(idx, v)| column.valid_mask().get(idx).then(|| v.to_string()))
+            .collect(),
+        data => panic!("expected string column, got {}", data),
+    }
+}
+
+#[test]
+fn test_same_type() {
+    let mut batch = parse("m v=1i 1");
+
+    let got = batch
+        .coerce_field("v", InfluxFieldType::Integer, FieldCoercionPolicy::Reject)
+        .unwrap();
+    assert_eq!(got, None);
+    assert_eq!(i64_values(&batch, "v"), vec![Some(1)]);
+}
+
+#[test]
+fn test_reject() {
+    let mut batch = parse("m v=1i 1");
+
+    let err = batch
+        .coerce_field("v", InfluxFieldType::Float, FieldCoercionPolicy::Reject)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::NotCoercible {
+            from: ColumnType::I64,
+            to: ColumnType::F64,
+            policy: FieldCoercionPolicy::Reject,
+            ..
+        }
+    ));
+    assert_eq!(i64_values(&batch, "v"), vec![Some(1)]);
+}
+
+#[test]
+fn test_widen_integer_to_float() {
+    let mut batch = parse("m v=1i 1\nm w=true 2\nm v=-3i 3");
+
+    let got = batch
+        .coerce_field("v", InfluxFieldType::Float, FieldCoercionPolicy::Widen)
+        .unwrap();
+    assert_eq!(got, Some(FieldCoercion::Widened));
+    assert_eq!(
+        column_type(&batch, "v"),
+        InfluxColumnType::Field(InfluxFieldType::Float)
+    );
+    assert_eq!(f64_values(&batch, "v"), vec![Some(1.0), None, Some(-3.0)]);
+    assert_eq!(
+        batch.column("v").unwrap().stats(),
+        Statistics::F64(StatValues::new(Some(-3.0), Some(1.0), 3, Some(1)))
+    );
+    assert_eq!(
+        batch
+            .to_arrow(schema::selection::Selection::All)
+            .unwrap()
+            .num_rows(),
+        3
+    );
+}
+
+#[test]
+fn test_widen_integer_to_float_lossy() {
+    // 2^53 + 1 is the first integer without an exact float representation.
+    let mut batch = parse("m v=1i 1\nm v=9007199254740993i 2");
+
+    let err = batch
+        .coerce_field("v", InfluxFieldType::Float, FieldCoercionPolicy::Widen)
+        .unwrap_err();
+    assert!(matches!(err, Error::NotCoercible { .. }));
+    assert_eq!(
+        i64_values(&batch, "v"),
+        vec![Some(1), Some(9007199254740993)]
+    );
+
+    // Large integers that are exactly representable are widened.
+    let mut batch = parse("m v=9007199254740992i 1\nm v=-9223372036854775808i 2");
+    let got = batch
+        .coerce_field("v", InfluxFieldType::Float, FieldCoercionPolicy::Widen)
+        .unwrap();
+    assert_eq!(got, Some(FieldCoercion::Widened));
+    assert_eq!(
+        f64_values(&batch, "v"),
+        vec![Some(9007199254740992.0), Some(-9223372036854775808.0)]
+    );
+
+    // i64::MAX rounds up to 2^63 as a float.
+    let mut batch = parse("m v=9223372036854775807i 1");
+    let err = batch
+        .coerce_field("v", InfluxFieldType::Float, FieldCoercionPolicy::Widen)
+        .unwrap_err();
+    assert!(matches!(err, Error::NotCoercible { .. }));
+}
+
+#[test]
+fn test_widen_unsigned_to_integer() {
+    let mut batch = parse("m v=5u 1\nm v=9223372036854775807u 2");
+
+    let got = batch
+        .coerce_field("v", InfluxFieldType::Integer, FieldCoercionPolicy::Widen)
+        .unwrap();
+    assert_eq!(got, Some(FieldCoercion::Widened));
+    assert_eq!(
+        column_type(&batch, "v"),
+        InfluxColumnType::Field(InfluxFieldType::Integer)
+    );
+    assert_eq!(i64_values(&batch, "v"), vec![Some(5), Some(i64::MAX)]);
+
+    let mut batch = parse("m v=5u 1\nm v=9223372036854775808u 2");
+    let err = batch
+        .coerce_field(
+            "v",
+            InfluxFieldType::Integer,
+            FieldCoercionPolicy::Stringify,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::NotCoercible {
+            from: ColumnType::U64,
+            to: ColumnType::I64,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_never_narrows() {
+    for (lp, to) in [
+        ("m v=1 1", InfluxFieldType::Integer),
+        ("m v=1i 1", InfluxFieldType::UInteger),
+        ("m v=1u 1", InfluxFieldType::Float),
+        ("m v=true 1", InfluxFieldType::Integer),
+        ("m v=\"1\" 1", InfluxFieldType::Float),
+    ] {
+        let mut batch = parse(lp);
+        let err = batch
+            .coerce_field("v", to, FieldCoercionPolicy::Stringify)
+            .unwrap_err();
+        assert!(matches!(err, Error::NotCoercible { .. }), "{}", lp);
+    }
+}
+
+#[test]
+fn test_stringify() {
+    let mut batch = parse("m f=1.5,i=-2i,u=3u,b=true 1\nm s=\"x\" 2");
+
+    for name in ["f", "i", "u", "b"] {
+        let err = batch
+            .coerce_field(name, InfluxFieldType::String, FieldCoercionPolicy::Widen)
+            .unwrap_err();
+        assert!(matches!(err, Error::NotCoercible { .. }));
+
+        let got = batch
+            .coerce_field(
+                name,
+                InfluxFieldType::String,
+                FieldCoercionPolicy::Stringify,
+            )
+            .unwrap();
+        assert_eq!(got, Some(FieldCoercion::Stringified));
+        assert_eq!(
+            column_type(&batch, name),
+            InfluxColumnType::Field(InfluxFieldType::String)
+        );
+    }
+
+    assert_eq!(
+        string_values(&batch, "f"),
+        vec![Some("1.5".to_string()), None]
+    );
+    assert_eq!(
+        string_values(&batch, "i"),
+        vec![Some("-2".to_string()), None]
+    );
+    assert_eq!(
+        string_values(&batch, "u"),
+        vec![Some("3".to_string()), None]
+    );
+    assert_eq!(
+        string_values(&batch, "b"),
+        vec![Some("true".to_string()), None]
+    );
+    assert_eq!(
+        batch.column("i").unwrap().stats(),
+        Statistics::String(StatValues::new(
+            Some("-2".to_string()),
+            Some("-2".to_string()),
+            2,
+            Some(1)
+        ))
+    );
+}
+
+#[test]
+fn test_widen_before_stringify() {
+    let mut batch = parse("m v=1i 1");
+
+    let got = batch
+        .coerce_field("v", InfluxFieldType::Float, FieldCoercionPolicy::Drop)
+        .unwrap();
+    assert_eq!(got, Some(FieldCoercion::Widened));
+    assert_eq!(f64_values(&batch, "v"), vec![Some(1.0)]);
+}
+
+#[test]
+fn test_drop() {
+    let mut batch = parse("m,t=a v=1.5,w=1i 1\nm,t=b v=2.5 2\nm,t=c w=2i 3");
+
+    let got = batch
+        .coerce_field("v", InfluxFieldType::Boolean, FieldCoercionPolicy::Drop)
+        .unwrap();
+    assert_eq!(got, Some(FieldCoercion::Dropped));
+
+    assert!(batch.column("v").is_err());
+    assert_eq!(batch.rows(), 3);
+    assert_eq!(i64_values(&batch, "w"), vec![Some(1), None, Some(2)]);
+
+    let mut names = batch
+        .columns()
+        .map(|(name, column)| {
+            assert_eq!(column.len(), 3);
+            name.as_str()
+        })
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    assert_eq!(names, vec!["t", "time", "w"]);
+
+    let record_batch = batch.to_arrow(schema::selection::Selection::All).unwrap();
+    assert_eq!(record_batch.num_columns(), 3);
+    assert_eq!(record_batch.num_rows(), 3);
+}
+
+#[test]
+fn test_not_a_field() {
+    let mut batch = parse("m,t=a v=1i 1");
+
+    let err = batch
+        .coerce_field("t", InfluxFieldType::String, FieldCoercionPolicy::Drop)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::NotAField {
+            column_type: ColumnType::Tag,
+            ..
+        }
+    ));
+
+    let err = batch
+        .coerce_field("time", InfluxFieldType::Integer, FieldCoercionPolicy::Drop)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::NotAField {
+            column_type: ColumnType::Time,
+            ..
+        }
+    ));
+
+    let err = batch
+        .coerce_field(
+            "missing",
+            InfluxFieldType::Integer,
+            FieldCoercionPolicy::Drop,
+        )
+        .unwrap_err();
+    assert!(matches!(err, Error::ColumnNotFound { .. }));
+}
@@ -7,6 +7,8 @@ use crate::{
     ParquetFilePath,
 };
 use arrow::{
+    array::new_null_array,
+    compute::{can_cast_types, cast},
     datatypes::{Field, SchemaRef},
     error::ArrowError,
     record_batch::RecordBatch,
@@ -24,8 +26,15 @@ use datafusion::{
     prelude::SessionContext,
 };
 use futures::{Stream, TryStreamExt};
-use object_store::{DynObjectStore, ObjectMeta};
+use object_store::{path::Path, DynObjectStore, ObjectMeta};
 use observability_deps::tracing::*;
+use parquet::{
+    arrow::{
+        arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder, RowSelection},
+        ProjectionMask,
+    },
+    file::reader::SerializedFileReader,
+};
 use predicate::Predicate;
 use schema::selection::{select_schema, Selection};
 use std::{
@@ -35,6 +44,10 @@ use std::{
 };
 use thiserror::Error;
 
+use self::dictionary::{ScanSelection, TagPredicate};
+
+mod dictionary;
+
 /// Parquet row group read size
 pub const ROW_GROUP_READ_SIZE: usize = 1024 * 1024;
 
@@ -78,6 +91,10 @@ pub enum ReadError {
     #[error("invalid parquet file: {0}")]
     Parquet(#[from] parquet::errors::ParquetError),
 
+    /// An error decoding the downloaded Parquet file to Arrow.
+    #[error("failed to decode parquet data: {0}")]
+    Arrow(#[from] ArrowError),
+
     /// Schema mismatch
     #[error("Schema mismatch (expected VS actual parquet file) for file '{path}': {source}")]
     SchemaMismatch {
@@ -192,6 +209,11 @@ impl ParquetStorage {
     /// No caching is performed by `read_filter()`, and each call to
     /// `read_filter()` will re-download the parquet file unless the underlying
     /// object store impl caches the fetched bytes.
+    ///
+    /// If `predicate` contains `=`, `!=`, `IN` or regex comparisons on tag
+    /// columns, these are first evaluated against the dictionary pages of the
+    /// file, and only the rows that may match are decoded. The returned rows
+    /// may still include rows that do not match `predicate`.
     pub fn read_filter(
         &self,
         predicate: &Predicate,
@@ -203,6 +225,8 @@ impl ParquetStorage {
         let path = path.object_store_path();
         trace!(path=?path, "fetching parquet data for filtered read");
 
+        let tag_predicates = TagPredicate::from_predicate(predicate, &schema);
+
         // Compute final (output) schema after selection
         let schema = Arc::new(
             select_schema(selection, &schema)
@@ -211,6 +235,20 @@ impl ParquetStorage {
                 .with_metadata(Default::default()),
         );
 
+        if !tag_predicates.is_empty() {
+            let object_store = Arc::clone(&self.object_store);
+            let output_schema = Arc::clone(&schema);
+            return Ok(Box::pin(RecordBatchStreamAdapter::new(
+                schema,
+                futures::stream::once(async move {
+                    read_dictionary_filtered(object_store, path, tag_predicates, output_schema)
+                        .await
+                        .map_err(|e| ArrowError::ExternalError(Box::new(e)))
+                })
+                .try_flatten(),
+            )));
+        }
+
         // create ParquetExec node
         let object_meta = ObjectMeta {
             location: path,
@@ -266,6 +304,109 @@ impl ParquetStorage {
     }
 }
 
+/// Reads the rows of the parquet file at `path` that may match `predicates`,
+/// projected to `schema`.
+///
+/// The predicates are evaluated against the dictionary pages of the tag
+/// columns, skipping the row groups and rows that cannot match without
+/// decoding them. The selected rows are decoded as the returned stream is
+/// polled.
+async fn read_dictionary_filtered(
+    object_store: Arc<DynObjectStore>,
+    path: Path,
+    predicates: Vec<TagPredicate>,
+    schema: SchemaRef,
+) -> Result<impl Stream<Item = Result<RecordBatch, ArrowError>> + Send, ReadError> {
+    let data = object_store.get(&path).await?.bytes().await?;
+
+    let (selection, reader) = dictionary_filtered_reader(data, &predicates, &schema)?;
+    debug!(
+        ?path,
+        total_rows = selection.total_rows,
+        selected_rows = selection.selected_rows,
+        row_groups = selection.row_groups.len(),
+        "evaluated tag predicates against parquet dictionaries"
+    );
+
+    Ok(futures::stream::iter(reader.into_iter().flatten().map(
+        move |batch| {
+            project_batch(batch?, &schema, &path)
+                .map_err(|e| ArrowError::ExternalError(Box::new(e)))
+        },
+    )))
+}
+
+/// Evaluates `predicates` against the dictionary pages of the parquet file
+/// in `data`, returning the rows selected for decoding and a reader of those
+/// rows, projected to the columns of `schema` present in the file.
+///
+/// No reader is returned if no rows may match.
+fn dictionary_filtered_reader(
+    data: Bytes,
+    predicates: &[TagPredicate],
+    schema: &SchemaRef,
+) -> Result<(ScanSelection, Option<ParquetRecordBatchReader>), ReadError> {
+    let file = SerializedFileReader::new(data.clone())?;
+    let selection = dictionary::select_rows(&file, predicates)?;
+    if selection.row_groups.is_empty() {
+        return Ok((selection, None));
+    }
+
+    let builder = ParquetRecordBatchReaderBuilder::try_new(data)?;
+    let projection = builder
+        .schema()
+        .fields()
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| schema.field_with_name(field.name()).is_ok())
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+    let projection = ProjectionMask::roots(builder.parquet_schema(), projection);
+
+    let reader = builder
+        .with_projection(projection)
+        .with_row_groups(selection.row_groups.clone())
+        .with_row_selection(RowSelection::from(selection.selectors.clone()))
+        .with_batch_size(ROW_GROUP_READ_SIZE)
+        .build()?;
+
+    Ok((selection, Some(reader)))
+}
+
+/// Projects a `batch` read from the parquet file at `path` to `schema`,
+/// adding NULL columns for any columns not present in the file.
+fn project_batch(
+    batch: RecordBatch,
+    schema: &SchemaRef,
+    path: &Path,
+) -> Result<RecordBatch, ReadError> {
+    let batch_schema = batch.schema();
+    let columns = schema
+        .fields()
+        .iter()
+        .map(
+            |expected| match batch_schema.column_with_name(expected.name()) {
+                Some((idx, actual)) if actual.data_type() == expected.data_type() => {
+                    Ok(Arc::clone(batch.column(idx)))
+                }
+                Some((idx, actual)) if can_cast_types(actual.data_type(), expected.data_type()) => {
+                    Ok(cast(batch.column(idx), expected.data_type())?)
+                }
+                Some((_, actual)) => Err(ReadError::SchemaMismatch {
+                    path: path.clone(),
+                    source: ProjectionError::FieldTypeMismatch {
+                        expected: expected.clone(),
+                        actual: actual.clone(),
+                    },
+                }),
+                None => Ok(new_null_array(expected.data_type(), batch.num_rows())),
+            },
+        )
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(RecordBatch::try_new(Arc::clone(schema), columns)?)
+}
+
 /// Error during projecting parquet file data to an expected schema.
 #[derive(Debug, Error)]
 #[allow(clippy::large_enum_variant)]
@@ -288,10 +429,17 @@ pub enum ProjectionError {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use arrow::array::{ArrayRef, Int64Array, StringArray};
+    use arrow::{
+        array::{ArrayRef, DictionaryArray, Int64Array, StringArray},
+        datatypes::Int32Type,
+    };
     use data_types::{CompactionLevel, NamespaceId, PartitionId, SequenceNumber, ShardId, TableId};
-    use datafusion::common::DataFusionError;
+    use datafusion::{
+        common::DataFusionError,
+        prelude::{col, lit},
+    };
     use iox_time::Time;
+    use query_functions::{regex_match_expr, regex_not_match_expr};
     use std::collections::HashMap;
 
     #[tokio::test]
@@ -524,6 +672,144 @@ mod tests {
         assert_roundtrip(file_batch, Selection::Some(&["a"]), schema, expected_batch).await;
     }
 
+    #[tokio::test]
+    async fn test_dictionary_predicates() {
+        let object_store: Arc<DynObjectStore> = Arc::new(object_store::memory::InMemory::default());
+
+        let store = ParquetStorage::new(object_store);
+
+        let meta = meta();
+        let batch = RecordBatch::try_from_iter([
+            (
+                "tag",
+                to_dictionary_array(&[Some("a"), Some("b"), None, Some("c"), Some("b")]),
+            ),
+            ("v", to_int_array(&[1, 2, 3, 4, 5])),
+        ])
+        .unwrap();
+        let schema = batch.schema();
+
+        let (_iox_md, file_size) = upload(&store, &meta, batch).await;
+
+        let cases = [
+            (col("tag").eq(lit("b")), vec![2, 5]),
+            (col("tag").not_eq(lit("b")), vec![1, 4]),
+            (
+                col("tag").in_list(vec![lit("a"), lit("c")], false),
+                vec![1, 4],
+            ),
+            (
+                col("tag").in_list(vec![lit("a"), lit("c")], true),
+                vec![2, 5],
+            ),
+            (regex_match_expr(col("tag"), "^[ab]$".into()), vec![1, 2, 5]),
+            (regex_not_match_expr(col("tag"), "a".into()), vec![2, 4, 5]),
+            (col("tag").eq(lit("d")), vec![]),
+        ];
+
+        for (expr, expected) in cases {
+            let predicate = Predicate::new().with_expr(expr.clone());
+            let path: ParquetFilePath = (&meta).into();
+            let rx = store
+                .read_filter(
+                    &predicate,
+                    Selection::All,
+                    Arc::clone(&schema),
+                    &path,
+                    file_size,
+                )
+                .unwrap();
+            let batches = datafusion::physical_plan::common::collect(rx)
+                .await
+                .unwrap();
+
+            // only the matching rows are decoded
+            let actual: Vec<_> = batches
+                .iter()
+                .flat_map(|batch| {
+                    assert_eq!(batch.schema(), schema);
+                    batch
+                        .column(1)
+                        .as_any()
+                        .downcast_ref::<Int64Array>()
+                        .unwrap()
+                        .values()
+                        .to_vec()
+                })
+                .collect();
+            assert_eq!(actual, expected, "{}", expr);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dictionary_predicates_decoded_rows() {
+        let object_store: Arc<DynObjectStore> = Arc::new(object_store::memory::InMemory::default());
+
+        let store = ParquetStorage::new(Arc::clone(&object_store));
+
+        let meta = meta();
+        let batch = RecordBatch::try_from_iter([
+            (
+                "tag",
+                to_dictionary_array(&[
+                    Some("a"),
+                    Some("b"),
+                    Some("a"),
+                    Some("c"),
+                    Some("b"),
+                    Some("a"),
+                    None,
+                    Some("c"),
+                    Some("a"),
+                    Some("b"),
+                ]),
+            ),
+            ("v", to_int_array(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10])),
+        ])
+        .unwrap();
+        let schema = batch.schema();
+
+        upload(&store, &meta, batch).await;
+        let path = ParquetFilePath::from(&meta).object_store_path();
+        let data = object_store
+            .get(&path)
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+
+        let cases = [
+            (col("tag").eq(lit("a")), 4),
+            (col("tag").not_eq(lit("a")), 5),
+            (regex_match_expr(col("tag"), "^[bc]$".into()), 5),
+            (col("tag").eq(lit("d")), 0),
+        ];
+
+        for (expr, expected) in cases {
+            let predicate = Predicate::new().with_expr(expr.clone());
+            let predicates = TagPredicate::from_predicate(&predicate, &schema);
+
+            let (selection, reader) =
+                dictionary_filtered_reader(data.clone(), &predicates, &schema).unwrap();
+            let returned_rows: usize = reader
+                .into_iter()
+                .flatten()
+                .map(|batch| batch.unwrap().num_rows())
+                .sum();
+
+            // only the matching rows are decoded, and all of them returned
+            assert_eq!(selection.total_rows, 10, "{}", expr);
+            assert_eq!(selection.selected_rows, expected, "{}", expr);
+            assert_eq!(returned_rows, selection.selected_rows, "{}", expr);
+        }
+    }
+
+    fn to_dictionary_array(strs: &[Option<&str>]) -> ArrayRef {
+        let array: DictionaryArray<Int32Type> = strs.iter().copied().collect();
+        Arc::new(array)
+    }
+
     fn to_string_array(strs: &[&str]) -> ArrayRef {
         let array: StringArray = strs.iter().map(|s| Some(*s)).collect();
         Arc::new(array)
@@ -0,0 +1,546 @@
+//! Evaluation of tag predicates against the dictionary pages of a parquet
+//! file, without decoding the tag values of every row.
+//!
+//! Tag columns are written as dictionary-encoded `BYTE_ARRAY` columns, so
+//! each column chunk contains a dictionary page holding the distinct tag
+//! values, followed by data pages holding (RLE / bit-packed) indices into
+//! that dictionary. A predicate such as `tag = 'foo'` is evaluated once per
+//! distinct value, and the resulting per-value outcome is then applied to the
+//! indices of each data page to determine the rows that may match.
+//!
+//! The row selection produced here is a superset of the matching rows; the
+//! predicate is still applied to the decoded rows after the scan.
+
+use arrow::{
+    array::{Array, ArrayRef, BooleanArray, StringArray},
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    record_batch::RecordBatch,
+};
+use bytes::Bytes;
+use datafusion::{
+    common::DFSchema,
+    logical_expr::{utils::expr_to_columns, BinaryExpr, Operator},
+    physical_expr::{create_physical_expr, execution_props::ExecutionProps},
+    physical_plan::{ColumnarValue, PhysicalExpr},
+    prelude::Expr,
+    scalar::ScalarValue,
+};
+use observability_deps::tracing::debug;
+use parquet::{
+    arrow::arrow_reader::RowSelector,
+    basic::{Encoding, Type as PhysicalType},
+    column::page::{Page, PageReader},
+    errors::ParquetError,
+    file::reader::{FileReader, SerializedFileReader},
+    schema::types::ColumnDescriptor,
+};
+use predicate::Predicate;
+use query_functions::{REGEX_MATCH_UDF_NAME, REGEX_NOT_MATCH_UDF_NAME};
+use std::{collections::HashSet, sync::Arc};
+
+/// A predicate on a single tag column that can be evaluated against the
+/// distinct values held in a dictionary page.
+#[derive(Debug)]
+pub(super) struct TagPredicate {
+    /// The tag column this predicate applies to.
+    column: String,
+
+    /// The predicate, evaluated against a `Utf8` column named `column`.
+    expr: Arc<dyn PhysicalExpr>,
+
+    /// True if a NULL tag value may satisfy the predicate.
+    null_matches: bool,
+}
+
+impl TagPredicate {
+    /// Extracts the expressions in `predicate` that compare a single tag
+    /// column in `schema` using `=`, `!=`, `IN` or a regex.
+    ///
+    /// Expressions that are unsupported are ignored, as `predicate` is a
+    /// conjunction and ignoring a term only widens the row selection.
+    pub(super) fn from_predicate(predicate: &Predicate, schema: &SchemaRef) -> Vec<Self> {
+        predicate
+            .exprs
+            .iter()
+            .filter_map(|expr| Self::try_new(expr, schema))
+            .collect()
+    }
+
+    fn try_new(expr: &Expr, schema: &SchemaRef) -> Option<Self> {
+        if !is_supported_expr(expr) {
+            return None;
+        }
+
+        let mut columns = HashSet::new();
+        expr_to_columns(expr, &mut columns).ok()?;
+        if columns.len() != 1 {
+            return None;
+        }
+        let column = columns.into_iter().next()?.name;
+
+        match schema.field_with_name(&column).ok()?.data_type() {
+            DataType::Dictionary(_, value_type) if value_type.as_ref() == &DataType::Utf8 => {}
+            _ => return None,
+        }
+
+        let input_schema = Arc::new(Schema::new(vec![Field::new(&column, DataType::Utf8, true)]));
+        let input_df_schema = DFSchema::try_from(input_schema.as_ref().clone()).ok()?;
+        let expr = create_physical_expr(
+            expr,
+            &input_df_schema,
+            &input_schema,
+            &ExecutionProps::default(),
+        )
+        .ok()?;
+
+        let mut predicate = Self {
+            column,
+            expr,
+            null_matches: true,
+        };
+        let null: StringArray = vec![None::<&str>].into_iter().collect();
+        predicate.null_matches = predicate.evaluate(Arc::new(null))?[0];
+
+        Some(predicate)
+    }
+
+    /// Evaluates this predicate against `values`, returning true for each
+    /// value that may satisfy it.
+    fn evaluate(&self, values: ArrayRef) -> Option<Vec<bool>> {
+        let num_rows = values.len();
+        let batch = RecordBatch::try_from_iter([(self.column.as_str(), values)]).ok()?;
+
+        let matches = match self.expr.evaluate(&batch) {
+            Ok(ColumnarValue::Array(array)) => array,
+            Ok(ColumnarValue::Scalar(scalar)) => scalar.to_array_of_size(num_rows),
+            Err(e) => {
+                debug!(%e, expr=?self.expr, "failed to evaluate dictionary predicate");
+                return None;
+            }
+        };
+        let matches = matches.as_any().downcast_ref::<BooleanArray>()?;
+
+        Some(
+            matches
+                .iter()
+                .map(|matches| matches.unwrap_or(false))
+                .collect(),
+        )
+    }
+}
+
+/// Returns true if `expr` is a `=`, `!=`, `IN` or regex comparison between a
+/// column and literal(s).
+fn is_supported_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+            matches!(
+                op,
+                Operator::Eq | Operator::NotEq | Operator::RegexMatch | Operator::RegexNotMatch
+            ) && matches!(
+                (left.as_ref(), right.as_ref()),
+                (Expr::Column(_), Expr::Literal(ScalarValue::Utf8(_)))
+                    | (Expr::Literal(ScalarValue::Utf8(_)), Expr::Column(_))
+            )
+        }
+        Expr::InList { expr, list, .. } => {
+            matches!(expr.as_ref(), Expr::Column(_))
+                && list
+                    .iter()
+                    .all(|e| matches!(e, Expr::Literal(ScalarValue::Utf8(_))))
+        }
+        Expr::ScalarUDF { fun, args } => {
+            (fun.name == REGEX_MATCH_UDF_NAME || fun.name == REGEX_NOT_MATCH_UDF_NAME)
+                && matches!(
+                    args.as_slice(),
+                    [Expr::Column(_), Expr::Literal(ScalarValue::Utf8(Some(_)))]
+                )
+        }
+        _ => false,
+    }
+}
+
+/// The rows of a parquet file to decode, as determined by a set of
+/// [`TagPredicate`]s.
+#[derive(Debug, Default)]
+pub(super) struct ScanSelection {
+    /// The row groups that contain at least one row that may match.
+    pub(super) row_groups: Vec<usize>,
+
+    /// The rows to decode within `row_groups`.
+    pub(super) selectors: Vec<RowSelector>,
+
+    /// The total number of rows in the file.
+    pub(super) total_rows: usize,
+
+    /// The number of rows selected for decoding.
+    pub(super) selected_rows: usize,
+}
+
+/// Evaluates `predicates` against the dictionary pages of the tag columns in
+/// `file`, returning the rows that may match all of them.
+pub(super) fn select_rows(
+    file: &SerializedFileReader<Bytes>,
+    predicates: &[TagPredicate],
+) -> Result<ScanSelection, ParquetError> {
+    let metadata = file.metadata();
+    let schema_descr = metadata.file_metadata().schema_descr();
+
+    let mut selection = ScanSelection::default();
+    for row_group_idx in 0..metadata.num_row_groups() {
+        let num_rows = metadata.row_group(row_group_idx).num_rows() as usize;
+        selection.total_rows += num_rows;
+
+        let mut mask = vec![true; num_rows];
+        for predicate in predicates {
+            let column_idx = schema_descr
+                .columns()
+                .iter()
+                .position(|c| c.path().parts() == [predicate.column.as_str()]);
+
+            let column_mask = match column_idx {
+                // the column is NULL for every row in this file
+                None => vec![predicate.null_matches; num_rows],
+                Some(column_idx) => {
+                    let mut page_reader = file
+                        .get_row_group(row_group_idx)?
+                        .get_column_page_reader(column_idx)?;
+                    column_mask(
+                        page_reader.as_mut(),
+                        schema_descr.column(column_idx).as_ref(),
+                        predicate,
+                        num_rows,
+                    )?
+                }
+            };
+
+            for (row, column_row) in mask.iter_mut().zip(column_mask) {
+                *row &= column_row;
+            }
+        }
+
+        let selected_rows = mask.iter().filter(|row| **row).count();
+        if selected_rows == 0 {
+            continue;
+        }
+
+        selection.row_groups.push(row_group_idx);
+        selection.selected_rows += selected_rows;
+        append_selectors(&mut selection.selectors, &mask);
+    }
+
+    Ok(selection)
+}
+
+/// Evaluates `predicate` against a single column chunk, returning true for
+/// each of the `num_rows` rows that may match.
+///
+/// Pages that are not dictionary-encoded, or cannot be interpreted, are
+/// treated as matching.
+fn column_mask(
+    page_reader: &mut dyn PageReader,
+    column: &ColumnDescriptor,
+    predicate: &TagPredicate,
+    num_rows: usize,
+) -> Result<Vec<bool>, ParquetError> {
+    if column.physical_type() != PhysicalType::BYTE_ARRAY || column.max_rep_level() != 0 {
+        return Ok(vec![true; num_rows]);
+    }
+    let max_def_level = column.max_def_level();
+
+    let mut mask = Vec::with_capacity(num_rows);
+    let mut dictionary_matches: Option<Vec<bool>> = None;
+    while let Some(page) = page_reader.get_next_page()? {
+        match page {
+            Page::DictionaryPage {
+                buf, num_values, ..
+            } => {
+                dictionary_matches = decode_dictionary(buf.as_ref(), num_values as usize)
+                    .and_then(|values| predicate.evaluate(Arc::new(values)));
+            }
+            Page::DataPage {
+                buf,
+                num_values,
+                encoding,
+                def_level_encoding,
+                ..
+            } => {
+                let num_values = num_values as usize;
+                let page_mask = split_v1_levels(buf.as_ref(), max_def_level, def_level_encoding)
+                    .and_then(|(def_levels, values)| {
+                        page_mask(
+                            dictionary_matches.as_deref()?,
+                            predicate.null_matches,
+                            encoding,
+                            max_def_level,
+                            def_levels,
+                            values,
+                            num_values,
+                        )
+                    });
+                mask.extend(page_mask.unwrap_or_else(|| vec![true; num_values]));
+            }
+            Page::DataPageV2 {
+                buf,
+                num_values,
+                encoding,
+                def_levels_byte_len,
+                rep_levels_byte_len,
+                ..
+            } => {
+                let num_values = num_values as usize;
+                let def_start = rep_levels_byte_len as usize;
+                let values_start = def_start + def_levels_byte_len as usize;
+                let data = buf.as_ref();
+                let page_mask = data
+                    .get(def_start..values_start)
+                    .zip(data.get(values_start..))
+                    .and_then(|(def_levels, values)| {
+                        page_mask(
+                            dictionary_matches.as_deref()?,
+                            predicate.null_matches,
+                            encoding,
+                            max_def_level,
+                            def_levels,
+                            values,
+                            num_values,
+                        )
+                    });
+                mask.extend(page_mask.unwrap_or_else(|| vec![true; num_values]));
+            }
+        }
+    }
+
+    if mask.len() != num_rows {
+        debug!(
+            column=%predicate.column,
+            expected=num_rows,
+            actual=mask.len(),
+            "unexpected number of values in column chunk"
+        );
+        return Ok(vec![true; num_rows]);
+    }
+
+    Ok(mask)
+}
+
+/// Splits the contents of a v1 data page into its definition levels and
+/// values. Repetition levels are not expected, as tags are not nested.
+fn split_v1_levels(
+    data: &[u8],
+    max_def_level: i16,
+    def_level_encoding: Encoding,
+) -> Option<(&[u8], &[u8])> {
+    if max_def_level == 0 {
+        return Some((&data[..0], data));
+    }
+    if def_level_encoding != Encoding::RLE {
+        return None;
+    }
+
+    // RLE encoded levels are prefixed with their length in v1 pages
+    let len = u32::from_le_bytes(data.get(..4)?.try_into().ok()?) as usize;
+    Some((data.get(4..4 + len)?, data.get(4 + len..)?))
+}
+
+/// Computes the mask for a single data page from its definition levels and
+/// dictionary-encoded values.
+fn page_mask(
+    dictionary_matches: &[bool],
+    null_matches: bool,
+    encoding: Encoding,
+    max_def_level: i16,
+    def_levels: &[u8],
+    values: &[u8],
+    num_values: usize,
+) -> Option<Vec<bool>> {
+    if !matches!(
+        encoding,
+        Encoding::PLAIN_DICTIONARY | Encoding::RLE_DICTIONARY
+    ) {
+        return None;
+    }
+
+    let def_levels = if max_def_level > 0 {
+        let bit_width = (16 - max_def_level.leading_zeros()) as u8;
+        Some(decode_rle_hybrid(def_levels, bit_width, num_values)?)
+    } else {
+        None
+    };
+    let num_non_null = match &def_levels {
+        Some(def_levels) => def_levels
+            .iter()
+            .filter(|level| **level == max_def_level as u32)
+            .count(),
+        None => num_values,
+    };
+
+    let (bit_width, values) = values.split_first()?;
+    let mut indices = decode_rle_hybrid(values, *bit_width, num_non_null)?.into_iter();
+
+    (0..num_values)
+        .map(|i| match &def_levels {
+            Some(def_levels) if def_levels[i] != max_def_level as u32 => Some(null_matches),
+            _ => {
+                let index = indices.next()? as usize;
+                Some(dictionary_matches.get(index).copied().unwrap_or(true))
+            }
+        })
+        .collect()
+}
+
+/// Decodes the `PLAIN` encoded `BYTE_ARRAY` values of a dictionary page.
+fn decode_dictionary(data: &[u8], num_values: usize) -> Option<StringArray> {
+    let mut values = Vec::with_capacity(num_values);
+    let mut pos = 0;
+    for _ in 0..num_values {
+        let len = u32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?) as usize;
+        pos += 4;
+        values.push(Some(std::str::from_utf8(data.get(pos..pos + len)?).ok()?));
+        pos += len;
+    }
+
+    Some(values.into_iter().collect())
+}
+
+/// Decodes `num_values` values of `bit_width` bits from the parquet
+/// RLE / bit-packing hybrid encoding.
+///
+/// Returns `None` if `data` is malformed.
+fn decode_rle_hybrid(data: &[u8], bit_width: u8, num_values: usize) -> Option<Vec<u32>> {
+    if bit_width > 32 {
+        return None;
+    }
+    let bit_width = bit_width as usize;
+
+    let mut out = Vec::with_capacity(num_values);
+    let mut pos = 0;
+    while out.len() < num_values {
+        let header = read_uleb128(data, &mut pos)?;
+        let remaining = num_values - out.len();
+
+        if header & 1 == 0 {
+            // RLE run: a single value, repeated
+            let run_len = (header >> 1) as usize;
+            let value_width = (bit_width + 7) / 8;
+            let value = data
+                .get(pos..pos + value_width)?
+                .iter()
+                .enumerate()
+                .fold(0u32, |value, (i, b)| value | (*b as u32) << (8 * i));
+            pos += value_width;
+
+            if run_len == 0 {
+                return None;
+            }
+            out.extend(std::iter::repeat(value).take(run_len.min(remaining)));
+        } else {
+            // bit-packed run: groups of 8 values
+            let num_groups = (header >> 1) as usize;
+            if num_groups == 0 {
+                return None;
+            }
+            let end = data.len().min(pos + num_groups * bit_width);
+            let packed = &data[pos..end];
+            pos = end;
+
+            let mask = if bit_width == 32 {
+                u32::MAX as u64
+            } else {
+                (1u64 << bit_width) - 1
+            };
+            for i in 0..(num_groups * 8).min(remaining) {
+                let bit = i * bit_width;
+                let mut word = [0u8; 8];
+                let bytes = packed.get(bit / 8..).unwrap_or_default();
+                let len = bytes.len().min(8);
+                word[..len].copy_from_slice(&bytes[..len]);
+                out.push(((u64::from_le_bytes(word) >> (bit % 8)) & mask) as u32);
+            }
+        }
+    }
+
+    Some(out)
+}
+
+/// Reads an unsigned LEB128 encoded integer from `data` at `pos`.
+fn read_uleb128(data: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*pos)?;
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Appends the runs of selected / skipped rows in `mask` to `selectors`.
+fn append_selectors(selectors: &mut Vec<RowSelector>, mask: &[bool]) {
+    let mut start = 0;
+    while start < mask.len() {
+        let selected = mask[start];
+        let len = mask[start..]
+            .iter()
+            .position(|row| *row != selected)
+            .unwrap_or(mask.len() - start);
+
+        match selectors.last_mut() {
+            Some(last) if last.skip == !selected => last.row_count += len,
+            _ if selected => selectors.push(RowSelector::select(len)),
+            _ => selectors.push(RowSelector::skip(len)),
+        }
+        start += len;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_rle_hybrid() {
+        // RLE run of 5 x 3, with a bit width of 2
+        let data = [5 << 1, 3];
+        assert_eq!(decode_rle_hybrid(&data, 2, 5).unwrap(), vec![3; 5]);
+
+        // bit-packed group of 8 values 0..8, with a bit width of 3
+        let data = [(1 << 1) | 1, 0b10001000, 0b11000110, 0b11111010];
+        assert_eq!(
+            decode_rle_hybrid(&data, 3, 8).unwrap(),
+            vec![0, 1, 2, 3, 4, 5, 6, 7]
+        );
+
+        // trailing padding in the bit-packed group is ignored
+        assert_eq!(decode_rle_hybrid(&data, 3, 3).unwrap(), vec![0, 1, 2]);
+
+        // RLE run followed by a bit-packed group
+        let data = [2 << 1, 1, (1 << 1) | 1, 0b01010101];
+        assert_eq!(
+            decode_rle_hybrid(&data, 1, 6).unwrap(),
+            vec![1, 1, 1, 0, 1, 0]
+        );
+
+        // truncated
+        assert!(decode_rle_hybrid(&[5 << 1], 2, 5).is_none());
+    }
+
+    #[test]
+    fn test_append_selectors() {
+        let mut selectors = vec![];
+        append_selectors(&mut selectors, &[true, true, false, true]);
+        append_selectors(&mut selectors, &[true, false, false]);
+
+        assert_eq!(
+            selectors,
+            vec![
+                RowSelector::select(2),
+                RowSelector::skip(1),
+                RowSelector::select(2),
+                RowSelector::skip(2),
+            ]
+        );
+    }
+}
@@ -1,19 +1,71 @@
 use data_types::{DeleteExpr, Op, Scalar};
+use query_functions::{REGEX_MATCH_UDF_NAME, REGEX_NOT_MATCH_UDF_NAME};
 use snafu::{ResultExt, Snafu};
 use std::ops::Deref;
 
 pub(crate) fn expr_to_df(expr: DeleteExpr) -> datafusion::logical_plan::Expr {
     use datafusion::logical_plan::Expr;
 
-    let column = datafusion::logical_plan::Column {
-        relation: None,
-        name: expr.column,
+    let column = |name: String| {
+        Expr::Column(datafusion::logical_plan::Column {
+            relation: None,
+            name,
+        })
     };
 
-    Expr::BinaryExpr {
-        left: Box::new(Expr::Column(column)),
-        op: op_to_df(expr.op),
-        right: Box::new(Expr::Literal(scalar_to_df(expr.scalar))),
+    match expr {
+        // use the InfluxRPC compatible regex functions, which also support dictionary encoded tag columns
+        DeleteExpr::Compare {
+            column: name,
+            op: Op::RegexMatch,
+            scalar: Scalar::String(pattern),
+        } => query_functions::regex_match_expr(column(name), pattern),
+        DeleteExpr::Compare {
+            column: name,
+            op: Op::RegexNotMatch,
+            scalar: Scalar::String(pattern),
+        } => query_functions::regex_not_match_expr(column(name), pattern),
+        DeleteExpr::Compare {
+            column: name,
+            op,
+            scalar,
+        } => Expr::BinaryExpr {
+            left: Box::new(column(name)),
+            op: op_to_df(op),
+            right: Box::new(Expr::Literal(scalar_to_df(scalar))),
+        },
+        // `IN` is evaluated as a disjunction of equalities (and `NOT IN` as a conjunction of
+        // inequalities) so that it works for all column types, including dictionary encoded tags
+        DeleteExpr::InList {
+            column: name,
+            list,
+            negated,
+        } => {
+            let (op, combine, empty): (_, fn(Expr, Expr) -> Expr, _) = if negated {
+                (Op::Ne, Expr::and, true)
+            } else {
+                (Op::Eq, Expr::or, false)
+            };
+
+            list.into_iter()
+                .map(|scalar| Expr::BinaryExpr {
+                    left: Box::new(column(name.clone())),
+                    op: op_to_df(op),
+                    right: Box::new(Expr::Literal(scalar_to_df(scalar))),
+                })
+                .reduce(combine)
+                .unwrap_or_else(|| datafusion::logical_plan::lit(empty))
+        }
+        DeleteExpr::Or(exprs) => exprs
+            .into_iter()
+            .map(expr_to_df)
+            .reduce(|acc, expr| acc.or(expr))
+            .unwrap_or_else(|| datafusion::logical_plan::lit(false)),
+        DeleteExpr::And(exprs) => exprs
+            .into_iter()
+            .map(expr_to_df)
+            .reduce(|acc, expr| acc.and(expr))
+            .unwrap_or_else(|| datafusion::logical_plan::lit(true)),
     }
 }
 
@@ -45,6 +97,31 @@ pub(crate) fn df_to_expr(
     expr: datafusion::logical_plan::Expr,
 ) -> Result<DeleteExpr, DataFusionToExprError> {
     match expr {
+        datafusion::logical_plan::Expr::BinaryExpr {
+            left,
+            op:
+                op @ (datafusion::logical_plan::Operator::And | datafusion::logical_plan::Operator::Or),
+            right,
+        } => {
+            let is_or = op == datafusion::logical_plan::Operator::Or;
+
+            // flatten nested expressions of the same kind, e.g. `(a OR b) OR c` => `OR(a, b, c)`
+            let mut exprs = vec![];
+            for expr in [*left, *right] {
+                match (df_to_expr(expr)?, is_or) {
+                    (DeleteExpr::Or(inner), true) | (DeleteExpr::And(inner), false) => {
+                        exprs.extend(inner)
+                    }
+                    (expr, _) => exprs.push(expr),
+                }
+            }
+
+            Ok(if is_or {
+                DeleteExpr::Or(exprs)
+            } else {
+                DeleteExpr::And(exprs)
+            })
+        }
         datafusion::logical_plan::Expr::BinaryExpr { left, op, right } => {
             let (column, scalar) = match (left.deref(), right.deref()) {
                 // The delete predicate parser currently only supports `<column><op><value>`, not `<value><op><column>`,
@@ -70,7 +147,55 @@ pub(crate) fn df_to_expr(
 
             let op = df_to_op(op).context(CannotConvertDataFusionOperatorSnafu)?;
 
-            Ok(DeleteExpr { column, op, scalar })
+            Ok(DeleteExpr::new(column, op, scalar))
+        }
+        datafusion::logical_plan::Expr::ScalarUDF { fun, args }
+            if fun.name == REGEX_MATCH_UDF_NAME || fun.name == REGEX_NOT_MATCH_UDF_NAME =>
+        {
+            let op = if fun.name == REGEX_MATCH_UDF_NAME {
+                Op::RegexMatch
+            } else {
+                Op::RegexNotMatch
+            };
+
+            match args.as_slice() {
+                [datafusion::logical_plan::Expr::Column(column), datafusion::logical_plan::Expr::Literal(datafusion::scalar::ScalarValue::Utf8(
+                    Some(pattern),
+                ))] => Ok(DeleteExpr::new(
+                    column.name.clone(),
+                    op,
+                    Scalar::String(pattern.clone()),
+                )),
+                _ => Err(DataFusionToExprError::UnsupportedExpression {
+                    expr: datafusion::logical_plan::Expr::ScalarUDF { fun, args },
+                }),
+            }
+        }
+        datafusion::logical_plan::Expr::InList {
+            expr,
+            list,
+            negated,
+        } => {
+            let column = match expr.deref() {
+                datafusion::logical_plan::Expr::Column(column) => column.name.clone(),
+                other => {
+                    return Err(DataFusionToExprError::UnsupportedExpression {
+                        expr: other.clone(),
+                    })
+                }
+            };
+
+            let list = list
+                .into_iter()
+                .map(|value| match value {
+                    datafusion::logical_plan::Expr::Literal(value) => {
+                        df_to_scalar(value).context(CannotConvertDataFusionScalarValueSnafu)
+                    }
+                    other => Err(DataFusionToExprError::UnsupportedExpression { expr: other }),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(DeleteExpr::in_list(column, list, negated))
         }
         other => Err(DataFusionToExprError::UnsupportedExpression { expr: other }),
     }
@@ -80,6 +205,12 @@ pub(crate) fn op_to_df(op: Op) -> datafusion::logical_plan::Operator {
     match op {
         Op::Eq => datafusion::logical_plan::Operator::Eq,
         Op::Ne => datafusion::logical_plan::Operator::NotEq,
+        Op::Gt => datafusion::logical_plan::Operator::Gt,
+        Op::Ge => datafusion::logical_plan::Operator::GtEq,
+        Op::Lt => datafusion::logical_plan::Operator::Lt,
+        Op::Le => datafusion::logical_plan::Operator::LtEq,
+        Op::RegexMatch => datafusion::logical_plan::Operator::RegexMatch,
+        Op::RegexNotMatch => datafusion::logical_plan::Operator::RegexNotMatch,
     }
 }
 
@@ -96,6 +227,12 @@ pub(crate) fn df_to_op(op: datafusion::logical_plan::Operator) -> Result<Op, Dat
     match op {
         datafusion::logical_plan::Operator::Eq => Ok(Op::Eq),
         datafusion::logical_plan::Operator::NotEq => Ok(Op::Ne),
+        datafusion::logical_plan::Operator::Gt => Ok(Op::Gt),
+        datafusion::logical_plan::Operator::GtEq => Ok(Op::Ge),
+        datafusion::logical_plan::Operator::Lt => Ok(Op::Lt),
+        datafusion::logical_plan::Operator::LtEq => Ok(Op::Le),
+        datafusion::logical_plan::Operator::RegexMatch => Ok(Op::RegexMatch),
+        datafusion::logical_plan::Operator::RegexNotMatch => Ok(Op::RegexNotMatch),
         other => Err(DataFusionToOpError::UnsupportedOperator { op: other }),
     }
 }
@@ -141,39 +278,101 @@ mod tests {
     #[test]
     fn test_roundtrips() {
         assert_expr_works(
-            DeleteExpr {
-                column: "foo".to_string(),
-                op: Op::Eq,
-                scalar: Scalar::Bool(true),
-            },
+            DeleteExpr::new("foo".to_string(), Op::Eq, Scalar::Bool(true)),
             r#""foo"=true"#,
         );
         assert_expr_works(
-            DeleteExpr {
-                column: "bar".to_string(),
-                op: Op::Ne,
-                scalar: Scalar::I64(-1),
-            },
+            DeleteExpr::new("bar".to_string(), Op::Ne, Scalar::I64(-1)),
             r#""bar"!=-1"#,
         );
         assert_expr_works(
-            DeleteExpr {
-                column: "baz".to_string(),
-                op: Op::Eq,
-                scalar: Scalar::F64((-1.1).into()),
-            },
+            DeleteExpr::new("baz".to_string(), Op::Eq, Scalar::F64((-1.1).into())),
             r#""baz"=-1.1"#,
         );
         assert_expr_works(
-            DeleteExpr {
-                column: "col".to_string(),
-                op: Op::Eq,
-                scalar: Scalar::String("foo".to_string()),
-            },
+            DeleteExpr::new("col".to_string(), Op::Eq, Scalar::String("foo".to_string())),
             r#""col"='foo'"#,
         );
     }
 
+    #[test]
+    fn test_roundtrips_richer_exprs() {
+        assert_expr_works(
+            DeleteExpr::new("foo".to_string(), Op::Ge, Scalar::F64((1.5).into())),
+            r#""foo">=1.5"#,
+        );
+        assert_expr_works(
+            DeleteExpr::new("foo".to_string(), Op::Lt, Scalar::I64(-1)),
+            r#""foo"<-1"#,
+        );
+        assert_expr_works(
+            DeleteExpr::new(
+                "foo".to_string(),
+                Op::RegexMatch,
+                Scalar::String("^b.r$".to_string()),
+            ),
+            r#""foo"=~/^b.r$/"#,
+        );
+        assert_expr_works(
+            DeleteExpr::new(
+                "foo".to_string(),
+                Op::RegexNotMatch,
+                Scalar::String("x".to_string()),
+            ),
+            r#""foo"!~/x/"#,
+        );
+        assert_expr_works(
+            DeleteExpr::Or(vec![
+                DeleteExpr::new("foo".to_string(), Op::Eq, Scalar::I64(1)),
+                DeleteExpr::And(vec![
+                    DeleteExpr::new("bar".to_string(), Op::Gt, Scalar::I64(2)),
+                    DeleteExpr::new("baz".to_string(), Op::Le, Scalar::I64(3)),
+                ]),
+                DeleteExpr::new("foo".to_string(), Op::Eq, Scalar::I64(4)),
+            ]),
+            r#"("foo"=1 OR ("bar">2 AND "baz"<=3) OR "foo"=4)"#,
+        );
+    }
+
+    #[test]
+    fn test_in_list() {
+        use datafusion::logical_plan::{col, lit, Expr};
+
+        let expr = DeleteExpr::in_list(
+            "foo".to_string(),
+            vec![
+                Scalar::String("a".to_string()),
+                Scalar::String("b".to_string()),
+            ],
+            false,
+        );
+        assert_eq!(
+            expr_to_df(expr.clone()),
+            col("foo").eq(lit("a")).or(col("foo").eq(lit("b")))
+        );
+        assert_eq!(expr.to_string(), r#""foo" IN ('a','b')"#);
+
+        let expr = DeleteExpr::in_list(
+            "foo".to_string(),
+            vec![Scalar::I64(1), Scalar::I64(2)],
+            true,
+        );
+        assert_eq!(
+            expr_to_df(expr.clone()),
+            col("foo")
+                .not_eq(lit(1i64))
+                .and(col("foo").not_eq(lit(2i64)))
+        );
+        assert_eq!(expr.to_string(), r#""foo" NOT IN (1,2)"#);
+
+        let df_expr = Expr::InList {
+            expr: Box::new(col("foo")),
+            list: vec![lit(1i64), lit(2i64)],
+            negated: true,
+        };
+        assert_eq!(df_to_expr(df_expr).unwrap(), expr);
+    }
+
     fn assert_expr_works(expr: DeleteExpr, display: &str) {
         let df_expr = expr_to_df(expr.clone());
         let expr2 = df_to_expr(df_expr).unwrap();
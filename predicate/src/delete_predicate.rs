@@ -1,10 +1,10 @@
 use crate::delete_expr::{df_to_expr, expr_to_df};
 use chrono::DateTime;
-use data_types::{DeleteExpr, DeletePredicate, TimestampRange, Tombstone};
+use data_types::{DeleteExpr, DeletePredicate, Op, Scalar, TimestampRange, Tombstone};
 use datafusion::logical_plan::{lit, Column, Expr, Operator};
 use snafu::{ResultExt, Snafu};
 use sqlparser::{
-    ast::{BinaryOperator, Expr as SqlParserExpr, Ident, Statement, Value},
+    ast::{BinaryOperator, Expr as SqlParserExpr, Ident, Statement, UnaryOperator, Value},
     dialect::GenericDialect,
     parser::Parser,
 };
@@ -33,9 +33,16 @@ pub enum Error {
     InvalidSemantics { value: String },
 
     /// Predicate include non supported expression
-    #[snafu(display("Delete predicate must be conjunctive expressions of binary 'column_name = literal' or 'column_name != literal': ({})", value))]
+    #[snafu(display("Delete predicate must be AND / OR combinations of 'column_name <op> literal' with <op> one of =, !=, >, >=, <, <=, =~ /regex/, !~ /regex/ or 'column_name [NOT] IN (literal, ...)': ({})", value))]
     NotSupportPredicate { value: String },
 
+    /// Predicate includes an invalid regular expression
+    #[snafu(display("Invalid regex '{}' in delete predicate: {}", pattern, source))]
+    InvalidRegex {
+        pattern: String,
+        source: regex::Error,
+    },
+
     #[snafu(display(r#"Unable to parse delete string '{}'"#, value))]
     DeleteInvalid {
         source: serde_json::Error,
@@ -69,7 +76,20 @@ impl From<DeletePredicate> for crate::Predicate {
         Self {
             field_columns: None,
             range: Some(pred.range),
-            exprs: pred.exprs.into_iter().map(expr_to_df).collect(),
+            exprs: pred
+                .exprs
+                .into_iter()
+                .map(|expr| {
+                    let deletes_null_rows = expr.deletes_null_rows();
+                    let expr = expr_to_df(expr);
+                    if deletes_null_rows {
+                        expr
+                    } else {
+                        // `NOT(expr)` is NULL when `expr` is, which would delete the row
+                        expr.clone().is_not_null().and(expr)
+                    }
+                })
+                .collect(),
             value_expr: vec![],
         }
     }
@@ -116,7 +136,8 @@ pub fn parse_delete_predicate(
 
 /// Parse the predicate and convert it into datafusion expression
 /// A delete predicate is a conjunctive expression of many
-/// binary expressions of 'colum = constant' or 'column != constant'
+/// binary expressions of 'column <op> constant', 'column [NOT] IN (constant, ...)'
+/// or parenthesized disjunctions of those
 ///
 fn parse_predicate(predicate: &str) -> Result<Vec<DeleteExpr>> {
     if predicate.is_empty() {
@@ -126,7 +147,7 @@ fn parse_predicate(predicate: &str) -> Result<Vec<DeleteExpr>> {
     // "DELETE FROM table_name WHERE predicate"
     // Table name can be anything to have sqlparser work on the right sql syntax
     let mut sql = "DELETE FROM table_name WHERE ".to_string();
-    sql.push_str(predicate);
+    sql.push_str(&rewrite_regex_literals(predicate));
 
     // parse the delete sql
     let dialect = GenericDialect {};
@@ -158,6 +179,9 @@ fn parse_predicate(predicate: &str) -> Result<Vec<DeleteExpr>> {
                             value: predicate.to_string(),
                         });
                     }
+                    for expr in &exprs {
+                        validate_regexes(expr)?;
+                    }
                     Ok(exprs)
                 }
                 _ => Err(Error::InvalidSemantics {
@@ -168,10 +192,96 @@ fn parse_predicate(predicate: &str) -> Result<Vec<DeleteExpr>> {
     }
 }
 
+/// Rewrite InfluxQL-style regex literals into the Postgres regex operators
+/// understood by sqlparser:
+///   `col =~ /pattern/` => `col ~ 'pattern'`
+///   `col !~ /pattern/` => `col !~ 'pattern'`
+///
+/// A `/` within the pattern is escaped as `\/`. Quoted strings and
+/// identifiers are left untouched.
+fn rewrite_regex_literals(predicate: &str) -> String {
+    let mut out = String::with_capacity(predicate.len());
+    let mut chars = predicate.chars().peekable();
+    let mut quote: Option<char> = None;
+
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            out.push(c);
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+
+        match c {
+            '\'' | '"' => {
+                quote = Some(c);
+                out.push(c);
+            }
+            '=' | '!' if chars.peek() == Some(&'~') => {
+                chars.next();
+
+                // skip whitespace to find the start of a regex literal
+                let mut whitespace = String::new();
+                while let Some(ws) = chars.next_if(|c| c.is_whitespace()) {
+                    whitespace.push(ws);
+                }
+                if chars.next_if_eq(&'/').is_none() {
+                    // not a regex literal, let the SQL parser report the error
+                    out.push(c);
+                    out.push('~');
+                    out.push_str(&whitespace);
+                    continue;
+                }
+
+                let mut pattern = String::new();
+                while let Some(p) = chars.next() {
+                    match p {
+                        '\\' if chars.peek() == Some(&'/') => {
+                            pattern.push(chars.next().expect("peeked"));
+                        }
+                        '/' => break,
+                        p => pattern.push(p),
+                    }
+                }
+
+                out.push_str(if c == '=' { " ~ '" } else { " !~ '" });
+                out.push_str(&pattern.replace('\'', "''"));
+                out.push('\'');
+            }
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// Return an error if `expr` contains a regex that does not compile
+fn validate_regexes(expr: &DeleteExpr) -> Result<()> {
+    match expr {
+        DeleteExpr::Compare {
+            op: Op::RegexMatch | Op::RegexNotMatch,
+            scalar,
+            ..
+        } => match scalar {
+            Scalar::String(pattern) => regex::Regex::new(pattern)
+                .map(|_| ())
+                .context(InvalidRegexSnafu { pattern }),
+            other => Err(Error::NotSupportPredicate {
+                value: other.to_string(),
+            }),
+        },
+        DeleteExpr::Compare { .. } | DeleteExpr::InList { .. } => Ok(()),
+        DeleteExpr::Or(exprs) | DeleteExpr::And(exprs) => {
+            exprs.iter().try_for_each(validate_regexes)
+        }
+    }
+}
+
 /// Recursively split all "AND" expressions into smaller ones
 /// Example: "A AND B AND C" => [A, B, C]
-/// Return false if not all of them are AND of binary expression of
-/// "column_name = literal" or "column_name != literal"
+/// Return false if not all of them are AND of supported expressions, see
+/// [`to_delete_expr`]
 ///
 /// The split expressions will be converted into data fusion expressions
 fn split_members(predicate: &SqlParserExpr, predicates: &mut Vec<DeleteExpr>) -> bool {
@@ -190,64 +300,134 @@ fn split_members(predicate: &SqlParserExpr, predicates: &mut Vec<DeleteExpr>) ->
                 return false;
             }
         }
+        SqlParserExpr::Nested(expr) => return split_members(expr, predicates),
+        other => match to_delete_expr(other) {
+            Some(expr) => predicates.push(expr),
+            None => return false,
+        },
+    }
+
+    true
+}
+
+/// Convert a single member of the delete predicate, which is one of
+///   - "column_name <op> literal" with <op> one of `=`, `!=`, `>`, `>=`, `<`, `<=`, `~`, `!~`
+///   - "column_name [NOT] IN (literal, ...)"
+///   - an "OR" / "AND" combination of the above
+///
+/// Return None if the expression is not supported
+fn to_delete_expr(expr: &SqlParserExpr) -> Option<DeleteExpr> {
+    match expr {
+        SqlParserExpr::Nested(expr) => to_delete_expr(expr),
+        SqlParserExpr::BinaryOp {
+            left,
+            op: op @ (BinaryOperator::Or | BinaryOperator::And),
+            right,
+        } => {
+            let is_or = matches!(op, BinaryOperator::Or);
+
+            // flatten nested expressions of the same kind, e.g. "(A OR B) OR C" => OR(A, B, C)
+            let mut exprs = vec![];
+            for expr in [left, right] {
+                match (to_delete_expr(expr)?, is_or) {
+                    (DeleteExpr::Or(inner), true) | (DeleteExpr::And(inner), false) => {
+                        exprs.extend(inner)
+                    }
+                    (expr, _) => exprs.push(expr),
+                }
+            }
+
+            Some(if is_or {
+                DeleteExpr::Or(exprs)
+            } else {
+                DeleteExpr::And(exprs)
+            })
+        }
         SqlParserExpr::BinaryOp { left, op, right } => {
             // Verify Operator
             let op = match op {
                 BinaryOperator::Eq => Operator::Eq,
                 BinaryOperator::NotEq => Operator::NotEq,
-                _ => return false,
-            };
-
-            // verify if left is identifier (column name)
-            let column = match &**left {
-                SqlParserExpr::Identifier(Ident {
-                    value,
-                    quote_style: _, // all quotes are ignored as done in idpe
-                }) => Expr::Column(Column {
-                    relation: None,
-                    name: value.to_string(),
-                }),
-                _ => return false, // not a column name
-            };
-
-            // verify if right is a literal or an identifier (e.g column name)
-            let value = match &**right {
-                SqlParserExpr::Identifier(Ident {
-                    value,
-                    quote_style: _,
-                }) => lit(value.to_string()),
-                SqlParserExpr::Value(Value::DoubleQuotedString(value)) => lit(value.to_string()),
-                SqlParserExpr::Value(Value::SingleQuotedString(value)) => lit(value.to_string()),
-                SqlParserExpr::Value(Value::NationalStringLiteral(value)) => lit(value.to_string()),
-                SqlParserExpr::Value(Value::HexStringLiteral(value)) => lit(value.to_string()),
-                SqlParserExpr::Value(Value::Number(v, _)) => match v.parse::<i64>() {
-                    Ok(v) => lit(v),
-                    Err(_) => lit(v.parse::<f64>().unwrap()),
-                },
-                SqlParserExpr::Value(Value::Boolean(v)) => lit(*v),
-                _ => return false, // not a literal
+                BinaryOperator::Gt => Operator::Gt,
+                BinaryOperator::GtEq => Operator::GtEq,
+                BinaryOperator::Lt => Operator::Lt,
+                BinaryOperator::LtEq => Operator::LtEq,
+                BinaryOperator::PGRegexMatch => Operator::RegexMatch,
+                BinaryOperator::PGRegexNotMatch => Operator::RegexNotMatch,
+                _ => return None,
             };
 
             let expr = Expr::BinaryExpr {
-                left: Box::new(column),
+                left: Box::new(to_column(left)?),
                 op,
-                right: Box::new(value),
+                right: Box::new(to_literal(right)?),
             };
-            let expr: Result<DeleteExpr, _> = df_to_expr(expr);
-            match expr {
-                Ok(expr) => {
-                    predicates.push(expr);
-                }
-                Err(_) => {
-                    // cannot convert
-                    return false;
-                }
-            }
+            df_to_expr(expr).ok()
         }
-        _ => return false,
+        SqlParserExpr::InList {
+            expr,
+            list,
+            negated,
+        } => {
+            let expr = Expr::InList {
+                expr: Box::new(to_column(expr)?),
+                list: list.iter().map(to_literal).collect::<Option<Vec<_>>>()?,
+                negated: *negated,
+            };
+            df_to_expr(expr).ok()
+        }
+        _ => None,
     }
+}
 
-    true
+/// Verify that `expr` is an identifier (column name)
+fn to_column(expr: &SqlParserExpr) -> Option<Expr> {
+    match expr {
+        SqlParserExpr::Identifier(Ident {
+            value,
+            quote_style: _, // all quotes are ignored as done in idpe
+        }) => Some(Expr::Column(Column {
+            relation: None,
+            name: value.to_string(),
+        })),
+        _ => None, // not a column name
+    }
+}
+
+/// Verify that `expr` is a literal or an identifier (e.g column name)
+fn to_literal(expr: &SqlParserExpr) -> Option<Expr> {
+    let value = match expr {
+        SqlParserExpr::Identifier(Ident {
+            value,
+            quote_style: _,
+        }) => lit(value.to_string()),
+        SqlParserExpr::Value(Value::DoubleQuotedString(value)) => lit(value.to_string()),
+        SqlParserExpr::Value(Value::SingleQuotedString(value)) => lit(value.to_string()),
+        SqlParserExpr::Value(Value::NationalStringLiteral(value)) => lit(value.to_string()),
+        SqlParserExpr::Value(Value::HexStringLiteral(value)) => lit(value.to_string()),
+        SqlParserExpr::Value(Value::Number(v, _)) => match v.parse::<i64>() {
+            Ok(v) => lit(v),
+            Err(_) => lit(v.parse::<f64>().ok()?),
+        },
+        SqlParserExpr::Value(Value::Boolean(v)) => lit(*v),
+        SqlParserExpr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        } => match expr.as_ref() {
+            SqlParserExpr::Value(Value::Number(v, _)) => {
+                // parse including the sign so that i64::MIN does not overflow
+                let v = format!("-{}", v);
+                match v.parse::<i64>() {
+                    Ok(v) => lit(v),
+                    Err(_) => lit(v.parse::<f64>().ok()?),
+                }
+            }
+            _ => return None,
+        },
+        _ => return None, // not a literal
+    };
+
+    Some(value)
 }
 
 /// Parse a time and return its time in nanosecond
@@ -528,21 +708,178 @@ mod tests {
         assert_eq!(result, expected)
     }
 
+    #[test]
+    fn test_parse_predicate_comparisons() {
+        let pred = r#"cost > 100 and cost <= 200.5 and temp >= -10 and temp < 0"#;
+        let result = parse_predicate(pred).unwrap();
+
+        let expected = vec![
+            DeleteExpr::new("cost".to_string(), Op::Gt, Scalar::I64(100)),
+            DeleteExpr::new("cost".to_string(), Op::Le, Scalar::F64((200.5).into())),
+            DeleteExpr::new("temp".to_string(), Op::Ge, Scalar::I64(-10)),
+            DeleteExpr::new("temp".to_string(), Op::Lt, Scalar::I64(0)),
+        ];
+
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_parse_predicate_in_list() {
+        let pred = r#"city IN ('Boston', 'NYC') and cost NOT IN (1, 2)"#;
+        let result = parse_predicate(pred).unwrap();
+
+        let expected = vec![
+            DeleteExpr::in_list(
+                "city".to_string(),
+                vec![
+                    Scalar::String("Boston".to_string()),
+                    Scalar::String("NYC".to_string()),
+                ],
+                false,
+            ),
+            DeleteExpr::in_list(
+                "cost".to_string(),
+                vec![Scalar::I64(1), Scalar::I64(2)],
+                true,
+            ),
+        ];
+
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_parse_predicate_regex() {
+        let pred = r#"host =~ /^server-\d+$/ and "path"!~/a\/b'c/ and name = "x=~/y/""#;
+        let result = parse_predicate(pred).unwrap();
+
+        let expected = vec![
+            DeleteExpr::new(
+                "host".to_string(),
+                Op::RegexMatch,
+                Scalar::String(r#"^server-\d+$"#.to_string()),
+            ),
+            DeleteExpr::new(
+                "path".to_string(),
+                Op::RegexNotMatch,
+                Scalar::String("a/b'c".to_string()),
+            ),
+            DeleteExpr::new(
+                "name".to_string(),
+                Op::Eq,
+                Scalar::String("x=~/y/".to_string()),
+            ),
+        ];
+
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_parse_predicate_invalid_regex() {
+        let pred = r#"host =~ /(unclosed/"#;
+        let err = parse_predicate(pred).unwrap_err();
+        assert!(matches!(err, Error::InvalidRegex { .. }), "{}", err);
+
+        // regex must be compared against a string
+        let pred = r#"host =~ 1"#;
+        parse_predicate(pred).unwrap_err();
+    }
+
+    #[test]
+    fn test_parse_predicate_or() {
+        let pred = r#"(city = Boston OR city = NYC and cost > 1) AND (state = MA or (state = NY))"#;
+        let result = parse_predicate(pred).unwrap();
+
+        let expected = vec![
+            DeleteExpr::Or(vec![
+                DeleteExpr::new(
+                    "city".to_string(),
+                    Op::Eq,
+                    Scalar::String("Boston".to_string()),
+                ),
+                DeleteExpr::And(vec![
+                    DeleteExpr::new(
+                        "city".to_string(),
+                        Op::Eq,
+                        Scalar::String("NYC".to_string()),
+                    ),
+                    DeleteExpr::new("cost".to_string(), Op::Gt, Scalar::I64(1)),
+                ]),
+            ]),
+            DeleteExpr::Or(vec![
+                DeleteExpr::new(
+                    "state".to_string(),
+                    Op::Eq,
+                    Scalar::String("MA".to_string()),
+                ),
+                DeleteExpr::new(
+                    "state".to_string(),
+                    Op::Eq,
+                    Scalar::String("NY".to_string()),
+                ),
+            ]),
+        ];
+
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_parse_predicate_roundtrip() {
+        // the serialized form stored in tombstones must parse back to the same predicate
+        let pred = r#"(city = Boston OR cost NOT IN (1, -2)) and host =~ /^a\/b/ and temp <= -1.5 and "col 1" != 'x'"#;
+        let exprs = parse_predicate(pred).unwrap();
+        let delete_predicate = DeletePredicate {
+            range: TimestampRange::new(0, 1),
+            exprs,
+        };
+
+        let serialized = delete_predicate.expr_sql_string();
+        assert_eq!(
+            serialized,
+            r#"("city"='Boston' OR "cost" NOT IN (1,-2)) AND "host"=~/^a\/b/ AND "temp"<=-1.5 AND "col 1"!='x'"#
+        );
+        assert_eq!(
+            parse_predicate(&serialized).unwrap(),
+            delete_predicate.exprs
+        );
+    }
+
+    #[test]
+    fn test_to_predicate_null_semantics() {
+        use datafusion::logical_plan::{col, lit};
+
+        // plain `=`/`!=` comparisons keep deleting rows for which they are NULL, all other
+        // expressions retain them
+        let exprs = parse_predicate(r#"city != Boston AND temp > 70"#).unwrap();
+        let pred = crate::Predicate::from(DeletePredicate {
+            range: TimestampRange::new(0, 1),
+            exprs,
+        });
+
+        let temp = col("temp").gt(lit(70i64));
+        assert_eq!(
+            pred.exprs,
+            vec![
+                col("city").not_eq(lit("Boston")),
+                temp.clone().is_not_null().and(temp),
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_predicate_invalid() {
-        let pred = r#"city= Boston Or cost !=100 and state != "MA""#; // OR
+        let pred = r#"city= Boston and cost !=100+1 and state != "MA""#; // 100 + 1
         let result = parse_predicate(pred);
         assert!(result.is_err());
 
-        let pred = r#"city= Boston and cost !=100+1 and state != "MA""#; // 100 + 1
+        let pred = r#"city= Boston or cost != cost2 + 1"#; // cost2 + 1 within OR
         let result = parse_predicate(pred);
         assert!(result.is_err());
 
-        let pred = r#"cost > 100"#; // >
+        let pred = r#"city LIKE 'Bos%'"#; // LIKE
         let result = parse_predicate(pred);
         assert!(result.is_err());
 
-        let pred = r#"cost <= 100"#; // <
+        let pred = r#"city IN (SELECT 1)"#; // subquery
         let result = parse_predicate(pred);
         assert!(result.is_err());
 
@@ -587,7 +924,7 @@ mod tests {
     fn test_full_delete_pred_invalid_pred() {
         let start = r#"100"#;
         let stop = r#"200"#;
-        let pred = r#"cost > 100"#;
+        let pred = r#"cost LIKE 100"#;
 
         let result = parse_delete_predicate(start, stop, pred);
         assert!(result.is_err());
@@ -13,8 +13,10 @@ use cache_system::{
     loader::{metrics::MetricsLoader, FunctionLoader},
     resource_consumption::FunctionEstimator,
 };
-use data_types::{ColumnId, NamespaceId, NamespaceSchema, TableId, TableSchema};
-use iox_catalog::interface::{get_schema_by_name, Catalog};
+use data_types::{
+    ColumnId, NamespaceId, NamespaceQueryLimits, NamespaceSchema, TableId, TableSchema,
+};
+use iox_catalog::interface::{get_schema_by_id, Catalog};
 use iox_time::TimeProvider;
 use schema::Schema;
 use std::{
@@ -80,12 +82,17 @@ impl NamespaceCache {
             let backoff_config = backoff_config.clone();
 
             async move {
-                let schema = Backoff::new(&backoff_config)
+                let (namespace, schema) = Backoff::new(&backoff_config)
                     .retry_all_errors("get namespace schema", || async {
                         let mut repos = catalog.repositories().await;
-                        match get_schema_by_name(&namespace_name, repos.as_mut()).await {
-                            Ok(schema) => Ok(Some(schema)),
-                            Err(iox_catalog::interface::Error::NamespaceNotFoundByName {
+                        let namespace =
+                            match repos.namespaces().get_by_name(&namespace_name).await? {
+                                Some(namespace) => namespace,
+                                None => return Ok(None),
+                            };
+                        match get_schema_by_id(namespace.id, repos.as_mut()).await {
+                            Ok(schema) => Ok(Some((namespace, schema))),
+                            Err(iox_catalog::interface::Error::NamespaceNotFoundById {
                                 ..
                             }) => Ok(None),
                             Err(e) => Err(e),
@@ -94,7 +101,9 @@ impl NamespaceCache {
                     .await
                     .expect("retry forever")?;
 
-                Some(Arc::new((&schema).into()))
+                let mut cached_namespace: CachedNamespace = (&schema).into();
+                cached_namespace.query_limits = namespace.query_limits;
+                Some(Arc::new(cached_namespace))
             }
         });
         let loader = Arc::new(MetricsLoader::new(
@@ -192,6 +201,11 @@ impl NamespaceCache {
             )
             .await
     }
+
+    /// Mark the entry for the namespace `name` as expired (and needs a refresh).
+    pub fn expire(&self, name: &Arc<str>) {
+        self.remove_if_handle.remove_if(name, |_| true);
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -240,6 +254,7 @@ impl From<&TableSchema> for CachedTable {
 pub struct CachedNamespace {
     pub id: NamespaceId,
     pub tables: HashMap<Arc<str>, Arc<CachedTable>>,
+    pub query_limits: NamespaceQueryLimits,
 }
 
 impl CachedNamespace {
@@ -266,7 +281,11 @@ impl From<&NamespaceSchema> for CachedNamespace {
             .collect();
         tables.shrink_to_fit();
 
-        Self { id: ns.id, tables }
+        Self {
+            id: ns.id,
+            tables,
+            query_limits: Default::default(),
+        }
     }
 }
 
@@ -299,6 +318,19 @@ mod tests {
         let col122 = table12.create_column("time", ColumnType::Time).await;
         let col211 = table21.create_column("time", ColumnType::Time).await;
 
+        let query_limits = NamespaceQueryLimits {
+            max_concurrent_queries: Some(1),
+            ..Default::default()
+        };
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .namespaces()
+            .update_query_limits("ns1", query_limits)
+            .await
+            .unwrap();
+
         let cache = NamespaceCache::new(
             catalog.catalog(),
             BackoffConfig::default(),
@@ -353,6 +385,7 @@ mod tests {
                     }),
                 ),
             ]),
+            query_limits,
         };
         assert_eq!(actual_ns_1_a.as_ref(), &expected_ns_1);
         assert_histogram_metric_count(&catalog.metric_registry, "namespace_get_by_name", 1);
@@ -374,6 +407,7 @@ mod tests {
                     )]),
                 }),
             )]),
+            query_limits: Default::default(),
         };
         assert_eq!(actual_ns_2.as_ref(), &expected_ns_2);
         assert_histogram_metric_count(&catalog.metric_registry, "namespace_get_by_name", 2);
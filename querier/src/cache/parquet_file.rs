@@ -5,6 +5,7 @@ use cache_system::{
     backend::policy::{
         lru::{LruPolicy, ResourcePool},
         remove_if::{RemoveIfHandle, RemoveIfPolicy},
+        ttl::{TtlPolicy, TtlProvider},
         PolicyBackend,
     },
     cache::{driver::CacheDriver, metrics::CacheWithMetrics, Cache},
@@ -16,13 +17,20 @@ use iox_catalog::interface::Catalog;
 use iox_time::TimeProvider;
 use observability_deps::tracing::debug;
 use snafu::{ResultExt, Snafu};
-use std::{collections::HashMap, mem, sync::Arc};
+use std::{collections::HashMap, mem, sync::Arc, time::Duration};
 use trace::span::Span;
 
 use super::ram::RamSize;
 
 const CACHE_ID: &str = "parquet_file";
 
+/// Duration to keep the parquet files of a table cached.
+///
+/// Files written by the compactor keep the max sequence number of the files they replace, so the
+/// ingester responses never expire a cache entry listing the replaced files. This TTL must stay
+/// well below the cutoff after which the garbage collector deletes replaced files.
+pub const TTL: Duration = Duration::from_secs(60 * 60);
+
 #[derive(Debug, Snafu)]
 #[allow(missing_copy_implementations, missing_docs)]
 pub enum Error {
@@ -152,6 +160,11 @@ impl ParquetFileCache {
         let (policy_constructor, remove_if_handle) =
             RemoveIfPolicy::create_constructor_and_handle(CACHE_ID, metric_registry);
         backend.add_policy(policy_constructor);
+        backend.add_policy(TtlPolicy::new(
+            Arc::new(ConstantTtl {}),
+            CACHE_ID,
+            metric_registry,
+        ));
         backend.add_policy(LruPolicy::new(
             Arc::clone(&ram_pool),
             CACHE_ID,
@@ -242,6 +255,18 @@ impl ParquetFileCache {
     }
 }
 
+#[derive(Debug)]
+struct ConstantTtl;
+
+impl TtlProvider for ConstantTtl {
+    type K = TableId;
+    type V = Arc<CachedParquetFiles>;
+
+    fn expires_in(&self, _k: &Self::K, _v: &Self::V) -> Option<Duration> {
+        Some(TTL)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
@@ -415,6 +440,40 @@ mod tests {
         assert_histogram_metric_count(&catalog.metric_registry, METRIC_NAME, 2);
     }
 
+    #[tokio::test]
+    async fn test_ttl() {
+        let (catalog, table, partition) = make_catalog().await;
+        let builder = TestParquetFileBuilder::default().with_line_protocol(TABLE1_LINE_PROTOCOL);
+        let tfile1 = partition.create_parquet_file(builder).await;
+        let cache = make_cache(&catalog);
+        let table_id = table.table.id;
+
+        assert_eq!(cache.get(table_id, None, None).await.ids(), ids(&[&tfile1]));
+        assert_histogram_metric_count(&catalog.metric_registry, METRIC_NAME, 1);
+
+        // a file replacing tfile1 with the same max sequence number does not expire the cache
+        let builder = TestParquetFileBuilder::default().with_line_protocol(TABLE1_LINE_PROTOCOL);
+        let tfile2 = partition.create_parquet_file(builder).await;
+        tfile1.flag_for_delete().await;
+        assert_eq!(
+            cache
+                .get(
+                    table_id,
+                    Some(tfile2.parquet_file.max_sequence_number),
+                    None
+                )
+                .await
+                .ids(),
+            ids(&[&tfile1])
+        );
+        assert_histogram_metric_count(&catalog.metric_registry, METRIC_NAME, 1);
+
+        // but the TTL does
+        catalog.mock_time_provider().inc(TTL);
+        assert_eq!(cache.get(table_id, None, None).await.ids(), ids(&[&tfile2]));
+        assert_histogram_metric_count(&catalog.metric_registry, METRIC_NAME, 2);
+    }
+
     #[tokio::test]
     async fn test_expire_empty() {
         let (catalog, table, partition) = make_catalog().await;
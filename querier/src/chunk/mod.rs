@@ -547,6 +547,7 @@ pub mod tests {
     use iox_query::{exec::IOxSessionContext, QueryChunk, QueryChunkMeta};
     use iox_tests::util::{TestCatalog, TestNamespace, TestParquetFileBuilder};
     use metric::{Attributes, Observation, RawReporter};
+    use predicate::delete_predicate::parse_delete_predicate;
     use schema::{builder::SchemaBuilder, selection::Selection, sort::SortKeyBuilder};
     use test_helpers::maybe_start_logging;
     use tokio::runtime::Handle;
@@ -671,6 +672,47 @@ pub mod tests {
         assert_eq!(catalog_metrics1, catalog_metrics2);
     }
 
+    #[tokio::test]
+    async fn test_delete_predicates_on_missing_columns() {
+        maybe_start_logging();
+        let test_data = TestData::new(QuerierChunkLoadSetting::ParquetOnly).await;
+        let namespace_schema = Arc::new(test_data.ns.schema().await);
+
+        // The compactor records a tombstone whose predicate references a column missing from a
+        // parquet file as processed without rewriting the file (see
+        // `compactor::parquet_file_combining::materialize_deletes`). This is only correct as long
+        // as queriers do not delete any rows of the file with such a predicate either.
+        //
+        // The parquet file has no tag2 column.
+        let chunk = test_data
+            .chunk(Arc::clone(&namespace_schema))
+            .await
+            .with_delete_predicates(vec![Arc::new(
+                parse_delete_predicate("0", "100000", "tag2=PA").unwrap(),
+            )]);
+        assert_content(&chunk).await;
+
+        // The other branches of an OR still delete rows
+        let chunk = test_data
+            .chunk(namespace_schema)
+            .await
+            .with_delete_predicates(vec![Arc::new(
+                parse_delete_predicate("0", "100000", "tag2=PA OR tag1=VT").unwrap(),
+            )]);
+        let batches = collect_read_filter(&chunk).await;
+        assert_batches_eq!(
+            &[
+                "+-----------+------+-----------------------------+",
+                "| field_int | tag1 | time                        |",
+                "+-----------+------+-----------------------------+",
+                "| 70        | UT   | 1970-01-01T00:00:00.000020Z |",
+                "| 1000      | WA   | 1970-01-01T00:00:00.000008Z |",
+                "+-----------+------+-----------------------------+",
+            ],
+            &batches
+        );
+    }
+
     /// collect data for the given chunk
     async fn collect_read_filter(chunk: &dyn QueryChunk) -> Vec<RecordBatch> {
         chunk
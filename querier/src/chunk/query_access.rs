@@ -1,6 +1,6 @@
-use crate::{chunk::QuerierChunk, QuerierChunkLoadSetting};
+use crate::{cache::CatalogCache, chunk::QuerierChunk, QuerierChunkLoadSetting};
 use arrow::{
-    datatypes::SchemaRef,
+    datatypes::{DataType, SchemaRef},
     error::{ArrowError, Result as ArrowResult},
     record_batch::RecordBatch,
 };
@@ -13,15 +13,19 @@ use datafusion::physical_plan::{
 use futures::{Stream, TryStreamExt};
 use iox_query::{
     exec::{stringset::StringSet, IOxSessionContext},
-    QueryChunk, QueryChunkError, QueryChunkMeta,
+    ChunkAggregateRequest, QueryChunk, QueryChunkError, QueryChunkMeta,
 };
 use observability_deps::tracing::debug;
+use parking_lot::RwLock;
+use parquet_file::storage::ParquetStorage;
 use predicate::Predicate;
+use query_functions::group_by::Aggregate;
 use read_buffer::ReadFilterResults;
 use schema::{
+    builder::SchemaBuilder,
     selection::{select_schema, HalfOwnedSelection, OwnedSelection, Selection},
     sort::SortKey,
-    Schema,
+    Schema, TIME_DATA_TYPE,
 };
 use snafu::{OptionExt, ResultExt, Snafu};
 use std::{
@@ -242,7 +246,10 @@ impl QueryChunk for QuerierChunk {
         let delete_predicates: Vec<_> = self
             .delete_predicates()
             .iter()
-            .map(|pred| Arc::new(pred.as_ref().clone().into()))
+            .filter_map(|pred| {
+                pred.restrict_to_columns(|col| self.schema.find_index_of(col).is_some())
+            })
+            .map(|pred| Arc::new(pred.into()))
             .collect();
         ctx.set_metadata("delete_predicates", delete_predicates.len() as i64);
 
@@ -267,26 +274,7 @@ impl QueryChunk for QuerierChunk {
             output_schema,
             futures::stream::once(async move {
                 if load_setting == QuerierChunkLoadSetting::OnDemand {
-                    // maybe load RB
-                    let parquet_file = match &*stage.read() {
-                        ChunkStage::Parquet { parquet_chunk, .. } => {
-                            Some(Arc::clone(parquet_chunk.parquet_file()))
-                        }
-                        ChunkStage::ReadBuffer { .. } => None,
-                    };
-
-                    if let Some(parquet_file) = parquet_file {
-                        let rb_chunk = catalog_cache
-                            .read_buffer()
-                            .get(
-                                parquet_file,
-                                schema,
-                                store,
-                                span_recorder.child_span("cache GET read_buffer"),
-                            )
-                            .await;
-                        stage.write().load_to_read_buffer(rb_chunk);
-                    }
+                    load_read_buffer(&stage, &catalog_cache, schema, store, &span_recorder).await;
                 }
 
                 let stage = stage.read();
@@ -316,7 +304,7 @@ impl QueryChunk for QuerierChunk {
 
                         // combine all delete expressions to RB's negated ones
                         let negated_delete_exprs =
-                            to_read_buffer_negated_predicates(&delete_predicates)?
+                            to_read_buffer_negated_predicates(&delete_predicates)
                                 .into_iter()
                                 // Any delete predicates unsupported by the Read Buffer will be elided.
                                 .filter_map(|p| rb_chunk.validate_predicate(p).ok())
@@ -345,6 +333,106 @@ impl QueryChunk for QuerierChunk {
         )))
     }
 
+    fn supports_read_aggregate(&self, predicate: &Predicate) -> bool {
+        // The Read Buffer applies the predicate exactly, provided it can be
+        // converted. Delete predicates are not supported.
+        self.load_setting != QuerierChunkLoadSetting::ParquetOnly
+            && self.delete_predicates.is_empty()
+            && predicate.field_columns.is_none()
+            && predicate.value_expr.is_empty()
+            && to_read_buffer_predicate(predicate).is_ok()
+    }
+
+    fn read_aggregate(
+        &self,
+        mut ctx: IOxSessionContext,
+        predicate: &Predicate,
+        request: &ChunkAggregateRequest,
+    ) -> Result<SendableRecordBatchStream, QueryChunkError> {
+        let span_recorder = SpanRecorder::new(
+            ctx.span()
+                .map(|span| span.child("QuerierChunk::read_aggregate")),
+        );
+        ctx.set_metadata("predicate", format!("{}", &predicate));
+
+        let rb_predicate = to_read_buffer_predicate(predicate)?;
+        let aggregates = request
+            .aggregates
+            .iter()
+            .map(|(column, agg)| {
+                to_read_buffer_aggregate(*agg)
+                    .map(|agg| (column.clone(), agg))
+                    .ok_or_else(|| format!("aggregate {:?} not supported by read buffer", agg))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let window = request
+            .window
+            .map(|window| read_buffer::TimeWindow::new(window.every, window.offset));
+        let group_columns = request.group_columns.clone();
+
+        let output_schema = read_aggregate_schema(&self.schema, request)?;
+
+        let load_setting = self.load_setting;
+        let chunk_id = self.id();
+        let stage = Arc::clone(&self.stage);
+        let store = self.store.clone();
+        let schema = Arc::clone(&self.schema);
+        let catalog_cache = Arc::clone(&self.catalog_cache);
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            output_schema,
+            futures::stream::once(async move {
+                if load_setting == QuerierChunkLoadSetting::OnDemand {
+                    load_read_buffer(&stage, &catalog_cache, schema, store, &span_recorder).await;
+                }
+
+                let stage = stage.read();
+                ctx.set_metadata("storage", stage.name());
+
+                let rb_chunk = match &*stage {
+                    ChunkStage::ReadBuffer { rb_chunk, .. } => rb_chunk,
+                    ChunkStage::Parquet { .. } => {
+                        return Err(ArrowError::ExternalError(
+                            "read_aggregate called on chunk not in read buffer".into(),
+                        ))
+                    }
+                };
+
+                // A predicate referencing columns not in this chunk cannot
+                // match any rows.
+                let rb_predicate = match rb_chunk.validate_predicate(rb_predicate) {
+                    Ok(rb_predicate) => rb_predicate,
+                    Err(e) => {
+                        debug!(%e, "predicate not applicable to RB chunk");
+                        return Ok(futures::stream::iter(vec![]));
+                    }
+                };
+                debug!(?rb_predicate, "RB aggregate predicate");
+                ctx.set_metadata("rb_predicate", format!("{}", &rb_predicate));
+
+                let group_columns = group_columns.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+                let aggregates = aggregates
+                    .iter()
+                    .map(|(column, agg)| (column.as_str(), *agg))
+                    .collect::<Vec<_>>();
+
+                let batches = rb_chunk
+                    .read_aggregate(
+                        rb_predicate,
+                        &Selection::Some(&group_columns),
+                        window,
+                        &aggregates,
+                    )
+                    .context(RBChunkSnafu { chunk_id })?
+                    .map(Ok)
+                    .collect::<Vec<_>>();
+                ctx.set_metadata("output_batches", batches.len() as i64);
+
+                Ok(futures::stream::iter(batches))
+            })
+            .try_flatten(),
+        )))
+    }
+
     fn chunk_type(&self) -> &str {
         self.stage.read().name()
     }
@@ -358,6 +446,86 @@ impl QueryChunk for QuerierChunk {
     }
 }
 
+/// Loads the parquet data of a chunk into the Read Buffer, if it is not
+/// already there.
+async fn load_read_buffer(
+    stage: &RwLock<ChunkStage>,
+    catalog_cache: &CatalogCache,
+    schema: Arc<Schema>,
+    store: ParquetStorage,
+    span_recorder: &SpanRecorder,
+) {
+    let parquet_file = match &*stage.read() {
+        ChunkStage::Parquet { parquet_chunk, .. } => Some(Arc::clone(parquet_chunk.parquet_file())),
+        ChunkStage::ReadBuffer { .. } => None,
+    };
+
+    if let Some(parquet_file) = parquet_file {
+        let rb_chunk = catalog_cache
+            .read_buffer()
+            .get(
+                parquet_file,
+                schema,
+                store,
+                span_recorder.child_span("cache GET read_buffer"),
+            )
+            .await;
+        stage.write().load_to_read_buffer(rb_chunk);
+    }
+}
+
+/// Maps an [`Aggregate`] to the equivalent Read Buffer aggregate, if any.
+fn to_read_buffer_aggregate(agg: Aggregate) -> Option<read_buffer::AggregateType> {
+    match agg {
+        Aggregate::Sum => Some(read_buffer::AggregateType::Sum),
+        Aggregate::Count => Some(read_buffer::AggregateType::Count),
+        Aggregate::Min => Some(read_buffer::AggregateType::Min),
+        Aggregate::Max => Some(read_buffer::AggregateType::Max),
+        Aggregate::First => Some(read_buffer::AggregateType::First),
+        Aggregate::Last => Some(read_buffer::AggregateType::Last),
+        Aggregate::Mean | Aggregate::None => None,
+    }
+}
+
+/// Returns the schema of the results produced by the Read Buffer for
+/// `request` against a chunk with `schema`.
+fn read_aggregate_schema(
+    schema: &Schema,
+    request: &ChunkAggregateRequest,
+) -> Result<SchemaRef, QueryChunkError> {
+    let mut builder = SchemaBuilder::new();
+    for column in &request.group_columns {
+        builder.tag(column);
+    }
+
+    if request.window.is_some() {
+        builder.timestamp();
+    }
+
+    for (column, agg) in &request.aggregates {
+        let name = match to_read_buffer_aggregate(*agg) {
+            Some(rb_agg) => format!("{}_{}", column, rb_agg),
+            None => return Err(format!("aggregate {:?} not supported by read buffer", agg).into()),
+        };
+
+        if *agg == Aggregate::Count {
+            builder.field(&name, DataType::UInt64);
+        } else {
+            let idx = schema
+                .find_index_of(column)
+                .ok_or_else(|| format!("column '{}' not found", column))?;
+            builder.field(&name, schema.field(idx).1.data_type().clone());
+        }
+
+        // first/last values are followed by the time they were selected at
+        if matches!(agg, Aggregate::First | Aggregate::Last) {
+            builder.field(&format!("{}_time", name), TIME_DATA_TYPE());
+        }
+    }
+
+    Ok(builder.build()?.into())
+}
+
 #[derive(Debug)]
 struct ReadBufferPredicateConversionError {
     msg: String,
@@ -376,12 +544,6 @@ impl std::fmt::Display for ReadBufferPredicateConversionError {
 
 impl std::error::Error for ReadBufferPredicateConversionError {}
 
-impl From<ReadBufferPredicateConversionError> for ArrowError {
-    fn from(e: ReadBufferPredicateConversionError) -> Self {
-        Self::ExternalError(Box::new(e))
-    }
-}
-
 /// Converts a [`predicate::Predicate`] into [`read_buffer::Predicate`], suitable for evaluating on
 /// the ReadBuffer.
 ///
@@ -418,21 +580,31 @@ fn to_read_buffer_predicate(
     }
 }
 
+/// Only delete predicates made of plain `=`/`!=` comparisons can be expressed as Read Buffer
+/// predicates. Any other delete predicate (`OR`, `IN`, regex or ordering comparisons) is skipped
+/// here. All delete predicates are applied again by the `FilterExec` that the query planner adds on
+/// top of every chunk with delete predicates, so skipping one never changes the query result.
+///
 /// NOTE: valid Read Buffer predicates are not guaranteed to be applicable to an arbitrary Read
 /// Buffer chunk, because the applicability of a predicate depends on the schema of the chunk.
 /// Callers should validate predicates against chunks they are to be executed against using
 /// `read_buffer::Chunk::validate_predicate`
 fn to_read_buffer_negated_predicates(
     delete_predicates: &[Arc<Predicate>],
-) -> Result<Vec<read_buffer::Predicate>, ReadBufferPredicateConversionError> {
-    let mut rb_preds: Vec<read_buffer::Predicate> = vec![];
-    for pred in delete_predicates {
-        let rb_pred = to_read_buffer_predicate(pred)?;
-        rb_preds.push(rb_pred);
-    }
+) -> Vec<read_buffer::Predicate> {
+    let rb_preds: Vec<read_buffer::Predicate> = delete_predicates
+        .iter()
+        .filter_map(|pred| match to_read_buffer_predicate(pred) {
+            Ok(rb_pred) => Some(rb_pred),
+            Err(e) => {
+                debug!(%e, "delete predicate not supported by the read buffer");
+                None
+            }
+        })
+        .collect();
 
     debug!(?rb_preds, "read buffer delete predicates");
-    Ok(rb_preds)
+    rb_preds
 }
 
 /// Adapter which will take a ReadFilterResults and make it an async stream
@@ -1,19 +1,27 @@
 //! Database for the querier that contains all namespaces.
 
 use crate::{
-    cache::CatalogCache, chunk::ChunkAdapter, ingester::IngesterConnection,
-    namespace::QuerierNamespace, query_log::QueryLog, table::PruneMetrics,
+    cache::CatalogCache,
+    chunk::ChunkAdapter,
+    ingester::{write_token::wait_until_readable, IngesterConnection},
+    namespace::QuerierNamespace,
+    query_history::QueryHistory,
+    query_log::QueryLog,
+    query_quota::QueryQuotas,
+    table::PruneMetrics,
 };
 use async_trait::async_trait;
 use backoff::{Backoff, BackoffConfig};
 use data_types::{Namespace, ShardIndex};
 use iox_catalog::interface::Catalog;
-use iox_query::exec::Executor;
+use iox_query::{
+    exec::Executor, KillQueryError, QueryAdmission, QueryQuotaExceeded, WriteWaitError,
+};
 use parquet_file::storage::ParquetStorage;
 use service_common::QueryDatabaseProvider;
 use sharder::JumpHash;
 use snafu::Snafu;
-use std::{collections::BTreeSet, sync::Arc};
+use std::{collections::BTreeSet, sync::Arc, time::Duration};
 use trace::span::{Span, SpanRecorder};
 use tracker::{
     AsyncSemaphoreMetrics, InstrumentedAsyncOwnedSemaphorePermit, InstrumentedAsyncSemaphore,
@@ -62,6 +70,12 @@ pub struct QuerierDatabase {
     /// Query log.
     query_log: Arc<QueryLog>,
 
+    /// Durable query history, if enabled.
+    query_history: Option<Arc<QueryHistory>>,
+
+    /// Running queries by namespace, to enforce the namespace query limits.
+    query_quotas: Arc<QueryQuotas>,
+
     /// Semaphore that limits the number of namespaces in used at the time by the query subsystem.
     ///
     /// This should be a 1-to-1 relation to the number of active queries.
@@ -87,12 +101,81 @@ impl QueryDatabaseProvider for QuerierDatabase {
         self.namespace(name, span).await
     }
 
+    async fn admit_query(
+        &self,
+        name: &str,
+        span: Option<Span>,
+    ) -> Result<QueryAdmission, QueryQuotaExceeded> {
+        // Unknown namespaces are reported once the query asks for them.
+        let ns = match self
+            .catalog_cache
+            .namespace()
+            .get(Arc::from(name), &[], span)
+            .await
+        {
+            Some(ns) => ns,
+            None => return Ok(QueryAdmission::default()),
+        };
+
+        let slot = self.query_quotas.admit(ns.id, name, &ns.query_limits)?;
+        Ok(QueryAdmission::new(slot))
+    }
+
     async fn acquire_semaphore(&self, span: Option<Span>) -> InstrumentedAsyncOwnedSemaphorePermit {
         Arc::clone(&self.query_execution_semaphore)
             .acquire_owned(span)
             .await
             .expect("Semaphore should not be closed by anyone")
     }
+
+    async fn wait_for_write(
+        &self,
+        name: &str,
+        write_token: &str,
+        timeout: Duration,
+        span: Option<Span>,
+    ) -> Result<(), WriteWaitError> {
+        let mut span_recorder = SpanRecorder::new(span);
+        let ingester_connection =
+            self.ingester_connection
+                .as_ref()
+                .ok_or_else(|| WriteWaitError::Unavailable {
+                    source: "no ingesters are configured".into(),
+                })?;
+
+        match wait_until_readable(ingester_connection.as_ref(), write_token, timeout).await {
+            Ok(waited) => {
+                // A write that only just became readable may have created
+                // tables and columns the cached namespace schema does not
+                // cover yet.
+                if waited {
+                    self.catalog_cache.namespace().expire(&Arc::from(name));
+                }
+                span_recorder.ok("readable");
+                Ok(())
+            }
+            Err(e) => {
+                span_recorder.error("not readable");
+                Err(e)
+            }
+        }
+    }
+
+    async fn kill_query(
+        &self,
+        name: &str,
+        query_id: u64,
+        span: Option<Span>,
+    ) -> Result<(), KillQueryError> {
+        let ns = self
+            .catalog_cache
+            .namespace()
+            .get(Arc::from(name), &[], span)
+            .await
+            .ok_or(KillQueryError::NotFound { query_id })?;
+
+        self.query_log.kill(query_id, Some(ns.id))
+    }
 }
 
 impl QuerierDatabase {
@@ -129,6 +212,7 @@ impl QuerierDatabase {
             Default::default(),
         ));
         let query_log = Arc::new(QueryLog::new(QUERY_LOG_SIZE, catalog_cache.time_provider()));
+        let query_quotas = Arc::new(QueryQuotas::new(&metric_registry));
         let semaphore_metrics = Arc::new(AsyncSemaphoreMetrics::new(
             &metric_registry,
             &[("semaphore", "query_execution")],
@@ -150,6 +234,8 @@ impl QuerierDatabase {
             exec,
             ingester_connection,
             query_log,
+            query_history: None,
+            query_quotas,
             query_execution_semaphore,
             sharder,
             max_table_query_bytes,
@@ -157,6 +243,14 @@ impl QuerierDatabase {
         })
     }
 
+    /// Write completed queries to the given durable history.
+    pub fn with_query_history(self, query_history: Arc<QueryHistory>) -> Self {
+        Self {
+            query_history: Some(query_history),
+            ..self
+        }
+    }
+
     /// Get namespace if it exists.
     ///
     /// This will await the internal namespace semaphore. Existence of namespaces is checked AFTER
@@ -181,12 +275,19 @@ impl QuerierDatabase {
             Arc::clone(&self.exec),
             self.ingester_connection.clone(),
             Arc::clone(&self.query_log),
+            self.query_history.clone(),
+            Arc::clone(&self.query_quotas),
             Arc::clone(&self.sharder),
             self.max_table_query_bytes,
             Arc::clone(&self.prune_metrics),
         )))
     }
 
+    /// Cancel the running query `query_id` of any namespace.
+    pub fn kill_any_query(&self, query_id: u64) -> Result<(), KillQueryError> {
+        self.query_log.kill(query_id, None)
+    }
+
     /// Return all namespaces this querier knows about
     pub async fn namespaces(&self) -> Vec<Namespace> {
         let catalog = &self.catalog_cache.catalog();
@@ -242,9 +343,13 @@ pub async fn create_sharder(
 mod tests {
     use super::*;
     use crate::create_ingester_connection_for_testing;
+    use data_types::NamespaceQueryLimits;
+    use iox_query::{exec::ExecutionContextProvider, QueryDatabase};
     use iox_tests::util::TestCatalog;
+    use predicate::rpc_predicate::QueryDatabaseMeta;
     use test_helpers::assert_error;
     use tokio::runtime::Handle;
+    use write_summary::WriteSummary;
 
     #[tokio::test]
     #[should_panic(
@@ -331,6 +436,71 @@ mod tests {
         assert!(db.namespace("ns2", None).await.is_none());
     }
 
+    #[tokio::test]
+    async fn test_admit_query() {
+        let catalog = TestCatalog::new();
+        // QuerierDatabase::new returns an error if there are no shards in the catalog
+        catalog.create_shard(0).await;
+
+        let catalog_cache = Arc::new(CatalogCache::new_testing(
+            catalog.catalog(),
+            catalog.time_provider(),
+            catalog.metric_registry(),
+            catalog.object_store(),
+            &Handle::current(),
+        ));
+        let db = QuerierDatabase::new(
+            catalog_cache,
+            catalog.metric_registry(),
+            ParquetStorage::new(catalog.object_store()),
+            catalog.exec(),
+            Some(create_ingester_connection_for_testing()),
+            QuerierDatabase::MAX_CONCURRENT_QUERIES_MAX,
+            usize::MAX,
+        )
+        .await
+        .unwrap();
+
+        catalog.create_namespace("ns1").await;
+        catalog
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_query_limits(
+                "ns1",
+                NamespaceQueryLimits {
+                    max_concurrent_queries: Some(1),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        // only one query of the namespace may run at a time
+        let admission = db.admit_query("ns1", None).await.unwrap();
+        let err = db.admit_query("ns1", None).await.unwrap_err();
+        assert!(
+            matches!(err, QueryQuotaExceeded::ConcurrentQueries { limit: 1, .. }),
+            "{err}"
+        );
+
+        // the rejected query was never logged
+        assert_eq!(db.query_log.entries().len(), 0);
+        let ns = db.namespace("ns1", None).await.unwrap();
+
+        // the slot is held by the query until it completes
+        let token = ns
+            .record_query(&ns.new_query_context(None), "sql", Box::new("SELECT 1"))
+            .with_admission(admission);
+        db.admit_query("ns1", None).await.unwrap_err();
+        drop(token);
+        db.admit_query("ns1", None).await.unwrap();
+
+        // unknown namespaces are reported by `db`
+        db.admit_query("ns2", None).await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_namespaces() {
         let catalog = TestCatalog::new();
@@ -365,4 +535,52 @@ mod tests {
         assert_eq!(namespaces[0].name, "ns1");
         assert_eq!(namespaces[1].name, "ns2");
     }
+
+    #[tokio::test]
+    async fn test_wait_for_write() {
+        let catalog = TestCatalog::new();
+        // QuerierDatabase::new returns an error if there are no shards in the catalog
+        catalog.create_shard(0).await;
+
+        let catalog_cache = Arc::new(CatalogCache::new_testing(
+            catalog.catalog(),
+            catalog.time_provider(),
+            catalog.metric_registry(),
+            catalog.object_store(),
+            &Handle::current(),
+        ));
+        let db = QuerierDatabase::new(
+            catalog_cache,
+            catalog.metric_registry(),
+            ParquetStorage::new(catalog.object_store()),
+            catalog.exec(),
+            Some(create_ingester_connection_for_testing()),
+            QuerierDatabase::MAX_CONCURRENT_QUERIES_MAX,
+            usize::MAX,
+        )
+        .await
+        .unwrap();
+
+        let ns = catalog.create_namespace("ns1").await;
+        async fn table_names(db: &QuerierDatabase) -> Vec<String> {
+            db.namespace("ns1", None).await.unwrap().table_names()
+        }
+        assert!(table_names(&db).await.is_empty());
+
+        // The write created a table the cached namespace does not cover.
+        ns.create_table("table").await;
+        assert!(table_names(&db).await.is_empty());
+
+        let token = WriteSummary::default().to_token();
+        db.wait_for_write("ns1", &token, Duration::from_secs(10), None)
+            .await
+            .unwrap();
+        assert_eq!(table_names(&db).await, vec!["table".to_string()]);
+
+        let err = db
+            .wait_for_write("ns1", "not a token", Duration::from_secs(10), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WriteWaitError::InvalidToken { .. }));
+    }
 }
@@ -59,6 +59,9 @@ pub struct FlightClientImpl {
     /// for a very short period of time, and any actual connection (and
     /// waiting) is done in CachedConnection
     connections: parking_lot::Mutex<HashMap<String, CachedConnection>>,
+
+    /// Builder of new connections.
+    connection_builder: connection::Builder,
 }
 
 impl FlightClientImpl {
@@ -67,6 +70,15 @@ impl FlightClientImpl {
         Self::default()
     }
 
+    /// Create new client that connects using the given builder, e.g. to
+    /// configure TLS.
+    pub fn new_with_connection_builder(connection_builder: connection::Builder) -> Self {
+        Self {
+            connections: Default::default(),
+            connection_builder,
+        }
+    }
+
     /// Establish connection to given addr and perform handshake.
     async fn connect(&self, ingester_address: Arc<str>) -> Result<Connection, Error> {
         let cached_connection = {
@@ -75,7 +87,8 @@ impl FlightClientImpl {
                 cached_connection.clone()
             } else {
                 // need to make a new one;
-                let cached_connection = CachedConnection::new(&ingester_address);
+                let cached_connection =
+                    CachedConnection::new(&ingester_address, &self.connection_builder);
                 connections.insert(ingester_address.to_string(), cached_connection.clone());
                 cached_connection
             }
@@ -140,14 +153,16 @@ impl QueryData for PerformQuery<proto::IngesterQueryResponseMetadata> {
 #[derive(Debug, Clone)]
 struct CachedConnection {
     ingester_address: Arc<str>,
+    connection_builder: connection::Builder,
     /// Real async mutex to
     maybe_connection: Arc<tokio::sync::Mutex<Option<Connection>>>,
 }
 
 impl CachedConnection {
-    fn new(ingester_address: &Arc<str>) -> Self {
+    fn new(ingester_address: &Arc<str>, connection_builder: &connection::Builder) -> Self {
         Self {
             ingester_address: Arc::clone(ingester_address),
+            connection_builder: connection_builder.clone(),
             maybe_connection: Arc::new(tokio::sync::Mutex::new(None)),
         }
     }
@@ -165,7 +180,9 @@ impl CachedConnection {
         } else {
             debug!(%ingester_address, "Connecting to ingester");
 
-            let connection = connection::Builder::new()
+            let connection = self
+                .connection_builder
+                .clone()
                 .build(ingester_address)
                 .await
                 .context(ConnectingSnafu { ingester_address })?;
@@ -42,6 +42,7 @@ use trace::span::{Span, SpanRecorder};
 
 pub(crate) mod flight_client;
 pub(crate) mod test_util;
+pub(crate) mod write_token;
 
 #[derive(Debug, Snafu)]
 #[allow(missing_copy_implementations, missing_docs)]
@@ -138,9 +139,12 @@ pub enum Error {
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 /// Create a new set of connections given a map of shard indexes to Ingester configurations
+///
+/// Connections to the ingesters are established with `connection_builder`.
 pub fn create_ingester_connections_by_shard(
     shard_to_ingesters: HashMap<ShardIndex, IngesterMapping>,
     catalog_cache: Arc<CatalogCache>,
+    connection_builder: connection::Builder,
 ) -> Arc<dyn IngesterConnection> {
     Arc::new(IngesterConnectionImpl::by_shard(
         shard_to_ingesters,
@@ -151,6 +155,7 @@ pub fn create_ingester_connections_by_shard(
             base: 3.0,
             deadline: Some(Duration::from_secs(10)),
         },
+        connection_builder,
     ))
 }
 
@@ -316,6 +321,7 @@ pub struct IngesterConnectionImpl {
     catalog_cache: Arc<CatalogCache>,
     metrics: Arc<IngesterConnectionMetrics>,
     backoff_config: BackoffConfig,
+    connection_builder: connection::Builder,
 }
 
 impl IngesterConnectionImpl {
@@ -334,17 +340,25 @@ impl IngesterConnectionImpl {
     ///   }
     /// }
     /// ```
+    ///
+    /// Connections are established with `connection_builder`.
     pub fn by_shard(
         shard_to_ingesters: HashMap<ShardIndex, IngesterMapping>,
         catalog_cache: Arc<CatalogCache>,
         backoff_config: BackoffConfig,
+        connection_builder: connection::Builder,
     ) -> Self {
-        Self::by_shard_with_flight_client(
-            shard_to_ingesters,
-            Arc::new(FlightClientImpl::new()),
-            catalog_cache,
-            backoff_config,
-        )
+        Self {
+            connection_builder: connection_builder.clone(),
+            ..Self::by_shard_with_flight_client(
+                shard_to_ingesters,
+                Arc::new(FlightClientImpl::new_with_connection_builder(
+                    connection_builder,
+                )),
+                catalog_cache,
+                backoff_config,
+            )
+        }
     }
 
     /// Create new set of connections with specific flight client implementation.
@@ -376,6 +390,7 @@ impl IngesterConnectionImpl {
             catalog_cache,
             metrics,
             backoff_config,
+            connection_builder: connection::Builder::new(),
         }
     }
 }
@@ -818,7 +833,9 @@ impl IngesterConnection for IngesterConnectionImpl {
         let responses = self
             .unique_ingester_addresses
             .iter()
-            .map(|ingester_address| execute_get_write_infos(ingester_address, write_token))
+            .map(|ingester_address| {
+                execute_get_write_infos(&self.connection_builder, ingester_address, write_token)
+            })
             .collect::<FuturesUnordered<_>>()
             .try_collect::<Vec<_>>()
             .await?;
@@ -832,10 +849,12 @@ impl IngesterConnection for IngesterConnectionImpl {
 }
 
 async fn execute_get_write_infos(
+    connection_builder: &connection::Builder,
     ingester_address: &str,
     write_token: &str,
 ) -> Result<GetWriteInfoResponse, Error> {
-    let connection = connection::Builder::new()
+    let connection = connection_builder
+        .clone()
         .build(ingester_address)
         .await
         .context(ConnectingSnafu { ingester_address })?;
@@ -3,13 +3,14 @@ use async_trait::async_trait;
 use data_types::ShardIndex;
 use generated_types::influxdata::iox::ingester::v1::GetWriteInfoResponse;
 use parking_lot::Mutex;
-use std::{any::Any, sync::Arc};
+use std::{any::Any, collections::VecDeque, sync::Arc};
 use trace::span::Span;
 
 /// IngesterConnection for testing
 #[derive(Debug, Default)]
 pub struct MockIngesterConnection {
     next_response: Mutex<Option<super::Result<Vec<super::IngesterPartition>>>>,
+    write_infos: Mutex<VecDeque<GetWriteInfoResponse>>,
 }
 
 impl MockIngesterConnection {
@@ -23,6 +24,14 @@ impl MockIngesterConnection {
     pub fn next_response(&self, response: super::Result<Vec<super::IngesterPartition>>) {
         *self.next_response.lock() = Some(response);
     }
+
+    /// Queue a write info response for this connection.
+    ///
+    /// The last queued response is returned for all further requests.
+    #[allow(dead_code)]
+    pub fn next_write_info(&self, response: GetWriteInfoResponse) {
+        self.write_infos.lock().push_back(response);
+    }
 }
 
 #[async_trait]
@@ -44,7 +53,13 @@ impl IngesterConnection for MockIngesterConnection {
     }
 
     async fn get_write_info(&self, _write_token: &str) -> super::Result<GetWriteInfoResponse> {
-        unimplemented!()
+        let mut write_infos = self.write_infos.lock();
+        let response = if write_infos.len() > 1 {
+            write_infos.pop_front()
+        } else {
+            write_infos.front().cloned()
+        };
+        Ok(response.unwrap_or_default())
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -0,0 +1,131 @@
+//! Waiting for the ingesters to make a write readable.
+
+use super::IngesterConnection;
+use generated_types::influxdata::iox::ingester::v1::ShardStatus;
+use iox_query::WriteWaitError;
+use observability_deps::tracing::debug;
+use std::time::Duration;
+use write_summary::WriteSummary;
+
+/// Delay before the first re-poll of the status of a write.
+const INIT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Maximum delay between two polls of the status of a write.
+const MAX_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Wait up to `timeout` until the ingesters report every shard the write
+/// identified by `write_token` was sequenced to as readable.
+///
+/// Returns whether the write was not readable yet when first polled.
+pub(crate) async fn wait_until_readable(
+    connection: &dyn IngesterConnection,
+    write_token: &str,
+    timeout: Duration,
+) -> Result<bool, WriteWaitError> {
+    WriteSummary::try_from_token(write_token)
+        .map_err(|reason| WriteWaitError::InvalidToken { reason })?;
+
+    let poll = async {
+        let mut interval = INIT_POLL_INTERVAL;
+        let mut waited = false;
+        loop {
+            let response = connection.get_write_info(write_token).await.map_err(|e| {
+                WriteWaitError::Unavailable {
+                    source: Box::new(e),
+                }
+            })?;
+
+            let pending = response
+                .shard_infos
+                .iter()
+                .filter(|info| {
+                    !matches!(
+                        info.status(),
+                        ShardStatus::Readable | ShardStatus::Persisted
+                    )
+                })
+                .count();
+            if pending == 0 {
+                return Ok(waited);
+            }
+
+            debug!(%write_token, pending, "waiting for write to become readable");
+            tokio::time::sleep(interval).await;
+            interval = (interval * 2).min(MAX_POLL_INTERVAL);
+            waited = true;
+        }
+    };
+
+    tokio::time::timeout(timeout, poll)
+        .await
+        .map_err(|_| WriteWaitError::Timeout { timeout })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingester::MockIngesterConnection;
+    use generated_types::influxdata::iox::ingester::v1::{GetWriteInfoResponse, ShardInfo};
+
+    fn write_info(statuses: &[ShardStatus]) -> GetWriteInfoResponse {
+        GetWriteInfoResponse {
+            shard_infos: statuses
+                .iter()
+                .enumerate()
+                .map(|(shard_index, status)| ShardInfo {
+                    shard_index: shard_index as i32,
+                    status: (*status).into(),
+                })
+                .collect(),
+        }
+    }
+
+    fn token() -> String {
+        WriteSummary::default().to_token()
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_readable() {
+        let connection = MockIngesterConnection::new();
+        connection.next_write_info(write_info(&[ShardStatus::Durable, ShardStatus::Readable]));
+        connection.next_write_info(write_info(&[ShardStatus::Unknown, ShardStatus::Readable]));
+        connection.next_write_info(write_info(&[ShardStatus::Persisted, ShardStatus::Readable]));
+
+        let waited = wait_until_readable(&connection, &token(), Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert!(waited);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_readable_already_readable() {
+        let connection = MockIngesterConnection::new();
+        connection.next_write_info(write_info(&[ShardStatus::Persisted, ShardStatus::Readable]));
+
+        let waited = wait_until_readable(&connection, &token(), Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert!(!waited);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_readable_timeout() {
+        let connection = MockIngesterConnection::new();
+        connection.next_write_info(write_info(&[ShardStatus::Readable, ShardStatus::Durable]));
+
+        let err = wait_until_readable(&connection, &token(), Duration::from_millis(50))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WriteWaitError::Timeout { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_readable_invalid_token() {
+        let connection = MockIngesterConnection::new();
+
+        let err = wait_until_readable(&connection, "not a token", Duration::from_secs(10))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WriteWaitError::InvalidToken { .. }));
+    }
+}
@@ -16,7 +16,9 @@ mod handler;
 mod ingester;
 mod namespace;
 mod poison;
+pub mod query_history;
 mod query_log;
+mod query_quota;
 mod server;
 mod system_tables;
 mod table;
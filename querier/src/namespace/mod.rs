@@ -4,7 +4,9 @@ use crate::{
     cache::{namespace::CachedNamespace, CatalogCache},
     chunk::ChunkAdapter,
     ingester::IngesterConnection,
+    query_history::QueryHistory,
     query_log::QueryLog,
+    query_quota::QueryQuotas,
     table::{PruneMetrics, QuerierTable, QuerierTableArgs},
     QuerierChunkLoadSetting,
 };
@@ -37,6 +39,9 @@ pub struct QuerierNamespace {
     /// Name of this namespace.
     name: Arc<str>,
 
+    /// Cached schema of this namespace.
+    ns: Arc<CachedNamespace>,
+
     /// Tables in this namespace.
     tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>,
 
@@ -48,6 +53,13 @@ pub struct QuerierNamespace {
 
     /// Query log.
     query_log: Arc<QueryLog>,
+
+    /// Durable query history, if enabled.
+    query_history: Option<Arc<QueryHistory>>,
+
+    /// Running queries by namespace, to enforce the query limits of this
+    /// namespace.
+    query_quotas: Arc<QueryQuotas>,
 }
 
 impl QuerierNamespace {
@@ -60,6 +72,8 @@ impl QuerierNamespace {
         exec: Arc<Executor>,
         ingester_connection: Option<Arc<dyn IngesterConnection>>,
         query_log: Arc<QueryLog>,
+        query_history: Option<Arc<QueryHistory>>,
+        query_quotas: Arc<QueryQuotas>,
         sharder: Arc<JumpHash<Arc<ShardIndex>>>,
         max_table_query_bytes: usize,
         prune_metrics: Arc<PruneMetrics>,
@@ -79,6 +93,8 @@ impl QuerierNamespace {
                     exec: Arc::clone(&exec),
                     max_query_bytes: max_table_query_bytes,
                     prune_metrics: Arc::clone(&prune_metrics),
+                    query_limits: ns.query_limits,
+                    query_quotas: Arc::clone(&query_quotas),
                 }));
 
                 (Arc::clone(table_name), table)
@@ -90,10 +106,13 @@ impl QuerierNamespace {
         Self {
             id,
             name,
+            ns,
             tables: Arc::new(tables),
             exec,
             catalog_cache: Arc::clone(chunk_adapter.catalog_cache()),
             query_log,
+            query_history,
+            query_quotas,
         }
     }
 
@@ -120,6 +139,7 @@ impl QuerierNamespace {
         ));
         let query_log = Arc::new(QueryLog::new(10, time_provider));
         let prune_metrics = Arc::new(PruneMetrics::new(&chunk_adapter.metric_registry()));
+        let query_quotas = Arc::new(QueryQuotas::new(&chunk_adapter.metric_registry()));
 
         Self::new(
             chunk_adapter,
@@ -128,6 +148,8 @@ impl QuerierNamespace {
             exec,
             ingester_connection,
             query_log,
+            None,
+            query_quotas,
             sharder,
             max_table_query_bytes,
             prune_metrics,
@@ -1,13 +1,14 @@
 //! This module contains implementations of [`iox_query`] interfaces for [QuerierNamespace].
 
 use crate::{
+    cache::{namespace::CachedNamespace, CatalogCache},
     namespace::QuerierNamespace,
+    query_history::CompletedQuery,
     query_log::QueryLog,
     system_tables::{SystemSchemaProvider, SYSTEM_SCHEMA},
     table::QuerierTable,
 };
 use async_trait::async_trait;
-use data_types::NamespaceId;
 use datafusion::{
     catalog::{catalog::CatalogProvider, schema::SchemaProvider},
     datasource::TableProvider,
@@ -54,9 +55,10 @@ impl QueryDatabase for QuerierNamespace {
         };
 
         let mut chunks = table
-            .chunks(
+            .chunks_with_stats(
                 predicate,
                 ctx.span().map(|span| span.child("querier table chunks")),
+                ctx.stats(),
             )
             .await?;
 
@@ -83,11 +85,29 @@ impl QueryDatabase for QuerierNamespace {
         query_text: QueryText,
     ) -> QueryCompletedToken {
         // When the query token is dropped the query entry's completion time
-        // will be set.
+        // will be set, and the query is added to the durable history.
         let query_log = Arc::clone(&self.query_log);
         let trace_id = ctx.span().map(|s| s.ctx.trace_id);
-        let entry = query_log.push(self.id, query_type, query_text, trace_id);
-        QueryCompletedToken::new(move |success| query_log.set_completed(entry, success))
+        let (entry, registration) = query_log.push(self.id, query_type, query_text, trace_id);
+        let query_id = entry.id();
+        let history = self.query_history.clone();
+        let name = Arc::clone(&self.name);
+        let stats = Arc::clone(ctx.stats());
+
+        let query_quotas = Arc::clone(&self.query_quotas);
+        let mut token = QueryCompletedToken::new(move |success| {
+            query_log.set_completed(Arc::clone(&entry), success);
+            if let Some(history) = history {
+                history.record(CompletedQuery::new(&name, &entry, &stats));
+            }
+        })
+        .with_query_id(query_id)
+        .with_registration(registration)
+        .with_limit_observer(move |limit| query_quotas.record_exceeded(limit));
+        if let Some(timeout) = self.ns.query_limits.max_query_duration() {
+            token = token.with_timeout(timeout);
+        }
+        token
     }
 
     fn as_meta(&self) -> &dyn QueryDatabaseMeta {
@@ -96,22 +116,26 @@ impl QueryDatabase for QuerierNamespace {
 }
 
 pub struct QuerierCatalogProvider {
-    /// Namespace ID.
-    namespace_id: NamespaceId,
+    /// Cached schema of the namespace.
+    namespace: Arc<CachedNamespace>,
 
     /// A snapshot of all tables.
     tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>,
 
     /// Query log.
     query_log: Arc<QueryLog>,
+
+    /// Catalog cache.
+    catalog_cache: Arc<CatalogCache>,
 }
 
 impl QuerierCatalogProvider {
     fn from_namespace(namespace: &QuerierNamespace) -> Self {
         Self {
-            namespace_id: namespace.id,
+            namespace: Arc::clone(&namespace.ns),
             tables: Arc::clone(&namespace.tables),
             query_log: Arc::clone(&namespace.query_log),
+            catalog_cache: Arc::clone(&namespace.catalog_cache),
         }
     }
 }
@@ -132,7 +156,9 @@ impl CatalogProvider for QuerierCatalogProvider {
             })),
             SYSTEM_SCHEMA => Some(Arc::new(SystemSchemaProvider::new(
                 Arc::clone(&self.query_log),
-                self.namespace_id,
+                Arc::clone(&self.namespace),
+                Arc::clone(&self.tables),
+                Arc::clone(&self.catalog_cache),
             ))),
             _ => None,
         }
@@ -181,11 +207,15 @@ impl SchemaProvider for UserSchemaProvider {
 
 impl ExecutionContextProvider for QuerierNamespace {
     fn new_query_context(&self, span_ctx: Option<SpanContext>) -> IOxSessionContext {
-        self.exec
+        let mut config = self
+            .exec
             .new_execution_config(ExecutorType::Query)
             .with_default_catalog(Arc::new(QuerierCatalogProvider::from_namespace(self)) as _)
-            .with_span_context(span_ctx)
-            .build()
+            .with_span_context(span_ctx);
+        if let Some(max_memory) = self.ns.query_limits.max_query_memory_bytes() {
+            config = config.with_memory_limit(max_memory);
+        }
+        config.build()
     }
 }
 
@@ -197,7 +227,7 @@ mod tests {
     };
     use arrow::record_batch::RecordBatch;
     use arrow_util::{assert_batches_eq, assert_batches_sorted_eq};
-    use data_types::ColumnType;
+    use data_types::{ColumnType, NamespaceQueryLimits};
     use datafusion::common::DataFusionError;
     use iox_query::frontend::sql::SqlQueryPlanner;
     use iox_tests::util::{TestCatalog, TestParquetFileBuilder};
@@ -630,6 +660,179 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_query_limits() {
+        let catalog = TestCatalog::new();
+
+        let ns = catalog.create_namespace("ns").await;
+        let table = ns.create_table("table").await;
+        let shard = ns.create_shard(1).await;
+        let partition = table.with_shard(&shard).create_partition("k").await;
+
+        table.create_column("time", ColumnType::Time).await;
+        table.create_column("foo", ColumnType::F64).await;
+
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol("table foo=1 11")
+            .with_max_seq(2)
+            .with_min_time(11)
+            .with_max_time(11);
+        partition.create_parquet_file(builder).await;
+
+        catalog
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_query_limits(
+                "ns",
+                NamespaceQueryLimits {
+                    max_query_scanned_bytes: Some(1),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let querier_namespace = Arc::new(querier_namespace(&ns).await);
+
+        // scanning more than allowed fails during planning
+        let err = run_res(&querier_namespace, "SELECT * FROM \"table\"", None)
+            .await
+            .unwrap_err();
+        match err {
+            RunError::Build { source } => assert!(
+                iox_query::is_quota_exceeded(&source),
+                "unexpected error: {source}"
+            ),
+            e => panic!("unexpected error: {e}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_system_tables() {
+        let catalog = TestCatalog::new();
+
+        let ns = catalog.create_namespace("ns").await;
+        let shard = ns.create_shard(1).await;
+        let table = ns.create_table("cpu").await;
+        table.create_column("host", ColumnType::Tag).await;
+        table.create_column("time", ColumnType::Time).await;
+        table.create_column("load", ColumnType::F64).await;
+
+        let partition = table
+            .with_shard(&shard)
+            .create_partition_with_sort_key("a", &["host", "time"])
+            .await;
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol("cpu,host=a load=1 11\ncpu,host=b load=2 22")
+            .with_max_seq(1)
+            .with_min_time(11)
+            .with_max_time(22);
+        partition.create_parquet_file(builder).await;
+        table
+            .with_shard(&shard)
+            .create_tombstone(2, 1, 13, "host=a")
+            .await;
+
+        let querier_namespace = Arc::new(querier_namespace(&ns).await);
+
+        assert_query(
+            &querier_namespace,
+            "SELECT table_name, column_count FROM system.tables",
+            &[
+                "+------------+--------------+",
+                "| table_name | column_count |",
+                "+------------+--------------+",
+                "| cpu        | 3            |",
+                "+------------+--------------+",
+            ],
+        )
+        .await;
+
+        assert_query(
+            &querier_namespace,
+            "SELECT table_name, column_name, column_type FROM system.columns",
+            &[
+                "+------------+-------------+-------------+",
+                "| table_name | column_name | column_type |",
+                "+------------+-------------+-------------+",
+                "| cpu        | host        | tag         |",
+                "| cpu        | load        | f64         |",
+                "| cpu        | time        | time        |",
+                "+------------+-------------+-------------+",
+            ],
+        )
+        .await;
+
+        assert_query(
+            &querier_namespace,
+            "SELECT table_name, partition_key, sort_key FROM system.partitions",
+            &[
+                "+------------+---------------+-----------+",
+                "| table_name | partition_key | sort_key  |",
+                "+------------+---------------+-----------+",
+                "| cpu        | a             | host,time |",
+                "+------------+---------------+-----------+",
+            ],
+        )
+        .await;
+
+        assert_query(
+            &querier_namespace,
+            "SELECT table_name, max_sequence_number, compaction_level, row_count, min_time, max_time \
+            FROM system.parquet_files",
+            &[
+                "+------------+---------------------+------------------+-----------+-------------------------------+-------------------------------+",
+                "| table_name | max_sequence_number | compaction_level | row_count | min_time                      | max_time                      |",
+                "+------------+---------------------+------------------+-----------+-------------------------------+-------------------------------+",
+                "| cpu        | 1                   | 0                | 2         | 1970-01-01T00:00:00.000000011 | 1970-01-01T00:00:00.000000022 |",
+                "+------------+---------------------+------------------+-----------+-------------------------------+-------------------------------+",
+            ],
+        )
+        .await;
+
+        assert_query(
+            &querier_namespace,
+            "SELECT table_name, sequence_number, min_time, max_time, predicate FROM system.tombstones",
+            &[
+                "+------------+-----------------+-------------------------------+-------------------------------+-----------+",
+                "| table_name | sequence_number | min_time                      | max_time                      | predicate |",
+                "+------------+-----------------+-------------------------------+-------------------------------+-----------+",
+                "| cpu        | 2               | 1970-01-01T00:00:00.000000001 | 1970-01-01T00:00:00.000000013 | host=a    |",
+                "+------------+-----------------+-------------------------------+-------------------------------+-----------+",
+            ],
+        )
+        .await;
+
+        // the mock ingester connection has no unpersisted data
+        assert_query(
+            &querier_namespace,
+            "SELECT count(*) AS partitions FROM system.ingester_partitions",
+            &[
+                "+------------+",
+                "| partitions |",
+                "+------------+",
+                "| 0          |",
+                "+------------+",
+            ],
+        )
+        .await;
+
+        // the parquet file cache was used to scan system.parquet_files
+        assert_query(
+            &querier_namespace,
+            "SELECT cache, misses > 0 AS missed FROM system.cache_stats WHERE cache = 'parquet_file'",
+            &[
+                "+--------------+--------+",
+                "| cache        | missed |",
+                "+--------------+--------+",
+                "| parquet_file | true   |",
+                "+--------------+--------+",
+            ],
+        )
+        .await;
+    }
+
     async fn assert_query(
         querier_namespace: &Arc<QuerierNamespace>,
         sql: &str,
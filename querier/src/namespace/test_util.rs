@@ -24,7 +24,15 @@ pub async fn querier_namespace_with_limit(
     let schema = get_schema_by_name(&ns.namespace.name, repos.as_mut())
         .await
         .unwrap();
-    let cached_ns = Arc::new(CachedNamespace::from(&schema));
+    let namespace = repos
+        .namespaces()
+        .get_by_name(&ns.namespace.name)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut cached_ns = CachedNamespace::from(&schema);
+    cached_ns.query_limits = namespace.query_limits;
+    let cached_ns = Arc::new(cached_ns);
 
     let catalog_cache = Arc::new(QuerierCatalogCache::new_testing(
         ns.catalog.catalog(),
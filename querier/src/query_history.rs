@@ -0,0 +1,461 @@
+//! Durable history of the queries run by this querier.
+//!
+//! Completed queries are written as line protocol into a table of a
+//! configurable namespace, via the regular write path of a router, so the
+//! history outlives the in-memory [`QueryLog`](crate::query_log::QueryLog)
+//! and can be queried like any other data.
+//!
+//! Entries are buffered in a bounded queue and written in batches by a
+//! background task. Recording an entry never waits: if the queue is full,
+//! because the router is slow or unavailable, the entry is dropped and
+//! counted.
+
+use crate::query_log::QueryLogEntry;
+use async_trait::async_trait;
+use influxdb_line_protocol::LineProtocolBuilder;
+use iox_query::exec::query_stats::QueryStats;
+use metric::U64Counter;
+use observability_deps::tracing::{debug, warn};
+use std::{fmt::Debug, sync::Arc, time::Duration};
+use tokio::{sync::mpsc, time::Instant};
+
+/// The table the query history is written to.
+pub const QUERY_HISTORY_TABLE: &str = "queries";
+
+/// Error returned by a [`QueryHistoryWriter`].
+pub type WriteError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Configuration of the query history.
+#[derive(Debug, Clone)]
+pub struct QueryHistoryConfig {
+    /// Namespace the history is written to.
+    pub namespace: String,
+
+    /// Identifier of this querier, written as the `querier` tag of every entry
+    /// so that entries of different queriers with the same timestamp do not
+    /// overwrite each other.
+    pub querier_id: String,
+
+    /// Maximum number of entries written at once.
+    pub batch_size: usize,
+
+    /// Maximum time an entry is buffered before it is written.
+    pub flush_interval: Duration,
+
+    /// Number of entries that can be buffered before new entries are dropped.
+    pub queue_size: usize,
+}
+
+/// Writes line protocol into a namespace.
+#[async_trait]
+pub trait QueryHistoryWriter: Debug + Send + Sync + 'static {
+    /// Writes `lp` into `namespace`.
+    async fn write(&self, namespace: &str, lp: String) -> Result<(), WriteError>;
+}
+
+/// Writes the query history through the gRPC write API of a router.
+///
+/// The router is connected to on the first write, so that an unavailable
+/// router does not prevent the querier from starting.
+#[derive(Debug)]
+pub struct RouterWriter {
+    router_address: String,
+    connection_builder: client_util::connection::Builder,
+    client: tokio::sync::Mutex<Option<influxdb_iox_client::write::Client>>,
+}
+
+impl RouterWriter {
+    /// Creates a writer for the router at `router_address`, connecting with
+    /// `connection_builder`.
+    pub fn new(
+        router_address: impl Into<String>,
+        connection_builder: client_util::connection::Builder,
+    ) -> Self {
+        Self {
+            router_address: router_address.into(),
+            connection_builder,
+            client: Default::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl QueryHistoryWriter for RouterWriter {
+    async fn write(&self, namespace: &str, lp: String) -> Result<(), WriteError> {
+        let mut client = {
+            let mut guard = self.client.lock().await;
+            match guard.as_ref() {
+                Some(client) => client.clone(),
+                None => {
+                    let connection = self
+                        .connection_builder
+                        .clone()
+                        .build(self.router_address.as_str())
+                        .await?;
+                    let client = influxdb_iox_client::write::Client::new(connection);
+                    *guard = Some(client.clone());
+                    client
+                }
+            }
+        };
+
+        client.write_lp(namespace, lp, 0).await?;
+        Ok(())
+    }
+}
+
+/// A completed query, as written to the history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletedQuery {
+    /// Name of the namespace the query ran against.
+    pub namespace: String,
+
+    /// The type of query.
+    pub query_type: String,
+
+    /// The text of the query.
+    pub query_text: String,
+
+    /// The trace ID if any, as hex.
+    pub trace_id: Option<String>,
+
+    /// Time at which the query was run, in nanoseconds since the epoch.
+    pub issue_time_nanos: i64,
+
+    /// How long the query took to complete.
+    pub duration: Duration,
+
+    /// If the query completed successfully.
+    pub success: bool,
+
+    /// Rows returned to the client.
+    pub rows_returned: u64,
+
+    /// Estimated bytes of the chunks scanned.
+    pub bytes_scanned: u64,
+
+    /// Parquet files scanned.
+    pub files_scanned: u64,
+
+    /// Partitions scanned.
+    pub partitions_scanned: u64,
+
+    /// Upper bound of the memory used while executing the query.
+    pub memory_bytes: u64,
+}
+
+impl CompletedQuery {
+    /// Creates the history entry of a completed query of `namespace`.
+    pub fn new(namespace: &str, entry: &QueryLogEntry, stats: &QueryStats) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            query_type: entry.query_type.clone(),
+            query_text: entry.query_text.to_string(),
+            trace_id: entry.trace_id.map(|id| format!("{:x}", id.get())),
+            issue_time_nanos: entry.issue_time.timestamp_nanos(),
+            duration: entry.query_completed_duration().unwrap_or_default(),
+            success: entry.success(),
+            rows_returned: stats.rows_returned(),
+            bytes_scanned: stats.bytes_scanned(),
+            files_scanned: stats.files_scanned(),
+            partitions_scanned: stats.partitions_scanned(),
+            memory_bytes: stats.memory_bytes(),
+        }
+    }
+}
+
+/// Renders `queries` run by the querier `querier_id` as line protocol.
+fn to_line_protocol(querier_id: &str, queries: &[CompletedQuery]) -> String {
+    let lp = queries
+        .iter()
+        .fold(LineProtocolBuilder::new(), |builder, q| {
+            let builder = builder
+                .measurement(QUERY_HISTORY_TABLE)
+                .tag("namespace", &q.namespace)
+                .tag("querier", querier_id)
+                .tag("query_type", &q.query_type)
+                .tag("success", if q.success { "true" } else { "false" })
+                .field("query_text", q.query_text.as_str());
+            let builder = match &q.trace_id {
+                Some(trace_id) => builder.field("trace_id", trace_id.as_str()),
+                None => builder,
+            };
+            builder
+                .field("duration_ns", q.duration.as_nanos() as u64)
+                .field("rows_returned", q.rows_returned)
+                .field("bytes_scanned", q.bytes_scanned)
+                .field("files_scanned", q.files_scanned)
+                .field("partitions_scanned", q.partitions_scanned)
+                .field("memory_bytes", q.memory_bytes)
+                .timestamp(q.issue_time_nanos)
+                .close_line()
+        })
+        .build();
+
+    String::from_utf8(lp).expect("line protocol is valid UTF-8")
+}
+
+#[derive(Debug)]
+struct QueryHistoryMetrics {
+    written: U64Counter,
+    dropped: U64Counter,
+    failed: U64Counter,
+}
+
+impl QueryHistoryMetrics {
+    fn new(registry: &metric::Registry) -> Self {
+        let entries = registry.register_metric::<U64Counter>(
+            "query_history_entries",
+            "number of query history entries by outcome",
+        );
+
+        Self {
+            written: entries.recorder(&[("status", "written")]),
+            dropped: entries.recorder(&[("status", "dropped")]),
+            failed: entries.recorder(&[("status", "failed")]),
+        }
+    }
+}
+
+/// Durable query history.
+///
+/// Dropping the history flushes the buffered entries in the background.
+#[derive(Debug)]
+pub struct QueryHistory {
+    tx: mpsc::Sender<CompletedQuery>,
+    metrics: Arc<QueryHistoryMetrics>,
+}
+
+impl QueryHistory {
+    /// Creates a new history, spawning the task that writes it via `writer`.
+    pub fn new(
+        config: QueryHistoryConfig,
+        writer: Arc<dyn QueryHistoryWriter>,
+        registry: &metric::Registry,
+    ) -> Self {
+        assert!(config.batch_size > 0, "batch size must be positive");
+
+        let (tx, rx) = mpsc::channel(config.queue_size.max(1));
+        let metrics = Arc::new(QueryHistoryMetrics::new(registry));
+
+        tokio::spawn(write_batches(config, writer, rx, Arc::clone(&metrics)));
+
+        Self { tx, metrics }
+    }
+
+    /// Records a completed query, dropping it if the queue is full.
+    pub fn record(&self, query: CompletedQuery) {
+        if self.tx.try_send(query).is_err() {
+            self.metrics.dropped.inc(1);
+        }
+    }
+}
+
+async fn write_batches(
+    config: QueryHistoryConfig,
+    writer: Arc<dyn QueryHistoryWriter>,
+    mut rx: mpsc::Receiver<CompletedQuery>,
+    metrics: Arc<QueryHistoryMetrics>,
+) {
+    // Wait for the first entry of a batch, then collect more until the batch
+    // is full or the flush interval elapsed.
+    while let Some(first) = rx.recv().await {
+        let deadline = Instant::now() + config.flush_interval;
+        let mut batch = vec![first];
+        let mut closed = false;
+
+        while batch.len() < config.batch_size {
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(Some(query)) => batch.push(query),
+                Ok(None) => {
+                    closed = true;
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+
+        let n = batch.len() as u64;
+        match writer
+            .write(
+                &config.namespace,
+                to_line_protocol(&config.querier_id, &batch),
+            )
+            .await
+        {
+            Ok(()) => {
+                debug!(entries = n, "wrote query history");
+                metrics.written.inc(n);
+            }
+            Err(e) => {
+                warn!(
+                    %e,
+                    entries = n,
+                    namespace = %config.namespace,
+                    "failed to write query history"
+                );
+                metrics.failed.inc(n);
+            }
+        }
+
+        if closed {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metric::{Attributes, Metric};
+    use parking_lot::Mutex;
+
+    #[derive(Debug, Default)]
+    struct MockWriter {
+        writes: Mutex<Vec<(String, String)>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl QueryHistoryWriter for MockWriter {
+        async fn write(&self, namespace: &str, lp: String) -> Result<(), WriteError> {
+            self.writes.lock().push((namespace.to_string(), lp));
+            if self.fail {
+                return Err("router unavailable".into());
+            }
+            Ok(())
+        }
+    }
+
+    fn query(text: &str) -> CompletedQuery {
+        CompletedQuery {
+            namespace: "ns".to_string(),
+            query_type: "sql".to_string(),
+            query_text: text.to_string(),
+            trace_id: None,
+            issue_time_nanos: 100,
+            duration: Duration::from_nanos(42),
+            success: true,
+            rows_returned: 3,
+            bytes_scanned: 1024,
+            files_scanned: 2,
+            partitions_scanned: 1,
+            memory_bytes: 4096,
+        }
+    }
+
+    fn config(batch_size: usize) -> QueryHistoryConfig {
+        QueryHistoryConfig {
+            namespace: "history".to_string(),
+            querier_id: "q1".to_string(),
+            batch_size,
+            flush_interval: Duration::from_millis(10),
+            queue_size: 100,
+        }
+    }
+
+    fn entries(registry: &metric::Registry, status: &'static str) -> u64 {
+        registry
+            .get_instrument::<Metric<U64Counter>>("query_history_entries")
+            .unwrap()
+            .get_observer(&Attributes::from(&[("status", status)]))
+            .unwrap()
+            .fetch()
+    }
+
+    async fn wait_for_writes(writer: &MockWriter, n: usize) {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while writer.writes.lock().len() < n {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        })
+        .await
+        .expect("writes did not happen");
+    }
+
+    #[test]
+    fn test_line_protocol() {
+        let mut traced = query("SELECT \"a\" FROM t");
+        traced.trace_id = Some("ab".to_string());
+        traced.success = false;
+
+        assert_eq!(
+            to_line_protocol("q1", &[query("SELECT 1"), traced]),
+            "queries,namespace=ns,querier=q1,query_type=sql,success=true \
+            query_text=\"SELECT 1\",duration_ns=42u,rows_returned=3u,bytes_scanned=1024u,\
+            files_scanned=2u,partitions_scanned=1u,memory_bytes=4096u 100\n\
+            queries,namespace=ns,querier=q1,query_type=sql,success=false \
+            query_text=\"SELECT \\\"a\\\" FROM t\",trace_id=\"ab\",duration_ns=42u,rows_returned=3u,\
+            bytes_scanned=1024u,files_scanned=2u,partitions_scanned=1u,memory_bytes=4096u 100\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batches() {
+        let registry = metric::Registry::default();
+        let writer = Arc::new(MockWriter::default());
+        let history = QueryHistory::new(
+            config(2),
+            Arc::clone(&writer) as Arc<dyn QueryHistoryWriter>,
+            &registry,
+        );
+
+        for text in ["SELECT 1", "SELECT 2", "SELECT 3"] {
+            history.record(query(text));
+        }
+
+        // The first two entries fill a batch, the third one is written once
+        // the flush interval elapsed.
+        wait_for_writes(&writer, 2).await;
+        let writes = writer.writes.lock().clone();
+        assert_eq!(writes[0].0, "history");
+        assert_eq!(writes[0].1.lines().count(), 2);
+        assert_eq!(writes[1].1.lines().count(), 1);
+        assert!(writes[1].1.contains("SELECT 3"));
+
+        assert_eq!(entries(&registry, "written"), 3);
+        assert_eq!(entries(&registry, "dropped"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_failed_write() {
+        let registry = metric::Registry::default();
+        let writer = Arc::new(MockWriter {
+            fail: true,
+            ..Default::default()
+        });
+        let history = QueryHistory::new(
+            config(10),
+            Arc::clone(&writer) as Arc<dyn QueryHistoryWriter>,
+            &registry,
+        );
+
+        history.record(query("SELECT 1"));
+        wait_for_writes(&writer, 1).await;
+
+        // Give the task a chance to record the failure.
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while entries(&registry, "failed") == 0 {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(entries(&registry, "written"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_drops() {
+        let registry = metric::Registry::default();
+        let (tx, _rx) = mpsc::channel(1);
+        let history = QueryHistory {
+            tx,
+            metrics: Arc::new(QueryHistoryMetrics::new(&registry)),
+        };
+
+        history.record(query("SELECT 1"));
+        history.record(query("SELECT 2"));
+
+        assert_eq!(entries(&registry, "dropped"), 1);
+    }
+}
@@ -1,7 +1,7 @@
 //! Ring buffer of queries that have been run with some brief information
 
 use data_types::NamespaceId;
-use iox_query::QueryText;
+use iox_query::{KillQueryError, QueryText};
 use iox_time::{Time, TimeProvider};
 use parking_lot::Mutex;
 use std::{
@@ -10,12 +10,16 @@ use std::{
     time::Duration,
 };
 use trace::ctx::TraceId;
+use tracker::{AbstractTaskRegistry, TaskId, TaskRegistration, TaskRegistry, TaskTracker};
 
 // The query duration used for queries still running.
 const UNCOMPLETED_DURATION: i64 = -1;
 
 /// Information about a single query that was executed
 pub struct QueryLogEntry {
+    /// Tracker of the running query, used to cancel it.
+    tracker: TaskTracker<NamespaceId>,
+
     /// Namespace ID.
     pub namespace_id: NamespaceId,
 
@@ -42,6 +46,7 @@ pub struct QueryLogEntry {
 impl std::fmt::Debug for QueryLogEntry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("QueryLogEntry")
+            .field("id", &self.id())
             .field("query_type", &self.query_type)
             .field("query_text", &self.query_text.to_string())
             .field("issue_time", &self.issue_time)
@@ -54,14 +59,15 @@ impl std::fmt::Debug for QueryLogEntry {
 impl QueryLogEntry {
     /// Creates a new QueryLogEntry -- use `QueryLog::push` to add new entries to the log
     fn new(
-        namespace_id: NamespaceId,
+        tracker: TaskTracker<NamespaceId>,
         query_type: String,
         query_text: QueryText,
         trace_id: Option<TraceId>,
         issue_time: Time,
     ) -> Self {
         Self {
-            namespace_id,
+            namespace_id: *tracker.metadata(),
+            tracker,
             query_type,
             query_text,
             trace_id,
@@ -71,6 +77,16 @@ impl QueryLogEntry {
         }
     }
 
+    /// ID of this query, used to cancel it.
+    pub fn id(&self) -> u64 {
+        self.tracker.id().0 as u64
+    }
+
+    /// Returns true if this query was cancelled while it was running.
+    pub fn cancelled(&self) -> bool {
+        self.tracker.is_cancelled()
+    }
+
     /// If this query is completed, returns `Some(duration)` of how
     /// long it took
     pub fn query_completed_duration(&self) -> Option<Duration> {
@@ -105,6 +121,9 @@ pub struct QueryLog {
     log: Mutex<VecDeque<Arc<QueryLogEntry>>>,
     max_size: usize,
     time_provider: Arc<dyn TimeProvider>,
+
+    /// Trackers of the running queries, by the ID of the query.
+    running: Mutex<TaskRegistry<NamespaceId>>,
 }
 
 impl QueryLog {
@@ -114,19 +133,30 @@ impl QueryLog {
         Self {
             log: Mutex::new(VecDeque::with_capacity(max_size)),
             max_size,
+            running: Mutex::new(TaskRegistry::new(Arc::clone(&time_provider))),
             time_provider,
         }
     }
 
+    /// Adds a new running query to the log.
+    ///
+    /// The query can be cancelled via [`kill`](Self::kill) until the returned
+    /// [`TaskRegistration`] and all futures tracked with it are dropped.
     pub fn push(
         &self,
         namespace_id: NamespaceId,
         query_type: impl Into<String>,
         query_text: QueryText,
         trace_id: Option<TraceId>,
-    ) -> Arc<QueryLogEntry> {
+    ) -> (Arc<QueryLogEntry>, TaskRegistration) {
+        let (tracker, registration) = {
+            let mut running = self.running.lock();
+            running.reclaim();
+            running.register(namespace_id)
+        };
+
         let entry = Arc::new(QueryLogEntry::new(
-            namespace_id,
+            tracker,
             query_type.into(),
             query_text,
             trace_id,
@@ -134,7 +164,7 @@ impl QueryLog {
         ));
 
         if self.max_size == 0 {
-            return entry;
+            return (entry, registration);
         }
 
         let mut log = self.log.lock();
@@ -145,7 +175,7 @@ impl QueryLog {
         }
 
         log.push_back(Arc::clone(&entry));
-        entry
+        (entry, registration)
     }
 
     pub fn entries(&self) -> VecDeque<Arc<QueryLogEntry>> {
@@ -158,20 +188,46 @@ impl QueryLog {
     pub fn set_completed(&self, entry: Arc<QueryLogEntry>, success: bool) {
         entry.set_completed(self.time_provider.now(), success)
     }
+
+    /// Cancels the running query `query_id`.
+    ///
+    /// If `namespace_id` is given, only queries of that namespace are found.
+    pub fn kill(
+        &self,
+        query_id: u64,
+        namespace_id: Option<NamespaceId>,
+    ) -> Result<(), KillQueryError> {
+        let tracker = self
+            .running
+            .lock()
+            .get(TaskId(query_id as usize))
+            .filter(|t| namespace_id.map_or(true, |id| *t.metadata() == id))
+            .ok_or(KillQueryError::NotFound { query_id })?;
+
+        if tracker.is_complete() {
+            return Err(KillQueryError::NotRunning { query_id });
+        }
+
+        tracker.cancel();
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod test_super {
     use iox_time::MockProvider;
+    use tracker::TrackedFutureExt;
 
     use super::*;
 
     #[test]
     fn test_query_log_entry_completed() {
-        let time_provider = MockProvider::new(Time::from_timestamp_millis(100));
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_millis(100)));
 
+        let registration = TaskRegistration::new(Arc::clone(&time_provider) as _);
+        let tracker = TaskTracker::new(TaskId(0), &registration, NamespaceId::new(1));
         let entry = Arc::new(QueryLogEntry::new(
-            NamespaceId::new(1),
+            tracker,
             "sql".into(),
             Box::new("SELECT 1"),
             None,
@@ -198,4 +254,38 @@ mod test_super {
         );
         assert!(!entry.success());
     }
+
+    #[tokio::test]
+    async fn test_kill() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_millis(100)));
+        let query_log = QueryLog::new(10, time_provider);
+        let ns1 = NamespaceId::new(1);
+        let ns2 = NamespaceId::new(2);
+
+        let (entry, registration) = query_log.push(ns1, "sql", Box::new("SELECT 1"), None);
+        let (done, _) = query_log.push(ns1, "sql", Box::new("SELECT 2"), None);
+        assert_ne!(entry.id(), done.id());
+
+        let query = futures::future::pending::<Result<(), ()>>().track(registration);
+
+        // queries are only found in their namespace
+        assert!(matches!(
+            query_log.kill(entry.id(), Some(ns2)),
+            Err(KillQueryError::NotFound { .. })
+        ));
+        assert!(matches!(
+            query_log.kill(42, None),
+            Err(KillQueryError::NotFound { .. })
+        ));
+        assert!(matches!(
+            query_log.kill(done.id(), None),
+            Err(KillQueryError::NotRunning { .. })
+        ));
+
+        assert!(!entry.cancelled());
+        query_log.kill(entry.id(), Some(ns1)).unwrap();
+        assert!(entry.cancelled());
+        assert!(query.await.is_err());
+        assert!(!done.cancelled());
+    }
 }
@@ -0,0 +1,225 @@
+//! Per-namespace query quotas, see [`NamespaceQueryLimits`].
+//!
+//! Memory and duration limits are enforced by the query itself (via the
+//! DataFusion runtime and the [`QueryCompletedToken`]) and only counted here
+//! once exceeded, the concurrency and scan limits are tracked here.
+//!
+//! [`QueryCompletedToken`]: iox_query::QueryCompletedToken
+
+use data_types::{NamespaceId, NamespaceQueryLimits};
+use iox_query::{exec::QueryStats, QueryLimit, QueryQuotaExceeded};
+use metric::{Metric, U64Counter};
+use parking_lot::Mutex;
+use std::{collections::HashMap, sync::Arc};
+
+/// Tracks the running queries of every namespace to enforce
+/// [`NamespaceQueryLimits`].
+#[derive(Debug)]
+pub struct QueryQuotas {
+    /// Number of running queries by namespace.
+    running: Mutex<HashMap<NamespaceId, usize>>,
+
+    /// Number of queries that exceeded a quota, by quota.
+    exceeded_concurrent_queries: U64Counter,
+    exceeded_scanned_bytes: U64Counter,
+    exceeded_duration: U64Counter,
+    exceeded_memory: U64Counter,
+}
+
+impl QueryQuotas {
+    /// Create new quota tracker.
+    pub fn new(metric_registry: &metric::Registry) -> Self {
+        let exceeded: Metric<U64Counter> = metric_registry.register_metric(
+            "query_quota_exceeded",
+            "Number of queries rejected or aborted because they exceeded a quota of their namespace",
+        );
+
+        Self {
+            running: Default::default(),
+            exceeded_concurrent_queries: exceeded.recorder(&[("quota", "concurrent_queries")]),
+            exceeded_scanned_bytes: exceeded.recorder(&[("quota", "scanned_bytes")]),
+            exceeded_duration: exceeded.recorder(&[("quota", "duration")]),
+            exceeded_memory: exceeded.recorder(&[("quota", "memory")]),
+        }
+    }
+
+    /// Admit a new query of the given namespace, unless the namespace already
+    /// runs as many queries as its limits allow.
+    ///
+    /// The query is counted as running until the returned [`QuerySlot`] is
+    /// dropped.
+    pub fn admit(
+        self: &Arc<Self>,
+        namespace_id: NamespaceId,
+        namespace_name: &str,
+        limits: &NamespaceQueryLimits,
+    ) -> Result<QuerySlot, QueryQuotaExceeded> {
+        let mut running = self.running.lock();
+        let count = running.entry(namespace_id).or_default();
+
+        if let Some(limit) = limits.max_concurrent_queries() {
+            if *count >= limit {
+                self.exceeded_concurrent_queries.inc(1);
+                return Err(QueryQuotaExceeded::ConcurrentQueries {
+                    namespace: namespace_name.to_string(),
+                    limit,
+                });
+            }
+        }
+
+        *count += 1;
+        Ok(QuerySlot {
+            quotas: Arc::clone(self),
+            namespace_id,
+        })
+    }
+
+    /// Check the bytes scanned so far by the query of `stats` against the
+    /// limits of its namespace.
+    pub fn check_scanned_bytes(
+        &self,
+        namespace_name: &str,
+        limits: &NamespaceQueryLimits,
+        stats: &QueryStats,
+    ) -> Result<(), QueryQuotaExceeded> {
+        match limits.max_query_scanned_bytes() {
+            Some(limit) if stats.bytes_scanned() > limit => {
+                self.exceeded_scanned_bytes.inc(1);
+                Err(QueryQuotaExceeded::ScannedBytes {
+                    namespace: namespace_name.to_string(),
+                    bytes: stats.bytes_scanned(),
+                    limit,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Count a query that was aborted because it exceeded `limit`.
+    pub fn record_exceeded(&self, limit: QueryLimit) {
+        match limit {
+            QueryLimit::Duration => self.exceeded_duration.inc(1),
+            QueryLimit::Memory => self.exceeded_memory.inc(1),
+        }
+    }
+
+    /// Number of running queries of the given namespace.
+    #[cfg(test)]
+    fn running(&self, namespace_id: NamespaceId) -> usize {
+        self.running
+            .lock()
+            .get(&namespace_id)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// A running query admitted by [`QueryQuotas::admit`].
+#[derive(Debug)]
+pub struct QuerySlot {
+    quotas: Arc<QueryQuotas>,
+    namespace_id: NamespaceId,
+}
+
+impl Drop for QuerySlot {
+    fn drop(&mut self) {
+        let mut running = self.quotas.running.lock();
+        if let Some(count) = running.get_mut(&self.namespace_id) {
+            *count -= 1;
+            if *count == 0 {
+                running.remove(&self.namespace_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metric::{Attributes, Observation};
+
+    fn exceeded(registry: &metric::Registry, quota: &'static str) -> u64 {
+        match registry
+            .get_instrument::<Metric<U64Counter>>("query_quota_exceeded")
+            .unwrap()
+            .get_observer(&Attributes::from(&[("quota", quota)]))
+            .unwrap()
+            .observe()
+        {
+            Observation::U64Counter(v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_concurrent_queries() {
+        let registry = metric::Registry::default();
+        let quotas = Arc::new(QueryQuotas::new(&registry));
+        let ns1 = NamespaceId::new(1);
+        let ns2 = NamespaceId::new(2);
+        let limits = NamespaceQueryLimits {
+            max_concurrent_queries: Some(1),
+            ..Default::default()
+        };
+
+        let slot = quotas.admit(ns1, "ns1", &limits).unwrap();
+        let err = quotas.admit(ns1, "ns1", &limits).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "namespace 'ns1' already runs its maximum of 1 concurrent queries"
+        );
+        assert_eq!(exceeded(&registry, "concurrent_queries"), 1);
+
+        // other namespaces and unlimited namespaces are not affected
+        let _other = quotas.admit(ns2, "ns2", &limits).unwrap();
+        let unlimited = quotas
+            .admit(ns1, "ns1", &NamespaceQueryLimits::default())
+            .unwrap();
+        assert_eq!(quotas.running(ns1), 2);
+
+        drop(slot);
+        drop(unlimited);
+        assert_eq!(quotas.running(ns1), 0);
+        quotas.admit(ns1, "ns1", &limits).unwrap();
+    }
+
+    #[test]
+    fn test_scanned_bytes() {
+        let registry = metric::Registry::default();
+        let quotas = QueryQuotas::new(&registry);
+        let limits = NamespaceQueryLimits {
+            max_query_scanned_bytes: Some(100),
+            ..Default::default()
+        };
+
+        let stats = QueryStats::default();
+        stats.add_scanned(1, 100, []);
+        quotas.check_scanned_bytes("ns", &limits, &stats).unwrap();
+        quotas
+            .check_scanned_bytes("ns", &NamespaceQueryLimits::default(), &stats)
+            .unwrap();
+
+        stats.add_scanned(1, 1, []);
+        let err = quotas
+            .check_scanned_bytes("ns", &limits, &stats)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "query would scan at least 101 bytes, more than the maximum of 100 bytes of namespace 'ns'"
+        );
+        assert_eq!(exceeded(&registry, "scanned_bytes"), 1);
+    }
+
+    #[test]
+    fn test_record_exceeded() {
+        let registry = metric::Registry::default();
+        let quotas = QueryQuotas::new(&registry);
+
+        quotas.record_exceeded(QueryLimit::Duration);
+        quotas.record_exceeded(QueryLimit::Memory);
+        quotas.record_exceeded(QueryLimit::Memory);
+        assert_eq!(exceeded(&registry, "duration"), 1);
+        assert_eq!(exceeded(&registry, "memory"), 2);
+        assert_eq!(exceeded(&registry, "concurrent_queries"), 0);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::system_tables::{batch_iterator, BatchIterator, IoxSystemTable};
+use arrow::{
+    array::{ArrayRef, StringArray, UInt64Array},
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    error::Result,
+    record_batch::RecordBatch,
+};
+use async_trait::async_trait;
+use metric::{Attributes, Observation, RawReporter};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Implementation of the `system.cache_stats` table.
+///
+/// The statistics are read from the metrics that `cache_system` records for
+/// every cache of the querier, so they cover all namespaces.
+#[derive(Debug)]
+pub(super) struct CacheStatsTable {
+    schema: SchemaRef,
+    metric_registry: Arc<metric::Registry>,
+}
+
+impl CacheStatsTable {
+    pub(super) fn new(metric_registry: Arc<metric::Registry>) -> Self {
+        Self {
+            schema: Arc::new(Schema::new(vec![
+                Field::new("cache", DataType::Utf8, false),
+                Field::new("hits", DataType::UInt64, false),
+                Field::new("misses", DataType::UInt64, false),
+                Field::new("misses_already_loading", DataType::UInt64, false),
+                Field::new("entries", DataType::UInt64, true),
+                Field::new("bytes", DataType::UInt64, true),
+                Field::new("evicted", DataType::UInt64, true),
+            ])),
+            metric_registry,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct CacheStats {
+    hits: u64,
+    misses: u64,
+    misses_already_loading: u64,
+    entries: Option<u64>,
+    bytes: Option<u64>,
+    evicted: Option<u64>,
+}
+
+#[async_trait]
+impl IoxSystemTable for CacheStatsTable {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    async fn scan(&self, batch_size: usize) -> Result<BatchIterator> {
+        let mut reporter = RawReporter::default();
+        self.metric_registry.report(&mut reporter);
+
+        let mut caches: BTreeMap<String, CacheStats> = BTreeMap::new();
+
+        // Every cache records its GET requests by status.
+        for (attributes, observation) in observations(&reporter, "iox_cache_get") {
+            let (name, status) = match (
+                attribute(attributes, "name"),
+                attribute(attributes, "status"),
+            ) {
+                (Some(name), Some(status)) => (name, status),
+                _ => continue,
+            };
+            let count = match observation {
+                Observation::DurationHistogram(h) => h.sample_count(),
+                _ => continue,
+            };
+
+            let stats = caches.entry(name.to_string()).or_default();
+            match status {
+                "hit" => stats.hits += count,
+                "miss" => stats.misses += count,
+                "miss_already_loading" => stats.misses_already_loading += count,
+                _ => {}
+            }
+        }
+
+        // Caches that are members of a RAM pool also report their size.
+        for metric_name in [
+            "cache_lru_member_count",
+            "cache_lru_member_usage",
+            "cache_lru_member_evicted",
+        ] {
+            for (attributes, observation) in observations(&reporter, metric_name) {
+                let value = match observation {
+                    Observation::U64Gauge(v) | Observation::U64Counter(v) => *v,
+                    _ => continue,
+                };
+                let name = match attribute(attributes, "member") {
+                    Some(name) => name,
+                    None => continue,
+                };
+
+                let stats = caches.entry(name.to_string()).or_default();
+                let field = match metric_name {
+                    "cache_lru_member_count" => &mut stats.entries,
+                    "cache_lru_member_usage" => &mut stats.bytes,
+                    _ => &mut stats.evicted,
+                };
+                *field.get_or_insert(0) += value;
+            }
+        }
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(
+                caches
+                    .keys()
+                    .map(|name| Some(name.as_str()))
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(
+                caches
+                    .values()
+                    .map(|s| Some(s.hits))
+                    .collect::<UInt64Array>(),
+            ),
+            Arc::new(
+                caches
+                    .values()
+                    .map(|s| Some(s.misses))
+                    .collect::<UInt64Array>(),
+            ),
+            Arc::new(
+                caches
+                    .values()
+                    .map(|s| Some(s.misses_already_loading))
+                    .collect::<UInt64Array>(),
+            ),
+            Arc::new(caches.values().map(|s| s.entries).collect::<UInt64Array>()),
+            Arc::new(caches.values().map(|s| s.bytes).collect::<UInt64Array>()),
+            Arc::new(caches.values().map(|s| s.evicted).collect::<UInt64Array>()),
+        ];
+
+        let batch = RecordBatch::try_new(self.schema(), columns)?;
+        Ok(batch_iterator(batch, batch_size))
+    }
+}
+
+fn observations<'a>(
+    reporter: &'a RawReporter,
+    metric_name: &str,
+) -> impl Iterator<Item = &'a (Attributes, Observation)> + 'a {
+    reporter
+        .metric(metric_name)
+        .into_iter()
+        .flat_map(|set| set.observations.iter())
+}
+
+fn attribute<'a>(attributes: &'a Attributes, key: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|(k, _)| **k == key)
+        .map(|(_, v)| v.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_util::assert_batches_eq;
+    use metric::{DurationHistogram, U64Gauge};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cache_stats() {
+        let registry = Arc::new(metric::Registry::default());
+
+        let get = registry.register_metric::<DurationHistogram>("iox_cache_get", "");
+        for (name, status, count) in [
+            ("namespace", "hit", 3),
+            ("namespace", "miss", 1),
+            ("parquet_file", "miss", 2),
+            ("parquet_file", "miss_already_loading", 1),
+        ] {
+            let recorder = get.recorder(&[("name", name), ("status", status)]);
+            for _ in 0..count {
+                recorder.record(Duration::from_millis(1));
+            }
+        }
+
+        registry
+            .register_metric::<U64Gauge>("cache_lru_member_count", "")
+            .recorder(&[("pool", "ram_metadata"), ("member", "namespace")])
+            .set(2);
+        registry
+            .register_metric::<U64Gauge>("cache_lru_member_usage", "")
+            .recorder(&[
+                ("pool", "ram_metadata"),
+                ("member", "namespace"),
+                ("unit", "bytes"),
+            ])
+            .set(1024);
+
+        let table = CacheStatsTable::new(registry);
+        let batches = table
+            .scan(10)
+            .await
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        let expected = vec![
+            "+--------------+------+--------+------------------------+---------+-------+---------+",
+            "| cache        | hits | misses | misses_already_loading | entries | bytes | evicted |",
+            "+--------------+------+--------+------------------------+---------+-------+---------+",
+            "| namespace    | 3    | 1      | 0                      | 2       | 1024  |         |",
+            "| parquet_file | 0    | 2      | 1                      |         |       |         |",
+            "+--------------+------+--------+------------------------+---------+-------+---------+",
+        ];
+        assert_batches_eq!(&expected, &batches);
+    }
+}
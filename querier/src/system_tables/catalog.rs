@@ -0,0 +1,474 @@
+//! System tables describing the catalog state of a namespace, as seen by
+//! the querier.
+
+use crate::{
+    cache::{namespace::CachedNamespace, CatalogCache},
+    system_tables::{batch_iterator, external_error, BatchIterator, IoxSystemTable},
+};
+use arrow::{
+    array::{ArrayRef, Int16Array, Int64Array, StringArray, TimestampNanosecondArray},
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
+    error::Result,
+    record_batch::RecordBatch,
+};
+use async_trait::async_trait;
+use data_types::{ColumnType, TableId};
+use std::{collections::HashMap, sync::Arc};
+
+/// Implementation of the `system.tables` table.
+#[derive(Debug)]
+pub(super) struct TablesTable {
+    schema: SchemaRef,
+    namespace: Arc<CachedNamespace>,
+}
+
+impl TablesTable {
+    pub(super) fn new(namespace: Arc<CachedNamespace>) -> Self {
+        Self {
+            schema: Arc::new(Schema::new(vec![
+                Field::new("table_id", DataType::Int64, false),
+                Field::new("table_name", DataType::Utf8, false),
+                Field::new("column_count", DataType::Int64, false),
+            ])),
+            namespace,
+        }
+    }
+}
+
+#[async_trait]
+impl IoxSystemTable for TablesTable {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    async fn scan(&self, batch_size: usize) -> Result<BatchIterator> {
+        let mut tables: Vec<_> = self.namespace.tables.iter().collect();
+        tables.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(
+                tables
+                    .iter()
+                    .map(|(_, table)| Some(table.id.get()))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                tables
+                    .iter()
+                    .map(|(name, _)| Some(name.as_ref()))
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(
+                tables
+                    .iter()
+                    .map(|(_, table)| Some(table.column_id_map.len() as i64))
+                    .collect::<Int64Array>(),
+            ),
+        ];
+
+        let batch = RecordBatch::try_new(self.schema(), columns)?;
+        Ok(batch_iterator(batch, batch_size))
+    }
+}
+
+/// Implementation of the `system.columns` table.
+#[derive(Debug)]
+pub(super) struct ColumnsTable {
+    schema: SchemaRef,
+    namespace: Arc<CachedNamespace>,
+}
+
+impl ColumnsTable {
+    pub(super) fn new(namespace: Arc<CachedNamespace>) -> Self {
+        Self {
+            schema: Arc::new(Schema::new(vec![
+                Field::new("table_name", DataType::Utf8, false),
+                Field::new("column_id", DataType::Int64, false),
+                Field::new("column_name", DataType::Utf8, false),
+                Field::new("column_type", DataType::Utf8, true),
+            ])),
+            namespace,
+        }
+    }
+}
+
+#[async_trait]
+impl IoxSystemTable for ColumnsTable {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    async fn scan(&self, batch_size: usize) -> Result<BatchIterator> {
+        let mut rows: Vec<_> = self
+            .namespace
+            .tables
+            .iter()
+            .flat_map(|(table_name, table)| {
+                table.column_id_map.iter().map(|(column_id, column_name)| {
+                    let column_type = table
+                        .schema
+                        .find_index_of(column_name)
+                        .and_then(|idx| table.schema.field(idx).0)
+                        .map(|t| ColumnType::from(t).as_str());
+                    (table_name, column_name, *column_id, column_type)
+                })
+            })
+            .collect();
+        rows.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(
+                rows.iter()
+                    .map(|row| Some(row.0.as_ref()))
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(
+                rows.iter()
+                    .map(|row| Some(row.2.get()))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                rows.iter()
+                    .map(|row| Some(row.1.as_ref()))
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(rows.iter().map(|row| row.3).collect::<StringArray>()),
+        ];
+
+        let batch = RecordBatch::try_new(self.schema(), columns)?;
+        Ok(batch_iterator(batch, batch_size))
+    }
+}
+
+/// Implementation of the `system.partitions` table.
+///
+/// Partitions are read from the catalog, as the querier only caches the
+/// sort keys of the partitions it queries.
+#[derive(Debug)]
+pub(super) struct PartitionsTable {
+    schema: SchemaRef,
+    namespace: Arc<CachedNamespace>,
+    catalog_cache: Arc<CatalogCache>,
+}
+
+impl PartitionsTable {
+    pub(super) fn new(namespace: Arc<CachedNamespace>, catalog_cache: Arc<CatalogCache>) -> Self {
+        Self {
+            schema: Arc::new(Schema::new(vec![
+                Field::new("partition_id", DataType::Int64, false),
+                Field::new("table_name", DataType::Utf8, true),
+                Field::new("shard_id", DataType::Int64, false),
+                Field::new("partition_key", DataType::Utf8, false),
+                Field::new("sort_key", DataType::Utf8, true),
+                Field::new("persisted_sequence_number", DataType::Int64, true),
+            ])),
+            namespace,
+            catalog_cache,
+        }
+    }
+}
+
+#[async_trait]
+impl IoxSystemTable for PartitionsTable {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    async fn scan(&self, batch_size: usize) -> Result<BatchIterator> {
+        let catalog = self.catalog_cache.catalog();
+        let mut partitions = catalog
+            .repositories()
+            .await
+            .partitions()
+            .list_by_namespace(self.namespace.id)
+            .await
+            .map_err(external_error)?;
+        partitions.sort_by_key(|p| p.id);
+
+        // Partitions of tables created after the namespace was cached have
+        // no name.
+        let table_names = table_names(&self.namespace);
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(
+                partitions
+                    .iter()
+                    .map(|p| Some(p.id.get()))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                partitions
+                    .iter()
+                    .map(|p| table_names.get(&p.table_id).copied())
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(
+                partitions
+                    .iter()
+                    .map(|p| Some(p.shard_id.get()))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                partitions
+                    .iter()
+                    .map(|p| Some(p.partition_key.to_string()))
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(
+                partitions
+                    .iter()
+                    .map(|p| (!p.sort_key.is_empty()).then(|| p.sort_key.join(",")))
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(
+                partitions
+                    .iter()
+                    .map(|p| p.persisted_sequence_number.map(|s| s.get()))
+                    .collect::<Int64Array>(),
+            ),
+        ];
+
+        let batch = RecordBatch::try_new(self.schema(), columns)?;
+        Ok(batch_iterator(batch, batch_size))
+    }
+}
+
+/// Implementation of the `system.parquet_files` table.
+///
+/// Lists the files in the parquet file cache, i.e. the files queries of
+/// this querier currently consider.
+#[derive(Debug)]
+pub(super) struct ParquetFilesTable {
+    schema: SchemaRef,
+    namespace: Arc<CachedNamespace>,
+    catalog_cache: Arc<CatalogCache>,
+}
+
+impl ParquetFilesTable {
+    pub(super) fn new(namespace: Arc<CachedNamespace>, catalog_cache: Arc<CatalogCache>) -> Self {
+        Self {
+            schema: Arc::new(Schema::new(vec![
+                Field::new("parquet_file_id", DataType::Int64, false),
+                Field::new("table_name", DataType::Utf8, false),
+                Field::new("partition_id", DataType::Int64, false),
+                Field::new("shard_id", DataType::Int64, false),
+                Field::new("object_store_id", DataType::Utf8, false),
+                Field::new("max_sequence_number", DataType::Int64, false),
+                Field::new("compaction_level", DataType::Int16, false),
+                Field::new("file_size_bytes", DataType::Int64, false),
+                Field::new("row_count", DataType::Int64, false),
+                Field::new("min_time", timestamp_type(), false),
+                Field::new("max_time", timestamp_type(), false),
+                Field::new("created_at", timestamp_type(), false),
+            ])),
+            namespace,
+            catalog_cache,
+        }
+    }
+}
+
+#[async_trait]
+impl IoxSystemTable for ParquetFilesTable {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    async fn scan(&self, batch_size: usize) -> Result<BatchIterator> {
+        let mut files = vec![];
+        for (table_name, table) in &self.namespace.tables {
+            let cached = self
+                .catalog_cache
+                .parquet_file()
+                .get(table.id, None, None)
+                .await;
+            files.extend(cached.files.iter().map(|f| (table_name, Arc::clone(f))));
+        }
+        files.sort_by_key(|(_, f)| f.id);
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(
+                files
+                    .iter()
+                    .map(|(_, f)| Some(f.id.get()))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                files
+                    .iter()
+                    .map(|(table_name, _)| Some(table_name.as_ref()))
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(
+                files
+                    .iter()
+                    .map(|(_, f)| Some(f.partition_id.get()))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                files
+                    .iter()
+                    .map(|(_, f)| Some(f.shard_id.get()))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                files
+                    .iter()
+                    .map(|(_, f)| Some(f.object_store_id.to_string()))
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(
+                files
+                    .iter()
+                    .map(|(_, f)| Some(f.max_sequence_number.get()))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                files
+                    .iter()
+                    .map(|(_, f)| Some(f.compaction_level as i16))
+                    .collect::<Int16Array>(),
+            ),
+            Arc::new(
+                files
+                    .iter()
+                    .map(|(_, f)| Some(f.file_size_bytes))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                files
+                    .iter()
+                    .map(|(_, f)| Some(f.row_count))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                files
+                    .iter()
+                    .map(|(_, f)| Some(f.min_time.get()))
+                    .collect::<TimestampNanosecondArray>(),
+            ),
+            Arc::new(
+                files
+                    .iter()
+                    .map(|(_, f)| Some(f.max_time.get()))
+                    .collect::<TimestampNanosecondArray>(),
+            ),
+            Arc::new(
+                files
+                    .iter()
+                    .map(|(_, f)| Some(f.created_at.get()))
+                    .collect::<TimestampNanosecondArray>(),
+            ),
+        ];
+
+        let batch = RecordBatch::try_new(self.schema(), columns)?;
+        Ok(batch_iterator(batch, batch_size))
+    }
+}
+
+/// Implementation of the `system.tombstones` table.
+///
+/// Lists the tombstones in the tombstone cache.
+#[derive(Debug)]
+pub(super) struct TombstonesTable {
+    schema: SchemaRef,
+    namespace: Arc<CachedNamespace>,
+    catalog_cache: Arc<CatalogCache>,
+}
+
+impl TombstonesTable {
+    pub(super) fn new(namespace: Arc<CachedNamespace>, catalog_cache: Arc<CatalogCache>) -> Self {
+        Self {
+            schema: Arc::new(Schema::new(vec![
+                Field::new("tombstone_id", DataType::Int64, false),
+                Field::new("table_name", DataType::Utf8, false),
+                Field::new("shard_id", DataType::Int64, false),
+                Field::new("sequence_number", DataType::Int64, false),
+                Field::new("min_time", timestamp_type(), false),
+                Field::new("max_time", timestamp_type(), false),
+                Field::new("predicate", DataType::Utf8, false),
+            ])),
+            namespace,
+            catalog_cache,
+        }
+    }
+}
+
+#[async_trait]
+impl IoxSystemTable for TombstonesTable {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    async fn scan(&self, batch_size: usize) -> Result<BatchIterator> {
+        let mut tombstones = vec![];
+        for (table_name, table) in &self.namespace.tables {
+            let cached = self
+                .catalog_cache
+                .tombstone()
+                .get(table.id, None, None)
+                .await;
+            tombstones.extend(cached.to_vec().into_iter().map(|t| (table_name, t)));
+        }
+        tombstones.sort_by_key(|(_, t)| t.id);
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(
+                tombstones
+                    .iter()
+                    .map(|(_, t)| Some(t.id.get()))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                tombstones
+                    .iter()
+                    .map(|(table_name, _)| Some(table_name.as_ref()))
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(
+                tombstones
+                    .iter()
+                    .map(|(_, t)| Some(t.shard_id.get()))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                tombstones
+                    .iter()
+                    .map(|(_, t)| Some(t.sequence_number.get()))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                tombstones
+                    .iter()
+                    .map(|(_, t)| Some(t.min_time.get()))
+                    .collect::<TimestampNanosecondArray>(),
+            ),
+            Arc::new(
+                tombstones
+                    .iter()
+                    .map(|(_, t)| Some(t.max_time.get()))
+                    .collect::<TimestampNanosecondArray>(),
+            ),
+            Arc::new(
+                tombstones
+                    .iter()
+                    .map(|(_, t)| Some(t.serialized_predicate.as_str()))
+                    .collect::<StringArray>(),
+            ),
+        ];
+
+        let batch = RecordBatch::try_new(self.schema(), columns)?;
+        Ok(batch_iterator(batch, batch_size))
+    }
+}
+
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Nanosecond, None)
+}
+
+fn table_names(namespace: &CachedNamespace) -> HashMap<TableId, &str> {
+    namespace
+        .tables
+        .iter()
+        .map(|(name, table)| (table.id, name.as_ref()))
+        .collect()
+}
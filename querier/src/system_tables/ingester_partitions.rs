@@ -0,0 +1,130 @@
+use crate::{
+    system_tables::{batch_iterator, external_error, BatchIterator, IoxSystemTable},
+    table::QuerierTable,
+};
+use arrow::{
+    array::{ArrayRef, Int64Array, StringArray, UInt64Array},
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    error::Result,
+    record_batch::RecordBatch,
+};
+use async_trait::async_trait;
+use predicate::Predicate;
+use std::{collections::HashMap, sync::Arc};
+
+/// Implementation of the `system.ingester_partitions` table.
+///
+/// Lists the partitions with unpersisted data that the ingesters report for
+/// the tables of the namespace. Scanning this table fetches all unpersisted
+/// data of the namespace from the ingesters.
+#[derive(Debug)]
+pub(super) struct IngesterPartitionsTable {
+    schema: SchemaRef,
+    tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>,
+}
+
+impl IngesterPartitionsTable {
+    pub(super) fn new(tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>) -> Self {
+        Self {
+            schema: Arc::new(Schema::new(vec![
+                Field::new("ingester", DataType::Utf8, false),
+                Field::new("table_name", DataType::Utf8, false),
+                Field::new("partition_id", DataType::Int64, false),
+                Field::new("shard_id", DataType::Int64, false),
+                Field::new("parquet_max_sequence_number", DataType::Int64, true),
+                Field::new("tombstone_max_sequence_number", DataType::Int64, true),
+                Field::new("chunks", DataType::UInt64, false),
+                Field::new("rows", DataType::UInt64, false),
+                Field::new("estimated_bytes", DataType::UInt64, false),
+            ])),
+            tables,
+        }
+    }
+}
+
+#[async_trait]
+impl IoxSystemTable for IngesterPartitionsTable {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    async fn scan(&self, batch_size: usize) -> Result<BatchIterator> {
+        let mut tables: Vec<_> = self.tables.values().collect();
+        tables.sort_by(|a, b| a.table_name().cmp(b.table_name()));
+
+        let mut partitions = vec![];
+        for table in tables {
+            let table_partitions = table
+                .ingester_partitions(&Predicate::default(), None)
+                .await
+                .map_err(external_error)?;
+            partitions.extend(
+                table_partitions
+                    .into_iter()
+                    .map(|p| (table.table_name(), p)),
+            );
+        }
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(
+                partitions
+                    .iter()
+                    .map(|(_, p)| Some(p.ingester().as_ref()))
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(
+                partitions
+                    .iter()
+                    .map(|(table_name, _)| Some(table_name.as_ref()))
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(
+                partitions
+                    .iter()
+                    .map(|(_, p)| Some(p.partition_id().get()))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                partitions
+                    .iter()
+                    .map(|(_, p)| Some(p.shard_id().get()))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                partitions
+                    .iter()
+                    .map(|(_, p)| p.parquet_max_sequence_number().map(|s| s.get()))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                partitions
+                    .iter()
+                    .map(|(_, p)| p.tombstone_max_sequence_number().map(|s| s.get()))
+                    .collect::<Int64Array>(),
+            ),
+            Arc::new(
+                partitions
+                    .iter()
+                    .map(|(_, p)| Some(p.chunks().len() as u64))
+                    .collect::<UInt64Array>(),
+            ),
+            Arc::new(
+                partitions
+                    .iter()
+                    .map(|(_, p)| Some(p.chunks().iter().map(|c| c.rows()).sum::<usize>() as u64))
+                    .collect::<UInt64Array>(),
+            ),
+            Arc::new(
+                partitions
+                    .iter()
+                    .map(|(_, p)| {
+                        Some(p.chunks().iter().map(|c| c.estimate_size()).sum::<usize>() as u64)
+                    })
+                    .collect::<UInt64Array>(),
+            ),
+        ];
+
+        let batch = RecordBatch::try_new(self.schema(), columns)?;
+        Ok(batch_iterator(batch, batch_size))
+    }
+}
@@ -1,7 +1,14 @@
-use crate::query_log::QueryLog;
-use arrow::{datatypes::SchemaRef, error::Result as ArrowResult, record_batch::RecordBatch};
+use crate::{
+    cache::{namespace::CachedNamespace, CatalogCache},
+    query_log::QueryLog,
+    table::QuerierTable,
+};
+use arrow::{
+    datatypes::SchemaRef,
+    error::{ArrowError, Result as ArrowResult},
+    record_batch::RecordBatch,
+};
 use async_trait::async_trait;
-use data_types::NamespaceId;
 use datafusion::{
     catalog::schema::SchemaProvider,
     datasource::TableProvider,
@@ -13,32 +20,108 @@ use datafusion::{
         SendableRecordBatchStream, Statistics,
     },
 };
+use futures::{stream::BoxStream, StreamExt};
 use std::{
     any::Any,
+    collections::HashMap,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
 };
 
+mod cache_stats;
+mod catalog;
+mod ingester_partitions;
 mod queries;
 
 pub const SYSTEM_SCHEMA: &str = "system";
 
 const QUERIES_TABLE: &str = "queries";
-
-const ALL_SYSTEM_TABLES: &[&str] = &[QUERIES_TABLE];
+const TABLES_TABLE: &str = "tables";
+const COLUMNS_TABLE: &str = "columns";
+const PARTITIONS_TABLE: &str = "partitions";
+const PARQUET_FILES_TABLE: &str = "parquet_files";
+const TOMBSTONES_TABLE: &str = "tombstones";
+const CACHE_STATS_TABLE: &str = "cache_stats";
+const INGESTER_PARTITIONS_TABLE: &str = "ingester_partitions";
+
+const ALL_SYSTEM_TABLES: &[&str] = &[
+    QUERIES_TABLE,
+    TABLES_TABLE,
+    COLUMNS_TABLE,
+    PARTITIONS_TABLE,
+    PARQUET_FILES_TABLE,
+    TOMBSTONES_TABLE,
+    CACHE_STATS_TABLE,
+    INGESTER_PARTITIONS_TABLE,
+];
 
 pub struct SystemSchemaProvider {
     queries: Arc<dyn TableProvider>,
+    tables: Arc<dyn TableProvider>,
+    columns: Arc<dyn TableProvider>,
+    partitions: Arc<dyn TableProvider>,
+    parquet_files: Arc<dyn TableProvider>,
+    tombstones: Arc<dyn TableProvider>,
+    cache_stats: Arc<dyn TableProvider>,
+    ingester_partitions: Arc<dyn TableProvider>,
 }
 
 impl SystemSchemaProvider {
-    pub fn new(query_log: Arc<QueryLog>, namespace_id: NamespaceId) -> Self {
+    pub fn new(
+        query_log: Arc<QueryLog>,
+        namespace: Arc<CachedNamespace>,
+        querier_tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>,
+        catalog_cache: Arc<CatalogCache>,
+    ) -> Self {
         let queries = Arc::new(SystemTableProvider {
-            table: Arc::new(queries::QueriesTable::new(query_log, Some(namespace_id))),
+            table: Arc::new(queries::QueriesTable::new(query_log, Some(namespace.id))),
+        });
+        let tables = Arc::new(SystemTableProvider {
+            table: Arc::new(catalog::TablesTable::new(Arc::clone(&namespace))),
+        });
+        let columns = Arc::new(SystemTableProvider {
+            table: Arc::new(catalog::ColumnsTable::new(Arc::clone(&namespace))),
+        });
+        let partitions = Arc::new(SystemTableProvider {
+            table: Arc::new(catalog::PartitionsTable::new(
+                Arc::clone(&namespace),
+                Arc::clone(&catalog_cache),
+            )),
+        });
+        let parquet_files = Arc::new(SystemTableProvider {
+            table: Arc::new(catalog::ParquetFilesTable::new(
+                Arc::clone(&namespace),
+                Arc::clone(&catalog_cache),
+            )),
+        });
+        let tombstones = Arc::new(SystemTableProvider {
+            table: Arc::new(catalog::TombstonesTable::new(
+                Arc::clone(&namespace),
+                Arc::clone(&catalog_cache),
+            )),
+        });
+        let cache_stats = Arc::new(SystemTableProvider {
+            table: Arc::new(cache_stats::CacheStatsTable::new(
+                catalog_cache.metric_registry(),
+            )),
+        });
+        let ingester_partitions = Arc::new(SystemTableProvider {
+            table: Arc::new(ingester_partitions::IngesterPartitionsTable::new(
+                querier_tables,
+            )),
         });
 
-        Self { queries }
+        Self {
+            queries,
+            tables,
+            columns,
+            partitions,
+            parquet_files,
+            tombstones,
+            cache_stats,
+            ingester_partitions,
+        }
     }
 }
 
@@ -57,6 +140,13 @@ impl SchemaProvider for SystemSchemaProvider {
     fn table(&self, name: &str) -> Option<Arc<dyn TableProvider>> {
         match name {
             QUERIES_TABLE => Some(Arc::clone(&self.queries)),
+            TABLES_TABLE => Some(Arc::clone(&self.tables)),
+            COLUMNS_TABLE => Some(Arc::clone(&self.columns)),
+            PARTITIONS_TABLE => Some(Arc::clone(&self.partitions)),
+            PARQUET_FILES_TABLE => Some(Arc::clone(&self.parquet_files)),
+            TOMBSTONES_TABLE => Some(Arc::clone(&self.tombstones)),
+            CACHE_STATS_TABLE => Some(Arc::clone(&self.cache_stats)),
+            INGESTER_PARTITIONS_TABLE => Some(Arc::clone(&self.ingester_partitions)),
             _ => None,
         }
     }
@@ -71,12 +161,32 @@ impl SchemaProvider for SystemSchemaProvider {
 type BatchIterator = Box<dyn Iterator<Item = ArrowResult<RecordBatch>> + Send + Sync>;
 
 /// The minimal thing that a system table needs to implement
+#[async_trait]
 trait IoxSystemTable: Send + Sync {
     /// Produce the schema from this system table
     fn schema(&self) -> SchemaRef;
 
-    /// Get the contents of the system table
-    fn scan(&self, batch_size: usize) -> ArrowResult<BatchIterator>;
+    /// Get the contents of the system table.
+    ///
+    /// This is called when the table is executed, so tables backed by the
+    /// catalog or the ingesters reflect their state at that time.
+    async fn scan(&self, batch_size: usize) -> ArrowResult<BatchIterator>;
+}
+
+/// Splits `batch` into batches of at most `batch_size` rows.
+fn batch_iterator(batch: RecordBatch, batch_size: usize) -> BatchIterator {
+    let batch_size = batch_size.max(1);
+    let num_rows = batch.num_rows();
+    Box::new(
+        (0..num_rows)
+            .step_by(batch_size)
+            .map(move |offset| Ok(batch.slice(offset, batch_size.min(num_rows - offset)))),
+    )
+}
+
+/// Wraps an error of the catalog or an ingester.
+fn external_error(e: impl std::error::Error + Send + Sync + 'static) -> ArrowError {
+    ArrowError::ExternalError(Box::new(e))
 }
 
 /// Adapter that makes any `IoxSystemTable` a DataFusion `TableProvider`
@@ -171,9 +281,17 @@ impl<T: IoxSystemTable + 'static> ExecutionPlan for SystemTableExecutionPlan<T>
     ) -> DataFusionResult<SendableRecordBatchStream> {
         let batch_size = context.session_config().batch_size();
 
+        let table = Arc::clone(&self.table);
+        let batches = futures::stream::once(async move { table.scan(batch_size).await })
+            .flat_map(|batches| match batches {
+                Ok(batches) => futures::stream::iter(batches).left_stream(),
+                Err(e) => futures::stream::iter(std::iter::once(Err(e))).right_stream(),
+            })
+            .boxed();
+
         Ok(Box::pin(SystemTableStream {
             projected_schema: Arc::clone(&self.projected_schema),
-            batches: self.table.scan(batch_size)?,
+            batches,
             projection: self.projection.clone(),
         }))
     }
@@ -186,7 +304,7 @@ impl<T: IoxSystemTable + 'static> ExecutionPlan for SystemTableExecutionPlan<T>
 struct SystemTableStream {
     projected_schema: SchemaRef,
     projection: Option<Vec<usize>>,
-    batches: BatchIterator,
+    batches: BoxStream<'static, ArrowResult<RecordBatch>>,
 }
 
 impl RecordBatchStream for SystemTableStream {
@@ -198,12 +316,14 @@ impl RecordBatchStream for SystemTableStream {
 impl futures::Stream for SystemTableStream {
     type Item = ArrowResult<RecordBatch>;
 
-    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        Poll::Ready(self.batches.next().map(|maybe_batch| {
-            maybe_batch.and_then(|batch| match &self.projection {
-                Some(projection) => batch.project(projection),
-                None => Ok(batch),
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.batches.poll_next_unpin(cx).map(|maybe_batch| {
+            maybe_batch.map(|maybe_batch| {
+                maybe_batch.and_then(|batch| match &self.projection {
+                    Some(projection) => batch.project(projection),
+                    None => Ok(batch),
+                })
             })
-        }))
+        })
     }
 }
@@ -5,12 +5,13 @@ use crate::{
 use arrow::{
     array::{
         ArrayRef, BooleanArray, DurationNanosecondArray, Int64Array, StringArray,
-        TimestampNanosecondArray,
+        TimestampNanosecondArray, UInt64Array,
     },
     datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
     error::Result,
     record_batch::RecordBatch,
 };
+use async_trait::async_trait;
 use data_types::NamespaceId;
 use observability_deps::tracing::error;
 use std::{collections::VecDeque, sync::Arc};
@@ -33,12 +34,13 @@ impl QueriesTable {
     }
 }
 
+#[async_trait]
 impl IoxSystemTable for QueriesTable {
     fn schema(&self) -> SchemaRef {
         Arc::clone(&self.schema)
     }
 
-    fn scan(&self, batch_size: usize) -> Result<BatchIterator> {
+    async fn scan(&self, batch_size: usize) -> Result<BatchIterator> {
         let schema = self.schema();
 
         let mut entries = self.query_log.entries();
@@ -75,7 +77,7 @@ impl IoxSystemTable for QueriesTable {
 }
 
 fn queries_schema(include_namespace_id: bool) -> SchemaRef {
-    let mut columns = vec![];
+    let mut columns = vec![Field::new("query_id", DataType::UInt64, false)];
     if include_namespace_id {
         columns.push(Field::new("namespace_id", DataType::Int64, false));
     }
@@ -93,6 +95,7 @@ fn queries_schema(include_namespace_id: bool) -> SchemaRef {
             true,
         ),
         Field::new("success", DataType::Boolean, false),
+        Field::new("cancelled", DataType::Boolean, false),
         Field::new("trace_id", DataType::Utf8, true),
     ]);
 
@@ -106,7 +109,14 @@ fn from_query_log_entries(
     len: usize,
     include_namespace_id: bool,
 ) -> Result<RecordBatch> {
-    let mut columns: Vec<ArrayRef> = vec![];
+    let mut columns: Vec<ArrayRef> = vec![Arc::new(
+        entries
+            .iter()
+            .skip(offset)
+            .take(len)
+            .map(|e| Some(e.id()))
+            .collect::<UInt64Array>(),
+    )];
 
     if include_namespace_id {
         columns.push(Arc::new(
@@ -165,6 +175,15 @@ fn from_query_log_entries(
             .collect::<BooleanArray>(),
     ));
 
+    columns.push(Arc::new(
+        entries
+            .iter()
+            .skip(offset)
+            .take(len)
+            .map(|e| Some(e.cancelled()))
+            .collect::<BooleanArray>(),
+    ));
+
     columns.push(Arc::new(
         entries
             .iter()
@@ -184,8 +203,8 @@ mod tests {
     use iox_time::{Time, TimeProvider};
     use trace::ctx::TraceId;
 
-    #[test]
-    fn test_from_query_log() {
+    #[tokio::test]
+    async fn test_from_query_log() {
         let now = Time::from_rfc3339("1996-12-19T16:39:57+00:00").unwrap();
         let time_provider = Arc::new(iox_time::MockProvider::new(now));
 
@@ -198,8 +217,9 @@ mod tests {
         ));
         query_log.push(id1, "sql", Box::new("select * from foo"), None);
         time_provider.inc(std::time::Duration::from_secs(24 * 60 * 60));
-        let sql2_entry = query_log.push(id1, "sql", Box::new("select * from bar"), None);
-        let read_filter_entry = query_log.push(
+        let (sql2_entry, _sql2_registration) =
+            query_log.push(id1, "sql", Box::new("select * from bar"), None);
+        let (read_filter_entry, _) = query_log.push(
             id2,
             "read_filter",
             Box::new("json goop"),
@@ -209,20 +229,26 @@ mod tests {
         let table = QueriesTable::new(Arc::clone(&query_log), None);
 
         let expected = vec![
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
-            "| namespace_id | issue_time           | query_type  | query_text        | completed_duration | success | trace_id |",
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
-            "| 1            | 1996-12-19T16:39:57Z | sql         | select * from foo |                    | false   |          |",
-            "| 1            | 1996-12-20T16:39:57Z | sql         | select * from bar |                    | false   |          |",
-            "| 2            | 1996-12-20T16:39:57Z | read_filter | json goop         |                    | false   | 45fe     |",
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
+            "+----------+--------------+----------------------+-------------+-------------------+--------------------+---------+-----------+----------+",
+            "| query_id | namespace_id | issue_time           | query_type  | query_text        | completed_duration | success | cancelled | trace_id |",
+            "+----------+--------------+----------------------+-------------+-------------------+--------------------+---------+-----------+----------+",
+            "| 0        | 1            | 1996-12-19T16:39:57Z | sql         | select * from foo |                    | false   | false     |          |",
+            "| 1        | 1            | 1996-12-20T16:39:57Z | sql         | select * from bar |                    | false   | false     |          |",
+            "| 2        | 2            | 1996-12-20T16:39:57Z | read_filter | json goop         |                    | false   | false     | 45fe     |",
+            "+----------+--------------+----------------------+-------------+-------------------+--------------------+---------+-----------+----------+",
         ];
 
-        let entries = table.scan(3).unwrap().collect::<Result<Vec<_>>>().unwrap();
+        let entries = table
+            .scan(3)
+            .await
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
         assert_eq!(entries.len(), 1);
         assert_batches_eq!(&expected, &entries);
 
-        // mark the sql query completed after 4s unsuccessfully
+        // kill the sql query, which completes it after 4s unsuccessfully
+        query_log.kill(sql2_entry.id(), None).unwrap();
         let now = Time::from_rfc3339("1996-12-20T16:40:01+00:00").unwrap();
         sql2_entry.set_completed(now, false);
 
@@ -230,16 +256,21 @@ mod tests {
         read_filter_entry.set_completed(now, true);
 
         let expected = vec![
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
-            "| namespace_id | issue_time           | query_type  | query_text        | completed_duration | success | trace_id |",
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
-            "| 1            | 1996-12-19T16:39:57Z | sql         | select * from foo |                    | false   |          |",
-            "| 1            | 1996-12-20T16:39:57Z | sql         | select * from bar | 4s                 | false   |          |",
-            "| 2            | 1996-12-20T16:39:57Z | read_filter | json goop         | 4s                 | true    | 45fe     |",
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
+            "+----------+--------------+----------------------+-------------+-------------------+--------------------+---------+-----------+----------+",
+            "| query_id | namespace_id | issue_time           | query_type  | query_text        | completed_duration | success | cancelled | trace_id |",
+            "+----------+--------------+----------------------+-------------+-------------------+--------------------+---------+-----------+----------+",
+            "| 0        | 1            | 1996-12-19T16:39:57Z | sql         | select * from foo |                    | false   | false     |          |",
+            "| 1        | 1            | 1996-12-20T16:39:57Z | sql         | select * from bar | 4s                 | false   | true      |          |",
+            "| 2        | 2            | 1996-12-20T16:39:57Z | read_filter | json goop         | 4s                 | true    | false     | 45fe     |",
+            "+----------+--------------+----------------------+-------------+-------------------+--------------------+---------+-----------+----------+",
         ];
 
-        let entries = table.scan(2).unwrap().collect::<Result<Vec<_>>>().unwrap();
+        let entries = table
+            .scan(2)
+            .await
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
         assert_eq!(entries.len(), 2);
         assert_batches_eq!(&expected, &entries);
 
@@ -247,15 +278,20 @@ mod tests {
         let table = QueriesTable::new(Arc::clone(&query_log), Some(id1));
 
         let expected = vec![
-            "+----------------------+------------+-------------------+--------------------+---------+----------+",
-            "| issue_time           | query_type | query_text        | completed_duration | success | trace_id |",
-            "+----------------------+------------+-------------------+--------------------+---------+----------+",
-            "| 1996-12-19T16:39:57Z | sql        | select * from foo |                    | false   |          |",
-            "| 1996-12-20T16:39:57Z | sql        | select * from bar | 4s                 | false   |          |",
-            "+----------------------+------------+-------------------+--------------------+---------+----------+",
+            "+----------+----------------------+------------+-------------------+--------------------+---------+-----------+----------+",
+            "| query_id | issue_time           | query_type | query_text        | completed_duration | success | cancelled | trace_id |",
+            "+----------+----------------------+------------+-------------------+--------------------+---------+-----------+----------+",
+            "| 0        | 1996-12-19T16:39:57Z | sql        | select * from foo |                    | false   | false     |          |",
+            "| 1        | 1996-12-20T16:39:57Z | sql        | select * from bar | 4s                 | false   | true      |          |",
+            "+----------+----------------------+------------+-------------------+--------------------+---------+-----------+----------+",
         ];
 
-        let entries = table.scan(3).unwrap().collect::<Result<Vec<_>>>().unwrap();
+        let entries = table
+            .scan(3)
+            .await
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
         assert_eq!(entries.len(), 1);
         assert_batches_eq!(&expected, &entries);
     }
@@ -3,14 +3,22 @@ use self::state_reconciler::Reconciler;
 use crate::chunk::util::create_basic_summary;
 use crate::table::query_access::MetricPruningObserver;
 use crate::{
-    chunk::ChunkAdapter,
+    chunk::{ChunkAdapter, QuerierChunk},
     ingester::{self, IngesterPartition},
+    query_quota::QueryQuotas,
     IngesterConnection,
 };
-use data_types::{ColumnId, PartitionId, ShardIndex, TableId, TimestampMinMax};
+use data_types::{
+    ColumnId, NamespaceQueryLimits, PartitionId, ShardIndex, TableId, TimestampMinMax,
+};
 use futures::{join, StreamExt};
 use iox_query::pruning::prune_summaries;
-use iox_query::{exec::Executor, provider, provider::ChunkPruner, QueryChunk};
+use iox_query::{
+    exec::{Executor, QueryStats},
+    provider,
+    provider::ChunkPruner,
+    QueryChunk, QueryQuotaExceeded,
+};
 use observability_deps::tracing::{debug, trace};
 use predicate::Predicate;
 use schema::Schema;
@@ -61,6 +69,9 @@ pub enum Error {
 
     #[snafu(display("Chunk pruning failed: {}", source))]
     ChunkPruning { source: provider::Error },
+
+    #[snafu(display("Query quota exceeded: {}", source))]
+    QueryQuota { source: QueryQuotaExceeded },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -77,6 +88,8 @@ pub struct QuerierTableArgs {
     pub exec: Arc<Executor>,
     pub max_query_bytes: usize,
     pub prune_metrics: Arc<PruneMetrics>,
+    pub query_limits: NamespaceQueryLimits,
+    pub query_quotas: Arc<QueryQuotas>,
 }
 
 /// Table representation for the querier.
@@ -114,6 +127,12 @@ pub struct QuerierTable {
 
     /// Metrics for chunk pruning.
     prune_metrics: Arc<PruneMetrics>,
+
+    /// Query limits of the namespace.
+    query_limits: NamespaceQueryLimits,
+
+    /// Tracker of the namespace query quotas.
+    query_quotas: Arc<QueryQuotas>,
 }
 
 impl QuerierTable {
@@ -130,6 +149,8 @@ impl QuerierTable {
             exec,
             max_query_bytes,
             prune_metrics,
+            query_limits,
+            query_quotas,
         } = args;
 
         let reconciler = Reconciler::new(
@@ -150,6 +171,8 @@ impl QuerierTable {
             exec,
             max_query_bytes,
             prune_metrics,
+            query_limits,
+            query_quotas,
         }
     }
 
@@ -176,9 +199,20 @@ impl QuerierTable {
         &self,
         predicate: &Predicate,
         span: Option<Span>,
+    ) -> Result<Vec<Arc<dyn QueryChunk>>> {
+        self.chunks_with_stats(predicate, span, &QueryStats::default())
+            .await
+    }
+
+    /// Query all chunks within this table, recording the number of pruned chunks in `stats`.
+    pub async fn chunks_with_stats(
+        &self,
+        predicate: &Predicate,
+        span: Option<Span>,
+        stats: &QueryStats,
     ) -> Result<Vec<Arc<dyn QueryChunk>>> {
         let mut span_recorder = SpanRecorder::new(span);
-        match self.chunks_inner(predicate, &span_recorder).await {
+        match self.chunks_inner(predicate, &span_recorder, stats).await {
             Ok(chunks) => {
                 span_recorder.ok("got chunks");
                 Ok(chunks)
@@ -194,6 +228,7 @@ impl QuerierTable {
         &self,
         predicate: &Predicate,
         span_recorder: &SpanRecorder,
+        stats: &QueryStats,
     ) -> Result<Vec<Arc<dyn QueryChunk>>> {
         debug!(
             ?predicate,
@@ -316,6 +351,7 @@ impl QuerierTable {
                 futures::stream::iter(parquet_files.files.iter().cloned().zip(keeps))
                     .filter(|(cached_parquet_file, keep)| {
                         if !keep {
+                            stats.add_files_pruned(1);
                             early_pruning_observer.was_pruned_early(
                                 cached_parquet_file.row_count as u64,
                                 cached_parquet_file.file_size_bytes as u64,
@@ -366,6 +402,21 @@ impl QuerierTable {
                 predicate,
             )
             .context(ChunkPruningSnafu)?;
+        stats.add_files_pruned((num_initial_chunks - chunks.len()) as u64);
+        stats.add_scanned(
+            chunks
+                .iter()
+                .filter(|c| c.as_any().is::<QuerierChunk>())
+                .count() as u64,
+            chunks
+                .iter()
+                .map(|c| query_access::chunk_estimate_size(c.as_ref()) as u64)
+                .sum(),
+            chunks.iter().map(|c| c.partition_id()),
+        );
+        self.query_quotas
+            .check_scanned_bytes(&self.namespace_name, &self.query_limits, stats)
+            .context(QueryQuotaSnafu)?;
         debug!(%predicate, num_initial_chunks, num_final_chunks=chunks.len(), "pruned with pushed down predicates");
         Ok(chunks)
     }
@@ -379,7 +430,7 @@ impl QuerierTable {
     }
 
     /// Get partitions from ingesters.
-    async fn ingester_partitions(
+    pub(crate) async fn ingester_partitions(
         &self,
         predicate: &Predicate,
         span: Option<Span>,
@@ -60,7 +60,11 @@ impl TableProvider for QuerierTable {
             .cloned()
             .fold(Predicate::default(), Predicate::with_expr);
         let chunks = self
-            .chunks(&pruning_predicate, ctx.child_span("querier table chunks"))
+            .chunks_with_stats(
+                &pruning_predicate,
+                ctx.child_span("querier table chunks"),
+                &ctx.query_stats(),
+            )
             .await
             .map_err(|e| DataFusionError::External(Box::new(e)))?;
 
@@ -197,7 +201,7 @@ impl PruningObserver for MetricPruningObserver {
     }
 }
 
-fn chunk_estimate_size(chunk: &dyn QueryChunk) -> usize {
+pub(super) fn chunk_estimate_size(chunk: &dyn QueryChunk) -> usize {
     let chunk = chunk.as_any();
 
     if let Some(chunk) = chunk.downcast_ref::<IngesterChunk>() {
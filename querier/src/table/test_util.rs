@@ -1,7 +1,7 @@
 use super::{PruneMetrics, QuerierTable, QuerierTableArgs};
 use crate::{
     cache::CatalogCache, chunk::ChunkAdapter, create_ingester_connection_for_testing,
-    IngesterPartition, QuerierChunkLoadSetting,
+    query_quota::QueryQuotas, IngesterPartition, QuerierChunkLoadSetting,
 };
 use arrow::record_batch::RecordBatch;
 use data_types::{ChunkId, ParquetFileId, SequenceNumber, ShardIndex};
@@ -54,6 +54,8 @@ pub async fn querier_table(
         exec: catalog.exec(),
         max_query_bytes: usize::MAX,
         prune_metrics: Arc::new(PruneMetrics::new(&catalog.metric_registry())),
+        query_limits: Default::default(),
+        query_quotas: Arc::new(QueryQuotas::new(&catalog.metric_registry())),
     })
 }
 
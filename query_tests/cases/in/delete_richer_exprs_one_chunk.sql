@@ -0,0 +1,12 @@
+-- Demonstrate soft deleted rows will not be return to queries when the delete uses OR, IN, regex and field comparisons
+-- IOX_SETUP: OneDeleteRicherExprsOneChunk
+
+-- select *
+SELECT * from cpu order by time;
+
+SELECT count(*), count(region), min(bar), max(bar) from cpu;
+
+--------------------------------------------------------
+-- With selection predicate
+
+SELECT foo, region, time from cpu where bar < 2 order by time;
@@ -0,0 +1,7 @@
+-- Demonstrate that a `!=` delete removes rows with a NULL tag while a `NOT IN` delete retains them
+-- IOX_SETUP: TwoDeletesNullTagsOneChunk
+
+-- select *
+SELECT * from cpu order by time;
+
+SELECT count(*), count(foo), min(bar), max(bar) from cpu;
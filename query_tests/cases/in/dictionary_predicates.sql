@@ -0,0 +1,26 @@
+-- Test for tag predicates evaluated against parquet dictionaries
+-- IOX_SETUP: TwoMeasurementsPredicatePushDown
+
+-- Test 1: tag equality
+SELECT town, count, time from restaurant where town = 'reading' order by time;
+
+-- Test 2: tag inequality
+SELECT town, count from restaurant where town != 'reading' order by count;
+
+-- Test 3: tag IN list
+SELECT town, count from restaurant where town in ('andover', 'bedford') order by count;
+
+-- Test 4: tag NOT IN list
+SELECT town, count from restaurant where town not in ('andover', 'bedford', 'reading') order by count;
+
+-- Test 5: tag regex match
+SELECT town, count from restaurant where town ~ '^l' order by count;
+
+-- Test 6: tag regex not match
+SELECT town, count from restaurant where town !~ '^[lr]' order by count;
+
+-- Test 7: tag and field predicates
+SELECT town, count, system from restaurant where town = 'reading' and system > 5.0;
+
+-- Test 8: no matching tag values
+SELECT town, count from restaurant where town = 'boston';
@@ -52,6 +52,22 @@ async fn test_cases_delete_multi_expr_one_chunk_sql() {
         .expect("flush worked");
 }
 
+#[tokio::test]
+// Tests from "delete_richer_exprs_one_chunk.sql",
+async fn test_cases_delete_richer_exprs_one_chunk_sql() {
+    test_helpers::maybe_start_logging();
+
+    let input_path = Path::new("cases").join("in").join("delete_richer_exprs_one_chunk.sql");
+    let mut runner = Runner::new();
+    runner
+        .run(input_path)
+        .await
+        .expect("test failed");
+    runner
+        .flush()
+        .expect("flush worked");
+}
+
 #[tokio::test]
 // Tests from "delete_simple_pred_one_chunk.sql",
 async fn test_cases_delete_simple_pred_one_chunk_sql() {
@@ -148,6 +164,38 @@ async fn test_cases_delete_two_del_multi_expr_one_chunk_sql() {
         .expect("flush worked");
 }
 
+#[tokio::test]
+// Tests from "delete_two_del_null_tags_one_chunk.sql",
+async fn test_cases_delete_two_del_null_tags_one_chunk_sql() {
+    test_helpers::maybe_start_logging();
+
+    let input_path = Path::new("cases").join("in").join("delete_two_del_null_tags_one_chunk.sql");
+    let mut runner = Runner::new();
+    runner
+        .run(input_path)
+        .await
+        .expect("test failed");
+    runner
+        .flush()
+        .expect("flush worked");
+}
+
+#[tokio::test]
+// Tests from "dictionary_predicates.sql",
+async fn test_cases_dictionary_predicates_sql() {
+    test_helpers::maybe_start_logging();
+
+    let input_path = Path::new("cases").join("in").join("dictionary_predicates.sql");
+    let mut runner = Runner::new();
+    runner
+        .run(input_path)
+        .await
+        .expect("test failed");
+    runner
+        .flush()
+        .expect("flush worked");
+}
+
 #[tokio::test]
 // Tests from "duplicates_ingester.sql",
 async fn test_cases_duplicates_ingester_sql() {
@@ -6,8 +6,9 @@ pub mod util;
 
 use async_trait::async_trait;
 use delete::{
-    OneDeleteMultiExprsOneChunk, OneDeleteSimpleExprOneChunk, OneDeleteSimpleExprOneChunkDeleteAll,
-    ThreeDeleteThreeChunks, TwoDeletesMultiExprsOneChunk,
+    OneDeleteMultiExprsOneChunk, OneDeleteRicherExprsOneChunk, OneDeleteSimpleExprOneChunk,
+    OneDeleteSimpleExprOneChunkDeleteAll, ThreeDeleteThreeChunks, TwoDeletesMultiExprsOneChunk,
+    TwoDeletesNullTagsOneChunk,
 };
 use once_cell::sync::OnceCell;
 use std::{collections::HashMap, sync::Arc};
@@ -59,7 +60,9 @@ pub fn get_all_setups() -> &'static HashMap<String, Arc<dyn DbSetup>> {
             register_setup!(OneDeleteSimpleExprOneChunkDeleteAll),
             register_setup!(OneDeleteSimpleExprOneChunk),
             register_setup!(OneDeleteMultiExprsOneChunk),
+            register_setup!(OneDeleteRicherExprsOneChunk),
             register_setup!(TwoDeletesMultiExprsOneChunk),
+            register_setup!(TwoDeletesNullTagsOneChunk),
             register_setup!(OneMeasurementRealisticTimes),
             register_setup!(TwoMeasurementsManyFieldsTwoChunks),
             register_setup!(ManyFieldsSeveralChunks),
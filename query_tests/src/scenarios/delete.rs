@@ -109,6 +109,101 @@ impl DbSetup for OneDeleteMultiExprsOneChunk {
     }
 }
 
+#[derive(Debug)]
+/// Setup for delete query test with one table and one chunk, using `OR`, `IN`, regex and field
+/// comparison expressions
+pub struct OneDeleteRicherExprsOneChunk {}
+#[async_trait]
+impl DbSetup for OneDeleteRicherExprsOneChunk {
+    async fn make(&self) -> Vec<DbScenario> {
+        let partition_key = "1970-01-01T00";
+        let table_name = "cpu";
+        // chunk data
+        let lp_lines = vec![
+            "cpu,foo=me,region=east bar=1 10", // deleted
+            "cpu,foo=you,region=east bar=2 20",
+            "cpu,foo=you,region=east bar=7 30", // deleted
+            "cpu,foo=me,region=west bar=1 40",
+            "cpu,foo=me bar=1 50", // not deleted: `region` is NULL
+            "cpu,foo=them,region=east bar=3 60", // deleted
+        ];
+        // delete predicate
+        // delete from cpu where 0 <= time <= 100 and (foo in ('me', 'them') or bar > 5) and region !~ /^west/
+        let pred = DeletePredicate {
+            range: TimestampRange::new(0, 100),
+            exprs: vec![
+                DeleteExpr::Or(vec![
+                    DeleteExpr::in_list(
+                        "foo".to_string(),
+                        vec![
+                            Scalar::String("me".to_string()),
+                            Scalar::String("them".to_string()),
+                        ],
+                        false,
+                    ),
+                    DeleteExpr::new("bar".to_string(), Op::Gt, Scalar::F64((5.0).into())),
+                ]),
+                DeleteExpr::new(
+                    "region".to_string(),
+                    Op::RegexNotMatch,
+                    Scalar::String("^west".to_string()),
+                ),
+            ],
+        };
+
+        all_scenarios_for_one_chunk(vec![&pred], vec![], lp_lines, table_name, partition_key).await
+    }
+}
+
+#[derive(Debug)]
+/// Setup for delete query test with one table and one chunk that has NULL tags. A plain `!=`
+/// comparison deletes rows with a NULL tag while a `NOT IN` expression retains them.
+pub struct TwoDeletesNullTagsOneChunk {}
+#[async_trait]
+impl DbSetup for TwoDeletesNullTagsOneChunk {
+    async fn make(&self) -> Vec<DbScenario> {
+        let partition_key = "1970-01-01T00";
+        let table_name = "cpu";
+        // chunk data
+        let lp_lines = vec![
+            "cpu,foo=me bar=1 10",
+            "cpu,foo=you bar=2 20", // deleted by pred1
+            "cpu bar=3 25",         // deleted by pred1
+            "cpu,foo=me bar=4 30",
+            "cpu,foo=you bar=5 40", // deleted by pred2
+            "cpu bar=6 50",
+        ];
+        // delete predicates
+        // pred1: delete from cpu where 0 <= time <= 25 and foo != 'me'
+        let pred1 = DeletePredicate {
+            range: TimestampRange::new(0, 25),
+            exprs: vec![DeleteExpr::new(
+                "foo".to_string(),
+                Op::Ne,
+                Scalar::String("me".to_string()),
+            )],
+        };
+        // pred2: delete from cpu where 30 <= time <= 60 and foo not in ('me')
+        let pred2 = DeletePredicate {
+            range: TimestampRange::new(30, 60),
+            exprs: vec![DeleteExpr::in_list(
+                "foo".to_string(),
+                vec![Scalar::String("me".to_string())],
+                true,
+            )],
+        };
+
+        all_scenarios_for_one_chunk(
+            vec![&pred1, &pred2],
+            vec![],
+            lp_lines,
+            table_name,
+            partition_key,
+        )
+        .await
+    }
+}
+
 #[derive(Debug)]
 /// Setup for multi-expression delete query test with one table and one chunk. Two deletes at
 /// different chunk stages.
@@ -1419,3 +1419,26 @@ impl DbSetup for TwoChunksMissingColumns {
         .await
     }
 }
+
+#[derive(Debug)]
+/// Single chunk where some series have NULL `temp` values at their earliest
+/// and latest timestamps, used to test first/last selectors skip NULLs.
+pub struct OneMeasurementNullSelectors {}
+#[async_trait]
+impl DbSetup for OneMeasurementNullSelectors {
+    async fn make(&self) -> Vec<DbScenario> {
+        let partition_key = "1970-01-01T00";
+
+        let lp_lines = vec![
+            "h2o,state=MA other_temp=1.0 20",
+            "h2o,state=MA temp=70.4 50",
+            "h2o,state=MA temp=72.4 250",
+            "h2o,state=MA other_temp=2.0 300",
+            "h2o,state=CA temp=90.0 200",
+            "h2o,state=CA temp=88.6 150",
+            "h2o,state=CA temp=90.5 350",
+        ];
+
+        all_scenarios_for_one_chunk(vec![], vec![], lp_lines, "h2o", partition_key).await
+    }
+}
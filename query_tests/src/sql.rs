@@ -8,7 +8,7 @@ use crate::scenarios;
 use super::scenarios::*;
 use arrow::record_batch::RecordBatch;
 use arrow_util::assert_batches_sorted_eq;
-use datafusion::error::DataFusionError;
+use datafusion::{error::DataFusionError, physical_plan::displayable};
 use iox_query::frontend::sql::SqlQueryPlanner;
 use test_helpers::assert_contains;
 
@@ -200,6 +200,59 @@ async fn sql_aggregate_on_tags() {
     .await;
 }
 
+#[tokio::test]
+async fn sql_selectors_pushed_down() {
+    test_helpers::maybe_start_logging();
+
+    // first/last partial aggregates are computed by read buffer chunks, and
+    // must skip the NULL values at the start and end of the MA series.
+    let sql = "select state, \
+        selector_first(temp, time)['value'] as first_temp, \
+        selector_first(temp, time)['time'] as first_time, \
+        selector_last(temp, time)['value'] as last_temp, \
+        selector_last(temp, time)['time'] as last_time \
+        from h2o group by state";
+    let expected = vec![
+        "+-------+------------+--------------------------------+-----------+--------------------------------+",
+        "| state | first_temp | first_time                     | last_temp | last_time                      |",
+        "+-------+------------+--------------------------------+-----------+--------------------------------+",
+        "| CA    | 88.6       | 1970-01-01T00:00:00.000000150Z | 90.5      | 1970-01-01T00:00:00.000000350Z |",
+        "| MA    | 70.4       | 1970-01-01T00:00:00.000000050Z | 72.4      | 1970-01-01T00:00:00.000000250Z |",
+        "+-------+------------+--------------------------------+-----------+--------------------------------+",
+    ];
+
+    let db_setup = OneMeasurementNullSelectors {};
+    for scenario in db_setup.make().await {
+        let DbScenario {
+            scenario_name, db, ..
+        } = scenario;
+
+        println!("Running scenario '{}'", scenario_name);
+        let planner = SqlQueryPlanner::default();
+        let ctx = db.new_query_context(None);
+
+        let physical_plan = planner
+            .query(sql, &ctx)
+            .await
+            .expect("built plan successfully");
+
+        // Only chunks loaded into the read buffer compute aggregates.
+        let plan = displayable(physical_plan.as_ref()).indent().to_string();
+        let pushed_down =
+            scenario_name.contains("stage=ReadBuffer") || scenario_name.contains("stage=OnDemand");
+        assert_eq!(
+            plan.contains("ChunkAggregateExec"),
+            pushed_down,
+            "unexpected plan for scenario '{}':\n{}",
+            scenario_name,
+            plan
+        );
+
+        let results: Vec<RecordBatch> = ctx.collect(physical_plan).await.expect("Running plan");
+        assert_batches_sorted_eq!(&expected, &results);
+    }
+}
+
 #[tokio::test]
 async fn sql_select_with_schema_merge_subset() {
     let expected = vec![
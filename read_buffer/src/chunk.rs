@@ -1,7 +1,7 @@
 use crate::{
     metrics::Metrics,
     row_group::{ColumnName, Predicate, RowGroup},
-    schema::{AggregateType, ResultSchema},
+    schema::{AggregateType, ResultSchema, TimeWindow},
     table::{self, Table},
 };
 use arrow::{error::ArrowError, record_batch::RecordBatch};
@@ -193,16 +193,22 @@ impl Chunk {
     /// columns, optionally filtered by the provided predicate. Results are
     /// merged across all row groups.
     ///
+    /// When `window` is provided rows are additionally grouped by the time
+    /// window their timestamp falls within, with the start of each window
+    /// emitted as a timestamp column following the group columns.
+    ///
     /// Note: `read_aggregate` currently only supports grouping on "tag"
-    /// columns.
-    pub(crate) fn read_aggregate(
+    /// columns, and does not support delete predicates.
+    pub fn read_aggregate(
         &self,
         predicate: Predicate,
         group_columns: &Selection<'_>,
+        window: Option<TimeWindow>,
         aggregates: &[(ColumnName<'_>, AggregateType)],
     ) -> Result<table::ReadAggregateResults> {
+        debug!(%predicate, ?group_columns, ?window, ?aggregates, "read_aggregate called");
         self.table
-            .read_aggregate(predicate, group_columns, aggregates)
+            .read_aggregate(predicate, group_columns, window, aggregates)
             .context(TableSnafu)
     }
 
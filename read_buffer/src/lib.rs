@@ -19,7 +19,7 @@ pub use self::schema::*;
 pub use chunk::{Chunk as RBChunk, ChunkBuilder as RBChunkBuilder, Error};
 pub use metrics::Metrics as ChunkMetrics;
 pub use row_group::{BinaryExpr, Predicate};
-pub use table::ReadFilterResults;
+pub use table::{ReadAggregateResults, ReadFilterResults};
 
 /// THIS MODULE SHOULD ONLY BE IMPORTED FOR BENCHMARKS.
 ///
@@ -14,7 +14,7 @@ use snafu::{ResultExt, Snafu};
 
 use crate::column::{self, cmp::Operator, Column, RowIDs, RowIDsOption};
 use crate::schema;
-use crate::schema::{AggregateType, LogicalDataType, ResultSchema};
+use crate::schema::{AggregateType, LogicalDataType, ResultSchema, TimeWindow};
 use crate::value::{
     AggregateVec, EncodedValues, OwnedValue, Scalar, Value, Values, ValuesIterator,
 };
@@ -530,11 +530,27 @@ impl RowGroup {
         predicate: &Predicate,
         group_columns: &[ColumnName<'_>],
         aggregates: &[(ColumnName<'_>, AggregateType)],
+    ) -> ReadAggregateResult<'_> {
+        self.read_aggregate_window(predicate, group_columns, None, aggregates)
+    }
+
+    /// The same as `read_aggregate`, but when `window` is provided the group
+    /// key for each row is additionally comprised of the start of the time
+    /// window the row's timestamp falls within.
+    ///
+    /// Note: `read_aggregate_window` does not order results.
+    pub fn read_aggregate_window(
+        &self,
+        predicate: &Predicate,
+        group_columns: &[ColumnName<'_>],
+        window: Option<TimeWindow>,
+        aggregates: &[(ColumnName<'_>, AggregateType)],
     ) -> ReadAggregateResult<'_> {
         let schema = ResultSchema {
             select_columns: vec![],
             group_columns: self.meta.schema_for_column_names(group_columns),
             aggregate_columns: self.meta.schema_for_aggregate_column_names(aggregates),
+            window,
         };
 
         let mut result = ReadAggregateResult {
@@ -543,22 +559,31 @@ impl RowGroup {
         };
 
         // Pure column aggregates - no grouping.
-        if group_columns.is_empty() {
+        if group_columns.is_empty() && window.is_none() {
             self.aggregate_columns(predicate, &mut result);
             return result;
         }
 
-        // All of the below assume grouping by columns.
+        // All of the below assume grouping by columns and/or time windows.
 
         // Handle case where there are no predicates and all the columns being
         // grouped support constant-time expression of the row_ids belonging to
-        // each grouped value.
+        // each grouped value. Time windows have no such pre-computed row ids.
         let all_group_cols_pre_computed = result.schema.group_column_names_iter().all(|name| {
             self.column_by_name(name)
                 .properties()
                 .has_pre_computed_row_ids
         });
-        if predicate.is_empty() && all_group_cols_pre_computed {
+
+        // First/last selectors need the timestamp of each row, so are always
+        // computed by hashing.
+        let has_selectors = result
+            .schema
+            .aggregate_columns
+            .iter()
+            .any(|(_, agg_type, _)| agg_type.is_selector());
+        if predicate.is_empty() && window.is_none() && !has_selectors && all_group_cols_pre_computed
+        {
             self.read_group_all_rows_all_rle(&mut result);
             return result;
         }
@@ -579,7 +604,7 @@ impl RowGroup {
         // materialise all *encoded* values for each column we are grouping on.
         // These will not be the logical (typically string) values, but will be
         // vectors of integers representing the physical values.
-        let groupby_encoded_ids: Vec<_> = result
+        let mut groupby_encoded_ids: Vec<_> = result
             .schema
             .group_column_names_iter()
             .map(|name| {
@@ -625,31 +650,98 @@ impl RowGroup {
             aggregate_columns_data.push(column_values);
         }
 
+        // When windowing, assign each distinct window start an encoded id so
+        // that windows can be grouped on in the same way as any other group
+        // column. The window ids are the last part of each group key.
+        let mut window_starts = vec![];
+        if let Some(window) = window {
+            let (ids, starts) = self.encoded_window_ids(window, filter_row_ids.as_deref());
+            groupby_encoded_ids.push(ids);
+            window_starts = starts;
+        }
+
+        // Materialise the timestamps first/last values are selected by.
+        let selector_times = has_selectors.then(|| match &filter_row_ids {
+            Some(row_ids) => self.time_column().values(row_ids),
+            None => self.time_column().all_values(),
+        });
+
         // Perform the group by using a hashmap
-        self.read_group_with_hashing(&mut result, &groupby_encoded_ids, aggregate_columns_data);
+        self.read_group_with_hashing(
+            &mut result,
+            &groupby_encoded_ids,
+            &window_starts,
+            aggregate_columns_data,
+            selector_times.as_ref(),
+        );
         result
     }
 
+    // Materialises an encoded window id for each row in `row_ids`, or all rows
+    // if `row_ids` is `None`. Returns the encoded ids along with a mapping of
+    // each encoded id to the start of the window it represents.
+    fn encoded_window_ids(
+        &self,
+        window: TimeWindow,
+        row_ids: Option<&[u32]>,
+    ) -> (Vec<u32>, Vec<i64>) {
+        let time_values = match row_ids {
+            Some(row_ids) => self.time_column().values(row_ids),
+            None => self.time_column().all_values(),
+        };
+
+        let mut window_ids: HashMap<i64, u32> = HashMap::default();
+        let mut window_starts = vec![];
+        let encoded_ids = (0..time_values.len())
+            .map(|row| {
+                let start = window.window_start(time_values.value_i64(row));
+                *window_ids.entry(start).or_insert_with(|| {
+                    window_starts.push(start);
+                    (window_starts.len() - 1) as u32
+                })
+            })
+            .collect::<Vec<_>>();
+
+        (encoded_ids, window_starts)
+    }
+
     // read_group_hash executes a read-group-aggregate operation on the
     // `RowGroup` using a hashmap to build up a collection of group keys and
     // aggregates.
     //
     // read_group_hash accepts a set of conjunctive predicates.
+    //
+    // When the results are windowed, the final column in `groupby_encoded_ids`
+    // holds encoded window ids, which can be decoded via `window_starts`.
     fn read_group_with_hashing<'a>(
         &'a self,
         dst: &mut ReadAggregateResult<'a>,
         groupby_encoded_ids: &[Vec<u32>],
+        window_starts: &[i64],
         aggregate_columns_data: Vec<Values<'a>>,
+        selector_times: Option<&Values<'a>>,
     ) {
         // An optimised approach to building the hashmap of group keys using a
         // single 128-bit integer as the group key. If grouping is on more than
         // four columns then a fallback to using an vector as a key will happen.
-        if dst.schema.group_columns.len() <= 4 {
-            self.read_group_hash_with_u128_key(dst, groupby_encoded_ids, aggregate_columns_data);
+        if groupby_encoded_ids.len() <= 4 {
+            self.read_group_hash_with_u128_key(
+                dst,
+                groupby_encoded_ids,
+                window_starts,
+                aggregate_columns_data,
+                selector_times,
+            );
             return;
         }
 
-        self.read_group_hash_with_vec_key(dst, groupby_encoded_ids, aggregate_columns_data);
+        self.read_group_hash_with_vec_key(
+            dst,
+            groupby_encoded_ids,
+            window_starts,
+            aggregate_columns_data,
+            selector_times,
+        );
     }
 
     // This function is used with `read_group_hash` when the number of columns
@@ -659,7 +751,9 @@ impl RowGroup {
         &'a self,
         dst: &mut ReadAggregateResult<'a>,
         groupby_encoded_ids: &[Vec<u32>],
+        window_starts: &[i64],
         aggregate_input_columns: Vec<Values<'a>>,
+        selector_times: Option<&Values<'a>>,
     ) {
         let total_rows = groupby_encoded_ids[0].len();
         assert!(groupby_encoded_ids.iter().all(|x| x.len() == total_rows));
@@ -667,7 +761,11 @@ impl RowGroup {
         // These vectors will hold the decoded values of each part of each
         // group key. They are the output columns of the input columns used for
         // the grouping operation.
-        let mut group_cols_out = vec![vec![]; groupby_encoded_ids.len()];
+        let mut group_cols_out = vec![vec![]; dst.schema.group_columns.len()];
+
+        // When windowing, this vector holds the decoded window start for each
+        // group key.
+        let mut window_col_out = vec![];
 
         // Each of these vectors will be used to store each aggregate row-value
         // for a specific aggregate result column.
@@ -694,7 +792,7 @@ impl RowGroup {
 
         // key_buf will be used as a temporary buffer for group keys represented
         // as a `Vec<u32>`.
-        let mut key_buf = vec![0; dst.schema.group_columns.len()];
+        let mut key_buf = vec![0; groupby_encoded_ids.len()];
         let mut next_ordinal_id = 0; // assign a position for each group key in output columns.
         for row in 0..total_rows {
             // update the group key buffer with the group key for this row
@@ -710,8 +808,10 @@ impl RowGroup {
                     // with the values present in the input columns at the
                     // current row.
                     for (agg_col_i, aggregate_result) in agg_cols_out.iter_mut().enumerate() {
-                        aggregate_result.update(
+                        update_aggregate(
+                            aggregate_result,
                             &aggregate_input_columns[agg_col_i],
+                            selector_times,
                             row,
                             *ordinal_id,
                         )
@@ -723,8 +823,10 @@ impl RowGroup {
                     // with the values present in the input columns at the
                     // current row.
                     for (agg_col_i, aggregate_result) in agg_cols_out.iter_mut().enumerate() {
-                        aggregate_result.update(
+                        update_aggregate(
+                            aggregate_result,
                             &aggregate_input_columns[agg_col_i],
+                            selector_times,
                             row,
                             next_ordinal_id,
                         )
@@ -744,6 +846,12 @@ impl RowGroup {
                         };
                     }
 
+                    // Add the decoded window start, which is always the last
+                    // part of the group key.
+                    if let Some(window_ids) = groupby_encoded_ids.get(group_cols_out.len()) {
+                        window_col_out.push(window_starts[window_ids[row] as usize]);
+                    }
+
                     // update the hashmap with the encoded group key and the
                     // associated ordinal offset.
                     entry.insert(key_buf.clone(), next_ordinal_id);
@@ -753,6 +861,7 @@ impl RowGroup {
         }
 
         dst.group_key_cols = group_cols_out;
+        dst.window_key_col = window_col_out;
         dst.aggregate_cols = agg_cols_out;
     }
 
@@ -767,17 +876,23 @@ impl RowGroup {
         &'a self,
         dst: &mut ReadAggregateResult<'a>,
         groupby_encoded_ids: &[Vec<u32>],
+        window_starts: &[i64],
         aggregate_input_columns: Vec<Values<'a>>,
+        selector_times: Option<&Values<'a>>,
     ) {
         let total_rows = groupby_encoded_ids[0].len();
         assert!(groupby_encoded_ids.iter().all(|x| x.len() == total_rows));
-        assert!(dst.schema.group_columns.len() <= 4);
+        assert!(groupby_encoded_ids.len() <= 4);
 
         // These vectors will hold the decoded values of each part of each
         // group key. They are the output columns derived from the input
         // grouping columns.
         let mut group_cols_out: Vec<Vec<Option<ColumnName<'a>>>> = vec![];
-        group_cols_out.resize(groupby_encoded_ids.len(), vec![]);
+        group_cols_out.resize(dst.schema.group_columns.len(), vec![]);
+
+        // When windowing, this vector holds the decoded window start for each
+        // group key.
+        let mut window_col_out = vec![];
 
         // Each of these vectors will be used to store each aggregate row-value
         // for a specific aggregate result column.
@@ -819,8 +934,10 @@ impl RowGroup {
                     // with the values present in the input columns at the
                     // current row.
                     for (agg_col_i, aggregate_result) in agg_cols_out.iter_mut().enumerate() {
-                        aggregate_result.update(
+                        update_aggregate(
+                            aggregate_result,
                             &aggregate_input_columns[agg_col_i],
+                            selector_times,
                             row,
                             *ordinal_id,
                         )
@@ -831,8 +948,10 @@ impl RowGroup {
                     // with the values present in the input columns at the
                     // current row.
                     for (agg_col_i, aggregate_result) in agg_cols_out.iter_mut().enumerate() {
-                        aggregate_result.update(
+                        update_aggregate(
+                            aggregate_result,
                             &aggregate_input_columns[agg_col_i],
+                            selector_times,
                             row,
                             next_ordinal_id,
                         )
@@ -852,6 +971,12 @@ impl RowGroup {
                         };
                     }
 
+                    // Add the decoded window start, which is always the last
+                    // part of the group key.
+                    if let Some(window_ids) = groupby_encoded_ids.get(group_cols_out.len()) {
+                        window_col_out.push(window_starts[window_ids[row] as usize]);
+                    }
+
                     // update the hashmap with the encoded group key and the
                     // associated ordinal offset.
                     entry.insert(group_key_packed, next_ordinal_id);
@@ -861,6 +986,7 @@ impl RowGroup {
         }
 
         dst.group_key_cols = group_cols_out;
+        dst.window_key_col = window_col_out;
         dst.aggregate_cols = agg_cols_out;
     }
 
@@ -991,8 +1117,9 @@ impl RowGroup {
                         let agg = agg_col.count(&group_key_row_ids.to_vec()) as u64;
                         agg_cols_out[agg_col_i].push(Value::Scalar(Scalar::U64(agg)))
                     }
-                    AggregateType::First => {}
-                    AggregateType::Last => {}
+                    AggregateType::First | AggregateType::Last => {
+                        unreachable!("first/last are computed by hashing")
+                    }
                     AggregateType::Min => {
                         let agg = agg_col.min(&group_key_row_ids.to_vec());
                         agg_cols_out[agg_col_i].push(agg);
@@ -1070,8 +1197,16 @@ impl RowGroup {
                         let value = Value::Scalar(Scalar::U64(col.count(&row_ids) as u64));
                         agg_vec.push(value);
                     }
-                    AggregateType::First => unimplemented!("First not yet implemented"),
-                    AggregateType::Last => unimplemented!("Last not yet implemented"),
+                    AggregateType::First | AggregateType::Last => {
+                        // A single NULL selection, replaced by the first
+                        // (last) non-NULL value of the rows.
+                        agg_vec.push(Value::Null);
+                        let values = col.values(&row_ids);
+                        let times = self.time_column().values(&row_ids);
+                        for i in 0..row_ids.len() {
+                            agg_vec.update_selector(&values, i, times.value_i64(i), 0);
+                        }
+                    }
                     AggregateType::Min => agg_vec.push(col.min(&row_ids)),
                     AggregateType::Max => agg_vec.push(col.max(&row_ids)),
                     AggregateType::Sum => agg_vec.push(Value::Scalar(col.sum(&row_ids))),
@@ -1356,6 +1491,35 @@ impl From<RecordBatch> for RowGroup {
     }
 }
 
+// Appends the aggregate at `offset` in `src` to `dst`, along with the
+// timestamp of first/last values.
+fn push_aggregate(dst: &mut AggregateVec, src: &AggregateVec, offset: usize) {
+    if dst.is_selector() {
+        let dst_offset = dst.len();
+        dst.update_selected(src, offset, dst_offset);
+    } else {
+        dst.push(src.value(offset));
+    }
+}
+
+// Updates the aggregate at `offset` with the value of `row` in `values`.
+// First/last values are selected by the timestamp of `row` in
+// `selector_times`.
+fn update_aggregate(
+    dst: &mut AggregateVec,
+    values: &Values<'_>,
+    selector_times: Option<&Values<'_>>,
+    row: usize,
+    offset: usize,
+) {
+    match selector_times {
+        Some(times) if dst.is_selector() => {
+            dst.update_selector(values, row, times.value_i64(row), offset)
+        }
+        _ => dst.update(values, row, offset),
+    }
+}
+
 // Packs an encoded values into a `u128` at `pos`, which must be `[0,4)`.
 #[inline(always)]
 fn pack_u32_in_u128(packed_value: u128, encoded_id: u32, pos: usize) -> u128 {
@@ -2006,6 +2170,10 @@ pub struct ReadAggregateResult<'row_group> {
     // The collection of columns forming the group keys.
     pub(crate) group_key_cols: Vec<Vec<Option<&'row_group str>>>,
 
+    // The start of the time window forming the final part of each group key,
+    // when the results are windowed. Empty otherwise.
+    pub(crate) window_key_col: Vec<i64>,
+
     // The collection of aggregate columns. Each value in each column is an
     // aggregate associated with the group key built from values in the group
     // columns and the same ordinal position.
@@ -2044,24 +2212,43 @@ impl<'row_group> ReadAggregateResult<'row_group> {
     // aggregate row.
     pub fn cardinality(&self) -> usize {
         if self.group_key_cols.is_empty() {
-            return 0;
+            return self.window_key_col.len();
         }
         self.group_key_cols[0].len()
     }
 
     // Is this result for a grouped aggregate?
     pub fn is_grouped_aggregate(&self) -> bool {
-        !self.group_key_cols.is_empty()
+        !self.group_key_cols.is_empty() || self.schema.window.is_some()
     }
 
-    // The number of grouping columns.
+    // The number of grouping columns, including any window column.
     pub fn group_key_columns(&self) -> usize {
-        self.group_key_cols.len()
+        self.group_key_cols.len() + usize::from(self.schema.window.is_some())
     }
 
     // Whether or not the rows in the results are sorted by group keys or not.
     pub fn group_keys_sorted(&self) -> bool {
-        self.group_key_cols.is_empty() || self.group_keys_sorted
+        !self.is_grouped_aggregate() || self.group_keys_sorted
+    }
+
+    // Compares the group key at `self_i` with the group key at `other_i` in
+    // `other`. Window starts are the least significant part of a group key.
+    fn cmp_group_keys(&self, self_i: usize, other: &Self, other_i: usize) -> Ordering {
+        for (col, other_col) in self.group_key_cols.iter().zip(&other.group_key_cols) {
+            match col[self_i].partial_cmp(&other_col[other_i]) {
+                Some(Ordering::Equal) | None => continue,
+                Some(ord) => return ord,
+            }
+        }
+
+        match (
+            self.window_key_col.get(self_i),
+            other.window_key_col.get(other_i),
+        ) {
+            (Some(a), Some(b)) => a.cmp(b),
+            _ => Ordering::Equal,
+        }
     }
 
     /// Merges `other` and self, returning a new set of results.
@@ -2110,6 +2297,9 @@ impl<'row_group> ReadAggregateResult<'row_group> {
                 for (col_i, col) in result.group_key_cols.iter_mut().enumerate() {
                     col.extend(other.group_key_cols[col_i].iter().skip(other_i));
                 }
+                result
+                    .window_key_col
+                    .extend(other.window_key_col.iter().skip(other_i));
 
                 // add the rest of other's aggregate columns
                 //
@@ -2119,36 +2309,46 @@ impl<'row_group> ReadAggregateResult<'row_group> {
                 // and an iterator of the same type to extend the aggregate vec.
                 for (col_i, (_, _, data_type)) in result.schema.aggregate_columns.iter().enumerate()
                 {
+                    if other.aggregate_cols[0].is_selector() {
+                        let arr = other.aggregate_cols.remove(0);
+                        let dst = &mut result.aggregate_cols[col_i];
+                        for i in other_i..arr.len() {
+                            let offset = dst.len();
+                            dst.update_selected(&arr, i, offset);
+                        }
+                        continue;
+                    }
+
                     match data_type {
                         LogicalDataType::Integer => {
                             let arr = other.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
-                                .extend_with_i64(arr.take_as_i64().into_iter());
+                                .extend_with_i64(arr.take_as_i64().into_iter().skip(other_i));
                         }
                         LogicalDataType::Unsigned => {
                             let arr = other.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
-                                .extend_with_u64(arr.take_as_u64().into_iter());
+                                .extend_with_u64(arr.take_as_u64().into_iter().skip(other_i));
                         }
                         LogicalDataType::Float => {
                             let arr = other.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
-                                .extend_with_f64(arr.take_as_f64().into_iter());
+                                .extend_with_f64(arr.take_as_f64().into_iter().skip(other_i));
                         }
                         LogicalDataType::String => {
                             let arr = other.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
-                                .extend_with_str(arr.take_as_str().into_iter());
+                                .extend_with_str(arr.take_as_str().into_iter().skip(other_i));
                         }
                         LogicalDataType::Binary => {
                             let arr = other.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
-                                .extend_with_bytes(arr.take_as_bytes().into_iter());
+                                .extend_with_bytes(arr.take_as_bytes().into_iter().skip(other_i));
                         }
                         LogicalDataType::Boolean => {
                             let arr = other.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
-                                .extend_with_bool(arr.take_as_bool().into_iter());
+                                .extend_with_bool(arr.take_as_bool().into_iter().skip(other_i));
                         }
                     }
                 }
@@ -2159,40 +2359,53 @@ impl<'row_group> ReadAggregateResult<'row_group> {
                 for (col_i, col) in result.group_key_cols.iter_mut().enumerate() {
                     col.extend(self.group_key_cols[col_i].iter().skip(self_i));
                 }
+                result
+                    .window_key_col
+                    .extend(self.window_key_col.iter().skip(self_i));
 
                 // add the rest of self's aggregate columns
                 for (col_i, (_, _, data_type)) in result.schema.aggregate_columns.iter().enumerate()
                 {
+                    if self.aggregate_cols[0].is_selector() {
+                        let arr = self.aggregate_cols.remove(0);
+                        let dst = &mut result.aggregate_cols[col_i];
+                        for i in self_i..arr.len() {
+                            let offset = dst.len();
+                            dst.update_selected(&arr, i, offset);
+                        }
+                        continue;
+                    }
+
                     match data_type {
                         LogicalDataType::Integer => {
                             let arr = self.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
-                                .extend_with_i64(arr.take_as_i64().into_iter());
+                                .extend_with_i64(arr.take_as_i64().into_iter().skip(self_i));
                         }
                         LogicalDataType::Unsigned => {
                             let arr = self.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
-                                .extend_with_u64(arr.take_as_u64().into_iter());
+                                .extend_with_u64(arr.take_as_u64().into_iter().skip(self_i));
                         }
                         LogicalDataType::Float => {
                             let arr = self.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
-                                .extend_with_f64(arr.take_as_f64().into_iter());
+                                .extend_with_f64(arr.take_as_f64().into_iter().skip(self_i));
                         }
                         LogicalDataType::String => {
                             let arr = self.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
-                                .extend_with_str(arr.take_as_str().into_iter());
+                                .extend_with_str(arr.take_as_str().into_iter().skip(self_i));
                         }
                         LogicalDataType::Binary => {
                             let arr = self.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
-                                .extend_with_bytes(arr.take_as_bytes().into_iter());
+                                .extend_with_bytes(arr.take_as_bytes().into_iter().skip(self_i));
                         }
                         LogicalDataType::Boolean => {
                             let arr = self.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
-                                .extend_with_bool(arr.take_as_bool().into_iter());
+                                .extend_with_bool(arr.take_as_bool().into_iter().skip(self_i));
                         }
                     }
                 }
@@ -2202,19 +2415,7 @@ impl<'row_group> ReadAggregateResult<'row_group> {
 
             // compare the next row in self and other and determine if there is
             // a clear lexicographic order.
-            let mut ord = Ordering::Equal;
-            for i in 0..result.schema.group_columns.len() {
-                match self.group_key_cols[i][self_i].partial_cmp(&other.group_key_cols[i][other_i])
-                {
-                    Some(o) => {
-                        ord = o;
-                        if !matches!(ord, Ordering::Equal) {
-                            break;
-                        }
-                    }
-                    None => continue,
-                }
-            }
+            let ord = self.cmp_group_keys(self_i, &other, other_i);
 
             match ord {
                 Ordering::Less => {
@@ -2222,8 +2423,11 @@ impl<'row_group> ReadAggregateResult<'row_group> {
                     for (col_i, col) in result.group_key_cols.iter_mut().enumerate() {
                         col.push(self.group_key_cols[col_i][self_i]);
                     }
+                    if let Some(window_start) = self.window_key_col.get(self_i) {
+                        result.window_key_col.push(*window_start);
+                    }
                     for (col_i, col) in result.aggregate_cols.iter_mut().enumerate() {
-                        col.push(self.aggregate_cols[col_i].value(self_i));
+                        push_aggregate(col, &self.aggregate_cols[col_i], self_i);
                     }
                     self_i += 1;
                 }
@@ -2232,9 +2436,19 @@ impl<'row_group> ReadAggregateResult<'row_group> {
                     for (col_i, col) in result.group_key_cols.iter_mut().enumerate() {
                         col.push(self.group_key_cols[col_i][self_i]);
                     }
+                    if let Some(window_start) = self.window_key_col.get(self_i) {
+                        result.window_key_col.push(*window_start);
+                    }
 
                     // merge all the aggregates for this group key.
                     for (col_i, col) in result.aggregate_cols.iter_mut().enumerate() {
+                        if col.is_selector() {
+                            let offset = col.len();
+                            col.update_selected(&self.aggregate_cols[col_i], self_i, offset);
+                            col.update_selected(&other.aggregate_cols[col_i], other_i, offset);
+                            continue;
+                        }
+
                         let self_value = self.aggregate_cols[col_i].value(self_i);
                         let other_value = other.aggregate_cols[col_i].value(other_i);
                         let (_, agg_type, _) = &self.schema.aggregate_columns[col_i];
@@ -2257,7 +2471,9 @@ impl<'row_group> ReadAggregateResult<'row_group> {
                                 None => self_value,
                             },
                             AggregateType::Sum => self_value + other_value,
-                            _ => unimplemented!("first/last not implemented"),
+                            AggregateType::First | AggregateType::Last => {
+                                unreachable!("selectors are merged by timestamp")
+                            }
                         });
                     }
                     self_i += 1;
@@ -2268,8 +2484,11 @@ impl<'row_group> ReadAggregateResult<'row_group> {
                     for (col_i, col) in result.group_key_cols.iter_mut().enumerate() {
                         col.push(other.group_key_cols[col_i][other_i]);
                     }
+                    if let Some(window_start) = other.window_key_col.get(other_i) {
+                        result.window_key_col.push(*window_start);
+                    }
                     for (col_i, col) in result.aggregate_cols.iter_mut().enumerate() {
-                        col.push(other.aggregate_cols[col_i].value(other_i));
+                        push_aggregate(col, &other.aggregate_cols[col_i], other_i);
                     }
                     other_i += 1;
                 }
@@ -2305,7 +2524,7 @@ impl<'row_group> ReadAggregateResult<'row_group> {
         // Create a vector of group keys, which allows us to determine a
         // permutation by which we should sort all columns.
         let mut group_keys = (0..self.rows())
-            .map(|i| GroupKey::new(&self.group_key_cols, i))
+            .map(|i| GroupKey::new(&self.group_key_cols, &self.window_key_col, i))
             .collect::<Vec<_>>();
 
         // sort the vector of group keys, which will give us a permutation
@@ -2324,7 +2543,11 @@ impl<'row_group> ReadAggregateResult<'row_group> {
                 }
             }
 
-            std::cmp::Ordering::Equal
+            // window starts are the least significant part of the group key.
+            match (a.window.get(a.row_offset), b.window.get(b.row_offset)) {
+                (Some(a), Some(b)) => a.cmp(b),
+                _ => std::cmp::Ordering::Equal,
+            }
         });
 
         // Now create a permutation by looking at how the row_offsets have been
@@ -2342,6 +2565,9 @@ impl<'row_group> ReadAggregateResult<'row_group> {
         for col in self.group_key_cols.iter_mut() {
             *col = perm.apply_slice(col.as_slice());
         }
+        if !self.window_key_col.is_empty() {
+            self.window_key_col = perm.apply_slice(self.window_key_col.as_slice());
+        }
 
         for col in self.aggregate_cols.iter_mut() {
             col.sort_with_permutation(&perm);
@@ -2359,13 +2585,15 @@ impl<'row_group> ReadAggregateResult<'row_group> {
 // `GroupKey`s in a vector and sorting that.
 struct GroupKey<'a> {
     columns: &'a [Vec<Option<&'a str>>],
+    window: &'a [i64],
     row_offset: usize,
 }
 
 impl<'a> GroupKey<'a> {
-    fn new(columns: &'a [Vec<Option<&'a str>>], offset: usize) -> Self {
+    fn new(columns: &'a [Vec<Option<&'a str>>], window: &'a [i64], offset: usize) -> Self {
         Self {
             columns,
+            window,
             row_offset: offset,
         }
     }
@@ -2399,28 +2627,35 @@ impl TryFrom<ReadAggregateResult<'_>> for RecordBatch {
             }
         }
 
+        if result.schema.window.is_some() {
+            columns.push(Arc::new(array::TimestampNanosecondArray::from(
+                std::mem::take(&mut result.window_key_col),
+            )));
+        }
+
         for (_, _, data_type) in &result.schema.aggregate_columns {
+            let aggregate_col = result.aggregate_cols.remove(0); // move column out of result
+            let selected_times = aggregate_col.selected_times().map(<[_]>::to_vec);
+
             match data_type {
                 LogicalDataType::Integer => {
                     columns.push(Arc::new(array::Int64Array::from(
-                        result.aggregate_cols.remove(0).take_as_i64(),
+                        aggregate_col.take_as_i64(),
                     )));
                 }
                 LogicalDataType::Unsigned => {
                     columns.push(Arc::new(array::UInt64Array::from(
-                        result.aggregate_cols.remove(0).take_as_u64(),
+                        aggregate_col.take_as_u64(),
                     )));
                 }
                 LogicalDataType::Float => {
                     columns.push(Arc::new(array::Float64Array::from(
-                        result.aggregate_cols.remove(0).take_as_f64(),
+                        aggregate_col.take_as_f64(),
                     )));
                 }
                 LogicalDataType::String => {
                     columns.push(Arc::new(array::StringArray::from(
-                        result
-                            .aggregate_cols
-                            .remove(0)
+                        aggregate_col
                             .take_as_str()
                             .iter()
                             .map(|x| x.as_deref())
@@ -2429,9 +2664,7 @@ impl TryFrom<ReadAggregateResult<'_>> for RecordBatch {
                 }
                 LogicalDataType::Binary => {
                     columns.push(Arc::new(array::BinaryArray::from(
-                        result
-                            .aggregate_cols
-                            .remove(0)
+                        aggregate_col
                             .take_as_bytes()
                             .iter()
                             .map(|x| x.as_deref())
@@ -2440,10 +2673,16 @@ impl TryFrom<ReadAggregateResult<'_>> for RecordBatch {
                 }
                 LogicalDataType::Boolean => {
                     columns.push(Arc::new(array::BooleanArray::from(
-                        result.aggregate_cols.remove(0).take_as_bool(),
+                        aggregate_col.take_as_bool(),
                     )));
                 }
             }
+
+            // first/last values are followed by the timestamp they were
+            // selected at.
+            if let Some(times) = selected_times {
+                columns.push(Arc::new(array::TimestampNanosecondArray::from(times)));
+            }
         }
 
         // everything has been moved and copied into record batch.
@@ -2462,6 +2701,7 @@ impl PartialEq for ReadAggregateResult<'_> {
     fn eq(&self, other: &Self) -> bool {
         self.schema() == other.schema()
             && self.group_key_cols == other.group_key_cols
+            && self.window_key_col == other.window_key_col
             && self.aggregate_cols == other.aggregate_cols
     }
 }
@@ -2501,6 +2741,10 @@ impl Display for ReadAggregateResult<'_> {
                         None => write!(f, "NULL,")?,
                     }
                 }
+
+                if let Some(window_start) = self.window_key_col.get(row) {
+                    write!(f, "{},", window_start)?;
+                }
             }
 
             // write row for aggregate columns
@@ -3416,6 +3660,7 @@ west,POST,304,101,203
                         LogicalDataType::Unsigned,
                     ),
                 ],
+                window: None,
             },
             group_key_cols: vec![
                 vec![
@@ -13,6 +13,11 @@ pub struct ResultSchema {
     pub select_columns: Vec<(ColumnType, LogicalDataType)>,
     pub group_columns: Vec<(ColumnType, LogicalDataType)>,
     pub aggregate_columns: Vec<(ColumnType, AggregateType, LogicalDataType)>,
+
+    /// When set, results are additionally grouped by the time window each
+    /// row's timestamp falls within. The window start is emitted as a
+    /// timestamp column following the group columns.
+    pub window: Option<TimeWindow>,
 }
 
 impl ResultSchema {
@@ -51,7 +56,14 @@ impl ResultSchema {
 
     /// The total number of columns the schema represents.
     pub fn len(&self) -> usize {
-        self.select_columns.len() + self.group_columns.len() + self.aggregate_columns.len()
+        self.select_columns.len()
+            + self.group_columns.len()
+            + usize::from(self.window.is_some())
+            + self
+                .aggregate_columns
+                .iter()
+                .map(|(_, agg_type, _)| agg_type.result_columns())
+                .sum::<usize>()
     }
 
     // How to display the name for a column that was constructed as an aggregate
@@ -84,9 +96,17 @@ impl Display for ResultSchema {
             write!(f, "{},", name)?;
         }
 
+        // the window column always follows the group by columns
+        if self.window.is_some() {
+            write!(f, "{},", ::schema::TIME_COLUMN_NAME)?;
+        }
+
         // finally, emit the aggregate columns
-        for (i, _) in self.aggregate_columns.iter().enumerate() {
+        for (i, (_, agg_type, _)) in self.aggregate_columns.iter().enumerate() {
             write!(f, "{}", self.aggregate_result_column_name(i))?;
+            if agg_type.is_selector() {
+                write!(f, ",{}_time", self.aggregate_result_column_name(i))?;
+            }
 
             if i < self.aggregate_columns.len() - 1 {
                 write!(f, ",")?;
@@ -119,20 +139,75 @@ impl TryFrom<&ResultSchema> for ::schema::Schema {
             };
         }
 
-        for (i, (col_type, _, data_type)) in rs.aggregate_columns.iter().enumerate() {
+        if rs.window.is_some() {
+            builder.timestamp();
+        }
+
+        for (i, (col_type, agg_type, data_type)) in rs.aggregate_columns.iter().enumerate() {
             let col_name = rs.aggregate_result_column_name(i);
 
-            match col_type {
-                ColumnType::Field(_) => builder.influx_field(col_name.as_str(), data_type.into()),
-                ColumnType::Other(_) => builder.field(col_name.as_str(), data_type.into()),
-                ct => unreachable!("not possible to aggregate {:?} columns", ct),
+            match (col_type, agg_type) {
+                (ColumnType::Field(_), _) => {
+                    builder.influx_field(col_name.as_str(), data_type.into())
+                }
+                (ColumnType::Other(_), _) | (_, AggregateType::Count) => {
+                    // Counting rows is possible on any column.
+                    builder.field(col_name.as_str(), data_type.into())
+                }
+                (ct, _) => unreachable!("not possible to aggregate {:?} columns", ct),
             };
+
+            if agg_type.is_selector() {
+                builder.field(
+                    format!("{}_time", col_name).as_str(),
+                    ::schema::TIME_DATA_TYPE(),
+                );
+            }
         }
 
         builder.build()
     }
 }
 
+/// A fixed-width time window used to group rows by the start of the window
+/// their timestamp falls within.
+///
+/// Windows are aligned to `offset`, such that the window start for a
+/// timestamp `t` is equivalent to `date_bin(every, t, offset)`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TimeWindow {
+    every: i64,
+    offset: i64,
+}
+
+impl TimeWindow {
+    /// Create a new window of width `every` nanoseconds, aligned to `offset`
+    /// nanoseconds since the epoch.
+    ///
+    /// Panics if `every` is not positive.
+    pub fn new(every: i64, offset: i64) -> Self {
+        assert!(every > 0, "window width must be positive, got {}", every);
+        Self { every, offset }
+    }
+
+    /// The width of the window in nanoseconds.
+    pub fn every(&self) -> i64 {
+        self.every
+    }
+
+    /// The origin windows are aligned to, in nanoseconds since the epoch.
+    pub fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    /// Returns the start of the window that `ts` falls within.
+    pub fn window_start(&self, ts: i64) -> i64 {
+        let delta = ts.wrapping_sub(self.offset);
+        self.offset
+            .wrapping_add(delta.div_euclid(self.every).wrapping_mul(self.every))
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 /// The logical data-type for a column.
 pub enum LogicalDataType {
@@ -201,6 +276,23 @@ pub enum AggregateType {
      * Percentile */
 }
 
+impl AggregateType {
+    /// Returns true for aggregates that select a row, and produce the
+    /// timestamp of the selected row alongside its value.
+    pub fn is_selector(&self) -> bool {
+        matches!(self, Self::First | Self::Last)
+    }
+
+    /// The number of columns the aggregate produces in results.
+    pub fn result_columns(&self) -> usize {
+        if self.is_selector() {
+            2
+        } else {
+            1
+        }
+    }
+}
+
 impl std::fmt::Display for AggregateType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
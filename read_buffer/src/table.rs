@@ -2,7 +2,7 @@ use crate::{
     chunk::ChunkColumnSummary,
     column,
     row_group::{self, ColumnName, Literal, Predicate, RowGroup},
-    schema::{AggregateType, ColumnType, LogicalDataType, ResultSchema},
+    schema::{AggregateType, ColumnType, LogicalDataType, ResultSchema, TimeWindow},
     value::{OwnedValue, Scalar, Value},
     BinaryExpr,
 };
@@ -332,11 +332,17 @@ impl Table {
     /// key", and each value in the same row for the aggregate columns contains
     /// aggregate values for those group keys.
     ///
-    /// Note: `read_aggregate` currently only supports "tag" columns.
+    /// When a `window` is provided, results are additionally grouped by the
+    /// time window each row's timestamp falls within, and the start of each
+    /// window forms the final part of the group key.
+    ///
+    /// Note: `read_aggregate` currently only supports grouping on "tag"
+    /// columns.
     pub fn read_aggregate<'input>(
         &self,
         predicate: Predicate,
         group_columns: &'input Selection<'_>,
+        window: Option<TimeWindow>,
         aggregates: &'input [(ColumnName<'input>, AggregateType)],
     ) -> Result<ReadAggregateResults> {
         //
@@ -357,6 +363,7 @@ impl Table {
                 Selection::Some(column_names) => meta.schema_for_column_names(column_names),
             },
             aggregate_columns: meta.schema_for_aggregate_column_names(aggregates),
+            window,
             ..ResultSchema::default()
         };
 
@@ -383,38 +390,6 @@ impl Table {
         })
     }
 
-    /// Returns aggregates segmented by grouping keys and windowed by time.
-    ///
-    /// The set of data to be aggregated may be filtered by (currently only)
-    /// equality predicates, but can be ranged by time, which should be
-    /// represented as nanoseconds since the epoch. Results are included if they
-    /// satisfy the predicate and fall with the [min, max) time range domain.
-    ///
-    /// Group keys are determined according to the provided group column names
-    /// (`group_columns`). Currently only grouping by string (tag key) columns
-    /// is supported.
-    ///
-    /// Required aggregates are specified via a tuple comprising a column name
-    /// and the type of aggregation required. Multiple aggregations can be
-    /// applied to the same column.
-    ///
-    /// Results are grouped and windowed according to the `window` parameter,
-    /// which represents an interval in nanoseconds. For example, to window
-    /// results by one minute, window should be set to 600_000_000_000.
-    pub fn aggregate_window<'a>(
-        &self,
-        _time_range: (i64, i64),
-        _predicates: &[(&str, &str)],
-        _group_columns: Vec<ColumnName<'a>>,
-        _aggregates: Vec<(ColumnName<'a>, AggregateType)>,
-        _window: i64,
-    ) -> BTreeMap<Vec<String>, Vec<(ColumnName<'a>, ReadAggregateResults)>> {
-        // identify segments where time range and predicates match could match
-        // using segment meta data, and then execute against those segments and
-        // merge results.
-        todo!()
-    }
-
     //
     // ---- Fast-path first/last selectors.
     //
@@ -1008,13 +983,14 @@ impl ReadAggregateResults {
             return None;
         }
 
-        let mut merged_results = self.row_groups.get(0).unwrap().read_aggregate(
+        let mut merged_results = self.row_groups.get(0).unwrap().read_aggregate_window(
             &self.predicate,
             &self
                 .schema
                 .group_column_names_iter()
                 .map(|s| s.as_str())
                 .collect::<Vec<_>>(),
+            self.schema.window,
             &self
                 .schema
                 .aggregate_columns
@@ -1027,13 +1003,14 @@ impl ReadAggregateResults {
         // Execute against remaining row groups, merging each into the merged
         // set.
         for row_group in self.row_groups.iter().skip(1) {
-            let result = row_group.read_aggregate(
+            let result = row_group.read_aggregate_window(
                 &self.predicate,
                 &self
                     .schema
                     .group_column_names_iter()
                     .map(|s| s.as_str())
                     .collect::<Vec<_>>(),
+                self.schema.window,
                 &self
                     .schema
                     .aggregate_columns
@@ -1105,7 +1082,7 @@ mod test {
         schema::{self, LogicalDataType},
         value::{AggregateVec, OwnedValue, Scalar},
     };
-    use arrow::array::BooleanArray;
+    use arrow::array::{BooleanArray, Float64Array};
     use data_types::{StatValues, Statistics};
 
     #[test]
@@ -1567,6 +1544,7 @@ mod test {
             .read_aggregate(
                 Predicate::default(),
                 &Selection::Some(&[]),
+                None,
                 &[("time", AggregateType::Count), ("time", AggregateType::Sum)],
             )
             .unwrap();
@@ -1600,6 +1578,7 @@ mod test {
             .read_aggregate(
                 Predicate::new(vec![BinaryExpr::from(("region", "=", "west"))]),
                 &Selection::Some(&[]),
+                None,
                 &[("time", AggregateType::Count), ("time", AggregateType::Sum)],
             )
             .unwrap();
@@ -1614,6 +1593,7 @@ mod test {
         let results = table.read_aggregate(
             Predicate::new(vec![BinaryExpr::from(("region", "=", "west"))]),
             &Selection::Some(&["time"]),
+            None,
             &[("min", AggregateType::Min)],
         );
 
@@ -1623,6 +1603,163 @@ mod test {
         ),);
     }
 
+    #[test]
+    fn read_aggregate_window() {
+        // Build first row group.
+        let columns = vec![
+            ("time".to_owned(), ColumnType::create_time(&[100, 150, 300])),
+            (
+                "region".to_owned(),
+                ColumnType::create_tag(&["west", "west", "east"]),
+            ),
+        ];
+        let rg = RowGroup::new(3, columns);
+        let mut table = Table::with_row_group(rg);
+
+        // Build another row group.
+        let columns = vec![
+            ("time".to_owned(), ColumnType::create_time(&[-2, 3, 199])),
+            (
+                "region".to_owned(),
+                ColumnType::create_tag(&["north", "north", "west"]),
+            ),
+        ];
+        let rg = RowGroup::new(3, columns);
+        table.add_row_group(rg);
+
+        // window only
+        let mut results = table
+            .read_aggregate(
+                Predicate::default(),
+                &Selection::Some(&[]),
+                Some(TimeWindow::new(100, 0)),
+                &[("time", AggregateType::Count)],
+            )
+            .unwrap();
+
+        assert_eq!(
+            DisplayReadAggregateResults(vec![results.next_merged_result().unwrap()]).to_string(),
+            "time,time_count\n-100,1\n0,1\n100,3\n300,1\n",
+        );
+
+        // group by tag and window, with windows aligned to an offset.
+        let mut results = table
+            .read_aggregate(
+                Predicate::default(),
+                &Selection::Some(&["region"]),
+                Some(TimeWindow::new(100, 50)),
+                &[("time", AggregateType::Count), ("time", AggregateType::Max)],
+            )
+            .unwrap();
+
+        assert_eq!(
+            DisplayReadAggregateResults(vec![results.next_merged_result().unwrap()]).to_string(),
+            "region,time,time_count,time_max\neast,250,1,300\nnorth,-50,2,3\nwest,50,1,100\nwest,150,2,199\n",
+        );
+
+        // apply a predicate
+        let mut results = table
+            .read_aggregate(
+                Predicate::new(vec![BinaryExpr::from(("region", "=", "west"))]),
+                &Selection::Some(&["region"]),
+                Some(TimeWindow::new(100, 0)),
+                &[("time", AggregateType::Count)],
+            )
+            .unwrap();
+
+        assert_eq!(
+            DisplayReadAggregateResults(vec![results.next_merged_result().unwrap()]).to_string(),
+            "region,time,time_count\nwest,100,3\n",
+        );
+    }
+
+    #[test]
+    fn read_aggregate_first_last() {
+        // Build first row group.
+        let columns = vec![
+            ("time".to_owned(), ColumnType::create_time(&[100, 150, 300])),
+            (
+                "region".to_owned(),
+                ColumnType::create_tag(&["west", "west", "east"]),
+            ),
+            (
+                "temp".to_owned(),
+                ColumnType::Field(Column::from(Float64Array::from(vec![
+                    Some(1.0),
+                    None,
+                    Some(3.0),
+                ]))),
+            ),
+        ];
+        let rg = RowGroup::new(3, columns);
+        let mut table = Table::with_row_group(rg);
+
+        // Build another row group.
+        let columns = vec![
+            ("time".to_owned(), ColumnType::create_time(&[-2, 3, 199])),
+            (
+                "region".to_owned(),
+                ColumnType::create_tag(&["north", "north", "west"]),
+            ),
+            (
+                "temp".to_owned(),
+                ColumnType::Field(Column::from(&[4.0, 5.0, 6.0][..])),
+            ),
+        ];
+        let rg = RowGroup::new(3, columns);
+        table.add_row_group(rg);
+
+        let aggregates = [
+            ("temp", AggregateType::First),
+            ("temp", AggregateType::Last),
+        ];
+
+        // no grouping
+        let mut results = table
+            .read_aggregate(
+                Predicate::default(),
+                &Selection::Some(&[]),
+                None,
+                &aggregates,
+            )
+            .unwrap();
+
+        assert_eq!(
+            DisplayReadAggregateResults(vec![results.next_merged_result().unwrap()]).to_string(),
+            "temp_first,temp_first_time,temp_last,temp_last_time\n4,-2,3,300\n",
+        );
+
+        // group by tag, where the NULL value is never selected.
+        let mut results = table
+            .read_aggregate(
+                Predicate::default(),
+                &Selection::Some(&["region"]),
+                None,
+                &aggregates,
+            )
+            .unwrap();
+
+        assert_eq!(
+            DisplayReadAggregateResults(vec![results.next_merged_result().unwrap()]).to_string(),
+            "region,temp_first,temp_first_time,temp_last,temp_last_time\neast,3,300,3,300\nnorth,4,-2,5,3\nwest,1,100,6,199\n",
+        );
+
+        // group by window, with a predicate
+        let mut results = table
+            .read_aggregate(
+                Predicate::new(vec![BinaryExpr::from(("region", "!=", "east"))]),
+                &Selection::Some(&[]),
+                Some(TimeWindow::new(100, 0)),
+                &aggregates,
+            )
+            .unwrap();
+
+        assert_eq!(
+            DisplayReadAggregateResults(vec![results.next_merged_result().unwrap()]).to_string(),
+            "time,temp_first,temp_first_time,temp_last,temp_last_time\n-100,4,-2,4,-2\n0,5,3,5,3\n100,1,100,6,199\n",
+        );
+    }
+
     #[test]
     fn read_aggregate_result_display() {
         let result_a = ReadAggregateResult {
@@ -1643,6 +1780,7 @@ mod test {
                     AggregateType::Sum,
                     LogicalDataType::Integer,
                 )],
+                window: None,
             },
             group_key_cols: vec![vec![Some("east")], vec![Some("host-a")]],
             aggregate_cols: vec![AggregateVec::SumI64(vec![Some(10)])],
@@ -1667,6 +1805,7 @@ mod test {
                     AggregateType::Sum,
                     LogicalDataType::Integer,
                 )],
+                window: None,
             },
             group_key_cols: vec![vec![Some("west")], vec![Some("host-b")]],
             aggregate_cols: vec![AggregateVec::SumI64(vec![Some(100)])],
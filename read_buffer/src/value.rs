@@ -101,7 +101,44 @@ impl AggregateVec {
             Self::MaxString(arr) => Value::from(arr[offset].as_deref()),
             Self::MaxBytes(arr) => Value::from(arr[offset].as_deref()),
             Self::MaxBool(arr) => Value::from(arr[offset]),
-            _ => unimplemented!("first/last not yet implemented"),
+            Self::FirstU64((arr, _)) => Value::from(arr[offset]),
+            Self::FirstI64((arr, _)) => Value::from(arr[offset]),
+            Self::FirstF64((arr, _)) => Value::from(arr[offset]),
+            Self::FirstString((arr, _)) => Value::from(arr[offset].as_deref()),
+            Self::FirstBytes((arr, _)) => Value::from(arr[offset].as_deref()),
+            Self::FirstBool((arr, _)) => Value::from(arr[offset]),
+            Self::LastU64((arr, _)) => Value::from(arr[offset]),
+            Self::LastI64((arr, _)) => Value::from(arr[offset]),
+            Self::LastF64((arr, _)) => Value::from(arr[offset]),
+            Self::LastString((arr, _)) => Value::from(arr[offset].as_deref()),
+            Self::LastBytes((arr, _)) => Value::from(arr[offset].as_deref()),
+            Self::LastBool((arr, _)) => Value::from(arr[offset]),
+        }
+    }
+
+    /// Returns true if the aggregate is a first or last selector, which
+    /// tracks the timestamp each value was selected at.
+    pub fn is_selector(&self) -> bool {
+        self.selected_times().is_some()
+    }
+
+    /// Returns the timestamp each value of a first or last selector was
+    /// selected at, or `None` for other aggregates.
+    pub fn selected_times(&self) -> Option<&[Option<i64>]> {
+        match self {
+            Self::FirstU64((_, time)) => Some(time),
+            Self::FirstI64((_, time)) => Some(time),
+            Self::FirstF64((_, time)) => Some(time),
+            Self::FirstString((_, time)) => Some(time),
+            Self::FirstBytes((_, time)) => Some(time),
+            Self::FirstBool((_, time)) => Some(time),
+            Self::LastU64((_, time)) => Some(time),
+            Self::LastI64((_, time)) => Some(time),
+            Self::LastF64((_, time)) => Some(time),
+            Self::LastString((_, time)) => Some(time),
+            Self::LastBytes((_, time)) => Some(time),
+            Self::LastBool((_, time)) => Some(time),
+            _ => None,
         }
     }
 
@@ -292,16 +329,168 @@ impl AggregateVec {
                     None => arr[offset] = Some(values.value_bool(row_id)),
                 }
             }
-            // TODO - implement first/last
-            _ => unimplemented!("aggregate update not implemented"),
+            _ => unimplemented!("first/last are updated via update_selector"),
+        }
+    }
+
+    /// Updates a first or last selector at `offset` with the value located at
+    /// `row_id` in the input column held in `values`, which belongs to a row
+    /// with the timestamp `time`.
+    ///
+    /// NULL values are never selected. Of multiple values with the same
+    /// timestamp, the value seen first is kept.
+    ///
+    /// Panics if `self` is not a selector or the type of `Values` does not
+    /// satisfy the aggregate type.
+    pub fn update_selector(
+        &mut self,
+        values: &Values<'_>,
+        row_id: usize,
+        time: i64,
+        offset: usize,
+    ) {
+        let time = (!values.is_null(row_id)).then_some(time);
+
+        match self {
+            Self::FirstU64(dst) => select(
+                dst,
+                offset,
+                time.map(|_| values.value_u64(row_id)),
+                time,
+                true,
+            ),
+            Self::FirstI64(dst) => select(
+                dst,
+                offset,
+                time.map(|_| values.value_i64(row_id)),
+                time,
+                true,
+            ),
+            Self::FirstF64(dst) => select(
+                dst,
+                offset,
+                time.map(|_| values.value_f64(row_id)),
+                time,
+                true,
+            ),
+            Self::FirstString(dst) => select(
+                dst,
+                offset,
+                time.map(|_| values.value_str(row_id).to_owned()),
+                time,
+                true,
+            ),
+            Self::FirstBytes(dst) => select(
+                dst,
+                offset,
+                time.map(|_| values.value_bytes(row_id).to_owned()),
+                time,
+                true,
+            ),
+            Self::FirstBool(dst) => select(
+                dst,
+                offset,
+                time.map(|_| values.value_bool(row_id)),
+                time,
+                true,
+            ),
+            Self::LastU64(dst) => select(
+                dst,
+                offset,
+                time.map(|_| values.value_u64(row_id)),
+                time,
+                false,
+            ),
+            Self::LastI64(dst) => select(
+                dst,
+                offset,
+                time.map(|_| values.value_i64(row_id)),
+                time,
+                false,
+            ),
+            Self::LastF64(dst) => select(
+                dst,
+                offset,
+                time.map(|_| values.value_f64(row_id)),
+                time,
+                false,
+            ),
+            Self::LastString(dst) => select(
+                dst,
+                offset,
+                time.map(|_| values.value_str(row_id).to_owned()),
+                time,
+                false,
+            ),
+            Self::LastBytes(dst) => select(
+                dst,
+                offset,
+                time.map(|_| values.value_bytes(row_id).to_owned()),
+                time,
+                false,
+            ),
+            Self::LastBool(dst) => select(
+                dst,
+                offset,
+                time.map(|_| values.value_bool(row_id)),
+                time,
+                false,
+            ),
+            _ => panic!("cannot update {} as a selector", self),
+        }
+    }
+
+    /// Updates a first or last selector at `offset` with the value selected
+    /// at `src_offset` by the selector `src`, merging the two selections.
+    ///
+    /// Panics if `self` and `src` are not the same kind of selector.
+    pub fn update_selected(&mut self, src: &Self, src_offset: usize, offset: usize) {
+        match (&mut *self, src) {
+            (Self::FirstU64(dst), Self::FirstU64((v, t))) => {
+                select(dst, offset, v[src_offset], t[src_offset], true)
+            }
+            (Self::FirstI64(dst), Self::FirstI64((v, t))) => {
+                select(dst, offset, v[src_offset], t[src_offset], true)
+            }
+            (Self::FirstF64(dst), Self::FirstF64((v, t))) => {
+                select(dst, offset, v[src_offset], t[src_offset], true)
+            }
+            (Self::FirstString(dst), Self::FirstString((v, t))) => {
+                select(dst, offset, v[src_offset].clone(), t[src_offset], true)
+            }
+            (Self::FirstBytes(dst), Self::FirstBytes((v, t))) => {
+                select(dst, offset, v[src_offset].clone(), t[src_offset], true)
+            }
+            (Self::FirstBool(dst), Self::FirstBool((v, t))) => {
+                select(dst, offset, v[src_offset], t[src_offset], true)
+            }
+            (Self::LastU64(dst), Self::LastU64((v, t))) => {
+                select(dst, offset, v[src_offset], t[src_offset], false)
+            }
+            (Self::LastI64(dst), Self::LastI64((v, t))) => {
+                select(dst, offset, v[src_offset], t[src_offset], false)
+            }
+            (Self::LastF64(dst), Self::LastF64((v, t))) => {
+                select(dst, offset, v[src_offset], t[src_offset], false)
+            }
+            (Self::LastString(dst), Self::LastString((v, t))) => {
+                select(dst, offset, v[src_offset].clone(), t[src_offset], false)
+            }
+            (Self::LastBytes(dst), Self::LastBytes((v, t))) => {
+                select(dst, offset, v[src_offset].clone(), t[src_offset], false)
+            }
+            (Self::LastBool(dst), Self::LastBool((v, t))) => {
+                select(dst, offset, v[src_offset], t[src_offset], false)
+            }
+            (dst, src) => panic!("cannot merge selector {} into {}", src, dst),
         }
     }
 
     /// Appends the provided value to the end of the aggregate vector.
     /// Panics if the type of `Value` does not satisfy the aggregate type.
     ///
-    /// Note: updating pushed first/last variants is not currently a supported
-    /// operation.
+    /// Note: first/last values pushed this way have no timestamp, and are
+    /// replaced by any value selected via `update_selector`.
     pub fn push(&mut self, value: Value<'_>) {
         match self {
             Self::Count(arr) => {
@@ -416,84 +605,96 @@ impl AggregateVec {
                     arr.push(Some(value.bool()));
                 }
             }
-            Self::FirstU64((arr, _)) => {
+            Self::FirstU64((arr, time)) => {
+                time.push(None);
                 if value.is_null() {
                     arr.push(None);
                 } else {
                     arr.push(Some(value.u64()));
                 }
             }
-            Self::FirstI64((arr, _)) => {
+            Self::FirstI64((arr, time)) => {
+                time.push(None);
                 if value.is_null() {
                     arr.push(None);
                 } else {
                     arr.push(Some(value.i64()));
                 }
             }
-            Self::FirstF64((arr, _)) => {
+            Self::FirstF64((arr, time)) => {
+                time.push(None);
                 if value.is_null() {
                     arr.push(None);
                 } else {
                     arr.push(Some(value.f64()));
                 }
             }
-            Self::FirstString((arr, _)) => {
+            Self::FirstString((arr, time)) => {
+                time.push(None);
                 if value.is_null() {
                     arr.push(None);
                 } else {
                     arr.push(Some(value.str().to_owned()));
                 }
             }
-            Self::FirstBytes((arr, _)) => {
+            Self::FirstBytes((arr, time)) => {
+                time.push(None);
                 if value.is_null() {
                     arr.push(None);
                 } else {
                     arr.push(Some(value.bytes().to_owned()));
                 }
             }
-            Self::FirstBool((arr, _)) => {
+            Self::FirstBool((arr, time)) => {
+                time.push(None);
                 if value.is_null() {
                     arr.push(None);
                 } else {
                     arr.push(Some(value.bool()));
                 }
             }
-            Self::LastU64((arr, _)) => {
+            Self::LastU64((arr, time)) => {
+                time.push(None);
                 if value.is_null() {
                     arr.push(None);
                 } else {
                     arr.push(Some(value.u64()));
                 }
             }
-            Self::LastI64((arr, _)) => {
+            Self::LastI64((arr, time)) => {
+                time.push(None);
                 if value.is_null() {
                     arr.push(None);
                 } else {
                     arr.push(Some(value.i64()));
                 }
             }
-            Self::LastF64((arr, _)) => {
+            Self::LastF64((arr, time)) => {
+                time.push(None);
                 if value.is_null() {
                     arr.push(None);
                 } else {
                     arr.push(Some(value.f64()));
                 }
             }
-            Self::LastString((arr, _)) => {
+            Self::LastString((arr, time)) => {
+                time.push(None);
                 if value.is_null() {
                     arr.push(None);
                 } else {
                     arr.push(Some(value.str().to_owned()));
                 }
             }
-            Self::LastBytes((arr, _)) => {
+            Self::LastBytes((arr, time)) => {
+                time.push(None);
                 if value.is_null() {
                     arr.push(None);
                 } else {
                     arr.push(Some(value.bytes().to_owned()));
                 }
             }
-            Self::LastBool((arr, _)) => {
+            Self::LastBool((arr, time)) => {
+                time.push(None);
                 if value.is_null() {
                     arr.push(None);
                 } else {
@@ -620,6 +821,14 @@ impl AggregateVec {
                 None => write!(f, "NULL")?,
             },
         }
+
+        // selected values are followed by the time they were selected at.
+        if let Some(times) = self.selected_times() {
+            match times[offset] {
+                Some(v) => write!(f, ",{}", v)?,
+                None => write!(f, ",NULL")?,
+            }
+        }
         Ok(())
     }
 
@@ -629,6 +838,8 @@ impl AggregateVec {
             Self::SumI64(arr) => arr,
             Self::MinI64(arr) => arr,
             Self::MaxI64(arr) => arr,
+            Self::FirstI64((arr, _)) => arr,
+            Self::LastI64((arr, _)) => arr,
             _ => panic!("cannot convert {} to Vec<Option<i64>>", self),
         }
     }
@@ -640,6 +851,8 @@ impl AggregateVec {
             Self::SumU64(arr) => arr,
             Self::MinU64(arr) => arr,
             Self::MaxU64(arr) => arr,
+            Self::FirstU64((arr, _)) => arr,
+            Self::LastU64((arr, _)) => arr,
             _ => panic!("cannot convert {} to Vec<Option<u64>>", self),
         }
     }
@@ -650,6 +863,8 @@ impl AggregateVec {
             Self::SumF64(arr) => arr,
             Self::MinF64(arr) => arr,
             Self::MaxF64(arr) => arr,
+            Self::FirstF64((arr, _)) => arr,
+            Self::LastF64((arr, _)) => arr,
             _ => panic!("cannot convert {} to Vec<Option<f64>>", self),
         }
     }
@@ -659,6 +874,8 @@ impl AggregateVec {
         match self {
             Self::MinString(arr) => arr,
             Self::MaxString(arr) => arr,
+            Self::FirstString((arr, _)) => arr,
+            Self::LastString((arr, _)) => arr,
             _ => panic!("cannot convert {} to Vec<Option<&str>>", self),
         }
     }
@@ -668,6 +885,8 @@ impl AggregateVec {
         match self {
             Self::MinBytes(arr) => arr,
             Self::MaxBytes(arr) => arr,
+            Self::FirstBytes((arr, _)) => arr,
+            Self::LastBytes((arr, _)) => arr,
             _ => panic!("cannot convert {} to Vec<Option<&[u8]>>", self),
         }
     }
@@ -677,6 +896,8 @@ impl AggregateVec {
         match self {
             Self::MinBool(arr) => arr,
             Self::MaxBool(arr) => arr,
+            Self::FirstBool((arr, _)) => arr,
+            Self::LastBool((arr, _)) => arr,
             _ => panic!("cannot convert {} to Vec<u64>", self),
         }
     }
@@ -873,6 +1094,42 @@ impl AggregateVec {
     }
 }
 
+/// The values chosen by a first or last selector, along with the timestamp
+/// each value was selected at.
+type Selected<T> = (Vec<Option<T>>, Vec<Option<i64>>);
+
+// Selects `value` with the timestamp `time` at `offset` if it precedes
+// (`first`) or follows (`!first`) the current selection. A missing value or
+// timestamp is never selected, but still extends `dst` to `offset`.
+fn select<T>(
+    dst: &mut Selected<T>,
+    offset: usize,
+    value: Option<T>,
+    time: Option<i64>,
+    first: bool,
+) {
+    let (values, times) = dst;
+    if offset >= values.len() {
+        values.resize_with(offset + 1, || None);
+        times.resize(offset + 1, None);
+    }
+
+    let (value, time) = match (value, time) {
+        (Some(value), Some(time)) => (value, time),
+        _ => return,
+    };
+
+    let selected = match times[offset] {
+        Some(current) if first => time < current,
+        Some(current) => time > current,
+        None => true,
+    };
+    if selected {
+        values[offset] = Some(value);
+        times[offset] = Some(time);
+    }
+}
+
 impl std::fmt::Display for AggregateVec {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -1564,7 +1821,7 @@ impl<'a> Values<'a> {
     }
 
     // Returns a value as an i64. Panics if not possible.
-    fn value_i64(&self, i: usize) -> i64 {
+    pub(crate) fn value_i64(&self, i: usize) -> i64 {
         match &self {
             Values::I64(c) => c[i],
             Values::I64N(c) => c[i].unwrap(),
@@ -48,6 +48,7 @@ where
     // handler's output type.
     type WriteInput = T::WriteInput;
     type WriteOutput = U::WriteOutput;
+    type DeleteOutput = U::DeleteOutput;
 
     // All errors are converted into DML errors before returning to the caller
     // in order to present a consistent error type for chained handlers.
@@ -80,7 +81,7 @@ where
         table_name: &str,
         predicate: &DeletePredicate,
         span_ctx: Option<SpanContext>,
-    ) -> Result<(), Self::DeleteError> {
+    ) -> Result<Self::DeleteOutput, Self::DeleteError> {
         self.first
             .delete(namespace, table_name, predicate, span_ctx.clone())
             .await
@@ -39,6 +39,7 @@ where
 {
     type WriteInput = I;
     type WriteOutput = Vec<T::WriteOutput>;
+    type DeleteOutput = T::DeleteOutput;
     type WriteError = T::WriteError;
     type DeleteError = T::DeleteError;
 
@@ -71,7 +72,7 @@ where
         table_name: &str,
         predicate: &DeletePredicate,
         span_ctx: Option<SpanContext>,
-    ) -> Result<(), Self::DeleteError> {
+    ) -> Result<Self::DeleteOutput, Self::DeleteError> {
         self.inner
             .delete(namespace, table_name, predicate, span_ctx)
             .await
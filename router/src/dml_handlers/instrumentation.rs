@@ -62,6 +62,7 @@ where
     type WriteError = T::WriteError;
     type DeleteError = T::DeleteError;
     type WriteOutput = T::WriteOutput;
+    type DeleteOutput = T::DeleteOutput;
 
     /// Call the inner `write` method and record the call latency.
     async fn write(
@@ -103,7 +104,7 @@ where
         table_name: &str,
         predicate: &DeletePredicate,
         span_ctx: Option<SpanContext>,
-    ) -> Result<(), Self::DeleteError> {
+    ) -> Result<Self::DeleteOutput, Self::DeleteError> {
         let t = self.time_provider.now();
 
         // Create a tracing span for this handler.
@@ -235,7 +236,7 @@ mod tests {
     #[tokio::test]
     async fn test_delete_ok() {
         let ns = "platanos".try_into().unwrap();
-        let handler = Arc::new(MockDmlHandler::<()>::default().with_delete_return([Ok(())]));
+        let handler = Arc::new(MockDmlHandler::<()>::default().with_delete_return([Ok(summary())]));
 
         let metrics = Arc::new(metric::Registry::default());
         let traces: Arc<dyn TraceCollector> = Arc::new(RingBufferTraceCollector::new(5));
@@ -58,6 +58,10 @@
 //! [`NamespaceCache`] as an optimisation, allowing the handler to skip sending
 //! requests to the catalog for namespaces that are known to exist.
 //!
+//! The [`WriteRateLimiter`] then throttles the writes to namespaces that have
+//! write rate limits configured in the catalog, caching the limits alongside
+//! the [`NamespaceSchema`] in the [`NamespaceCache`].
+//!
 //! Incoming line-protocol writes then pass through the [`Partitioner`], parsing
 //! the LP and splitting them into batches per partition, before passing each
 //! partitioned batch through the rest of the request pipeline.
@@ -102,5 +106,8 @@ pub use fan_out::*;
 mod write_summary;
 pub use self::write_summary::*;
 
+mod rate_limit;
+pub use rate_limit::*;
+
 #[cfg(test)]
 pub mod mock;
@@ -26,6 +26,7 @@ where
     type DeleteError = DmlError;
     type WriteInput = T;
     type WriteOutput = T;
+    type DeleteOutput = ();
 
     async fn write(
         &self,
@@ -43,7 +44,7 @@ where
         table_name: &str,
         predicate: &DeletePredicate,
         _span_ctx: Option<SpanContext>,
-    ) -> Result<(), Self::DeleteError> {
+    ) -> Result<Self::DeleteOutput, Self::DeleteError> {
         info!(%namespace, %table_name, ?predicate, "dropping delete operation");
         Ok(())
     }
@@ -72,6 +72,7 @@ where
     // unmodified.
     type WriteInput = T;
     type WriteOutput = T;
+    type DeleteOutput = ();
 
     /// Write `batches` to `namespace`.
     async fn write(
@@ -123,7 +124,7 @@ where
         _table_name: &str,
         _predicate: &DeletePredicate,
         _span_ctx: Option<SpanContext>,
-    ) -> Result<(), Self::DeleteError> {
+    ) -> Result<Self::DeleteOutput, Self::DeleteError> {
         Ok(())
     }
 }
@@ -149,6 +150,9 @@ mod tests {
                 topic_id: TopicId::new(2),
                 query_pool_id: QueryPoolId::new(3),
                 tables: Default::default(),
+                write_limits: Default::default(),
+                strict_schema: false,
+                field_coercion_policy: Default::default(),
             },
         );
 
@@ -224,6 +228,11 @@ mod tests {
                 query_pool_id: QueryPoolId::new(42),
                 max_tables: 10000,
                 max_columns_per_table: 1000,
+                query_limits: Default::default(),
+                persistence_policy: Default::default(),
+                write_limits: Default::default(),
+                strict_schema: false,
+                field_coercion_policy: Default::default(),
             }
         );
     }
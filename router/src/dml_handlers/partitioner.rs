@@ -65,6 +65,7 @@ impl DmlHandler for Partitioner {
 
     type WriteInput = HashMap<String, MutableBatch>;
     type WriteOutput = Vec<Partitioned<Self::WriteInput>>;
+    type DeleteOutput = ();
 
     /// Partition the per-table [`MutableBatch`].
     async fn write(
@@ -105,7 +106,7 @@ impl DmlHandler for Partitioner {
         _table_name: &str,
         _predicate: &DeletePredicate,
         _span_ctx: Option<SpanContext>,
-    ) -> Result<(), Self::DeleteError> {
+    ) -> Result<Self::DeleteOutput, Self::DeleteError> {
         Ok(())
     }
 }
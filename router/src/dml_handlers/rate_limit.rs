@@ -0,0 +1,792 @@
+use super::DmlHandler;
+use crate::namespace_cache::NamespaceCache;
+use async_trait::async_trait;
+use data_types::{DatabaseName, DeletePredicate, NamespaceWriteLimits};
+use hashbrown::{hash_map::Entry, HashMap};
+use iox_catalog::interface::Catalog;
+use iox_time::{SystemProvider, Time, TimeProvider};
+use metric::{Metric, U64Counter};
+use mutable_batch::MutableBatch;
+use observability_deps::tracing::*;
+use parking_lot::Mutex;
+use std::{borrow::Cow, sync::Arc, time::Duration};
+use thiserror::Error;
+use trace::ctx::SpanContext;
+
+/// An error throttling a write.
+#[derive(Debug, Error)]
+pub enum RateLimitError {
+    /// The write exceeds the write rate limits of the namespace.
+    #[error("namespace {namespace} exceeded its write rate limit, retry after {retry_after:?}")]
+    Throttled {
+        /// The throttled namespace.
+        namespace: String,
+        /// The time after which the write is expected to be accepted.
+        retry_after: Duration,
+    },
+}
+
+impl RateLimitError {
+    /// The time after which the throttled write is expected to be accepted.
+    pub fn retry_after(&self) -> Duration {
+        match self {
+            Self::Throttled { retry_after, .. } => *retry_after,
+        }
+    }
+}
+
+/// A token bucket refilling at `rate` tokens per second, holding at most one
+/// second worth of tokens.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: u64,
+    tokens: f64,
+    refilled_at: Time,
+}
+
+impl TokenBucket {
+    fn new(rate: u64, now: Time) -> Self {
+        Self {
+            rate,
+            tokens: rate as f64,
+            refilled_at: now,
+        }
+    }
+
+    /// Change the refill rate, keeping the tokens accumulated so far (up to
+    /// the new capacity).
+    fn set_rate(&mut self, rate: u64) {
+        self.rate = rate;
+        self.tokens = self.tokens.min(rate as f64);
+    }
+
+    fn refill(&mut self, now: Time) {
+        if let Some(elapsed) = now.checked_duration_since(self.refilled_at) {
+            self.tokens =
+                (self.tokens + elapsed.as_secs_f64() * self.rate as f64).min(self.rate as f64);
+            self.refilled_at = now;
+        }
+    }
+
+    /// Return how long to wait before `cost` tokens can be taken.
+    ///
+    /// A `cost` larger than the bucket capacity only needs a full bucket, the
+    /// excess is taken as debt that delays subsequent writes.
+    fn wait_time(&self, cost: u64) -> Duration {
+        let needed = cost.min(self.rate) as f64;
+        if self.tokens >= needed {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64((needed - self.tokens) / self.rate as f64)
+    }
+
+    fn take(&mut self, cost: u64) {
+        self.tokens -= cost as f64;
+    }
+}
+
+/// Ensure `bucket` matches `rate`, creating, updating or removing it.
+fn sync_bucket(bucket: &mut Option<TokenBucket>, rate: Option<u64>, now: Time) {
+    match (bucket.as_mut(), rate) {
+        (Some(b), Some(rate)) => b.set_rate(rate),
+        (None, Some(rate)) => *bucket = Some(TokenBucket::new(rate, now)),
+        (_, None) => *bucket = None,
+    }
+}
+
+/// The rate limiting state of a single namespace.
+#[derive(Debug)]
+struct NamespaceState {
+    limits: NamespaceWriteLimits,
+    /// When the limits were last read from the catalog.
+    refreshed_at: Time,
+
+    lines: Option<TokenBucket>,
+    bytes: Option<TokenBucket>,
+
+    throttled_writes: U64Counter,
+    throttled_lines: U64Counter,
+    throttled_bytes: U64Counter,
+}
+
+impl NamespaceState {
+    /// Returns true if the limits are due for a refresh and the buckets are
+    /// full, meaning dropping the state does not change how the next write is
+    /// throttled.
+    fn is_idle(&mut self, now: Time, refresh_interval: Duration) -> bool {
+        let refresh_due = now
+            .checked_duration_since(self.refreshed_at)
+            .map_or(false, |elapsed| elapsed >= refresh_interval);
+
+        refresh_due
+            && [&mut self.lines, &mut self.bytes]
+                .into_iter()
+                .flatten()
+                .all(|bucket| {
+                    bucket.refill(now);
+                    bucket.tokens >= bucket.rate as f64
+                })
+    }
+}
+
+/// The rate limiting state of all namespaces written to recently.
+#[derive(Debug)]
+struct Namespaces {
+    states: HashMap<DatabaseName<'static>, NamespaceState>,
+    /// When the idle states were last evicted.
+    swept_at: Time,
+}
+
+impl Namespaces {
+    /// Drop the state of the idle namespaces, at most once per
+    /// `refresh_interval`.
+    fn evict_idle(&mut self, now: Time, refresh_interval: Duration) {
+        let sweep_due = now
+            .checked_duration_since(self.swept_at)
+            .map_or(false, |elapsed| elapsed >= refresh_interval);
+        if !sweep_due {
+            return;
+        }
+
+        self.swept_at = now;
+        let before = self.states.len();
+        self.states
+            .retain(|_, state| !state.is_idle(now, refresh_interval));
+        trace!(
+            evicted = before - self.states.len(),
+            "evicted idle namespace write limits"
+        );
+    }
+}
+
+/// A layer throttling the writes to each namespace according to the
+/// [`NamespaceWriteLimits`] configured for it in the catalog.
+///
+/// Each namespace with limits gets a token bucket for the number of lines
+/// (rows) and one for the number of bytes (the in-memory size of the decoded
+/// batches) it accepts per second. A bucket holds at most one second worth of
+/// tokens, allowing short bursts of writes up to the limit. A write needing
+/// more tokens than the buckets hold is rejected with a
+/// [`RateLimitError::Throttled`] carrying the time after which it is expected
+/// to succeed - a write larger than the limit is accepted once the buckets are
+/// full, delaying the subsequent writes instead.
+///
+/// Limits are enforced by each router independently, so the rate accepted
+/// across a cluster of routers is the sum of the rates they each accept.
+///
+/// The limiter is placed after the [`SchemaValidator`] so that it only tracks
+/// namespaces that exist, and the state of a namespace is dropped once it is
+/// idle.
+///
+/// # Caching
+///
+/// The limits are cached alongside the [`NamespaceSchema`] in the
+/// [`NamespaceCache`], and re-read from the catalog once per refresh interval
+/// (and the first time a namespace is written to) so that changed limits are
+/// picked up without restarting the router. If the catalog cannot be read, the
+/// cached limits keep applying.
+///
+/// Deletes are passed through unthrottled.
+///
+/// [`NamespaceSchema`]: data_types::NamespaceSchema
+/// [`SchemaValidator`]: super::SchemaValidator
+#[derive(Debug)]
+pub struct WriteRateLimiter<C> {
+    catalog: Arc<dyn Catalog>,
+    cache: C,
+    refresh_interval: Duration,
+    time_provider: Arc<dyn TimeProvider>,
+
+    namespaces: Mutex<Namespaces>,
+
+    throttled_writes: Metric<U64Counter>,
+    throttled_lines: Metric<U64Counter>,
+    throttled_bytes: Metric<U64Counter>,
+}
+
+impl<C> WriteRateLimiter<C> {
+    /// Initialise a new [`WriteRateLimiter`], reading the limits of the
+    /// namespaces from `catalog` every `refresh_interval` and caching them in
+    /// `ns_cache`.
+    pub fn new(
+        catalog: Arc<dyn Catalog>,
+        ns_cache: C,
+        refresh_interval: Duration,
+        metrics: &metric::Registry,
+    ) -> Self {
+        let throttled_writes = metrics.register_metric::<U64Counter>(
+            "write_rate_limit_throttled_writes",
+            "number of writes rejected by the write rate limits of their namespace",
+        );
+        let throttled_lines = metrics.register_metric::<U64Counter>(
+            "write_rate_limit_throttled_lines",
+            "number of lines in the writes rejected by the write rate limits of their namespace",
+        );
+        let throttled_bytes = metrics.register_metric::<U64Counter>(
+            "write_rate_limit_throttled_bytes",
+            "estimated size of the writes rejected by the write rate limits of their namespace",
+        );
+
+        let time_provider: Arc<dyn TimeProvider> = Arc::new(SystemProvider::default());
+        let now = time_provider.now();
+
+        Self {
+            catalog,
+            cache: ns_cache,
+            refresh_interval,
+            time_provider,
+            namespaces: Mutex::new(Namespaces {
+                states: Default::default(),
+                swept_at: now,
+            }),
+            throttled_writes,
+            throttled_lines,
+            throttled_bytes,
+        }
+    }
+
+    /// Use `time_provider` to refill the token buckets and schedule limit
+    /// refreshes.
+    pub fn with_time_provider(mut self, time_provider: Arc<dyn TimeProvider>) -> Self {
+        self.namespaces.get_mut().swept_at = time_provider.now();
+        self.time_provider = time_provider;
+        self
+    }
+}
+
+impl<C> WriteRateLimiter<C>
+where
+    C: NamespaceCache,
+{
+    /// Returns true if the limits of `namespace` have never been read, or were
+    /// read longer than the refresh interval ago.
+    fn needs_refresh(&self, namespace: &DatabaseName<'static>, now: Time) -> bool {
+        match self.namespaces.lock().states.get(namespace) {
+            Some(state) => now
+                .checked_duration_since(state.refreshed_at)
+                .map(|elapsed| elapsed >= self.refresh_interval)
+                .unwrap_or(false),
+            None => true,
+        }
+    }
+
+    /// Read the limits of `namespace` from the catalog, updating the cached
+    /// schema if they changed, or return `None` if the namespace does not
+    /// exist.
+    ///
+    /// Falls back to the cached limits if the catalog cannot be read.
+    async fn load_limits(&self, namespace: &DatabaseName<'static>) -> Option<NamespaceWriteLimits> {
+        let cached = self.cache.get_schema(namespace);
+
+        let loaded = self
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .get_by_name(namespace.as_str())
+            .await;
+
+        let limits = match loaded {
+            Ok(Some(ns)) => ns.write_limits,
+            Ok(None) => {
+                // Only possible if the namespace was deleted after the
+                // schema validator accepted the write.
+                trace!(%namespace, "namespace not found reading write limits");
+                return None;
+            }
+            Err(e) => {
+                warn!(error=%e, %namespace, "failed to read namespace write limits");
+                return Some(cached.map(|s| s.write_limits).unwrap_or_default());
+            }
+        };
+
+        // Update the cached schema so that the limits survive the schema
+        // being re-read or extended by the schema validator.
+        if let Some(schema) = cached {
+            if schema.write_limits != limits {
+                let mut schema = (*schema).clone();
+                schema.write_limits = limits;
+                self.cache.put_schema(namespace.clone(), schema);
+                debug!(%namespace, ?limits, "namespace write limits updated");
+            }
+        }
+
+        Some(limits)
+    }
+}
+
+#[async_trait]
+impl<C> DmlHandler for WriteRateLimiter<C>
+where
+    C: NamespaceCache,
+{
+    type WriteError = RateLimitError;
+    type DeleteError = RateLimitError;
+
+    type WriteInput = HashMap<String, MutableBatch>;
+    type WriteOutput = Self::WriteInput;
+    type DeleteOutput = ();
+
+    /// Take the lines and bytes of `batches` from the token buckets of
+    /// `namespace`, or reject the write if the buckets do not hold enough
+    /// tokens.
+    async fn write(
+        &self,
+        namespace: &DatabaseName<'static>,
+        batches: Self::WriteInput,
+        _span_ctx: Option<SpanContext>,
+    ) -> Result<Self::WriteOutput, Self::WriteError> {
+        let mut now = self.time_provider.now();
+
+        // Refresh the limits without holding the lock over the catalog
+        // request - racing refreshes of the same namespace are harmless.
+        let refreshed = if self.needs_refresh(namespace, now) {
+            let limits = match self.load_limits(namespace).await {
+                Some(limits) => limits,
+                None => return Ok(batches),
+            };
+            now = self.time_provider.now();
+            Some(limits)
+        } else {
+            None
+        };
+
+        let mut namespaces = self.namespaces.lock();
+        namespaces.evict_idle(now, self.refresh_interval);
+
+        let state = match (namespaces.states.entry(namespace.clone()), refreshed) {
+            (Entry::Occupied(entry), _) => entry.into_mut(),
+            (Entry::Vacant(entry), Some(_)) => {
+                let attributes = [("namespace", Cow::Owned(namespace.to_string()))];
+                entry.insert(NamespaceState {
+                    limits: Default::default(),
+                    refreshed_at: now,
+                    lines: None,
+                    bytes: None,
+                    throttled_writes: self.throttled_writes.recorder(attributes.clone()),
+                    throttled_lines: self.throttled_lines.recorder(attributes.clone()),
+                    throttled_bytes: self.throttled_bytes.recorder(attributes),
+                })
+            }
+            // The state was evicted by a concurrent write since checking for a
+            // refresh - it was idle, so its buckets were full and would have
+            // accepted this write. The next write reloads the limits.
+            (Entry::Vacant(_), None) => return Ok(batches),
+        };
+
+        if let Some(limits) = refreshed {
+            state.limits = limits;
+            state.refreshed_at = now;
+            sync_bucket(&mut state.lines, limits.lines_per_second(), now);
+            sync_bucket(&mut state.bytes, limits.bytes_per_second(), now);
+        }
+
+        if !state.limits.is_limited() {
+            return Ok(batches);
+        }
+
+        let (lines, bytes) = batches.values().fold((0, 0), |(lines, bytes), b| {
+            (lines + b.rows() as u64, bytes + b.size() as u64)
+        });
+
+        let wait = [(&mut state.lines, lines), (&mut state.bytes, bytes)]
+            .into_iter()
+            .filter_map(|(bucket, cost)| {
+                let bucket = bucket.as_mut()?;
+                bucket.refill(now);
+                Some(bucket.wait_time(cost))
+            })
+            .max()
+            .unwrap_or_default();
+
+        if !wait.is_zero() {
+            state.throttled_writes.inc(1);
+            state.throttled_lines.inc(lines);
+            state.throttled_bytes.inc(bytes);
+            debug!(%namespace, lines, bytes, ?wait, "write throttled");
+            return Err(RateLimitError::Throttled {
+                namespace: namespace.to_string(),
+                retry_after: wait,
+            });
+        }
+
+        if let Some(b) = state.lines.as_mut() {
+            b.take(lines);
+        }
+        if let Some(b) = state.bytes.as_mut() {
+            b.take(bytes);
+        }
+
+        Ok(batches)
+    }
+
+    /// Deletes are not throttled.
+    async fn delete(
+        &self,
+        _namespace: &DatabaseName<'static>,
+        _table_name: &str,
+        _predicate: &DeletePredicate,
+        _span_ctx: Option<SpanContext>,
+    ) -> Result<Self::DeleteOutput, Self::DeleteError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::namespace_cache::MemoryNamespaceCache;
+    use assert_matches::assert_matches;
+    use data_types::{NamespaceId, NamespaceSchema, QueryPoolId, TopicId};
+    use iox_catalog::mem::MemCatalog;
+    use iox_time::MockProvider;
+    use ioxd_common::http::error::retry_after_secs;
+    use metric::{Attributes, Registry};
+    use once_cell::sync::Lazy;
+
+    static NAMESPACE: Lazy<DatabaseName<'static>> = Lazy::new(|| "bananas".try_into().unwrap());
+
+    const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+    // Parse `lp` into a table-keyed MutableBatch map.
+    fn lp_to_writes(lp: &str) -> HashMap<String, MutableBatch> {
+        let (writes, _) = mutable_batch_lp::lines_to_batches_stats(lp, 42)
+            .expect("failed to build test writes from LP");
+        writes
+    }
+
+    /// Initialise an in-memory catalog with a single namespace named
+    /// [`NAMESPACE`] with the given limits.
+    async fn create_catalog(limits: NamespaceWriteLimits) -> Arc<dyn Catalog> {
+        let metrics = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+
+        let mut repos = catalog.repositories().await;
+        repos
+            .namespaces()
+            .create(
+                NAMESPACE.as_str(),
+                "inf",
+                TopicId::new(42),
+                QueryPoolId::new(24),
+            )
+            .await
+            .expect("failed to create test namespace");
+        repos
+            .namespaces()
+            .update_write_limits(NAMESPACE.as_str(), limits)
+            .await
+            .expect("failed to set write limits");
+
+        catalog
+    }
+
+    fn limiter(
+        catalog: Arc<dyn Catalog>,
+        time: &Arc<MockProvider>,
+        metrics: &Registry,
+    ) -> WriteRateLimiter<Arc<MemoryNamespaceCache>> {
+        WriteRateLimiter::new(
+            catalog,
+            Arc::new(MemoryNamespaceCache::default()),
+            REFRESH_INTERVAL,
+            metrics,
+        )
+        .with_time_provider(Arc::clone(time) as _)
+    }
+
+    fn throttled_metric(metrics: &Registry, name: &'static str) -> u64 {
+        metrics
+            .get_instrument::<Metric<U64Counter>>(name)
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[("namespace", "bananas")]))
+            .expect("failed to get observer")
+            .fetch()
+    }
+
+    #[tokio::test]
+    async fn test_no_limits() {
+        let catalog = create_catalog(NamespaceWriteLimits::default()).await;
+        let time = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let metrics = Registry::default();
+        let handler = limiter(catalog, &time, &metrics);
+
+        for _ in 0..10 {
+            handler
+                .write(
+                    &*NAMESPACE,
+                    lp_to_writes("bananas val=42i 1\nbananas val=1i 2"),
+                    None,
+                )
+                .await
+                .expect("unlimited writes should never be throttled");
+        }
+        assert_eq!(
+            throttled_metric(&metrics, "write_rate_limit_throttled_writes"),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lines_limit() {
+        let catalog = create_catalog(NamespaceWriteLimits {
+            write_lines_per_second: Some(4),
+            write_bytes_per_second: None,
+        })
+        .await;
+        let time = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let metrics = Registry::default();
+        let handler = limiter(catalog, &time, &metrics);
+
+        let lp = "bananas val=42i 1\nbananas val=1i 2";
+
+        // The bucket starts full, allowing a burst of 4 lines.
+        handler
+            .write(&*NAMESPACE, lp_to_writes(lp), None)
+            .await
+            .unwrap();
+        handler
+            .write(&*NAMESPACE, lp_to_writes(lp), None)
+            .await
+            .unwrap();
+
+        let err = handler
+            .write(&*NAMESPACE, lp_to_writes(lp), None)
+            .await
+            .expect_err("write should be throttled");
+        assert_matches!(err, RateLimitError::Throttled { ref namespace, retry_after } => {
+            assert_eq!(namespace, "bananas");
+            assert_eq!(retry_after, Duration::from_millis(500));
+        });
+        assert_eq!(retry_after_secs(err.retry_after()), 1);
+        assert_eq!(
+            throttled_metric(&metrics, "write_rate_limit_throttled_writes"),
+            1
+        );
+        assert_eq!(
+            throttled_metric(&metrics, "write_rate_limit_throttled_lines"),
+            2
+        );
+
+        // Refilling the bucket for the advertised time allows the write.
+        time.inc(Duration::from_millis(500));
+        handler
+            .write(&*NAMESPACE, lp_to_writes(lp), None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_write_larger_than_limit() {
+        let catalog = create_catalog(NamespaceWriteLimits {
+            write_lines_per_second: Some(1),
+            write_bytes_per_second: None,
+        })
+        .await;
+        let time = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let metrics = Registry::default();
+        let handler = limiter(catalog, &time, &metrics);
+
+        // A full bucket accepts a write larger than the limit...
+        handler
+            .write(
+                &*NAMESPACE,
+                lp_to_writes("bananas val=1i 1\nbananas val=2i 2\nbananas val=3i 3"),
+                None,
+            )
+            .await
+            .unwrap();
+
+        // ...and the debt delays the following writes.
+        let err = handler
+            .write(&*NAMESPACE, lp_to_writes("bananas val=1i 1"), None)
+            .await
+            .expect_err("write should be throttled");
+        assert_eq!(err.retry_after(), Duration::from_secs(3));
+    }
+
+    #[tokio::test]
+    async fn test_bytes_limit() {
+        let writes = lp_to_writes("bananas val=42i 1");
+        let size = writes.values().map(|b| b.size() as i64).sum::<i64>();
+
+        let catalog = create_catalog(NamespaceWriteLimits {
+            write_lines_per_second: None,
+            write_bytes_per_second: Some(size),
+        })
+        .await;
+        let time = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let metrics = Registry::default();
+        let handler = limiter(catalog, &time, &metrics);
+
+        handler
+            .write(&*NAMESPACE, writes.clone(), None)
+            .await
+            .unwrap();
+        let err = handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect_err("write should be throttled");
+        assert_eq!(err.retry_after(), Duration::from_secs(1));
+        assert_eq!(
+            throttled_metric(&metrics, "write_rate_limit_throttled_bytes"),
+            size as u64
+        );
+    }
+
+    #[tokio::test]
+    async fn test_limits_refresh() {
+        let catalog = create_catalog(NamespaceWriteLimits::default()).await;
+        let time = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let metrics = Registry::default();
+        let cache = Arc::new(MemoryNamespaceCache::default());
+        cache.put_schema(
+            NAMESPACE.clone(),
+            NamespaceSchema::new(NamespaceId::new(1), TopicId::new(42), QueryPoolId::new(24)),
+        );
+        let handler = WriteRateLimiter::new(
+            Arc::clone(&catalog),
+            Arc::clone(&cache),
+            REFRESH_INTERVAL,
+            &metrics,
+        )
+        .with_time_provider(Arc::clone(&time) as _);
+
+        let lp = "bananas val=42i 1";
+        handler
+            .write(&*NAMESPACE, lp_to_writes(lp), None)
+            .await
+            .unwrap();
+
+        let limits = NamespaceWriteLimits {
+            write_lines_per_second: Some(1),
+            write_bytes_per_second: None,
+        };
+        catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_write_limits(NAMESPACE.as_str(), limits)
+            .await
+            .unwrap();
+
+        // The limits are not re-read before the refresh interval elapses.
+        handler
+            .write(&*NAMESPACE, lp_to_writes(lp), None)
+            .await
+            .unwrap();
+        handler
+            .write(&*NAMESPACE, lp_to_writes(lp), None)
+            .await
+            .unwrap();
+
+        time.inc(REFRESH_INTERVAL);
+        handler
+            .write(&*NAMESPACE, lp_to_writes(lp), None)
+            .await
+            .unwrap();
+        handler
+            .write(&*NAMESPACE, lp_to_writes(lp), None)
+            .await
+            .expect_err("write should be throttled");
+
+        // The refreshed limits are cached alongside the schema.
+        let schema = cache.get_schema(&*NAMESPACE).expect("schema cached");
+        assert_eq!(schema.write_limits, limits);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_namespace() {
+        let catalog = create_catalog(NamespaceWriteLimits::default()).await;
+        let time = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let metrics = Registry::default();
+        let handler = limiter(catalog, &time, &metrics);
+
+        let platanos = DatabaseName::new("platanos").unwrap();
+        handler
+            .write(&platanos, lp_to_writes("bananas val=42i 1"), None)
+            .await
+            .expect("unknown namespaces are not throttled");
+        assert!(handler.namespaces.lock().states.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_evict_idle() {
+        let limits = NamespaceWriteLimits {
+            write_lines_per_second: Some(1),
+            write_bytes_per_second: None,
+        };
+        let catalog = create_catalog(limits).await;
+        catalog
+            .repositories()
+            .await
+            .namespaces()
+            .create("platanos", "inf", TopicId::new(42), QueryPoolId::new(24))
+            .await
+            .unwrap();
+        let time = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let metrics = Registry::default();
+        let handler = limiter(catalog, &time, &metrics);
+        let platanos = DatabaseName::new("platanos").unwrap();
+
+        // A write larger than the limit leaves "bananas" in debt.
+        handler
+            .write(
+                &*NAMESPACE,
+                lp_to_writes(&"bananas val=1i 1\n".repeat(100)),
+                None,
+            )
+            .await
+            .unwrap();
+        handler
+            .write(&platanos, lp_to_writes("bananas val=1i 1"), None)
+            .await
+            .unwrap();
+
+        // Both are due for a refresh, but only "platanos" has full buckets.
+        time.inc(REFRESH_INTERVAL);
+        handler
+            .write(&platanos, lp_to_writes("bananas val=1i 1"), None)
+            .await
+            .unwrap();
+        {
+            let namespaces = handler.namespaces.lock();
+            assert_eq!(namespaces.states.len(), 2);
+        }
+
+        // Once the debt is paid off, the idle "bananas" is evicted.
+        time.inc(REFRESH_INTERVAL);
+        handler
+            .write(&platanos, lp_to_writes("bananas val=1i 1"), None)
+            .await
+            .unwrap();
+        let namespaces = handler.namespaces.lock();
+        assert_eq!(
+            namespaces.states.keys().collect::<Vec<_>>(),
+            vec![&platanos]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_not_throttled() {
+        let catalog = create_catalog(NamespaceWriteLimits {
+            write_lines_per_second: Some(1),
+            write_bytes_per_second: Some(1),
+        })
+        .await;
+        let time = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let metrics = Registry::default();
+        let handler = limiter(catalog, &time, &metrics);
+
+        let predicate = DeletePredicate {
+            range: data_types::TimestampRange::new(1, 2),
+            exprs: vec![],
+        };
+        for _ in 0..3 {
+            handler
+                .delete(&*NAMESPACE, "bananas", &predicate, None)
+                .await
+                .expect("deletes should not be throttled");
+        }
+    }
+}
@@ -1,15 +1,16 @@
 use super::DmlHandler;
 use crate::namespace_cache::{metrics::InstrumentedCache, MemoryNamespaceCache, NamespaceCache};
 use async_trait::async_trait;
-use data_types::{DatabaseName, DeletePredicate};
+use data_types::{ColumnType, DatabaseName, DeletePredicate, NamespaceSchema, TableSchema};
 use hashbrown::HashMap;
 use iox_catalog::{
     interface::{get_schema_by_name, Catalog, Error as CatalogError},
-    validate_or_insert_schema,
+    validate_or_insert_schema, TableScopedError,
 };
 use metric::U64Counter;
-use mutable_batch::MutableBatch;
+use mutable_batch::{coerce::FieldCoercion, MutableBatch};
 use observability_deps::tracing::*;
+use schema::{InfluxColumnType, InfluxFieldType};
 use std::{ops::DerefMut, sync::Arc};
 use thiserror::Error;
 use trace::ctx::SpanContext;
@@ -27,7 +28,22 @@ pub enum SchemaError {
 
     /// The request schema conflicts with the existing namespace schema.
     #[error("schema conflict: {0}")]
-    Conflict(iox_catalog::TableScopedError),
+    Conflict(SchemaConflict),
+
+    /// The request writes to a table or column that is not declared in the
+    /// schema of a namespace in strict schema mode.
+    #[error("strict schema violation: {0}")]
+    Undeclared(UndeclaredSchemaError),
+
+    /// Dropping the fields conflicting with the namespace schema left rows of
+    /// the request without any field.
+    #[error("dropping conflicting fields left {rows} rows of table {table} without any field")]
+    NoFields {
+        /// The table written to.
+        table: String,
+        /// The number of rows left without fields.
+        rows: usize,
+    },
 
     /// A catalog error during schema validation.
     ///
@@ -38,6 +54,169 @@ pub enum SchemaError {
     UnexpectedCatalogError(iox_catalog::interface::Error),
 }
 
+/// The column types of a request conflicting with the namespace schema.
+#[derive(Debug, Error)]
+#[error("{error}")]
+pub struct SchemaConflict {
+    error: TableScopedError,
+    columns: Vec<ColumnConflict>,
+}
+
+impl SchemaConflict {
+    /// Wrap the conflict `error` returned by the catalog, along with all
+    /// conflicting `columns` of the request.
+    pub fn new(error: TableScopedError, columns: Vec<ColumnConflict>) -> Self {
+        Self { error, columns }
+    }
+
+    /// Return the table of the conflict reported by the catalog.
+    pub fn table(&self) -> &str {
+        self.error.table()
+    }
+
+    /// Return the conflict reported by the catalog, which stops at the first
+    /// conflicting column.
+    pub fn err(&self) -> &CatalogError {
+        self.error.err()
+    }
+
+    /// Return all columns of the request conflicting with the namespace
+    /// schema, ordered by table and column name.
+    pub fn columns(&self) -> &[ColumnConflict] {
+        &self.columns
+    }
+}
+
+/// A column of a request whose type conflicts with the namespace schema.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ColumnConflict {
+    /// The table of the column.
+    pub table: String,
+    /// The conflicting column.
+    pub column: String,
+    /// The type of the column in the namespace schema.
+    pub existing: ColumnType,
+}
+
+/// A table or column absent from the schema of a namespace in strict schema
+/// mode.
+#[derive(Debug, Error)]
+pub enum UndeclaredSchemaError {
+    /// The table is not declared.
+    #[error("table {table} is not declared in the schema of namespace {namespace}")]
+    Table {
+        /// The namespace written to.
+        namespace: String,
+        /// The undeclared table.
+        table: String,
+    },
+
+    /// The column is not declared.
+    #[error(
+        "column {column} of table {table} is not declared in the schema of namespace {namespace}"
+    )]
+    Column {
+        /// The namespace written to.
+        namespace: String,
+        /// The table of the column.
+        table: String,
+        /// The undeclared column.
+        column: String,
+    },
+}
+
+/// Return the first table or column of `batches` missing from `schema`.
+fn find_undeclared(
+    namespace: &DatabaseName<'static>,
+    schema: &NamespaceSchema,
+    batches: &HashMap<String, MutableBatch>,
+) -> Option<UndeclaredSchemaError> {
+    for (table, batch) in batches {
+        let table_schema = match schema.tables.get(table) {
+            Some(v) => v,
+            None => {
+                return Some(UndeclaredSchemaError::Table {
+                    namespace: namespace.to_string(),
+                    table: table.clone(),
+                })
+            }
+        };
+        if let Some((column, _)) = batch
+            .columns()
+            .find(|(column, _)| !table_schema.columns.contains_key(column.as_str()))
+        {
+            return Some(UndeclaredSchemaError::Column {
+                namespace: namespace.to_string(),
+                table: table.clone(),
+                column: column.clone(),
+            });
+        }
+    }
+    None
+}
+
+/// Return all columns of `batches` whose type conflicts with `schema`, along
+/// with the column of the conflict `err` reported by the catalog, which may be
+/// missing from a stale `schema`.
+fn column_conflicts(
+    schema: &NamespaceSchema,
+    batches: &HashMap<String, MutableBatch>,
+    err: &TableScopedError,
+) -> Vec<ColumnConflict> {
+    let mut conflicts: Vec<_> = batches
+        .iter()
+        .filter_map(|(table, batch)| Some((table, batch, schema.tables.get(table)?)))
+        .flat_map(|(table, batch, table_schema)| {
+            batch.columns().filter_map(|(name, column)| {
+                let existing = table_schema.columns.get(name.as_str())?;
+                (!existing.matches_type(column.influx_type())).then(|| ColumnConflict {
+                    table: table.clone(),
+                    column: name.clone(),
+                    existing: existing.column_type,
+                })
+            })
+        })
+        .collect();
+
+    if let CatalogError::ColumnTypeMismatch { name, existing, .. } = err.err() {
+        if !conflicts
+            .iter()
+            .any(|c| c.table == err.table() && &c.column == name)
+        {
+            conflicts.push(ColumnConflict {
+                table: err.table().to_string(),
+                column: name.clone(),
+                existing: *existing,
+            });
+        }
+    }
+
+    conflicts.sort();
+    conflicts
+}
+
+/// Return the field columns of `batch` whose type conflicts with the field
+/// type of the same column in `table`, along with that type.
+fn field_conflicts(table: &TableSchema, batch: &MutableBatch) -> Vec<(String, InfluxFieldType)> {
+    batch
+        .columns()
+        .filter_map(|(name, column)| {
+            let existing = table.columns.get(name.as_str())?;
+            match (
+                InfluxColumnType::from(existing.column_type),
+                column.influx_type(),
+            ) {
+                (InfluxColumnType::Field(existing), InfluxColumnType::Field(new))
+                    if existing != new =>
+                {
+                    Some((name.clone(), existing))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
 /// A [`SchemaValidator`] checks the schema of incoming writes against a
 /// centralised schema store, maintaining an in-memory cache of all observed
 /// schemas.
@@ -58,6 +237,30 @@ pub enum SchemaError {
 /// Any successful write that adds new columns causes the new schema to be
 /// cached.
 ///
+/// # Field Coercion
+///
+/// A field written with a type conflicting with the type of an existing
+/// (cached) column is coerced to the type of the column following the
+/// [`FieldCoercionPolicy`] of the namespace - see
+/// [`MutableBatch::coerce_field()`]. The request is rejected if a conflict
+/// cannot be resolved by the policy, or if dropping conflicting fields leaves
+/// a row without any field ([`SchemaError::NoFields`]).
+///
+/// The policy is read from the catalog along with the rest of the namespace
+/// schema, so a changed policy takes effect once the cached schema expires
+/// (see [`MemoryNamespaceCache::with_ttl()`]).
+///
+/// # Strict Schema
+///
+/// Namespaces in strict schema mode never have tables or columns created
+/// implicitly - writes using tables or columns that have not been declared
+/// through the schema API are rejected with [`SchemaError::Undeclared`].
+///
+/// Because declarations and the mode of a namespace may change after its
+/// schema was cached, a write that would grow the cached schema first re-reads
+/// the mode of the namespace from the catalog and, for namespaces in strict
+/// mode, the declared schema.
+///
 /// To minimise locking, this cache is designed to allow (and tolerate) spurious
 /// cache "updates" racing with each other and overwriting newer schemas with
 /// older schemas. This is acceptable due to the incremental, additive schema
@@ -83,6 +286,8 @@ pub enum SchemaError {
 /// produce incorrect schemas ([#3573]).
 ///
 /// [#3573]: https://github.com/influxdata/influxdb_iox/issues/3573
+/// [`FieldCoercionPolicy`]: data_types::FieldCoercionPolicy
+/// [`FieldCoercionPolicy::Drop`]: data_types::FieldCoercionPolicy::Drop
 #[derive(Debug)]
 pub struct SchemaValidator<C = Arc<InstrumentedCache<MemoryNamespaceCache>>> {
     catalog: Arc<dyn Catalog>,
@@ -90,6 +295,10 @@ pub struct SchemaValidator<C = Arc<InstrumentedCache<MemoryNamespaceCache>>> {
 
     service_limit_hit: U64Counter,
     schema_conflict: U64Counter,
+    undeclared_schema: U64Counter,
+    fields_widened: U64Counter,
+    fields_stringified: U64Counter,
+    fields_dropped: U64Counter,
 }
 
 impl<C> SchemaValidator<C> {
@@ -110,14 +319,86 @@ impl<C> SchemaValidator<C> {
                 "number of requests that fail due to a schema conflict",
             )
             .recorder(&[]);
+        let undeclared_schema = metrics
+            .register_metric::<U64Counter>(
+                "schema_validation_undeclared_schema",
+                "number of requests to strict schema namespaces that fail due to an undeclared table or column",
+            )
+            .recorder(&[]);
+        let field_coercion = metrics.register_metric::<U64Counter>(
+            "schema_validation_field_coercion",
+            "number of fields with a conflicting type coerced to the type of the existing column",
+        );
+        let fields_widened = field_coercion.recorder(&[("coercion", "widened")]);
+        let fields_stringified = field_coercion.recorder(&[("coercion", "stringified")]);
+        let fields_dropped = field_coercion.recorder(&[("coercion", "dropped")]);
 
         Self {
             catalog,
             cache: ns_cache,
             service_limit_hit,
             schema_conflict,
+            undeclared_schema,
+            fields_widened,
+            fields_stringified,
+            fields_dropped,
         }
     }
+
+    /// Coerce the fields of `batches` conflicting with the column types of
+    /// `schema` following its field coercion policy.
+    ///
+    /// Conflicts the policy does not resolve are left in place, to be rejected
+    /// by the schema validation.
+    fn coerce_fields(
+        &self,
+        namespace: &DatabaseName<'static>,
+        schema: &NamespaceSchema,
+        batches: &mut HashMap<String, MutableBatch>,
+    ) -> Result<(), SchemaError> {
+        let policy = schema.field_coercion_policy;
+        for (table, batch) in batches.iter_mut() {
+            let table_schema = match schema.tables.get(table) {
+                Some(v) => v,
+                None => continue,
+            };
+            let mut dropped = false;
+            for (column, existing) in field_conflicts(table_schema, batch) {
+                match batch.coerce_field(&column, existing, policy) {
+                    Ok(Some(coercion)) => {
+                        debug!(
+                            %namespace,
+                            table_name=%table,
+                            column_name=%column,
+                            coercion=coercion.as_str(),
+                            "field type conflict coerced"
+                        );
+                        match coercion {
+                            FieldCoercion::Widened => self.fields_widened.inc(1),
+                            FieldCoercion::Stringified => self.fields_stringified.inc(1),
+                            FieldCoercion::Dropped => {
+                                self.fields_dropped.inc(1);
+                                dropped = true;
+                            }
+                        }
+                    }
+                    Ok(None) | Err(_) => {}
+                }
+            }
+
+            if dropped {
+                let rows = batch.rows_without_fields();
+                if rows > 0 {
+                    warn!(%namespace, table_name=%table, rows, "rows left without fields rejected");
+                    return Err(SchemaError::NoFields {
+                        table: table.clone(),
+                        rows,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -130,6 +411,7 @@ where
 
     type WriteInput = HashMap<String, MutableBatch>;
     type WriteOutput = Self::WriteInput;
+    type DeleteOutput = ();
 
     /// Validate the schema of all the writes in `batches`.
     ///
@@ -138,18 +420,23 @@ where
     /// If `namespace` does not exist, [`SchemaError::NamespaceLookup`] is
     /// returned.
     ///
-    /// If the schema validation fails due to a schema conflict in the request,
-    /// [`SchemaError::Conflict`] is returned.
+    /// If the schema validation fails due to a schema conflict in the request
+    /// that the field coercion policy of `namespace` does not resolve,
+    /// [`SchemaError::Conflict`] is returned. If dropping conflicting fields
+    /// leaves rows without any field, [`SchemaError::NoFields`] is returned.
     ///
     /// If the schema validation fails due to a service limit being reached,
     /// [`SchemaError::ServiceLimit`] is returned.
     ///
+    /// If `namespace` is in strict schema mode and the request uses an
+    /// undeclared table or column, [`SchemaError::Undeclared`] is returned.
+    ///
     /// A request that fails validation on one or more tables fails the request
     /// as a whole - calling this method has "all or nothing" semantics.
     async fn write(
         &self,
         namespace: &DatabaseName<'static>,
-        batches: Self::WriteInput,
+        mut batches: Self::WriteInput,
         _span_ctx: Option<SpanContext>,
     ) -> Result<Self::WriteOutput, Self::WriteError> {
         let mut repos = self.catalog.repositories().await;
@@ -178,6 +465,51 @@ where
             }
         };
 
+        // A write growing the cached schema must not create tables or columns
+        // in a strict schema namespace - re-read the mode of the namespace,
+        // as it may have changed since the schema was cached.
+        let schema = match find_undeclared(namespace, &schema, &batches) {
+            Some(_) => {
+                let strict_schema = repos
+                    .namespaces()
+                    .get_by_name(namespace.as_str())
+                    .await
+                    .map_err(SchemaError::UnexpectedCatalogError)?
+                    .map(|ns| ns.strict_schema)
+                    .unwrap_or_default();
+
+                if strict_schema || schema.strict_schema {
+                    // Pick up the tables and columns declared since the
+                    // schema was cached, along with the current mode.
+                    let schema = get_schema_by_name(namespace, repos.deref_mut())
+                        .await
+                        .map_err(|e| {
+                            warn!(error=%e, %namespace, "failed to retrieve namespace schema");
+                            SchemaError::NamespaceLookup(e)
+                        })
+                        .map(Arc::new)?;
+                    self.cache
+                        .put_schema(namespace.clone(), Arc::clone(&schema));
+
+                    if schema.strict_schema {
+                        if let Some(e) = find_undeclared(namespace, &schema, &batches) {
+                            warn!(%namespace, error=%e, "write to undeclared schema rejected");
+                            self.undeclared_schema.inc(1);
+                            return Err(SchemaError::Undeclared(e));
+                        }
+                    }
+                    schema
+                } else {
+                    schema
+                }
+            }
+            None => schema,
+        };
+
+        // Coerce the fields conflicting with the cached column types. Remaining
+        // conflicts are rejected by the validation below.
+        self.coerce_fields(namespace, &schema, &mut batches)?;
+
         let maybe_new_schema = validate_or_insert_schema(
             batches.iter().map(|(k, v)| (k.as_str(), v)),
             &schema,
@@ -201,7 +533,8 @@ where
                         "schema conflict"
                     );
                     self.schema_conflict.inc(1);
-                    SchemaError::Conflict(e)
+                    let columns = column_conflicts(&schema, &batches, &e);
+                    SchemaError::Conflict(SchemaConflict::new(e, columns))
                 }
                 // Service limits
                 CatalogError::ColumnCreateLimitError { .. }
@@ -248,7 +581,7 @@ where
         _table_name: &str,
         _predicate: &DeletePredicate,
         _span_ctx: Option<SpanContext>,
-    ) -> Result<(), Self::DeleteError> {
+    ) -> Result<Self::DeleteOutput, Self::DeleteError> {
         Ok(())
     }
 }
@@ -257,10 +590,13 @@ where
 mod tests {
     use super::*;
     use assert_matches::assert_matches;
-    use data_types::{ColumnType, QueryPoolId, TimestampRange, TopicId};
+    use data_types::{ColumnType, FieldCoercionPolicy, QueryPoolId, TimestampRange, TopicId};
     use iox_catalog::mem::MemCatalog;
+    use iox_time::{MockProvider, Time};
     use once_cell::sync::Lazy;
-    use std::sync::Arc;
+    use std::{sync::Arc, time::Duration};
+
+    const SCHEMA_TTL: Duration = Duration::from_secs(60);
 
     static NAMESPACE: Lazy<DatabaseName<'static>> = Lazy::new(|| "bananas".try_into().unwrap());
 
@@ -397,6 +733,53 @@ mod tests {
         assert_eq!(1, handler.schema_conflict.fetch());
     }
 
+    #[tokio::test]
+    async fn test_write_schema_conflict_all_columns() {
+        let catalog = create_catalog().await;
+        let metrics = Arc::new(metric::Registry::default());
+        let handler = SchemaValidator::new(
+            catalog,
+            Arc::new(MemoryNamespaceCache::default()),
+            &*metrics,
+        );
+
+        let writes = lp_to_writes(
+            "bananas,tag1=A val=42i,other=1i 123456\n\
+             platanos,tag1=A val=42i 123456",
+        );
+        handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect("request should succeed");
+
+        // The catalog reports the first conflict only, but all conflicting
+        // columns of all tables are returned.
+        let writes = lp_to_writes(
+            "bananas,tag1=A val=42.0,other=\"x\" 123456\n\
+             platanos,tag1=A val=true 123456",
+        );
+        let err = handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect_err("request should fail");
+
+        let conflict = |table: &str, column: &str| ColumnConflict {
+            table: table.to_string(),
+            column: column.to_string(),
+            existing: ColumnType::I64,
+        };
+        assert_matches!(err, SchemaError::Conflict(e) => {
+            assert_eq!(
+                e.columns(),
+                [
+                    conflict("bananas", "other"),
+                    conflict("bananas", "val"),
+                    conflict("platanos", "val"),
+                ]
+            );
+        });
+    }
+
     #[tokio::test]
     async fn test_write_table_service_limit() {
         let catalog = create_catalog().await;
@@ -473,6 +856,239 @@ mod tests {
         assert_eq!(1, handler.service_limit_hit.fetch());
     }
 
+    #[tokio::test]
+    async fn test_write_strict_schema() {
+        let catalog = create_catalog().await;
+        let metrics = Arc::new(metric::Registry::default());
+        let handler = SchemaValidator::new(
+            Arc::clone(&catalog),
+            Arc::new(MemoryNamespaceCache::default()),
+            &*metrics,
+        );
+
+        // A first write grows the schema of the namespace, caching it.
+        let writes = lp_to_writes("bananas,tag1=A val=42i 123456");
+        handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect("request should succeed");
+
+        // Switch the namespace to strict mode after its schema was cached.
+        {
+            let mut repos = catalog.repositories().await;
+            repos
+                .namespaces()
+                .update_strict_schema(NAMESPACE.as_str(), true)
+                .await
+                .unwrap();
+        }
+
+        // Writes to the known tables and columns are accepted.
+        let writes = lp_to_writes("bananas,tag1=B val=24i 123457");
+        handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect("request should succeed");
+
+        let writes = lp_to_writes("bananas,tag1=A val=42i,val2=42i 123456");
+        let err = handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect_err("request should fail");
+        assert_matches!(err, SchemaError::Undeclared(UndeclaredSchemaError::Column { table, column, .. }) => {
+            assert_eq!(table, "bananas");
+            assert_eq!(column, "val2");
+        });
+
+        let writes = lp_to_writes("platanos,tag1=A val=42i 123456");
+        let err = handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect_err("request should fail");
+        assert_matches!(err, SchemaError::Undeclared(UndeclaredSchemaError::Table { table, .. }) => {
+            assert_eq!(table, "platanos");
+        });
+        assert_eq!(2, handler.undeclared_schema.fetch());
+
+        // Declaring the column allows the write.
+        {
+            let mut repos = catalog.repositories().await;
+            let table = repos
+                .tables()
+                .get_by_namespace_and_name(
+                    handler.cache.get_schema(&*NAMESPACE).unwrap().id,
+                    "bananas",
+                )
+                .await
+                .unwrap()
+                .unwrap();
+            repos
+                .columns()
+                .create_or_get("val2", table.id, ColumnType::I64)
+                .await
+                .unwrap();
+        }
+        let writes = lp_to_writes("bananas,tag1=A val=42i,val2=42i 123456");
+        handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect("request should succeed");
+        assert_cache(&handler, "bananas", "val2", ColumnType::I64);
+
+        // Leaving strict mode grows the schema implicitly again.
+        {
+            let mut repos = catalog.repositories().await;
+            repos
+                .namespaces()
+                .update_strict_schema(NAMESPACE.as_str(), false)
+                .await
+                .unwrap();
+        }
+        let writes = lp_to_writes("platanos,tag1=A val=42i 123456");
+        handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect("request should succeed");
+        assert_cache(&handler, "platanos", "val", ColumnType::I64);
+    }
+
+    #[tokio::test]
+    async fn test_write_field_coercion() {
+        let catalog = create_catalog().await;
+        let metrics = Arc::new(metric::Registry::default());
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let handler = SchemaValidator::new(
+            Arc::clone(&catalog),
+            Arc::new(MemoryNamespaceCache::with_ttl(
+                SCHEMA_TTL,
+                Arc::clone(&time_provider) as _,
+            )),
+            &*metrics,
+        );
+
+        // Set the policy, and expire the cached schema to pick it up.
+        let set_policy = |policy| {
+            let catalog = Arc::clone(&catalog);
+            let time_provider = Arc::clone(&time_provider);
+            async move {
+                catalog
+                    .repositories()
+                    .await
+                    .namespaces()
+                    .update_field_coercion_policy(NAMESPACE.as_str(), policy)
+                    .await
+                    .expect("failed to set field coercion policy");
+                time_provider.inc(SCHEMA_TTL);
+            }
+        };
+
+        // First write sets the schema
+        let writes = lp_to_writes("bananas,tag1=A val=4.2,text=\"a\" 123456");
+        handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect("request should succeed");
+
+        // The conflicting integer is rejected by default.
+        let err = handler
+            .write(
+                &*NAMESPACE,
+                lp_to_writes("bananas,tag1=A val=42i 123457"),
+                None,
+            )
+            .await
+            .expect_err("request should fail");
+        assert_matches!(err, SchemaError::Conflict(_));
+
+        // Relaxing the policy takes effect once the cached schema expires.
+        catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_field_coercion_policy(NAMESPACE.as_str(), FieldCoercionPolicy::Widen)
+            .await
+            .unwrap();
+        let err = handler
+            .write(
+                &*NAMESPACE,
+                lp_to_writes("bananas,tag1=A val=42i 123457"),
+                None,
+            )
+            .await
+            .expect_err("request should fail");
+        assert_matches!(err, SchemaError::Conflict(_));
+
+        // The expired schema is re-read with the relaxed policy, widening the
+        // integer.
+        time_provider.inc(SCHEMA_TTL);
+        let got = handler
+            .write(
+                &*NAMESPACE,
+                lp_to_writes("bananas,tag1=A val=42i 123457"),
+                None,
+            )
+            .await
+            .expect("request should succeed");
+        assert_eq!(
+            got["bananas"].column("val").unwrap().influx_type(),
+            InfluxColumnType::Field(InfluxFieldType::Float)
+        );
+        assert_eq!(
+            handler
+                .cache
+                .get_schema(&*NAMESPACE)
+                .unwrap()
+                .field_coercion_policy,
+            FieldCoercionPolicy::Widen
+        );
+        assert_cache(&handler, "bananas", "val", ColumnType::F64);
+
+        // Widening does not turn booleans into strings.
+        let writes = lp_to_writes("bananas,tag1=A val=1.0,text=true 123458");
+        let err = handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect_err("request should fail");
+        assert_matches!(err, SchemaError::Conflict(_));
+
+        set_policy(FieldCoercionPolicy::Stringify).await;
+        let writes = lp_to_writes("bananas,tag1=A val=1.0,text=true 123458");
+        let got = handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect("request should succeed");
+        assert_eq!(
+            got["bananas"].column("text").unwrap().influx_type(),
+            InfluxColumnType::Field(InfluxFieldType::String)
+        );
+
+        // Dropping keeps the other fields of the line.
+        set_policy(FieldCoercionPolicy::Drop).await;
+        let writes = lp_to_writes("bananas,tag1=A val=true,other=1i 123459");
+        let got = handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect("request should succeed");
+        assert!(got["bananas"].column("val").is_err());
+        assert!(got["bananas"].column("other").is_ok());
+        assert_cache(&handler, "bananas", "other", ColumnType::I64);
+
+        // A line left without fields is rejected.
+        let writes = lp_to_writes("bananas,tag1=A val=true 123460\nbananas,tag1=B other=2i 123461");
+        let err = handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect_err("request should fail");
+        assert_matches!(err, SchemaError::NoFields { table, rows: 1 } => {
+            assert_eq!(table, "bananas");
+        });
+
+        assert_eq!(1, handler.fields_widened.fetch());
+        assert_eq!(1, handler.fields_stringified.fetch());
+        assert_eq!(2, handler.fields_dropped.fetch());
+        assert_eq!(3, handler.schema_conflict.fetch());
+    }
+
     #[tokio::test]
     async fn test_write_delete_passthrough_ok() {
         const NAMESPACE: &str = "NAMESPACE_IS_NOT_VALIDATED";
@@ -87,6 +87,7 @@ where
 
     type WriteInput = Partitioned<HashMap<String, MutableBatch>>;
     type WriteOutput = Vec<DmlMeta>;
+    type DeleteOutput = Vec<DmlMeta>;
 
     /// Shard `writes` and dispatch the resultant DML operations.
     async fn write(
@@ -144,7 +145,7 @@ where
         table_name: &str,
         predicate: &DeletePredicate,
         span_ctx: Option<SpanContext>,
-    ) -> Result<(), ShardError> {
+    ) -> Result<Self::DeleteOutput, ShardError> {
         let predicate = predicate.clone();
         let shards = self.sharder.shard(table_name, namespace, &predicate);
 
@@ -165,10 +166,7 @@ where
             (s, DmlOperation::from(dml.clone()))
         });
 
-        // TODO: return shard metadata
-        parallel_enqueue(iter).await?;
-
-        Ok(())
+        parallel_enqueue(iter).await
     }
 }
 
@@ -590,10 +588,15 @@ mod tests {
 
         // Call the ShardedWriteBuffer and drive the test
         let ns = DatabaseName::new("namespace").unwrap();
-        w.delete(&ns, TABLE, &predicate, None)
+        let metas = w
+            .delete(&ns, TABLE, &predicate, None)
             .await
             .expect("delete failed");
 
+        // The metadata of the delete in both shards is returned
+        assert_eq!(metas.len(), 2);
+        assert!(metas.iter().all(|meta| meta.sequence().is_some()));
+
         // The write buffer for shard 1 should observe the delete
         let mut got = write_buffer1_state.get_messages(shard1.shard_index());
         assert_eq!(got.len(), 1);
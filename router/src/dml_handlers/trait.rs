@@ -1,7 +1,9 @@
-use super::{partitioner::PartitionError, NamespaceCreationError, SchemaError, ShardError};
+use super::{
+    partitioner::PartitionError, NamespaceCreationError, RateLimitError, SchemaError, ShardError,
+};
 use async_trait::async_trait;
 use data_types::{DatabaseName, DeletePredicate};
-use std::{error::Error, fmt::Debug, sync::Arc};
+use std::{error::Error, fmt::Debug, sync::Arc, time::Duration};
 use thiserror::Error;
 use trace::ctx::SpanContext;
 
@@ -29,11 +31,26 @@ pub enum DmlError {
     #[error(transparent)]
     Partition(#[from] PartitionError),
 
+    /// The write was throttled by the write rate limits of the namespace.
+    #[error(transparent)]
+    RateLimited(#[from] RateLimitError),
+
     /// An unknown error occured while processing the DML request.
     #[error("internal dml handler error: {0}")]
     Internal(Box<dyn Error + Send + Sync>),
 }
 
+impl DmlError {
+    /// The time after which a throttled request is expected to be accepted,
+    /// if the request was throttled.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited(e) => Some(e.retry_after()),
+            _ => None,
+        }
+    }
+}
+
 /// A composable, abstract handler of DML requests.
 #[async_trait]
 pub trait DmlHandler: Debug + Send + Sync {
@@ -48,6 +65,9 @@ pub trait DmlHandler: Debug + Send + Sync {
     /// processing a write.
     type WriteOutput: Debug + Send + Sync;
 
+    /// The output type returned by this handler after processing a delete.
+    type DeleteOutput: Debug + Send + Sync;
+
     /// The type of error a [`DmlHandler`] implementation produces for write
     /// requests.
     ///
@@ -72,7 +92,7 @@ pub trait DmlHandler: Debug + Send + Sync {
         table_name: &str,
         predicate: &DeletePredicate,
         span_ctx: Option<SpanContext>,
-    ) -> Result<(), Self::DeleteError>;
+    ) -> Result<Self::DeleteOutput, Self::DeleteError>;
 }
 
 #[async_trait]
@@ -82,6 +102,7 @@ where
 {
     type WriteInput = T::WriteInput;
     type WriteOutput = T::WriteOutput;
+    type DeleteOutput = T::DeleteOutput;
     type WriteError = T::WriteError;
     type DeleteError = T::DeleteError;
 
@@ -101,7 +122,7 @@ where
         table_name: &str,
         predicate: &DeletePredicate,
         span_ctx: Option<SpanContext>,
-    ) -> Result<(), Self::DeleteError> {
+    ) -> Result<Self::DeleteOutput, Self::DeleteError> {
         (**self)
             .delete(namespace, table_name, predicate, span_ctx)
             .await
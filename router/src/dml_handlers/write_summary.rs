@@ -7,8 +7,8 @@ use trace::ctx::SpanContext;
 use write_summary::WriteSummary;
 
 /// A [`WriteSummaryAdapter`] wraps DML Handler that produces
-///  `Vec<Vec<DmlMeta>>` for each write, and produces a WriteSummary,
-///  suitable for
+///  `Vec<Vec<DmlMeta>>` for each write and `Vec<DmlMeta>` for each delete,
+///  and produces a WriteSummary, suitable for
 /// sending back to a client
 #[derive(Debug, Default)]
 pub struct WriteSummaryAdapter<T> {
@@ -26,10 +26,11 @@ impl<T> WriteSummaryAdapter<T> {
 #[async_trait]
 impl<T> DmlHandler for WriteSummaryAdapter<T>
 where
-    T: DmlHandler<WriteOutput = Vec<Vec<DmlMeta>>>,
+    T: DmlHandler<WriteOutput = Vec<Vec<DmlMeta>>, DeleteOutput = Vec<DmlMeta>>,
 {
     type WriteInput = T::WriteInput;
     type WriteOutput = WriteSummary;
+    type DeleteOutput = WriteSummary;
     type WriteError = T::WriteError;
     type DeleteError = T::DeleteError;
 
@@ -45,16 +46,19 @@ where
         Ok(WriteSummary::new(metas))
     }
 
-    /// Pass the delete through to the inner handler.
+    /// Sends the delete to the inner handler, which returns a
+    /// `Vec<DmlMeta>`, creating a `WriteSummary`
     async fn delete(
         &self,
         namespace: &DatabaseName<'static>,
         table_name: &str,
         predicate: &DeletePredicate,
         span_ctx: Option<SpanContext>,
-    ) -> Result<(), Self::DeleteError> {
-        self.inner
+    ) -> Result<Self::DeleteOutput, Self::DeleteError> {
+        let metas = self
+            .inner
             .delete(namespace, table_name, predicate, span_ctx)
-            .await
+            .await?;
+        Ok(WriteSummary::new(vec![metas]))
     }
 }
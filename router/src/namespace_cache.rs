@@ -25,3 +25,19 @@ pub trait NamespaceCache: Debug + Send + Sync {
         schema: impl Into<Arc<NamespaceSchema>>,
     ) -> Option<Arc<NamespaceSchema>>;
 }
+
+/// A read-only, object-safe view of a [`NamespaceCache`], for callers that
+/// only look up the cached schemas.
+pub trait NamespaceSchemaLookup: Debug + Send + Sync {
+    /// Return the cached [`NamespaceSchema`] for `namespace`, if any.
+    fn lookup(&self, namespace: &DatabaseName<'_>) -> Option<Arc<NamespaceSchema>>;
+}
+
+impl<T> NamespaceSchemaLookup for T
+where
+    T: NamespaceCache,
+{
+    fn lookup(&self, namespace: &DatabaseName<'_>) -> Option<Arc<NamespaceSchema>> {
+        self.get_schema(namespace)
+    }
+}
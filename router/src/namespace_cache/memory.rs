@@ -1,19 +1,60 @@
 use super::NamespaceCache;
 use data_types::{DatabaseName, NamespaceSchema};
 use hashbrown::HashMap;
+use iox_time::{Time, TimeProvider};
 use parking_lot::RwLock;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
+
+/// A cached [`NamespaceSchema`], along with the time it was first cached.
+#[derive(Debug)]
+struct CachedSchema {
+    schema: Arc<NamespaceSchema>,
+    cached_at: Option<Time>,
+}
 
 /// An in-memory cache of [`NamespaceSchema`] backed by a hashmap protected with
 /// a read-write mutex.
+///
+/// If configured with a TTL, a schema is no longer returned once it was
+/// cached for longer than the TTL, causing the caller to re-read it from the
+/// catalog and pick up the changed settings of the namespace. Replacing a
+/// cached schema that has not expired (such as with a schema extended by
+/// new columns) does not extend its lifetime.
 #[derive(Debug, Default)]
 pub struct MemoryNamespaceCache {
-    cache: RwLock<HashMap<DatabaseName<'static>, Arc<NamespaceSchema>>>,
+    cache: RwLock<HashMap<DatabaseName<'static>, CachedSchema>>,
+    ttl: Option<(Duration, Arc<dyn TimeProvider>)>,
+}
+
+impl MemoryNamespaceCache {
+    /// Initialise a new, empty [`MemoryNamespaceCache`] expiring schemas
+    /// `ttl` after they were first cached.
+    pub fn with_ttl(ttl: Duration, time_provider: Arc<dyn TimeProvider>) -> Self {
+        Self {
+            cache: Default::default(),
+            ttl: Some((ttl, time_provider)),
+        }
+    }
+
+    /// Returns true if `cached` is older than the TTL.
+    fn is_expired(&self, cached: &CachedSchema) -> bool {
+        match (&self.ttl, cached.cached_at) {
+            (Some((ttl, time_provider)), Some(cached_at)) => time_provider
+                .now()
+                .checked_duration_since(cached_at)
+                .map_or(false, |age| age >= *ttl),
+            _ => false,
+        }
+    }
 }
 
 impl NamespaceCache for Arc<MemoryNamespaceCache> {
     fn get_schema(&self, namespace: &DatabaseName<'_>) -> Option<Arc<NamespaceSchema>> {
-        self.cache.read().get(namespace).map(Arc::clone)
+        self.cache
+            .read()
+            .get(namespace)
+            .filter(|cached| !self.is_expired(cached))
+            .map(|cached| Arc::clone(&cached.schema))
     }
 
     fn put_schema(
@@ -21,7 +62,24 @@ impl NamespaceCache for Arc<MemoryNamespaceCache> {
         namespace: DatabaseName<'static>,
         schema: impl Into<Arc<NamespaceSchema>>,
     ) -> Option<Arc<NamespaceSchema>> {
-        self.cache.write().insert(namespace, schema.into())
+        let mut cache = self.cache.write();
+        let cached_at = match cache.get(&namespace) {
+            Some(cached) if !self.is_expired(cached) => cached.cached_at,
+            _ => self
+                .ttl
+                .as_ref()
+                .map(|(_, time_provider)| time_provider.now()),
+        };
+
+        cache
+            .insert(
+                namespace,
+                CachedSchema {
+                    schema: schema.into(),
+                    cached_at,
+                },
+            )
+            .map(|cached| cached.schema)
     }
 }
 
@@ -29,6 +87,7 @@ impl NamespaceCache for Arc<MemoryNamespaceCache> {
 mod tests {
     use super::*;
     use data_types::{NamespaceId, QueryPoolId, TopicId};
+    use iox_time::MockProvider;
 
     #[test]
     fn test_put_get() {
@@ -42,6 +101,9 @@ mod tests {
             topic_id: TopicId::new(24),
             query_pool_id: QueryPoolId::new(1234),
             tables: Default::default(),
+            write_limits: Default::default(),
+            strict_schema: false,
+            field_coercion_policy: Default::default(),
         };
         assert!(cache.put_schema(ns.clone(), schema1.clone()).is_none());
         assert_eq!(*cache.get_schema(&ns).expect("lookup failure"), schema1);
@@ -51,6 +113,9 @@ mod tests {
             topic_id: TopicId::new(2),
             query_pool_id: QueryPoolId::new(2),
             tables: Default::default(),
+            write_limits: Default::default(),
+            strict_schema: false,
+            field_coercion_policy: Default::default(),
         };
 
         assert_eq!(
@@ -61,4 +126,31 @@ mod tests {
         );
         assert_eq!(*cache.get_schema(&ns).expect("lookup failure"), schema2);
     }
+
+    #[test]
+    fn test_ttl() {
+        let ns = DatabaseName::new("test").expect("database name is valid");
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let cache = Arc::new(MemoryNamespaceCache::with_ttl(
+            Duration::from_secs(10),
+            Arc::clone(&time_provider) as _,
+        ));
+
+        let schema =
+            NamespaceSchema::new(NamespaceId::new(1), TopicId::new(2), QueryPoolId::new(3));
+        cache.put_schema(ns.clone(), schema.clone());
+
+        // Replacing the schema does not extend its lifetime.
+        time_provider.inc(Duration::from_secs(9));
+        assert!(cache.put_schema(ns.clone(), schema.clone()).is_some());
+        assert!(cache.get_schema(&ns).is_some());
+
+        time_provider.inc(Duration::from_secs(1));
+        assert!(cache.get_schema(&ns).is_none());
+
+        // Caching the schema again restarts its lifetime.
+        cache.put_schema(ns.clone(), schema);
+        time_provider.inc(Duration::from_secs(9));
+        assert!(cache.get_schema(&ns).is_some());
+    }
 }
@@ -194,6 +194,9 @@ mod tests {
             topic_id: TopicId::new(24),
             query_pool_id: QueryPoolId::new(1234),
             tables,
+            write_limits: Default::default(),
+            strict_schema: false,
+            field_coercion_policy: Default::default(),
         }
     }
 
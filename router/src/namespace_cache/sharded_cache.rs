@@ -60,6 +60,9 @@ mod tests {
             topic_id: TopicId::new(1),
             query_pool_id: QueryPoolId::new(1),
             tables: Default::default(),
+            write_limits: Default::default(),
+            strict_schema: false,
+            field_coercion_policy: Default::default(),
         }
     }
 
@@ -17,6 +17,10 @@ use generated_types::{
 };
 use hashbrown::HashMap;
 use iox_catalog::interface::Catalog;
+use ioxd_common::{
+    authz::{self, Authorizer, Permission},
+    http::error::retry_after_secs,
+};
 use metric::U64Counter;
 use mutable_batch::MutableBatch;
 use object_store::DynObjectStore;
@@ -26,7 +30,10 @@ use service_grpc_catalog::CatalogService;
 use service_grpc_object_store::ObjectStoreService;
 use service_grpc_schema::SchemaService;
 use std::sync::Arc;
-use tonic::{metadata::AsciiMetadataValue, Request, Response, Status};
+use tonic::{
+    metadata::{AsciiMetadataValue, MetadataMap},
+    Code, Request, Response, Status,
+};
 use trace::ctx::SpanContext;
 use write_summary::WriteSummary;
 
@@ -34,6 +41,10 @@ use write_summary::WriteSummary;
 // investigate the cause if you dare.
 const WRITE_TOKEN_GRPC_HEADER: &str = "x-iox-write-token";
 
+/// The metadata key of the number of seconds after which a throttled write is
+/// expected to be accepted.
+const RETRY_AFTER_GRPC_HEADER: &str = "retry-after";
+
 /// This type is responsible for managing all gRPC services exposed by `router`.
 #[derive(Debug)]
 pub struct GrpcDelegate<D, S> {
@@ -42,6 +53,7 @@ pub struct GrpcDelegate<D, S> {
     object_store: Arc<DynObjectStore>,
     metrics: Arc<metric::Registry>,
     shard_service: ShardService<S>,
+    authz: Option<Arc<dyn Authorizer>>,
 }
 
 impl<D, S> GrpcDelegate<D, S> {
@@ -59,8 +71,16 @@ impl<D, S> GrpcDelegate<D, S> {
             object_store,
             metrics,
             shard_service,
+            authz: None,
         }
     }
+
+    /// Require writes and schema declarations to carry a token granting
+    /// `authz` write access to the namespace they write to.
+    pub fn with_authorizer(mut self, authz: Arc<dyn Authorizer>) -> Self {
+        self.authz = Some(authz);
+        self
+    }
 }
 
 impl<D, S> GrpcDelegate<D, S>
@@ -74,19 +94,22 @@ where
     pub fn write_service(
         &self,
     ) -> write_service_server::WriteServiceServer<impl write_service_server::WriteService> {
-        write_service_server::WriteServiceServer::new(WriteService::new(
-            Arc::clone(&self.dml_handler),
-            &*self.metrics,
-        ))
+        let mut service = WriteService::new(Arc::clone(&self.dml_handler), &*self.metrics);
+        if let Some(authz) = &self.authz {
+            service = service.with_authorizer(Arc::clone(authz));
+        }
+        write_service_server::WriteServiceServer::new(service)
     }
 
     /// Acquire a [`SchemaService`] gRPC service implementation.
     ///
     /// [`SchemaService`]: generated_types::influxdata::iox::schema::v1::schema_service_server::SchemaService.
     pub fn schema_service(&self) -> schema_service_server::SchemaServiceServer<SchemaService> {
-        schema_service_server::SchemaServiceServer::new(SchemaService::new(Arc::clone(
-            &self.catalog,
-        )))
+        let mut service = SchemaService::new(Arc::clone(&self.catalog));
+        if let Some(authz) = &self.authz {
+            service = service.with_authorizer(Arc::clone(authz));
+        }
+        schema_service_server::SchemaServiceServer::new(service)
     }
 
     /// Acquire a [`CatalogService`] gRPC service implementation.
@@ -128,6 +151,7 @@ where
 #[derive(Debug)]
 struct WriteService<D> {
     dml_handler: Arc<D>,
+    authz: Option<Arc<dyn Authorizer>>,
 
     write_metric_rows: U64Counter,
     write_metric_columns: U64Counter,
@@ -157,11 +181,17 @@ impl<D> WriteService<D> {
 
         Self {
             dml_handler,
+            authz: None,
             write_metric_rows,
             write_metric_columns,
             write_metric_tables,
         }
     }
+
+    fn with_authorizer(mut self, authz: Arc<dyn Authorizer>) -> Self {
+        self.authz = Some(authz);
+        self
+    }
 }
 
 #[tonic::async_trait]
@@ -175,6 +205,10 @@ where
         request: Request<WriteRequest>,
     ) -> Result<Response<WriteResponse>, Status> {
         let span_ctx: Option<SpanContext> = request.extensions().get().cloned();
+        let token = match self.authz {
+            Some(_) => authz::token_from_grpc_request(&request)?,
+            None => None,
+        };
         let database_batch = request
             .into_inner()
             .database_batch
@@ -206,6 +240,12 @@ where
                 description: format!("Invalid namespace: {}", e),
             })?;
 
+        if let Some(authz) = &self.authz {
+            authz
+                .authorize(token.as_ref(), &namespace, Permission::Write)
+                .await?;
+        }
+
         let num_tables = tables.len();
         debug!(
             num_tables,
@@ -227,6 +267,15 @@ where
                 | DmlError::Partition(PartitionError::BatchWrite(_))) => {
                     Status::internal(e.to_string())
                 }
+
+                DmlError::RateLimited(e) => {
+                    let mut metadata = MetadataMap::new();
+                    metadata.insert(
+                        RETRY_AFTER_GRPC_HEADER,
+                        AsciiMetadataValue::from(retry_after_secs(e.retry_after())),
+                    );
+                    Status::with_metadata(Code::ResourceExhausted, e.to_string(), metadata)
+                }
             })?;
 
         self.write_metric_rows.inc(row_count as _);
@@ -250,7 +299,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::dml_handlers::{mock::MockDmlHandler, DmlError};
+    use crate::dml_handlers::{mock::MockDmlHandler, DmlError, RateLimitError};
     use generated_types::influxdata::pbdata::v1::write_service_server::WriteService;
     use std::sync::Arc;
 
@@ -275,6 +324,63 @@ mod tests {
         assert!(err.message().contains("database_batch"));
     }
 
+    /// Rejects all requests.
+    #[derive(Debug)]
+    struct DenyAuthorizer;
+
+    #[async_trait::async_trait]
+    impl Authorizer for DenyAuthorizer {
+        async fn authorize(
+            &self,
+            token: Option<&authz::Token>,
+            _namespace: &str,
+            _permission: Permission,
+        ) -> Result<(), authz::Error> {
+            match token {
+                Some(_) => Err(authz::Error::InvalidToken),
+                None => Err(authz::Error::NoToken),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_unauthorized() {
+        let metrics = Arc::new(metric::Registry::default());
+        let handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(summary())]));
+        let grpc = super::WriteService::new(Arc::clone(&handler), &metrics)
+            .with_authorizer(Arc::new(DenyAuthorizer));
+
+        let req = WriteRequest {
+            database_batch: Some(DatabaseBatch {
+                database_name: "bananas".to_owned(),
+                table_batches: vec![],
+                partition_key: Default::default(),
+            }),
+        };
+
+        let mut request = Request::new(req.clone());
+        request
+            .metadata_mut()
+            .insert("authorization", "Token platanos".parse().unwrap());
+        let err = grpc
+            .write(request)
+            .await
+            .expect_err("rpc request should fail");
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+
+        let mut request = Request::new(req);
+        request
+            .metadata_mut()
+            .insert("authorization", "Basic platanos".parse().unwrap());
+        let err = grpc
+            .write(request)
+            .await
+            .expect_err("rpc request should fail");
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+
+        assert!(handler.calls().is_empty());
+    }
+
     #[tokio::test]
     async fn test_write_no_namespace() {
         let metrics = Arc::new(metric::Registry::default());
@@ -361,4 +467,39 @@ mod tests {
         assert_eq!(err.code(), tonic::Code::NotFound);
         assert!(err.message().contains("nope"));
     }
+
+    #[tokio::test]
+    async fn test_write_rate_limited() {
+        let metrics = Arc::new(metric::Registry::default());
+        let handler = Arc::new(MockDmlHandler::default().with_write_return([Err(
+            DmlError::RateLimited(RateLimitError::Throttled {
+                namespace: "bananas".to_string(),
+                retry_after: std::time::Duration::from_millis(2500),
+            }),
+        )]));
+        let grpc = super::WriteService::new(Arc::clone(&handler), &metrics);
+
+        let req = WriteRequest {
+            database_batch: Some(DatabaseBatch {
+                database_name: "bananas".to_owned(),
+                table_batches: vec![],
+                partition_key: Default::default(),
+            }),
+        };
+
+        let err = grpc
+            .write(Request::new(req))
+            .await
+            .expect_err("rpc request should fail");
+
+        assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+        assert_eq!(
+            err.metadata()
+                .get(RETRY_AFTER_GRPC_HEADER)
+                .expect("retry-after metadata")
+                .to_str()
+                .unwrap(),
+            "3"
+        );
+    }
 }
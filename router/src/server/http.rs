@@ -1,20 +1,38 @@
 //! HTTP service implementations for `router`.
 
-use crate::dml_handlers::{DmlError, DmlHandler, PartitionError, SchemaError};
+pub mod idempotency;
+
+use self::idempotency::{
+    CachedWrite, IdempotencyCache, KeyState, IDEMPOTENCY_KEY_HTTP_HEADER, MAX_IDEMPOTENCY_KEY_LEN,
+};
+use crate::{
+    dml_handlers::{ColumnConflict, DmlError, DmlHandler, PartitionError, SchemaError},
+    namespace_cache::NamespaceSchemaLookup,
+};
 use bytes::{Bytes, BytesMut};
-use data_types::{org_and_bucket_to_database, OrgBucketMappingError};
+use data_types::{org_and_bucket_to_database, DatabaseName, OrgBucketMappingError};
 use futures::StreamExt;
 use hashbrown::HashMap;
-use hyper::{header::CONTENT_ENCODING, Body, Method, Request, Response, StatusCode};
+use hyper::{
+    header::{CONTENT_ENCODING, CONTENT_TYPE},
+    Body, Method, Request, Response, StatusCode,
+};
 use iox_time::{SystemProvider, TimeProvider};
-use metric::{DurationHistogram, U64Counter};
+use ioxd_common::authz::{self, Authorizer, Permission};
+use metric::{DurationHistogram, Metric, U64Counter};
 use mutable_batch::MutableBatch;
-use mutable_batch_lp::LinesConverter;
+use mutable_batch_lp::{LineWriteError, LinesConverter};
 use observability_deps::tracing::*;
 use predicate::delete_predicate::{parse_delete_predicate, parse_http_delete_request};
-use serde::Deserialize;
-use std::time::Instant;
-use std::{str::Utf8Error, sync::Arc};
+use schema::InfluxColumnType;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    str::Utf8Error,
+    sync::Arc,
+};
 use thiserror::Error;
 use tokio::sync::{Semaphore, TryAcquireError};
 use trace::ctx::SpanContext;
@@ -73,6 +91,22 @@ pub enum Error {
     /// simultaneous requests.
     #[error("this service is overloaded, please try again later")]
     RequestLimit,
+
+    /// The request is not authorized to access the namespace.
+    #[error(transparent)]
+    Authz(#[from] authz::Error),
+
+    /// The `Idempotency-Key` header is invalid.
+    #[error("invalid idempotency key header: {0}")]
+    InvalidIdempotencyKey(String),
+
+    /// The idempotency key was used by a recent write with a different body.
+    #[error("idempotency key {0} was used by a different write request")]
+    IdempotencyKeyReused(String),
+
+    /// A write with the same idempotency key is still in flight.
+    #[error("a write with idempotency key {0} is in progress, please retry later")]
+    IdempotencyKeyInFlight(String),
 }
 
 impl Error {
@@ -95,6 +129,21 @@ impl Error {
             }
             Error::DmlHandler(err) => StatusCode::from(err),
             Error::RequestLimit => StatusCode::SERVICE_UNAVAILABLE,
+            Error::Authz(e) => e.http_status_code(),
+            Error::InvalidIdempotencyKey(_) => StatusCode::BAD_REQUEST,
+            Error::IdempotencyKeyReused(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Error::IdempotencyKeyInFlight(_) => StatusCode::CONFLICT,
+        }
+    }
+
+    /// The time after which the request is expected to be accepted, if the
+    /// request was throttled.
+    ///
+    /// Returned to the end user in the `Retry-After` header.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::DmlHandler(err) => err.retry_after(),
+            _ => None,
         }
     }
 }
@@ -115,6 +164,8 @@ impl From<&DmlError> for StatusCode {
                 StatusCode::TOO_MANY_REQUESTS
             }
             DmlError::Schema(SchemaError::Conflict(_)) => StatusCode::BAD_REQUEST,
+            DmlError::Schema(SchemaError::Undeclared(_)) => StatusCode::BAD_REQUEST,
+            DmlError::Schema(SchemaError::NoFields { .. }) => StatusCode::BAD_REQUEST,
             DmlError::Schema(SchemaError::UnexpectedCatalogError(_)) => {
                 StatusCode::INTERNAL_SERVER_ERROR
             }
@@ -123,6 +174,7 @@ impl From<&DmlError> for StatusCode {
                 StatusCode::INTERNAL_SERVER_ERROR
             }
             DmlError::Partition(PartitionError::BatchWrite(_)) => StatusCode::INTERNAL_SERVER_ERROR,
+            DmlError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
         }
     }
 }
@@ -144,7 +196,7 @@ pub enum OrgBucketError {
     MappingFail(#[from] OrgBucketMappingError),
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Hash)]
 enum Precision {
     #[serde(rename = "s")]
     Seconds,
@@ -182,6 +234,62 @@ pub struct WriteInfo {
 
     #[serde(default)]
     precision: Precision,
+
+    /// Accept the valid lines of a write, rejecting only the invalid ones.
+    #[serde(default)]
+    partial_writes: bool,
+}
+
+/// A line rejected by a partial write.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+struct RejectedLine {
+    /// The 1-based line number.
+    line: usize,
+    /// Why the line was rejected, one of `parse_error`, `invalid_line`,
+    /// `timestamp_overflow` or `schema_conflict`.
+    reason: &'static str,
+    /// The error rejecting the line.
+    error: String,
+}
+
+impl From<mutable_batch_lp::Error> for RejectedLine {
+    fn from(e: mutable_batch_lp::Error) -> Self {
+        let reason = match &e {
+            mutable_batch_lp::Error::LineProtocol { .. } => "parse_error",
+            mutable_batch_lp::Error::Write {
+                source: LineWriteError::SchemaConflict { .. },
+                ..
+            } => "schema_conflict",
+            mutable_batch_lp::Error::Write { .. } => "invalid_line",
+            mutable_batch_lp::Error::TimestampOverflow { .. } => "timestamp_overflow",
+            mutable_batch_lp::Error::EmptyPayload => unreachable!("not a line error"),
+        };
+
+        Self {
+            line: e.line().expect("line error"),
+            reason,
+            error: e.to_string(),
+        }
+    }
+}
+
+/// The result of a DML request.
+#[derive(Debug, Default, Clone)]
+struct WriteOutcome {
+    summary: WriteSummary,
+    /// The number of lines written.
+    accepted_lines: usize,
+    /// The lines rejected by a partial write.
+    rejected_lines: Vec<RejectedLine>,
+}
+
+/// The response body of a partial write that rejected some lines.
+#[derive(Debug, Serialize)]
+struct PartialWriteResponse {
+    code: &'static str,
+    message: String,
+    accepted_lines: usize,
+    rejected_lines: Vec<RejectedLine>,
 }
 
 impl<T> TryFrom<&Request<T>> for WriteInfo {
@@ -200,6 +308,25 @@ impl<T> TryFrom<&Request<T>> for WriteInfo {
     }
 }
 
+/// Returns the idempotency key of `req`, if any.
+fn idempotency_key<T>(req: &Request<T>) -> Result<Option<String>, Error> {
+    let key = match req.headers().get(IDEMPOTENCY_KEY_HTTP_HEADER) {
+        Some(v) => v
+            .to_str()
+            .map_err(|e| Error::InvalidIdempotencyKey(e.to_string()))?,
+        None => return Ok(None),
+    };
+
+    if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LEN {
+        return Err(Error::InvalidIdempotencyKey(format!(
+            "expected 1 to {} characters",
+            MAX_IDEMPOTENCY_KEY_LEN
+        )));
+    }
+
+    Ok(Some(key.to_string()))
+}
+
 /// This type is responsible for servicing requests to the `router` HTTP
 /// endpoint.
 ///
@@ -212,6 +339,18 @@ pub struct HttpDelegate<D, T = SystemProvider> {
     time_provider: T,
     dml_handler: Arc<D>,
 
+    // The authorizer checking the namespace permissions of the request token,
+    // if requests are authorized.
+    authz: Option<Arc<dyn Authorizer>>,
+
+    // The outcome of the recent writes carrying an idempotency key, if
+    // retried writes are detected.
+    idempotency_cache: Option<IdempotencyCache>,
+
+    // The cached namespace schemas, used to reject the lines of a partial
+    // write conflicting with the namespace schema while parsing them.
+    namespace_schemas: Option<Arc<dyn NamespaceSchemaLookup>>,
+
     // A request limiter to restrict the number of simultaneous requests this
     // router services.
     //
@@ -222,12 +361,14 @@ pub struct HttpDelegate<D, T = SystemProvider> {
     request_sem: Semaphore,
 
     write_metric_lines: U64Counter,
+    write_metric_rejected_lines: Metric<U64Counter>,
     http_line_protocol_parse_duration: DurationHistogram,
     write_metric_fields: U64Counter,
     write_metric_tables: U64Counter,
     write_metric_body_size: U64Counter,
     delete_metric_body_size: U64Counter,
     request_limit_rejected: U64Counter,
+    write_metric_idempotent_retries: U64Counter,
 }
 
 impl<D> HttpDelegate<D, SystemProvider> {
@@ -248,6 +389,10 @@ impl<D> HttpDelegate<D, SystemProvider> {
                 "cumulative number of line protocol lines successfully routed",
             )
             .recorder(&[]);
+        let write_metric_rejected_lines = metrics.register_metric::<U64Counter>(
+            "http_write_rejected_lines_total",
+            "cumulative number of line protocol lines rejected by partial writes, by reason",
+        );
         let write_metric_fields = metrics
             .register_metric::<U64Counter>(
                 "http_write_fields_total",
@@ -278,6 +423,12 @@ impl<D> HttpDelegate<D, SystemProvider> {
                 "number of HTTP requests rejected due to exceeding parallel request limit",
             )
             .recorder(&[]);
+        let write_metric_idempotent_retries = metrics
+            .register_metric::<U64Counter>(
+                "http_write_idempotent_retries_total",
+                "number of write requests answered with the outcome of a prior request with the same idempotency key",
+            )
+            .recorder(&[]);
         let http_line_protocol_parse_duration = metrics
             .register_metric::<DurationHistogram>(
                 "http_line_protocol_parse_duration",
@@ -289,21 +440,63 @@ impl<D> HttpDelegate<D, SystemProvider> {
             max_request_bytes,
             time_provider: SystemProvider::default(),
             dml_handler,
+            authz: None,
+            idempotency_cache: None,
+            namespace_schemas: None,
             request_sem: Semaphore::new(max_requests),
             write_metric_lines,
+            write_metric_rejected_lines,
             http_line_protocol_parse_duration,
             write_metric_fields,
             write_metric_tables,
             write_metric_body_size,
             delete_metric_body_size,
             request_limit_rejected,
+            write_metric_idempotent_retries,
         }
     }
 }
 
+impl<D, T> HttpDelegate<D, T> {
+    /// Require the requests to carry a token granting `authz` write access to
+    /// the namespace they write to or delete from.
+    pub fn with_authorizer(mut self, authz: Arc<dyn Authorizer>) -> Self {
+        self.authz = Some(authz);
+        self
+    }
+
+    /// Answer a write carrying the idempotency key of a recent write to the
+    /// same namespace with the outcome of that write, instead of writing it
+    /// again.
+    ///
+    /// Writes of lines without a timestamp are assigned the time of the
+    /// request, so that a retried write would otherwise produce duplicate
+    /// rows.
+    ///
+    /// The key of a write is reserved while the write is in flight, and a
+    /// retry racing it is rejected with [`Error::IdempotencyKeyInFlight`]. The
+    /// key is released if the write fails, so that it can be retried.
+    pub fn with_idempotency_cache(mut self, cache: IdempotencyCache) -> Self {
+        self.idempotency_cache = Some(cache);
+        self
+    }
+
+    /// Reject the lines of a partial write that conflict with the column
+    /// types of the namespace schema in `schemas` while parsing the write,
+    /// instead of retrying the write after learning them from a schema conflict.
+    pub fn with_namespace_schemas(mut self, schemas: Arc<dyn NamespaceSchemaLookup>) -> Self {
+        self.namespace_schemas = Some(schemas);
+        self
+    }
+}
+
 impl<D, T> HttpDelegate<D, T>
 where
-    D: DmlHandler<WriteInput = HashMap<String, MutableBatch>, WriteOutput = WriteSummary>,
+    D: DmlHandler<
+        WriteInput = HashMap<String, MutableBatch>,
+        WriteOutput = WriteSummary,
+        DeleteOutput = WriteSummary,
+    >,
     T: TimeProvider,
 {
     /// Routes `req` to the appropriate handler, if any, returning the handler
@@ -329,19 +522,54 @@ where
         // Route the request to a handler.
         match (req.method(), req.uri().path()) {
             (&Method::POST, "/api/v2/write") => self.write_handler(req).await,
-            (&Method::POST, "/api/v2/delete") => self.delete_handler(req).await,
+            (&Method::POST, "/api/v2/delete") => {
+                self.delete_handler(req).await.map(|summary| WriteOutcome {
+                    summary,
+                    ..Default::default()
+                })
+            }
             _ => return Err(Error::NoHandler),
         }
-        .map(|summary| {
+        .map(|outcome| {
+            let WriteOutcome {
+                summary,
+                accepted_lines,
+                rejected_lines,
+            } = outcome;
+
+            if rejected_lines.is_empty() {
+                return Response::builder()
+                    .status(StatusCode::NO_CONTENT)
+                    .header(WRITE_TOKEN_HTTP_HEADER, summary.to_token())
+                    .body(Body::empty())
+                    .unwrap();
+            }
+
+            // As with InfluxDB 2, a partial write is answered with a 400,
+            // listing the rejected lines.
+            let body = PartialWriteResponse {
+                code: "invalid",
+                message: format!(
+                    "partial write: {} lines rejected, {} lines accepted",
+                    rejected_lines.len(),
+                    accepted_lines
+                ),
+                accepted_lines,
+                rejected_lines,
+            };
+
             Response::builder()
-                .status(StatusCode::NO_CONTENT)
+                .status(StatusCode::BAD_REQUEST)
                 .header(WRITE_TOKEN_HTTP_HEADER, summary.to_token())
-                .body(Body::empty())
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    serde_json::to_vec(&body).expect("serialize partial write response"),
+                ))
                 .unwrap()
         })
     }
 
-    async fn write_handler(&self, req: Request<Body>) -> Result<WriteSummary, Error> {
+    async fn write_handler(&self, req: Request<Body>) -> Result<WriteOutcome, Error> {
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
         let write_info = WriteInfo::try_from(&req)?;
@@ -350,54 +578,199 @@ where
 
         trace!(org=%write_info.org, bucket=%write_info.bucket, %namespace, "processing write request");
 
+        if let Some(authz) = &self.authz {
+            let token = authz::token_from_http_request(&req)?;
+            authz
+                .authorize(token.as_ref(), &namespace, Permission::Write)
+                .await?;
+        }
+
+        let idempotency_key = match &self.idempotency_cache {
+            Some(_) => idempotency_key(&req)?,
+            None => None,
+        };
+
         // Read the HTTP body and convert it to a str.
         let body = self.read_body(req).await?;
         let body = std::str::from_utf8(&body).map_err(Error::NonUtf8Body)?;
 
+        let (cache, key) = match (&self.idempotency_cache, idempotency_key) {
+            (Some(cache), Some(key)) => (cache, key),
+            _ => return self.write_lp(&namespace, &write_info, body, span_ctx).await,
+        };
+
+        // The outcome of a write depends on its parameters as well as its body.
+        let mut hasher = DefaultHasher::new();
+        body.hash(&mut hasher);
+        write_info.precision.hash(&mut hasher);
+        write_info.partial_writes.hash(&mut hasher);
+        let body_hash = hasher.finish();
+
+        // A retried write is answered with the outcome of the original write,
+        // which is not written again. The key is reserved while the write is
+        // in flight, so that a concurrent retry does not write it again either.
+        let reservation = match cache.reserve(namespace.as_str(), &key, body_hash) {
+            KeyState::Reserved(reservation) => reservation,
+            KeyState::InFlight {
+                body_hash: original,
+            }
+            | KeyState::Done(CachedWrite {
+                body_hash: original,
+                ..
+            }) if original != body_hash => {
+                return Err(Error::IdempotencyKeyReused(key));
+            }
+            KeyState::InFlight { .. } => return Err(Error::IdempotencyKeyInFlight(key)),
+            KeyState::Done(cached) => {
+                debug!(%namespace, idempotency_key=%key, "answering retried write");
+                self.write_metric_idempotent_retries.inc(1);
+                return Ok(cached.outcome);
+            }
+        };
+
+        // A failed write drops the reservation, releasing the key for a retry.
+        let outcome = self
+            .write_lp(&namespace, &write_info, body, span_ctx)
+            .await?;
+        reservation.complete(outcome.clone());
+
+        Ok(outcome)
+    }
+
+    /// Write the line protocol `body` to `namespace`.
+    async fn write_lp(
+        &self,
+        namespace: &DatabaseName<'static>,
+        write_info: &WriteInfo,
+        body: &str,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<WriteOutcome, Error> {
         // The time, in nanoseconds since the epoch, to assign to any points that don't
         // contain a timestamp
         let default_time = self.time_provider.now().timestamp_nanos();
-        let start_instant = Instant::now();
-
-        let mut converter = LinesConverter::new(default_time);
-        converter.set_timestamp_base(write_info.precision.timestamp_base());
-        let (batches, stats) = match converter.write_lp(body).and_then(|_| converter.finish()) {
-            Ok(v) => v,
-            Err(mutable_batch_lp::Error::EmptyPayload) => {
-                debug!("nothing to write");
-                return Ok(WriteSummary::default());
-            }
-            Err(e) => return Err(Error::ParseLineProtocol(e)),
-        };
 
-        let num_tables = batches.len();
-        let duration = start_instant.elapsed();
-        self.http_line_protocol_parse_duration.record(duration);
-        debug!(
-            num_lines=stats.num_lines,
-            num_fields=stats.num_fields,
-            num_tables,
-            precision=?write_info.precision,
-            body_size=body.len(),
-            %namespace,
-            org=%write_info.org,
-            bucket=%write_info.bucket,
-            duration=?duration,
-            "routing write",
-        );
+        // The cached schema of the namespace, if any, seeding the column types
+        // the lines of a partial write are checked against.
+        let schema = self
+            .namespace_schemas
+            .as_ref()
+            .filter(|_| write_info.partial_writes)
+            .and_then(|schemas| schemas.lookup(namespace));
+
+        // The column types learned from the schema conflict of the first
+        // attempt, should the cached schema be missing or stale. The write is
+        // retried at most once.
+        let mut learned: Option<Vec<ColumnConflict>> = None;
+
+        loop {
+            let start_instant = Instant::now();
+
+            let mut converter = LinesConverter::new(default_time);
+            converter.set_timestamp_base(write_info.precision.timestamp_base());
+            for (table_name, table) in schema.iter().flat_map(|s| &s.tables) {
+                for (column_name, column) in &table.columns {
+                    converter.set_column_type(table_name, column_name, column.column_type.into());
+                }
+            }
+            for conflict in learned.iter().flatten() {
+                converter.set_column_type(
+                    &conflict.table,
+                    &conflict.column,
+                    InfluxColumnType::from(conflict.existing),
+                );
+            }
 
-        let summary = self
-            .dml_handler
-            .write(&namespace, batches, span_ctx)
-            .await
-            .map_err(Into::into)?;
+            let mut rejected_lines = vec![];
+            let result = if write_info.partial_writes {
+                rejected_lines = converter
+                    .write_lp_partial(body)
+                    .into_iter()
+                    .map(RejectedLine::from)
+                    .collect();
+                converter.finish()
+            } else {
+                converter.write_lp(body).and_then(|_| converter.finish())
+            };
+            let (batches, stats) = match result {
+                Ok(v) => v,
+                Err(mutable_batch_lp::Error::EmptyPayload) => {
+                    debug!("nothing to write");
+                    self.record_rejected_lines(&rejected_lines);
+                    return Ok(WriteOutcome {
+                        rejected_lines,
+                        ..Default::default()
+                    });
+                }
+                Err(e) => return Err(Error::ParseLineProtocol(e)),
+            };
+
+            let num_tables = batches.len();
+            let duration = start_instant.elapsed();
+            self.http_line_protocol_parse_duration.record(duration);
+            debug!(
+                num_lines=stats.num_lines,
+                num_fields=stats.num_fields,
+                num_tables,
+                num_rejected_lines=rejected_lines.len(),
+                precision=?write_info.precision,
+                body_size=body.len(),
+                %namespace,
+                org=%write_info.org,
+                bucket=%write_info.bucket,
+                duration=?duration,
+                "routing write",
+            );
 
-        self.write_metric_lines.inc(stats.num_lines as _);
-        self.write_metric_fields.inc(stats.num_fields as _);
-        self.write_metric_tables.inc(num_tables as _);
-        self.write_metric_body_size.inc(body.len() as _);
+            let result: Result<WriteSummary, DmlError> = self
+                .dml_handler
+                .write(namespace, batches, span_ctx.clone())
+                .await
+                .map_err(Into::into);
+
+            match result {
+                Ok(summary) => {
+                    self.write_metric_lines.inc(stats.num_lines as _);
+                    self.write_metric_fields.inc(stats.num_fields as _);
+                    self.write_metric_tables.inc(num_tables as _);
+                    self.write_metric_body_size.inc(body.len() as _);
+                    self.record_rejected_lines(&rejected_lines);
+
+                    return Ok(WriteOutcome {
+                        summary,
+                        accepted_lines: stats.num_lines,
+                        rejected_lines,
+                    });
+                }
+                Err(DmlError::Schema(SchemaError::Conflict(e)))
+                    if write_info.partial_writes && learned.is_none() =>
+                {
+                    // Retry the write once, rejecting the lines that conflict
+                    // with the namespace schema in any of the columns.
+                    if e.columns().is_empty() {
+                        return Err(DmlError::Schema(SchemaError::Conflict(e)).into());
+                    }
+                    for conflict in e.columns() {
+                        debug!(
+                            table=%conflict.table,
+                            column=%conflict.column,
+                            column_type=%conflict.existing,
+                            %namespace,
+                            "rejecting lines conflicting with the namespace schema"
+                        );
+                    }
+                    learned = Some(e.columns().to_vec());
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
 
-        Ok(summary)
+    fn record_rejected_lines(&self, rejected_lines: &[RejectedLine]) {
+        for rejected in rejected_lines {
+            self.write_metric_rejected_lines
+                .recorder(&[("reason", rejected.reason)])
+                .inc(1);
+        }
     }
 
     async fn delete_handler(&self, req: Request<Body>) -> Result<WriteSummary, Error> {
@@ -409,6 +782,13 @@ where
 
         trace!(org=%account.org, bucket=%account.bucket, %namespace, "processing delete request");
 
+        if let Some(authz) = &self.authz {
+            let token = authz::token_from_http_request(&req)?;
+            authz
+                .authorize(token.as_ref(), &namespace, Permission::Write)
+                .await?;
+        }
+
         // Read the HTTP body and convert it to a str.
         let body = self.read_body(req).await?;
         let body = std::str::from_utf8(&body).map_err(Error::NonUtf8Body)?;
@@ -433,7 +813,8 @@ where
             "routing delete"
         );
 
-        self.dml_handler
+        let summary = self
+            .dml_handler
             .delete(
                 &namespace,
                 parsed_delete.table_name.as_str(),
@@ -445,9 +826,7 @@ where
 
         self.delete_metric_body_size.inc(body.len() as _);
 
-        // TODO pass back write summaries for deletes as well
-        // https://github.com/influxdata/influxdb_iox/issues/4209
-        Ok(WriteSummary::default())
+        Ok(summary)
     }
 
     /// Parse the request's body into raw bytes, applying the configured size
@@ -510,9 +889,13 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::{io::Write, iter, sync::Arc, time::Duration};
+    use std::{io::Write, iter, ops::DerefMut, sync::Arc, time::Duration};
 
     use assert_matches::assert_matches;
+    use data_types::{
+        ColumnId, ColumnSchema, ColumnType, NamespaceId, NamespaceSchema, QueryPoolId, TableId,
+        TableSchema, TopicId,
+    };
 
     use flate2::{write::GzEncoder, Compression};
     use hyper::header::HeaderValue;
@@ -522,7 +905,13 @@ mod tests {
     use test_helpers::timeout::FutureTimeout;
     use tokio_stream::wrappers::ReceiverStream;
 
-    use crate::dml_handlers::mock::{MockDmlHandler, MockDmlHandlerCall};
+    use crate::{
+        dml_handlers::{
+            mock::{MockDmlHandler, MockDmlHandlerCall},
+            RateLimitError, SchemaConflict,
+        },
+        namespace_cache::{MemoryNamespaceCache, NamespaceCache},
+    };
 
     use super::*;
 
@@ -882,6 +1271,32 @@ mod tests {
         }
     );
 
+    test_write_handler!(
+        rate_limited,
+        query_string = "?org=bananas&bucket=test",
+        body = "platanos,tag1=A,tag2=B val=42i 123456".as_bytes(),
+        dml_handler = [Err(DmlError::RateLimited(RateLimitError::Throttled {
+            namespace: "bananas_test".to_string(),
+            retry_after: Duration::from_millis(1500),
+        }))],
+        want_result = Err(Error::DmlHandler(DmlError::RateLimited(_))),
+        want_dml_calls = [MockDmlHandlerCall::Write{namespace, ..}] => {
+            assert_eq!(namespace, "bananas_test");
+        }
+    );
+
+    #[test]
+    fn test_rate_limited_error() {
+        let err = Error::DmlHandler(DmlError::RateLimited(RateLimitError::Throttled {
+            namespace: "bananas_test".to_string(),
+            retry_after: Duration::from_millis(1500),
+        }));
+        assert_eq!(err.as_status_code(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(err.retry_after(), Some(Duration::from_millis(1500)));
+
+        assert_eq!(Error::RequestLimit.retry_after(), None);
+    }
+
     test_write_handler!(
         dml_handler_error,
         query_string = "?org=bananas&bucket=test",
@@ -923,7 +1338,7 @@ mod tests {
         ok,
         query_string = "?org=bananas&bucket=test",
         body = r#"{"start":"2021-04-01T14:00:00Z","stop":"2021-04-02T14:00:00Z", "predicate":"_measurement=its_a_table and location=Boston"}"#.as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(summary())],
         want_result = Ok(_),
         want_dml_calls = [MockDmlHandlerCall::Delete{namespace, table, predicate}] => {
             assert_eq!(table, "its_a_table");
@@ -945,7 +1360,7 @@ mod tests {
         no_query_params,
         query_string = "",
         body = "".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(summary())],
         want_result = Err(Error::InvalidOrgBucket(OrgBucketError::NotSpecified)),
         want_dml_calls = [] // None
     );
@@ -954,7 +1369,7 @@ mod tests {
         no_org_bucket,
         query_string = "?",
         body = "".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(summary())],
         want_result = Err(Error::InvalidOrgBucket(OrgBucketError::DecodeFail(_))),
         want_dml_calls = [] // None
     );
@@ -963,7 +1378,7 @@ mod tests {
         empty_org_bucket,
         query_string = "?org=&bucket=",
         body = "".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(summary())],
         want_result = Err(Error::InvalidOrgBucket(OrgBucketError::NotSpecified)),
         want_dml_calls = [] // None
     );
@@ -972,7 +1387,7 @@ mod tests {
         invalid_org_bucket,
         query_string = format!("?org=test&bucket={}", "A".repeat(1000)),
         body = "".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(summary())],
         want_result = Err(Error::InvalidOrgBucket(OrgBucketError::MappingFail(_))),
         want_dml_calls = [] // None
     );
@@ -981,7 +1396,7 @@ mod tests {
         non_utf8_body,
         query_string = "?org=bananas&bucket=test",
         body = vec![0xc3, 0x28],
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(summary())],
         want_result = Err(Error::NonUtf8Body(_)),
         want_dml_calls = [] // None
     );
@@ -1262,4 +1677,470 @@ mod tests {
         // And the request rejected metric must remain unchanged
         assert_metric_hit(&*metrics, "http_request_limit_rejected", Some(1));
     }
+
+    /// Grants write access to the `bananas_test` namespace to the token
+    /// `bananas`.
+    #[derive(Debug)]
+    struct MockAuthorizer;
+
+    #[async_trait::async_trait]
+    impl Authorizer for MockAuthorizer {
+        async fn authorize(
+            &self,
+            token: Option<&authz::Token>,
+            namespace: &str,
+            permission: Permission,
+        ) -> Result<(), authz::Error> {
+            match token.map(|t| t.as_bytes()) {
+                None => Err(authz::Error::NoToken),
+                Some(b"bananas") if namespace == "bananas_test" => Ok(()),
+                Some(b"bananas") => Err(authz::Error::Forbidden {
+                    namespace: namespace.to_string(),
+                    permission,
+                }),
+                Some(_) => Err(authz::Error::InvalidToken),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_authorization() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(summary())]));
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(MAX_BYTES, 100, Arc::clone(&dml_handler), &metrics)
+            .with_authorizer(Arc::new(MockAuthorizer));
+
+        let request = |bucket: &str, path: &str, authorization: Option<&str>| {
+            let mut builder = Request::builder()
+                .uri(format!(
+                    "https://bananas.example/api/v2/{}?org=bananas&bucket={}",
+                    path, bucket
+                ))
+                .method("POST");
+            if let Some(authorization) = authorization {
+                builder = builder.header("authorization", authorization);
+            }
+            builder
+                .body(Body::from("platanos,tag1=A val=42i 123456"))
+                .unwrap()
+        };
+
+        let cases = [
+            ("test", "write", None, StatusCode::UNAUTHORIZED),
+            (
+                "test",
+                "write",
+                Some("Basic YmFuYW5hcw=="),
+                StatusCode::UNAUTHORIZED,
+            ),
+            (
+                "test",
+                "write",
+                Some("Token platanos"),
+                StatusCode::UNAUTHORIZED,
+            ),
+            (
+                "other",
+                "write",
+                Some("Token bananas"),
+                StatusCode::FORBIDDEN,
+            ),
+            (
+                "other",
+                "delete",
+                Some("Bearer bananas"),
+                StatusCode::FORBIDDEN,
+            ),
+        ];
+        for (bucket, path, authorization, want) in cases {
+            let err = delegate
+                .route(request(bucket, path, authorization))
+                .await
+                .expect_err("request should be rejected");
+            assert_matches!(err, Error::Authz(_));
+            assert_eq!(err.as_status_code(), want, "{} {:?}", bucket, authorization);
+        }
+        assert!(dml_handler.calls().is_empty());
+
+        delegate
+            .route(request("test", "write", Some("Token bananas")))
+            .await
+            .expect("authorized write should succeed");
+        assert_matches!(
+            dml_handler.calls().as_slice(),
+            [MockDmlHandlerCall::Write { namespace, .. }] => {
+                assert_eq!(namespace, "bananas_test");
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_partial_write() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(summary())]));
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(MAX_BYTES, 100, Arc::clone(&dml_handler), &metrics);
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test&partial_writes=true")
+            .method("POST")
+            .body(Body::from(
+                "platanos,tag1=A val=42i 123456\n\
+                 platanos val=\n\
+                 bananas,tag1=A val=1i 123457\n\
+                 platanos,tag1=B val=\"str\" 123458",
+            ))
+            .unwrap();
+
+        let response = delegate.route(request).await.expect("partial write failed");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(response.headers().contains_key(WRITE_TOKEN_HTTP_HEADER));
+
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], "invalid");
+        assert_eq!(body["accepted_lines"], 2);
+        let rejected: Vec<_> = body["rejected_lines"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| (v["line"].as_u64().unwrap(), v["reason"].as_str().unwrap()))
+            .collect();
+        assert_eq!(rejected, [(2, "parse_error"), (4, "invalid_line")]);
+
+        assert_matches!(
+            dml_handler.calls().as_slice(),
+            [MockDmlHandlerCall::Write { write_input, .. }] => {
+                assert_eq!(write_input.len(), 2);
+                assert_eq!(write_input["platanos"].rows(), 1);
+            }
+        );
+
+        let rejected_lines = |reason: &'static str| {
+            metrics
+                .get_instrument::<Metric<U64Counter>>("http_write_rejected_lines_total")
+                .expect("failed to read metric")
+                .get_observer(&Attributes::from(&[("reason", reason)]))
+                .expect("failed to get observer")
+                .fetch()
+        };
+        assert_eq!(rejected_lines("parse_error"), 1);
+        assert_eq!(rejected_lines("invalid_line"), 1);
+        assert_metric_hit(&metrics, "http_write_lines_total", Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_partial_write_all_lines_rejected() {
+        let dml_handler = Arc::new(MockDmlHandler::default());
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(MAX_BYTES, 100, Arc::clone(&dml_handler), &metrics);
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test&partial_writes=true")
+            .method("POST")
+            .body(Body::from("platanos val="))
+            .unwrap();
+
+        let response = delegate.route(request).await.expect("partial write failed");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["accepted_lines"], 0);
+        assert_eq!(body["rejected_lines"].as_array().unwrap().len(), 1);
+
+        // Nothing is written.
+        assert!(dml_handler.calls().is_empty());
+    }
+
+    /// Returns a namespace schema holding the table `platanos` with the
+    /// column `val` of type `column_type`.
+    fn platanos_schema(column_type: ColumnType) -> NamespaceSchema {
+        let mut table = TableSchema::new(TableId::new(1));
+        for (id, (name, column_type)) in [("time", ColumnType::Time), ("val", column_type)]
+            .into_iter()
+            .enumerate()
+        {
+            table.columns.insert(
+                name.to_string(),
+                ColumnSchema {
+                    id: ColumnId::new(id as _),
+                    column_type,
+                },
+            );
+        }
+
+        let mut schema =
+            NamespaceSchema::new(NamespaceId::new(1), TopicId::new(2), QueryPoolId::new(3));
+        schema.tables.insert("platanos".to_string(), table);
+        schema
+    }
+
+    /// Returns the schema conflict of writing `lp` to a namespace where the
+    /// column `platanos.val` is of type `column_type`, reporting the
+    /// conflicting `columns` of `platanos`.
+    async fn schema_conflict(column_type: ColumnType, lp: &str, columns: &[&str]) -> DmlError {
+        let catalog = iox_catalog::mem::MemCatalog::new(Default::default());
+        let mut repos = catalog.repositories().await;
+        let batches = mutable_batch_lp::lines_to_batches(lp, 0).unwrap();
+
+        let err = iox_catalog::validate_or_insert_schema(
+            batches.iter().map(|(k, v)| (k.as_str(), v)),
+            &platanos_schema(column_type),
+            repos.deref_mut(),
+        )
+        .await
+        .expect_err("write should conflict");
+        let columns = columns
+            .iter()
+            .map(|column| ColumnConflict {
+                table: "platanos".to_string(),
+                column: column.to_string(),
+                existing: column_type,
+            })
+            .collect();
+        DmlError::Schema(SchemaError::Conflict(SchemaConflict::new(err, columns)))
+    }
+
+    #[tokio::test]
+    async fn test_partial_write_cached_schema() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(summary())]));
+        let metrics = Arc::new(metric::Registry::default());
+        let ns_cache = Arc::new(MemoryNamespaceCache::default());
+        ns_cache.put_schema(
+            DatabaseName::try_from("bananas_test").unwrap(),
+            platanos_schema(ColumnType::I64),
+        );
+        let delegate = HttpDelegate::new(MAX_BYTES, 100, Arc::clone(&dml_handler), &metrics)
+            .with_namespace_schemas(Arc::new(ns_cache));
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test&partial_writes=true")
+            .method("POST")
+            .body(Body::from(
+                "platanos,tag1=A val=\"str\" 123456\n\
+                 bananas,tag1=A val=1i 123457",
+            ))
+            .unwrap();
+
+        let response = delegate.route(request).await.expect("partial write failed");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["accepted_lines"], 1);
+        assert_eq!(body["rejected_lines"][0]["line"], 1);
+        assert_eq!(body["rejected_lines"][0]["reason"], "schema_conflict");
+
+        // The conflicting line is rejected while parsing, without a retry.
+        assert_matches!(
+            dml_handler.calls().as_slice(),
+            [MockDmlHandlerCall::Write { write_input, .. }] => {
+                assert_eq!(write_input.len(), 1);
+                assert!(write_input.contains_key("bananas"));
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_partial_write_schema_conflict_retried_once() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([
+            Err(schema_conflict(ColumnType::String, "platanos val=42i", &["val"]).await),
+            Err(schema_conflict(ColumnType::String, "platanos val=42i", &["val"]).await),
+            Ok(summary()),
+        ]));
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(MAX_BYTES, 100, Arc::clone(&dml_handler), &metrics);
+
+        let request = || {
+            Request::builder()
+                .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test&partial_writes=true")
+                .method("POST")
+                .body(Body::from(
+                    "platanos,tag1=A val=42i 123456\n\
+                     bananas,tag1=A val=1i 123457",
+                ))
+                .unwrap()
+        };
+
+        // The first conflict is retried without the conflicting line, and a
+        // second conflict (of a concurrently changed schema) fails the write.
+        let err = delegate
+            .route(request())
+            .await
+            .expect_err("write should fail");
+        assert_matches!(
+            err,
+            Error::DmlHandler(DmlError::Schema(SchemaError::Conflict(_)))
+        );
+        assert_matches!(
+            dml_handler.calls().as_slice(),
+            [
+                MockDmlHandlerCall::Write { write_input: first, .. },
+                MockDmlHandlerCall::Write { write_input: retry, .. },
+            ] => {
+                assert_eq!(first.len(), 2);
+                assert_eq!(retry.len(), 1);
+                assert!(retry.contains_key("bananas"));
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_partial_write_schema_conflict_all_columns() {
+        let dml_handler = Arc::new(
+            MockDmlHandler::default().with_write_return([
+                Err(schema_conflict(
+                    ColumnType::String,
+                    "platanos val=42i,other=1i",
+                    &["other", "val"],
+                )
+                .await),
+                Ok(summary()),
+            ]),
+        );
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(MAX_BYTES, 100, Arc::clone(&dml_handler), &metrics);
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test&partial_writes=true")
+            .method("POST")
+            .body(Body::from(
+                "platanos,tag1=A val=42i 123456\n\
+                 platanos,tag1=B other=1i 123456\n\
+                 bananas,tag1=A val=1i 123457",
+            ))
+            .unwrap();
+
+        // A single retry rejects the lines conflicting in either column.
+        let response = delegate.route(request).await.expect("partial write failed");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["accepted_lines"], 1);
+        assert_eq!(body["rejected_lines"][0]["line"], 1);
+        assert_eq!(body["rejected_lines"][1]["line"], 2);
+
+        assert_matches!(
+            dml_handler.calls().as_slice(),
+            [
+                MockDmlHandlerCall::Write { write_input: first, .. },
+                MockDmlHandlerCall::Write { write_input: retry, .. },
+            ] => {
+                assert_eq!(first.len(), 2);
+                assert_eq!(retry.len(), 1);
+                assert!(retry.contains_key("bananas"));
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_write() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([
+            Ok(summary()),
+            Ok(summary()),
+            Err(DmlError::Internal("💣".into())),
+            Ok(summary()),
+        ]));
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(MAX_BYTES, 100, Arc::clone(&dml_handler), &metrics)
+            .with_idempotency_cache(IdempotencyCache::new(
+                Duration::from_secs(60),
+                10,
+                Arc::new(iox_time::MockProvider::new(
+                    iox_time::Time::from_timestamp_nanos(0),
+                )),
+                Arc::clone(&metrics),
+            ));
+
+        let request = |bucket: &str, key: &str, body: &'static str| {
+            Request::builder()
+                .uri(format!(
+                    "https://bananas.example/api/v2/write?org=bananas&bucket={}",
+                    bucket
+                ))
+                .method("POST")
+                .header(IDEMPOTENCY_KEY_HTTP_HEADER, key)
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let response = delegate
+            .route(request("test", "retry-me", "platanos,tag1=A val=42i"))
+            .await
+            .expect("write should succeed");
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let token = response.headers()[WRITE_TOKEN_HTTP_HEADER].clone();
+
+        // The retry is answered with the original write token, without
+        // writing again.
+        let response = delegate
+            .route(request("test", "retry-me", "platanos,tag1=A val=42i"))
+            .await
+            .expect("retry should succeed");
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(response.headers()[WRITE_TOKEN_HTTP_HEADER], token);
+        assert_eq!(dml_handler.calls().len(), 1);
+        assert_metric_hit(&metrics, "http_write_idempotent_retries_total", Some(1));
+
+        // Reusing the key for another write is an error.
+        let err = delegate
+            .route(request("test", "retry-me", "platanos,tag1=B val=42i"))
+            .await
+            .expect_err("reused key should fail");
+        assert_matches!(err, Error::IdempotencyKeyReused(key) => {
+            assert_eq!(key, "retry-me");
+        });
+        assert_eq!(err.as_status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        // So is reusing it for the same body with other write parameters.
+        let err = delegate
+            .route(
+                Request::builder()
+                    .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test&precision=s")
+                    .method("POST")
+                    .header(IDEMPOTENCY_KEY_HTTP_HEADER, "retry-me")
+                    .body(Body::from("platanos,tag1=A val=42i"))
+                    .unwrap(),
+            )
+            .await
+            .expect_err("reused key should fail");
+        assert_matches!(err, Error::IdempotencyKeyReused(_));
+
+        // Keys are scoped to the namespace.
+        delegate
+            .route(request("other", "retry-me", "platanos,tag1=A val=42i"))
+            .await
+            .expect("write should succeed");
+        assert_matches!(
+            dml_handler.calls().as_slice(),
+            [
+                MockDmlHandlerCall::Write { namespace: first, .. },
+                MockDmlHandlerCall::Write { namespace: second, .. },
+            ] => {
+                assert_eq!(first, "bananas_test");
+                assert_eq!(second, "bananas_other");
+            }
+        );
+
+        // A failed write releases its key, so that it can be retried.
+        delegate
+            .route(request("test", "fail-once", "platanos val=1i"))
+            .await
+            .expect_err("write should fail");
+        delegate
+            .route(request("test", "fail-once", "platanos val=1i"))
+            .await
+            .expect("retry should succeed");
+        assert_eq!(dml_handler.calls().len(), 4);
+
+        let err = delegate
+            .route(request(
+                "test",
+                &"k".repeat(MAX_IDEMPOTENCY_KEY_LEN + 1),
+                "platanos val=1i",
+            ))
+            .await
+            .expect_err("oversized key should fail");
+        assert_matches!(err, Error::InvalidIdempotencyKey(_));
+        assert_eq!(err.as_status_code(), StatusCode::BAD_REQUEST);
+    }
 }
@@ -0,0 +1,363 @@
+//! A cache of the outcome of recent writes, keyed by the idempotency key the
+//! client supplied with the write.
+
+use super::WriteOutcome;
+use cache_system::{
+    backend::{
+        policy::{
+            lru::{LruPolicy, ResourcePool},
+            ttl::{TtlPolicy, TtlProvider},
+            PolicyBackend,
+        },
+        CacheBackend,
+    },
+    resource_consumption::{FunctionEstimator, Resource},
+};
+use iox_time::TimeProvider;
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    ops::{Add, Sub},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// The HTTP header carrying the idempotency key of a write request.
+pub const IDEMPOTENCY_KEY_HTTP_HEADER: &str = "Idempotency-Key";
+
+/// The maximum length of an idempotency key, in bytes.
+pub const MAX_IDEMPOTENCY_KEY_LEN: usize = 256;
+
+const CACHE_ID: &str = "idempotency_key";
+
+/// A cache key, as (namespace, idempotency key).
+type Key = (String, String);
+
+/// The outcome of a write, along with a hash of the request body that
+/// produced it.
+#[derive(Debug, Clone)]
+pub(super) struct CachedWrite {
+    pub(super) body_hash: u64,
+    pub(super) outcome: WriteOutcome,
+}
+
+/// A cached write, or the reservation of its key by a write still in flight.
+#[derive(Debug, Clone)]
+enum Entry {
+    InFlight { body_hash: u64, reservation_id: u64 },
+    Done(CachedWrite),
+}
+
+/// The state of an idempotency key, as returned by
+/// [`IdempotencyCache::reserve()`].
+#[derive(Debug)]
+pub(super) enum KeyState<'a> {
+    /// The key was not used recently, and is now reserved for the write.
+    Reserved(Reservation<'a>),
+    /// A write with the key is still in flight.
+    InFlight { body_hash: u64 },
+    /// A write with the key completed recently.
+    Done(CachedWrite),
+}
+
+/// The reservation of an idempotency key by an in-flight write.
+///
+/// Dropping the reservation without [completing](Self::complete) it releases
+/// the key, so that a failed write can be retried.
+///
+/// The reserved entry may be evicted while the write is in flight, and the key
+/// reserved again by a later write, so the reservation carries an ID that is
+/// unique within its cache to tell its own entry apart.
+#[derive(Debug)]
+pub(super) struct Reservation<'a> {
+    cache: &'a IdempotencyCache,
+    key: Option<Key>,
+    body_hash: u64,
+    id: u64,
+}
+
+impl<'a> Reservation<'a> {
+    /// Cache the outcome of the write.
+    pub(super) fn complete(mut self, outcome: WriteOutcome) {
+        let key = self.key.take().expect("reservation completed once");
+        self.cache.backend.lock().set(
+            key,
+            Entry::Done(CachedWrite {
+                body_hash: self.body_hash,
+                outcome,
+            }),
+        );
+    }
+}
+
+impl<'a> Drop for Reservation<'a> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            let mut backend = self.cache.backend.lock();
+            if matches!(
+                backend.get(&key),
+                Some(Entry::InFlight { reservation_id, .. }) if reservation_id == self.id
+            ) {
+                backend.remove(&key);
+            }
+        }
+    }
+}
+
+/// The number of entries of the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd)]
+struct Entries(usize);
+
+impl Resource for Entries {
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn unit() -> &'static str {
+        "entries"
+    }
+}
+
+impl From<Entries> for u64 {
+    fn from(e: Entries) -> Self {
+        e.0 as Self
+    }
+}
+
+impl Add for Entries {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Entries {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+/// Expires all entries after the same duration.
+#[derive(Debug)]
+struct FixedTtlProvider(Duration);
+
+impl TtlProvider for FixedTtlProvider {
+    type K = Key;
+    type V = Entry;
+
+    fn expires_in(&self, _k: &Self::K, _v: &Self::V) -> Option<Duration> {
+        Some(self.0)
+    }
+}
+
+/// A bounded cache mapping the idempotency key of a recent write to a
+/// namespace to the outcome of that write.
+///
+/// Entries expire `ttl` after being cached, and the least recently used
+/// entries are evicted once the cache holds `max_entries` entries.
+#[derive(Debug)]
+pub struct IdempotencyCache {
+    backend: Mutex<PolicyBackend<Key, Entry>>,
+    next_reservation_id: AtomicU64,
+}
+
+impl IdempotencyCache {
+    /// Initialise a new, empty [`IdempotencyCache`].
+    pub fn new(
+        ttl: Duration,
+        max_entries: usize,
+        time_provider: Arc<dyn TimeProvider>,
+        metrics: Arc<metric::Registry>,
+    ) -> Self {
+        let mut backend = PolicyBackend::new(Box::new(HashMap::new()), time_provider);
+        backend.add_policy(TtlPolicy::new(
+            Arc::new(FixedTtlProvider(ttl)),
+            CACHE_ID,
+            &metrics,
+        ));
+        let pool = Arc::new(ResourcePool::new(CACHE_ID, Entries(max_entries), metrics));
+        backend.add_policy(LruPolicy::new(
+            pool,
+            CACHE_ID,
+            Arc::new(FunctionEstimator::new(|_k: &Key, _v: &Entry| Entries(1))),
+        ));
+
+        Self {
+            backend: Mutex::new(backend),
+            next_reservation_id: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the state of the idempotency key `key` of a write with the
+    /// body hash `body_hash` to `namespace`, reserving the key if it was not
+    /// used recently.
+    pub(super) fn reserve(&self, namespace: &str, key: &str, body_hash: u64) -> KeyState<'_> {
+        let key = (namespace.to_string(), key.to_string());
+
+        let mut backend = self.backend.lock();
+        match backend.get(&key) {
+            Some(Entry::InFlight { body_hash, .. }) => KeyState::InFlight { body_hash },
+            Some(Entry::Done(write)) => KeyState::Done(write),
+            None => {
+                let id = self.next_reservation_id.fetch_add(1, Ordering::Relaxed);
+                backend.set(
+                    key.clone(),
+                    Entry::InFlight {
+                        body_hash,
+                        reservation_id: id,
+                    },
+                );
+                KeyState::Reserved(Reservation {
+                    cache: self,
+                    key: Some(key),
+                    body_hash,
+                    id,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use iox_time::{MockProvider, Time};
+
+    fn insert(cache: &IdempotencyCache, namespace: &str, key: &str, body_hash: u64) {
+        match cache.reserve(namespace, key, body_hash) {
+            KeyState::Reserved(reservation) => reservation.complete(WriteOutcome::default()),
+            state => panic!("key {} not reserved: {:?}", key, state),
+        }
+    }
+
+    fn body_hash(cache: &IdempotencyCache, namespace: &str, key: &str) -> Option<u64> {
+        match cache.reserve(namespace, key, 0) {
+            KeyState::Done(write) => Some(write.body_hash),
+            KeyState::InFlight { body_hash } => panic!("write {} in flight", body_hash),
+            KeyState::Reserved(_) => None,
+        }
+    }
+
+    #[test]
+    fn test_ttl() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let cache = IdempotencyCache::new(
+            Duration::from_secs(10),
+            100,
+            Arc::clone(&time_provider) as _,
+            Default::default(),
+        );
+
+        insert(&cache, "bananas", "key", 1);
+        assert_eq!(body_hash(&cache, "bananas", "key"), Some(1));
+
+        // Keys are scoped to the namespace.
+        assert_eq!(body_hash(&cache, "platanos", "key"), None);
+        assert_eq!(body_hash(&cache, "bananas", "other"), None);
+
+        time_provider.inc(Duration::from_secs(9));
+        assert_eq!(body_hash(&cache, "bananas", "key"), Some(1));
+
+        time_provider.inc(Duration::from_secs(1));
+        assert_eq!(body_hash(&cache, "bananas", "key"), None);
+    }
+
+    #[test]
+    fn test_bounded() {
+        let cache = IdempotencyCache::new(
+            Duration::from_secs(10),
+            2,
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))),
+            Default::default(),
+        );
+
+        insert(&cache, "bananas", "a", 1);
+        insert(&cache, "bananas", "b", 2);
+        // Use "a", leaving "b" as the least recently used entry.
+        assert_eq!(body_hash(&cache, "bananas", "a"), Some(1));
+        insert(&cache, "bananas", "c", 3);
+
+        assert_eq!(body_hash(&cache, "bananas", "a"), Some(1));
+        assert_eq!(body_hash(&cache, "bananas", "b"), None);
+        assert_eq!(body_hash(&cache, "bananas", "c"), Some(3));
+    }
+
+    #[test]
+    fn test_reservation() {
+        let cache = IdempotencyCache::new(
+            Duration::from_secs(10),
+            100,
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))),
+            Default::default(),
+        );
+
+        let reservation = match cache.reserve("bananas", "key", 1) {
+            KeyState::Reserved(reservation) => reservation,
+            state => panic!("key not reserved: {:?}", state),
+        };
+        assert_matches!(
+            cache.reserve("bananas", "key", 2),
+            KeyState::InFlight { body_hash: 1 }
+        );
+
+        // Dropping the reservation of a failed write releases the key.
+        drop(reservation);
+        assert_matches!(cache.reserve("bananas", "key", 2), KeyState::Reserved(r) => {
+            r.complete(WriteOutcome::default());
+        });
+        assert_matches!(
+            cache.reserve("bananas", "key", 1),
+            KeyState::Done(CachedWrite { body_hash: 2, .. })
+        );
+    }
+
+    #[test]
+    fn test_reservation_evicted() {
+        let cache = IdempotencyCache::new(
+            Duration::from_secs(10),
+            1,
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))),
+            Default::default(),
+        );
+
+        let reservation = match cache.reserve("bananas", "key", 1) {
+            KeyState::Reserved(reservation) => reservation,
+            state => panic!("key not reserved: {:?}", state),
+        };
+
+        // Evict the reservation, and let a later write reserve the key.
+        insert(&cache, "bananas", "other", 3);
+        let later = match cache.reserve("bananas", "key", 2) {
+            KeyState::Reserved(reservation) => reservation,
+            state => panic!("key not reserved: {:?}", state),
+        };
+
+        // Dropping the evicted reservation leaves the later one in place.
+        drop(reservation);
+        assert_matches!(
+            cache.reserve("bananas", "key", 1),
+            KeyState::InFlight { body_hash: 2 }
+        );
+
+        // Nor does it remove the outcome of the later write.
+        let reservation = Reservation {
+            cache: &cache,
+            key: Some(("bananas".to_string(), "key".to_string())),
+            body_hash: 1,
+            id: u64::MAX,
+        };
+        later.complete(WriteOutcome::default());
+        drop(reservation);
+        assert_matches!(
+            cache.reserve("bananas", "key", 1),
+            KeyState::Done(CachedWrite { body_hash: 2, .. })
+        );
+    }
+}
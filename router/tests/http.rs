@@ -288,6 +288,68 @@ async fn test_schema_conflict() {
     assert_eq!(err.as_status_code(), StatusCode::BAD_REQUEST);
 }
 
+#[tokio::test]
+async fn test_partial_write_schema_conflict() {
+    let ctx = TestContext::new();
+
+    let request = Request::builder()
+        .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test")
+        .method("POST")
+        .body(Body::from("platanos,tag1=A val=42i 123456"))
+        .expect("failed to construct HTTP request");
+    let response = ctx
+        .delegate()
+        .route(request)
+        .await
+        .expect("LP write request failed");
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    // The second line conflicts with the schema of the first write, and is
+    // rejected while the others are written.
+    let request = Request::builder()
+        .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test&partial_writes=true")
+        .method("POST")
+        .body(Body::from(
+            "platanos,tag1=A val=43i 123457\n\
+             platanos,tag1=B val=1.5 123458\n\
+             bananas count=2i 123459\n\
+             platanos,tag1=C val=44i 123460",
+        ))
+        .expect("failed to construct HTTP request");
+    let response = ctx
+        .delegate()
+        .route(request)
+        .await
+        .expect("LP write request failed");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(response.headers().contains_key("X-IOx-Write-Token"));
+
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["accepted_lines"], 3);
+    let rejected = body["rejected_lines"].as_array().unwrap();
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0]["line"], 2);
+    assert_eq!(rejected[0]["reason"], "schema_conflict");
+
+    let writes = ctx.write_buffer_state().get_messages(ShardIndex::new(0));
+    assert_eq!(writes.len(), 2);
+    assert_matches!(&writes[1], Ok(DmlOperation::Write(w)) => {
+        assert_eq!(w.table("platanos").unwrap().rows(), 2);
+        assert_eq!(w.table("bananas").unwrap().rows(), 1);
+    });
+
+    assert_eq!(
+        ctx.metrics()
+            .get_instrument::<Metric<U64Counter>>("http_write_rejected_lines_total")
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[("reason", "schema_conflict")]))
+            .expect("failed to get observer")
+            .fetch(),
+        1
+    );
+}
+
 #[tokio::test]
 async fn test_schema_limit() {
     let ctx = TestContext::new();
@@ -2,11 +2,15 @@
 
 pub mod planner;
 pub mod test_util;
+pub mod write_token;
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use async_trait::async_trait;
-use iox_query::{exec::ExecutionContextProvider, QueryDatabase};
+use iox_query::{
+    exec::ExecutionContextProvider, KillQueryError, QueryAdmission, QueryDatabase,
+    QueryQuotaExceeded, WriteWaitError,
+};
 use trace::span::Span;
 use tracker::InstrumentedAsyncOwnedSemaphorePermit;
 
@@ -22,6 +26,44 @@ pub trait QueryDatabaseProvider: std::fmt::Debug + Send + Sync + 'static {
     /// Get database if it exists.
     async fn db(&self, name: &str, span: Option<Span>) -> Option<Arc<Self::Db>>;
 
+    /// Admit a new query against the limits of the database `name`.
+    ///
+    /// Must be called before a [semaphore](Self::acquire_semaphore) permit is
+    /// acquired, so that rejected queries never wait for or take up one. The
+    /// query counts against the limits until the returned admission, attached
+    /// to its [`QueryCompletedToken`](iox_query::QueryCompletedToken), is
+    /// dropped.
+    async fn admit_query(
+        &self,
+        name: &str,
+        span: Option<Span>,
+    ) -> Result<QueryAdmission, QueryQuotaExceeded>;
+
     /// Acquire concurrency-limiting sempahore
     async fn acquire_semaphore(&self, span: Option<Span>) -> InstrumentedAsyncOwnedSemaphorePermit;
+
+    /// Wait up to `timeout` until the write identified by `write_token` is
+    /// readable by queries against the database `name`.
+    ///
+    /// Must be called before [`db`](Self::db) so that the returned database
+    /// covers the tables and columns created by the write.
+    async fn wait_for_write(
+        &self,
+        name: &str,
+        write_token: &str,
+        timeout: Duration,
+        span: Option<Span>,
+    ) -> Result<(), WriteWaitError>;
+
+    /// Cancel the running query `query_id` of the database `name`, as
+    /// reported by `system.queries`.
+    ///
+    /// Does not acquire a [semaphore](Self::acquire_semaphore) permit, so that
+    /// queries can be killed while all permits are in use.
+    async fn kill_query(
+        &self,
+        name: &str,
+        query_id: u64,
+        span: Option<Span>,
+    ) -> Result<(), KillQueryError>;
 }
@@ -1,7 +1,7 @@
 //! Query planner wrapper for use in IOx services
 use std::sync::Arc;
 
-use datafusion::physical_plan::ExecutionPlan;
+use datafusion::{physical_plan::ExecutionPlan, scalar::ScalarValue};
 use iox_query::{
     exec::IOxSessionContext,
     frontend::{influxrpc::InfluxRpcPlanner, sql::SqlQueryPlanner},
@@ -43,6 +43,22 @@ impl Planner {
             .await
     }
 
+    /// Plan a SQL query like [`sql`](Self::sql), binding its positional
+    /// placeholders `$1`, `$2`, ... to the literals of `params`.
+    pub async fn sql_with_params(
+        &self,
+        query: impl Into<String> + Send,
+        params: Vec<ScalarValue>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let planner = SqlQueryPlanner::new();
+        let query = query.into();
+        let ctx = self.ctx.child_ctx("planner sql");
+
+        self.ctx
+            .run(async move { planner.query_with_params(&query, &params, &ctx).await })
+            .await
+    }
+
     /// Creates a plan as described on
     /// [`InfluxRpcPlanner::table_names`], on a separate threadpool
     pub async fn table_names<D>(
@@ -1,7 +1,14 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 
 use async_trait::async_trait;
-use iox_query::{exec::Executor, test::TestDatabase};
+use iox_query::{
+    exec::Executor, test::TestDatabase, KillQueryError, QueryAdmission, QueryQuotaExceeded,
+    WriteWaitError,
+};
 use parking_lot::Mutex;
 use trace::span::Span;
 use tracker::{
@@ -16,6 +23,7 @@ pub struct TestDatabaseStore {
     executor: Arc<Executor>,
     pub metric_registry: Arc<metric::Registry>,
     pub query_semaphore: Arc<InstrumentedAsyncSemaphore>,
+    readable_write_tokens: Mutex<HashSet<String>>,
 }
 
 impl TestDatabaseStore {
@@ -34,6 +42,7 @@ impl TestDatabaseStore {
             executor: Arc::new(Executor::new(1)),
             metric_registry,
             query_semaphore: Arc::new(semaphore_metrics.new_semaphore(semaphore_size)),
+            readable_write_tokens: Default::default(),
         }
     }
 
@@ -48,6 +57,13 @@ impl TestDatabaseStore {
             new_db
         }
     }
+
+    /// Mark the write identified by `write_token` as readable.
+    pub fn set_write_readable(&self, write_token: &str) {
+        self.readable_write_tokens
+            .lock()
+            .insert(write_token.to_string());
+    }
 }
 
 impl Default for TestDatabaseStore {
@@ -67,10 +83,44 @@ impl QueryDatabaseProvider for TestDatabaseStore {
         databases.get(name).cloned()
     }
 
+    /// Databases have no query limits.
+    async fn admit_query(
+        &self,
+        _name: &str,
+        _span: Option<Span>,
+    ) -> Result<QueryAdmission, QueryQuotaExceeded> {
+        Ok(QueryAdmission::default())
+    }
+
     async fn acquire_semaphore(&self, span: Option<Span>) -> InstrumentedAsyncOwnedSemaphorePermit {
         Arc::clone(&self.query_semaphore)
             .acquire_owned(span)
             .await
             .unwrap()
     }
+
+    /// Returns immediately, timing out if the write was not marked readable.
+    async fn wait_for_write(
+        &self,
+        _name: &str,
+        write_token: &str,
+        timeout: Duration,
+        _span: Option<Span>,
+    ) -> Result<(), WriteWaitError> {
+        if self.readable_write_tokens.lock().contains(write_token) {
+            Ok(())
+        } else {
+            Err(WriteWaitError::Timeout { timeout })
+        }
+    }
+
+    /// No queries are tracked, so none can be killed.
+    async fn kill_query(
+        &self,
+        _name: &str,
+        query_id: u64,
+        _span: Option<Span>,
+    ) -> Result<(), KillQueryError> {
+        Err(KillQueryError::NotFound { query_id })
+    }
 }
@@ -0,0 +1,103 @@
+//! Read-your-writes consistency for queries carrying the write token the
+//! router returned for a write.
+
+use iox_query::WriteWaitError;
+use std::time::Duration;
+
+/// The gRPC metadata key carrying the write token of a query.
+pub const WRITE_TOKEN_GRPC_HEADER: &str = "x-iox-write-token";
+
+/// The gRPC metadata key carrying the timeout of a request.
+const GRPC_TIMEOUT_HEADER: &str = "grpc-timeout";
+
+/// How long a query carrying a write token waits for the write to become
+/// readable, unless the query has a shorter timeout.
+pub const DEFAULT_WRITE_TOKEN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Extract the write token of a gRPC request, if any.
+pub fn write_token_from_grpc_request<T>(
+    req: &tonic::Request<T>,
+) -> Result<Option<String>, WriteWaitError> {
+    req.metadata()
+        .get(WRITE_TOKEN_GRPC_HEADER)
+        .map(|v| {
+            v.to_str()
+                .map(ToString::to_string)
+                .map_err(|e| WriteWaitError::InvalidToken {
+                    reason: e.to_string(),
+                })
+        })
+        .transpose()
+}
+
+/// Returns how long the query of a gRPC request waits for its write token:
+/// the timeout of the request, if it has one, or
+/// [`DEFAULT_WRITE_TOKEN_TIMEOUT`].
+pub fn write_token_timeout_from_grpc_request<T>(req: &tonic::Request<T>) -> Duration {
+    req.metadata()
+        .get(GRPC_TIMEOUT_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_grpc_timeout)
+        .unwrap_or(DEFAULT_WRITE_TOKEN_TIMEOUT)
+}
+
+/// Parses a `grpc-timeout` header value, an integer followed by a unit.
+fn parse_grpc_timeout(s: &str) -> Option<Duration> {
+    if s.is_empty() || !s.is_char_boundary(s.len() - 1) {
+        return None;
+    }
+    let (value, unit) = s.split_at(s.len() - 1);
+    let value: u64 = value.parse().ok()?;
+
+    Some(match unit {
+        "H" => Duration::from_secs(value.saturating_mul(60 * 60)),
+        "M" => Duration::from_secs(value.saturating_mul(60)),
+        "S" => Duration::from_secs(value),
+        "m" => Duration::from_millis(value),
+        "u" => Duration::from_micros(value),
+        "n" => Duration::from_nanos(value),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_token_timeout() {
+        let mut req = tonic::Request::new(());
+        assert_eq!(
+            write_token_timeout_from_grpc_request(&req),
+            DEFAULT_WRITE_TOKEN_TIMEOUT
+        );
+
+        req.metadata_mut()
+            .insert(GRPC_TIMEOUT_HEADER, "1500m".parse().unwrap());
+        assert_eq!(
+            write_token_timeout_from_grpc_request(&req),
+            Duration::from_millis(1500)
+        );
+
+        req.metadata_mut()
+            .insert(GRPC_TIMEOUT_HEADER, "bananas".parse().unwrap());
+        assert_eq!(
+            write_token_timeout_from_grpc_request(&req),
+            DEFAULT_WRITE_TOKEN_TIMEOUT
+        );
+    }
+
+    #[test]
+    fn test_parse_grpc_timeout() {
+        assert_eq!(parse_grpc_timeout("2H"), Some(Duration::from_secs(7200)));
+        assert_eq!(parse_grpc_timeout("3M"), Some(Duration::from_secs(180)));
+        assert_eq!(parse_grpc_timeout("4S"), Some(Duration::from_secs(4)));
+        assert_eq!(parse_grpc_timeout("5u"), Some(Duration::from_micros(5)));
+        assert_eq!(parse_grpc_timeout("6n"), Some(Duration::from_nanos(6)));
+        assert_eq!(parse_grpc_timeout(""), None);
+        assert_eq!(parse_grpc_timeout("S"), None);
+        assert_eq!(parse_grpc_timeout("5x"), None);
+        assert_eq!(parse_grpc_timeout("-5S"), None);
+        assert_eq!(parse_grpc_timeout("5é"), None);
+    }
+}
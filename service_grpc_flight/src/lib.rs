@@ -1,28 +1,44 @@
 //! Implements the native gRPC IOx query API using Arrow Flight
 
-use arrow::error::ArrowError;
+mod params;
+
+use arrow::{datatypes::Schema, error::ArrowError};
 use arrow_flight::{
     flight_service_server::{FlightService as Flight, FlightServiceServer as FlightServer},
     Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
-    HandshakeRequest, HandshakeResponse, PutResult, SchemaAsIpc, SchemaResult, Ticket,
+    HandshakeRequest, HandshakeResponse, IpcMessage, PutResult, SchemaAsIpc, SchemaResult, Ticket,
 };
 use arrow_util::optimize::{optimize_record_batch, optimize_schema};
 use bytes::{Bytes, BytesMut};
 use data_types::{DatabaseName, DatabaseNameError};
 use datafusion::physical_plan::ExecutionPlan;
+use flatbuffers::FlatBufferBuilder;
 use futures::{SinkExt, Stream, StreamExt};
 use generated_types::influxdata::iox::querier::v1 as proto;
 use iox_query::{
-    exec::{ExecutionContextProvider, IOxSessionContext},
-    QueryCompletedToken, QueryDatabase,
+    exec::{ExecutionContextProvider, IOxSessionContext, ScanStats},
+    frontend::sql::parse_kill_query,
+    is_quota_exceeded, KillQueryError, QueryAborted, QueryCompletedToken, QueryDatabase,
+    QueryQuotaExceeded, WriteWaitError,
 };
+use ioxd_common::authz::{self, Authorizer, Permission};
 use observability_deps::tracing::{info, warn};
 use pin_project::{pin_project, pinned_drop};
 use prost::Message;
 use serde::Deserialize;
-use service_common::{planner::Planner, QueryDatabaseProvider};
+use service_common::{
+    planner::Planner,
+    write_token::{write_token_from_grpc_request, DEFAULT_WRITE_TOKEN_TIMEOUT},
+    QueryDatabaseProvider,
+};
 use snafu::{ResultExt, Snafu};
-use std::{fmt::Debug, pin::Pin, sync::Arc, task::Poll};
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    sync::Arc,
+    task::Poll,
+    time::{Duration, Instant},
+};
 use tokio::task::JoinHandle;
 use tonic::{Request, Response, Streaming};
 use trace::{ctx::SpanContext, span::SpanExt};
@@ -70,6 +86,27 @@ pub enum Error {
 
     #[snafu(display("Error during protobuf serialization: {}", source))]
     Serialization { source: prost::EncodeError },
+
+    #[snafu(display("Invalid query parameters: {}", source))]
+    InvalidParams { source: params::Error },
+
+    #[snafu(display("Unknown action: {}", action_type))]
+    UnknownAction { action_type: String },
+
+    #[snafu(display("Invalid action body. Error: {:?}", source))]
+    InvalidActionBody { source: prost::DecodeError },
+
+    #[snafu(display("Error killing query: {}", source))]
+    KillQuery { source: KillQueryError },
+
+    #[snafu(display("Query was aborted: {}", source))]
+    Aborted { source: QueryAborted },
+
+    #[snafu(display("Query was rejected: {}", source))]
+    Rejected { source: QueryQuotaExceeded },
+
+    #[snafu(display("Error waiting for write: {}", source))]
+    WaitForWrite { source: WriteWaitError },
 }
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -85,9 +122,16 @@ impl From<Error> for tonic::Status {
             | Error::InvalidTicket { .. }
             | Error::InvalidTicketLegacy { .. }
             | Error::InvalidQuery { .. }
+            | Error::InvalidParams { .. }
+            | Error::UnknownAction { .. }
+            | Error::InvalidActionBody { .. }
+            | Error::KillQuery { .. }
+            | Error::WaitForWrite { .. }
             // TODO(edd): this should be `debug`. Keeping at info whilst IOx still in early development
             | Error::InvalidDatabaseName { .. } => info!(?err, msg),
-            Error::Query { .. } => info!(?err, msg),
+            Error::Query { .. }
+            | Error::Aborted { .. }
+            | Error::Rejected { .. } => info!(?err, msg),
             Error::Optimize { .. }
             | Error::Planning { .. } | Error::Serialization { .. } => warn!(?err, msg),
         }
@@ -100,6 +144,9 @@ impl Error {
     /// status
     fn to_status(&self) -> tonic::Status {
         use tonic::Status;
+        if is_quota_exceeded(self) {
+            return Status::resource_exhausted(self.to_string());
+        }
         match &self {
             Self::InvalidTicket { .. } => Status::invalid_argument(self.to_string()),
             Self::InvalidTicketLegacy { .. } => Status::invalid_argument(self.to_string()),
@@ -113,6 +160,31 @@ impl Error {
             Self::Planning { .. } => Status::invalid_argument(self.to_string()),
             Self::Optimize { .. } => Status::internal(self.to_string()),
             Self::Serialization { .. } => Status::internal(self.to_string()),
+            Self::InvalidParams { .. } => Status::invalid_argument(self.to_string()),
+            Self::UnknownAction { .. } => Status::invalid_argument(self.to_string()),
+            Self::InvalidActionBody { .. } => Status::invalid_argument(self.to_string()),
+            Self::KillQuery {
+                source: KillQueryError::NotFound { .. },
+            } => Status::not_found(self.to_string()),
+            Self::KillQuery {
+                source: KillQueryError::NotRunning { .. },
+            } => Status::failed_precondition(self.to_string()),
+            Self::Aborted {
+                source: QueryAborted::Cancelled,
+            } => Status::cancelled(self.to_string()),
+            Self::Aborted {
+                source: QueryAborted::Timeout { .. },
+            } => Status::deadline_exceeded(self.to_string()),
+            Self::Rejected { .. } => Status::resource_exhausted(self.to_string()),
+            Self::WaitForWrite {
+                source: WriteWaitError::InvalidToken { .. },
+            } => Status::invalid_argument(self.to_string()),
+            Self::WaitForWrite {
+                source: WriteWaitError::Timeout { .. },
+            } => Status::deadline_exceeded(self.to_string()),
+            Self::WaitForWrite {
+                source: WriteWaitError::Unavailable { .. },
+            } => Status::unavailable(self.to_string()),
         }
     }
 }
@@ -124,6 +196,19 @@ type TonicStream<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send
 struct ReadInfo {
     database_name: String,
     sql_query: String,
+
+    /// Values of the positional placeholders in `sql_query` (protobuf
+    /// tickets only).
+    #[serde(skip)]
+    params: Vec<proto::QueryParam>,
+
+    /// Maximum duration of the query, if any.
+    #[serde(skip)]
+    timeout: Option<Duration>,
+
+    /// Write token of a write the query must observe, if any.
+    #[serde(skip)]
+    write_token: Option<String>,
 }
 
 impl ReadInfo {
@@ -143,10 +228,17 @@ impl ReadInfo {
         Ok(Self {
             database_name: read_info.namespace_name,
             sql_query: read_info.sql_query,
+            params: read_info.params,
+            timeout: (read_info.timeout_ms > 0)
+                .then(|| Duration::from_millis(read_info.timeout_ms)),
+            write_token: (!read_info.write_token.is_empty()).then_some(read_info.write_token),
         })
     }
 }
 
+/// Name of the flight action that cancels a running query.
+const CANCEL_QUERY_ACTION: &str = "CancelQuery";
+
 /// Concrete implementation of the gRPC Arrow Flight Service API
 #[derive(Debug)]
 struct FlightService<S>
@@ -154,13 +246,40 @@ where
     S: QueryDatabaseProvider,
 {
     server: Arc<S>,
+    authz: Option<Arc<dyn Authorizer>>,
+}
+
+impl<S> FlightService<S>
+where
+    S: QueryDatabaseProvider,
+{
+    fn new(server: Arc<S>) -> Self {
+        Self {
+            server,
+            authz: None,
+        }
+    }
+
+    fn with_authorizer(mut self, authz: Arc<dyn Authorizer>) -> Self {
+        self.authz = Some(authz);
+        self
+    }
 }
 
-pub fn make_server<S>(server: Arc<S>) -> FlightServer<impl Flight>
+/// Create the Flight service, requiring queries to carry a token granting
+/// `authz` read access to the namespace they query if an authorizer is given.
+pub fn make_server<S>(
+    server: Arc<S>,
+    authz: Option<Arc<dyn Authorizer>>,
+) -> FlightServer<impl Flight>
 where
     S: QueryDatabaseProvider,
 {
-    FlightServer::new(FlightService { server })
+    let mut service = FlightService::new(server);
+    if let Some(authz) = authz {
+        service = service.with_authorizer(authz);
+    }
+    FlightServer::new(service)
 }
 
 #[tonic::async_trait]
@@ -189,16 +308,82 @@ where
     ) -> Result<Response<Self::DoGetStream>, tonic::Status> {
         let external_span_ctx: Option<RequestLogContext> = request.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = request.extensions().get().cloned();
+        let token = match self.authz {
+            Some(_) => authz::token_from_grpc_request(&request)?,
+            None => None,
+        };
+        let header_write_token =
+            write_token_from_grpc_request(&request).context(WaitForWriteSnafu)?;
         let ticket = request.into_inner();
 
         // decode ticket
-        let read_info = match ReadInfo::decode_protobuf(&ticket.ticket) {
+        let mut read_info = match ReadInfo::decode_protobuf(&ticket.ticket) {
             Ok(read_info) => read_info,
             Err(_) => {
                 // try legacy json
                 ReadInfo::decode_json(&ticket.ticket)?
             }
         };
+        read_info.write_token = read_info.write_token.or(header_write_token);
+
+        if let Some(authz) = &self.authz {
+            authz
+                .authorize(token.as_ref(), &read_info.database_name, Permission::Read)
+                .await?;
+        }
+
+        // Kill queries right away, without waiting for a permit: the query to
+        // kill may be holding one of them.
+        if let Some(query_id) = parse_kill_query(&read_info.sql_query).context(PlanningSnafu)? {
+            info!(
+                db_name=%read_info.database_name,
+                query_id,
+                trace=%external_span_ctx.format_jaeger(),
+                "flight kill query",
+            );
+            self.server
+                .kill_query(
+                    &read_info.database_name,
+                    query_id,
+                    span_ctx.child_span("kill query"),
+                )
+                .await
+                .context(KillQuerySnafu)?;
+
+            let schema_flight_data =
+                schema_flight_data(&Schema::empty(), &proto::AppMetadata::default())?;
+            let output = futures::stream::iter(std::iter::once(Ok(schema_flight_data)));
+            return Ok(Response::new(Box::pin(output) as Self::DoGetStream));
+        }
+
+        let start = Instant::now();
+        let params = params::to_scalar_values(&read_info.params).context(InvalidParamsSnafu)?;
+
+        // Wait for the write before acquiring a permit, so that waiting
+        // queries do not take up the query concurrency.
+        if let Some(write_token) = &read_info.write_token {
+            let timeout = match read_info.timeout {
+                Some(timeout) => timeout.saturating_sub(start.elapsed()),
+                None => DEFAULT_WRITE_TOKEN_TIMEOUT,
+            };
+            self.server
+                .wait_for_write(
+                    &read_info.database_name,
+                    write_token,
+                    timeout,
+                    span_ctx.child_span("wait for write"),
+                )
+                .await
+                .context(WaitForWriteSnafu)?;
+        }
+
+        // Admit the query before acquiring a permit, so that rejected queries
+        // neither wait for nor take up the query concurrency.
+        let admission = self
+            .server
+            .admit_query(&read_info.database_name, span_ctx.child_span("admit query"))
+            .await
+            .context(RejectedSnafu)?;
 
         let permit = self
             .server
@@ -207,6 +392,7 @@ where
         info!(
             db_name=%read_info.database_name,
             sql_query=%read_info.sql_query,
+            n_params=params.len(),
             trace=%external_span_ctx.format_jaeger(),
             "flight do_get",
         );
@@ -221,12 +407,21 @@ where
             .ok_or_else(|| tonic::Status::not_found(format!("Unknown namespace: {database}")))?;
 
         let ctx = db.new_query_context(span_ctx);
-        let query_completed_token =
-            db.record_query(&ctx, "sql", Box::new(read_info.sql_query.clone()));
+        let mut query_completed_token = db
+            .record_query(&ctx, "sql", Box::new(read_info.sql_query.clone()))
+            .with_admission(admission);
+        // The timeout of the request applies in addition to that of the
+        // namespace, both aborting the query through its cancellation.
+        if let Some(timeout) = read_info.timeout {
+            query_completed_token = query_completed_token.with_timeout_since(start, timeout);
+        }
 
-        let physical_plan = Planner::new(&ctx)
-            .sql(&read_info.sql_query)
+        let planner = Planner::new(&ctx);
+        let physical_plan = query_completed_token
+            .cancellation()
+            .run(planner.sql_with_params(&read_info.sql_query, params))
             .await
+            .context(AbortedSnafu)?
             .context(PlanningSnafu)?;
 
         let output = GetStream::new(
@@ -235,6 +430,7 @@ where
             read_info.database_name,
             query_completed_token,
             permit,
+            start,
         )
         .await?;
 
@@ -277,16 +473,55 @@ where
 
     async fn do_action(
         &self,
-        _request: Request<Action>,
+        request: Request<Action>,
     ) -> Result<Response<Self::DoActionStream>, tonic::Status> {
-        Err(tonic::Status::unimplemented("Not yet implemented"))
+        let token = match self.authz {
+            Some(_) => authz::token_from_grpc_request(&request)?,
+            None => None,
+        };
+        let action = request.into_inner();
+
+        match action.r#type.as_str() {
+            CANCEL_QUERY_ACTION => {
+                let request = proto::CancelQueryRequest::decode(Bytes::from(action.body))
+                    .context(InvalidActionBodySnafu)?;
+                if let Some(authz) = &self.authz {
+                    authz
+                        .authorize(token.as_ref(), &request.namespace_name, Permission::Read)
+                        .await?;
+                }
+                info!(
+                    db_name=%request.namespace_name,
+                    query_id=request.query_id,
+                    "flight cancel query",
+                );
+                self.server
+                    .kill_query(&request.namespace_name, request.query_id, None)
+                    .await
+                    .context(KillQuerySnafu)?;
+
+                let output = futures::stream::iter(std::iter::once(Ok(arrow_flight::Result {
+                    body: vec![],
+                })));
+                Ok(Response::new(Box::pin(output) as Self::DoActionStream))
+            }
+            other => Err(Error::UnknownAction {
+                action_type: other.to_string(),
+            }
+            .into()),
+        }
     }
 
     async fn list_actions(
         &self,
         _request: Request<Empty>,
     ) -> Result<Response<Self::ListActionsStream>, tonic::Status> {
-        Err(tonic::Status::unimplemented("Not yet implemented"))
+        let actions = vec![Ok(ActionType {
+            r#type: CANCEL_QUERY_ACTION.to_string(),
+            description: "Cancel a running query by its namespace and query ID".to_string(),
+        })];
+        let output = futures::stream::iter(actions);
+        Ok(Response::new(Box::pin(output) as Self::ListActionsStream))
     }
 
     async fn do_exchange(
@@ -303,8 +538,9 @@ struct GetStream {
     rx: futures::channel::mpsc::Receiver<Result<FlightData, tonic::Status>>,
     join_handle: JoinHandle<()>,
     done: bool,
+    /// Released once the stream is done.
     #[allow(dead_code)]
-    permit: InstrumentedAsyncOwnedSemaphorePermit,
+    permit: Option<InstrumentedAsyncOwnedSemaphorePermit>,
 }
 
 impl GetStream {
@@ -314,6 +550,7 @@ impl GetStream {
         database_name: String,
         mut query_completed_token: QueryCompletedToken,
         permit: InstrumentedAsyncOwnedSemaphorePermit,
+        start: Instant,
     ) -> Result<Self, tonic::Status> {
         // setup channel
         let (mut tx, rx) = futures::channel::mpsc::channel::<Result<FlightData, tonic::Status>>(1);
@@ -323,13 +560,11 @@ impl GetStream {
 
         // setup stream
         let options = arrow::ipc::writer::IpcWriteOptions::default();
-        let mut schema_flight_data: FlightData = SchemaAsIpc::new(&schema, &options).into();
-
-        // Add response metadata
-        let mut bytes = BytesMut::new();
-        let app_metadata = proto::AppMetadata {};
-        prost::Message::encode(&app_metadata, &mut bytes).context(SerializationSnafu)?;
-        schema_flight_data.app_metadata = bytes.to_vec();
+        let app_metadata = proto::AppMetadata {
+            query_id: query_completed_token.query_id().unwrap_or_default(),
+            ..Default::default()
+        };
+        let schema_flight_data = schema_flight_data(&schema, &app_metadata)?;
 
         let mut stream_record_batches = ctx
             .execute_stream(Arc::clone(&physical_plan))
@@ -339,15 +574,19 @@ impl GetStream {
                 database_name: &database_name,
             })?;
 
-        let join_handle = tokio::spawn(async move {
+        let cancellation = query_completed_token.cancellation();
+        let mut aborted_tx = tx.clone();
+        let execution = async move {
             if tx.send(Ok(schema_flight_data)).await.is_err() {
                 // receiver gone
                 return;
             }
 
+            let mut output_rows = 0;
             while let Some(batch_or_err) = stream_record_batches.next().await {
                 match batch_or_err {
                     Ok(batch) => {
+                        output_rows += batch.num_rows() as u64;
                         match optimize_record_batch(&batch, Arc::clone(&schema)) {
                             Ok(batch) => {
                                 let (flight_dictionaries, flight_batch) =
@@ -379,6 +618,8 @@ impl GetStream {
                         }
                     }
                     Err(e) => {
+                        query_completed_token.record_error(&e);
+
                         // failure sending here is OK because we're cutting the stream anyways
                         tx.send(Err(Error::Query {
                             database_name: database_name.clone(),
@@ -395,14 +636,48 @@ impl GetStream {
             }
 
             // if we get here, all is good
-            query_completed_token.set_success()
+            let scan_stats = ScanStats::from_plan(physical_plan.as_ref());
+            ctx.stats().set_executed(output_rows, &scan_stats);
+            query_completed_token.set_success();
+
+            // finish with the statistics of the query
+            let stats = proto::QueryStats {
+                rows_scanned: scan_stats.rows_scanned,
+                output_rows,
+                chunks_scanned: scan_stats.chunks_scanned,
+                files_pruned: ctx.stats().files_pruned(),
+                execution_time_ns: start.elapsed().as_nanos() as u64,
+            };
+            let app_metadata = proto::AppMetadata {
+                stats: Some(stats),
+                ..Default::default()
+            };
+            let stats_flight_data = FlightData::new(
+                None,
+                IpcMessage(build_none_flight_msg()),
+                app_metadata.encode_to_vec(),
+                vec![],
+            );
+            tx.send(Ok(stats_flight_data)).await.ok();
+        };
+
+        let join_handle = tokio::spawn(async move {
+            // Dropping the execution when the query is killed or times out
+            // aborts it.
+            if let Err(source) = cancellation.run(execution).await {
+                // failure sending here is OK because we're cutting the stream anyways
+                aborted_tx
+                    .send(Err(Error::Aborted { source }.into()))
+                    .await
+                    .ok();
+            }
         });
 
         Ok(Self {
             rx,
             join_handle,
             done: false,
-            permit,
+            permit: Some(permit),
         })
     }
 }
@@ -428,10 +703,12 @@ impl Stream for GetStream {
             match this.rx.poll_next(cx) {
                 Poll::Ready(None) => {
                     *this.done = true;
+                    *this.permit = None;
                     Poll::Ready(None)
                 }
                 e @ Poll::Ready(Some(Err(_))) => {
                     *this.done = true;
+                    *this.permit = None;
                     e
                 }
                 other => other,
@@ -440,15 +717,246 @@ impl Stream for GetStream {
     }
 }
 
+/// Encodes `schema` as the first message of a do_get response.
+fn schema_flight_data(schema: &Schema, app_metadata: &proto::AppMetadata) -> Result<FlightData> {
+    let options = arrow::ipc::writer::IpcWriteOptions::default();
+    let mut schema_flight_data: FlightData = SchemaAsIpc::new(schema, &options).into();
+
+    // Add response metadata
+    let mut bytes = BytesMut::new();
+    prost::Message::encode(app_metadata, &mut bytes).context(SerializationSnafu)?;
+    schema_flight_data.app_metadata = bytes.to_vec();
+
+    Ok(schema_flight_data)
+}
+
+fn build_none_flight_msg() -> Vec<u8> {
+    let mut fbb = FlatBufferBuilder::new();
+
+    let mut message = arrow::ipc::MessageBuilder::new(&mut fbb);
+    message.add_version(arrow::ipc::MetadataVersion::V5);
+    message.add_header_type(arrow::ipc::MessageHeader::NONE);
+    message.add_bodyLength(0);
+
+    let data = message.finish();
+    fbb.finish(data, None);
+
+    fbb.finished_data().to_vec()
+}
+
 #[cfg(test)]
 mod tests {
     use futures::Future;
     use metric::{Attributes, Metric, U64Gauge};
-    use service_common::test_util::TestDatabaseStore;
+    use service_common::{test_util::TestDatabaseStore, write_token::WRITE_TOKEN_GRPC_HEADER};
     use tokio::pin;
 
     use super::*;
 
+    /// Grants read access to the `my_db` namespace to the token `reader`.
+    #[derive(Debug)]
+    struct MockAuthorizer;
+
+    #[tonic::async_trait]
+    impl Authorizer for MockAuthorizer {
+        async fn authorize(
+            &self,
+            token: Option<&authz::Token>,
+            namespace: &str,
+            permission: Permission,
+        ) -> Result<(), authz::Error> {
+            match token.map(|t| t.as_bytes()) {
+                None => Err(authz::Error::NoToken),
+                Some(b"reader") if namespace == "my_db" && permission == Permission::Read => Ok(()),
+                Some(b"reader") => Err(authz::Error::Forbidden {
+                    namespace: namespace.to_string(),
+                    permission,
+                }),
+                Some(_) => Err(authz::Error::InvalidToken),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_do_get_authorization() {
+        let test_storage = Arc::new(TestDatabaseStore::default());
+        test_storage.db_or_create("my_db").await;
+        test_storage.db_or_create("other_db").await;
+
+        let service =
+            FlightService::new(Arc::clone(&test_storage)).with_authorizer(Arc::new(MockAuthorizer));
+        let request = |database: &str, authorization: Option<&str>| {
+            let mut request = tonic::Request::new(Ticket {
+                ticket: format!(
+                    r#"{{"database_name": "{}", "sql_query": "SELECT 1;"}}"#,
+                    database
+                )
+                .into_bytes(),
+            });
+            if let Some(authorization) = authorization {
+                request
+                    .metadata_mut()
+                    .insert("authorization", authorization.parse().unwrap());
+            }
+            request
+        };
+
+        let status = service.do_get(request("my_db", None)).await.err().unwrap();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+        let status = service
+            .do_get(request("my_db", Some("Token writer")))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+        let status = service
+            .do_get(request("other_db", Some("Token reader")))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        service
+            .do_get(request("my_db", Some("Bearer reader")))
+            .await
+            .unwrap();
+
+        // queries can only be cancelled in namespaces the token can read
+        let cancel = |database: &str, authorization: Option<&str>| {
+            let mut request = tonic::Request::new(Action {
+                r#type: CANCEL_QUERY_ACTION.to_string(),
+                body: proto::CancelQueryRequest {
+                    namespace_name: database.to_string(),
+                    query_id: 42,
+                }
+                .encode_to_vec(),
+            });
+            if let Some(authorization) = authorization {
+                request
+                    .metadata_mut()
+                    .insert("authorization", authorization.parse().unwrap());
+            }
+            request
+        };
+
+        let status = service
+            .do_action(cancel("my_db", None))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+        let status = service
+            .do_action(cancel("other_db", Some("Token reader")))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        // authorized, but there is no such query
+        let status = service
+            .do_action(cancel("my_db", Some("Token reader")))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_do_get_write_token() {
+        let test_storage = Arc::new(TestDatabaseStore::default());
+        test_storage.db_or_create("my_db").await;
+        test_storage.set_write_readable("readable");
+
+        let service = FlightService::new(Arc::clone(&test_storage));
+        let request = |ticket_token: &str, header_token: Option<&str>| {
+            let read_info = proto::ReadInfo {
+                namespace_name: "my_db".to_string(),
+                sql_query: "SELECT 1;".to_string(),
+                write_token: ticket_token.to_string(),
+                ..Default::default()
+            };
+            let mut request = tonic::Request::new(Ticket {
+                ticket: read_info.encode_to_vec(),
+            });
+            if let Some(header_token) = header_token {
+                request
+                    .metadata_mut()
+                    .insert(WRITE_TOKEN_GRPC_HEADER, header_token.parse().unwrap());
+            }
+            request
+        };
+
+        service.do_get(request("readable", None)).await.unwrap();
+        service.do_get(request("", Some("readable"))).await.unwrap();
+        // The ticket takes precedence over the header.
+        service
+            .do_get(request("readable", Some("pending")))
+            .await
+            .unwrap();
+
+        let status = service
+            .do_get(request("pending", None))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(status.code(), tonic::Code::DeadlineExceeded);
+
+        let status = service
+            .do_get(request("", Some("pending")))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(status.code(), tonic::Code::DeadlineExceeded);
+    }
+
+    #[tokio::test]
+    async fn test_kill_query_without_permit() {
+        let test_storage = Arc::new(TestDatabaseStore::new_with_semaphore_size(1));
+        test_storage.db_or_create("my_db").await;
+
+        let service = FlightService::new(Arc::clone(&test_storage));
+        let request = |sql_query: &str| {
+            let read_info = proto::ReadInfo {
+                namespace_name: "my_db".to_string(),
+                sql_query: sql_query.to_string(),
+                ..Default::default()
+            };
+            tonic::Request::new(Ticket {
+                ticket: read_info.encode_to_vec(),
+            })
+        };
+
+        // take the only permit
+        let _streaming_resp = service.do_get(request("SELECT 1;")).await.unwrap();
+
+        // killing does not wait for a permit
+        let fut = service.do_get(request("KILL QUERY 42"));
+        let status = tokio::time::timeout(Duration::from_secs(10), fut)
+            .await
+            .expect("kill waits for a permit")
+            .err()
+            .unwrap();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+
+        let action = Action {
+            r#type: CANCEL_QUERY_ACTION.to_string(),
+            body: proto::CancelQueryRequest {
+                namespace_name: "my_db".to_string(),
+                query_id: 42,
+            }
+            .encode_to_vec(),
+        };
+        let status = service
+            .do_action(tonic::Request::new(action))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+
     #[tokio::test]
     async fn test_query_semaphore() {
         let semaphore_size = 2;
@@ -473,9 +981,7 @@ mod tests {
         // add some data
         test_storage.db_or_create("my_db").await;
 
-        let service = FlightService {
-            server: Arc::clone(&test_storage),
-        };
+        let service = FlightService::new(Arc::clone(&test_storage));
         let ticket = Ticket {
             ticket: br#"{"database_name": "my_db", "sql_query": "SELECT 1;"}"#.to_vec(),
         };
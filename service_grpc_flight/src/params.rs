@@ -0,0 +1,80 @@
+//! Conversion of the positional query parameters (`$1`, `$2`, ...) of a
+//! ticket to the values bound when planning.
+
+use datafusion::scalar::ScalarValue;
+use generated_types::influxdata::iox::querier::v1::{query_param::Value, QueryParam};
+use snafu::Snafu;
+
+#[derive(Debug, Snafu, PartialEq, Eq)]
+pub enum Error {
+    #[snafu(display("Parameter ${} has no value", index))]
+    EmptyParam { index: usize },
+}
+
+/// Converts `params` to the values of the placeholders `$1`, `$2`, ... in
+/// the order given.
+pub fn to_scalar_values(params: &[QueryParam]) -> Result<Vec<ScalarValue>, Error> {
+    params
+        .iter()
+        .enumerate()
+        .map(|(i, param)| {
+            let value = param
+                .value
+                .as_ref()
+                .ok_or(Error::EmptyParam { index: i + 1 })?;
+
+            Ok(match value {
+                Value::NullValue(_) => ScalarValue::Null,
+                Value::BoolValue(v) => ScalarValue::Boolean(Some(*v)),
+                Value::I64Value(v) => ScalarValue::Int64(Some(*v)),
+                Value::U64Value(v) => ScalarValue::UInt64(Some(*v)),
+                Value::F64Value(v) => ScalarValue::Float64(Some(*v)),
+                Value::StringValue(v) => ScalarValue::Utf8(Some(v.clone())),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use generated_types::influxdata::iox::querier::v1::query_param::NullValue;
+
+    fn param(value: Value) -> QueryParam {
+        QueryParam { value: Some(value) }
+    }
+
+    #[test]
+    fn test_to_scalar_values() {
+        let params = vec![
+            param(Value::StringValue("it's".to_string())),
+            param(Value::I64Value(-3)),
+            param(Value::F64Value(1.0)),
+            param(Value::BoolValue(true)),
+            param(Value::NullValue(NullValue {})),
+            param(Value::U64Value(7)),
+        ];
+
+        assert_eq!(
+            to_scalar_values(&params).unwrap(),
+            vec![
+                ScalarValue::Utf8(Some("it's".to_string())),
+                ScalarValue::Int64(Some(-3)),
+                ScalarValue::Float64(Some(1.0)),
+                ScalarValue::Boolean(Some(true)),
+                ScalarValue::Null,
+                ScalarValue::UInt64(Some(7)),
+            ]
+        );
+        assert_eq!(to_scalar_values(&[]).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_empty_param() {
+        let params = vec![param(Value::I64Value(1)), QueryParam { value: None }];
+        assert_eq!(
+            to_scalar_values(&params),
+            Err(Error::EmptyParam { index: 2 })
+        );
+    }
+}
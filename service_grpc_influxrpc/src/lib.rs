@@ -15,6 +15,7 @@ pub mod input;
 pub mod service;
 
 use generated_types::storage_server::{Storage, StorageServer};
+use ioxd_common::authz::Authorizer;
 use service_common::QueryDatabaseProvider;
 use std::sync::Arc;
 
@@ -22,10 +23,15 @@ use std::sync::Arc;
 #[derive(Debug)]
 struct StorageService<T: QueryDatabaseProvider> {
     pub db_store: Arc<T>,
+
+    /// Checks that the requests carry a token granting read access to the
+    /// namespace they query, if set.
+    pub authz: Option<Arc<dyn Authorizer>>,
 }
 
 pub fn make_server<T: QueryDatabaseProvider + 'static>(
     db_store: Arc<T>,
+    authz: Option<Arc<dyn Authorizer>>,
 ) -> StorageServer<impl Storage> {
-    StorageServer::new(StorageService { db_store })
+    StorageServer::new(StorageService { db_store, authz })
 }
@@ -29,20 +29,30 @@ use iox_query::{
         fieldlist::FieldList, seriesset::converter::Error as SeriesSetError,
         ExecutionContextProvider, IOxSessionContext,
     },
-    QueryDatabase, QueryText,
+    is_quota_exceeded, QueryAborted, QueryAdmission, QueryCompletedToken, QueryDatabase,
+    QueryQuotaExceeded, QueryText, WriteWaitError,
 };
+use ioxd_common::authz::{self, Permission, Token};
 use observability_deps::tracing::{error, info, trace};
 use pin_project::pin_project;
-use service_common::{planner::Planner, QueryDatabaseProvider};
+use service_common::{
+    planner::Planner,
+    write_token::{write_token_from_grpc_request, write_token_timeout_from_grpc_request},
+    QueryDatabaseProvider,
+};
 use snafu::{OptionExt, ResultExt, Snafu};
 use std::{
     collections::{BTreeSet, HashMap},
+    future::Future,
     sync::Arc,
 };
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::Status;
-use trace::{ctx::SpanContext, span::SpanExt};
+use trace::{
+    ctx::SpanContext,
+    span::{Span, SpanExt},
+};
 use trace_http::ctx::{RequestLogContext, RequestLogContextExt};
 use tracker::InstrumentedAsyncOwnedSemaphorePermit;
 
@@ -168,6 +178,15 @@ pub enum Error {
 
     #[snafu(display("Operation not yet implemented:  {}", operation))]
     NotYetImplemented { operation: String },
+
+    #[snafu(display("Query was aborted: {}", source))]
+    Aborted { source: QueryAborted },
+
+    #[snafu(display("Query was rejected: {}", source))]
+    Rejected { source: QueryQuotaExceeded },
+
+    #[snafu(display("Error waiting for write: {}", source))]
+    WaitForWrite { source: WriteWaitError },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -185,6 +204,9 @@ impl Error {
     /// Converts a result from the business logic into the appropriate tonic
     /// status
     fn to_status(&self) -> tonic::Status {
+        if is_quota_exceeded(self) {
+            return Status::resource_exhausted(self.to_string());
+        }
         match &self {
             Self::DatabaseNotFound { .. } => Status::not_found(self.to_string()),
             Self::ListingTables { .. } => Status::internal(self.to_string()),
@@ -214,6 +236,22 @@ impl Error {
             Self::MeasurementLiteralOrRegex { .. } => Status::invalid_argument(self.to_string()),
             Self::MissingTagKeyPredicate {} => Status::invalid_argument(self.to_string()),
             Self::InvalidTagKeyRegex { .. } => Status::invalid_argument(self.to_string()),
+            Self::Aborted {
+                source: QueryAborted::Cancelled,
+            } => Status::cancelled(self.to_string()),
+            Self::Aborted {
+                source: QueryAborted::Timeout { .. },
+            } => Status::deadline_exceeded(self.to_string()),
+            Self::Rejected { .. } => Status::resource_exhausted(self.to_string()),
+            Self::WaitForWrite {
+                source: WriteWaitError::InvalidToken { .. },
+            } => Status::invalid_argument(self.to_string()),
+            Self::WaitForWrite {
+                source: WriteWaitError::Timeout { .. },
+            } => Status::deadline_exceeded(self.to_string()),
+            Self::WaitForWrite {
+                source: WriteWaitError::Unavailable { .. },
+            } => Status::unavailable(self.to_string()),
         }
     }
 }
@@ -234,12 +272,26 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let token = self.request_token(&req)?;
+        let write_token = write_token_from_grpc_request(&req).context(WaitForWriteSnafu)?;
+        let write_token_timeout = write_token_timeout_from_grpc_request(&req);
         let req = req.into_inner();
+        let db_name = get_database_name(&req)?;
+        self.authorize(token, &db_name).await?;
+        self.wait_for_write(
+            write_token,
+            write_token_timeout,
+            &db_name,
+            span_ctx.child_span("wait for write"),
+        )
+        .await?;
+        let admission = self
+            .admit_query(&db_name, span_ctx.child_span("admit query"))
+            .await?;
         let permit = self
             .db_store
             .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
             .await;
-        let db_name = get_database_name(&req)?;
         info!(
             %db_name,
             ?req.range,
@@ -255,13 +307,18 @@ where
             .context(DatabaseNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
-        let mut query_completed_token = db.record_query(&ctx, "read_filter", defer_json(&req));
+        let mut query_completed_token = db
+            .record_query(&ctx, "read_filter", defer_json(&req))
+            .with_admission(admission);
 
-        let results = read_filter_impl(Arc::clone(&db), db_name, req, &ctx)
-            .await?
-            .into_iter()
-            .map(Ok)
-            .collect::<Vec<_>>();
+        let results = cancellable(
+            &query_completed_token,
+            read_filter_impl(Arc::clone(&db), db_name, req, &ctx),
+        )
+        .await?
+        .into_iter()
+        .map(Ok)
+        .collect::<Vec<_>>();
 
         if results.iter().all(|r| r.is_ok()) {
             query_completed_token.set_success();
@@ -282,14 +339,27 @@ where
     ) -> Result<tonic::Response<Self::ReadGroupStream>, Status> {
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+        let token = self.request_token(&req)?;
+        let write_token = write_token_from_grpc_request(&req).context(WaitForWriteSnafu)?;
+        let write_token_timeout = write_token_timeout_from_grpc_request(&req);
         let req = req.into_inner();
+        let db_name = get_database_name(&req)?;
+        self.authorize(token, &db_name).await?;
+        self.wait_for_write(
+            write_token,
+            write_token_timeout,
+            &db_name,
+            span_ctx.child_span("wait for write"),
+        )
+        .await?;
+        let admission = self
+            .admit_query(&db_name, span_ctx.child_span("admit query"))
+            .await?;
         let permit = self
             .db_store
             .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
             .await;
 
-        let db_name = get_database_name(&req)?;
-
         info!(
             %db_name,
             ?req.range,
@@ -308,7 +378,9 @@ where
             .context(DatabaseNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
-        let mut query_completed_token = db.record_query(&ctx, "read_group", defer_json(&req));
+        let mut query_completed_token = db
+            .record_query(&ctx, "read_group", defer_json(&req))
+            .with_admission(admission);
 
         let ReadGroupRequest {
             read_source: _read_source,
@@ -331,14 +403,17 @@ where
         let gby_agg = expr::make_read_group_aggregate(aggregate, group, group_keys)
             .context(ConvertingReadGroupAggregateSnafu { aggregate_string })?;
 
-        let results = query_group_impl(
-            Arc::clone(&db),
-            db_name,
-            range,
-            predicate,
-            gby_agg,
-            TagKeyMetaNames::Text,
-            &ctx,
+        let results = cancellable(
+            &query_completed_token,
+            query_group_impl(
+                Arc::clone(&db),
+                db_name,
+                range,
+                predicate,
+                gby_agg,
+                TagKeyMetaNames::Text,
+                &ctx,
+            ),
         )
         .await
         .map_err(|e| e.to_status())?
@@ -365,13 +440,26 @@ where
     ) -> Result<tonic::Response<Self::ReadGroupStream>, Status> {
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+        let token = self.request_token(&req)?;
+        let write_token = write_token_from_grpc_request(&req).context(WaitForWriteSnafu)?;
+        let write_token_timeout = write_token_timeout_from_grpc_request(&req);
         let req = req.into_inner();
+        let db_name = get_database_name(&req)?;
+        self.authorize(token, &db_name).await?;
+        self.wait_for_write(
+            write_token,
+            write_token_timeout,
+            &db_name,
+            span_ctx.child_span("wait for write"),
+        )
+        .await?;
+        let admission = self
+            .admit_query(&db_name, span_ctx.child_span("admit query"))
+            .await?;
         let permit = self
             .db_store
             .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
             .await;
-
-        let db_name = get_database_name(&req)?;
         info!(
             %db_name,
             ?req.range,
@@ -391,8 +479,9 @@ where
             .context(DatabaseNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
-        let mut query_completed_token =
-            db.record_query(&ctx, "read_window_aggregate", defer_json(&req));
+        let mut query_completed_token = db
+            .record_query(&ctx, "read_window_aggregate", defer_json(&req))
+            .with_admission(admission);
 
         let ReadWindowAggregateRequest {
             read_source: _read_source,
@@ -413,14 +502,17 @@ where
         let gby_agg = expr::make_read_window_aggregate(aggregate, window_every, offset, window)
             .context(ConvertingWindowAggregateSnafu { aggregate_string })?;
 
-        let results = query_group_impl(
-            Arc::clone(&db),
-            db_name,
-            range,
-            predicate,
-            gby_agg,
-            TagKeyMetaNames::from_i32(tag_key_meta_names).unwrap_or_default(),
-            &ctx,
+        let results = cancellable(
+            &query_completed_token,
+            query_group_impl(
+                Arc::clone(&db),
+                db_name,
+                range,
+                predicate,
+                gby_agg,
+                TagKeyMetaNames::from_i32(tag_key_meta_names).unwrap_or_default(),
+                &ctx,
+            ),
         )
         .await
         .map_err(|e| e.to_status())?
@@ -448,13 +540,26 @@ where
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
         let (tx, rx) = mpsc::channel(4);
 
+        let token = self.request_token(&req)?;
+        let write_token = write_token_from_grpc_request(&req).context(WaitForWriteSnafu)?;
+        let write_token_timeout = write_token_timeout_from_grpc_request(&req);
         let req = req.into_inner();
+        let db_name = get_database_name(&req)?;
+        self.authorize(token, &db_name).await?;
+        self.wait_for_write(
+            write_token,
+            write_token_timeout,
+            &db_name,
+            span_ctx.child_span("wait for write"),
+        )
+        .await?;
+        let admission = self
+            .admit_query(&db_name, span_ctx.child_span("admit query"))
+            .await?;
         let permit = self
             .db_store
             .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
             .await;
-
-        let db_name = get_database_name(&req)?;
         info!(
             %db_name,
             ?req.range,
@@ -470,7 +575,9 @@ where
             .context(DatabaseNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
-        let mut query_completed_token = db.record_query(&ctx, "tag_keys", defer_json(&req));
+        let mut query_completed_token = db
+            .record_query(&ctx, "tag_keys", defer_json(&req))
+            .with_admission(admission);
 
         let TagKeysRequest {
             tags_source: _tag_source,
@@ -480,13 +587,16 @@ where
 
         let measurement = None;
 
-        let response = tag_keys_impl(
-            Arc::clone(&db),
-            db_name,
-            measurement,
-            range,
-            predicate,
-            &ctx,
+        let response = cancellable(
+            &query_completed_token,
+            tag_keys_impl(
+                Arc::clone(&db),
+                db_name,
+                measurement,
+                range,
+                predicate,
+                &ctx,
+            ),
         )
         .await
         .map_err(|e| e.to_status());
@@ -515,13 +625,26 @@ where
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
         let (tx, rx) = mpsc::channel(4);
 
+        let token = self.request_token(&req)?;
+        let write_token = write_token_from_grpc_request(&req).context(WaitForWriteSnafu)?;
+        let write_token_timeout = write_token_timeout_from_grpc_request(&req);
         let req = req.into_inner();
+        let db_name = get_database_name(&req)?;
+        self.authorize(token, &db_name).await?;
+        self.wait_for_write(
+            write_token,
+            write_token_timeout,
+            &db_name,
+            span_ctx.child_span("wait for write"),
+        )
+        .await?;
+        let admission = self
+            .admit_query(&db_name, span_ctx.child_span("admit query"))
+            .await?;
         let permit = self
             .db_store
             .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
             .await;
-
-        let db_name = get_database_name(&req)?;
         let tag_key = DecodedTagKey::try_from(req.tag_key.clone())
             .context(ConvertingTagKeyInTagValuesSnafu)?;
         info!(
@@ -540,7 +663,9 @@ where
             .context(DatabaseNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
-        let mut query_completed_token = db.record_query(&ctx, "tag_values", defer_json(&req));
+        let mut query_completed_token = db
+            .record_query(&ctx, "tag_values", defer_json(&req))
+            .with_admission(admission);
 
         let TagValuesRequest {
             tags_source: _tag_source,
@@ -563,12 +688,18 @@ where
                     .to_status());
                 }
 
-                measurement_name_impl(Arc::clone(&db), db_name, range, predicate, &ctx).await
+                cancellable(
+                    &query_completed_token,
+                    measurement_name_impl(Arc::clone(&db), db_name, range, predicate, &ctx),
+                )
+                .await
             }
             DecodedTagKey::Field => {
-                let fieldlist =
-                    field_names_impl(Arc::clone(&db), db_name, None, range, predicate, &ctx)
-                        .await?;
+                let fieldlist = cancellable(
+                    &query_completed_token,
+                    field_names_impl(Arc::clone(&db), db_name, None, range, predicate, &ctx),
+                )
+                .await?;
 
                 // Pick out the field names into a Vec<Vec<u8>>for return
                 let values = fieldlist
@@ -580,14 +711,17 @@ where
                 Ok(StringValuesResponse { values })
             }
             DecodedTagKey::Normal(tag_key) => {
-                tag_values_impl(
-                    Arc::clone(&db),
-                    db_name,
-                    tag_key,
-                    measurement,
-                    range,
-                    predicate,
-                    &ctx,
+                cancellable(
+                    &query_completed_token,
+                    tag_values_impl(
+                        Arc::clone(&db),
+                        db_name,
+                        tag_key,
+                        measurement,
+                        range,
+                        predicate,
+                        &ctx,
+                    ),
                 )
                 .await
             }
@@ -620,13 +754,26 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let token = self.request_token(&req)?;
+        let write_token = write_token_from_grpc_request(&req).context(WaitForWriteSnafu)?;
+        let write_token_timeout = write_token_timeout_from_grpc_request(&req);
         let req = req.into_inner();
+        let db_name = get_database_name(&req)?;
+        self.authorize(token, &db_name).await?;
+        self.wait_for_write(
+            write_token,
+            write_token_timeout,
+            &db_name,
+            span_ctx.child_span("wait for write"),
+        )
+        .await?;
+        let admission = self
+            .admit_query(&db_name, span_ctx.child_span("admit query"))
+            .await?;
         let permit = self
             .db_store
             .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
             .await;
-
-        let db_name = get_database_name(&req)?;
         info!(
             %db_name,
             ?req.measurement_patterns,
@@ -643,19 +790,23 @@ where
             .context(DatabaseNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
-        let mut query_completed_token = db.record_query(
-            &ctx,
-            "tag_values_grouped_by_measurement_and_tag_key",
-            defer_json(&req),
-        );
+        let mut query_completed_token = db
+            .record_query(
+                &ctx,
+                "tag_values_grouped_by_measurement_and_tag_key",
+                defer_json(&req),
+            )
+            .with_admission(admission);
 
-        let results =
-            tag_values_grouped_by_measurement_and_tag_key_impl(Arc::clone(&db), db_name, req, &ctx)
-                .await
-                .map_err(|e| e.to_status())?
-                .into_iter()
-                .map(Ok)
-                .collect::<Vec<_>>();
+        let results = cancellable(
+            &query_completed_token,
+            tag_values_grouped_by_measurement_and_tag_key_impl(Arc::clone(&db), db_name, req, &ctx),
+        )
+        .await
+        .map_err(|e| e.to_status())?
+        .into_iter()
+        .map(Ok)
+        .collect::<Vec<_>>();
 
         if results.iter().all(|r| r.is_ok()) {
             query_completed_token.set_success();
@@ -729,13 +880,26 @@ where
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
         let (tx, rx) = mpsc::channel(4);
 
+        let token = self.request_token(&req)?;
+        let write_token = write_token_from_grpc_request(&req).context(WaitForWriteSnafu)?;
+        let write_token_timeout = write_token_timeout_from_grpc_request(&req);
         let req = req.into_inner();
+        let db_name = get_database_name(&req)?;
+        self.authorize(token, &db_name).await?;
+        self.wait_for_write(
+            write_token,
+            write_token_timeout,
+            &db_name,
+            span_ctx.child_span("wait for write"),
+        )
+        .await?;
+        let admission = self
+            .admit_query(&db_name, span_ctx.child_span("admit query"))
+            .await?;
         let permit = self
             .db_store
             .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
             .await;
-
-        let db_name = get_database_name(&req)?;
         info!(
             %db_name,
             ?req.range,
@@ -751,8 +915,9 @@ where
             .context(DatabaseNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
-        let mut query_completed_token =
-            db.record_query(&ctx, "measurement_names", defer_json(&req));
+        let mut query_completed_token = db
+            .record_query(&ctx, "measurement_names", defer_json(&req))
+            .with_admission(admission);
 
         let MeasurementNamesRequest {
             source: _source,
@@ -760,9 +925,12 @@ where
             predicate,
         } = req;
 
-        let response = measurement_name_impl(Arc::clone(&db), db_name, range, predicate, &ctx)
-            .await
-            .map_err(|e| e.to_status());
+        let response = cancellable(
+            &query_completed_token,
+            measurement_name_impl(Arc::clone(&db), db_name, range, predicate, &ctx),
+        )
+        .await
+        .map_err(|e| e.to_status());
 
         if response.is_ok() {
             query_completed_token.set_success();
@@ -789,13 +957,26 @@ where
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
         let (tx, rx) = mpsc::channel(4);
 
+        let token = self.request_token(&req)?;
+        let write_token = write_token_from_grpc_request(&req).context(WaitForWriteSnafu)?;
+        let write_token_timeout = write_token_timeout_from_grpc_request(&req);
         let req = req.into_inner();
+        let db_name = get_database_name(&req)?;
+        self.authorize(token, &db_name).await?;
+        self.wait_for_write(
+            write_token,
+            write_token_timeout,
+            &db_name,
+            span_ctx.child_span("wait for write"),
+        )
+        .await?;
+        let admission = self
+            .admit_query(&db_name, span_ctx.child_span("admit query"))
+            .await?;
         let permit = self
             .db_store
             .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
             .await;
-
-        let db_name = get_database_name(&req)?;
         info!(
             %db_name,
             ?req.range,
@@ -812,8 +993,9 @@ where
             .context(DatabaseNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
-        let mut query_completed_token =
-            db.record_query(&ctx, "measurement_tag_keys", defer_json(&req));
+        let mut query_completed_token = db
+            .record_query(&ctx, "measurement_tag_keys", defer_json(&req))
+            .with_admission(admission);
 
         let MeasurementTagKeysRequest {
             source: _source,
@@ -824,13 +1006,16 @@ where
 
         let measurement = Some(measurement);
 
-        let response = tag_keys_impl(
-            Arc::clone(&db),
-            db_name,
-            measurement,
-            range,
-            predicate,
-            &ctx,
+        let response = cancellable(
+            &query_completed_token,
+            tag_keys_impl(
+                Arc::clone(&db),
+                db_name,
+                measurement,
+                range,
+                predicate,
+                &ctx,
+            ),
         )
         .await
         .map_err(|e| e.to_status());
@@ -860,13 +1045,26 @@ where
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
         let (tx, rx) = mpsc::channel(4);
 
+        let token = self.request_token(&req)?;
+        let write_token = write_token_from_grpc_request(&req).context(WaitForWriteSnafu)?;
+        let write_token_timeout = write_token_timeout_from_grpc_request(&req);
         let req = req.into_inner();
+        let db_name = get_database_name(&req)?;
+        self.authorize(token, &db_name).await?;
+        self.wait_for_write(
+            write_token,
+            write_token_timeout,
+            &db_name,
+            span_ctx.child_span("wait for write"),
+        )
+        .await?;
+        let admission = self
+            .admit_query(&db_name, span_ctx.child_span("admit query"))
+            .await?;
         let permit = self
             .db_store
             .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
             .await;
-
-        let db_name = get_database_name(&req)?;
         info!(
             %db_name,
             ?req.range,
@@ -884,8 +1082,9 @@ where
             .context(DatabaseNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
-        let mut query_completed_token =
-            db.record_query(&ctx, "measurement_tag_values", defer_json(&req));
+        let mut query_completed_token = db
+            .record_query(&ctx, "measurement_tag_values", defer_json(&req))
+            .with_admission(admission);
 
         let MeasurementTagValuesRequest {
             source: _source,
@@ -897,14 +1096,17 @@ where
 
         let measurement = Some(measurement);
 
-        let response = tag_values_impl(
-            Arc::clone(&db),
-            db_name,
-            tag_key,
-            measurement,
-            range,
-            predicate,
-            &ctx,
+        let response = cancellable(
+            &query_completed_token,
+            tag_values_impl(
+                Arc::clone(&db),
+                db_name,
+                tag_key,
+                measurement,
+                range,
+                predicate,
+                &ctx,
+            ),
         )
         .await
         .map_err(|e| e.to_status());
@@ -934,13 +1136,26 @@ where
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
         let (tx, rx) = mpsc::channel(4);
 
+        let token = self.request_token(&req)?;
+        let write_token = write_token_from_grpc_request(&req).context(WaitForWriteSnafu)?;
+        let write_token_timeout = write_token_timeout_from_grpc_request(&req);
         let req = req.into_inner();
+        let db_name = get_database_name(&req)?;
+        self.authorize(token, &db_name).await?;
+        self.wait_for_write(
+            write_token,
+            write_token_timeout,
+            &db_name,
+            span_ctx.child_span("wait for write"),
+        )
+        .await?;
+        let admission = self
+            .admit_query(&db_name, span_ctx.child_span("admit query"))
+            .await?;
         let permit = self
             .db_store
             .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
             .await;
-
-        let db_name = get_database_name(&req)?;
         info!(
             %db_name,
             ?req.range,
@@ -957,8 +1172,9 @@ where
             .context(DatabaseNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
-        let mut query_completed_token =
-            db.record_query(&ctx, "measurement_fields", defer_json(&req));
+        let mut query_completed_token = db
+            .record_query(&ctx, "measurement_fields", defer_json(&req))
+            .with_admission(admission);
 
         let MeasurementFieldsRequest {
             source: _source,
@@ -969,13 +1185,16 @@ where
 
         let measurement = Some(measurement);
 
-        let response = field_names_impl(
-            Arc::clone(&db),
-            db_name,
-            measurement,
-            range,
-            predicate,
-            &ctx,
+        let response = cancellable(
+            &query_completed_token,
+            field_names_impl(
+                Arc::clone(&db),
+                db_name,
+                measurement,
+                range,
+                predicate,
+                &ctx,
+            ),
         )
         .await
         .map(|fieldlist| {
@@ -1012,6 +1231,85 @@ where
     }
 }
 
+/// Runs `fut` as part of the query tracked by `query_completed_token`,
+/// failing with [`Error::Aborted`] if the query is killed or times out
+/// before it finishes.
+async fn cancellable<T, F>(query_completed_token: &QueryCompletedToken, fut: F) -> Result<T>
+where
+    F: Future<Output = Result<T>> + Send,
+{
+    let res = query_completed_token
+        .cancellation()
+        .run(fut)
+        .await
+        .context(AbortedSnafu)?;
+    if let Err(e) = &res {
+        query_completed_token.record_error(e);
+    }
+    res
+}
+
+impl<T> StorageService<T>
+where
+    T: QueryDatabaseProvider,
+{
+    /// Extract the API token of `req`, if requests are authorized.
+    fn request_token<R>(&self, req: &tonic::Request<R>) -> Result<Option<Token>, Status> {
+        match self.authz {
+            Some(_) => Ok(authz::token_from_grpc_request(req)?),
+            None => Ok(None),
+        }
+    }
+
+    /// Check that `token` grants read access to `db_name`, if requests are
+    /// authorized.
+    async fn authorize(&self, token: Option<Token>, db_name: &str) -> Result<(), Status> {
+        if let Some(authz) = &self.authz {
+            authz
+                .authorize(token.as_ref(), db_name, Permission::Read)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Admit the query against the limits of `db_name`.
+    ///
+    /// Called before acquiring the query permit, so that rejected queries
+    /// neither wait for nor take up the query concurrency.
+    async fn admit_query(
+        &self,
+        db_name: &str,
+        span: Option<Span>,
+    ) -> Result<QueryAdmission, Status> {
+        Ok(self
+            .db_store
+            .admit_query(db_name, span)
+            .await
+            .context(RejectedSnafu)?)
+    }
+
+    /// Wait until the write identified by `write_token` is readable, if the
+    /// request carries a write token.
+    ///
+    /// Called before acquiring the query permit, so that waiting queries do
+    /// not take up the query concurrency.
+    async fn wait_for_write(
+        &self,
+        write_token: Option<String>,
+        timeout: std::time::Duration,
+        db_name: &str,
+        span: Option<Span>,
+    ) -> Result<(), Status> {
+        if let Some(write_token) = write_token {
+            self.db_store
+                .wait_for_write(db_name, &write_token, timeout, span)
+                .await
+                .context(WaitForWriteSnafu)?;
+        }
+        Ok(())
+    }
+}
+
 fn get_database_name(input: &impl GrpcInputs) -> Result<DatabaseName<'static>, Status> {
     org_and_bucket_to_database(input.org_id()?.to_string(), &input.bucket_name()?)
         .map_err(|e| Status::internal(e.to_string()))
@@ -1622,7 +1920,7 @@ mod tests {
     use metric::{Attributes, Metric, U64Counter, U64Gauge};
     use panic_logging::SendPanicsToTracing;
     use predicate::{Predicate, PredicateMatch};
-    use service_common::test_util::TestDatabaseStore;
+    use service_common::{test_util::TestDatabaseStore, write_token::WRITE_TOKEN_GRPC_HEADER};
     use std::{
         any::Any,
         net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -3266,6 +3564,104 @@ mod tests {
         }
     }
 
+    /// Grants read access to all namespaces to the token `reader`.
+    #[derive(Debug)]
+    struct MockAuthorizer;
+
+    #[tonic::async_trait]
+    impl ioxd_common::authz::Authorizer for MockAuthorizer {
+        async fn authorize(
+            &self,
+            token: Option<&Token>,
+            namespace: &str,
+            permission: Permission,
+        ) -> Result<(), authz::Error> {
+            match token.map(|t| t.as_bytes()) {
+                None => Err(authz::Error::NoToken),
+                Some(b"reader") if permission == Permission::Read => Ok(()),
+                Some(b"reader") => Err(authz::Error::Forbidden {
+                    namespace: namespace.to_string(),
+                    permission,
+                }),
+                Some(_) => Err(authz::Error::InvalidToken),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_authorization() {
+        let test_storage = Arc::new(TestDatabaseStore::default());
+        let db_info = org_and_bucket();
+        test_storage.db_or_create(db_info.db_name()).await;
+
+        let service = StorageService {
+            db_store: Arc::clone(&test_storage),
+            authz: Some(Arc::new(MockAuthorizer)),
+        };
+        let request = |authorization: Option<&str>| {
+            let mut request = tonic::Request::new(MeasurementNamesRequest {
+                source: Some(StorageClient::read_source(&db_info, 1)),
+                range: None,
+                predicate: None,
+            });
+            if let Some(authorization) = authorization {
+                request
+                    .metadata_mut()
+                    .insert("authorization", authorization.parse().unwrap());
+            }
+            request
+        };
+
+        let status = service.measurement_names(request(None)).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+        let status = service
+            .measurement_names(request(Some("Token writer")))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+        service
+            .measurement_names(request(Some("Token reader")))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_write_token() {
+        let test_storage = Arc::new(TestDatabaseStore::default());
+        let db_info = org_and_bucket();
+        test_storage.db_or_create(db_info.db_name()).await;
+        test_storage.set_write_readable("readable");
+
+        let service = StorageService {
+            db_store: Arc::clone(&test_storage),
+            authz: None,
+        };
+        let request = |write_token: &str| {
+            let mut request = tonic::Request::new(MeasurementNamesRequest {
+                source: Some(StorageClient::read_source(&db_info, 1)),
+                range: None,
+                predicate: None,
+            });
+            request
+                .metadata_mut()
+                .insert(WRITE_TOKEN_GRPC_HEADER, write_token.parse().unwrap());
+            request
+        };
+
+        service
+            .measurement_names(request("readable"))
+            .await
+            .unwrap();
+
+        let status = service
+            .measurement_names(request("pending"))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::DeadlineExceeded);
+    }
+
     #[tokio::test]
     async fn test_query_semaphore() {
         maybe_start_logging();
@@ -3288,6 +3684,7 @@ mod tests {
             println!("Testing with request: {:?}", t);
             let service = StorageService {
                 db_store: Arc::clone(&test_storage),
+                authz: None,
             };
 
             assert_semaphore_metric(
@@ -3522,7 +3919,7 @@ mod tests {
                     true,
                 ))
                 .add_service(service_grpc_testing::make_server())
-                .add_service(crate::make_server(Arc::clone(&test_storage)));
+                .add_service(crate::make_server(Arc::clone(&test_storage), None));
 
             let server = async move {
                 let stream = TcpListenerStream::new(socket);
@@ -1,10 +1,17 @@
 //! Implementation of the schema gRPC service
 
-use std::{ops::DerefMut, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::DerefMut,
+    sync::Arc,
+};
 
-use generated_types::influxdata::iox::schema::v1::*;
-use iox_catalog::interface::{get_schema_by_name, Catalog};
-use observability_deps::tracing::warn;
+use data_types::ColumnType;
+use generated_types::{google::FieldViolation, influxdata::iox::schema::v1::*};
+use iox_catalog::interface::{get_schema_by_name, Catalog, Error as CatalogError, RepoCollection};
+use ioxd_common::authz::{self, Authorizer, Permission};
+use observability_deps::tracing::{info, warn};
+use schema::TIME_COLUMN_NAME;
 use tonic::{Request, Response, Status};
 
 /// Implementation of the gRPC schema service
@@ -12,11 +19,53 @@ use tonic::{Request, Response, Status};
 pub struct SchemaService {
     /// Catalog.
     catalog: Arc<dyn Catalog>,
+
+    /// The authorizer checking that the requests changing the schema of a
+    /// namespace carry a token granting write access to it, if any.
+    authz: Option<Arc<dyn Authorizer>>,
 }
 
 impl SchemaService {
     pub fn new(catalog: Arc<dyn Catalog>) -> Self {
-        Self { catalog }
+        Self {
+            catalog,
+            authz: None,
+        }
+    }
+
+    /// Require the requests declaring tables and columns or changing the
+    /// schema mode of a namespace to carry a token granting `authz` write
+    /// access to the namespace.
+    pub fn with_authorizer(mut self, authz: Arc<dyn Authorizer>) -> Self {
+        self.authz = Some(authz);
+        self
+    }
+
+    /// Check the token of `request` grants write access to `namespace`.
+    async fn authorize_write<T>(
+        &self,
+        request: &Request<T>,
+        namespace: &str,
+    ) -> Result<(), Status> {
+        if let Some(authz) = &self.authz {
+            let token = authz::token_from_grpc_request(request)?;
+            authz
+                .authorize(token.as_ref(), namespace, Permission::Write)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Read the schema of `namespace` from the catalog.
+    async fn read_schema(&self, namespace: &str) -> Result<NamespaceSchema, Status> {
+        let mut repos = self.catalog.repositories().await;
+        get_schema_by_name(namespace, repos.deref_mut())
+            .await
+            .map_err(|e| {
+                warn!(error=%e, %namespace, "failed to retrieve namespace schema");
+                Status::not_found(e.to_string())
+            })
+            .map(|schema| schema_to_proto(&schema))
     }
 }
 
@@ -26,54 +75,195 @@ impl schema_service_server::SchemaService for SchemaService {
         &self,
         request: Request<GetSchemaRequest>,
     ) -> Result<Response<GetSchemaResponse>, Status> {
-        let mut repos = self.catalog.repositories().await;
+        let req = request.into_inner();
+        let schema = self.read_schema(&req.namespace).await?;
+        Ok(Response::new(GetSchemaResponse {
+            schema: Some(schema),
+        }))
+    }
+
+    async fn upsert_schema(
+        &self,
+        request: Request<UpsertSchemaRequest>,
+    ) -> Result<Response<UpsertSchemaResponse>, Status> {
+        let namespace = request.get_ref().namespace.clone();
+        self.authorize_write(&request, &namespace).await?;
 
         let req = request.into_inner();
-        let schema = get_schema_by_name(&req.namespace, repos.deref_mut())
+        let tables = declared_tables(&req.tables)?;
+
+        // Declare all the tables and columns, or none of them.
+        let mut txn = self
+            .catalog
+            .start_transaction()
             .await
-            .map_err(|e| {
-                warn!(error=%e, %req.namespace, "failed to retrieve namespace schema");
-                Status::not_found(e.to_string())
-            })
-            .map(Arc::new)?;
-        Ok(Response::new(schema_to_proto(schema)))
+            .map_err(|e| Status::internal(e.to_string()))?;
+        match declare_tables(txn.deref_mut(), &namespace, &tables).await {
+            Ok(()) => txn
+                .commit()
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?,
+            Err(e) => {
+                if let Err(abort_err) = txn.abort().await {
+                    warn!(error=%abort_err, %namespace, "failed to abort schema declaration");
+                }
+                return Err(e);
+            }
+        }
+        info!(%namespace, tables = tables.len(), "declared namespace schema");
+
+        let schema = self.read_schema(&namespace).await?;
+        Ok(Response::new(UpsertSchemaResponse {
+            schema: Some(schema),
+        }))
+    }
+
+    async fn set_strict_schema(
+        &self,
+        request: Request<SetStrictSchemaRequest>,
+    ) -> Result<Response<SetStrictSchemaResponse>, Status> {
+        let namespace = request.get_ref().namespace.clone();
+        self.authorize_write(&request, &namespace).await?;
+        let strict_schema = request.into_inner().strict_schema;
+
+        self.catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_strict_schema(&namespace, strict_schema)
+            .await
+            .map_err(|e| match e {
+                CatalogError::NamespaceNotFoundByName { .. } => Status::not_found(e.to_string()),
+                _ => Status::internal(e.to_string()),
+            })?;
+        info!(%namespace, strict_schema, "changed namespace schema mode");
+
+        let schema = self.read_schema(&namespace).await?;
+        Ok(Response::new(SetStrictSchemaResponse {
+            schema: Some(schema),
+        }))
     }
 }
 
-fn schema_to_proto(schema: Arc<data_types::NamespaceSchema>) -> GetSchemaResponse {
-    let response = GetSchemaResponse {
-        schema: Some(NamespaceSchema {
-            id: schema.id.get(),
-            topic_id: schema.topic_id.get(),
-            query_pool_id: schema.query_pool_id.get(),
-            tables: schema
-                .tables
+/// Validate the declared `tables`, adding the time column to each of them.
+fn declared_tables(
+    tables: &HashMap<String, DeclaredTable>,
+) -> Result<BTreeMap<&str, BTreeMap<&str, ColumnType>>, FieldViolation> {
+    tables
+        .iter()
+        .map(|(table, declared)| {
+            if table.is_empty() {
+                return Err(FieldViolation {
+                    field: "tables".into(),
+                    description: "table names must not be empty".into(),
+                });
+            }
+
+            let mut columns = declared
+                .columns
                 .iter()
-                .map(|(name, t)| {
-                    (
-                        name.clone(),
-                        TableSchema {
-                            id: t.id.get(),
-                            columns: t
-                                .columns
-                                .iter()
-                                .map(|(name, c)| {
-                                    (
-                                        name.clone(),
-                                        ColumnSchema {
-                                            id: c.id.get(),
-                                            column_type: c.column_type as i32,
-                                        },
-                                    )
-                                })
-                                .collect(),
-                        },
-                    )
+                .map(|(column, &column_type)| {
+                    i16::try_from(column_type)
+                        .ok()
+                        .and_then(|v| ColumnType::try_from(v).ok())
+                        .map(|column_type| (column.as_str(), column_type))
+                        .ok_or_else(|| FieldViolation {
+                            field: format!("tables.{}.columns.{}", table, column),
+                            description: format!("invalid column type {}", column_type),
+                        })
                 })
-                .collect(),
-        }),
-    };
-    response
+                .collect::<Result<BTreeMap<_, _>, _>>()?;
+
+            match columns.insert(TIME_COLUMN_NAME, ColumnType::Time) {
+                None | Some(ColumnType::Time) => {}
+                Some(_) => {
+                    return Err(FieldViolation {
+                        field: format!("tables.{}.columns.{}", table, TIME_COLUMN_NAME),
+                        description: "the time column must have the time type".into(),
+                    })
+                }
+            }
+
+            Ok((table.as_str(), columns))
+        })
+        .collect()
+}
+
+/// Create the `tables` and their columns in `namespace` if they do not exist.
+async fn declare_tables<R>(
+    repos: &mut R,
+    namespace: &str,
+    tables: &BTreeMap<&str, BTreeMap<&str, ColumnType>>,
+) -> Result<(), Status>
+where
+    R: RepoCollection + ?Sized,
+{
+    let namespace = repos
+        .namespaces()
+        .get_by_name(namespace)
+        .await
+        .map_err(|e| Status::internal(e.to_string()))?
+        .ok_or_else(|| Status::not_found(format!("namespace {} not found", namespace)))?;
+
+    for (&table, columns) in tables {
+        let table = repos
+            .tables()
+            .create_or_get(table, namespace.id)
+            .await
+            .map_err(catalog_error_to_status)?;
+        for (&column, &column_type) in columns {
+            repos
+                .columns()
+                .create_or_get(column, table.id, column_type)
+                .await
+                .map_err(catalog_error_to_status)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn catalog_error_to_status(e: CatalogError) -> Status {
+    match e {
+        CatalogError::ColumnTypeMismatch { .. } => Status::failed_precondition(e.to_string()),
+        CatalogError::TableCreateLimitError { .. }
+        | CatalogError::ColumnCreateLimitError { .. } => Status::resource_exhausted(e.to_string()),
+        _ => Status::internal(e.to_string()),
+    }
+}
+
+fn schema_to_proto(schema: &data_types::NamespaceSchema) -> NamespaceSchema {
+    NamespaceSchema {
+        id: schema.id.get(),
+        topic_id: schema.topic_id.get(),
+        query_pool_id: schema.query_pool_id.get(),
+        tables: schema
+            .tables
+            .iter()
+            .map(|(name, t)| {
+                (
+                    name.clone(),
+                    TableSchema {
+                        id: t.id.get(),
+                        columns: t
+                            .columns
+                            .iter()
+                            .map(|(name, c)| {
+                                (
+                                    name.clone(),
+                                    ColumnSchema {
+                                        id: c.id.get(),
+                                        column_type: c.column_type as i32,
+                                    },
+                                )
+                            })
+                            .collect(),
+                    },
+                )
+            })
+            .collect(),
+        strict_schema: schema.strict_schema,
+    }
 }
 
 #[cfg(test)]
@@ -138,4 +328,197 @@ mod tests {
             vec![&"schema_test_column".to_string()]
         );
     }
+
+    async fn create_namespace() -> Arc<dyn Catalog> {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+        let mut repos = catalog.repositories().await;
+        let topic = repos.topics().create_or_get("franz").await.unwrap();
+        let pool = repos.query_pools().create_or_get("franz").await.unwrap();
+        repos
+            .namespaces()
+            .create("namespace_schema_test", "inf", topic.id, pool.id)
+            .await
+            .unwrap();
+        catalog
+    }
+
+    fn upsert_request(columns: &[(&str, column_schema::ColumnType)]) -> UpsertSchemaRequest {
+        UpsertSchemaRequest {
+            namespace: "namespace_schema_test".to_string(),
+            tables: HashMap::from([(
+                "cpu".to_string(),
+                DeclaredTable {
+                    columns: columns
+                        .iter()
+                        .map(|(name, column_type)| (name.to_string(), *column_type as i32))
+                        .collect(),
+                },
+            )]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upsert_schema() {
+        let catalog = create_namespace().await;
+        let grpc = super::SchemaService::new(Arc::clone(&catalog));
+
+        let request = upsert_request(&[
+            ("host", column_schema::ColumnType::Tag),
+            ("usage", column_schema::ColumnType::F64),
+        ]);
+        let schema = grpc
+            .upsert_schema(Request::new(request))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner()
+            .schema
+            .expect("schema should be Some()");
+
+        let table = schema.tables.get("cpu").expect("table should be declared");
+        let mut columns = table
+            .columns
+            .iter()
+            .map(|(name, c)| (name.as_str(), c.column_type))
+            .collect::<Vec<_>>();
+        columns.sort_unstable();
+        assert_eq!(
+            columns,
+            vec![
+                ("host", ColumnType::Tag as i32),
+                ("time", ColumnType::Time as i32),
+                ("usage", ColumnType::F64 as i32),
+            ]
+        );
+
+        // Declaring the same columns again is a no-op.
+        let request = upsert_request(&[("usage", column_schema::ColumnType::F64)]);
+        grpc.upsert_schema(Request::new(request))
+            .await
+            .expect("rpc request should succeed");
+
+        // Declaring an existing column with a different type fails, without
+        // declaring the other columns of the request.
+        let request = upsert_request(&[
+            ("idle", column_schema::ColumnType::F64),
+            ("usage", column_schema::ColumnType::I64),
+        ]);
+        let err = grpc
+            .upsert_schema(Request::new(request))
+            .await
+            .expect_err("rpc request should fail");
+        assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+
+        let schema = grpc
+            .get_schema(Request::new(GetSchemaRequest {
+                namespace: "namespace_schema_test".to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .schema
+            .unwrap();
+        assert!(!schema
+            .tables
+            .get("cpu")
+            .unwrap()
+            .columns
+            .contains_key("idle"));
+    }
+
+    #[tokio::test]
+    async fn test_upsert_schema_invalid() {
+        let catalog = create_namespace().await;
+        let grpc = super::SchemaService::new(catalog);
+
+        let request = upsert_request(&[("usage", column_schema::ColumnType::Unspecified)]);
+        let err = grpc
+            .upsert_schema(Request::new(request))
+            .await
+            .expect_err("rpc request should fail");
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+        let request = upsert_request(&[("time", column_schema::ColumnType::I64)]);
+        let err = grpc
+            .upsert_schema(Request::new(request))
+            .await
+            .expect_err("rpc request should fail");
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+        let mut request = upsert_request(&[("usage", column_schema::ColumnType::F64)]);
+        request.namespace = "does_not_exist".to_string();
+        let err = grpc
+            .upsert_schema(Request::new(request))
+            .await
+            .expect_err("rpc request should fail");
+        assert_eq!(err.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_set_strict_schema() {
+        let catalog = create_namespace().await;
+        let grpc = super::SchemaService::new(catalog);
+
+        let schema = grpc
+            .set_strict_schema(Request::new(SetStrictSchemaRequest {
+                namespace: "namespace_schema_test".to_string(),
+                strict_schema: true,
+            }))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner()
+            .schema
+            .unwrap();
+        assert!(schema.strict_schema);
+
+        let err = grpc
+            .set_strict_schema(Request::new(SetStrictSchemaRequest {
+                namespace: "does_not_exist".to_string(),
+                strict_schema: true,
+            }))
+            .await
+            .expect_err("rpc request should fail");
+        assert_eq!(err.code(), tonic::Code::NotFound);
+    }
+
+    /// Rejects all requests.
+    #[derive(Debug)]
+    struct DenyAuthorizer;
+
+    #[async_trait::async_trait]
+    impl Authorizer for DenyAuthorizer {
+        async fn authorize(
+            &self,
+            token: Option<&authz::Token>,
+            _namespace: &str,
+            _permission: Permission,
+        ) -> Result<(), authz::Error> {
+            match token {
+                Some(_) => Err(authz::Error::InvalidToken),
+                None => Err(authz::Error::NoToken),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_schema_changes_unauthorized() {
+        let catalog = create_namespace().await;
+        let grpc = super::SchemaService::new(catalog).with_authorizer(Arc::new(DenyAuthorizer));
+
+        let request = upsert_request(&[("usage", column_schema::ColumnType::F64)]);
+        let err = grpc
+            .upsert_schema(Request::new(request))
+            .await
+            .expect_err("rpc request should fail");
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+
+        let err = grpc
+            .set_strict_schema(Request::new(SetStrictSchemaRequest {
+                namespace: "namespace_schema_test".to_string(),
+                strict_schema: true,
+            }))
+            .await
+            .expect_err("rpc request should fail");
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+    }
 }
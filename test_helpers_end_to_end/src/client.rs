@@ -243,6 +243,7 @@ pub async fn try_run_query(
         .perform_query(ReadInfo {
             namespace_name: namespace,
             sql_query: sql,
+            ..Default::default()
         })
         .await?;
 